@@ -1,6 +1,15 @@
-use crate::ai::types::{OpenAIMessage, OpenAIRequest, OpenAIResponse, ResponseFormat};
+use crate::ai::context::estimate_tokens;
+use crate::ai::hosts::HostPool;
+use crate::ai::local_only;
+use crate::ai::ratelimit::RateLimiter;
+use crate::ai::sanitize::{apply_redactions, set_active_provider, Redactor};
+use crate::ai::types::{
+    EmbeddingRequest, EmbeddingResponse, OllamaOptions, OpenAIMessage, OpenAIRequest, OpenAIResponse, OpenAIUsage, ResponseFormat,
+};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{RwLock, Semaphore};
@@ -11,6 +20,18 @@ use tokio::sync::{RwLock, Semaphore};
 pub enum LLMProvider {
     OpenAI,
     Ollama,
+    /// OpenAI-compatible server running locally (LM Studio, llama.cpp `llama-server`)
+    #[serde(rename = "local_openai")]
+    LocalOpenAI,
+    /// Canned responses from a fixtures directory (`base_url`), for tests without network access
+    Mock,
+}
+
+impl LLMProvider {
+    /// Providers running on the user's machine: slower, single-GPU, no API key required
+    pub fn is_local(&self) -> bool {
+        !matches!(self, LLMProvider::OpenAI)
+    }
 }
 
 /// LLM provider configuration
@@ -20,6 +41,129 @@ pub struct LLMConfig {
     pub base_url: String,
     pub api_key: Option<String>,
     pub model: String,
+    /// Replace names and phone numbers with placeholders before calling a cloud provider
+    #[serde(default)]
+    pub privacy_mode: bool,
+    /// Provider tried when this one is unreachable (e.g. OpenAI behind a local Ollama)
+    #[serde(default)]
+    pub fallback: Option<LLMFallback>,
+    /// Request timeout; None = 30s for cloud providers, 120s for local ones
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Model for `/v1/embeddings`; None = the provider default (see `embedding_model_name`)
+    #[serde(default)]
+    pub embedding_model: Option<String>,
+    /// Ollama model residency after each request, as an Ollama duration ("10m", "1h",
+    /// "-1" = keep loaded, "0" = unload right away); None = Ollama's default (5m)
+    #[serde(default)]
+    pub keep_alive: Option<String>,
+    /// Further Ollama instances (e.g. a GPU machine on the LAN); calls are spread
+    /// round-robin across these and `base_url`
+    #[serde(default)]
+    pub extra_hosts: Vec<String>,
+}
+
+/// Secondary provider for `LLMConfig::fallback`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LLMFallback {
+    pub provider: LLMProvider,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+const DEFAULT_CLOUD_TIMEOUT_SECS: u64 = 30;
+/// Local models are slow, especially on first load
+const DEFAULT_LOCAL_TIMEOUT_SECS: u64 = 120;
+
+/// How long a single call may take, relative to the provider's timeout
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallTimeout {
+    /// Small calls made while handling an incoming message: half the provider timeout
+    Short,
+    Default,
+    /// Long prompts such as detailed summaries: double the provider timeout
+    Long,
+}
+
+impl CallTimeout {
+    fn apply(self, base: Duration) -> Duration {
+        match self {
+            CallTimeout::Short => base / 2,
+            CallTimeout::Default => base,
+            CallTimeout::Long => base * 2,
+        }
+    }
+}
+
+impl LLMConfig {
+    /// Configured request timeout, or the provider default
+    pub fn request_timeout(&self) -> Duration {
+        let default = if self.provider.is_local() {
+            DEFAULT_LOCAL_TIMEOUT_SECS
+        } else {
+            DEFAULT_CLOUD_TIMEOUT_SECS
+        };
+        Duration::from_secs(self.timeout_secs.filter(|&secs| secs > 0).unwrap_or(default))
+    }
+
+    /// Configured embedding model, or a sensible default for the provider.
+    /// OpenAI-compatible local servers only embed with whatever model is loaded.
+    pub fn embedding_model_name(&self) -> String {
+        if let Some(model) = self.embedding_model.as_ref().filter(|m| !m.trim().is_empty()) {
+            return model.clone();
+        }
+        match self.provider {
+            LLMProvider::OpenAI => "text-embedding-3-small".to_string(),
+            LLMProvider::Ollama => "nomic-embed-text".to_string(),
+            LLMProvider::LocalOpenAI | LLMProvider::Mock => self.model.clone(),
+        }
+    }
+
+    /// Config for the fallback provider, sharing this config's privacy setting
+    pub fn fallback_config(&self) -> Option<LLMConfig> {
+        self.fallback.as_ref().map(|fallback| LLMConfig {
+            provider: fallback.provider.clone(),
+            base_url: fallback.base_url.clone(),
+            api_key: fallback.api_key.clone(),
+            model: fallback.model.clone(),
+            privacy_mode: self.privacy_mode,
+            fallback: None,
+            timeout_secs: fallback.timeout_secs,
+            embedding_model: None,
+            keep_alive: None,
+            extra_hosts: vec![],
+        })
+    }
+
+    /// Base URLs to spread calls across: `base_url` plus, for Ollama, the extra hosts
+    pub fn hosts(&self) -> Vec<String> {
+        let mut hosts = vec![self.base_url.trim_end_matches('/').to_string()];
+        if self.provider == LLMProvider::Ollama {
+            for host in &self.extra_hosts {
+                let host = host.trim().trim_end_matches('/');
+                if !host.is_empty() && !hosts.iter().any(|h| h == host) {
+                    hosts.push(host.to_string());
+                }
+            }
+        }
+        hosts
+    }
+
+    /// This config pointed at one of its hosts
+    fn with_host(&self, host: &str) -> LLMConfig {
+        LLMConfig { base_url: host.to_string(), extra_hosts: vec![], ..self.clone() }
+    }
+
+    /// Keep-alive to send with Ollama requests (None for other providers or when unset)
+    pub fn ollama_keep_alive(&self) -> Option<&str> {
+        match self.provider {
+            LLMProvider::Ollama => self.keep_alive.as_deref().map(str::trim).filter(|k| !k.is_empty()),
+            _ => None,
+        }
+    }
 }
 
 impl Default for LLMConfig {
@@ -29,48 +173,138 @@ impl Default for LLMConfig {
             base_url: "https://api.openai.com".to_string(),
             api_key: None,
             model: "gpt-4o-mini".to_string(),
+            privacy_mode: false,
+            fallback: None,
+            timeout_secs: None,
+            embedding_model: None,
+            keep_alive: None,
+            extra_hosts: vec![],
         }
     }
 }
 
-/// LLM API client with retry logic, supporting OpenAI and Ollama
+/// LLM API client with retry logic, supporting OpenAI, Ollama and local OpenAI-compatible servers
 pub struct LLMClient {
     client_openai: Client,
-    client_ollama: Client,
+    client_local: Client,
     config: RwLock<LLMConfig>,
-    ollama_semaphore: Arc<Semaphore>,
+    local_semaphore: Arc<Semaphore>,
+    /// Permits currently in `local_semaphore`; scales with the number of Ollama hosts
+    local_permits: AtomicUsize,
+    hosts: HostPool,
+    /// Paces OpenAI requests using the rate-limit headers of earlier responses
+    rate_limiter: RateLimiter,
+    response_cache: bool,
 }
 
+/// Concurrent local requests per host
+const LOCAL_PERMITS_PER_HOST: usize = 2;
+
 /// Retry configuration
 const MAX_RETRIES: u32 = 3;
 const INITIAL_RETRY_DELAY_MS: u64 = 1000;
 
+/// Texts per embeddings request
+const EMBEDDING_BATCH_SIZE: usize = 64;
+
+/// Requests at or below this temperature are close enough to deterministic to cache
+const CACHE_MAX_TEMPERATURE: f32 = 0.3;
+
+/// How long a cached response is served: the retention window for the LLM cache
+fn cache_ttl_secs() -> Option<i64> {
+    crate::db::settings::load_retention()
+        .unwrap_or_default()
+        .llm_cache_days
+        .map(|days| days as i64 * 86400)
+}
+
+/// A chat completion response. Low-temperature responses carry what's needed to
+/// cache them, but are only cached once the caller has checked that they parse,
+/// so malformed or truncated output is never replayed.
+#[derive(Debug)]
+pub struct Completion {
+    pub content: String,
+    cache: Option<CacheSlot>,
+}
+
+#[derive(Debug)]
+struct CacheSlot {
+    key: String,
+    model: String,
+    /// Response as the provider returned it (redacted in privacy mode)
+    raw: String,
+}
+
+impl Completion {
+    pub(crate) fn uncached(content: String) -> Self {
+        Self { content, cache: None }
+    }
+
+    /// Parse the response, caching it for identical future prompts if that succeeds
+    pub fn parse_and_cache<T>(self, parse: impl FnOnce(&str) -> Result<T, String>) -> Result<T, String> {
+        let parsed = parse(&self.content)?;
+        if let Some(slot) = self.cache {
+            if let Err(e) = crate::db::llm_cache::store_response(&slot.key, &slot.model, &slot.raw) {
+                log::debug!("Failed to cache LLM response: {}", e);
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// Cache key covering everything that affects the response: endpoint, model,
+/// sampling parameters and the exact (sanitized) messages
+fn prompt_hash(config: &LLMConfig, request: &OpenAIRequest) -> Option<String> {
+    // Keep-alive only affects model residency, not the response
+    let request = OpenAIRequest { keep_alive: None, ..request.clone() };
+    let body = serde_json::to_string(&request).ok()?;
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{:?}\n{}\n", config.provider, api_base(&config.base_url)));
+    hasher.update(body);
+    Some(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
 impl LLMClient {
     /// Create a new LLM client with the given config
     pub fn new(config: LLMConfig) -> Self {
+        // Overall timeouts are set per request (see `CallTimeout`), so config
+        // changes apply without rebuilding the clients
         let client_openai = Client::builder()
-            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(10))
             .build()
             .expect("Failed to create HTTP client");
 
-        let client_ollama = Client::builder()
-            .timeout(Duration::from_secs(120))
+        let client_local = Client::builder()
+            .connect_timeout(Duration::from_secs(10))
             .build()
             .expect("Failed to create HTTP client");
 
+        set_active_provider(config.provider.clone());
+        let local_permits = LOCAL_PERMITS_PER_HOST * config.hosts().len();
+
         Self {
             client_openai,
-            client_ollama,
+            client_local,
             config: RwLock::new(config),
-            ollama_semaphore: Arc::new(Semaphore::new(2)),
+            local_semaphore: Arc::new(Semaphore::new(local_permits)),
+            local_permits: AtomicUsize::new(local_permits),
+            hosts: HostPool::default(),
+            rate_limiter: RateLimiter::default(),
+            response_cache: true,
         }
     }
 
-    /// Check if the client is configured (has API key for OpenAI, always true for Ollama)
+    /// Always call the provider, e.g. when testing a connection
+    pub fn without_response_cache(mut self) -> Self {
+        self.response_cache = false;
+        self
+    }
+
+    /// Check if the client is configured (has API key for OpenAI, always true for local providers)
     pub async fn is_configured(&self) -> bool {
         let config = self.config.read().await;
         match config.provider {
-            LLMProvider::Ollama => true,
+            LLMProvider::Ollama | LLMProvider::LocalOpenAI | LLMProvider::Mock => true,
             LLMProvider::OpenAI => config
                 .api_key
                 .as_ref()
@@ -81,16 +315,260 @@ impl LLMClient {
 
     /// Update the runtime configuration
     pub async fn update_config(&self, new_config: LLMConfig) {
+        set_active_provider(new_config.provider.clone());
+        self.resize_local_permits(LOCAL_PERMITS_PER_HOST * new_config.hosts().len());
         let mut config = self.config.write().await;
         *config = new_config;
     }
 
+    /// Grow or shrink the local concurrency limit. Permits held by in-flight
+    /// requests can't be reclaimed, so shrinking may take effect partially.
+    fn resize_local_permits(&self, wanted: usize) {
+        let current = self.local_permits.load(Ordering::Relaxed);
+        if wanted > current {
+            self.local_semaphore.add_permits(wanted - current);
+            self.local_permits.store(wanted, Ordering::Relaxed);
+        } else if wanted < current {
+            let forgotten = self.local_semaphore.forget_permits(current - wanted);
+            self.local_permits.store(current - forgotten, Ordering::Relaxed);
+        }
+    }
+
     /// Get a clone of the current configuration
     pub async fn get_config(&self) -> LLMConfig {
         self.config.read().await.clone()
     }
 
-    /// Make a chat completion request with retry logic
+    /// Whether privacy mode applies: it is on and a host the config may call (including
+    /// the fallback) isn't on this machine, e.g. OpenAI or an Ollama server on the LAN
+    async fn should_redact(&self) -> bool {
+        let config = self.get_config().await;
+        config.privacy_mode && local_only::check_config(&config).await.is_err()
+    }
+
+    /// Cheap reachability check: lists the server's models instead of generating
+    /// tokens, and reports whether the configured model is among them
+    pub async fn ping(&self) -> LLMPing {
+        ping_config(&self.get_config().await).await
+    }
+
+    /// Ping every configured host, updating the round-robin health state
+    pub async fn check_hosts(&self) -> Vec<HostPing> {
+        let config = self.get_config().await;
+        let mut results = Vec::new();
+        for host in config.hosts() {
+            let ping = ping_config(&config.with_host(&host)).await;
+            if ping.reachable {
+                self.hosts.mark_up(&host);
+            } else {
+                self.hosts.mark_down(&host);
+            }
+            results.push(HostPing { base_url: host, ping });
+        }
+        results
+    }
+
+    /// Load the configured Ollama model into memory so the next request doesn't wait
+    /// for a cold start. Returns the load time, or None for providers with nothing to warm.
+    pub async fn warm_up(&self) -> Result<Option<u64>, String> {
+        let config = self.get_config().await;
+        if config.provider != LLMProvider::Ollama {
+            return Ok(None);
+        }
+
+        // Every host takes its share of briefing calls, so warm them all; the
+        // slowest load is reported
+        let keep_alive = config.ollama_keep_alive().unwrap_or(OLLAMA_WARM_UP_KEEP_ALIVE);
+        let mut slowest_ms = None;
+        let mut last_error = None;
+        for host in config.hosts() {
+            let started = std::time::Instant::now();
+            match warm_up_ollama_model(&host, &config.model, keep_alive).await {
+                Ok(()) => {
+                    let elapsed_ms = started.elapsed().as_millis() as u64;
+                    log::info!("Warmed up Ollama model {} on {} in {}ms", config.model, host, elapsed_ms);
+                    slowest_ms = slowest_ms.max(Some(elapsed_ms));
+                }
+                Err(e) => {
+                    log::warn!("Failed to warm up {} on {}: {}", config.model, host, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        match (slowest_ms, last_error) {
+            (None, Some(e)) => Err(e),
+            (slowest_ms, _) => Ok(slowest_ms),
+        }
+    }
+
+    /// Ask Ollama to release the configured model's memory now. Returns false for
+    /// providers that don't support it.
+    pub async fn unload(&self) -> Result<bool, String> {
+        let config = self.get_config().await;
+        if config.provider != LLMProvider::Ollama {
+            return Ok(false);
+        }
+
+        for host in config.hosts() {
+            unload_ollama_model(&host, &config.model).await?;
+        }
+        log::info!("Unloaded Ollama model {}", config.model);
+        Ok(true)
+    }
+
+    /// Like `chat_completion`, but in privacy mode (when the provider or its fallback
+    /// is in the cloud) the given names and any phone numbers are swapped for
+    /// placeholders in the non-system messages and restored in the response.
+    pub async fn chat_completion_private(
+        &self,
+        messages: Vec<OpenAIMessage>,
+        names: &[String],
+        temperature: f32,
+        max_tokens: i32,
+        json_response: bool,
+        timeout: CallTimeout,
+    ) -> Result<String, String> {
+        self.complete_private(messages, names, temperature, max_tokens, json_response, timeout)
+            .await
+            .map(|completion| completion.content)
+    }
+
+    /// `chat_completion_private` for responses the caller parses: the result is only
+    /// written to the response cache by `Completion::parse_and_cache`
+    pub async fn complete_private(
+        &self,
+        messages: Vec<OpenAIMessage>,
+        names: &[String],
+        temperature: f32,
+        max_tokens: i32,
+        json_response: bool,
+        timeout: CallTimeout,
+    ) -> Result<Completion, String> {
+        self.complete_redacted(messages, names, temperature, max_tokens, json_response, timeout, true)
+            .await
+    }
+
+    /// `chat_completion_private` that neither reads nor writes the response cache, for
+    /// follow-up calls such as JSON repair whose bad output must not be pinned
+    pub async fn chat_completion_private_uncached(
+        &self,
+        messages: Vec<OpenAIMessage>,
+        names: &[String],
+        temperature: f32,
+        max_tokens: i32,
+        json_response: bool,
+        timeout: CallTimeout,
+    ) -> Result<String, String> {
+        self.complete_redacted(messages, names, temperature, max_tokens, json_response, timeout, false)
+            .await
+            .map(|completion| completion.content)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn complete_redacted(
+        &self,
+        mut messages: Vec<OpenAIMessage>,
+        names: &[String],
+        temperature: f32,
+        max_tokens: i32,
+        json_response: bool,
+        timeout: CallTimeout,
+        use_cache: bool,
+    ) -> Result<Completion, String> {
+        if !self.should_redact().await {
+            return self
+                .complete_timed(messages, temperature, max_tokens, json_response, timeout, use_cache)
+                .await;
+        }
+
+        let mut redactor = Redactor::new();
+        for name in names {
+            redactor.add_name(name);
+        }
+        for message in messages.iter_mut().filter(|m| m.role != "system") {
+            message.content = redactor.redact(&message.content);
+        }
+
+        // The cache keeps the redacted response, matching the redacted prompt it is keyed by
+        let mut completion = self
+            .complete_timed(messages, temperature, max_tokens, json_response, timeout, use_cache)
+            .await?;
+        completion.content = redactor.restore(&completion.content);
+        Ok(completion)
+    }
+
+    /// Embed texts with the primary provider's embedding model, in input order.
+    /// In privacy mode the given names and phone numbers are redacted first.
+    pub async fn embed(&self, texts: &[String], names: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        if !self.is_configured().await {
+            return Err("LLM not configured: API key required for OpenAI".to_string());
+        }
+        if texts.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let texts: Vec<String> = if self.should_redact().await {
+            let mut redactor = Redactor::new();
+            for name in names {
+                redactor.add_name(name);
+            }
+            texts.iter().map(|t| redactor.redact(t)).collect()
+        } else {
+            texts.to_vec()
+        };
+
+        let config = self.config.read().await.clone();
+        if config.provider == LLMProvider::Mock {
+            return Err("Embeddings are not supported by the mock provider".to_string());
+        }
+        let url = format!("{}/v1/embeddings", api_base(&config.base_url));
+        local_only::check_url(&url).await?;
+        let http_client = if config.provider.is_local() {
+            &self.client_local
+        } else {
+            &self.client_openai
+        };
+
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(EMBEDDING_BATCH_SIZE) {
+            let request = EmbeddingRequest {
+                model: config.embedding_model_name(),
+                input: batch.to_vec(),
+            };
+            let mut req = http_client
+                .post(&url)
+                .timeout(CallTimeout::Long.apply(config.request_timeout()))
+                .header("Content-Type", "application/json");
+            if let Some(api_key) = config.api_key.as_ref().filter(|k| !k.is_empty()) {
+                req = req.header("Authorization", format!("Bearer {}", api_key));
+            }
+
+            let response = req
+                .json(&request)
+                .send()
+                .await
+                .map_err(|e| format!("Request failed: {}", e))?;
+            let status = response.status();
+            if !status.is_success() {
+                let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+                return Err(format!("API error ({}): {}", status.as_u16(), error_text));
+            }
+
+            let mut parsed: EmbeddingResponse = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse embeddings: {}", e))?;
+            if parsed.data.len() != batch.len() {
+                return Err(format!("Expected {} embeddings, got {}", batch.len(), parsed.data.len()));
+            }
+            parsed.data.sort_by_key(|d| d.index);
+            embeddings.extend(parsed.data.into_iter().map(|d| d.embedding));
+        }
+
+        Ok(embeddings)
+    }
+
+    /// Make a chat completion request with retry logic and the provider's default timeout
     pub async fn chat_completion(
         &self,
         messages: Vec<OpenAIMessage>,
@@ -98,54 +576,153 @@ impl LLMClient {
         max_tokens: i32,
         json_response: bool,
     ) -> Result<String, String> {
+        self.chat_completion_timed(messages, temperature, max_tokens, json_response, CallTimeout::Default)
+            .await
+    }
+
+    /// Make a chat completion request with retry logic, switching to the fallback
+    /// provider (if configured) when the primary one is unreachable
+    pub async fn chat_completion_timed(
+        &self,
+        messages: Vec<OpenAIMessage>,
+        temperature: f32,
+        max_tokens: i32,
+        json_response: bool,
+        timeout: CallTimeout,
+    ) -> Result<String, String> {
+        self.complete_timed(messages, temperature, max_tokens, json_response, timeout, true)
+            .await
+            .map(|completion| completion.content)
+    }
+
+    async fn complete_timed(
+        &self,
+        messages: Vec<OpenAIMessage>,
+        temperature: f32,
+        max_tokens: i32,
+        json_response: bool,
+        timeout: CallTimeout,
+        use_cache: bool,
+    ) -> Result<Completion, String> {
         if !self.is_configured().await {
             return Err("LLM not configured: API key required for OpenAI".to_string());
         }
 
         let config = self.config.read().await.clone();
+        let fallback = config.fallback_config();
 
-        let (response_format, messages) = match config.provider {
-            LLMProvider::Ollama => {
-                // Ollama models may not support response_format; reinforce via prompt
-                let mut msgs = messages;
-                if json_response {
-                    if let Some(system_msg) = msgs.iter_mut().find(|m| m.role == "system") {
-                        system_msg.content.push_str(
-                            "\n\nCRITICAL: Output ONLY the raw JSON object. No markdown code fences, no explanation, no text before or after the JSON."
-                        );
-                    }
-                }
-                (None, msgs)
+        let request = build_request(&config, messages.clone(), temperature, max_tokens, json_response);
+        let result = self
+            .complete_across_hosts(&config, &request, timeout, fallback.is_some(), use_cache)
+            .await;
+
+        match (result, fallback) {
+            (Err(e), Some(fallback)) if Self::is_unavailable(&e) => {
+                log::warn!(
+                    "{:?} unavailable ({}), falling back to {:?} ({})",
+                    config.provider,
+                    e,
+                    fallback.provider,
+                    fallback.model
+                );
+                let request = build_request(&fallback, messages, temperature, max_tokens, json_response);
+                self.complete_with(&fallback, &request, timeout, false, use_cache)
+                    .await
+                    .map_err(|fallback_error| format!("{}; fallback also failed: {}", e, fallback_error))
             }
-            LLMProvider::OpenAI => {
-                let fmt = if json_response {
-                    Some(ResponseFormat {
-                        format_type: "json_object".to_string(),
-                    })
-                } else {
-                    None
-                };
-                (fmt, messages)
+            (result, _) => result,
+        }
+    }
+
+    /// Run a completion on the provider's hosts in round-robin order, moving on to
+    /// the next host when one is unreachable
+    async fn complete_across_hosts(
+        &self,
+        config: &LLMConfig,
+        request: &OpenAIRequest,
+        timeout: CallTimeout,
+        fail_fast: bool,
+        use_cache: bool,
+    ) -> Result<Completion, String> {
+        let hosts = config.hosts();
+        if hosts.len() < 2 {
+            return self.complete_with(config, request, timeout, fail_fast, use_cache).await;
+        }
+
+        let order = self.hosts.order(&hosts);
+        let mut last_error = String::new();
+        for (i, host) in order.iter().enumerate() {
+            // Only the last host gets the full retries, so a dead one hands over quickly
+            let is_last = i + 1 == order.len();
+            match self
+                .complete_with(&config.with_host(host), request, timeout, fail_fast || !is_last, use_cache)
+                .await
+            {
+                Ok(completion) => {
+                    self.hosts.mark_up(host);
+                    return Ok(completion);
+                }
+                Err(e) if Self::is_unreachable(&e) => {
+                    log::warn!("Ollama host {} unreachable: {}", host, e);
+                    self.hosts.mark_down(host);
+                    last_error = e;
+                }
+                Err(e) => return Err(e),
             }
-        };
+        }
+        Err(last_error)
+    }
+
+    /// Run a completion against one provider. With `fail_fast`, connection
+    /// errors and timeouts are not retried so the fallback can take over quickly.
+    async fn complete_with(
+        &self,
+        config: &LLMConfig,
+        request: &OpenAIRequest,
+        timeout: CallTimeout,
+        fail_fast: bool,
+        use_cache: bool,
+    ) -> Result<Completion, String> {
+        let timeout = timeout.apply(config.request_timeout());
 
-        let request = OpenAIRequest {
-            model: config.model.clone(),
-            messages,
-            temperature,
-            max_tokens,
-            response_format,
+        let cacheable = use_cache && self.response_cache && config.provider != LLMProvider::Mock;
+        let cache_key = if cacheable && request.temperature <= CACHE_MAX_TEMPERATURE {
+            prompt_hash(config, request)
+        } else {
+            None
         };
+        if let Some(key) = &cache_key {
+            match crate::db::llm_cache::load_response(key, cache_ttl_secs()) {
+                Ok(Some(content)) => {
+                    log::debug!("LLM cache hit ({})", config.model);
+                    return Ok(Completion::uncached(content));
+                }
+                Ok(None) => {}
+                Err(e) => log::debug!("LLM cache unavailable: {}", e),
+            }
+        }
 
         let mut last_error = String::new();
         let mut delay_ms = INITIAL_RETRY_DELAY_MS;
 
         for attempt in 0..MAX_RETRIES {
-            match self.make_request(&config, &request).await {
-                Ok(content) => return Ok(content),
+            match self.make_request(config, request, timeout).await {
+                Ok(content) => {
+                    // Stored only once the caller has parsed it (`Completion::parse_and_cache`)
+                    let cache = cache_key.map(|key| CacheSlot {
+                        key,
+                        model: config.model.clone(),
+                        raw: content.clone(),
+                    });
+                    return Ok(Completion { content, cache });
+                }
                 Err(e) => {
                     last_error = e.clone();
 
+                    if fail_fast && Self::is_unreachable(&e) {
+                        break;
+                    }
+
                     if attempt < MAX_RETRIES - 1 && Self::should_retry(&e) {
                         log::warn!(
                             "LLM request failed (attempt {}/{}): {}. Retrying in {}ms...",
@@ -174,24 +751,51 @@ impl LLMClient {
         ))
     }
 
-    /// Make a single request to the LLM API
+    /// Make a single request to the LLM API, pacing OpenAI calls and recording
+    /// the call in the AI call log when the user opted in
     async fn make_request(
         &self,
         config: &LLMConfig,
         request: &OpenAIRequest,
+        timeout: Duration,
     ) -> Result<String, String> {
-        let url = format!(
-            "{}/v1/chat/completions",
-            config.base_url.trim_end_matches('/')
-        );
+        if !config.provider.is_local() {
+            let prompt_tokens: usize = request.messages.iter().map(|m| estimate_tokens(&m.content)).sum();
+            self.rate_limiter.wait((prompt_tokens + request.max_tokens.max(0) as usize) as u64).await;
+        }
+
+        let started = std::time::Instant::now();
+        let result = self.send_request(config, request, timeout).await;
+        let latency_ms = started.elapsed().as_millis() as i64;
+
+        if crate::db::settings::load_ai_call_log_enabled().unwrap_or(false) {
+            log_call(config, request, &result, latency_ms);
+        }
+        result.map(|(content, _)| content)
+    }
+
+    async fn send_request(
+        &self,
+        config: &LLMConfig,
+        request: &OpenAIRequest,
+        timeout: Duration,
+    ) -> Result<(String, Option<OpenAIUsage>), String> {
+        if config.provider == LLMProvider::Mock {
+            return mock_response(&config.base_url, &request.messages).map(|content| (content, None));
+        }
+
+        let url = format!("{}/v1/chat/completions", api_base(&config.base_url));
+        local_only::check_url(&url).await?;
 
-        let http_client = match config.provider {
-            LLMProvider::Ollama => &self.client_ollama,
-            LLMProvider::OpenAI => &self.client_openai,
+        let http_client = if config.provider.is_local() {
+            &self.client_local
+        } else {
+            &self.client_openai
         };
 
         let mut req = http_client
             .post(&url)
+            .timeout(timeout)
             .header("Content-Type", "application/json");
 
         if let Some(ref api_key) = config.api_key {
@@ -206,6 +810,9 @@ impl LLMClient {
             .await
             .map_err(|e| format!("Request failed: {}", e))?;
 
+        if !config.provider.is_local() {
+            self.rate_limiter.observe(response.headers());
+        }
         let status = response.status();
 
         if status.is_success() {
@@ -217,7 +824,7 @@ impl LLMClient {
             llm_response
                 .choices
                 .first()
-                .map(|choice| choice.message.content.clone())
+                .map(|choice| (choice.message.content.clone(), llm_response.usage))
                 .ok_or_else(|| "No response content".to_string())
         } else {
             let error_text = response
@@ -229,6 +836,21 @@ impl LLMClient {
         }
     }
 
+    /// Connection refused, DNS failure or timeout: the host is down or asleep
+    fn is_unreachable(error: &str) -> bool {
+        let lower = error.to_lowercase();
+        lower.contains("request failed:")
+            || lower.contains("connection refused")
+            || lower.contains("timed out")
+            || lower.contains("timeout")
+    }
+
+    /// Errors that justify switching to the fallback provider. Client errors
+    /// (bad request, wrong model name) are left alone since they need fixing.
+    fn is_unavailable(error: &str) -> bool {
+        Self::is_unreachable(error) || Self::should_retry(error)
+    }
+
     /// Determine if an error is retryable
     fn should_retry(error: &str) -> bool {
         // Rate limiting
@@ -241,7 +863,7 @@ impl LLMClient {
         {
             return true;
         }
-        // Connection errors (critical for Ollama)
+        // Connection errors (critical for local providers)
         let lower = error.to_lowercase();
         lower.contains("connection refused")
             || lower.contains("timed out")
@@ -249,19 +871,183 @@ impl LLMClient {
             || lower.contains("reset by peer")
     }
 
-    /// Acquire a concurrency permit for Ollama requests. Returns None for OpenAI (zero overhead).
+    /// Acquire a concurrency permit for local providers. Returns None for OpenAI (zero overhead).
     pub async fn acquire_permit(&self) -> Option<tokio::sync::OwnedSemaphorePermit> {
-        let config = self.config.read().await;
-        match config.provider {
-            LLMProvider::Ollama => {
-                drop(config); // Release read lock before awaiting permit
-                Some(self.ollama_semaphore.clone().acquire_owned().await.expect("semaphore closed"))
+        let is_local = self.config.read().await.provider.is_local();
+        if is_local {
+            Some(self.local_semaphore.clone().acquire_owned().await.expect("semaphore closed"))
+        } else {
+            None
+        }
+    }
+}
+
+/// Fixture name for a mock prompt: SHA-256 of the request messages as JSON
+pub fn mock_prompt_key(messages: &[OpenAIMessage]) -> String {
+    let body = serde_json::to_string(messages).unwrap_or_default();
+    Sha256::digest(body.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Fixture name a mock provider looks up for these messages, after the
+/// provider-specific prompt changes `build_request` makes
+#[cfg(test)]
+pub fn mock_request_key(config: &LLMConfig, messages: Vec<OpenAIMessage>, json_response: bool) -> String {
+    mock_prompt_key(&build_request(config, messages, 0.0, 0, json_response).messages)
+}
+
+/// Canned response for a mock request: `<dir>/<prompt key>.json`, else `<dir>/default.json`.
+/// The file holds the raw response text (usually JSON).
+fn mock_response(dir: &str, messages: &[OpenAIMessage]) -> Result<String, String> {
+    let key = mock_prompt_key(messages);
+    let dir = std::path::Path::new(dir);
+    [dir.join(format!("{}.json", key)), dir.join("default.json")]
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .ok_or_else(|| format!("API error (404): no mock response for prompt {} in {}", key, dir.display()))
+}
+
+/// Write one call to the AI call log with the configured redaction rules applied
+fn log_call(
+    config: &LLMConfig,
+    request: &OpenAIRequest,
+    result: &Result<(String, Option<OpenAIUsage>), String>,
+    latency_ms: i64,
+) {
+    let messages: Vec<OpenAIMessage> = request
+        .messages
+        .iter()
+        .map(|m| OpenAIMessage { role: m.role.clone(), content: apply_redactions(&m.content) })
+        .collect();
+    let prompt = serde_json::to_string(&messages).unwrap_or_default();
+    let (response, error, usage) = match result {
+        Ok((content, usage)) => (Some(apply_redactions(content)), None, *usage),
+        Err(e) => (None, Some(e.as_str()), None),
+    };
+
+    let call = crate::db::ai_call_log::NewAICall {
+        provider: &format!("{:?}", config.provider),
+        model: &config.model,
+        prompt: &prompt,
+        response: response.as_deref(),
+        error,
+        latency_ms,
+        prompt_tokens: usage.map(|u| u.prompt_tokens),
+        completion_tokens: usage.map(|u| u.completion_tokens),
+    };
+    if let Err(e) = crate::db::ai_call_log::record_call(&call) {
+        log::debug!("Failed to log AI call: {}", e);
+    }
+}
+
+/// Ollama context windows are sized in steps, so most calls share one size and
+/// the model isn't reloaded for every prompt length
+const OLLAMA_NUM_CTX_STEP: usize = 8192;
+
+/// Context window that fits the prompt and the response
+fn ollama_num_ctx(messages: &[OpenAIMessage], max_tokens: i32) -> usize {
+    let prompt: usize = messages.iter().map(|m| estimate_tokens(&m.content) + 4).sum();
+    let needed = prompt + max_tokens.max(0) as usize;
+    needed.div_ceil(OLLAMA_NUM_CTX_STEP).max(1) * OLLAMA_NUM_CTX_STEP
+}
+
+/// Build the request body for a provider
+fn build_request(
+    config: &LLMConfig,
+    messages: Vec<OpenAIMessage>,
+    temperature: f32,
+    max_tokens: i32,
+    json_response: bool,
+) -> OpenAIRequest {
+    let (response_format, messages) = match config.provider {
+        LLMProvider::Ollama | LLMProvider::LocalOpenAI | LLMProvider::Mock => {
+            // Local models may not support response_format (LM Studio only accepts
+            // json_schema); reinforce via prompt
+            let mut msgs = messages;
+            if json_response {
+                if let Some(system_msg) = msgs.iter_mut().find(|m| m.role == "system") {
+                    system_msg.content.push_str(
+                        "\n\nCRITICAL: Output ONLY the raw JSON object. No markdown code fences, no explanation, no text before or after the JSON."
+                    );
+                }
             }
-            LLMProvider::OpenAI => None,
+            (None, msgs)
+        }
+        LLMProvider::OpenAI => {
+            let fmt = if json_response {
+                Some(ResponseFormat {
+                    format_type: "json_object".to_string(),
+                })
+            } else {
+                None
+            };
+            (fmt, messages)
         }
+    };
+
+    let options = (config.provider == LLMProvider::Ollama)
+        .then(|| OllamaOptions { num_ctx: ollama_num_ctx(&messages, max_tokens) });
+
+    OpenAIRequest {
+        model: config.model.clone(),
+        messages,
+        temperature,
+        max_tokens,
+        response_format,
+        keep_alive: config.ollama_keep_alive().map(str::to_string),
+        options,
     }
 }
 
+/// Base URL without a trailing slash or `/v1`, so both `http://localhost:1234`
+/// and `http://localhost:1234/v1` (as LM Studio displays it) work
+fn api_base(base_url: &str) -> &str {
+    let base = base_url.trim_end_matches('/');
+    base.strip_suffix("/v1").unwrap_or(base)
+}
+
+/// List models served by an OpenAI-compatible local server (LM Studio, llama-server)
+pub async fn list_local_openai_models(base_url: &str, api_key: Option<&str>) -> Result<Vec<OllamaModel>, String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let url = format!("{}/v1/models", api_base(base_url));
+    local_only::check_url(&url).await?;
+
+    let mut req = client.get(&url);
+    if let Some(api_key) = api_key.filter(|k| !k.is_empty()) {
+        req = req.header("Authorization", format!("Bearer {}", api_key));
+    }
+
+    let response = req
+        .send()
+        .await
+        .map_err(|_| "Local server is not running or unreachable. Start the server and try again.".to_string())?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Local server returned error ({})",
+            response.status().as_u16()
+        ));
+    }
+
+    let body: OpenAIModelsResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse models response: {}", e))?;
+
+    Ok(body
+        .data
+        .into_iter()
+        .map(|m| OllamaModel {
+            name: m.id,
+            size: None,
+            modified_at: None,
+        })
+        .collect())
+}
+
 /// List available models from an Ollama instance
 pub async fn list_ollama_models(base_url: &str) -> Result<Vec<OllamaModel>, String> {
     let client = Client::builder()
@@ -270,6 +1056,7 @@ pub async fn list_ollama_models(base_url: &str) -> Result<Vec<OllamaModel>, Stri
         .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
     let url = format!("{}/api/tags", base_url.trim_end_matches('/'));
+    local_only::check_url(&url).await?;
 
     let response = client
         .get(&url)
@@ -300,6 +1087,286 @@ pub async fn list_ollama_models(base_url: &str) -> Result<Vec<OllamaModel>, Stri
         .collect())
 }
 
+/// One progress update from Ollama's streaming `/api/pull`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaPullProgress {
+    #[serde(default)]
+    pub model: String,
+    pub status: String,
+    pub digest: Option<String>,
+    pub total: Option<u64>,
+    pub completed: Option<u64>,
+}
+
+/// Internal: a line of the pull stream, which reports failures in-band
+#[derive(Debug, Deserialize)]
+struct OllamaPullLine {
+    #[serde(default)]
+    status: String,
+    digest: Option<String>,
+    total: Option<u64>,
+    completed: Option<u64>,
+    error: Option<String>,
+}
+
+/// Parse one line of the pull stream; blank lines give None
+fn parse_pull_line(name: &str, line: &[u8]) -> Result<Option<OllamaPullProgress>, String> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+    let parsed: OllamaPullLine =
+        serde_json::from_slice(line).map_err(|e| format!("Failed to parse Ollama pull progress: {}", e))?;
+    if let Some(error) = parsed.error {
+        return Err(format!("Failed to pull {}: {}", name, error));
+    }
+    Ok(Some(OllamaPullProgress {
+        model: name.to_string(),
+        status: parsed.status,
+        digest: parsed.digest,
+        total: parsed.total,
+        completed: parsed.completed,
+    }))
+}
+
+/// Download a model into an Ollama instance, calling `on_progress` for each
+/// update of the newline-delimited JSON stream
+pub async fn pull_ollama_model<F>(base_url: &str, name: &str, mut on_progress: F) -> Result<(), String>
+where
+    F: FnMut(OllamaPullProgress),
+{
+    // No overall timeout: multi-gigabyte downloads take a while
+    let client = Client::builder()
+        .connect_timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let url = format!("{}/api/pull", base_url.trim_end_matches('/'));
+    local_only::check_url(&url).await?;
+
+    let mut response = client
+        .post(&url)
+        .json(&serde_json::json!({ "model": name, "stream": true }))
+        .send()
+        .await
+        .map_err(|_| "Ollama is not running or unreachable. Start Ollama and try again.".to_string())?;
+
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama returned error ({}): {}", status, error_text));
+    }
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut handle_line = |line: &[u8]| -> Result<bool, String> {
+        let Some(progress) = parse_pull_line(name, line)? else {
+            return Ok(false);
+        };
+        let done = progress.status == "success";
+        on_progress(progress);
+        Ok(done)
+    };
+
+    let mut finished = false;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Model download interrupted: {}", e))?
+    {
+        buffer.extend_from_slice(&chunk);
+        while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            finished |= handle_line(&line)?;
+        }
+    }
+    finished |= handle_line(&buffer)?;
+
+    if finished {
+        Ok(())
+    } else {
+        Err(format!("Pull of {} ended before Ollama reported success", name))
+    }
+}
+
+/// Remove a model from an Ollama instance
+pub async fn delete_ollama_model(base_url: &str, name: &str) -> Result<(), String> {
+    let client = Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let url = format!("{}/api/delete", base_url.trim_end_matches('/'));
+    local_only::check_url(&url).await?;
+
+    let response = client
+        .delete(&url)
+        .json(&serde_json::json!({ "model": name }))
+        .send()
+        .await
+        .map_err(|_| "Ollama is not running or unreachable. Start Ollama and try again.".to_string())?;
+
+    match response.status().as_u16() {
+        200..=299 => Ok(()),
+        404 => Err(format!("Model not found: {}", name)),
+        status => Err(format!("Ollama returned error ({})", status)),
+    }
+}
+
+/// How long Ollama keeps a warmed-up model loaded when no keep-alive is configured
+const OLLAMA_WARM_UP_KEEP_ALIVE: &str = "30m";
+
+/// Whether `value` is a keep-alive Ollama accepts: a number of seconds ("300", "-1")
+/// or a duration made of number+unit parts ("10m", "1h30m", "500ms")
+pub fn is_valid_keep_alive(value: &str) -> bool {
+    let value = value.trim();
+    let unsigned = value.strip_prefix('-').unwrap_or(value);
+    if unsigned.is_empty() {
+        return false;
+    }
+    if unsigned.chars().all(|c| c.is_ascii_digit()) {
+        return true;
+    }
+
+    let mut rest = unsigned;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        if digits == 0 || rest[..digits].parse::<f64>().is_err() {
+            return false;
+        }
+        rest = &rest[digits..];
+        let unit = ["ms", "h", "m", "s"].iter().find(|unit| rest.starts_with(*unit));
+        match unit {
+            Some(unit) => rest = &rest[unit.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Result of `LLMClient::ping`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LLMPing {
+    pub reachable: bool,
+    pub latency_ms: u64,
+    /// Whether the server lists the configured model (None when unreachable)
+    pub model_available: Option<bool>,
+    pub error: Option<String>,
+}
+
+/// `LLMPing` for one host of a multi-host config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostPing {
+    pub base_url: String,
+    #[serde(flatten)]
+    pub ping: LLMPing,
+}
+
+async fn ping_config(config: &LLMConfig) -> LLMPing {
+    let started = std::time::Instant::now();
+    let result = list_model_names(config).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(names) => LLMPing {
+            reachable: true,
+            latency_ms,
+            model_available: Some(names.iter().any(|name| model_matches(name, &config.model))),
+            error: None,
+        },
+        Err(e) => LLMPing { reachable: false, latency_ms, model_available: None, error: Some(e) },
+    }
+}
+
+/// Ollama reports "llama3.2:latest" for a model configured as "llama3.2"
+fn model_matches(listed: &str, configured: &str) -> bool {
+    listed == configured || listed.strip_suffix(":latest") == Some(configured)
+}
+
+/// Model names served by the configured provider
+async fn list_model_names(config: &LLMConfig) -> Result<Vec<String>, String> {
+    if config.provider == LLMProvider::Mock {
+        return match std::path::Path::new(&config.base_url).is_dir() {
+            true => Ok(vec![config.model.clone()]),
+            false => Err(format!("Mock fixtures directory not found: {}", config.base_url)),
+        };
+    }
+
+    let client = Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    if config.provider == LLMProvider::Ollama {
+        let url = format!("{}/api/tags", config.base_url.trim_end_matches('/'));
+        local_only::check_url(&url).await?;
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| format!("Ollama is unreachable: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Ollama returned error ({})", response.status().as_u16()));
+        }
+        let body: OllamaTagsResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+        return Ok(body.models.into_iter().map(|m| m.name).collect());
+    }
+
+    let url = format!("{}/v1/models", api_base(&config.base_url));
+    local_only::check_url(&url).await?;
+    let mut req = client.get(&url);
+    if let Some(api_key) = config.api_key.as_deref().filter(|k| !k.is_empty()) {
+        req = req.header("Authorization", format!("Bearer {}", api_key));
+    }
+    let response = req
+        .send()
+        .await
+        .map_err(|e| format!("LLM server is unreachable: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("LLM server returned error ({})", response.status().as_u16()));
+    }
+    let body: OpenAIModelsResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse models response: {}", e))?;
+    Ok(body.data.into_iter().map(|m| m.id).collect())
+}
+
+/// Preload a model with an empty generate request; `keep_alive` is Ollama's duration
+/// string (e.g. "30m", or "-1" to keep it loaded indefinitely)
+pub async fn warm_up_ollama_model(base_url: &str, model: &str, keep_alive: &str) -> Result<(), String> {
+    // Loading a large model from disk can take a while
+    let client = Client::builder()
+        .connect_timeout(Duration::from_secs(5))
+        .timeout(Duration::from_secs(300))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let url = format!("{}/api/generate", base_url.trim_end_matches('/'));
+    local_only::check_url(&url).await?;
+    let response = client
+        .post(&url)
+        .json(&serde_json::json!({ "model": model, "prompt": "", "keep_alive": keep_alive, "stream": false }))
+        .send()
+        .await
+        .map_err(|_| "Ollama is not running or unreachable. Start Ollama and try again.".to_string())?;
+
+    match response.status().as_u16() {
+        200..=299 => Ok(()),
+        404 => Err(format!("Model not found: {}. Pull it first.", model)),
+        status => {
+            let error_text = response.text().await.unwrap_or_default();
+            Err(format!("Ollama returned error ({}): {}", status, error_text))
+        }
+    }
+}
+
+/// Release a model's memory immediately (a zero keep-alive)
+pub async fn unload_ollama_model(base_url: &str, model: &str) -> Result<(), String> {
+    warm_up_ollama_model(base_url, model, "0").await
+}
+
 /// Ollama model info returned to frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OllamaModel {
@@ -322,6 +1389,17 @@ struct OllamaTagModel {
     modified_at: Option<String>,
 }
 
+/// Internal: OpenAI-style /v1/models response
+#[derive(Debug, Deserialize)]
+struct OpenAIModelsResponse {
+    data: Vec<OpenAIModelEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIModelEntry {
+    id: String,
+}
+
 /// Extract a JSON object from LLM output that may contain markdown fences or extra text.
 /// Tries raw parse first, then strips code fences, then finds the outermost `{...}`.
 fn extract_json(content: &str) -> Option<&str> {
@@ -382,3 +1460,121 @@ pub fn safe_json_parse<T: serde::de::DeserializeOwned>(
     );
     Err(format!("JSON parse error for {}: could not extract valid JSON", context))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_provider_serves_fixtures() {
+        let dir = std::env::temp_dir().join(format!("mock-llm-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let config = LLMConfig {
+            provider: LLMProvider::Mock,
+            base_url: dir.to_string_lossy().to_string(),
+            model: "mock".to_string(),
+            ..LLMConfig::default()
+        };
+        let client = LLMClient::new(config.clone());
+        let ask = |text: &str| vec![OpenAIMessage { role: "user".to_string(), content: text.to_string() }];
+
+        let request = build_request(&config, ask("hello"), 0.0, 10, false);
+        std::fs::write(dir.join(format!("{}.json", mock_prompt_key(&request.messages))), "hi there").unwrap();
+        assert_eq!(client.chat_completion(ask("hello"), 0.0, 10, false).await.unwrap(), "hi there");
+
+        let missing = client.chat_completion(ask("other"), 0.0, 10, false).await.unwrap_err();
+        assert!(missing.contains("no mock response"), "{}", missing);
+
+        std::fs::write(dir.join("default.json"), "fallback").unwrap();
+        assert_eq!(client.chat_completion(ask("other"), 0.0, 10, false).await.unwrap(), "fallback");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_privacy_mode_covers_every_remote_host() {
+        let redacts = |config: LLMConfig| async move { LLMClient::new(config).should_redact().await };
+        let local = LLMConfig {
+            provider: LLMProvider::Ollama,
+            base_url: "http://127.0.0.1:11434".to_string(),
+            privacy_mode: true,
+            ..LLMConfig::default()
+        };
+
+        assert!(!redacts(local.clone()).await);
+        assert!(redacts(LLMConfig { base_url: "http://192.168.1.20:11434".to_string(), ..local.clone() }).await);
+        assert!(redacts(LLMConfig { extra_hosts: vec!["http://192.168.1.21:11434".to_string()], ..local.clone() }).await);
+        let cloud_fallback = LLMFallback {
+            provider: LLMProvider::OpenAI,
+            base_url: "https://api.openai.com".to_string(),
+            api_key: None,
+            model: "gpt-4o-mini".to_string(),
+            timeout_secs: None,
+        };
+        assert!(redacts(LLMConfig { fallback: Some(cloud_fallback), ..local.clone() }).await);
+        assert!(!redacts(LLMConfig { base_url: "http://192.168.1.20:11434".to_string(), privacy_mode: false, ..local }).await);
+    }
+
+    #[test]
+    fn test_ollama_requests_size_the_context_window() {
+        let ask = |text: &str| vec![OpenAIMessage { role: "user".to_string(), content: text.to_string() }];
+        let ollama = LLMConfig { provider: LLMProvider::Ollama, ..LLMConfig::default() };
+
+        let short = build_request(&ollama, ask("hello"), 0.0, 500, false);
+        assert_eq!(short.options, Some(OllamaOptions { num_ctx: OLLAMA_NUM_CTX_STEP }));
+
+        // A deep-analysis sized prompt gets a window that holds it and the answer
+        let long = build_request(&ollama, ask(&"word ".repeat(24_000)), 0.0, 2000, false);
+        let num_ctx = long.options.unwrap().num_ctx;
+        assert!(num_ctx >= estimate_tokens(&"word ".repeat(24_000)) + 2000, "{}", num_ctx);
+        assert_eq!(num_ctx % OLLAMA_NUM_CTX_STEP, 0);
+
+        let openai = LLMConfig { provider: LLMProvider::OpenAI, ..LLMConfig::default() };
+        assert!(build_request(&openai, ask("hello"), 0.0, 500, false).options.is_none());
+    }
+
+    #[test]
+    fn test_only_parsed_responses_are_cached() {
+        crate::db::init_test_db();
+        let completion = |key: &str, content: &str| Completion {
+            content: content.to_string(),
+            cache: Some(CacheSlot { key: key.to_string(), model: "m".to_string(), raw: content.to_string() }),
+        };
+        let parse = |content: &str| safe_json_parse::<serde_json::Value>(content, "test");
+
+        assert!(completion("cache-invalid", "{\"truncated\": ").parse_and_cache(parse).is_err());
+        assert!(crate::db::llm_cache::load_response("cache-invalid", None).unwrap().is_none());
+
+        assert!(completion("cache-valid", "{\"ok\": true}").parse_and_cache(parse).is_ok());
+        assert_eq!(
+            crate::db::llm_cache::load_response("cache-valid", None).unwrap().as_deref(),
+            Some("{\"ok\": true}")
+        );
+    }
+
+    #[test]
+    fn test_parse_pull_line() {
+        let line = br#"{"status":"pulling 6a0746a1ec1a","digest":"sha256:6a07","total":4661211424,"completed":1048576}"#;
+        let progress = parse_pull_line("llama3.2", line).unwrap().unwrap();
+        assert_eq!(progress.model, "llama3.2");
+        assert_eq!((progress.total, progress.completed), (Some(4_661_211_424), Some(1_048_576)));
+
+        assert_eq!(parse_pull_line("llama3.2", b"{\"status\":\"success\"}\n").unwrap().unwrap().status, "success");
+        assert!(parse_pull_line("llama3.2", b"  \n").unwrap().is_none());
+        // Failures come in-band, with a success status code
+        assert_eq!(
+            parse_pull_line("nope", br#"{"error":"pull model manifest: file does not exist"}"#).unwrap_err(),
+            "Failed to pull nope: pull model manifest: file does not exist"
+        );
+        assert!(parse_pull_line("llama3.2", b"{\"status\":").is_err());
+    }
+
+    #[test]
+    fn test_keep_alive_validation() {
+        for valid in ["-1", "0", "300", "10m", "1h30m", "500ms", "1.5h"] {
+            assert!(is_valid_keep_alive(valid), "{}", valid);
+        }
+        for invalid in ["", "-", "10 minutes", "m", "5d", "1h-30m"] {
+            assert!(!is_valid_keep_alive(invalid), "{}", invalid);
+        }
+    }
+}
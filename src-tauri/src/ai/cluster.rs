@@ -0,0 +1,103 @@
+//! Topic clustering over embedding vectors (spherical k-means).
+
+/// Iterations after which k-means stops even if assignments still change
+const MAX_ITERATIONS: usize = 25;
+
+fn normalize(vector: &[f32]) -> Vec<f32> {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return vector.to_vec();
+    }
+    vector.iter().map(|x| x / norm).collect()
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Cluster count for `n` items: about sqrt(n/2), at least 2 and at most `max`
+pub fn suggested_cluster_count(n: usize, max: usize) -> usize {
+    (((n as f64) / 2.0).sqrt().round() as usize).clamp(2, max.max(2)).min(n)
+}
+
+/// Group vectors into at most `k` clusters by cosine similarity.
+/// Deterministic: seeds with the first vector, then repeatedly the vector least
+/// similar to all chosen seeds. Returns clusters of indices, largest first,
+/// dropping clusters smaller than `min_size`.
+pub fn kmeans(vectors: &[Vec<f32>], k: usize, min_size: usize) -> Vec<Vec<usize>> {
+    if vectors.is_empty() || k == 0 {
+        return vec![];
+    }
+    let points: Vec<Vec<f32>> = vectors.iter().map(|v| normalize(v)).collect();
+    let k = k.min(points.len());
+
+    // Farthest-point seeding
+    let mut centroids = vec![points[0].clone()];
+    while centroids.len() < k {
+        let next = (0..points.len())
+            .min_by(|&a, &b| {
+                let best = |i: usize| centroids.iter().map(|c| dot(&points[i], c)).fold(f32::MIN, f32::max);
+                best(a).total_cmp(&best(b))
+            })
+            .unwrap_or(0);
+        centroids.push(points[next].clone());
+    }
+
+    let nearest = |point: &[f32], centroids: &[Vec<f32>]| {
+        (0..centroids.len())
+            .max_by(|&a, &b| dot(point, &centroids[a]).total_cmp(&dot(point, &centroids[b])))
+            .unwrap_or(0)
+    };
+
+    let mut assignment: Vec<usize> = points.iter().map(|p| nearest(p, &centroids)).collect();
+    for _ in 0..MAX_ITERATIONS {
+        // Recompute centroids as the normalized mean of their members
+        for (c, centroid) in centroids.iter_mut().enumerate() {
+            let mut sum = vec![0.0f32; centroid.len()];
+            for (point, _) in points.iter().zip(&assignment).filter(|(_, &a)| a == c) {
+                for (s, x) in sum.iter_mut().zip(point) {
+                    *s += x;
+                }
+            }
+            if sum.iter().any(|x| *x != 0.0) {
+                *centroid = normalize(&sum);
+            }
+        }
+
+        let next: Vec<usize> = points.iter().map(|p| nearest(p, &centroids)).collect();
+        if next == assignment {
+            break;
+        }
+        assignment = next;
+    }
+
+    let mut clusters: Vec<Vec<usize>> = vec![vec![]; k];
+    for (idx, c) in assignment.into_iter().enumerate() {
+        clusters[c].push(idx);
+    }
+    clusters.retain(|c| !c.is_empty() && c.len() >= min_size);
+    clusters.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kmeans_separates_topics() {
+        let vectors = vec![
+            vec![1.0, 0.1, 0.0],
+            vec![0.9, 0.0, 0.1],
+            vec![1.0, 0.2, 0.1],
+            vec![0.0, 1.0, 0.1],
+            vec![0.1, 0.9, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ];
+        let clusters = kmeans(&vectors, 3, 2);
+        assert_eq!(clusters, vec![vec![0, 1, 2], vec![3, 4]]);
+
+        assert_eq!(suggested_cluster_count(200, 12), 10);
+        assert_eq!(suggested_cluster_count(3, 12), 2);
+    }
+}
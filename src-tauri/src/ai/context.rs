@@ -0,0 +1,532 @@
+use crate::ai::sanitize::sanitize_sender_name;
+use crate::ai::types::{BriefingV2Response, ChatContext, ChatMessage, ChatSummaryContext, ChatType, FYIItem};
+use crate::commands::calls::is_missed_call;
+use crate::telegram::client::{BatchMessageRequest, Chat, Message, MessageContent, StoryReaction};
+use crate::telegram::TelegramClient;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Large groups (500+ members) are auto-classified as FYI to save API calls.
+/// Mirrors LARGE_GROUP_THRESHOLD in the frontend briefing store.
+pub const LARGE_GROUP_THRESHOLD: i32 = 500;
+
+/// Text used when handing a message to the LLM (non-text content is collapsed)
+pub fn message_text_for_ai(content: &MessageContent) -> String {
+    content.describe()
+}
+
+/// Whether a chat should skip the LLM and be reported as FYI directly
+pub fn is_large_group(chat: &Chat) -> bool {
+    (chat.chat_type == "group" || chat.chat_type == "channel")
+        && chat.member_count.unwrap_or(0) >= LARGE_GROUP_THRESHOLD
+}
+
+/// Build the FYI item used for large groups that are not sent to the LLM
+pub fn large_group_fyi(chat: &Chat, id: i32) -> FYIItem {
+    let (message_count, reading_time_secs) = unread_backlog(&[], chat.unread_count);
+    FYIItem {
+        id,
+        chat_id: chat.id,
+        chat_name: chat.title.clone(),
+        chat_type: if chat.chat_type == "channel" { "channel" } else { "group" }.to_string(),
+        unread_count: chat.unread_count,
+        last_message: chat.last_message.as_ref().map(|m| m.content.describe()),
+        last_message_date: chat.last_message.as_ref().and_then(|m| {
+            chrono::DateTime::from_timestamp(m.date, 0).map(|dt| dt.to_rfc3339())
+        }),
+        priority: "fyi".to_string(),
+        summary: format!("{} new messages in large group", chat.unread_count),
+        injection_warning: None,
+        chat_note: chat.note.clone(),
+        message_count,
+        reading_time_secs,
+        category: None,
+    }
+}
+
+/// Story reactions older than this are left out of the briefing (stories expire after a day)
+pub const STORY_REACTION_WINDOW_SECS: i64 = 86400;
+
+/// One FYI item per person who reacted to the user's stories, e.g.
+/// "Alice reacted ❤️ to your story". Ids count up from `first_id`.
+pub fn story_reaction_fyis(reactions: &[StoryReaction], first_id: i32) -> Vec<FYIItem> {
+    // People in the order of their latest reaction, newest first
+    let mut sorted: Vec<&StoryReaction> = reactions.iter().collect();
+    sorted.sort_by_key(|r| std::cmp::Reverse(r.reaction.date));
+    let mut people: Vec<i64> = vec![];
+    for r in &sorted {
+        if !people.contains(&r.reaction.user_id) {
+            people.push(r.reaction.user_id);
+        }
+    }
+
+    people
+        .iter()
+        .enumerate()
+        .map(|(idx, &user_id)| {
+            let theirs: Vec<&StoryReaction> = sorted.iter().copied().filter(|r| r.reaction.user_id == user_id).collect();
+            let mut emojis: Vec<&str> = vec![];
+            let mut stories: Vec<i32> = vec![];
+            for r in &theirs {
+                if let Some(emoji) = r.reaction.emoji.as_deref() {
+                    if !emojis.contains(&emoji) {
+                        emojis.push(emoji);
+                    }
+                }
+                if !stories.contains(&r.story_id) {
+                    stories.push(r.story_id);
+                }
+            }
+
+            let name = if theirs[0].reaction.name.is_empty() { "Someone" } else { theirs[0].reaction.name.as_str() };
+            let emojis = if emojis.is_empty() { String::new() } else { format!(" {}", emojis.join(" ")) };
+            let target = match stories.len() {
+                1 => "your story".to_string(),
+                n => format!("{} of your stories", n),
+            };
+
+            FYIItem {
+                id: first_id.wrapping_add(idx as i32),
+                chat_id: user_id,
+                chat_name: name.to_string(),
+                chat_type: ChatType::Dm.to_string(),
+                unread_count: 0,
+                last_message: None,
+                last_message_date: chrono::DateTime::from_timestamp(theirs[0].reaction.date, 0).map(|dt| dt.to_rfc3339()),
+                priority: "fyi".to_string(),
+                summary: format!("{} reacted{} to {}", name, emojis, target),
+                injection_warning: None,
+                chat_note: None,
+                message_count: 0,
+                reading_time_secs: 0,
+                category: None,
+            }
+        })
+        .collect()
+}
+
+/// Reading speed used for backlog estimates
+const READING_WORDS_PER_MINUTE: f64 = 230.0;
+
+/// Time to take in a message beyond its words (sender, photo, sticker)
+const SECS_PER_MESSAGE: f64 = 2.0;
+
+/// Words assumed for unread messages that weren't loaded
+const DEFAULT_WORDS_PER_MESSAGE: f64 = 12.0;
+
+/// Size of a chat's unread backlog: (messages, estimated seconds to read them).
+/// Unread messages are the incoming ones after the user's last message; those
+/// older than what was loaded are assumed to be as long as the loaded ones.
+pub fn unread_backlog(messages: &[ChatMessage], unread_count: i32) -> (i32, i32) {
+    let limit = if unread_count > 0 { unread_count as usize } else { usize::MAX };
+    let loaded: Vec<&ChatMessage> =
+        messages.iter().rev().take_while(|m| !m.is_outgoing).take(limit).collect();
+    let count = if unread_count > 0 { unread_count as usize } else { loaded.len() };
+
+    let loaded_words: usize = loaded.iter().map(|m| m.text.split_whitespace().count()).sum();
+    let avg_words = if loaded.is_empty() {
+        DEFAULT_WORDS_PER_MESSAGE
+    } else {
+        loaded_words as f64 / loaded.len() as f64
+    };
+    let words = loaded_words as f64 + avg_words * (count - loaded.len()) as f64;
+    let secs = words / READING_WORDS_PER_MINUTE * 60.0 + count as f64 * SECS_PER_MESSAGE;
+    (count as i32, secs.ceil() as i32)
+}
+
+/// Phrases that usually mean the sender expects an answer or an action
+static REQUEST_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)\b(can you|could you|would you|will you|please|pls|let me know|lmk|any update|any news|what do you think|your thoughts|get back to me|waiting for your|need you to|are you free|are you available)\b",
+    )
+    .unwrap()
+});
+
+/// Deterministic check for a question or request in a message (no LLM involved)
+pub fn is_question_or_request(text: &str) -> bool {
+    text.contains('?') || REQUEST_PATTERN.is_match(text)
+}
+
+/// True if the last incoming message is a text ending with a question mark
+fn detect_question(messages: &[Message]) -> bool {
+    messages
+        .iter()
+        .rev()
+        .find(|m| !m.is_outgoing)
+        .map(|m| match &m.content {
+            MessageContent::Text { text } => text.trim().ends_with('?'),
+            _ => false,
+        })
+        .unwrap_or(false)
+}
+
+/// True if an incoming call was missed after the user's last outgoing message
+fn detect_missed_call(messages: &[Message]) -> bool {
+    messages
+        .iter()
+        .rev()
+        .take_while(|m| !m.is_outgoing)
+        .any(is_missed_call)
+}
+
+/// Hours since the last message (999 when there are no messages)
+fn hours_since_last(messages: &[Message]) -> f64 {
+    match messages.last() {
+        Some(m) => (chrono::Utc::now().timestamp() - m.date) as f64 / 3600.0,
+        None => 999.0,
+    }
+}
+
+/// Build a briefing ChatContext from a chat and its recent messages (chronological order).
+/// Backend counterpart of the context assembly done in the frontend briefing store.
+pub fn build_chat_context(chat: &Chat, messages: &[Message]) -> ChatContext {
+    ChatContext {
+        chat_id: chat.id,
+        chat_title: chat.title.clone(),
+        chat_type: chat.chat_type.clone(),
+        messages: messages
+            .iter()
+            .map(|m| ChatMessage {
+                id: m.id,
+                sender_name: m.sender_name.clone(),
+                text: message_text_for_ai(&m.content),
+                date: m.date,
+                is_outgoing: m.is_outgoing,
+                kind: Some(m.content.kind().to_string()),
+            })
+            .collect(),
+        unread_count: chat.unread_count,
+        last_message_is_outgoing: messages.last().map(|m| m.is_outgoing).unwrap_or(false),
+        has_unanswered_question: detect_question(messages),
+        hours_since_last_activity: hours_since_last(messages),
+        is_private_chat: chat.chat_type == "private",
+        has_missed_call: detect_missed_call(messages),
+    }
+}
+
+/// How an automatic FYI describes `count` messages of a notification-only kind,
+/// or None for kinds that need the model to look at them
+fn notification_phrase(kind: &str, count: usize) -> Option<String> {
+    match (kind, count) {
+        ("storyMention", 1) => Some("mentioned you in a story".to_string()),
+        ("storyMention", n) => Some(format!("mentioned you in {} stories", n)),
+        ("premiumGift", 1) => Some("gifted you Telegram Premium".to_string()),
+        ("premiumGift", n) => Some(format!("sent you {} Telegram Premium gifts", n)),
+        ("starsGift", 1) => Some("gifted you Telegram Stars".to_string()),
+        ("starsGift", n) => Some(format!("sent you {} Telegram Stars gifts", n)),
+        _ => None,
+    }
+}
+
+/// Summary for chats whose messages since the user's last outgoing message are all
+/// notifications (story mentions, gifts). Such chats are reported as FYI without an
+/// LLM call; None means the chat needs classifying.
+pub fn auto_fyi_summary(chat: &ChatContext) -> Option<String> {
+    let mut incoming: Vec<&ChatMessage> = chat.messages.iter().rev().take_while(|m| !m.is_outgoing).collect();
+    incoming.reverse();
+
+    // Kinds in the order they first appear, with counts
+    let mut kinds: Vec<(&str, usize)> = vec![];
+    for message in &incoming {
+        let kind = message.kind.as_deref()?;
+        match kinds.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, count)) => *count += 1,
+            None => kinds.push((kind, 1)),
+        }
+    }
+
+    let phrases = kinds
+        .iter()
+        .map(|(kind, count)| notification_phrase(kind, *count))
+        .collect::<Option<Vec<String>>>()?;
+    let sender = sanitize_sender_name(&incoming.last()?.sender_name);
+    Some(format!("{} {}", sender, phrases.join(" and ")))
+}
+
+/// Summary for chats the user pinned to a priority, which skip the LLM:
+/// "3 new messages from Alice, Bob and 2 others"
+pub fn pinned_summary(chat: &ChatContext, message_count: i32) -> String {
+    let mut senders: Vec<String> = vec![];
+    for message in chat.messages.iter().rev().take_while(|m| !m.is_outgoing) {
+        let sender = sanitize_sender_name(&message.sender_name);
+        if !sender.is_empty() && !senders.contains(&sender) {
+            senders.push(sender);
+        }
+    }
+    senders.reverse();
+
+    let messages = match message_count {
+        0 => return "No new messages".to_string(),
+        1 => "1 new message".to_string(),
+        n => format!("{} new messages", n),
+    };
+    let from = match senders.as_slice() {
+        [] => return messages,
+        [only] => only.clone(),
+        [first, second] => format!("{} and {}", first, second),
+        [first, second, rest @ ..] => match rest.len() {
+            1 => format!("{}, {} and {}", first, second, rest[0]),
+            n => format!("{}, {} and {} others", first, second, n),
+        },
+    };
+    format!("{} from {}", messages, from)
+}
+
+/// Rough token count for prompt budgeting (about 4 characters per token)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Newest messages whose sender names and text fit within `budget` tokens.
+/// The latest message is always kept, even if it alone exceeds the budget.
+pub fn fit_token_budget(messages: &[ChatMessage], budget: usize) -> &[ChatMessage] {
+    let mut used = 0;
+    let mut start = messages.len();
+    for (idx, message) in messages.iter().enumerate().rev() {
+        // +2 for the "Name: " separator and newline
+        used += estimate_tokens(&message.sender_name) + estimate_tokens(&message.text) + 2;
+        if used > budget && start < messages.len() {
+            break;
+        }
+        start = idx;
+    }
+    &messages[start..]
+}
+
+/// Build a summary context from a chat and its messages (chronological order)
+pub fn build_summary_context(chat: &Chat, messages: &[Message]) -> ChatSummaryContext {
+    ChatSummaryContext {
+        chat_id: chat.id,
+        chat_title: chat.title.clone(),
+        chat_type: chat.chat_type.clone(),
+        messages: build_chat_context(chat, messages).messages,
+        unread_count: chat.unread_count,
+    }
+}
+
+/// Fetch recent messages for unread chats and build briefing inputs.
+/// Returns the contexts to send to the LLM plus pre-classified FYI items for large groups
+/// and reactions to the user's stories.
+pub async fn gather_briefing_inputs(
+    client: &TelegramClient,
+    chats: &[Chat],
+) -> Result<(Vec<ChatContext>, Vec<FYIItem>), String> {
+    let mut chats = chats.to_vec();
+    crate::db::apply_local_chat_state(&mut chats);
+    let unread: Vec<&Chat> = chats.iter().filter(|c| c.unread_count > 0).collect();
+    let now_id = chrono::Utc::now().timestamp() as i32;
+
+    let (large, small): (Vec<&Chat>, Vec<&Chat>) = unread.into_iter().partition(|c| is_large_group(c));
+
+    let mut fyis: Vec<FYIItem> = large
+        .iter()
+        .enumerate()
+        .map(|(idx, chat)| large_group_fyi(chat, now_id.wrapping_add(idx as i32)))
+        .collect();
+
+    // Story reactions don't arrive as messages, so they become FYI items directly
+    let since = chrono::Utc::now().timestamp() - STORY_REACTION_WINDOW_SECS;
+    match client.get_story_reactions(since).await {
+        Ok(reactions) => {
+            let first_id = now_id.wrapping_add(fyis.len() as i32);
+            fyis.extend(story_reaction_fyis(&reactions, first_id));
+        }
+        Err(e) => log::warn!("Failed to load story reactions: {}", e),
+    }
+
+    if small.is_empty() {
+        return Ok((vec![], fyis));
+    }
+
+    let requests: Vec<BatchMessageRequest> = small
+        .iter()
+        .map(|c| BatchMessageRequest {
+            chat_id: c.id,
+            limit: c.unread_count.clamp(5, 30),
+        })
+        .collect();
+
+    let results = crate::db::message_sync::batch_messages(client, requests).await;
+
+    let contexts = small
+        .iter()
+        .filter_map(|chat| {
+            let result = results.iter().find(|r| r.chat_id == chat.id)?;
+            if result.messages.is_empty() {
+                return None;
+            }
+            Some(build_chat_context(chat, &result.messages))
+        })
+        .collect();
+
+    Ok((contexts, fyis))
+}
+
+/// Merge large-group FYI items into an LLM briefing response, updating stats
+pub fn merge_large_group_fyis(mut response: BriefingV2Response, fyis: Vec<FYIItem>) -> BriefingV2Response {
+    response.stats.total_unread += fyis.iter().map(|f| f.unread_count).sum::<i32>();
+    response.fyi_summaries.extend(fyis);
+    response.stats.fyi_count = response.fyi_summaries.len() as i32;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_question_or_request() {
+        assert!(is_question_or_request("Are we still on for tomorrow?"));
+        assert!(is_question_or_request("Could you send me the deck"));
+        assert!(is_question_or_request("LMK when you land"));
+        assert!(!is_question_or_request("Thanks, sounds good"));
+        assert!(!is_question_or_request("Pleased to meet you"));
+    }
+
+    #[test]
+    fn test_unread_backlog() {
+        let message = |text: &str, is_outgoing: bool| ChatMessage {
+            id: 0,
+            sender_name: "Alice".to_string(),
+            text: text.to_string(),
+            date: 0,
+            is_outgoing,
+            kind: None,
+        };
+        let long = vec!["word"; 230].join(" ");
+        let messages = vec![message("Already read", false), message("Ok", true), message(&long, false)];
+
+        // One loaded unread message of 230 words: a minute plus the per-message time
+        assert_eq!(unread_backlog(&messages, 1), (1, 62));
+        // Unread messages that weren't loaded count as long as the loaded ones
+        assert_eq!(unread_backlog(&messages, 3), (3, 186));
+        // Without an unread count, the messages since the user's reply are the backlog
+        assert_eq!(unread_backlog(&messages, 0), (1, 62));
+        assert_eq!(unread_backlog(&[], 0), (0, 0));
+    }
+
+    #[test]
+    fn test_auto_fyi_for_notifications() {
+        let message = |text: &str, is_outgoing: bool, kind: &str| ChatMessage {
+            id: 0,
+            sender_name: "Alice".to_string(),
+            text: text.to_string(),
+            date: 0,
+            is_outgoing,
+            kind: Some(kind.to_string()),
+        };
+        let context = |messages: Vec<ChatMessage>| ChatContext {
+            chat_id: 1,
+            chat_title: "Alice".to_string(),
+            chat_type: "private".to_string(),
+            messages,
+            unread_count: 1,
+            last_message_is_outgoing: false,
+            has_unanswered_question: false,
+            hours_since_last_activity: 1.0,
+            is_private_chat: true,
+            has_missed_call: false,
+        };
+        let mention = || message("[Mentioned you in a story]", false, "storyMention");
+
+        let chat = context(vec![message("Can you call me?", false, "text"), message("Sure", true, "text"), mention()]);
+        assert_eq!(auto_fyi_summary(&chat).as_deref(), Some("Alice mentioned you in a story"));
+        let chat = context(vec![mention(), mention()]);
+        assert_eq!(auto_fyi_summary(&chat).as_deref(), Some("Alice mentioned you in 2 stories"));
+        let chat = context(vec![mention(), message("[Gifted Telegram Premium for 3 months]", false, "premiumGift")]);
+        assert_eq!(
+            auto_fyi_summary(&chat).as_deref(),
+            Some("Alice mentioned you in a story and gifted you Telegram Premium")
+        );
+
+        // Anything else since the last reply needs the model
+        assert!(auto_fyi_summary(&context(vec![mention(), message("Did you see it?", false, "text")])).is_none());
+        assert!(auto_fyi_summary(&context(vec![message("Thanks", true, "text")])).is_none());
+        let mut untyped = mention();
+        untyped.kind = None;
+        assert!(auto_fyi_summary(&context(vec![untyped])).is_none());
+    }
+
+    #[test]
+    fn test_story_reaction_fyis() {
+        let reaction = |story_id: i32, user_id: i64, name: &str, emoji: Option<&str>, date: i64| StoryReaction {
+            story_id,
+            reaction: crate::telegram::client::PeerReaction {
+                user_id,
+                name: name.to_string(),
+                emoji: emoji.map(str::to_string),
+                date,
+            },
+        };
+        let reactions = vec![
+            reaction(1, 10, "Alice", Some("❤"), 100),
+            reaction(1, 20, "", None, 300),
+            reaction(2, 10, "Alice", Some("🔥"), 200),
+            reaction(3, 10, "Alice", Some("❤"), 150),
+        ];
+
+        let fyis = story_reaction_fyis(&reactions, 7);
+        let summaries: Vec<(i32, i64, &str)> = fyis.iter().map(|f| (f.id, f.chat_id, f.summary.as_str())).collect();
+        assert_eq!(
+            summaries,
+            vec![(7, 20, "Someone reacted to your story"), (8, 10, "Alice reacted 🔥 ❤ to 3 of your stories")]
+        );
+        assert!(fyis.iter().all(|f| f.priority == "fyi" && f.unread_count == 0));
+        assert!(story_reaction_fyis(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn test_pinned_summary() {
+        let message = |sender: &str, is_outgoing: bool| ChatMessage {
+            id: 0,
+            sender_name: sender.to_string(),
+            text: "Hi".to_string(),
+            date: 0,
+            is_outgoing,
+            kind: None,
+        };
+        let context = |messages: Vec<ChatMessage>| ChatContext {
+            chat_id: 1,
+            chat_title: "Team".to_string(),
+            chat_type: "group".to_string(),
+            messages,
+            unread_count: 0,
+            last_message_is_outgoing: false,
+            has_unanswered_question: false,
+            hours_since_last_activity: 1.0,
+            is_private_chat: false,
+            has_missed_call: false,
+        };
+
+        let chat = context(vec![
+            message("Dan", false),
+            message("Me", true),
+            message("Ann", false),
+            message("Bob", false),
+        ]);
+        assert_eq!(pinned_summary(&chat, 2), "2 new messages from Ann and Bob");
+        let chat = context(["Ann", "Bob", "Cy", "Dan", "Ann"].iter().map(|s| message(s, false)).collect());
+        assert_eq!(pinned_summary(&chat, 5), "5 new messages from Bob, Cy and 2 others");
+        assert_eq!(pinned_summary(&context(vec![]), 1), "1 new message");
+        assert_eq!(pinned_summary(&context(vec![]), 0), "No new messages");
+    }
+
+    #[test]
+    fn test_fit_token_budget() {
+        let message = |text: &str| ChatMessage {
+            id: 0,
+            sender_name: "Bob".to_string(),
+            text: text.to_string(),
+            date: 0,
+            is_outgoing: false,
+            kind: None,
+        };
+        let messages = vec![message(&"a".repeat(400)), message("short"), message("latest")];
+
+        // 400 chars = 100 tokens; the two short messages cost 5 tokens each
+        assert_eq!(fit_token_budget(&messages, 20).len(), 2);
+        assert_eq!(fit_token_budget(&messages, 200).len(), 3);
+        assert_eq!(fit_token_budget(&messages[..1], 10).len(), 1);
+        assert!(fit_token_budget(&[], 10).is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+//! Briefing evaluation: replay canned chats with known priorities through the
+//! classifier and score the result, so prompt or model changes can be checked
+//! before release. Fixtures are JSON arrays of `EvalCase`
+//! (see `fixtures/briefing_eval.json`).
+
+use crate::ai::types::{BriefingV2Response, ChatContext};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Priorities a chat can be classified as
+pub const PRIORITIES: [&str; 3] = ["urgent", "needs_reply", "fyi"];
+
+/// Reported for chats the model failed to classify
+const FAILED: &str = "failed";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCase {
+    /// Short description shown in mismatches
+    pub name: String,
+    pub expected_priority: String,
+    pub chat: ChatContext,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityScore {
+    pub priority: String,
+    pub expected: usize,
+    pub predicted: usize,
+    pub correct: usize,
+    /// correct / predicted (None when nothing was predicted)
+    pub precision: Option<f64>,
+    /// correct / expected (None when nothing was expected)
+    pub recall: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalMismatch {
+    pub name: String,
+    pub chat_id: i64,
+    pub expected: String,
+    pub actual: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalReport {
+    pub total: usize,
+    pub correct: usize,
+    pub accuracy: f64,
+    pub failed: usize,
+    pub per_priority: Vec<PriorityScore>,
+    pub mismatches: Vec<EvalMismatch>,
+}
+
+/// Parse a fixture file, checking chat ids are unique and priorities are known
+pub fn parse_fixtures(json: &str) -> Result<Vec<EvalCase>, String> {
+    let cases: Vec<EvalCase> = serde_json::from_str(json).map_err(|e| format!("Invalid eval fixture: {}", e))?;
+    let mut seen = HashMap::new();
+    for case in &cases {
+        if !PRIORITIES.contains(&case.expected_priority.as_str()) {
+            return Err(format!("Case \"{}\" has unknown priority: {}", case.name, case.expected_priority));
+        }
+        if let Some(other) = seen.insert(case.chat.chat_id, &case.name) {
+            return Err(format!("Cases \"{}\" and \"{}\" share chat id {}", other, case.name, case.chat.chat_id));
+        }
+    }
+    Ok(cases)
+}
+
+pub fn load_fixtures(path: &str) -> Result<Vec<EvalCase>, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    parse_fixtures(&json)
+}
+
+/// Predicted priority per chat id from a briefing
+pub fn predictions(briefing: &BriefingV2Response) -> HashMap<i64, String> {
+    let mut predicted = HashMap::new();
+    for item in &briefing.needs_response {
+        predicted.insert(item.chat_id, item.priority.clone());
+    }
+    for item in &briefing.fyi_summaries {
+        predicted.insert(item.chat_id, item.priority.clone());
+    }
+    for chat in &briefing.failed_chats {
+        predicted.insert(chat.chat_id, FAILED.to_string());
+    }
+    predicted
+}
+
+fn ratio(numerator: usize, denominator: usize) -> Option<f64> {
+    (denominator > 0).then(|| numerator as f64 / denominator as f64)
+}
+
+/// Compare predictions with the expected priorities
+pub fn score(cases: &[EvalCase], predicted: &HashMap<i64, String>) -> EvalReport {
+    let mut mismatches = vec![];
+    let mut correct = 0;
+    let mut failed = 0;
+    let actual_for = |case: &EvalCase| {
+        predicted.get(&case.chat.chat_id).cloned().unwrap_or_else(|| FAILED.to_string())
+    };
+
+    for case in cases {
+        let actual = actual_for(case);
+        if actual == FAILED {
+            failed += 1;
+        }
+        if actual == case.expected_priority {
+            correct += 1;
+        } else {
+            mismatches.push(EvalMismatch {
+                name: case.name.clone(),
+                chat_id: case.chat.chat_id,
+                expected: case.expected_priority.clone(),
+                actual,
+            });
+        }
+    }
+
+    let per_priority = PRIORITIES
+        .iter()
+        .map(|priority| {
+            let expected = cases.iter().filter(|c| c.expected_priority == *priority).count();
+            let predicted = cases.iter().filter(|c| actual_for(c) == *priority).count();
+            let correct = cases
+                .iter()
+                .filter(|c| c.expected_priority == *priority && actual_for(c) == *priority)
+                .count();
+            PriorityScore {
+                priority: priority.to_string(),
+                expected,
+                predicted,
+                correct,
+                precision: ratio(correct, predicted),
+                recall: ratio(correct, expected),
+            }
+        })
+        .collect();
+
+    EvalReport {
+        total: cases.len(),
+        correct,
+        accuracy: ratio(correct, cases.len()).unwrap_or(0.0),
+        failed,
+        per_priority,
+        mismatches,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOLDEN: &str = include_str!("../../fixtures/briefing_eval.json");
+
+    #[test]
+    fn test_golden_fixtures_parse_and_score() {
+        let cases = parse_fixtures(GOLDEN).unwrap();
+        assert!(PRIORITIES.iter().all(|p| cases.iter().any(|c| c.expected_priority == *p)));
+
+        let perfect: HashMap<i64, String> = cases
+            .iter()
+            .map(|c| (c.chat.chat_id, c.expected_priority.clone()))
+            .collect();
+        let report = score(&cases, &perfect);
+        assert_eq!(report.correct, cases.len());
+        assert!(report.per_priority.iter().all(|s| s.precision == Some(1.0)));
+
+        // Everything classified as fyi, and the first (urgent) chat missing
+        assert_eq!(cases[0].expected_priority, "urgent");
+        let mut lazy: HashMap<i64, String> = cases.iter().map(|c| (c.chat.chat_id, "fyi".to_string())).collect();
+        lazy.remove(&cases[0].chat.chat_id);
+        let report = score(&cases, &lazy);
+        assert_eq!(report.failed, 1);
+        let fyi = report.per_priority.iter().find(|s| s.priority == "fyi").unwrap();
+        assert_eq!(fyi.recall, Some(1.0));
+        assert_eq!(report.per_priority[0].precision, None);
+        assert_eq!(report.mismatches.len(), cases.len() - report.correct);
+    }
+}
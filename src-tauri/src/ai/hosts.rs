@@ -0,0 +1,70 @@
+//! Round-robin over several Ollama instances (e.g. the laptop plus a GPU box on
+//! the LAN). Each call starts at the next host in turn; hosts that just failed
+//! to connect are tried last until their cooldown expires.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long a host that refused a connection is skipped
+const HOST_COOLDOWN: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+pub struct HostPool {
+    next: AtomicUsize,
+    down_until: Mutex<HashMap<String, Instant>>,
+}
+
+/// `hosts` rotated to start at `start`, with hosts in `down` moved to the end
+fn rotation(hosts: &[String], start: usize, down: &HashSet<&str>) -> Vec<String> {
+    let rotated = (0..hosts.len()).map(|i| &hosts[(start + i) % hosts.len()]);
+    let (up, down): (Vec<&String>, Vec<&String>) = rotated.partition(|host| !down.contains(host.as_str()));
+    up.into_iter().chain(down).cloned().collect()
+}
+
+impl HostPool {
+    /// Order in which to try `hosts` for the next call
+    pub fn order(&self, hosts: &[String]) -> Vec<String> {
+        if hosts.len() < 2 {
+            return hosts.to_vec();
+        }
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % hosts.len();
+
+        let now = Instant::now();
+        let mut down_until = self.down_until.lock().unwrap_or_else(|e| e.into_inner());
+        down_until.retain(|_, until| *until > now);
+        let down: HashSet<&str> = down_until.keys().map(String::as_str).collect();
+        rotation(hosts, start, &down)
+    }
+
+    /// Skip `host` for a while after a connection failure
+    pub fn mark_down(&self, host: &str) {
+        let mut down_until = self.down_until.lock().unwrap_or_else(|e| e.into_inner());
+        down_until.insert(host.to_string(), Instant::now() + HOST_COOLDOWN);
+    }
+
+    pub fn mark_up(&self, host: &str) {
+        let mut down_until = self.down_until.lock().unwrap_or_else(|e| e.into_inner());
+        down_until.remove(host);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_skips_down_hosts() {
+        let hosts: Vec<String> = ["a", "b", "c"].iter().map(|h| h.to_string()).collect();
+        assert_eq!(rotation(&hosts, 1, &HashSet::new()), vec!["b", "c", "a"]);
+        assert_eq!(rotation(&hosts, 1, &HashSet::from(["b"])), vec!["c", "a", "b"]);
+
+        let pool = HostPool::default();
+        assert_eq!(pool.order(&hosts)[0], "a");
+        pool.mark_down("c");
+        assert_eq!(pool.order(&hosts), vec!["b", "a", "c"]);
+        pool.mark_up("c");
+        assert_eq!(pool.order(&hosts), vec!["c", "a", "b"]);
+    }
+}
@@ -0,0 +1,95 @@
+//! Local-only AI mode: when enabled, every LLM request must go to a loopback
+//! address. Hostnames are resolved before the request is sent and all of their
+//! addresses have to be loopback, so a `localhost` entry in /etc/hosts pointing
+//! elsewhere doesn't slip through.
+
+use crate::ai::client::{LLMConfig, LLMProvider};
+use reqwest::Url;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Reject `url` if local-only mode is on and it doesn't point at this machine
+pub async fn check_url(url: &str) -> Result<(), String> {
+    if !is_enabled() {
+        return Ok(());
+    }
+    ensure_local(url).await
+}
+
+/// Reject a config whose hosts (including extra Ollama hosts and the fallback)
+/// aren't all local. Checked regardless of the switch, for turning it on.
+pub async fn check_config(config: &LLMConfig) -> Result<(), String> {
+    if config.provider == LLMProvider::Mock {
+        return Ok(());
+    }
+    for host in config.hosts() {
+        ensure_local(&host).await?;
+    }
+    if let Some(fallback) = config.fallback_config() {
+        ensure_local(&fallback.base_url)
+            .await
+            .map_err(|e| format!("{} (fallback provider)", e))?;
+    }
+    Ok(())
+}
+
+async fn ensure_local(url: &str) -> Result<(), String> {
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid LLM URL {}: {}", url, e))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| format!("LLM URL has no host: {}", url))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let rejected = || format!("Local-only mode: {} is not a localhost address", host);
+
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return if ip.is_loopback() { Ok(()) } else { Err(rejected()) };
+    }
+
+    let port = parsed.port_or_known_default().unwrap_or(80);
+    let addresses: Vec<IpAddr> = tokio::net::lookup_host((host.as_str(), port))
+        .await
+        .map_err(|_| format!("Local-only mode: could not resolve {}", host))?
+        .map(|addr| addr.ip())
+        .collect();
+    if addresses.is_empty() || !addresses.iter().all(IpAddr::is_loopback) {
+        return Err(rejected());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_only_loopback_hosts_are_local() {
+        assert!(ensure_local("http://127.0.0.1:11434").await.is_ok());
+        assert!(ensure_local("http://[::1]:1234/v1").await.is_ok());
+        assert!(ensure_local("http://localhost:11434").await.is_ok());
+
+        assert!(ensure_local("http://10.0.0.5:11434").await.is_err());
+        assert!(ensure_local("http://192.168.1.20:11434").await.is_err());
+        assert!(ensure_local("not a url").await.is_err());
+
+        let config = LLMConfig {
+            provider: LLMProvider::Ollama,
+            base_url: "http://localhost:11434".to_string(),
+            extra_hosts: vec!["http://192.168.1.20:11434".to_string()],
+            ..LLMConfig::default()
+        };
+        assert!(check_config(&config).await.is_err());
+        assert!(check_config(&LLMConfig { extra_hosts: vec![], ..config }).await.is_ok());
+    }
+}
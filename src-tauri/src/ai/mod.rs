@@ -1,5 +1,12 @@
 pub mod client;
+pub mod cluster;
+pub mod context;
+#[cfg(debug_assertions)]
+pub mod eval;
+pub mod hosts;
+pub mod local_only;
 pub mod prompts;
+pub mod ratelimit;
 pub mod sanitize;
 pub mod types;
 
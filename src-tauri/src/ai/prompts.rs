@@ -17,16 +17,19 @@ CLASSIFICATION RULES:
 - has_unanswered_question=true (they asked a question you haven't answered)
 - Clear requests: "can you", "please", "let me know", "waiting for", "need your"
 - You're directly addressed or asked for input
+- has_missed_call=true (they called and you missed it)
 
 **FYI** - No action needed:
 - last_message_is_outgoing=true (you already replied)
 - Channel broadcasts or announcements
 - Group discussions where you're not addressed
 - Automated messages or notifications
+- Service events such as "[Joined the chat]" or "[Pinned a message]"
 - General news/updates
 
 IMPORTANT: If last_message_is_outgoing=true, it's almost always FYI (you already responded).
 If is_private_chat=true AND last_message_is_outgoing=false, it's almost always NEEDS_REPLY.
+If USER FEEDBACK says how the user classified this chat before, follow it unless the new messages clearly call for something else.
 
 Respond in JSON:
 {
@@ -35,6 +38,58 @@ Respond in JSON:
   "suggested_reply": "natural reply text or null if fyi"
 }"#;
 
+/// JSON shape expected from the briefing prompt, shown to the model when repairing output
+pub const BRIEFING_RESPONSE_SCHEMA: &str = r#"{
+  "priority": "urgent" | "needs_reply" | "fyi",
+  "summary": "1-2 sentence summary",
+  "suggested_reply": "natural reply text" | null
+}"#;
+
+/// Briefing system prompt with the user's custom categories (name, description)
+/// added; the model tags each chat with one of them or null
+pub fn briefing_system_prompt(categories: &[(String, String)]) -> String {
+    if categories.is_empty() {
+        return BRIEFING_V2_SYSTEM_PROMPT.to_string();
+    }
+    let list: String = categories
+        .iter()
+        .map(|(name, description)| format!("- \"{}\": {}\n", name, description))
+        .collect();
+    format!(
+        "{}\n\nCUSTOM CATEGORIES:\nAlso pick the one category below that fits the chat best, or null if none fits:\n{}\n\
+         Add it to the JSON as \"category\": \"<category name>\" | null",
+        BRIEFING_V2_SYSTEM_PROMPT, list
+    )
+}
+
+/// `BRIEFING_RESPONSE_SCHEMA`, with the category field when custom categories are defined
+pub fn briefing_response_schema(categories: &[(String, String)]) -> String {
+    if categories.is_empty() {
+        return BRIEFING_RESPONSE_SCHEMA.to_string();
+    }
+    let names: Vec<String> = categories.iter().map(|(name, _)| format!("\"{}\"", name)).collect();
+    format!(
+        "{},\n  \"category\": {} | null\n}}",
+        BRIEFING_RESPONSE_SCHEMA.trim_end_matches('}').trim_end(),
+        names.join(" | ")
+    )
+}
+
+/// System prompt for fixing model output that didn't match the expected JSON
+pub const JSON_REPAIR_SYSTEM_PROMPT: &str = r#"You fix malformed JSON produced by another model.
+
+Rewrite the given output so it is a single valid JSON object matching the schema exactly.
+Keep the original meaning; do not add information that isn't in the output.
+Respond with the JSON object only."#;
+
+/// Format the user prompt for a JSON repair pass
+pub fn format_json_repair_user_prompt(schema: &str, invalid_output: &str, error: &str) -> String {
+    format!(
+        "Schema:\n{}\n\nProblem: {}\n\nOutput to fix:\n{}",
+        schema, error, invalid_output
+    )
+}
+
 /// System prompt for detailed summary generation
 pub const DETAILED_SUMMARY_PROMPT: &str = r#"You are an AI assistant that provides detailed summaries of Telegram conversations.
 
@@ -54,6 +109,88 @@ Respond in JSON format:
   "needs_response": boolean
 }"#;
 
+/// System prompt for splitting a busy group conversation into topics
+pub const TOPIC_SUMMARY_SYSTEM_PROMPT: &str = r#"You are an AI assistant that untangles busy Telegram group conversations.
+
+Several conversations are often interleaved in the same group. Group the messages into separate topics (threads) and summarize each one on its own:
+- Each topic should be a distinct subject, not a time slice
+- Give each topic a short title (a few words) and a 1-2 sentence summary
+- List the participants who took part in that topic
+- Estimate how many messages belong to the topic
+- Return at most 6 topics, largest first; ignore one-off greetings and noise
+
+Respond in JSON format:
+{
+  "topics": [
+    {
+      "title": "string",
+      "summary": "string",
+      "participants": ["string"],
+      "message_count": number
+    }
+  ]
+}"#;
+
+/// System prompt for extracting decisions and commitments
+pub const DECISIONS_SYSTEM_PROMPT: &str = r##"You are an AI assistant that keeps track of decisions and commitments made in Telegram chats for someone running projects.
+
+Each message is prefixed with its id and date, like "#123 2024-05-02 [Name]: text". Messages from "You" were sent by the user.
+
+Extract:
+- Decisions: something the group agreed on or someone with authority settled ("we'll launch on the 15th", "going with vendor B")
+- Commitments: a person promising to do something ("I'll send the contract by Friday")
+
+Rules:
+- Only include items clearly stated in the messages; do not infer or invent
+- Ignore proposals that were not agreed, questions and small talk
+- "owner" is who decided or who promised (use the sender name as written, or "You")
+- "due" is the deadline exactly as stated, or null if none was given
+- "message_id" is the id of the message where it was stated
+- Keep "text" short and self-contained (one sentence)
+
+Respond in JSON format:
+{
+  "decisions": [
+    {
+      "kind": "decision" | "commitment",
+      "text": "string",
+      "owner": "string" | null,
+      "due": "string" | null,
+      "message_id": number
+    }
+  ]
+}
+
+If there are none, return {"decisions": []}."##;
+
+/// System prompt for the daily standup built from the user's own messages
+pub const STANDUP_SYSTEM_PROMPT: &str = r#"You are an AI assistant that writes a daily standup for the user from the messages they sent in work chats.
+
+Messages are grouped by chat. Lines from "You" were sent by the user; other lines are context from the chat.
+
+Write two lists:
+- "done": what the user did, shipped, decided, reviewed or replied to
+- "pending": what the user promised, is waiting on, or left unanswered
+
+Rules:
+- Write each item in first person, short and concrete ("Sent the Q3 budget to finance")
+- Merge related messages into one item; skip greetings, jokes and small talk
+- Only use what the messages say; do not invent work
+- Set "chat" to the chat the item came from
+
+Respond in JSON format:
+{
+  "done": [{"text": "string", "chat": "string"}],
+  "pending": [{"text": "string", "chat": "string"}]
+}"#;
+
+/// System prompt for pulling fields out of a bot alert for an automation rule
+pub const BOT_EXTRACTION_SYSTEM_PROMPT: &str = r#"You extract information from automated messages sent by bots (monitoring alerts, CI results, notifications).
+
+The user describes what to extract. Answer with only the extracted information, as short as possible (one line per item). If the message doesn't contain it, answer "none".
+
+Do not explain, add commentary, or invent details the message doesn't contain."#;
+
 /// System prompt for draft generation
 pub const DRAFT_SYSTEM_PROMPT: &str = r#"You are an AI assistant helping a user draft a message in Telegram.
 
@@ -76,6 +213,152 @@ Do NOT:
 
 Output ONLY the draft message text, nothing else."#;
 
+/// System prompt for short tap-to-send reply alternatives
+pub const REPLY_OPTIONS_SYSTEM_PROMPT: &str = r#"You are an AI assistant suggesting quick replies for a user in Telegram.
+
+IMPORTANT: You are writing on behalf of "You" (the user). The conversation shows messages between "You" and other participants.
+
+Suggest exactly 3 short alternative replies to the latest message, each with a different intent:
+- "agree": accept, confirm or say yes
+- "decline": politely say no or push back
+- "ask_for_time": acknowledge and say you'll get back to them
+
+Rules:
+- One short sentence each (under 15 words), ready to send as-is
+- Match the language, tone and formality of the conversation
+- No placeholders like [name] or [time]; do not make up facts
+- If an intent makes no sense for this message, replace it with another natural reply and name its intent in one or two words
+
+Respond in JSON format:
+{
+  "options": [
+    {"intent": "agree", "text": "string"},
+    {"intent": "decline", "text": "string"},
+    {"intent": "ask_for_time", "text": "string"}
+  ]
+}"#;
+
+/// System prompt for explaining what the sender of a message wants
+pub const MESSAGE_INTENT_SYSTEM_PROMPT: &str = r#"You are a conversation coach helping a user understand a Telegram message they received.
+
+You will get some earlier messages for context and then the message to analyze. Messages from "You" were sent by the user.
+
+Break the message down:
+- "tldr": one sentence saying what the sender wants
+- "explicit_asks": things the sender directly asks the user to do or answer
+- "implicit_expectations": things the sender expects without saying it outright (a deadline implied, a decision they are waiting for, reassurance they want)
+- "suggested_next_steps": concrete actions for the user, most important first
+- "urgency": "low", "medium" or "high"
+
+Rules:
+- Keep each item short and actionable
+- Only use what the messages support; use empty lists when there is nothing
+- Focus on the analyzed message; use the earlier messages only as context
+
+Respond in JSON format:
+{
+  "tldr": "string",
+  "explicit_asks": ["string"],
+  "implicit_expectations": ["string"],
+  "suggested_next_steps": ["string"],
+  "urgency": "low" | "medium" | "high"
+}"#;
+
+/// System prompt for the in-depth analysis of a high-stakes conversation
+pub const DEEP_ANALYSIS_SYSTEM_PROMPT: &str = r#"You are an experienced negotiation and communication advisor. The user ("You") is in an important Telegram conversation and wants a thorough analysis before their next move.
+
+Each message is prefixed with its date, like "2024-05-02 [Name]: text".
+
+Provide:
+1. "overview": 3-5 sentences on what the conversation is about, where it stands and the relationship dynamics
+2. "timeline": how each party's position evolved; one entry per meaningful shift (offer, concession, demand, refusal, agreement), in order
+3. "open_questions": unresolved points that still need an answer or decision
+4. "risks": things that could go wrong or that the user may be overlooking
+5. "suggested_strategy": concrete, ordered steps for the user's next moves, including what to say or ask
+
+Rules:
+- Be specific and ground every point in the messages; do not invent facts
+- Name parties as they appear in the messages (the user is "You")
+- Prefer fewer, sharper points over long lists
+
+Respond in JSON format:
+{
+  "overview": "string",
+  "timeline": [{"date": "YYYY-MM-DD", "party": "string", "position": "string"}],
+  "open_questions": ["string"],
+  "risks": ["string"],
+  "suggested_strategy": ["string"]
+}"#;
+
+/// System prompt for naming clusters of chats as scope profiles
+pub const SCOPE_NAMING_SYSTEM_PROMPT: &str = r#"You help a user organize hundreds of Telegram chats into scopes (named groups of chats they review together).
+
+You will get numbered groups of chat titles that were clustered by topic. Give each group a short name (1-2 words) describing what those chats have in common, e.g. "Crypto", "Family", "Hiring", "Travel", "Work".
+
+Rules:
+- Names must be distinct from each other
+- Prefer the topic over the chat type ("Hiring", not "Groups")
+- If a group has no clear common theme, name it "Misc"
+
+Respond in JSON format:
+{
+  "groups": [{"group": number, "name": "string"}]
+}"#;
+
+/// System prompt for suggesting contact tags from an existing vocabulary
+pub const TAG_SUGGESTION_SYSTEM_PROMPT: &str = r#"You help a user organize their Telegram contacts with tags.
+
+You will receive a contact's name, bio, their current tags, the user's tag vocabulary, and recent messages between the user ("You") and the contact.
+
+Rules:
+- Only suggest tags that appear EXACTLY in the vocabulary. Never invent new tags.
+- Do not suggest tags the contact already has.
+- Only suggest a tag when the bio or messages clearly support it.
+- Suggest at most 5 tags. An empty list is a fine answer.
+
+Respond in JSON:
+{
+  "tags": [{"tag": "tag from vocabulary", "reason": "short reason"}]
+}"#;
+
+/// System prompt for the relationship summary in a contact dossier
+pub const DOSSIER_SYSTEM_PROMPT: &str = r#"You help a user get up to speed on one of their Telegram contacts before a meeting or call.
+
+You will receive what the user knows about the contact (notes, tags, bio), the groups they share, and the direct message history between the user ("You") and the contact, oldest first.
+
+Write:
+- "summary": 2-4 sentences on who this person is to the user and how the relationship has developed
+- "topics": the main subjects they talk about (at most 6, a few words each)
+- "openItems": promises, questions or plans that are still open on either side (may be empty)
+
+Only use what the information says; do not invent facts.
+
+Respond in JSON format:
+{
+  "summary": "string",
+  "topics": ["string"],
+  "openItems": ["string"]
+}"#;
+
+/// System prompt for the "where we left off" brief before a meeting
+pub const MEETING_PREP_SYSTEM_PROMPT: &str = r#"You help a user prepare for a meeting or call with specific people.
+
+You will receive the participants, the most recent messages with them grouped by chat (lines from "You" were sent by the user), and action items already tracked for those chats.
+
+Write:
+- "summary": 2-4 sentences on where the conversation left off: what was discussed last and what is happening now
+- "openItems": unresolved questions, promises and pending tasks on either side (may be empty)
+- "talkingPoints": up to 5 short points the user should bring up in the meeting
+
+Keep it short and concrete. Only use what the messages and action items say; do not invent facts.
+
+Respond in JSON format:
+{
+  "summary": "string",
+  "openItems": ["string"],
+  "talkingPoints": ["string"]
+}"#;
+
 /// Format messages for briefing V2 user prompt
 pub fn format_briefing_v2_user_prompt(
     chat_title: &str,
@@ -85,8 +368,18 @@ pub fn format_briefing_v2_user_prompt(
     has_unanswered_question: bool,
     hours_since_last_activity: f64,
     is_private_chat: bool,
+    has_missed_call: bool,
+    corrected_priority: Option<&str>,
     messages: &[(String, String)], // (sender_name, text)
 ) -> String {
+    let feedback = corrected_priority
+        .map(|priority| {
+            format!(
+                "\nUSER FEEDBACK:\nThe user previously said this chat is {}.\n",
+                priority.to_uppercase()
+            )
+        })
+        .unwrap_or_default();
     let messages_text: String = messages
         .iter()
         .map(|(sender, text)| format!("[{}]: {}", sender, text))
@@ -102,7 +395,8 @@ SIGNALS:
 - has_unanswered_question: {}
 - hours_since_last_activity: {:.1}
 - is_private_chat: {}
-
+- has_missed_call: {}
+{}
 MESSAGES:
 {}"#,
         chat_title,
@@ -112,6 +406,8 @@ MESSAGES:
         has_unanswered_question,
         hours_since_last_activity,
         is_private_chat,
+        has_missed_call,
+        feedback,
         messages_text
     )
 }
@@ -141,10 +437,79 @@ Provide your analysis in JSON format."#,
     )
 }
 
-/// Format messages for draft user prompt
+/// Format messages for decision extraction; each line carries the message id and date
+pub fn format_decisions_user_prompt(
+    chat_title: &str,
+    messages: &[(i64, i64, String, String)], // (message_id, date, sender_name, text)
+) -> String {
+    let messages_text: String = messages
+        .iter()
+        .map(|(id, date, sender, text)| {
+            let day = chrono::DateTime::from_timestamp(*date, 0)
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            format!("#{} {} [{}]: {}", id, day, sender, text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"Chat: {}
+
+MESSAGES:
+{}
+
+List the decisions and commitments in JSON format."#,
+        chat_title, messages_text
+    )
+}
+
+/// Format a bot message and the rule's extraction instruction
+pub fn format_bot_extraction_user_prompt(instruction: &str, message: &str) -> String {
+    format!(
+        r#"Extract: {}
+
+MESSAGE:
+{}"#,
+        instruction, message
+    )
+}
+
+/// Format per-chat messages for the standup prompt
+pub fn format_standup_user_prompt(
+    days: i64,
+    chats: &[(String, Vec<(String, String)>)], // (chat_title, [(sender_name, text)])
+) -> String {
+    let chats_text: String = chats
+        .iter()
+        .map(|(title, messages)| {
+            let lines: Vec<String> = messages
+                .iter()
+                .map(|(sender, text)| format!("[{}]: {}", sender, text))
+                .collect();
+            format!("## {}\n{}", title, lines.join("\n"))
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!(
+        r#"Period: last {} day(s)
+
+{}
+
+Write the standup in JSON format."#,
+        days, chats_text
+    )
+}
+
+/// Format messages for draft user prompt.
+/// `goal` replaces the default reply/follow-up hint (e.g. "Wish them a happy birthday").
+/// `about` is what the user knows about the contact (their notes and tags).
 pub fn format_draft_user_prompt(
     chat_title: &str,
     messages: &[(String, String, bool)], // (sender_name, text, is_outgoing)
+    goal: Option<&str>,
+    about: Option<&str>,
 ) -> String {
     let messages_text: String = messages
         .iter()
@@ -153,7 +518,9 @@ pub fn format_draft_user_prompt(
         .join("\n");
 
     // Determine context hint based on last message
-    let context_hint = if let Some((sender, _, is_outgoing)) = messages.last() {
+    let context_hint = if let Some(goal) = goal {
+        format!("Goal for this message: {}", goal)
+    } else if let Some((sender, _, is_outgoing)) = messages.last() {
         if *is_outgoing {
             "The last message was from You. Write a follow-up or continue the conversation.".to_string()
         } else {
@@ -166,17 +533,200 @@ pub fn format_draft_user_prompt(
         "Start the conversation naturally.".to_string()
     };
 
+    let about_text = about
+        .map(|about| {
+            format!(
+                "\nWhat You know about {} (private notes: use them for tone and context, never quote or mention them):\n{}\n",
+                chat_title, about
+            )
+        })
+        .unwrap_or_default();
+
     format!(
         r#"Generate a draft message for this conversation:
 
 Chat with: {}
-
+{}
 Recent messages:
 {}
 
 {}
 
 Write the draft message that "You" will send:"#,
-        chat_title, messages_text, context_hint
+        chat_title,
+        about_text,
+        if messages_text.is_empty() { "(no previous messages)" } else { &messages_text },
+        context_hint
+    )
+}
+
+/// Format the intent analysis prompt: earlier messages, then the one to analyze
+pub fn format_message_intent_user_prompt(
+    chat_title: &str,
+    context: &[(String, String)], // (sender_name, text)
+    sender: &str,
+    text: &str,
+) -> String {
+    let context_text: String = context
+        .iter()
+        .map(|(sender, text)| format!("{}: {}", sender, text))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"Chat: {}
+
+Earlier messages:
+{}
+
+MESSAGE TO ANALYZE (from {}):
+{}
+
+Provide your analysis in JSON format."#,
+        chat_title,
+        if context_text.is_empty() { "(none)" } else { &context_text },
+        sender,
+        text
+    )
+}
+
+/// Format a full conversation with dates for the deep analysis prompt
+pub fn format_deep_analysis_user_prompt(
+    chat_title: &str,
+    chat_type: &str,
+    messages: &[(i64, String, String)], // (date, sender_name, text)
+) -> String {
+    let messages_text: String = messages
+        .iter()
+        .map(|(date, sender, text)| {
+            let day = chrono::DateTime::from_timestamp(*date, 0)
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_default();
+            format!("{} [{}]: {}", day, sender, text)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"Chat: {} ({})
+
+MESSAGES:
+{}
+
+Provide your analysis in JSON format."#,
+        chat_title, chat_type, messages_text
+    )
+}
+
+/// Format clustered chat titles for the scope naming prompt (groups numbered from 1)
+pub fn format_scope_naming_user_prompt(groups: &[Vec<String>]) -> String {
+    let groups_text: String = groups
+        .iter()
+        .enumerate()
+        .map(|(idx, titles)| format!("Group {}:\n- {}", idx + 1, titles.join("\n- ")))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    format!("{}\n\nName each group in JSON format.", groups_text)
+}
+
+/// A message line for prompts that show when it was sent: (date, sender_name, text)
+pub type DatedLine = (String, String, String);
+
+/// Format a contact's details and DM history for the dossier user prompt
+pub fn format_dossier_user_prompt(
+    contact_name: &str,
+    about: Option<&str>,
+    shared_groups: &[String],
+    messages: &[DatedLine],
+) -> String {
+    let messages_text: String = messages
+        .iter()
+        .map(|(date, sender, text)| format!("[{}] {}: {}", date, sender, text))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"Contact: {}
+{}
+Shared groups: {}
+
+Messages:
+{}
+
+Write the relationship summary in JSON format."#,
+        contact_name,
+        about.unwrap_or("Nothing noted about this contact."),
+        if shared_groups.is_empty() { "(none)".to_string() } else { shared_groups.join(", ") },
+        if messages_text.is_empty() { "(no messages)".to_string() } else { messages_text }
+    )
+}
+
+/// Format recent exchanges and tracked action items for the meeting prep user prompt
+pub fn format_meeting_prep_user_prompt(
+    participants: &[String],
+    chats: &[(String, Vec<DatedLine>)], // (chat_title, lines)
+    action_items: &[String],
+) -> String {
+    let chats_text: String = chats
+        .iter()
+        .map(|(title, messages)| {
+            let lines: Vec<String> = messages
+                .iter()
+                .map(|(date, sender, text)| format!("[{}] {}: {}", date, sender, text))
+                .collect();
+            format!("## {}\n{}", title, lines.join("\n"))
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let items_text = if action_items.is_empty() {
+        "(none)".to_string()
+    } else {
+        action_items.iter().map(|i| format!("- {}", i)).collect::<Vec<_>>().join("\n")
+    };
+
+    format!(
+        r#"Participants: {}
+
+{}
+
+Tracked action items:
+{}
+
+Write the meeting brief in JSON format."#,
+        participants.join(", "),
+        chats_text,
+        items_text
+    )
+}
+
+/// Format contact details for the tag suggestion user prompt
+pub fn format_tag_suggestion_user_prompt(
+    contact_name: &str,
+    bio: Option<&str>,
+    current_tags: &[String],
+    vocabulary: &[String],
+    messages: &[(String, String)], // (sender_name, text)
+) -> String {
+    let messages_text: String = messages
+        .iter()
+        .map(|(sender, text)| format!("{}: {}", sender, text))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        r#"Contact: {}
+Bio: {}
+Current tags: {}
+
+Vocabulary: {}
+
+Recent messages:
+{}"#,
+        contact_name,
+        bio.filter(|b| !b.trim().is_empty()).unwrap_or("(none)"),
+        if current_tags.is_empty() { "(none)".to_string() } else { current_tags.join(", ") },
+        vocabulary.join(", "),
+        if messages_text.is_empty() { "(no messages)" } else { &messages_text },
     )
 }
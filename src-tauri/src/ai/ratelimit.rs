@@ -0,0 +1,203 @@
+//! Request pacing for OpenAI based on the `x-ratelimit-*` response headers.
+//! Instead of firing a big briefing batch and retrying on 429s, requests are
+//! spaced so the remaining budget lasts until the window resets.
+
+use reqwest::header::HeaderMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Below this many remaining requests, calls are spread over the reset window
+const LOW_REMAINING_REQUESTS: u64 = 10;
+
+/// Never wait longer than this for a single request
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Rate-limit budget as of the last response
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Budget {
+    remaining_requests: Option<u64>,
+    remaining_tokens: Option<u64>,
+    reset_requests: Option<Duration>,
+    reset_tokens: Option<Duration>,
+    /// From `retry-after` on a 429
+    retry_after: Option<Duration>,
+    observed_at: Instant,
+}
+
+#[derive(Default)]
+struct Pacing {
+    budget: Option<Budget>,
+    /// When the last request that had to wait goes out
+    next_send: Option<Instant>,
+}
+
+impl Pacing {
+    /// Delay before a request of about `tokens` tokens, taking its share out of
+    /// the budget so requests waiting at the same time don't all spend the same
+    /// remaining budget
+    fn reserve(&mut self, now: Instant, tokens: u64) -> Duration {
+        let Some(budget) = self.budget.as_mut() else {
+            return Duration::ZERO;
+        };
+        let mut delay = delay_for(budget, now, tokens);
+        if !delay.is_zero() {
+            // Queue behind the requests already waiting instead of firing with them
+            if let Some(next) = self.next_send {
+                delay = (delay + next.saturating_duration_since(now)).min(MAX_DELAY);
+            }
+            self.next_send = Some(now + delay);
+        }
+        budget.remaining_requests = budget.remaining_requests.map(|r| r.saturating_sub(1));
+        budget.remaining_tokens = budget.remaining_tokens.map(|t| t.saturating_sub(tokens));
+        delay
+    }
+}
+
+#[derive(Default)]
+pub struct RateLimiter {
+    pacing: Mutex<Pacing>,
+}
+
+/// Parse OpenAI's reset durations: "1s", "6m0s", "20ms", "1h2m3.5s"
+fn parse_reset(value: &str) -> Option<Duration> {
+    let mut total = 0.0;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        let number: f64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let (unit, seconds) = [("ms", 0.001), ("h", 3600.0), ("m", 60.0), ("s", 1.0)]
+            .into_iter()
+            .find(|(unit, _)| rest.starts_with(unit))?;
+        total += number * seconds;
+        rest = &rest[unit.len()..];
+    }
+    Some(Duration::from_secs_f64(total))
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn parse_budget(headers: &HeaderMap, now: Instant) -> Option<Budget> {
+    let budget = Budget {
+        remaining_requests: header(headers, "x-ratelimit-remaining-requests").and_then(|v| v.parse().ok()),
+        remaining_tokens: header(headers, "x-ratelimit-remaining-tokens").and_then(|v| v.parse().ok()),
+        reset_requests: header(headers, "x-ratelimit-reset-requests").and_then(parse_reset),
+        reset_tokens: header(headers, "x-ratelimit-reset-tokens").and_then(parse_reset),
+        retry_after: header(headers, "retry-after")
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(Duration::from_secs_f64),
+        observed_at: now,
+    };
+    let empty = budget.remaining_requests.is_none() && budget.remaining_tokens.is_none() && budget.retry_after.is_none();
+    (!empty).then_some(budget)
+}
+
+/// How long to wait before sending a request estimated at `tokens` tokens
+fn delay_for(budget: &Budget, now: Instant, tokens: u64) -> Duration {
+    let elapsed = now.saturating_duration_since(budget.observed_at);
+    let left = |reset: Option<Duration>| reset.unwrap_or_default().saturating_sub(elapsed);
+
+    let delay = if let Some(retry_after) = budget.retry_after {
+        retry_after.saturating_sub(elapsed)
+    } else if budget.remaining_requests == Some(0) {
+        left(budget.reset_requests)
+    } else if budget.remaining_tokens.is_some_and(|remaining| remaining < tokens) {
+        left(budget.reset_tokens)
+    } else {
+        match budget.remaining_requests {
+            // Spread what's left evenly over the rest of the window
+            Some(remaining) if remaining < LOW_REMAINING_REQUESTS => left(budget.reset_requests) / remaining as u32,
+            _ => Duration::ZERO,
+        }
+    };
+    delay.min(MAX_DELAY)
+}
+
+impl RateLimiter {
+    /// Wait until a request of about `tokens` tokens fits in the last known budget
+    pub async fn wait(&self, tokens: u64) {
+        let delay = self.pacing.lock().unwrap_or_else(|e| e.into_inner()).reserve(Instant::now(), tokens);
+        if !delay.is_zero() {
+            log::info!("Pacing OpenAI request by {}ms to stay under rate limits", delay.as_millis());
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Record the budget reported by a response
+    pub fn observe(&self, headers: &HeaderMap) {
+        if let Some(budget) = parse_budget(headers, Instant::now()) {
+            self.pacing.lock().unwrap_or_else(|e| e.into_inner()).budget = Some(budget);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reset() {
+        assert_eq!(parse_reset("1s"), Some(Duration::from_secs(1)));
+        assert_eq!(parse_reset("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_reset("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(parse_reset("1h2m3.5s"), Some(Duration::from_millis(3_723_500)));
+        assert_eq!(parse_reset("soon"), None);
+    }
+
+    #[test]
+    fn test_delay_for_budget() {
+        let now = Instant::now();
+        let budget = Budget {
+            remaining_requests: Some(100),
+            remaining_tokens: Some(10_000),
+            reset_requests: Some(Duration::from_secs(10)),
+            reset_tokens: Some(Duration::from_secs(4)),
+            retry_after: None,
+            observed_at: now,
+        };
+
+        assert_eq!(delay_for(&budget, now, 500), Duration::ZERO);
+        assert_eq!(delay_for(&budget, now, 20_000), Duration::from_secs(4));
+        assert_eq!(delay_for(&budget, now + Duration::from_secs(1), 20_000), Duration::from_secs(3));
+
+        let low = Budget { remaining_requests: Some(5), ..budget };
+        assert_eq!(delay_for(&low, now, 500), Duration::from_secs(2));
+
+        let exhausted = Budget { remaining_requests: Some(0), ..budget };
+        assert_eq!(delay_for(&exhausted, now, 500), Duration::from_secs(10));
+
+        let throttled = Budget { retry_after: Some(Duration::from_secs(120)), ..budget };
+        assert_eq!(delay_for(&throttled, now, 500), MAX_DELAY);
+    }
+
+    #[test]
+    fn test_concurrent_requests_reserve_budget() {
+        let now = Instant::now();
+        let budget = Budget {
+            remaining_requests: Some(100),
+            remaining_tokens: Some(1_000),
+            reset_requests: Some(Duration::from_secs(10)),
+            reset_tokens: Some(Duration::from_secs(4)),
+            retry_after: None,
+            observed_at: now,
+        };
+        let mut pacing = Pacing { budget: Some(budget), next_send: None };
+
+        // The first request spends the token budget, so the second waits for its reset
+        assert_eq!(pacing.reserve(now, 800), Duration::ZERO);
+        assert_eq!(pacing.reserve(now, 800), Duration::from_secs(4));
+        assert_eq!(pacing.budget.unwrap().remaining_requests, Some(98));
+
+        // Requests spread over a low budget go out one after another
+        let low = Budget { remaining_requests: Some(2), remaining_tokens: None, ..budget };
+        let mut pacing = Pacing { budget: Some(low), next_send: None };
+        assert_eq!(pacing.reserve(now, 10), Duration::from_secs(5));
+        assert_eq!(pacing.reserve(now, 10), Duration::from_secs(15));
+        assert_eq!(Pacing::default().reserve(now, 10), Duration::ZERO);
+    }
+}
@@ -8,6 +8,9 @@ pub struct ChatMessage {
     pub text: String,
     pub date: i64,
     pub is_outgoing: bool,
+    /// The message's content type ("text", "photo", "storyMention", ...), when known
+    #[serde(default)]
+    pub kind: Option<String>,
 }
 
 /// Chat context for briefing generation
@@ -27,6 +30,9 @@ pub struct ChatContext {
     pub hours_since_last_activity: f64,
     #[serde(default)]
     pub is_private_chat: bool,
+    /// An incoming call was missed and nothing was sent since
+    #[serde(default)]
+    pub has_missed_call: bool,
 }
 
 /// Chat context for summary generation
@@ -121,6 +127,24 @@ pub struct ResponseItem {
     pub priority: String,
     pub summary: String,
     pub suggested_reply: Option<String>,
+    /// Set when a message in the chat looked like a prompt-injection attempt
+    #[serde(default)]
+    pub injection_warning: Option<String>,
+    /// The user's notes on the chat
+    #[serde(default)]
+    pub chat_note: Option<String>,
+    /// Unread messages in the backlog
+    #[serde(default)]
+    pub message_count: i32,
+    /// Estimated seconds to read the unread backlog
+    #[serde(default)]
+    pub reading_time_secs: i32,
+    /// Custom category the chat was tagged with (see `db::briefing_categories`)
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Newest message the briefing saw; replying marks the chat read up to it
+    #[serde(default)]
+    pub last_message_id: Option<i64>,
 }
 
 /// FYI item in briefing
@@ -135,6 +159,25 @@ pub struct FYIItem {
     pub last_message_date: Option<String>,
     pub priority: String,
     pub summary: String,
+    #[serde(default)]
+    pub injection_warning: Option<String>,
+    #[serde(default)]
+    pub chat_note: Option<String>,
+    #[serde(default)]
+    pub message_count: i32,
+    #[serde(default)]
+    pub reading_time_secs: i32,
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+/// A custom category's section of a briefing: the chats tagged with it, which
+/// also appear in `needs_response` or `fyi_summaries`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefingSection {
+    pub name: String,
+    pub description: String,
+    pub chat_ids: Vec<i64>,
 }
 
 /// Statistics for briefing
@@ -154,6 +197,51 @@ pub struct BriefingV2Response {
     pub generated_at: String,
     pub cached: bool,
     pub cache_age: Option<String>,
+    /// Id in briefing history (None for empty briefings, which are not saved)
+    #[serde(default)]
+    pub briefing_id: Option<String>,
+    /// Chats the model could not classify, even after a repair attempt
+    #[serde(default)]
+    pub failed_chats: Vec<FailedChat>,
+    /// One section per custom category, in the user's order
+    #[serde(default)]
+    pub sections: Vec<BriefingSection>,
+}
+
+/// One chat's outcome in a streaming briefing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum BriefingItem {
+    NeedsResponse(ResponseItem),
+    Fyi(FYIItem),
+    Failed(FailedChat),
+}
+
+/// Payload of `ai://briefing-item`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefingItemEvent {
+    pub request_id: String,
+    /// Index of the chat in the request, for stable ordering
+    pub position: usize,
+    pub completed: usize,
+    pub total: usize,
+    pub item: BriefingItem,
+}
+
+/// Payload of `ai://briefing-complete`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BriefingCompleteEvent {
+    pub request_id: String,
+    pub briefing: BriefingV2Response,
+}
+
+/// Chat left out of a briefing because analysis failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedChat {
+    pub chat_id: i64,
+    pub chat_name: String,
+    pub unread_count: i32,
+    pub reason: String,
 }
 
 // ============================================================================
@@ -173,6 +261,20 @@ pub struct ChatSummaryResult {
     pub needs_response: bool,
     pub message_count: i32,
     pub last_message_date: i64,
+    /// Separate threads of conversation in busy groups (empty for DMs and quiet chats)
+    #[serde(default)]
+    pub topics: Vec<TopicSummary>,
+}
+
+/// One thread of conversation within a group summary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicSummary {
+    pub title: String,
+    pub summary: String,
+    #[serde(default)]
+    pub participants: Vec<String>,
+    #[serde(default)]
+    pub message_count: i32,
 }
 
 /// Batch summary response
@@ -188,11 +290,32 @@ pub struct BatchSummaryResponse {
 // Draft Response Types
 // ============================================================================
 
+/// What `generate_draft` produces
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DraftMode {
+    /// One full draft
+    #[default]
+    Single,
+    /// A few short alternative replies with different intents
+    Options,
+}
+
+/// Short reply alternative ("agree", "decline", "ask_for_time")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplyOption {
+    pub intent: String,
+    pub text: String,
+}
+
 /// Draft generation response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DraftResponse {
     pub draft: String,
     pub chat_id: i64,
+    /// Alternatives in `DraftMode::Options`; `draft` is then the first of them
+    #[serde(default)]
+    pub options: Vec<ReplyOption>,
 }
 
 // ============================================================================
@@ -215,6 +338,18 @@ pub struct OpenAIRequest {
     pub max_tokens: i32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response_format: Option<ResponseFormat>,
+    /// Ollama only: how long the model stays loaded after this request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<String>,
+    /// Ollama only: model options, sized for the prompt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<OllamaOptions>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OllamaOptions {
+    /// Context window in tokens; Ollama's default silently truncates long prompts
+    pub num_ctx: usize,
 }
 
 /// Response format for JSON mode
@@ -228,6 +363,15 @@ pub struct ResponseFormat {
 #[derive(Debug, Clone, Deserialize)]
 pub struct OpenAIResponse {
     pub choices: Vec<OpenAIChoice>,
+    #[serde(default)]
+    pub usage: Option<OpenAIUsage>,
+}
+
+/// Token counts reported with a completion
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct OpenAIUsage {
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
 }
 
 /// Choice in OpenAI response
@@ -242,6 +386,25 @@ pub struct OpenAIResponseMessage {
     pub content: String,
 }
 
+/// OpenAI-compatible embeddings request (also served by Ollama and LM Studio)
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingRequest {
+    pub model: String,
+    pub input: Vec<String>,
+}
+
+/// Embeddings response; `index` refers to the position in the request input
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmbeddingResponse {
+    pub data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmbeddingData {
+    pub embedding: Vec<f32>,
+    pub index: usize,
+}
+
 // ============================================================================
 // Internal AI Response Types (for JSON parsing)
 // ============================================================================
@@ -253,6 +416,23 @@ pub struct AIBriefingResponse {
     pub summary: String,
     #[serde(default)]
     pub suggested_reply: Option<String>,
+    /// Name of a custom category, when any are defined
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+impl AIBriefingResponse {
+    /// Reject well-formed JSON that still doesn't follow the schema
+    pub fn validate(&self) -> Result<(), String> {
+        match self.priority.to_lowercase().as_str() {
+            "urgent" | "needs_reply" | "fyi" => {}
+            other => return Err(format!("invalid priority \"{}\"", other)),
+        }
+        if self.summary.trim().is_empty() {
+            return Err("empty summary".to_string());
+        }
+        Ok(())
+    }
 }
 
 /// Internal summary response from AI
@@ -269,6 +449,171 @@ pub struct AISummaryResponse {
     pub needs_response: bool,
 }
 
+/// Internal topic clustering response from AI
+#[derive(Debug, Clone, Deserialize)]
+pub struct AITopicsResponse {
+    #[serde(default)]
+    pub topics: Vec<TopicSummary>,
+}
+
+/// Decision or commitment as returned by the AI
+#[derive(Debug, Clone, Deserialize)]
+pub struct AIDecisionItem {
+    /// "decision" or "commitment"
+    pub kind: String,
+    pub text: String,
+    /// Who made the commitment (or who decided)
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Deadline as stated in the chat ("Friday", "end of March")
+    #[serde(default)]
+    pub due: Option<String>,
+    /// Id of the message it was taken from
+    #[serde(default)]
+    pub message_id: Option<i64>,
+}
+
+/// Internal decision extraction response from AI
+#[derive(Debug, Clone, Deserialize)]
+pub struct AIDecisionsResponse {
+    #[serde(default)]
+    pub decisions: Vec<AIDecisionItem>,
+}
+
+/// Standup line as returned by the AI, with the chat it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandupItem {
+    pub text: String,
+    #[serde(default)]
+    pub chat: Option<String>,
+}
+
+/// Internal standup response from AI
+#[derive(Debug, Clone, Deserialize)]
+pub struct AIStandupResponse {
+    #[serde(default)]
+    pub done: Vec<StandupItem>,
+    #[serde(default)]
+    pub pending: Vec<StandupItem>,
+}
+
+/// Relationship summary written by the AI for a contact dossier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipSummary {
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub open_items: Vec<String>,
+}
+
+/// Internal meeting prep response from AI
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AIMeetingBriefResponse {
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub open_items: Vec<String>,
+    #[serde(default)]
+    pub talking_points: Vec<String>,
+}
+
+/// Internal reply options response from AI
+#[derive(Debug, Clone, Deserialize)]
+pub struct AIReplyOptionsResponse {
+    #[serde(default)]
+    pub options: Vec<ReplyOption>,
+}
+
+/// What the sender of a message wants from the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageIntentAnalysis {
+    pub chat_id: i64,
+    pub message_id: i64,
+    pub sender_name: String,
+    /// One-sentence TL;DR of the message
+    pub tldr: String,
+    pub explicit_asks: Vec<String>,
+    pub implicit_expectations: Vec<String>,
+    pub suggested_next_steps: Vec<String>,
+    /// "low", "medium" or "high"
+    pub urgency: String,
+}
+
+/// Internal intent analysis response from AI
+#[derive(Debug, Clone, Deserialize)]
+pub struct AIIntentResponse {
+    pub tldr: String,
+    #[serde(default)]
+    pub explicit_asks: Vec<String>,
+    #[serde(default)]
+    pub implicit_expectations: Vec<String>,
+    #[serde(default)]
+    pub suggested_next_steps: Vec<String>,
+    #[serde(default = "default_urgency")]
+    pub urgency: String,
+}
+
+fn default_urgency() -> String {
+    "medium".to_string()
+}
+
+/// A party's stated position at a point in a conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionChange {
+    /// Date as written in the prompt (YYYY-MM-DD)
+    #[serde(default)]
+    pub date: Option<String>,
+    pub party: String,
+    pub position: String,
+}
+
+/// Internal deep analysis response from AI
+#[derive(Debug, Clone, Deserialize)]
+pub struct AIDeepAnalysisResponse {
+    pub overview: String,
+    #[serde(default)]
+    pub timeline: Vec<PositionChange>,
+    #[serde(default)]
+    pub open_questions: Vec<String>,
+    #[serde(default)]
+    pub risks: Vec<String>,
+    #[serde(default)]
+    pub suggested_strategy: Vec<String>,
+}
+
+/// Name the AI gave to one cluster of chats
+#[derive(Debug, Clone, Deserialize)]
+pub struct AIScopeName {
+    pub group: usize,
+    pub name: String,
+}
+
+/// Internal scope naming response from AI
+#[derive(Debug, Clone, Deserialize)]
+pub struct AIScopeNamesResponse {
+    #[serde(default)]
+    pub groups: Vec<AIScopeName>,
+}
+
+/// Tag proposed for a contact, with the model's reason
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagSuggestion {
+    pub tag: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Internal tag suggestion response from AI
+#[derive(Debug, Clone, Deserialize)]
+pub struct AITagSuggestionResponse {
+    #[serde(default)]
+    pub tags: Vec<TagSuggestion>,
+}
+
 fn default_sentiment() -> String {
     "neutral".to_string()
 }
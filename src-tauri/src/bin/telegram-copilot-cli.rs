@@ -0,0 +1,3 @@
+fn main() {
+    telegram_copilot_lib::cli::run()
+}
@@ -55,8 +55,15 @@ impl<V: Clone> TTLCache<V> {
         entries.remove(key);
     }
 
+    /// Invalidate the entries whose value matches `predicate`, returning how many were dropped
+    pub async fn invalidate_where<F: Fn(&V) -> bool>(&self, predicate: F) -> usize {
+        let mut entries = self.entries.write().await;
+        let before = entries.len();
+        entries.retain(|_, entry| !predicate(&entry.data));
+        before - entries.len()
+    }
+
     /// Invalidate all entries in the cache
-    #[allow(dead_code)]
     pub async fn invalidate_all(&self) {
         let mut entries = self.entries.write().await;
         entries.clear();
@@ -86,11 +93,28 @@ pub fn generate_chat_ids_key(chat_ids: &[i64]) -> String {
 pub struct BriefingCache(pub TTLCache<crate::ai::types::BriefingV2Response>);
 pub struct SummaryCache(pub TTLCache<crate::ai::types::BatchSummaryResponse>);
 pub struct ContactsCache(pub TTLCache<Vec<crate::commands::contacts::ContactWithMetadata>>);
+/// Sorted dialog listings behind `get_chats_page`, so later pages don't reload every dialog
+pub struct ChatListCache(pub TTLCache<Vec<crate::telegram::client::Chat>>);
 
 impl BriefingCache {
     pub fn new() -> Self {
         Self(TTLCache::new())
     }
+
+    /// Drop the cached briefings that cover any of `chat_ids`
+    pub async fn invalidate_chats(&self, chat_ids: &[i64]) -> usize {
+        self.0
+            .invalidate_where(|briefing| {
+                let mut covered = briefing
+                    .needs_response
+                    .iter()
+                    .map(|item| item.chat_id)
+                    .chain(briefing.fyi_summaries.iter().map(|item| item.chat_id))
+                    .chain(briefing.failed_chats.iter().map(|item| item.chat_id));
+                covered.any(|id| chat_ids.contains(&id))
+            })
+            .await
+    }
 }
 
 impl Default for BriefingCache {
@@ -103,6 +127,13 @@ impl SummaryCache {
     pub fn new() -> Self {
         Self(TTLCache::new())
     }
+
+    /// Drop the cached summaries that cover any of `chat_ids`
+    pub async fn invalidate_chats(&self, chat_ids: &[i64]) -> usize {
+        self.0
+            .invalidate_where(|batch| batch.summaries.iter().any(|s| chat_ids.contains(&s.chat_id)))
+            .await
+    }
 }
 
 impl Default for SummaryCache {
@@ -123,6 +154,18 @@ impl Default for ContactsCache {
     }
 }
 
+impl ChatListCache {
+    pub fn new() -> Self {
+        Self(TTLCache::new())
+    }
+}
+
+impl Default for ChatListCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Format age in seconds to human-readable string
 pub fn format_cache_age(age_secs: u64) -> String {
     if age_secs < 60 {
@@ -135,3 +178,50 @@ pub fn format_cache_age(age_secs: u64) -> String {
         format!("{}d ago", age_secs / 86400)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::types::{BriefingStats, BriefingV2Response, FailedChat};
+
+    #[tokio::test]
+    async fn test_invalidate_where() {
+        let cache: TTLCache<Vec<i64>> = TTLCache::new();
+        cache.set("a", vec![1, 2]).await;
+        cache.set("b", vec![3]).await;
+
+        assert_eq!(cache.invalidate_where(|ids| ids.contains(&2)).await, 1);
+        assert!(cache.get("a", 60).await.is_none());
+        assert_eq!(cache.get("b", 60).await.map(|(ids, _)| ids), Some(vec![3]));
+    }
+
+    fn briefing_of(failed_chat_ids: &[i64]) -> BriefingV2Response {
+        BriefingV2Response {
+            needs_response: vec![],
+            fyi_summaries: vec![],
+            stats: BriefingStats { needs_response_count: 0, fyi_count: 0, total_unread: 0 },
+            generated_at: String::new(),
+            cached: false,
+            cache_age: None,
+            briefing_id: None,
+            failed_chats: failed_chat_ids
+                .iter()
+                .map(|&chat_id| FailedChat { chat_id, chat_name: String::new(), unread_count: 1, reason: String::new() })
+                .collect(),
+            sections: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_briefing_invalidate_chats() {
+        let cache = BriefingCache::new();
+        cache.0.set("a", briefing_of(&[1, 2])).await;
+        cache.0.set("b", briefing_of(&[3])).await;
+
+        // Only briefings covering one of the chats are dropped
+        assert_eq!(cache.invalidate_chats(&[2, 9]).await, 1);
+        assert!(cache.0.get("a", 60).await.is_none());
+        assert!(cache.0.get("b", 60).await.is_some());
+        assert_eq!(cache.invalidate_chats(&[]).await, 0);
+    }
+}
@@ -0,0 +1,356 @@
+use crate::ai::context::{gather_briefing_inputs, merge_large_group_fyis};
+use crate::ai::types::BriefingV2Response;
+use crate::ai::LLMClient;
+use crate::commands::ai::run_briefing;
+use crate::commands::delivery::deliver_if_enabled;
+use crate::commands::focus::focus_active;
+use crate::commands::outreach::OutreachQueue;
+use crate::commands::scopes::resolve_scope_chats;
+use crate::db;
+use crate::mcp::McpServer;
+use crate::telegram::client::{ChatFilters, Message};
+use crate::telegram::TelegramClient;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Must match the `identifier` in tauri.conf.json so the CLI shares the app's data directory
+const APP_IDENTIFIER: &str = "com.telegram-copilot.desktop";
+
+const USAGE: &str = r#"Usage: telegram-copilot-cli <command> [options]
+
+Commands:
+  briefing [--scope NAME] [--limit N] [--json] [--no-deliver] [--force]
+      Generate the AI briefing for unread chats (and post it to Saved Messages or
+      a channel if briefing delivery is enabled in the app). Skipped while focus
+      mode is on unless --force is given
+  outreach status [QUEUE_ID] [--json]
+      Show one outreach queue, or the most recent queues
+  search QUERY [--chat CHAT_ID] [--limit N] [--json]
+      Search messages across all chats or within one chat
+  mcp
+      Run a Model Context Protocol server on stdio (for Claude Desktop and other MCP clients)
+
+Environment:
+  TELEGRAM_COPILOT_DATA_DIR   Override the app data directory (database + session)
+
+The CLI uses the desktop app's database and Telegram session; log in through the app first."#;
+
+/// Parsed command-line arguments
+struct CliArgs {
+    positional: Vec<String>,
+    scope: Option<String>,
+    chat_id: Option<i64>,
+    limit: Option<i32>,
+    json: bool,
+    no_deliver: bool,
+    force: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<CliArgs, String> {
+    let mut parsed = CliArgs {
+        positional: vec![],
+        scope: None,
+        chat_id: None,
+        limit: None,
+        json: false,
+        no_deliver: false,
+        force: false,
+    };
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => parsed.json = true,
+            "--no-deliver" => parsed.no_deliver = true,
+            "--force" => parsed.force = true,
+            "--help" | "-h" => parsed.positional.insert(0, "help".to_string()),
+            "--scope" => {
+                parsed.scope = Some(iter.next().ok_or("--scope requires a value")?.clone());
+            }
+            "--chat" => {
+                let value = iter.next().ok_or("--chat requires a value")?;
+                parsed.chat_id = Some(value.parse().map_err(|_| format!("Invalid chat id: {}", value))?);
+            }
+            "--limit" => {
+                let value = iter.next().ok_or("--limit requires a value")?;
+                parsed.limit = Some(value.parse().map_err(|_| format!("Invalid limit: {}", value))?);
+            }
+            other if other.starts_with("--") => return Err(format!("Unknown option: {}", other)),
+            other => parsed.positional.push(other.to_string()),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Resolve the app data directory shared with the desktop app
+fn app_data_dir() -> Result<PathBuf, String> {
+    if let Ok(dir) = std::env::var("TELEGRAM_COPILOT_DATA_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Could not determine the app data directory".to_string())
+}
+
+/// Open the shared database and return the data directory
+fn init_storage() -> Result<PathBuf, String> {
+    let app_dir = app_data_dir()?;
+    if !app_dir.exists() {
+        return Err(format!(
+            "App data directory {:?} not found. Run the desktop app and log in first.",
+            app_dir
+        ));
+    }
+    db::init_db(app_dir.clone())?;
+    if let Err(e) = db::settings::apply_saved_sanitization() {
+        log::warn!("Failed to apply sanitization config: {}", e);
+    }
+    if let Err(e) = db::settings::apply_saved_local_only_ai() {
+        log::warn!("Failed to apply local-only AI setting: {}", e);
+    }
+    Ok(app_dir)
+}
+
+/// Connect to Telegram with the app's saved session
+async fn connect_telegram(app_dir: &std::path::Path) -> Result<TelegramClient, String> {
+    let client = TelegramClient::new(crate::telegram_config_from_env());
+    client.set_session_file(app_dir.join("telegram.session"));
+
+    if !client.connect().await? {
+        return Err("Not logged in. Sign in through the desktop app first.".to_string());
+    }
+    Ok(client)
+}
+
+/// Build the LLM client from the saved config, falling back to env defaults
+async fn llm_client() -> Arc<LLMClient> {
+    let client = Arc::new(LLMClient::new(crate::default_llm_config_from_env()));
+    match db::settings::load_llm_config() {
+        Ok(Some(config)) => client.update_config(config).await,
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to load saved LLM config: {}", e),
+    }
+    client
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize output: {}", e))?;
+    println!("{}", json);
+    Ok(())
+}
+
+async fn cmd_briefing(args: &CliArgs) -> Result<(), String> {
+    let app_dir = init_storage()?;
+    if focus_active() && !args.force {
+        println!("Focus mode is on; skipping the briefing (pass --force to run it anyway)");
+        return Ok(());
+    }
+
+    let llm = llm_client().await;
+    if !llm.is_configured().await {
+        return Err("LLM not configured: set OPENAI_API_KEY or configure a provider in the app".to_string());
+    }
+
+    let telegram = connect_telegram(&app_dir).await?;
+    let limit = args.limit.unwrap_or(100);
+
+    let chats = match &args.scope {
+        Some(name) => {
+            let scope = db::scopes::load_scope(name)?
+                .ok_or_else(|| format!("Scope '{}' not found", name))?;
+            resolve_scope_chats(&telegram, &scope.config, limit).await?
+        }
+        None => {
+            let filters = ChatFilters {
+                include_private_chats: true,
+                include_non_contacts: true,
+                include_groups: true,
+                include_channels: true,
+                include_unread_only: true,
+                ..Default::default()
+            };
+            telegram.get_chats(limit, Some(filters)).await?
+        }
+    };
+
+    let (contexts, large_group_fyis) = gather_briefing_inputs(&telegram, &chats).await?;
+    let mut response = merge_large_group_fyis(run_briefing(llm, &contexts).await, large_group_fyis);
+    if !contexts.is_empty() || response.stats.fyi_count > 0 {
+        match db::briefings::save_briefing(&response) {
+            Ok(id) => response.briefing_id = Some(id),
+            Err(e) => log::warn!("Failed to save briefing history: {}", e),
+        }
+
+        if !args.no_deliver {
+            if let Err(e) = deliver_if_enabled(&telegram, &response).await {
+                eprintln!("Warning: failed to deliver briefing: {}", e);
+            }
+        }
+    }
+
+    if args.json {
+        print_json(&response)
+    } else {
+        print_briefing(&response);
+        Ok(())
+    }
+}
+
+fn print_briefing(briefing: &BriefingV2Response) {
+    println!(
+        "Briefing ({} need a response, {} FYI, {} unread)",
+        briefing.stats.needs_response_count, briefing.stats.fyi_count, briefing.stats.total_unread
+    );
+
+    if !briefing.needs_response.is_empty() {
+        println!("\nNEEDS RESPONSE");
+        for item in &briefing.needs_response {
+            let marker = if item.priority == "urgent" { "!!" } else { "- " };
+            println!("{} {} [{}] ({} unread)", marker, item.chat_name, item.chat_type, item.unread_count);
+            println!("   {}", item.summary);
+            if let Some(reply) = &item.suggested_reply {
+                println!("   Suggested reply: {}", reply);
+            }
+        }
+    }
+
+    if !briefing.fyi_summaries.is_empty() {
+        println!("\nFYI");
+        for item in &briefing.fyi_summaries {
+            println!("-  {} [{}]: {}", item.chat_name, item.chat_type, item.summary);
+        }
+    }
+
+    match crate::commands::reminders::due_reminders() {
+        Ok(reminders) if !reminders.is_empty() => {
+            println!("\nREMINDERS");
+            for reminder in &reminders {
+                println!("*  {} [{}]: {}", reminder.chat_title, reminder.chat_type, reminder.note.as_deref().unwrap_or("follow up"));
+            }
+        }
+        Ok(_) => {}
+        Err(e) => log::warn!("Failed to load reminders: {}", e),
+    }
+
+    if !briefing.failed_chats.is_empty() {
+        println!("\nCOULD NOT ANALYZE");
+        for chat in &briefing.failed_chats {
+            println!("?  {} ({} unread): {}", chat.chat_name, chat.unread_count, chat.reason);
+        }
+    }
+}
+
+async fn cmd_outreach(args: &CliArgs) -> Result<(), String> {
+    match args.positional.get(1).map(String::as_str) {
+        Some("status") => {}
+        Some(other) => return Err(format!("Unknown outreach command: {}", other)),
+        None => return Err("Usage: telegram-copilot-cli outreach status [QUEUE_ID]".to_string()),
+    }
+
+    init_storage()?;
+
+    let queues: Vec<OutreachQueue> = match args.positional.get(2) {
+        Some(queue_id) => {
+            let queue = db::with_db(|conn| db::outreach::load_queue(conn, queue_id))?
+                .ok_or_else(|| format!("Queue not found: {}", queue_id))?;
+            vec![queue]
+        }
+        None => db::with_db(|conn| {
+            db::outreach::load_recent_queues(conn, args.limit.unwrap_or(10) as i64)
+        })?,
+    };
+
+    if args.json {
+        return print_json(&queues);
+    }
+
+    if queues.is_empty() {
+        println!("No outreach queues found");
+    }
+    for queue in &queues {
+        println!(
+            "{}  {}  sent {}/{}  failed {}",
+            queue.id,
+            queue.status,
+            queue.sent_count,
+            queue.recipients.len(),
+            queue.failed_count
+        );
+    }
+    Ok(())
+}
+
+async fn cmd_search(args: &CliArgs) -> Result<(), String> {
+    let query = args.positional[1..].join(" ");
+    if query.trim().is_empty() {
+        return Err("Usage: telegram-copilot-cli search QUERY [--chat CHAT_ID]".to_string());
+    }
+
+    let app_dir = init_storage()?;
+    let telegram = connect_telegram(&app_dir).await?;
+    let messages = telegram
+        .search_messages(&query, args.chat_id, args.limit.unwrap_or(20))
+        .await?;
+
+    if args.json {
+        return print_json(&messages);
+    }
+
+    if messages.is_empty() {
+        println!("No messages found");
+    }
+    for message in &messages {
+        print_message(message);
+    }
+    Ok(())
+}
+
+fn print_message(message: &Message) {
+    let date = chrono::DateTime::from_timestamp(message.date, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+    println!("[{}] chat {} | {}: {}", date, message.chat_id, message.sender_name, message.content.describe());
+}
+
+async fn cmd_mcp() -> Result<(), String> {
+    let app_dir = init_storage()?;
+    let telegram = connect_telegram(&app_dir).await?;
+    McpServer::new(telegram).serve_stdio().await
+}
+
+/// Entry point for the `telegram-copilot-cli` binary
+pub fn run() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+    crate::load_dotenv();
+
+    let raw_args: Vec<String> = std::env::args().skip(1).collect();
+    let args = match parse_args(&raw_args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+    let result = runtime.block_on(async {
+        match args.positional.first().map(String::as_str) {
+            Some("briefing") => cmd_briefing(&args).await,
+            Some("outreach") => cmd_outreach(&args).await,
+            Some("search") => cmd_search(&args).await,
+            Some("mcp") => cmd_mcp().await,
+            Some("help") | None => {
+                println!("{}", USAGE);
+                Ok(())
+            }
+            Some(other) => Err(format!("Unknown command: {}\n\n{}", other, USAGE)),
+        }
+    });
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,197 @@
+//! The local message archive: syncing chats into it on demand, with
+//! `archive://sync-progress` events so the frontend can show how far it got, and
+//! a background backfill that gradually archives a scope's older history, and
+//! statistics and compaction to keep its size in check. Deletions and edits
+//! reported by the update loop are recorded in it as well.
+
+use super::scopes::resolve_scope_chats;
+use crate::db::archive_backfill::{self as db_backfill, BackfillCheckpoint};
+use crate::db::message_sync::{self, SyncReport};
+use crate::db::messages::{self as db_messages, ArchiveStats, DeletedMessage};
+use crate::db::scopes as db_scopes;
+use crate::db::settings::{self, ArchiveBackfillConfig};
+use crate::telegram::client::AuthState;
+use crate::telegram::TelegramClient;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+/// Between checks while the backfill is off, signed out or finished
+const BACKFILL_IDLE_SECS: u64 = 5 * 60;
+
+/// Pause after Telegram asks to slow down
+const BACKFILL_FLOOD_PAUSE_SECS: u64 = 15 * 60;
+
+/// Most chats of a scope the backfill covers
+const BACKFILL_CHAT_LIMIT: i32 = 500;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillStatus {
+    pub config: ArchiveBackfillConfig,
+    pub chats_started: usize,
+    pub chats_complete: usize,
+    pub pages: i64,
+    pub checkpoints: Vec<BackfillCheckpoint>,
+}
+
+/// Archive a chat's messages newer than the newest archived one, then
+/// `backfill_pages` pages of older history
+#[tauri::command]
+pub async fn sync_chat_history(
+    app: AppHandle,
+    client: State<'_, Arc<TelegramClient>>,
+    chat_id: i64,
+    backfill_pages: Option<u32>,
+) -> Result<SyncReport, String> {
+    let report = message_sync::sync_chat(client.inner().as_ref(), chat_id, backfill_pages.unwrap_or(0), |progress| {
+        let _ = app.emit("archive://sync-progress", progress);
+    })
+    .await?;
+    log::info!(
+        "Synced chat {}: {} new, {} older messages archived",
+        chat_id,
+        report.new_messages,
+        report.backfilled_messages
+    );
+    Ok(report)
+}
+
+/// Start of the archive retention window, if there is one
+fn retention_since() -> Option<i64> {
+    let days = settings::load_retention().unwrap_or_default().archive_days?;
+    Some(chrono::Utc::now().timestamp() - days as i64 * 86400)
+}
+
+/// The backfill settings, if it should keep going with this scope
+async fn running_config(client: &TelegramClient, scope_name: &str) -> Option<ArchiveBackfillConfig> {
+    let config = match settings::load_archive_backfill() {
+        Ok(config) => config,
+        Err(e) => {
+            log::warn!("Failed to load archive backfill config: {}", e);
+            return None;
+        }
+    };
+    let ready = matches!(client.get_auth_state().await, AuthState::Ready);
+    (ready && config.enabled && config.scope_name.as_deref() == Some(scope_name)).then_some(config)
+}
+
+/// Archive the configured scope's chats a page at a time, paced to the
+/// configured requests per minute, until each reaches its first message or the
+/// retention window. Checkpoints make it pick up where it stopped after a restart.
+async fn backfill_pass(app: &AppHandle, client: &TelegramClient) -> Result<(), String> {
+    let config = settings::load_archive_backfill()?;
+    let Some(scope_name) = config.scope_name.filter(|_| config.enabled) else {
+        return Ok(());
+    };
+    if !matches!(client.get_auth_state().await, AuthState::Ready) {
+        return Ok(());
+    }
+    let Some(scope) = db_scopes::load_scope(&scope_name)? else {
+        log::warn!("Archive backfill scope '{}' no longer exists", scope_name);
+        return Ok(());
+    };
+
+    let checkpoints: HashMap<i64, BackfillCheckpoint> =
+        db_backfill::load_checkpoints()?.into_iter().map(|c| (c.chat_id, c)).collect();
+    let pending: Vec<i64> = resolve_scope_chats(client, &scope.config, BACKFILL_CHAT_LIMIT)
+        .await?
+        .into_iter()
+        .map(|chat| chat.id)
+        .filter(|id| checkpoints.get(id).map_or(true, |c| !c.complete))
+        .collect();
+
+    for chat_id in pending {
+        loop {
+            let Some(config) = running_config(client, &scope_name).await else {
+                return Ok(());
+            };
+            let now = chrono::Utc::now().timestamp();
+            let complete = match message_sync::backfill_step(client, chat_id, retention_since()).await {
+                Ok(complete) => {
+                    db_backfill::record_page(chat_id, complete, now)?;
+                    complete
+                }
+                Err(e) => {
+                    db_backfill::record_error(chat_id, &e, now)?;
+                    if e.to_lowercase().contains("flood") {
+                        return Err(e);
+                    }
+                    log::warn!("Archive backfill skipped chat {}: {}", chat_id, e);
+                    true
+                }
+            };
+            let _ = app.emit("archive://backfill-progress", chat_id);
+            tokio::time::sleep(Duration::from_millis(60_000 / config.requests_per_minute.max(1) as u64)).await;
+            if complete {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run the archive backfill in the background for as long as the app is open
+pub async fn archive_backfill_loop(app: AppHandle, client: Arc<TelegramClient>) {
+    loop {
+        let pause = match backfill_pass(&app, &client).await {
+            Ok(()) => BACKFILL_IDLE_SECS,
+            Err(e) if e.to_lowercase().contains("flood") => {
+                log::warn!("Archive backfill paused by FLOOD_WAIT: {}", e);
+                BACKFILL_FLOOD_PAUSE_SECS
+            }
+            Err(e) => {
+                log::warn!("Archive backfill failed: {}", e);
+                BACKFILL_IDLE_SECS
+            }
+        };
+        tokio::time::sleep(Duration::from_secs(pause)).await;
+    }
+}
+
+#[tauri::command]
+pub async fn get_archive_backfill_status() -> Result<BackfillStatus, String> {
+    let checkpoints = db_backfill::load_checkpoints()?;
+    Ok(BackfillStatus {
+        config: settings::load_archive_backfill()?,
+        chats_started: checkpoints.len(),
+        chats_complete: checkpoints.iter().filter(|c| c.complete).count(),
+        pages: checkpoints.iter().map(|c| c.pages).sum(),
+        checkpoints,
+    })
+}
+
+/// Save the backfill settings; picking another scope starts its chats over
+#[tauri::command]
+pub async fn set_archive_backfill_config(config: ArchiveBackfillConfig) -> Result<(), String> {
+    if config.requests_per_minute == 0 {
+        return Err("Requests per minute must be at least 1".to_string());
+    }
+    if settings::load_archive_backfill()?.scope_name != config.scope_name {
+        db_backfill::clear()?;
+    }
+    settings::save_archive_backfill(&config)
+}
+
+/// Messages per chat, database size and the archived date range
+#[tauri::command]
+pub async fn get_archive_stats() -> Result<ArchiveStats, String> {
+    db_messages::archive_stats()
+}
+
+/// Delete archived messages of some chats and/or from before a date, and reclaim
+/// the space; returns how many messages were deleted
+#[tauri::command]
+pub async fn compact_archive(chat_ids: Option<Vec<i64>>, older_than: Option<i64>) -> Result<usize, String> {
+    let deleted = db_messages::compact_archive(chat_ids.as_deref(), older_than)?;
+    log::info!("Compacted the message archive: {} messages deleted", deleted);
+    Ok(deleted)
+}
+
+/// Archived messages of a chat that were deleted, with their earlier versions
+#[tauri::command]
+pub async fn get_recently_deleted(chat_id: i64, limit: Option<i32>) -> Result<Vec<DeletedMessage>, String> {
+    db_messages::load_deleted(chat_id, limit.unwrap_or(50))
+}
@@ -1,7 +1,7 @@
 use crate::cache::{BriefingCache, ContactsCache, SummaryCache};
 use crate::telegram::TelegramClient;
 use crate::telegram::client::{AuthState, User};
-use tauri::State;
+use tauri::{AppHandle, State};
 use std::sync::Arc;
 
 #[tauri::command]
@@ -51,6 +51,7 @@ pub async fn get_current_user(
 
 #[tauri::command]
 pub async fn logout(
+    app: AppHandle,
     client: State<'_, Arc<TelegramClient>>,
     contacts_cache: State<'_, Arc<ContactsCache>>,
     briefing_cache: State<'_, Arc<BriefingCache>>,
@@ -60,6 +61,12 @@ pub async fn logout(
     contacts_cache.0.invalidate_all().await;
     briefing_cache.0.invalidate_all().await;
     summary_cache.0.invalidate_all().await;
+    if let Err(e) = crate::db::retention::wipe_account_data() {
+        log::error!("Failed to wipe account data on logout: {}", e);
+    }
+    if let Err(e) = crate::commands::media::clear_media(&app) {
+        log::error!("Failed to delete downloaded media on logout: {}", e);
+    }
 
     client.logout().await
 }
@@ -0,0 +1,173 @@
+use crate::commands::pending_drafts::{emit_pending_count, send_or_queue, Delivery};
+use crate::db;
+use crate::db::away::AwayReply;
+use crate::db::pending_drafts::SOURCE_AWAY;
+use crate::db::settings::AwayConfig;
+use crate::telegram::client::Message;
+use crate::telegram::TelegramApi;
+use crate::utils::rate_limiter::RateLimiter;
+use crate::utils::template::fill_placeholders;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+/// Most auto-replies sent in any hour, so a burst of DMs can't trigger a FLOOD_WAIT
+const MAX_REPLIES_PER_HOUR: i64 = 20;
+
+/// Send the away reply for an incoming message if away mode is on and the sender
+/// is a person in a DM who hasn't had one this away period. Returns None when no
+/// reply was due. Queued replies count as given, so a sender gets one draft.
+pub(crate) async fn auto_reply_if_away(
+    client: &dyn TelegramApi,
+    limiter: &RateLimiter,
+    message: &Message,
+) -> Result<Option<Delivery>, String> {
+    if message.is_outgoing {
+        return Ok(None);
+    }
+    // A keyword reply already answered it
+    if db::bot_rules::replied_to_message(message.chat_id, message.id)? {
+        return Ok(None);
+    }
+    let config = db::settings::load_away_config()?;
+    if !config.enabled || config.excluded_chat_ids.contains(&message.chat_id) {
+        return Ok(None);
+    }
+
+    let Some(chat) = client.get_chat(message.chat_id).await? else {
+        return Ok(None);
+    };
+    if chat.chat_type != "private" || chat.is_bot || chat.id != message.sender_id {
+        return Ok(None);
+    }
+    if db::away::replied_since(chat.id, config.started_at.unwrap_or(0))? {
+        return Ok(None);
+    }
+
+    let hour_ago = chrono::Utc::now().timestamp() - 3600;
+    if db::away::count_since(hour_ago)? >= MAX_REPLIES_PER_HOUR {
+        log::warn!("Away reply to {} skipped: hourly limit reached", chat.id);
+        return Ok(None);
+    }
+    if let Err(wait) = limiter.can_send(chat.id) {
+        log::info!("Away reply to {} skipped: rate limited for {}s", chat.id, wait);
+        return Ok(None);
+    }
+
+    let values = HashMap::from([
+        ("name".to_string(), chat.title.clone()),
+        ("first_name".to_string(), chat.title.split_whitespace().next().unwrap_or("").to_string()),
+    ]);
+    let text = fill_placeholders(&config.template, &values);
+    let delivery = send_or_queue(client, chat.id, &text, SOURCE_AWAY).await?;
+    if delivery == Delivery::Sent {
+        limiter.record_send(chat.id);
+        log::info!("Sent away reply to {}", chat.id);
+    }
+    db::away::record_reply(chat.id, &chat.title, &text)?;
+    Ok(Some(delivery))
+}
+
+/// Auto-reply to an incoming message if away mode calls for it
+pub(crate) async fn apply_away_mode(app: &AppHandle, client: &dyn TelegramApi, message: &Message) {
+    let limiter = app.state::<Arc<RateLimiter>>();
+    match auto_reply_if_away(client, limiter.inner().as_ref(), message).await {
+        Ok(Some(Delivery::Queued)) => emit_pending_count(app),
+        Ok(_) => {}
+        Err(e) => log::warn!("Failed to send away reply: {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_away_config() -> Result<AwayConfig, String> {
+    db::settings::load_away_config()
+}
+
+/// Save the away config. Turning it on starts a new away period, so everyone
+/// can get the reply again.
+#[tauri::command]
+pub async fn set_away_config(mut config: AwayConfig) -> Result<AwayConfig, String> {
+    if config.enabled && config.template.trim().is_empty() {
+        return Err("The away reply can't be empty".to_string());
+    }
+    let previous = db::settings::load_away_config()?;
+    config.started_at = match (config.enabled, previous.enabled) {
+        (true, true) => previous.started_at,
+        (true, false) => Some(chrono::Utc::now().timestamp()),
+        (false, _) => None,
+    };
+    db::settings::save_away_config(&config)?;
+    Ok(config)
+}
+
+/// Who received the auto-reply, newest first. Defaults to the current away period.
+#[tauri::command]
+pub async fn get_away_replies(since: Option<i64>) -> Result<Vec<AwayReply>, String> {
+    let since = match since {
+        Some(since) => since,
+        None => db::settings::load_away_config()?.started_at.unwrap_or(0),
+    };
+    db::away::load_replies(since)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::bot_rules::{NewBotRule, ACTION_REPLY, TRIGGER_DM};
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_away_reply_sent_once_per_contact() {
+        crate::db::init_test_db();
+        let alice = fake::user(7391, "Alice", "Smith");
+        let bob = fake::user(7392, "Bob", "");
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(fake::chat(7391, "private", "Alice Smith", 1, 0))
+            .with_chat(fake::chat(7392, "private", "Bob", 1, 1))
+            .with_chat(fake::chat(7393, "group", "Team", 1, 2));
+        let limiter = RateLimiter::new(0);
+        let from_alice = fake::text_message(1, 7391, &alice, "Hi!", 100);
+
+        // Off by default
+        assert!(auto_reply_if_away(&client, &limiter, &from_alice).await.unwrap().is_none());
+
+        let config = AwayConfig {
+            enabled: true,
+            template: "{first_name}, I'm on vacation".to_string(),
+            excluded_chat_ids: vec![7392],
+            ..AwayConfig::default()
+        };
+        assert!(set_away_config(config).await.unwrap().started_at.is_some());
+
+        // No second reply to a message a keyword rule answered
+        let rule = db::bot_rules::add_rule(&NewBotRule {
+            name: "Prices".to_string(),
+            trigger: TRIGGER_DM.to_string(),
+            bot_id: 0,
+            pattern: "(?i)price".to_string(),
+            action: ACTION_REPLY.to_string(),
+            target: Some("prices".to_string()),
+            daily_cap: None,
+        })
+        .unwrap();
+        db::bot_rules::record_hit(&rule, 7391, 4, "Price?", Some("Prices: ..."), None).unwrap();
+        let asked = fake::text_message(4, 7391, &alice, "Price?", 100);
+        assert!(auto_reply_if_away(&client, &limiter, &asked).await.unwrap().is_none());
+        db::bot_rules::delete_rule(rule.id).unwrap();
+
+        let delivery = auto_reply_if_away(&client, &limiter, &from_alice).await.unwrap();
+        assert_eq!(delivery, Some(Delivery::Sent));
+        assert!(auto_reply_if_away(&client, &limiter, &from_alice).await.unwrap().is_none());
+        assert!(auto_reply_if_away(&client, &limiter, &fake::text_message(2, 7392, &bob, "Hey", 100)).await.unwrap().is_none());
+        assert!(auto_reply_if_away(&client, &limiter, &fake::text_message(3, 7393, &bob, "All", 100)).await.unwrap().is_none());
+        assert_eq!(client.sent(), vec![(7391, "Alice, I'm on vacation".to_string())]);
+
+        let replies = get_away_replies(None).await.unwrap();
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].name, "Alice Smith");
+
+        let config = db::settings::load_away_config().unwrap();
+        set_away_config(AwayConfig { enabled: false, ..config }).await.unwrap();
+        assert!(auto_reply_if_away(&client, &limiter, &from_alice).await.unwrap().is_none());
+    }
+}
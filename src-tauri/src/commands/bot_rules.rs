@@ -0,0 +1,416 @@
+use crate::ai::client::CallTimeout;
+use crate::ai::context::message_text_for_ai;
+use crate::ai::prompts::{format_bot_extraction_user_prompt, BOT_EXTRACTION_SYSTEM_PROMPT};
+use crate::ai::sanitize::sanitize_message_text;
+use crate::ai::types::OpenAIMessage;
+use crate::ai::LLMClient;
+use crate::commands::canned::{normalize_shortcut, render_for_chat, require_canned};
+use crate::commands::outreach::extract_flood_wait_seconds;
+use crate::commands::pending_drafts::{emit_pending_count, send_or_queue, Delivery};
+use crate::db;
+use crate::db::bot_rules::{
+    self as db_bot_rules, BotRule, BotRuleHit, NewBotRule, ACTIONS, ACTION_EXTRACT, ACTION_FORWARD, ACTION_NOTIFY,
+    ACTION_REPLY, ACTION_TAG, TRIGGER_BOT, TRIGGER_DM,
+};
+use crate::db::pending_drafts::SOURCE_RULE;
+use crate::telegram::client::Message;
+use crate::telegram::TelegramApi;
+use crate::utils::rate_limiter::RateLimiter;
+use chrono::Timelike;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Hits shown by `get_bot_rule_hits` when no limit is given
+const DEFAULT_HITS_LIMIT: i64 = 100;
+
+/// Daily cap for forward rules created without one
+const DEFAULT_FORWARD_DAILY_CAP: i64 = 50;
+
+/// Unix timestamp of the most recent local midnight
+fn today_start() -> i64 {
+    chrono::Utc::now().timestamp() - chrono::Local::now().num_seconds_from_midnight() as i64
+}
+
+/// Rules triggered by `message` whose pattern matches `text`; DM rules only apply
+/// when `is_person_dm`
+fn matching_rules<'a>(rules: &'a [BotRule], message: &Message, text: &str, is_person_dm: bool) -> Vec<&'a BotRule> {
+    rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter(|rule| match rule.trigger.as_str() {
+            TRIGGER_BOT => rule.bot_id == message.sender_id,
+            TRIGGER_DM => is_person_dm,
+            _ => false,
+        })
+        .filter(|rule| match Regex::new(&rule.pattern) {
+            Ok(re) => re.is_match(text),
+            Err(e) => {
+                log::warn!("Skipping bot rule {} with invalid pattern: {}", rule.id, e);
+                false
+            }
+        })
+        .collect()
+}
+
+/// Run one rule's action; Ok carries the result stored with the hit
+async fn run_action(
+    client: &dyn TelegramApi,
+    llm: &LLMClient,
+    limiter: &RateLimiter,
+    rule: &BotRule,
+    message: &Message,
+    text: &str,
+) -> Result<Option<String>, String> {
+    let target = rule.target.as_deref().unwrap_or("");
+    match rule.action.as_str() {
+        ACTION_FORWARD => {
+            let to_chat_id: i64 = target.parse().map_err(|_| format!("Invalid forward target: {}", target))?;
+            // Forwards are sends too, so a noisy bot can't run past the send budget
+            if let Err(wait) = limiter.can_send(to_chat_id) {
+                return Err(format!("Rate limited for {}s", wait));
+            }
+            if let Err(e) = client.forward_message(message.chat_id, message.id, to_chat_id).await {
+                if e.to_lowercase().contains("flood") {
+                    if let Some(wait_secs) = extract_flood_wait_seconds(&e) {
+                        limiter.handle_flood_wait(wait_secs);
+                    }
+                }
+                return Err(e);
+            }
+            limiter.record_send(to_chat_id);
+            Ok(None)
+        }
+        // Tags the sender, so the tag shows up (and can be filtered on) in Contacts
+        ACTION_TAG => {
+            db::contacts::add_contact_tag(message.sender_id, target)?;
+            Ok(Some(target.to_string()))
+        }
+        ACTION_NOTIFY => Ok(None),
+        ACTION_EXTRACT => {
+            let messages = vec![
+                OpenAIMessage {
+                    role: "system".to_string(),
+                    content: BOT_EXTRACTION_SYSTEM_PROMPT.to_string(),
+                },
+                OpenAIMessage {
+                    role: "user".to_string(),
+                    content: format_bot_extraction_user_prompt(target, &sanitize_message_text(text)),
+                },
+            ];
+            let response = llm
+                .chat_completion_private(messages, &[], 0.0, 300, false, CallTimeout::Short)
+                .await
+                .map_err(|e| format!("Extraction failed: {}", e))?;
+            Ok(Some(response.trim().to_string()))
+        }
+        ACTION_REPLY => {
+            let canned = require_canned(target)?;
+            let rendered = render_for_chat(client, &canned.text, message.chat_id, HashMap::new()).await?;
+            if !rendered.missing_variables.is_empty() {
+                return Err(format!("Missing values for: {}", rendered.missing_variables.join(", ")));
+            }
+            // Replies share the app-wide send budget with every other send
+            if let Err(wait) = limiter.can_send(message.chat_id) {
+                return Err(format!("Rate limited for {}s", wait));
+            }
+            match send_or_queue(client, message.chat_id, &rendered.text, SOURCE_RULE).await {
+                Ok(Delivery::Sent) => limiter.record_send(message.chat_id),
+                Ok(Delivery::Queued) => {}
+                Err(e) => {
+                    if let Some(wait_secs) = extract_flood_wait_seconds(&e) {
+                        limiter.handle_flood_wait(wait_secs);
+                    }
+                    return Err(e);
+                }
+            }
+            Ok(Some(rendered.text))
+        }
+        other => Err(format!("Unknown bot rule action: {}", other)),
+    }
+}
+
+/// Evaluate the rules for the message's sender and record a hit for every match.
+/// A failing action is recorded on its hit and doesn't stop the other rules. A
+/// message gets at most one reply, however many reply rules match it.
+pub(crate) async fn run_bot_rules(
+    client: &dyn TelegramApi,
+    llm: &LLMClient,
+    limiter: &RateLimiter,
+    message: &Message,
+) -> Result<Vec<BotRuleHit>, String> {
+    if message.is_outgoing {
+        return Ok(Vec::new());
+    }
+    let rules = db_bot_rules::load_enabled_for_sender(message.sender_id)?;
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Private chat id equals the user id; only look the chat up when a DM rule could fire
+    let is_person_dm = message.chat_id == message.sender_id
+        && rules.iter().any(|r| r.trigger == TRIGGER_DM)
+        && client
+            .get_chat(message.chat_id)
+            .await?
+            .is_some_and(|chat| chat.chat_type == "private" && !chat.is_bot);
+
+    let text = message_text_for_ai(&message.content);
+    let mut hits = Vec::new();
+    let mut replied = db_bot_rules::replied_to_message(message.chat_id, message.id)?;
+    for rule in matching_rules(&rules, message, &text, is_person_dm) {
+        if rule.action == ACTION_REPLY && replied {
+            continue;
+        }
+        if let Some(cap) = rule.daily_cap {
+            if db_bot_rules::count_successful_hits(rule.id, today_start())? >= cap {
+                log::info!("Bot rule {} skipped: daily cap of {} reached", rule.id, cap);
+                continue;
+            }
+        }
+        let hit = match run_action(client, llm, limiter, rule, message, &text).await {
+            Ok(result) => db_bot_rules::record_hit(rule, message.chat_id, message.id, &text, result.as_deref(), None)?,
+            Err(e) => {
+                log::warn!("Bot rule {} failed on message {}: {}", rule.id, message.id, e);
+                db_bot_rules::record_hit(rule, message.chat_id, message.id, &text, None, Some(&e))?
+            }
+        };
+        replied |= hit.action == ACTION_REPLY && hit.error.is_none();
+        hits.push(hit);
+    }
+    Ok(hits)
+}
+
+/// Run the bot rules for an incoming message, emitting `notify://bot-rule` for
+/// hits of "notify" rules
+pub(crate) async fn apply_bot_rules(app: &AppHandle, client: &dyn TelegramApi, message: &Message) {
+    let llm = app.state::<Arc<LLMClient>>();
+    let limiter = app.state::<Arc<RateLimiter>>();
+    match run_bot_rules(client, llm.inner().as_ref(), limiter.inner().as_ref(), message).await {
+        Ok(hits) => {
+            for hit in hits.iter().filter(|h| h.action == ACTION_NOTIFY) {
+                let _ = app.emit("notify://bot-rule", hit);
+            }
+            // Replies may have been queued for approval
+            if hits.iter().any(|h| h.action == ACTION_REPLY && h.error.is_none()) {
+                emit_pending_count(app);
+            }
+        }
+        Err(e) => log::warn!("Failed to evaluate bot rules: {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_bot_rules() -> Result<Vec<BotRule>, String> {
+    db_bot_rules::load_rules()
+}
+
+/// Add a rule for messages from `bot_id` matching the `pattern` regex. `target` is
+/// the chat id for "forward", the contact tag given to the sender for "tag", the
+/// instruction for "extract". The rule fires at most `daily_cap` times a day;
+/// forward rules are capped at `DEFAULT_FORWARD_DAILY_CAP` unless given one.
+#[tauri::command]
+pub async fn add_bot_rule(
+    name: String,
+    bot_id: i64,
+    pattern: String,
+    action: String,
+    target: Option<String>,
+    daily_cap: Option<i64>,
+) -> Result<BotRule, String> {
+    if !ACTIONS.contains(&action.as_str()) {
+        return Err(format!("Unknown bot rule action: {}", action));
+    }
+    if daily_cap.is_some_and(|cap| cap < 1) {
+        return Err("Daily cap must be at least 1".to_string());
+    }
+    Regex::new(&pattern).map_err(|e| format!("Invalid pattern: {}", e))?;
+
+    let target = target.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    match (action.as_str(), target.as_deref()) {
+        (ACTION_FORWARD, Some(t)) if t.parse::<i64>().is_err() => {
+            return Err(format!("Forward target must be a chat id: {}", t));
+        }
+        (ACTION_FORWARD | ACTION_TAG | ACTION_EXTRACT, None) => {
+            return Err(format!("A \"{}\" rule needs a target", action));
+        }
+        _ => {}
+    }
+
+    let name = name.trim();
+    db_bot_rules::add_rule(&NewBotRule {
+        name: if name.is_empty() { pattern.clone() } else { name.to_string() },
+        trigger: TRIGGER_BOT.to_string(),
+        bot_id,
+        pattern,
+        daily_cap: daily_cap.or((action == ACTION_FORWARD).then_some(DEFAULT_FORWARD_DAILY_CAP)),
+        action,
+        target,
+    })
+}
+
+/// Reply to incoming DMs that contain `keyword` (case-insensitive) with the canned
+/// response `shortcut`, at most `daily_cap` times a day. Replies are logged as hits.
+#[tauri::command]
+pub async fn add_keyword_reply_rule(
+    name: Option<String>,
+    keyword: String,
+    shortcut: String,
+    daily_cap: Option<i64>,
+) -> Result<BotRule, String> {
+    let keyword = keyword.trim();
+    if keyword.is_empty() {
+        return Err("Keyword is empty".to_string());
+    }
+    if daily_cap.is_some_and(|cap| cap < 1) {
+        return Err("Daily cap must be at least 1".to_string());
+    }
+    let shortcut = normalize_shortcut(&shortcut);
+    require_canned(&shortcut)?;
+
+    let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    db_bot_rules::add_rule(&NewBotRule {
+        name: name.unwrap_or_else(|| format!("\"{}\" → /{}", keyword, shortcut)),
+        trigger: TRIGGER_DM.to_string(),
+        bot_id: 0,
+        pattern: format!("(?i){}", regex::escape(keyword)),
+        action: ACTION_REPLY.to_string(),
+        target: Some(shortcut),
+        daily_cap,
+    })
+}
+
+#[tauri::command]
+pub async fn set_bot_rule_enabled(id: i64, enabled: bool) -> Result<(), String> {
+    db_bot_rules::set_enabled(id, enabled)
+}
+
+#[tauri::command]
+pub async fn delete_bot_rule(id: i64) -> Result<(), String> {
+    db_bot_rules::delete_rule(id)
+}
+
+/// Messages that matched a rule, most recent first
+#[tauri::command]
+pub async fn get_bot_rule_hits(limit: Option<i64>) -> Result<Vec<BotRuleHit>, String> {
+    db_bot_rules::load_hits(limit.unwrap_or(DEFAULT_HITS_LIMIT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::LLMConfig;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_bot_rules_route_matching_alerts() {
+        crate::db::init_test_db();
+        let bot = fake::user(7381, "MonitorBot", "");
+        let alert = fake::text_message(1, 7381, &bot, "CRITICAL: disk full on db-1", 100);
+        let ok = fake::text_message(2, 7381, &bot, "RESOLVED: disk on db-1", 200);
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(fake::chat(7381, "private", "MonitorBot", 0, 0))
+            .with_chat(fake::chat(7382, "group", "On-call", 0, 1))
+            .with_messages(7381, vec![alert.clone(), ok.clone()]);
+        let llm = LLMClient::new(LLMConfig::default());
+        let limiter = RateLimiter::new(0);
+
+        let forward =
+            add_bot_rule("Page on-call".into(), 7381, "^CRITICAL".into(), "forward".into(), Some("7382".into()), None)
+                .await
+                .unwrap();
+        assert_eq!(forward.daily_cap, Some(DEFAULT_FORWARD_DAILY_CAP));
+        add_bot_rule("".into(), 7381, "(?i)disk".into(), "tag".into(), Some("infra".into()), None).await.unwrap();
+        // Another bot's rule never fires for this one
+        add_bot_rule("Other".into(), 7389, ".*".into(), "notify".into(), None, None).await.unwrap();
+
+        assert!(add_bot_rule("Bad".into(), 7381, "(".into(), "notify".into(), None, None).await.is_err());
+        assert!(add_bot_rule("Bad".into(), 7381, "x".into(), "forward".into(), Some("ops".into()), None).await.is_err());
+        assert!(add_bot_rule("Bad".into(), 7381, "x".into(), "explode".into(), None, None).await.is_err());
+
+        let hits = run_bot_rules(&client, &llm, &limiter, &alert).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(client.sent(), vec![(7382, "CRITICAL: disk full on db-1".to_string())]);
+        assert_eq!(hits[1].rule_name, "(?i)disk");
+        assert_eq!(hits[1].result.as_deref(), Some("infra"));
+        assert!(crate::db::contacts::get_contact_tags(7381).unwrap().contains(&"infra".to_string()));
+
+        // Only the tag rule matches the recovery message, and disabled rules are skipped
+        set_bot_rule_enabled(forward.id, false).await.unwrap();
+        let hits = run_bot_rules(&client, &llm, &limiter, &alert).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(run_bot_rules(&client, &llm, &limiter, &ok).await.unwrap().len(), 1);
+        assert_eq!(client.sent().len(), 1);
+
+        let recorded = get_bot_rule_hits(None).await.unwrap();
+        assert!(recorded.iter().filter(|h| h.chat_id == 7381).count() >= 4);
+    }
+
+    #[tokio::test]
+    async fn test_forwards_respect_rate_limit_and_daily_cap() {
+        crate::db::init_test_db();
+        let bot = fake::user(7691, "AlertBot", "");
+        let alerts: Vec<Message> = (1..=3)
+            .map(|id| fake::text_message(id, 7691, &bot, &format!("CRITICAL: alert {}", id), id as i64 * 100))
+            .collect();
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(fake::chat(7691, "private", "AlertBot", 0, 0))
+            .with_chat(fake::chat(7692, "group", "On-call", 0, 1))
+            .with_messages(7691, alerts.clone());
+        let llm = LLMClient::new(LLMConfig::default());
+        let limiter = RateLimiter::new(60);
+
+        let zero_cap = add_bot_rule("Bad".into(), 7691, "x".into(), "forward".into(), Some("7692".into()), Some(0)).await;
+        assert!(zero_cap.is_err());
+        let rule = add_bot_rule("Page".into(), 7691, "^CRITICAL".into(), "forward".into(), Some("7692".into()), Some(2))
+            .await
+            .unwrap();
+
+        // A burst of alerts doesn't forward back-to-back; the held one is recorded as failed
+        run_bot_rules(&client, &llm, &limiter, &alerts[0]).await.unwrap();
+        let hits = run_bot_rules(&client, &llm, &limiter, &alerts[1]).await.unwrap();
+        assert!(hits[0].error.as_deref().is_some_and(|e| e.contains("Rate limited")));
+        assert_eq!(client.sent().len(), 1);
+
+        // Once the limiter allows it, the daily cap still stops the rule
+        let limiter = RateLimiter::new(0);
+        run_bot_rules(&client, &llm, &limiter, &alerts[1]).await.unwrap();
+        assert!(run_bot_rules(&client, &llm, &limiter, &alerts[2]).await.unwrap().is_empty());
+        assert_eq!(client.sent().len(), 2);
+
+        delete_bot_rule(rule.id).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_keyword_replies_respect_daily_cap() {
+        crate::db::init_test_db();
+        let buyer = fake::user(7401, "Dana", "");
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_contact(buyer.clone())
+            .with_chat(fake::chat(7401, "private", "Dana", 1, 0))
+            .with_chat(fake::chat(7402, "group", "Market", 1, 1));
+        let llm = LLMClient::new(LLMConfig::default());
+        let limiter = RateLimiter::new(0);
+
+        assert!(add_keyword_reply_rule(None, "price list".into(), "/nosuch7401".into(), None).await.is_err());
+        crate::db::canned::save_canned("prices7401", "Hi {name}, prices: ...", &["name".to_string()]).unwrap();
+        let rule = add_keyword_reply_rule(None, "Price List".into(), "/prices7401".into(), Some(1)).await.unwrap();
+        assert_eq!(rule.trigger, TRIGGER_DM);
+
+        let ask = fake::text_message(1, 7401, &buyer, "can I get your price list?", 100);
+        let hits = run_bot_rules(&client, &llm, &limiter, &ask).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(client.sent(), vec![(7401, "Hi Dana, prices: ...".to_string())]);
+
+        // One reply per message, capped for the day, and never outside DMs
+        assert!(run_bot_rules(&client, &llm, &limiter, &ask).await.unwrap().is_empty());
+        let again = fake::text_message(3, 7401, &buyer, "price list again?", 200);
+        assert!(run_bot_rules(&client, &llm, &limiter, &again).await.unwrap().is_empty());
+        let in_group = fake::text_message(2, 7402, &buyer, "price list please", 100);
+        assert!(run_bot_rules(&client, &llm, &limiter, &in_group).await.unwrap().is_empty());
+        assert_eq!(client.sent().len(), 1);
+
+        delete_bot_rule(rule.id).await.unwrap();
+    }
+}
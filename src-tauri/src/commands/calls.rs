@@ -0,0 +1,124 @@
+use crate::db::calls::{self as db_calls, CallRecord, NewCall};
+use crate::telegram::{TelegramApi, client::{Chat, Message, MessageContent}};
+
+const DEFAULT_MISSED_CALL_DAYS: i64 = 7;
+
+/// Whether a message is an incoming call the user missed
+pub(crate) fn is_missed_call(message: &Message) -> bool {
+    !message.is_outgoing && matches!(message.content, MessageContent::Call { missed: true, .. })
+}
+
+/// Calls among `messages` (one-to-one call service messages only)
+fn collect_calls(chat_id: i64, chat_title: &str, messages: &[Message]) -> Vec<NewCall> {
+    messages
+        .iter()
+        .filter_map(|m| match &m.content {
+            MessageContent::Call { video, missed, duration } => Some(NewCall {
+                chat_id,
+                chat_title: chat_title.to_string(),
+                message_id: m.id,
+                caller_id: m.sender_id,
+                caller_name: m.sender_name.clone(),
+                is_outgoing: m.is_outgoing,
+                video: *video,
+                missed: *missed,
+                duration: *duration,
+                call_date: m.date,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn store_calls(chat_id: i64, calls: &[NewCall]) {
+    match db_calls::record_calls(calls) {
+        Ok(0) => {}
+        Ok(count) => log::info!("Recorded {} new call(s) in chat {}", count, chat_id),
+        Err(e) => log::warn!("Failed to record calls for chat {}: {}", chat_id, e),
+    }
+}
+
+/// Record calls found in fetched history or live updates for one chat.
+/// Best-effort: failures are logged and never fail the caller.
+pub(crate) async fn record_calls_from(client: &dyn TelegramApi, chat_id: i64, messages: &[Message]) {
+    if !messages.iter().any(|m| matches!(m.content, MessageContent::Call { .. })) {
+        return;
+    }
+
+    let chat_title = match client.get_chat(chat_id).await {
+        Ok(Some(chat)) => chat.title,
+        _ => String::new(),
+    };
+    store_calls(chat_id, &collect_calls(chat_id, &chat_title, messages));
+}
+
+/// Record calls that are the last message of a listed chat (a missed call is
+/// often the last thing in a DM)
+pub(crate) fn record_calls_from_chats(chats: &[Chat]) {
+    for chat in chats {
+        if let Some(message) = &chat.last_message {
+            let calls = collect_calls(chat.id, &chat.title, std::slice::from_ref(message));
+            if !calls.is_empty() {
+                store_calls(chat.id, &calls);
+            }
+        }
+    }
+}
+
+fn since_days(days: i64) -> i64 {
+    chrono::Utc::now().timestamp() - days.max(0) * 86_400
+}
+
+/// Incoming calls missed in the last `days` days (default 7), newest first
+#[tauri::command]
+pub async fn get_missed_calls(days: Option<i64>) -> Result<Vec<CallRecord>, String> {
+    db_calls::load_calls(since_days(days.unwrap_or(DEFAULT_MISSED_CALL_DAYS)), true)
+}
+
+/// All recorded calls in the last `days` days (default 7), newest first
+#[tauri::command]
+pub async fn get_call_log(days: Option<i64>) -> Result<Vec<CallRecord>, String> {
+    db_calls::load_calls(since_days(days.unwrap_or(DEFAULT_MISSED_CALL_DAYS)), false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    fn call(id: i64, is_outgoing: bool, missed: bool, date: i64) -> Message {
+        let sender = fake::user(if is_outgoing { 1 } else { 2 }, "Client", "");
+        Message {
+            content: MessageContent::Call { video: false, missed, duration: (!missed).then_some(60) },
+            is_outgoing,
+            ..fake::text_message(id, 7001, &sender, "", date)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missed_calls_recorded_from_history() {
+        crate::db::init_test_db();
+        let now = chrono::Utc::now().timestamp();
+        let client = FakeTelegram::new(fake::user(1, "Me", "")).with_chat(fake::chat(7001, "private", "Client", 0, 0));
+        let sender = fake::user(2, "Client", "");
+        let messages = vec![
+            fake::text_message(1, 7001, &sender, "Are you there?", now - 300),
+            call(2, false, true, now - 200),
+            call(3, true, true, now - 100),
+            call(4, false, false, now - 50),
+            call(5, false, true, now - 30 * 86_400),
+        ];
+        assert!(is_missed_call(&messages[1]) && !is_missed_call(&messages[2]));
+
+        record_calls_from(&client, 7001, &messages).await;
+        // Already recorded calls are ignored
+        record_calls_from(&client, 7001, &messages).await;
+
+        let log: Vec<CallRecord> = get_call_log(Some(7)).await.unwrap().into_iter().filter(|c| c.chat_id == 7001).collect();
+        assert_eq!(log.iter().map(|c| c.message_id).collect::<Vec<_>>(), vec![4, 3, 2]);
+
+        let missed: Vec<CallRecord> = get_missed_calls(None).await.unwrap().into_iter().filter(|c| c.chat_id == 7001).collect();
+        assert_eq!(missed.len(), 1);
+        assert_eq!((missed[0].message_id, missed[0].chat_title.as_str()), (2, "Client"));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::commands::outreach::extract_flood_wait_seconds;
+use crate::db::canned::{self as db_canned, CannedResponse};
+use crate::telegram::client::Message;
+use crate::telegram::{TelegramApi, TelegramClient};
+use crate::utils::rate_limiter::RateLimiter;
+use crate::utils::template::{contact_variables, fill_placeholders, placeholders};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedTemplate {
+    pub text: String,
+    /// Variables without a value, left as `{placeholder}` in the text
+    pub missing_variables: Vec<String>,
+}
+
+/// Shortcuts are stored without a leading "/" and case-insensitively
+pub(crate) fn normalize_shortcut(shortcut: &str) -> String {
+    shortcut.trim().trim_start_matches('/').to_lowercase()
+}
+
+#[tauri::command]
+pub async fn list_canned_responses() -> Result<Vec<CannedResponse>, String> {
+    db_canned::list_canned()
+}
+
+/// Create or update a canned response. `{placeholder}` variables in the text are
+/// detected automatically; contact variables ({name}, {full_name}, ...) are filled in
+/// from the chat and any others must be supplied when sending.
+#[tauri::command]
+pub async fn save_canned_response(shortcut: String, text: String) -> Result<CannedResponse, String> {
+    let shortcut = normalize_shortcut(&shortcut);
+    if shortcut.is_empty() || shortcut.contains(char::is_whitespace) {
+        return Err("Shortcut must be a single word".to_string());
+    }
+    if text.trim().is_empty() {
+        return Err("Response text is empty".to_string());
+    }
+
+    db_canned::save_canned(&shortcut, &text, &placeholders(&text))?;
+    db_canned::load_canned(&shortcut)?.ok_or_else(|| "Canned response not found after save".to_string())
+}
+
+#[tauri::command]
+pub async fn delete_canned_response(shortcut: String) -> Result<(), String> {
+    db_canned::delete_canned(&normalize_shortcut(&shortcut))
+}
+
+/// Contact variables for a chat: the contact's names for DMs, the chat title otherwise
+async fn chat_variables(client: &dyn TelegramApi, chat_id: i64) -> Result<HashMap<String, String>, String> {
+    // Private chat id equals the user id
+    if let Some(user) = client.get_contacts().await?.into_iter().find(|u| u.id == chat_id) {
+        return Ok(contact_variables(&user.first_name, &user.last_name, user.username.as_deref()));
+    }
+    let title = client.get_chat(chat_id).await?.map(|c| c.title).unwrap_or_default();
+    Ok(contact_variables(&title, "", None))
+}
+
+/// Fill a template for a chat. Explicit `overrides` win over contact variables.
+pub(crate) async fn render_for_chat(
+    client: &dyn TelegramApi,
+    template: &str,
+    chat_id: i64,
+    overrides: HashMap<String, String>,
+) -> Result<RenderedTemplate, String> {
+    let mut values = chat_variables(client, chat_id).await?;
+    values.extend(overrides);
+
+    Ok(RenderedTemplate {
+        text: fill_placeholders(template, &values),
+        missing_variables: placeholders(template)
+            .into_iter()
+            .filter(|v| !values.contains_key(v))
+            .collect(),
+    })
+}
+
+pub(crate) fn require_canned(shortcut: &str) -> Result<CannedResponse, String> {
+    db_canned::load_canned(shortcut)?.ok_or_else(|| format!("No canned response for /{}", shortcut))
+}
+
+/// Preview a canned response (by shortcut) for a chat without sending it
+#[tauri::command]
+pub async fn render_template(
+    client: State<'_, Arc<TelegramClient>>,
+    template_id: String,
+    chat_id: i64,
+    variables: Option<HashMap<String, String>>,
+) -> Result<RenderedTemplate, String> {
+    let canned = require_canned(&normalize_shortcut(&template_id))?;
+    render_for_chat(client.inner().as_ref(), &canned.text, chat_id, variables.unwrap_or_default()).await
+}
+
+/// Send a canned response to a chat, going through the shared send rate limiter
+#[tauri::command]
+pub async fn send_canned(
+    client: State<'_, Arc<TelegramClient>>,
+    rate_limiter: State<'_, Arc<RateLimiter>>,
+    chat_id: i64,
+    shortcut: String,
+    variables: Option<HashMap<String, String>>,
+) -> Result<Message, String> {
+    let canned = require_canned(&normalize_shortcut(&shortcut))?;
+
+    let rendered =
+        render_for_chat(client.inner().as_ref(), &canned.text, chat_id, variables.unwrap_or_default()).await?;
+    if !rendered.missing_variables.is_empty() {
+        return Err(format!("Missing values for: {}", rendered.missing_variables.join(", ")));
+    }
+
+    if let Err(wait) = rate_limiter.can_send(chat_id) {
+        return Err(format!("Rate limited: wait {} seconds before messaging this chat again", wait));
+    }
+
+    match client.send_message(chat_id, &rendered.text).await {
+        Ok(message) => {
+            rate_limiter.record_send(chat_id);
+            Ok(message)
+        }
+        Err(e) => {
+            if e.to_lowercase().contains("flood") {
+                if let Some(wait_secs) = extract_flood_wait_seconds(&e) {
+                    rate_limiter.handle_flood_wait(wait_secs);
+                }
+            }
+            Err(e)
+        }
+    }
+}
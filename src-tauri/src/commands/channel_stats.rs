@@ -0,0 +1,219 @@
+//! Channel growth for owners without Telegram Premium analytics: the user's
+//! channels are snapshotted periodically (subscribers and recent post views) and
+//! `get_channel_growth` turns the snapshots into a trend.
+
+use crate::db::channel_stats::{self as db_stats, ChannelSnapshot};
+use crate::telegram::client::{AuthState, PostViews};
+use crate::telegram::TelegramClient;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+
+/// How often the user's channels are snapshotted
+const SNAPSHOT_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+/// How often the loop looks for channels due a snapshot
+const CHECK_INTERVAL_SECS: u64 = 15 * 60;
+
+/// Latest posts whose views are read with each snapshot
+const SNAPSHOT_POST_LIMIT: i32 = 20;
+
+/// Most viewed posts listed with a growth report
+const TOP_POSTS_LIMIT: i64 = 5;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrowthPoint {
+    /// Start of the day or week (unix seconds, UTC)
+    pub date: i64,
+    /// Subscribers at the last snapshot of the bucket
+    pub subscriber_count: i64,
+    /// Average of the bucket's snapshots
+    pub avg_views: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelGrowth {
+    pub channel_id: i64,
+    pub period: String,
+    pub points: Vec<GrowthPoint>,
+    /// Subscribers gained (or lost) between the first and last snapshot
+    pub subscriber_change: i64,
+    pub subscriber_change_percent: Option<f64>,
+    pub top_posts: Vec<PostViews>,
+}
+
+/// Window and bucket size (in days) of a period
+fn period_days(period: &str) -> Result<(i64, i64), String> {
+    match period {
+        "week" => Ok((7, 1)),
+        "month" => Ok((30, 1)),
+        "quarter" => Ok((90, 7)),
+        "year" => Ok((365, 7)),
+        _ => Err(format!("Unknown period '{}', expected week, month, quarter or year", period)),
+    }
+}
+
+/// One point per bucket of `bucket_secs`, from snapshots sorted oldest first
+fn aggregate_growth(snapshots: &[ChannelSnapshot], bucket_secs: i64) -> Vec<GrowthPoint> {
+    let mut points: Vec<GrowthPoint> = Vec::new();
+    let mut views: Vec<i64> = Vec::new();
+    for snapshot in snapshots {
+        let date = snapshot.taken_at - snapshot.taken_at.rem_euclid(bucket_secs);
+        if points.last().map_or(true, |p| p.date != date) {
+            views.clear();
+            points.push(GrowthPoint { date, subscriber_count: 0, avg_views: None });
+        }
+        let point = points.last_mut().expect("pushed above");
+        point.subscriber_count = snapshot.subscriber_count;
+        views.extend(snapshot.avg_views);
+        if !views.is_empty() {
+            point.avg_views = Some(views.iter().sum::<i64>() / views.len() as i64);
+        }
+    }
+    points
+}
+
+/// Read a channel's subscribers and recent post views and store them
+async fn take_snapshot(client: &TelegramClient, channel_id: i64) -> Result<ChannelSnapshot, String> {
+    let stats = client.get_channel_stats(channel_id, SNAPSHOT_POST_LIMIT).await?;
+    let avg_views = (!stats.posts.is_empty())
+        .then(|| stats.posts.iter().map(|p| p.views as i64).sum::<i64>() / stats.posts.len() as i64);
+    let snapshot = ChannelSnapshot {
+        channel_id,
+        taken_at: chrono::Utc::now().timestamp(),
+        subscriber_count: stats.subscriber_count as i64,
+        avg_views,
+    };
+    db_stats::save_snapshot(&snapshot, &stats.posts)?;
+    Ok(snapshot)
+}
+
+/// Whether a channel last snapshotted at `latest` is due another one
+fn snapshot_due(latest: Option<i64>, now: i64) -> bool {
+    latest.map_or(true, |taken_at| now - taken_at >= SNAPSHOT_INTERVAL_SECS as i64)
+}
+
+/// Snapshot every channel the user owns, periodically while signed in. Channels
+/// are checked at startup too, so a restart doesn't push the next snapshot back.
+pub async fn channel_stats_loop(client: Arc<TelegramClient>) {
+    loop {
+        if matches!(client.get_auth_state().await, AuthState::Ready) {
+            snapshot_due_channels(&client).await;
+        }
+        tokio::time::sleep(Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+    }
+}
+
+async fn snapshot_due_channels(client: &TelegramClient) {
+    let channel_ids = match client.get_owned_channel_ids().await {
+        Ok(ids) => ids,
+        Err(e) => {
+            log::warn!("Failed to list owned channels: {}", e);
+            return;
+        }
+    };
+    let now = chrono::Utc::now().timestamp();
+    for channel_id in channel_ids {
+        match db_stats::latest_snapshot_at(channel_id) {
+            Ok(latest) if !snapshot_due(latest, now) => continue,
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to check snapshots of channel {}: {}", channel_id, e),
+        }
+        if let Err(e) = take_snapshot(client, channel_id).await {
+            log::warn!("Failed to snapshot channel {}: {}", channel_id, e);
+        }
+    }
+}
+
+/// Snapshot a channel now, e.g. to start tracking it without waiting for the loop
+#[tauri::command]
+pub async fn snapshot_channel(
+    client: State<'_, Arc<TelegramClient>>,
+    channel_id: i64,
+) -> Result<ChannelSnapshot, String> {
+    take_snapshot(&client, channel_id).await
+}
+
+/// Subscriber and view trends of a channel over "week", "month", "quarter" or "year"
+#[tauri::command]
+pub async fn get_channel_growth(channel_id: i64, period: String) -> Result<ChannelGrowth, String> {
+    let (window_days, bucket_days) = period_days(&period)?;
+    let since = chrono::Utc::now().timestamp() - window_days * DAY_SECS;
+
+    let snapshots = db_stats::load_snapshots(channel_id, since)?;
+    let subscriber_change = match (snapshots.first(), snapshots.last()) {
+        (Some(first), Some(last)) => last.subscriber_count - first.subscriber_count,
+        _ => 0,
+    };
+    let subscriber_change_percent = snapshots
+        .first()
+        .filter(|first| first.subscriber_count > 0)
+        .map(|first| subscriber_change as f64 * 100.0 / first.subscriber_count as f64);
+
+    Ok(ChannelGrowth {
+        channel_id,
+        period,
+        points: aggregate_growth(&snapshots, bucket_days * DAY_SECS),
+        subscriber_change,
+        subscriber_change_percent,
+        top_posts: db_stats::load_top_posts(channel_id, since, TOP_POSTS_LIMIT)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_growth_from_snapshots() {
+        crate::db::init_test_db();
+        let day = 1_900_000_000 - 1_900_000_000 % DAY_SECS;
+        let snapshot = |taken_at, subscriber_count, avg_views| ChannelSnapshot {
+            channel_id: 7511,
+            taken_at,
+            subscriber_count,
+            avg_views,
+        };
+        let post = |message_id, views| PostViews { message_id, date: day, views };
+
+        db_stats::save_snapshot(&snapshot(day + 3600, 100, Some(40)), &[post(1, 30), post(2, 50)]).unwrap();
+        db_stats::save_snapshot(&snapshot(day + 7200, 104, None), &[]).unwrap();
+        db_stats::save_snapshot(&snapshot(day + DAY_SECS, 110, Some(60)), &[post(2, 90)]).unwrap();
+
+        let snapshots = db_stats::load_snapshots(7511, day).unwrap();
+        let points = aggregate_growth(&snapshots, DAY_SECS);
+        let summary: Vec<(i64, i64, Option<i64>)> =
+            points.iter().map(|p| (p.date, p.subscriber_count, p.avg_views)).collect();
+        assert_eq!(summary, vec![(day, 104, Some(40)), (day + DAY_SECS, 110, Some(60))]);
+
+        // Later snapshots update a post's views
+        let top = db_stats::load_top_posts(7511, day, 1).unwrap();
+        assert_eq!((top[0].message_id, top[0].views), (2, 90));
+
+        assert_eq!(period_days("month"), Ok((30, 1)));
+        assert!(period_days("decade").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_due_after_interval() {
+        crate::db::init_test_db();
+        let now = 1_900_000_000;
+        let interval = SNAPSHOT_INTERVAL_SECS as i64;
+
+        // Never snapshotted, or the last one is older than the interval: take one now
+        assert_eq!(db_stats::latest_snapshot_at(7512).unwrap(), None);
+        assert!(snapshot_due(None, now));
+        assert!(snapshot_due(Some(now - interval), now));
+
+        let snapshot = ChannelSnapshot { channel_id: 7512, taken_at: now - 60, subscriber_count: 10, avg_views: None };
+        db_stats::save_snapshot(&snapshot, &[]).unwrap();
+        let latest = db_stats::latest_snapshot_at(7512).unwrap();
+        assert_eq!(latest, Some(now - 60));
+        assert!(!snapshot_due(latest, now));
+    }
+}
@@ -1,14 +1,139 @@
-use crate::telegram::{TelegramClient, client::{Chat, Message, ChatFilters, BatchMessageRequest, BatchMessageResult}};
+use crate::telegram::{TelegramApi, TelegramClient, client::{Chat, Message, ChatFilters, BatchMessageRequest, BatchMessageResult, BotCallbackAnswer}};
+use crate::cache::{BriefingCache, ChatListCache, SummaryCache};
+use crate::commands::calls::{record_calls_from, record_calls_from_chats};
+use crate::commands::mentions::record_mentions_from;
+use crate::commands::outbox;
+use crate::commands::outreach::extract_flood_wait_seconds;
+use crate::db;
+use serde::{Deserialize, Serialize};
 use tauri::State;
 use std::sync::Arc;
+use tokio::time::{sleep, Duration};
 
+/// Chats in Telegram's dialog order, or sorted by `sort` when given
 #[tauri::command]
 pub async fn get_chats(
     client: State<'_, Arc<TelegramClient>>,
     limit: i32,
     filters: Option<ChatFilters>,
+    sort: Option<ChatSort>,
 ) -> Result<Vec<Chat>, String> {
-    client.get_chats(limit, filters).await
+    let mut chats = client.get_chats(limit, filters).await?;
+    db::apply_local_chat_state(&mut chats);
+    record_calls_from_chats(&chats);
+    if let Some(sort) = sort {
+        sort_chats(&mut chats, sort);
+    }
+    Ok(chats)
+}
+
+/// Server-side ordering for `get_chats` and `get_chats_page`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatSort {
+    /// Pinned first, then Telegram's dialog order
+    #[default]
+    Default,
+    /// Most unread messages first
+    UnreadDesc,
+    /// Most recent message first, ignoring pins
+    LastActivity,
+    /// Largest groups and channels first (DMs last)
+    MemberCount,
+    /// Chats starred in the copilot first, then the default order
+    StarredFirst,
+}
+
+/// One page of chats; `next_offset` is None on the last page
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatPage {
+    pub chats: Vec<Chat>,
+    /// Chats matching the filters (within `limit`)
+    pub total: usize,
+    pub next_offset: Option<usize>,
+}
+
+/// Sort chats in place; ties keep Telegram's dialog order
+fn sort_chats(chats: &mut [Chat], sort: ChatSort) {
+    match sort {
+        ChatSort::Default => chats.sort_by(|a, b| b.is_pinned.cmp(&a.is_pinned).then(a.order.cmp(&b.order))),
+        ChatSort::UnreadDesc => chats.sort_by(|a, b| b.unread_count.cmp(&a.unread_count).then(a.order.cmp(&b.order))),
+        ChatSort::LastActivity => chats.sort_by_key(|c| c.order),
+        ChatSort::MemberCount => chats.sort_by(|a, b| {
+            b.member_count.unwrap_or(0).cmp(&a.member_count.unwrap_or(0)).then(a.order.cmp(&b.order))
+        }),
+        ChatSort::StarredFirst => chats.sort_by(|a, b| {
+            b.is_starred.cmp(&a.is_starred).then(b.is_pinned.cmp(&a.is_pinned)).then(a.order.cmp(&b.order))
+        }),
+    }
+}
+
+/// Slice out one page, returning the offset of the next page if there is one
+fn paginate(chats: Vec<Chat>, offset: usize, page_size: usize) -> ChatPage {
+    let total = chats.len();
+    let page: Vec<Chat> = chats.into_iter().skip(offset).take(page_size).collect();
+    let end = offset + page.len();
+    ChatPage {
+        chats: page,
+        total,
+        next_offset: (end < total).then_some(end),
+    }
+}
+
+/// How long a sorted listing serves later pages before dialogs are reloaded
+const CHAT_LIST_TTL_SECS: u64 = 120;
+
+/// Which chats and in what order a page is taken from
+#[derive(Debug, Clone, Serialize)]
+struct ChatListQuery {
+    limit: i32,
+    filters: Option<ChatFilters>,
+    sort: Option<ChatSort>,
+}
+
+/// Like `get_chats`, but sorted and paginated in Rust. `limit` bounds how many
+/// dialogs are loaded; `offset`/`page_size` select the page within them.
+/// The first page reloads dialogs; later ones reuse that listing while it's fresh.
+#[tauri::command]
+pub async fn get_chats_page(
+    client: State<'_, Arc<TelegramClient>>,
+    chat_list_cache: State<'_, Arc<ChatListCache>>,
+    limit: i32,
+    filters: Option<ChatFilters>,
+    sort: Option<ChatSort>,
+    offset: Option<usize>,
+    page_size: Option<usize>,
+) -> Result<ChatPage, String> {
+    let query = ChatListQuery { limit, filters, sort };
+    load_chats_page(client.inner().as_ref(), &chat_list_cache, query, offset, page_size).await
+}
+
+async fn load_chats_page(
+    client: &dyn TelegramApi,
+    chat_list_cache: &ChatListCache,
+    query: ChatListQuery,
+    offset: Option<usize>,
+    page_size: Option<usize>,
+) -> Result<ChatPage, String> {
+    let offset = offset.unwrap_or(0);
+    let key = serde_json::to_string(&query).map_err(|e| format!("Failed to build chat list key: {}", e))?;
+    let cached = match offset {
+        0 => None,
+        _ => chat_list_cache.0.get(&key, CHAT_LIST_TTL_SECS).await,
+    };
+    let chats = match cached {
+        Some((chats, _)) => chats,
+        None => {
+            let mut chats = client.get_chats(query.limit, query.filters).await?;
+            db::apply_local_chat_state(&mut chats);
+            record_calls_from_chats(&chats);
+            sort_chats(&mut chats, query.sort.unwrap_or_default());
+            chat_list_cache.0.set(&key, chats.clone()).await;
+            chats
+        }
+    };
+    Ok(paginate(chats, offset, page_size.unwrap_or(50).max(1)))
 }
 
 #[tauri::command]
@@ -16,7 +141,25 @@ pub async fn get_chat(
     client: State<'_, Arc<TelegramClient>>,
     chat_id: i64,
 ) -> Result<Option<Chat>, String> {
-    client.get_chat(chat_id).await
+    let mut chat = client.get_chat(chat_id).await?;
+    db::apply_local_chat_state(chat.as_mut_slice());
+    Ok(chat)
+}
+
+#[tauri::command]
+pub async fn update_chat_notes(chat_id: i64, notes: String) -> Result<(), String> {
+    db::chat_notes::update_chat_notes(chat_id, &notes)
+}
+
+#[tauri::command]
+pub async fn set_chat_starred(chat_id: i64, starred: bool) -> Result<(), String> {
+    db::stars::set_starred(chat_id, starred)
+}
+
+/// Give a chat a local nickname; None or an empty alias restores the Telegram title
+#[tauri::command]
+pub async fn set_chat_alias(chat_id: i64, alias: Option<String>) -> Result<(), String> {
+    db::aliases::set_alias(chat_id, alias.as_deref())
 }
 
 #[tauri::command]
@@ -26,16 +169,56 @@ pub async fn get_chat_messages(
     limit: i32,
     from_message_id: Option<i64>,
 ) -> Result<Vec<Message>, String> {
-    client.get_chat_messages(chat_id, limit, from_message_id).await
+    load_chat_messages(client.inner().as_ref(), chat_id, limit, from_message_id).await
+}
+
+/// Fetch a page of history (archived as it arrives), recording any mentions of
+/// the user and calls in it
+async fn load_chat_messages(
+    client: &dyn TelegramApi,
+    chat_id: i64,
+    limit: i32,
+    from_message_id: Option<i64>,
+) -> Result<Vec<Message>, String> {
+    let messages = db::message_sync::fetch_messages(client, chat_id, limit, from_message_id).await?;
+    record_mentions_from(client, chat_id, &messages).await;
+    record_calls_from(client, chat_id, &messages).await;
+    Ok(messages)
+}
+
+/// Drop the cached briefings and summaries covering a chat, e.g. once a reply
+/// has changed what it needs
+pub(crate) async fn invalidate_chat_analysis(
+    briefing_cache: &BriefingCache,
+    summary_cache: &SummaryCache,
+    chat_id: i64,
+) {
+    briefing_cache.invalidate_chats(&[chat_id]).await;
+    summary_cache.invalidate_chats(&[chat_id]).await;
 }
 
 #[tauri::command]
 pub async fn send_message(
     client: State<'_, Arc<TelegramClient>>,
+    briefing_cache: State<'_, Arc<BriefingCache>>,
+    summary_cache: State<'_, Arc<SummaryCache>>,
     chat_id: i64,
     text: String,
 ) -> Result<Message, String> {
-    client.send_message(chat_id, &text).await
+    let message = outbox::send_or_queue_offline(client.inner().as_ref(), chat_id, &text).await?;
+    invalidate_chat_analysis(&briefing_cache, &summary_cache, chat_id).await;
+    Ok(message)
+}
+
+/// Press a bot's inline keyboard button; `data` is the button's payload as sent with the message
+#[tauri::command]
+pub async fn click_inline_button(
+    client: State<'_, Arc<TelegramClient>>,
+    chat_id: i64,
+    message_id: i64,
+    data: String,
+) -> Result<BotCallbackAnswer, String> {
+    client.click_inline_button(chat_id, message_id, &data).await
 }
 
 #[tauri::command]
@@ -43,7 +226,22 @@ pub async fn get_batch_messages(
     client: State<'_, Arc<TelegramClient>>,
     requests: Vec<BatchMessageRequest>,
 ) -> Result<Vec<BatchMessageResult>, String> {
-    client.get_batch_messages(requests).await
+    let results = db::message_sync::batch_messages(client.inner().as_ref(), requests).await;
+    for result in &results {
+        record_mentions_from(client.inner().as_ref(), result.chat_id, &result.messages).await;
+        record_calls_from(client.inner().as_ref(), result.chat_id, &result.messages).await;
+    }
+    Ok(results)
+}
+
+/// Mark a chat as read in Telegram itself, up to `up_to_message_id` or entirely
+#[tauri::command]
+pub async fn mark_chat_read(
+    client: State<'_, Arc<TelegramClient>>,
+    chat_id: i64,
+    up_to_message_id: Option<i64>,
+) -> Result<(), String> {
+    client.mark_chat_read(chat_id, up_to_message_id).await
 }
 
 #[tauri::command]
@@ -53,3 +251,240 @@ pub async fn invalidate_chat_cache(
     client.invalidate_cache().await;
     Ok(())
 }
+
+/// Forget what is cached about one chat: its dialog is fetched again, its recent
+/// messages are no longer served from the archive, and cached briefings and
+/// summaries covering it are dropped. Returns the refreshed chat.
+#[tauri::command]
+pub async fn invalidate_chat(
+    client: State<'_, Arc<TelegramClient>>,
+    briefing_cache: State<'_, Arc<BriefingCache>>,
+    summary_cache: State<'_, Arc<SummaryCache>>,
+    chat_id: i64,
+) -> Result<Option<Chat>, String> {
+    db::messages::mark_stale(chat_id)?;
+    invalidate_chat_analysis(&briefing_cache, &summary_cache, chat_id).await;
+
+    let mut chat = client.refresh_chat(chat_id).await?;
+    db::apply_local_chat_state(chat.as_mut_slice());
+    Ok(chat)
+}
+
+/// Messages deleted per request (Telegram's limit)
+const DELETE_BATCH_SIZE: usize = 100;
+
+/// Longest FLOOD_WAIT we sit out during a cleanup before stopping
+const MAX_DELETE_FLOOD_WAIT_SECS: u64 = 120;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteMessagesResult {
+    /// Own messages matching the request
+    pub found: usize,
+    pub deleted: usize,
+    /// Set when the cleanup stopped before finishing (e.g. a long FLOOD_WAIT)
+    pub stopped_reason: Option<String>,
+}
+
+/// Delete our own messages in a chat (for everyone), optionally only those sent
+/// before `before_date` (unix seconds). With `dry_run`, only counts them.
+#[tauri::command]
+pub async fn delete_my_messages(
+    client: State<'_, Arc<TelegramClient>>,
+    chat_id: i64,
+    before_date: Option<i64>,
+    dry_run: Option<bool>,
+) -> Result<DeleteMessagesResult, String> {
+    let ids = client.get_own_message_ids(chat_id, before_date).await?;
+    if dry_run.unwrap_or(false) {
+        return Ok(DeleteMessagesResult {
+            found: ids.len(),
+            deleted: 0,
+            stopped_reason: None,
+        });
+    }
+
+    let result = delete_in_batches(client.inner().as_ref(), chat_id, &ids).await?;
+    log::info!("Deleted {} of {} own messages in chat {}", result.deleted, result.found, chat_id);
+    Ok(result)
+}
+
+/// Delete messages a batch at a time, sitting out short FLOOD_WAITs. Once some
+/// were deleted, an error stops the cleanup instead of failing it.
+async fn delete_in_batches(client: &dyn TelegramApi, chat_id: i64, ids: &[i32]) -> Result<DeleteMessagesResult, String> {
+    let mut result = DeleteMessagesResult {
+        found: ids.len(),
+        deleted: 0,
+        stopped_reason: None,
+    };
+
+    for (index, batch) in ids.chunks(DELETE_BATCH_SIZE).enumerate() {
+        if index > 0 {
+            sleep(Duration::from_secs(1)).await;
+        }
+
+        let mut attempt = client.delete_messages(chat_id, batch).await;
+        if let Err(e) = &attempt {
+            if e.to_lowercase().contains("flood") {
+                let wait_secs = extract_flood_wait_seconds(e).unwrap_or(60);
+                if wait_secs > MAX_DELETE_FLOOD_WAIT_SECS {
+                    result.stopped_reason = Some(format!("Telegram asked to wait {} seconds", wait_secs));
+                    break;
+                }
+                log::warn!("FLOOD_WAIT while deleting messages, waiting {} seconds", wait_secs);
+                sleep(Duration::from_secs(wait_secs)).await;
+                attempt = client.delete_messages(chat_id, batch).await;
+            }
+        }
+
+        match attempt {
+            Ok(_) => result.deleted += batch.len(),
+            Err(e) if result.deleted > 0 => {
+                result.stopped_reason = Some(e);
+                break;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chat(id: i64, order: i64, unread_count: i32, member_count: Option<i32>, is_pinned: bool) -> Chat {
+        Chat {
+            id,
+            chat_type: "group".to_string(),
+            title: format!("Chat {}", id),
+            unread_count,
+            is_pinned,
+            order,
+            photo: None,
+            last_message: None,
+            member_count,
+            is_muted: false,
+            is_archived: false,
+            is_bot: false,
+            is_contact: false,
+            original_title: None,
+            is_starred: false,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_sort_and_paginate_chats() {
+        let mut chats = vec![
+            chat(1, -300, 0, None, false),
+            chat(2, -100, 5, Some(40), true),
+            chat(3, -200, 9, Some(900), false),
+            chat(4, -50, 5, None, false),
+        ];
+        let ids = |chats: &[Chat]| chats.iter().map(|c| c.id).collect::<Vec<_>>();
+
+        sort_chats(&mut chats, ChatSort::LastActivity);
+        assert_eq!(ids(&chats), vec![1, 3, 2, 4]);
+        sort_chats(&mut chats, ChatSort::UnreadDesc);
+        assert_eq!(ids(&chats), vec![3, 2, 4, 1]);
+        sort_chats(&mut chats, ChatSort::MemberCount);
+        assert_eq!(ids(&chats), vec![3, 2, 1, 4]);
+        sort_chats(&mut chats, ChatSort::Default);
+        assert_eq!(ids(&chats), vec![2, 1, 3, 4]);
+
+        let page = paginate(chats.clone(), 0, 3);
+        assert_eq!((ids(&page.chats), page.total, page.next_offset), (vec![2, 1, 3], 4, Some(3)));
+        let page = paginate(chats.clone(), 3, 3);
+        assert_eq!((ids(&page.chats), page.next_offset), (vec![4], None));
+        assert!(paginate(chats.clone(), 10, 3).chats.is_empty());
+
+        // A starred chat goes ahead of pinned ones
+        chats[3].is_starred = true;
+        sort_chats(&mut chats, ChatSort::StarredFirst);
+        assert_eq!(ids(&chats), vec![4, 2, 1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_chats_page_against_fake_backend() {
+        use crate::telegram::fake::{self, FakeTelegram};
+
+        let telegram = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(fake::chat(10, "private", "Alice", 2, 1))
+            .with_chat(fake::chat(11, "group", "Team", 7, 2))
+            .with_chat(fake::chat(12, "channel", "News", 40, 3))
+            .with_chat(fake::chat(13, "group", "Quiet", 0, 4));
+        let filters = ChatFilters { include_channels: false, include_unread_only: true, ..fake_filters() };
+
+        let query = ChatListQuery { limit: 100, filters: Some(filters), sort: Some(ChatSort::UnreadDesc) };
+        let cache = ChatListCache::new();
+
+        let page = load_chats_page(&telegram, &cache, query.clone(), None, Some(1)).await.unwrap();
+        assert_eq!(page.chats.iter().map(|c| c.id).collect::<Vec<_>>(), vec![11]);
+        assert_eq!((page.total, page.next_offset), (2, Some(1)));
+
+        // The next page comes from the first page's listing, not a new dialog load
+        let no_dialogs = FakeTelegram::new(fake::user(1, "Me", ""));
+        let page = load_chats_page(&no_dialogs, &cache, query.clone(), Some(1), Some(1)).await.unwrap();
+        assert_eq!(page.chats.iter().map(|c| c.id).collect::<Vec<_>>(), vec![10]);
+        assert_eq!(page.next_offset, None);
+
+        // Starting over reloads
+        let page = load_chats_page(&no_dialogs, &cache, query, None, Some(1)).await.unwrap();
+        assert_eq!(page.total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_history_pages_record_mentions() {
+        use crate::telegram::fake::{self, FakeTelegram};
+
+        crate::db::init_test_db();
+        let bob = fake::user(2, "Bob", "");
+        let mut mention = fake::text_message(3, 77, &bob, "@me can you check?", 300);
+        mention.mentions_me = true;
+        let telegram = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(fake::chat(77, "group", "Team", 3, 1))
+            .with_messages(
+                77,
+                vec![fake::text_message(1, 77, &bob, "hi", 100), fake::text_message(2, 77, &bob, "ping", 200), mention],
+            );
+
+        let latest = load_chat_messages(&telegram, 77, 2, None).await.unwrap();
+        assert_eq!(latest.iter().map(|m| m.id).collect::<Vec<_>>(), vec![2, 3]);
+        let older = load_chat_messages(&telegram, 77, 2, Some(2)).await.unwrap();
+        assert_eq!(older.iter().map(|m| m.id).collect::<Vec<_>>(), vec![1]);
+
+        let mentions = crate::db::mentions::load_mentions(false, 100).unwrap();
+        assert!(mentions.iter().any(|m| m.chat_id == 77 && m.message_id == 3 && m.chat_title == "Team"));
+    }
+
+    /// Filters with everything but bots, archived and muted chats included
+    fn fake_filters() -> ChatFilters {
+        serde_json::from_str("{}").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_delete_in_batches() {
+        use crate::telegram::fake::{self, FakeTelegram};
+
+        let me = fake::user(1, "Me", "");
+        let history: Vec<Message> = (1..=150).map(|id| fake::text_message(id, 78, &me, "old", id)).collect();
+        let telegram = FakeTelegram::new(me.clone()).with_messages(78, history);
+        let ids: Vec<i32> = (1..=150).collect();
+
+        // Two batches, a second apart
+        let result = delete_in_batches(&telegram, 78, &ids).await.unwrap();
+        assert_eq!((result.found, result.deleted, result.stopped_reason), (150, 150, None));
+        assert!(telegram.get_chat_messages(78, 200, None).await.unwrap().is_empty());
+
+        // A FLOOD_WAIT too long to sit out stops the cleanup
+        let flooded = FakeTelegram::new(me.clone()).with_delete_error(79, "FLOOD_WAIT_600");
+        let result = delete_in_batches(&flooded, 79, &[1, 2]).await.unwrap();
+        assert_eq!(result.deleted, 0);
+        assert_eq!(result.stopped_reason.as_deref(), Some("Telegram asked to wait 600 seconds"));
+
+        // Any other failure before anything was deleted fails the cleanup
+        let forbidden = FakeTelegram::new(me).with_delete_error(79, "MESSAGE_DELETE_FORBIDDEN");
+        assert_eq!(delete_in_batches(&forbidden, 79, &[1]).await.unwrap_err(), "MESSAGE_DELETE_FORBIDDEN");
+    }
+}
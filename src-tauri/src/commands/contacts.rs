@@ -1,7 +1,15 @@
+use crate::ai::context::message_text_for_ai;
+use crate::ai::types::DraftMessage;
+use crate::ai::LLMClient;
 use crate::cache::{format_cache_age, ContactsCache};
+use crate::ai::types::TagSuggestion;
+use crate::commands::ai::{contact_context, draft_message, suggest_tags};
+use crate::db;
 use crate::db::contacts as db_contacts;
-use crate::telegram::client::ChatFilters;
-use crate::telegram::TelegramClient;
+use crate::commands::offboard::UserAccessHashCache;
+use crate::telegram::client::{BatchMessageRequest, Birthday, Chat, ChatFilters, Message, UserProfile};
+use crate::telegram::{TelegramApi, TelegramClient};
+use chrono::{Datelike, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -20,6 +28,14 @@ pub struct ContactWithMetadata {
     pub last_contact_date: Option<i64>,
     pub days_since_contact: Option<i64>,
     pub unread_count: Option<i32>,
+    #[serde(default)]
+    pub birthday: Option<Birthday>,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub is_premium: bool,
+    #[serde(default)]
+    pub is_verified: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,34 +48,60 @@ pub struct ContactsResponse {
 
 const CONTACTS_CACHE_KEY: &str = "contacts:all";
 
-#[tauri::command]
-pub async fn get_contacts(
-    client: State<'_, Arc<TelegramClient>>,
-    cache: State<'_, Arc<ContactsCache>>,
-    force_refresh: Option<bool>,
-    ttl_minutes: Option<i64>,
-) -> Result<ContactsResponse, String> {
-    let force_refresh = force_refresh.unwrap_or(false);
-    let ttl_minutes = ttl_minutes.unwrap_or(10080); // Default 7 days
-    let ttl_secs = (ttl_minutes * 60) as u64;
+/// Birthdays are re-checked with users.getFullUser at most this often
+const BIRTHDAY_REFRESH_DAYS: i64 = 7;
 
-    log::info!(
-        "Getting contacts (force_refresh: {}, ttl: {}m)",
-        force_refresh,
-        ttl_minutes
-    );
+/// Greetings are only drafted for birthdays this close (today or tomorrow)
+const GREETING_DRAFT_DAYS: i64 = 1;
 
-    // Check cache unless force refresh
-    if !force_refresh {
-        if let Some((cached_contacts, age_secs)) = cache.0.get(CONTACTS_CACHE_KEY, ttl_secs).await {
-            log::info!("Returning cached contacts (age: {}s)", age_secs);
-            return Ok(ContactsResponse {
-                contacts: cached_contacts,
-                cached: true,
-                cache_age: Some(format_cache_age(age_secs)),
-            });
-        }
-    }
+/// DM messages included in a contact timeline
+const TIMELINE_MESSAGE_LIMIT: i32 = 100;
+
+/// Dialogs looked through for archived chats when collecting a person's activity
+const ACTIVITY_CHAT_LIMIT: i32 = 200;
+
+/// Recent messages scanned per chat for a person's activity
+const ACTIVITY_MESSAGES_PER_CHAT: i32 = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEvent {
+    /// "message_in", "message_out", "outreach", or a logged event kind
+    /// ("tag_added", "tag_removed", "notes_updated", "removed_from_group", "welcomed_to_group", "pipeline_stage")
+    pub kind: String,
+    pub timestamp: i64,
+    pub text: String,
+}
+
+/// A message someone sent, with the chat it was sent in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonActivityItem {
+    pub chat_id: i64,
+    pub chat_title: String,
+    pub message: Message,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpcomingBirthday {
+    pub user_id: i64,
+    pub name: String,
+    pub username: Option<String>,
+    pub birthday: Birthday,
+    /// Next occurrence as YYYY-MM-DD
+    pub date: String,
+    pub days_until: i64,
+    /// Age they turn, when the birth year is known
+    pub turning_age: Option<i32>,
+    pub greeting_draft: Option<String>,
+}
+
+/// Fetch contacts and enrich them with tags, notes and last-contact info.
+/// Shared by the `get_contacts` command and the MCP server.
+pub async fn load_contacts_with_metadata(
+    client: &TelegramClient,
+) -> Result<Vec<ContactWithMetadata>, String> {
     let users = client.get_contacts().await?;
     let now = chrono::Utc::now().timestamp();
 
@@ -92,6 +134,11 @@ pub async fn get_contacts(
     for user in users {
         let tags = db_contacts::get_contact_tags(user.id).unwrap_or_default();
         let notes = db_contacts::get_contact_notes(user.id).unwrap_or_default();
+        let profile = db_contacts::get_contact_profile(user.id).unwrap_or(None);
+        let birthday = match &profile {
+            Some(p) => p.birthday,
+            None => db_contacts::get_contact_birthday(user.id).unwrap_or(None),
+        };
 
         // Get chat data (last message date and unread count)
         let chat_data = chat_data_map.get(&user.id);
@@ -110,16 +157,53 @@ pub async fn get_contacts(
             user_id: user.id,
             first_name: user.first_name,
             last_name: user.last_name,
-            username: user.username,
+            username: user.username.or_else(|| profile.as_ref().and_then(|p| p.username.clone())),
             phone_number: user.phone_number,
             tags,
             notes,
             last_contact_date,
             days_since_contact,
             unread_count,
+            birthday,
+            bio: profile.as_ref().and_then(|p| p.bio.clone()),
+            is_premium: profile.as_ref().map(|p| p.is_premium).unwrap_or(false),
+            is_verified: profile.as_ref().map(|p| p.is_verified).unwrap_or(false),
         });
     }
 
+    Ok(contacts)
+}
+
+#[tauri::command]
+pub async fn get_contacts(
+    client: State<'_, Arc<TelegramClient>>,
+    cache: State<'_, Arc<ContactsCache>>,
+    force_refresh: Option<bool>,
+    ttl_minutes: Option<i64>,
+) -> Result<ContactsResponse, String> {
+    let force_refresh = force_refresh.unwrap_or(false);
+    let ttl_minutes = ttl_minutes.unwrap_or(10080); // Default 7 days
+    let ttl_secs = (ttl_minutes * 60) as u64;
+
+    log::info!(
+        "Getting contacts (force_refresh: {}, ttl: {}m)",
+        force_refresh,
+        ttl_minutes
+    );
+
+    // Check cache unless force refresh
+    if !force_refresh {
+        if let Some((cached_contacts, age_secs)) = cache.0.get(CONTACTS_CACHE_KEY, ttl_secs).await {
+            log::info!("Returning cached contacts (age: {}s)", age_secs);
+            return Ok(ContactsResponse {
+                contacts: cached_contacts,
+                cached: true,
+                cache_age: Some(format_cache_age(age_secs)),
+            });
+        }
+    }
+    let contacts = load_contacts_with_metadata(&client).await?;
+
     // Store in cache
     cache.0.set(CONTACTS_CACHE_KEY, contacts.clone()).await;
 
@@ -130,6 +214,13 @@ pub async fn get_contacts(
     })
 }
 
+/// Timeline logging is best-effort; a failure shouldn't fail the edit itself
+pub(crate) fn log_event(user_id: i64, kind: &str, detail: &str) {
+    if let Err(e) = db_contacts::log_contact_event(user_id, kind, detail) {
+        log::warn!("Failed to log {} event for {}: {}", kind, user_id, e);
+    }
+}
+
 #[tauri::command]
 pub async fn add_contact_tag(
     cache: State<'_, Arc<ContactsCache>>,
@@ -139,6 +230,7 @@ pub async fn add_contact_tag(
     let result = db_contacts::add_contact_tag(user_id, &tag);
     if result.is_ok() {
         cache.0.invalidate(CONTACTS_CACHE_KEY).await;
+        log_event(user_id, db_contacts::EVENT_TAG_ADDED, &tag);
     }
     result
 }
@@ -152,6 +244,7 @@ pub async fn remove_contact_tag(
     let result = db_contacts::remove_contact_tag(user_id, &tag);
     if result.is_ok() {
         cache.0.invalidate(CONTACTS_CACHE_KEY).await;
+        log_event(user_id, db_contacts::EVENT_TAG_REMOVED, &tag);
     }
     result
 }
@@ -165,6 +258,7 @@ pub async fn update_contact_notes(
     let result = db_contacts::update_contact_notes(user_id, &notes);
     if result.is_ok() {
         cache.0.invalidate(CONTACTS_CACHE_KEY).await;
+        log_event(user_id, db_contacts::EVENT_NOTES_UPDATED, &notes);
     }
     result
 }
@@ -173,3 +267,537 @@ pub async fn update_contact_notes(
 pub async fn get_all_tags() -> Result<Vec<(String, i32)>, String> {
     db_contacts::get_all_tags()
 }
+
+/// Pull bios, usernames, premium/verified flags and birthdays for the given users
+/// via users.getFullUser and store them with the contact metadata.
+#[tauri::command]
+pub async fn refresh_contact_profiles(
+    client: State<'_, Arc<TelegramClient>>,
+    cache: State<'_, Arc<ContactsCache>>,
+    user_hash_cache: State<'_, Arc<UserAccessHashCache>>,
+    user_ids: Vec<i64>,
+) -> Result<Vec<UserProfile>, String> {
+    log::info!("Refreshing profiles for {} users", user_ids.len());
+
+    let mut profiles = Vec::new();
+    let mut populated = false;
+
+    for user_id in user_ids {
+        let mut access_hash = user_hash_cache.get(user_id).await;
+        if access_hash.is_none() && !populated {
+            user_hash_cache.populate_from_contacts(&client).await?;
+            populated = true;
+            access_hash = user_hash_cache.get(user_id).await;
+        }
+        let Some(access_hash) = access_hash else {
+            log::warn!("No access hash for user {}, skipping profile refresh", user_id);
+            continue;
+        };
+
+        match client.get_full_user(user_id, access_hash).await {
+            Ok(profile) => {
+                db_contacts::save_contact_profile(&profile)?;
+                profiles.push(profile);
+            }
+            Err(e) if e.to_lowercase().contains("flood") => {
+                log::warn!("FLOOD_WAIT while refreshing profiles, stopping after {}: {}", profiles.len(), e);
+                break;
+            }
+            Err(e) => log::warn!("Failed to refresh profile for {}: {}", user_id, e),
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+
+    if !profiles.is_empty() {
+        cache.0.invalidate(CONTACTS_CACHE_KEY).await;
+    }
+    Ok(profiles)
+}
+
+/// Contacts whose bio mentions any of the keywords, for outreach segmentation
+#[tauri::command]
+pub async fn find_contacts_by_bio(keywords: Vec<String>) -> Result<Vec<i64>, String> {
+    db_contacts::find_user_ids_by_bio(&keywords)
+}
+
+/// Keep suggestions that name a vocabulary tag (case-insensitively, mapped to the
+/// vocabulary's spelling) the contact doesn't already have, once each.
+fn filter_tag_suggestions(
+    suggestions: Vec<TagSuggestion>,
+    vocabulary: &[String],
+    current_tags: &[String],
+) -> Vec<TagSuggestion> {
+    let mut accepted: Vec<TagSuggestion> = vec![];
+    for suggestion in suggestions {
+        let Some(tag) = vocabulary.iter().find(|t| t.eq_ignore_ascii_case(suggestion.tag.trim())) else {
+            continue;
+        };
+        if current_tags.contains(tag) || accepted.iter().any(|s| &s.tag == tag) {
+            continue;
+        }
+        accepted.push(TagSuggestion {
+            tag: tag.clone(),
+            reason: suggestion.reason,
+        });
+    }
+    accepted
+}
+
+/// Propose tags for a contact from the existing tag vocabulary, based on their bio
+/// and recent DMs. Nothing is written; accepted tags go through `apply_contact_tags`.
+#[tauri::command]
+pub async fn suggest_contact_tags(
+    client: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+    user_id: i64,
+) -> Result<Vec<TagSuggestion>, String> {
+    if !llm.is_configured().await {
+        return Err("LLM not configured".to_string());
+    }
+
+    let vocabulary: Vec<String> = db_contacts::get_all_tags()?.into_iter().map(|(tag, _)| tag).collect();
+    if vocabulary.is_empty() {
+        return Ok(vec![]);
+    }
+    let current_tags = db_contacts::get_contact_tags(user_id)?;
+
+    let name = client
+        .get_contacts()
+        .await?
+        .into_iter()
+        .find(|u| u.id == user_id)
+        .map(|u| format!("{} {}", u.first_name, u.last_name).trim().to_string())
+        .unwrap_or_else(|| user_id.to_string());
+    let bio = db_contacts::get_contact_profile(user_id)?.and_then(|p| p.bio);
+
+    // Private chat id equals the user id; a bio alone is enough to go on
+    let messages: Vec<DraftMessage> = client
+        .get_chat_messages(user_id, 30, None)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|m| DraftMessage {
+            sender_name: m.sender_name.clone(),
+            text: message_text_for_ai(&m.content),
+            is_outgoing: m.is_outgoing,
+        })
+        .collect();
+
+    let suggestions = suggest_tags(&llm, &name, bio.as_deref(), &current_tags, &vocabulary, &messages).await?;
+    Ok(filter_tag_suggestions(suggestions, &vocabulary, &current_tags))
+}
+
+/// Write the tag suggestions the user accepted. Tags outside the vocabulary are rejected.
+#[tauri::command]
+pub async fn apply_contact_tags(
+    cache: State<'_, Arc<ContactsCache>>,
+    user_id: i64,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let vocabulary: Vec<String> = db_contacts::get_all_tags()?.into_iter().map(|(tag, _)| tag).collect();
+    if let Some(unknown) = tags.iter().find(|t| !vocabulary.contains(t)) {
+        return Err(format!("Unknown tag: {}", unknown));
+    }
+
+    for tag in &tags {
+        db_contacts::add_contact_tag(user_id, tag)?;
+        log_event(user_id, db_contacts::EVENT_TAG_ADDED, tag);
+    }
+    cache.0.invalidate(CONTACTS_CACHE_KEY).await;
+    Ok(())
+}
+
+/// Every interaction with a contact in one chronological feed: recent DMs,
+/// outreach sends, and logged tag/note edits, offboarding and pipeline moves.
+#[tauri::command]
+pub async fn get_contact_timeline(
+    client: State<'_, Arc<TelegramClient>>,
+    user_id: i64,
+    message_limit: Option<i32>,
+) -> Result<Vec<TimelineEvent>, String> {
+    log::info!("Building timeline for contact {}", user_id);
+    contact_timeline(client.inner().as_ref(), user_id, message_limit.unwrap_or(TIMELINE_MESSAGE_LIMIT)).await
+}
+
+async fn contact_timeline(client: &dyn TelegramApi, user_id: i64, limit: i32) -> Result<Vec<TimelineEvent>, String> {
+    let mut events: Vec<TimelineEvent> = db_contacts::get_contact_events(user_id)?
+        .into_iter()
+        .map(|(kind, text, timestamp)| TimelineEvent { kind, timestamp, text })
+        .collect();
+
+    let sent = db::with_db(|conn| db::outreach::load_sent_to_user(conn, user_id))?;
+    events.extend(sent.into_iter().map(|(template, timestamp)| TimelineEvent {
+        kind: "outreach".to_string(),
+        timestamp,
+        text: template,
+    }));
+
+    // Private chat id equals the user id; a contact we never messaged has no DM history
+    match client.get_chat_messages(user_id, limit, None).await {
+        Ok(messages) => events.extend(messages.iter().map(|m| TimelineEvent {
+            kind: if m.is_outgoing { "message_out" } else { "message_in" }.to_string(),
+            timestamp: m.date,
+            text: message_text_for_ai(&m.content),
+        })),
+        Err(e) => log::warn!("Failed to load DM history for timeline of {}: {}", user_id, e),
+    }
+
+    events.sort_by_key(|e| e.timestamp);
+    Ok(events)
+}
+
+/// Archived chats `user_id` can have written in: their DM and any group
+fn archived_activity_chats(user_id: i64, chats: Vec<Chat>) -> Vec<Chat> {
+    chats
+        .into_iter()
+        .filter(|c| c.is_archived && (c.chat_type == "group" || c.id == user_id))
+        .collect()
+}
+
+/// Messages from `user_id` sent since `since` in the given (chat_id, title, messages) chats, newest first
+fn person_activity(user_id: i64, since: i64, chats: Vec<(i64, String, Vec<Message>)>) -> Vec<PersonActivityItem> {
+    let mut items: Vec<PersonActivityItem> = chats
+        .into_iter()
+        .flat_map(|(chat_id, chat_title, messages)| {
+            messages
+                .into_iter()
+                .filter(|m| m.sender_id == user_id && !m.is_outgoing && m.date >= since)
+                .map(move |message| PersonActivityItem {
+                    chat_id,
+                    chat_title: chat_title.clone(),
+                    message,
+                })
+        })
+        .collect();
+    items.sort_by_key(|i| std::cmp::Reverse(i.message.date));
+    items
+}
+
+/// Everything a person said in the last `days` days across the archived chats
+/// (their DM and groups in the archive folder), newest first. Only the latest
+/// messages of each chat are scanned, so very busy groups may be cut short.
+#[tauri::command]
+pub async fn get_person_activity(
+    client: State<'_, Arc<TelegramClient>>,
+    user_id: i64,
+    days: i64,
+) -> Result<Vec<PersonActivityItem>, String> {
+    let days = days.max(1);
+    let since = chrono::Utc::now().timestamp() - days * 86400;
+    log::info!("Collecting activity of {} over the last {}d", user_id, days);
+
+    let filters = ChatFilters {
+        include_private_chats: true,
+        include_non_contacts: true,
+        include_groups: true,
+        include_channels: false,
+        include_archived: true,
+        include_muted: true,
+        ..Default::default()
+    };
+    let chats: Vec<(i64, String)> =
+        archived_activity_chats(user_id, client.get_chats(ACTIVITY_CHAT_LIMIT, Some(filters)).await?)
+            .into_iter()
+            .map(|c| (c.id, c.title))
+            .collect();
+    if chats.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let requests = chats
+        .iter()
+        .map(|(chat_id, _)| BatchMessageRequest {
+            chat_id: *chat_id,
+            limit: ACTIVITY_MESSAGES_PER_CHAT,
+        })
+        .collect();
+    let results = client.get_batch_messages(requests).await?;
+
+    let chats = chats
+        .into_iter()
+        .map(|(chat_id, title)| {
+            let messages = match results.iter().find(|r| r.chat_id == chat_id) {
+                Some(result) => {
+                    if let Some(error) = &result.error {
+                        log::warn!("Failed to load messages of {} for person activity: {}", chat_id, error);
+                    }
+                    result.messages.clone()
+                }
+                None => vec![],
+            };
+            (chat_id, title, messages)
+        })
+        .collect();
+    Ok(person_activity(user_id, since, chats))
+}
+
+/// Next occurrence of a birthday on or after `today` (Feb 29 falls back to Feb 28 in common years)
+fn next_birthday(today: NaiveDate, birthday: &Birthday) -> Option<NaiveDate> {
+    let on_year = |year: i32| {
+        NaiveDate::from_ymd_opt(year, birthday.month, birthday.day).or_else(|| {
+            (birthday.month == 2 && birthday.day == 29)
+                .then(|| NaiveDate::from_ymd_opt(year, 2, 28))
+                .flatten()
+        })
+    };
+    let this_year = on_year(today.year())?;
+    if this_year >= today {
+        Some(this_year)
+    } else {
+        on_year(today.year() + 1)
+    }
+}
+
+/// Re-check contact birthdays (and the rest of the profile) that haven't been fetched recently.
+/// Stops early (keeping what it has) when Telegram asks us to slow down.
+async fn refresh_birthdays(client: &TelegramClient) -> Result<usize, String> {
+    let fresh_since = chrono::Utc::now().timestamp() - BIRTHDAY_REFRESH_DAYS * 86400;
+    let fresh: std::collections::HashSet<i64> =
+        db_contacts::get_birthday_checked_since(fresh_since)?.into_iter().collect();
+
+    let stale: Vec<(i64, i64)> = client
+        .get_contacts_with_access_hash()
+        .await?
+        .into_iter()
+        .filter(|(user_id, _)| !fresh.contains(user_id))
+        .collect();
+
+    let mut refreshed = 0;
+    for (user_id, access_hash) in stale {
+        match client.get_full_user(user_id, access_hash).await {
+            Ok(profile) => {
+                db_contacts::save_contact_profile(&profile)?;
+                refreshed += 1;
+            }
+            Err(e) if e.to_lowercase().contains("flood") => {
+                log::warn!("FLOOD_WAIT while refreshing birthdays, stopping after {}: {}", refreshed, e);
+                break;
+            }
+            Err(e) => log::warn!("Failed to fetch birthday for {}: {}", user_id, e),
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+
+    log::info!("Refreshed {} contact birthdays", refreshed);
+    Ok(refreshed)
+}
+
+/// Draft a birthday greeting from the recent DM history
+async fn draft_birthday_greeting(
+    client: &TelegramClient,
+    llm: &LLMClient,
+    user_id: i64,
+    name: &str,
+    days_until: i64,
+) -> Result<String, String> {
+    // Private chat id equals the user id; no history is fine for a greeting
+    let messages: Vec<DraftMessage> = client
+        .get_chat_messages(user_id, 15, None)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|m| DraftMessage {
+            sender_name: m.sender_name.clone(),
+            text: message_text_for_ai(&m.content),
+            is_outgoing: m.is_outgoing,
+        })
+        .collect();
+
+    let when = if days_until == 0 { "today" } else { "tomorrow" };
+    let goal = format!(
+        "It is {}'s birthday {}. Write a short, warm birthday greeting to send them.",
+        name, when
+    );
+    draft_message(llm, name, &messages, Some(&goal), contact_context(user_id).as_deref()).await
+}
+
+/// Contacts with a birthday within the next `days` days, soonest first.
+/// Reads cached birthdays unless `refresh` is set, which first re-fetches stale
+/// ones from Telegram profiles (one request per contact, so only on demand);
+/// `draft_greetings` drafts a greeting for birthdays today or tomorrow.
+#[tauri::command]
+pub async fn get_upcoming_birthdays(
+    client: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+    days: i64,
+    refresh: Option<bool>,
+    draft_greetings: Option<bool>,
+) -> Result<Vec<UpcomingBirthday>, String> {
+    log::info!("Getting upcoming birthdays (days: {})", days);
+
+    if refresh.unwrap_or(false) {
+        if let Err(e) = refresh_birthdays(&client).await {
+            log::warn!("Failed to refresh birthdays: {}", e);
+        }
+    }
+
+    let users: HashMap<i64, _> = client
+        .get_contacts()
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u))
+        .collect();
+    let today = chrono::Local::now().date_naive();
+
+    let mut upcoming: Vec<UpcomingBirthday> = db_contacts::get_all_birthdays()?
+        .into_iter()
+        .filter_map(|(user_id, birthday)| {
+            let user = users.get(&user_id)?;
+            let next = next_birthday(today, &birthday)?;
+            let days_until = (next - today).num_days();
+            if days_until > days {
+                return None;
+            }
+            Some(UpcomingBirthday {
+                user_id,
+                name: format!("{} {}", user.first_name, user.last_name).trim().to_string(),
+                username: user.username.clone(),
+                birthday,
+                date: next.format("%Y-%m-%d").to_string(),
+                days_until,
+                turning_age: birthday.year.map(|y| next.year() - y),
+                greeting_draft: None,
+            })
+        })
+        .collect();
+
+    upcoming.sort_by_key(|b| b.days_until);
+
+    if draft_greetings.unwrap_or(false) && llm.is_configured().await {
+        for item in upcoming.iter_mut().filter(|b| b.days_until <= GREETING_DRAFT_DAYS) {
+            match draft_birthday_greeting(&client, &llm, item.user_id, &item.name, item.days_until).await {
+                Ok(draft) => item.greeting_draft = Some(draft),
+                Err(e) => log::warn!("Failed to draft greeting for {}: {}", item.user_id, e),
+            }
+        }
+    }
+
+    Ok(upcoming)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::outreach::{OutreachQueue, OutreachRecipient};
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn suggestion(tag: &str) -> TagSuggestion {
+        TagSuggestion {
+            tag: tag.to_string(),
+            reason: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_contact_timeline_merges_sources() {
+        crate::db::init_test_db();
+        let me = fake::user(1, "Me", "");
+        let ada = fake::user(7621, "Ada", "");
+        let mut reply = fake::text_message(2, 7621, &me, "Sure", 100);
+        reply.is_outgoing = true;
+        let client = FakeTelegram::new(me)
+            .with_messages(7621, vec![fake::text_message(1, 7621, &ada, "Coffee?", 10), reply]);
+
+        db_contacts::log_contact_event(7621, db_contacts::EVENT_TAG_ADDED, "investor").unwrap();
+        let queue = OutreachQueue {
+            id: "timeline-7621".to_string(),
+            template: "Hi {first_name}".to_string(),
+            recipients: vec![OutreachRecipient {
+                user_id: 7621,
+                first_name: "Ada".to_string(),
+                last_name: String::new(),
+                username: None,
+                status: "sent".to_string(),
+                error: None,
+                sent_at: Some(50),
+            }],
+            status: "completed".to_string(),
+            started_at: Some(40),
+            completed_at: Some(60),
+            sent_count: 1,
+            failed_count: 0,
+        };
+        db::with_db(|conn| db::outreach::save_queue(conn, &queue)).unwrap();
+
+        // Logged events are stamped now, after the old messages and outreach
+        let events = contact_timeline(&client, 7621, 10).await.unwrap();
+        let kinds: Vec<&str> = events.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["message_in", "outreach", "message_out", db_contacts::EVENT_TAG_ADDED]);
+        assert_eq!(events[1].text, "Hi {first_name}");
+        assert_eq!(events[3].text, "investor");
+    }
+
+    #[test]
+    fn test_person_activity_across_chats() {
+        let ada = fake::user(7461, "Ada", "");
+        let bob = fake::user(7462, "Bob", "");
+        let message = |id, chat_id, sender: &crate::telegram::client::User, date| {
+            fake::text_message(id, chat_id, sender, "hi", date)
+        };
+        let chats = vec![
+            (7461, "Ada".to_string(), vec![message(1, 7461, &ada, 500), message(2, 7461, &ada, 50)]),
+            (7463, "Team".to_string(), vec![message(3, 7463, &bob, 600), message(4, 7463, &ada, 700)]),
+        ];
+
+        let items = person_activity(7461, 100, chats);
+        let found: Vec<(i64, i64)> = items.iter().map(|i| (i.chat_id, i.message.id)).collect();
+        assert_eq!(found, vec![(7463, 4), (7461, 1)]);
+        assert_eq!(items[0].chat_title, "Team");
+    }
+
+    #[test]
+    fn test_archived_activity_chats() {
+        let archived = |mut chat: Chat| {
+            chat.is_archived = true;
+            chat
+        };
+        let chats = vec![
+            archived(fake::chat(7461, "private", "Ada", 0, 0)),
+            archived(fake::chat(7462, "private", "Bob", 0, 1)),
+            archived(fake::chat(7463, "group", "Team", 0, 2)),
+            fake::chat(7464, "group", "Book club", 0, 3),
+        ];
+
+        // Only the archive folder, and no one else's DM
+        let ids: Vec<i64> = archived_activity_chats(7461, chats).iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![7461, 7463]);
+    }
+
+    #[test]
+    fn test_filter_tag_suggestions() {
+        let vocabulary = vec!["Investor".to_string(), "Friend".to_string(), "Cohort 4".to_string()];
+        let current = vec!["Friend".to_string()];
+        let suggestions = vec![
+            suggestion("investor"),
+            suggestion("Friend"),
+            suggestion("VIP"),
+            suggestion(" Cohort 4 "),
+            suggestion("Investor"),
+        ];
+
+        let tags: Vec<String> = filter_tag_suggestions(suggestions, &vocabulary, &current)
+            .into_iter()
+            .map(|s| s.tag)
+            .collect();
+        assert_eq!(tags, vec!["Investor", "Cohort 4"]);
+    }
+
+    #[test]
+    fn test_next_birthday() {
+        let birthday = Birthday { day: 10, month: 3, year: None };
+        assert_eq!(next_birthday(date(2025, 3, 1), &birthday), Some(date(2025, 3, 10)));
+        assert_eq!(next_birthday(date(2025, 3, 10), &birthday), Some(date(2025, 3, 10)));
+        assert_eq!(next_birthday(date(2025, 3, 11), &birthday), Some(date(2026, 3, 10)));
+    }
+
+    #[test]
+    fn test_next_birthday_leap_day() {
+        let birthday = Birthday { day: 29, month: 2, year: Some(2000) };
+        assert_eq!(next_birthday(date(2025, 1, 1), &birthday), Some(date(2025, 2, 28)));
+        assert_eq!(next_birthday(date(2028, 1, 1), &birthday), Some(date(2028, 2, 29)));
+    }
+}
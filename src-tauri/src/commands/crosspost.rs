@@ -0,0 +1,309 @@
+//! Cross-posting: send one text, or forward one message, to several channels and
+//! groups, each at its own time. Jobs run in the background like outreach queues,
+//! sharing the rate limiter, and are resumed on startup so scheduled posts survive
+//! a restart.
+
+use crate::commands::outreach::extract_flood_wait_seconds;
+use crate::db::crosspost::{
+    self as db_crosspost, CrosspostJob, CrosspostTarget, STATUS_CANCELLED, STATUS_COMPLETED, STATUS_RUNNING,
+    TARGET_FAILED, TARGET_PENDING, TARGET_SENT,
+};
+use crate::telegram::{TelegramApi, TelegramClient};
+use crate::utils::rate_limiter::RateLimiter;
+use serde::Deserialize;
+use std::sync::Arc;
+use tauri::State;
+use tokio::time::{sleep, Duration};
+
+/// How often a waiting job checks whether it was cancelled
+const CANCEL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Wait before retrying a target while the client is offline
+const OFFLINE_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How many jobs `get_crossposts` returns
+const RECENT_JOBS_LIMIT: i64 = 50;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrosspostTargetRequest {
+    pub chat_id: i64,
+    /// Unix seconds; omitted to send right away
+    #[serde(default)]
+    pub send_at: Option<i64>,
+}
+
+fn is_cancelled(job_id: &str) -> bool {
+    // A job that vanished counts as cancelled
+    db_crosspost::load_job(job_id)
+        .ok()
+        .flatten()
+        .map_or(true, |job| job.status == STATUS_CANCELLED)
+}
+
+/// Sleep for `duration`, waking up to check for cancellation. Returns false if
+/// the job was cancelled meanwhile.
+async fn wait_unless_cancelled(job_id: &str, duration: Duration) -> bool {
+    let until = tokio::time::Instant::now() + duration;
+    loop {
+        if is_cancelled(job_id) {
+            return false;
+        }
+        let now = tokio::time::Instant::now();
+        if now >= until {
+            return true;
+        }
+        sleep(CANCEL_CHECK_INTERVAL.min(until - now)).await;
+    }
+}
+
+/// Validate a cross-post request and store it as a job with pending targets
+async fn create_job(
+    client: &dyn TelegramApi,
+    text: Option<String>,
+    source_chat_id: Option<i64>,
+    message_id: Option<i64>,
+    targets: Vec<CrosspostTargetRequest>,
+) -> Result<CrosspostJob, String> {
+    let text = text.filter(|t| !t.trim().is_empty());
+    let source = source_chat_id.zip(message_id);
+    match (&text, source) {
+        (Some(_), Some(_)) => return Err("Give either a text or a message to forward, not both".to_string()),
+        (None, None) => return Err("Nothing to cross-post: give a text or a message to forward".to_string()),
+        _ => {}
+    }
+    if targets.is_empty() {
+        return Err("Pick at least one chat to cross-post to".to_string());
+    }
+
+    let mut job_targets: Vec<CrosspostTarget> = Vec::with_capacity(targets.len());
+    for target in targets {
+        if job_targets.iter().any(|t| t.chat_id == target.chat_id) {
+            continue;
+        }
+        let chat_title = client
+            .get_chat(target.chat_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|c| c.title)
+            .unwrap_or_else(|| target.chat_id.to_string());
+        job_targets.push(CrosspostTarget {
+            chat_id: target.chat_id,
+            chat_title,
+            send_at: target.send_at,
+            status: TARGET_PENDING.to_string(),
+            error: None,
+            sent_at: None,
+        });
+    }
+
+    let job = CrosspostJob {
+        id: uuid::Uuid::new_v4().to_string(),
+        text,
+        source_chat_id: source.map(|(chat_id, _)| chat_id),
+        source_message_id: source.map(|(_, id)| id),
+        status: STATUS_RUNNING.to_string(),
+        targets: job_targets,
+        sent_count: 0,
+        failed_count: 0,
+        created_at: chrono::Utc::now().timestamp(),
+        completed_at: None,
+    };
+    db_crosspost::save_job(&job)?;
+    Ok(job)
+}
+
+/// Send a job's pending targets in schedule order, honouring cancellation, the
+/// rate limiter and FLOOD_WAITs. Targets that fail for other reasons are marked
+/// failed and skipped.
+pub(crate) async fn process_crosspost(client: Arc<dyn TelegramApi>, limiter: Arc<RateLimiter>, job_id: String) {
+    let job = match db_crosspost::load_job(&job_id) {
+        Ok(Some(job)) => job,
+        Ok(None) => return,
+        Err(e) => {
+            log::error!("[Crosspost] Failed to load job {}: {}", job_id, e);
+            return;
+        }
+    };
+    log::info!("[Crosspost] Processing job {} ({} targets)", job_id, job.targets.len());
+
+    for target in job.targets.iter().filter(|t| t.status == TARGET_PENDING) {
+        if let Some(send_at) = target.send_at {
+            let wait = (send_at - chrono::Utc::now().timestamp()).max(0) as u64;
+            if !wait_unless_cancelled(&job_id, Duration::from_secs(wait)).await {
+                log::info!("[Crosspost] Job {} was cancelled", job_id);
+                return;
+            }
+        }
+
+        let result = loop {
+            if let Err(wait_secs) = limiter.can_send(target.chat_id) {
+                log::info!("[Crosspost] Rate limiter: waiting {} seconds for chat {}", wait_secs, target.chat_id);
+                if !wait_unless_cancelled(&job_id, Duration::from_secs(wait_secs.max(1))).await {
+                    log::info!("[Crosspost] Job {} was cancelled during rate limit wait", job_id);
+                    return;
+                }
+                continue;
+            }
+            if is_cancelled(&job_id) {
+                log::info!("[Crosspost] Job {} was cancelled before sending", job_id);
+                return;
+            }
+
+            let result = match (&job.text, job.source_chat_id, job.source_message_id) {
+                (Some(text), _, _) => client.send_message(target.chat_id, text).await.map(|_| ()),
+                (None, Some(from_chat_id), Some(message_id)) => {
+                    client.forward_message(from_chat_id, message_id, target.chat_id).await
+                }
+                _ => Err("Nothing to cross-post".to_string()),
+            };
+            match result {
+                // Offline: hold the target until the client is back rather than failing it
+                Err(e) if TelegramClient::is_connection_error(&e) => {
+                    log::warn!("[Crosspost] Offline, retrying chat {} later: {}", target.chat_id, e);
+                    if !wait_unless_cancelled(&job_id, OFFLINE_RETRY_INTERVAL).await {
+                        return;
+                    }
+                }
+                // The limiter now holds every send until the wait is over
+                Err(e) if e.to_lowercase().contains("flood") => {
+                    let wait_secs = extract_flood_wait_seconds(&e).unwrap_or(60);
+                    log::warn!("[Crosspost] FLOOD_WAIT received, pausing {} seconds", wait_secs);
+                    limiter.handle_flood_wait(wait_secs);
+                }
+                result => break result,
+            }
+        };
+
+        let update = match result {
+            Ok(()) => {
+                log::info!("[Crosspost] Posted to {} ({})", target.chat_title, target.chat_id);
+                limiter.record_send(target.chat_id);
+                db_crosspost::update_target_status(&job_id, target.chat_id, TARGET_SENT, None)
+            }
+            Err(e) => {
+                log::error!("[Crosspost] Failed to post to {} ({}): {}", target.chat_title, target.chat_id, e);
+                db_crosspost::update_target_status(&job_id, target.chat_id, TARGET_FAILED, Some(&e))
+            }
+        };
+        if let Err(e) = update {
+            log::error!("[Crosspost] Failed to record status for chat {}: {}", target.chat_id, e);
+        }
+    }
+
+    if !is_cancelled(&job_id) {
+        if let Err(e) = db_crosspost::update_job_status(&job_id, STATUS_COMPLETED) {
+            log::error!("[Crosspost] Failed to complete job {}: {}", job_id, e);
+        }
+        log::info!("[Crosspost] Job {} completed", job_id);
+    }
+}
+
+/// Pick up jobs that were still sending when the app last closed
+pub(crate) async fn resume_crossposts(client: Arc<dyn TelegramApi>, limiter: Arc<RateLimiter>) {
+    let jobs = match db_crosspost::load_running_jobs() {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            log::error!("[Crosspost] Failed to load running jobs: {}", e);
+            return;
+        }
+    };
+    for job in jobs {
+        log::info!("[Crosspost] Resuming job {}", job.id);
+        tauri::async_runtime::spawn(process_crosspost(client.clone(), limiter.clone(), job.id));
+    }
+}
+
+/// Cross-post a text, or forward the message `message_id` of `source_chat_id`, to
+/// each target chat at its `send_at` (or right away). Returns the job id.
+#[tauri::command]
+pub async fn crosspost(
+    client: State<'_, Arc<TelegramClient>>,
+    rate_limiter: State<'_, Arc<RateLimiter>>,
+    text: Option<String>,
+    source_chat_id: Option<i64>,
+    message_id: Option<i64>,
+    targets: Vec<CrosspostTargetRequest>,
+) -> Result<String, String> {
+    log::info!("[Crosspost] Starting cross-post to {} chats", targets.len());
+    let job = create_job(client.inner().as_ref(), text, source_chat_id, message_id, targets).await?;
+
+    let client: Arc<dyn TelegramApi> = client.inner().clone();
+    let limiter = Arc::clone(&rate_limiter);
+    tauri::async_runtime::spawn(process_crosspost(client, limiter, job.id.clone()));
+
+    Ok(job.id)
+}
+
+#[tauri::command]
+pub async fn get_crosspost_status(job_id: String) -> Result<Option<CrosspostJob>, String> {
+    db_crosspost::load_job(&job_id)
+}
+
+/// Recent cross-posts, newest first
+#[tauri::command]
+pub async fn get_crossposts() -> Result<Vec<CrosspostJob>, String> {
+    db_crosspost::load_recent_jobs(RECENT_JOBS_LIMIT)
+}
+
+/// Stop a cross-post; targets already posted to stay posted
+#[tauri::command]
+pub async fn cancel_crosspost(job_id: String) -> Result<(), String> {
+    let job = db_crosspost::load_job(&job_id)?.ok_or_else(|| format!("Cross-post {} not found", job_id))?;
+    if job.status != STATUS_RUNNING {
+        return Err(format!("Cross-post {} is already {}", job_id, job.status));
+    }
+    db_crosspost::update_job_status(&job_id, STATUS_CANCELLED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_crosspost_text_and_forward() {
+        crate::db::init_test_db();
+        let me = fake::user(1, "Me", "");
+        let telegram = Arc::new(
+            FakeTelegram::new(me.clone())
+                .with_chat(fake::chat(7491, "channel", "Announcements", 0, 0))
+                .with_chat(fake::chat(7492, "group", "Community", 0, 1))
+                .with_chat(fake::chat(7493, "group", "Archive", 0, 2))
+                .with_messages(7491, vec![fake::text_message(30, 7491, &me, "Release 2.0 is out", 100)])
+                .with_send_error(7493, "CHAT_WRITE_FORBIDDEN"),
+        );
+        let limiter = Arc::new(RateLimiter::new(0));
+        let target = |chat_id| CrosspostTargetRequest { chat_id, send_at: None };
+
+        assert!(create_job(telegram.as_ref(), None, None, None, vec![target(7492)]).await.is_err());
+        assert!(create_job(telegram.as_ref(), Some("Hi".to_string()), None, None, vec![]).await.is_err());
+
+        let job = create_job(
+            telegram.as_ref(),
+            Some("Meetup on Friday".to_string()),
+            None,
+            None,
+            vec![target(7492), target(7493), target(7492)],
+        )
+        .await
+        .unwrap();
+        assert_eq!(job.targets.len(), 2);
+        process_crosspost(telegram.clone(), limiter.clone(), job.id.clone()).await;
+
+        let job = db_crosspost::load_job(&job.id).unwrap().unwrap();
+        assert_eq!((job.status.as_str(), job.sent_count, job.failed_count), (STATUS_COMPLETED, 1, 1));
+        let failed = job.targets.iter().find(|t| t.chat_id == 7493).unwrap();
+        assert_eq!((failed.chat_title.as_str(), failed.error.as_deref()), ("Archive", Some("CHAT_WRITE_FORBIDDEN")));
+        assert!(cancel_crosspost(job.id.clone()).await.is_err());
+
+        let forward = create_job(telegram.as_ref(), None, Some(7491), Some(30), vec![target(7492)]).await.unwrap();
+        process_crosspost(telegram.clone(), limiter, forward.id.clone()).await;
+        assert_eq!(
+            telegram.sent(),
+            vec![(7492, "Meetup on Friday".to_string()), (7492, "Release 2.0 is out".to_string())]
+        );
+        assert_eq!(db_crosspost::load_job(&forward.id).unwrap().unwrap().sent_count, 1);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::ai::client::{safe_json_parse, CallTimeout};
+use crate::ai::context::{build_summary_context, fit_token_budget};
+use crate::ai::prompts::{format_decisions_user_prompt, DECISIONS_SYSTEM_PROMPT};
+use crate::ai::sanitize::{sanitize_chat_title, sanitize_message_text, sanitize_sender_name};
+use crate::ai::types::{AIDecisionItem, AIDecisionsResponse, ChatMessage, OpenAIMessage};
+use crate::ai::LLMClient;
+use crate::db::decisions::{self as db_decisions, DecisionRecord, NewDecision, KIND_COMMITMENT, KIND_DECISION};
+use crate::telegram::TelegramClient;
+use std::sync::Arc;
+use tauri::State;
+
+/// Most messages read from the chat for one extraction
+const DECISIONS_MAX_MESSAGES: i32 = 500;
+
+/// Token budget for the message portion of the prompt; older messages beyond it are dropped
+const DECISIONS_TOKEN_BUDGET: usize = 8000;
+
+/// Extract decisions and commitments from the last `period_days` days of a chat and
+/// store them with the message each came from. Returns everything stored for the chat.
+#[tauri::command]
+pub async fn extract_decisions(
+    client: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+    chat_id: i64,
+    period_days: i64,
+) -> Result<Vec<DecisionRecord>, String> {
+    log::info!("Extracting decisions for chat {} (last {}d)", chat_id, period_days);
+
+    let chat = client
+        .get_chat(chat_id)
+        .await?
+        .ok_or_else(|| format!("Chat {} not found", chat_id))?;
+
+    let since = chrono::Utc::now().timestamp() - period_days.max(1) * 86400;
+    let messages: Vec<_> = client
+        .get_chat_messages(chat_id, DECISIONS_MAX_MESSAGES, None)
+        .await?
+        .into_iter()
+        .filter(|m| m.date >= since)
+        .collect();
+
+    if messages.is_empty() {
+        return db_decisions::load_decisions(Some(chat_id));
+    }
+
+    let context = build_summary_context(&chat, &messages);
+    let window = fit_token_budget(&context.messages, DECISIONS_TOKEN_BUDGET);
+    let lines: Vec<(i64, i64, String, String)> = window
+        .iter()
+        .map(|m| {
+            let sender = if m.is_outgoing {
+                "You".to_string()
+            } else {
+                sanitize_sender_name(&m.sender_name)
+            };
+            (m.id, m.date, sender, sanitize_message_text(&m.text))
+        })
+        .collect();
+
+    let chat_title = sanitize_chat_title(&chat.title);
+    let llm_messages = vec![
+        OpenAIMessage {
+            role: "system".to_string(),
+            content: DECISIONS_SYSTEM_PROMPT.to_string(),
+        },
+        OpenAIMessage {
+            role: "user".to_string(),
+            content: format_decisions_user_prompt(&chat_title, &lines),
+        },
+    ];
+
+    let mut names: Vec<String> = lines.iter().map(|(_, _, sender, _)| sender.clone()).collect();
+    if chat.chat_type == "private" {
+        names.push(chat_title.clone());
+    }
+
+    let parsed: AIDecisionsResponse = llm
+        .complete_private(llm_messages, &names, 0.2, 1200, true, CallTimeout::Long)
+        .await
+        .map_err(|e| format!("Failed to extract decisions: {}", e))?
+        .parse_and_cache(|response| safe_json_parse(response, "decisions"))?;
+
+    let decisions = to_new_decisions(parsed.decisions, window);
+    let inserted = db_decisions::save_decisions(chat_id, &chat.title, &decisions)?;
+    log::info!("Extracted {} decisions for chat {} ({} new)", decisions.len(), chat_id, inserted);
+
+    db_decisions::load_decisions(Some(chat_id))
+}
+
+/// Keep the well-formed items, with provenance only for messages the model was sent
+fn to_new_decisions(items: Vec<AIDecisionItem>, window: &[ChatMessage]) -> Vec<NewDecision> {
+    items
+        .into_iter()
+        .filter(|d| (d.kind == KIND_DECISION || d.kind == KIND_COMMITMENT) && !d.text.trim().is_empty())
+        .map(|d| {
+            let source = d.message_id.and_then(|id| window.iter().find(|m| m.id == id));
+            NewDecision {
+                kind: d.kind,
+                text: d.text.trim().to_string(),
+                owner: d.owner.filter(|o| !o.trim().is_empty()),
+                due: d.due.filter(|due| !due.trim().is_empty()),
+                message_id: source.map(|m| m.id),
+                sender_name: source.map(|m| if m.is_outgoing { "You".to_string() } else { m.sender_name.clone() }),
+                message_date: source.map(|m| m.date),
+            }
+        })
+        .collect()
+}
+
+/// Stored decisions and commitments, for one chat or all chats
+#[tauri::command]
+pub async fn get_decisions(chat_id: Option<i64>) -> Result<Vec<DecisionRecord>, String> {
+    db_decisions::load_decisions(chat_id)
+}
+
+#[tauri::command]
+pub async fn delete_decision(id: i64) -> Result<(), String> {
+    db_decisions::delete_decision(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(kind: &str, text: &str, message_id: Option<i64>) -> AIDecisionItem {
+        AIDecisionItem {
+            kind: kind.to_string(),
+            text: text.to_string(),
+            owner: Some(" ".to_string()),
+            due: None,
+            message_id,
+        }
+    }
+
+    #[test]
+    fn test_to_new_decisions_keeps_seen_provenance() {
+        let window = vec![ChatMessage {
+            id: 12,
+            sender_name: "Me".to_string(),
+            text: "I'll send the slides".to_string(),
+            date: 500,
+            is_outgoing: true,
+            kind: None,
+        }];
+        let items = vec![
+            item(KIND_COMMITMENT, " Send the slides ", Some(12)),
+            item(KIND_DECISION, "Ship on Monday", Some(99)),
+            item("question", "Who hosts?", None),
+            item(KIND_DECISION, "  ", None),
+        ];
+
+        let decisions = to_new_decisions(items, &window);
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0].text, "Send the slides");
+        assert_eq!((decisions[0].message_id, decisions[0].sender_name.as_deref()), (Some(12), Some("You")));
+        assert!(decisions[0].owner.is_none());
+        // A message the model wasn't sent isn't credited
+        assert_eq!((decisions[1].message_id, decisions[1].message_date), (None, None));
+    }
+}
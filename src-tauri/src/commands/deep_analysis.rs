@@ -0,0 +1,205 @@
+use crate::ai::client::{safe_json_parse, CallTimeout};
+use crate::ai::context::{build_summary_context, estimate_tokens, fit_token_budget};
+use crate::ai::prompts::{format_deep_analysis_user_prompt, DEEP_ANALYSIS_SYSTEM_PROMPT};
+use crate::ai::sanitize::{sanitize_chat_title, sanitize_message_text, sanitize_sender_name};
+use crate::ai::types::{AIDeepAnalysisResponse, ChatMessage, ChatType, OpenAIMessage, PositionChange};
+use crate::ai::LLMClient;
+use crate::telegram::TelegramClient;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::State;
+
+/// Messages read for a deep analysis (far more than a regular summary)
+const DEEP_ANALYSIS_MAX_MESSAGES: i32 = 400;
+
+/// Token budget for the conversation; older messages beyond it are dropped
+const DEEP_ANALYSIS_TOKEN_BUDGET: usize = 24000;
+
+/// Room left for the analysis itself
+const DEEP_ANALYSIS_MAX_OUTPUT_TOKENS: i32 = 2000;
+
+/// How long the user has to confirm an estimate before it must be redone
+const CONFIRMATION_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Single-use tokens handed out with estimates, each good for one run on one chat
+#[derive(Default)]
+struct Confirmations {
+    pending: Mutex<HashMap<String, (i64, Instant)>>,
+}
+
+impl Confirmations {
+    fn issue(&self, chat_id: i64, now: Instant) -> String {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.retain(|_, (_, issued_at)| now.duration_since(*issued_at) < CONFIRMATION_TTL);
+        pending.insert(token.clone(), (chat_id, now));
+        token
+    }
+
+    /// Use up `token`, which must have been issued for `chat_id` and not expired
+    fn redeem(&self, token: &str, chat_id: i64, now: Instant) -> Result<(), String> {
+        let issued = self.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(token);
+        match issued {
+            Some((issued_for, issued_at))
+                if issued_for == chat_id && now.duration_since(issued_at) < CONFIRMATION_TTL =>
+            {
+                Ok(())
+            }
+            _ => Err("Deep analysis needs a fresh estimate confirmed by the user".to_string()),
+        }
+    }
+}
+
+static CONFIRMATIONS: Lazy<Confirmations> = Lazy::new(Confirmations::default);
+
+/// What a deep analysis will send, so the UI can ask before spending it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepAnalysisEstimate {
+    pub chat_id: i64,
+    pub message_count: usize,
+    /// Approximate prompt plus output tokens
+    pub estimated_tokens: usize,
+    /// Pass to `deep_analysis` once the user accepts the cost
+    pub confirmation_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepAnalysis {
+    pub chat_id: i64,
+    pub chat_title: String,
+    pub overview: String,
+    pub timeline: Vec<PositionChange>,
+    pub open_questions: Vec<String>,
+    pub risks: Vec<String>,
+    pub suggested_strategy: Vec<String>,
+    pub message_count: usize,
+    pub generated_at: i64,
+}
+
+/// The chat and the newest messages that fit the budget, oldest first
+async fn load_conversation(client: &TelegramClient, chat_id: i64) -> Result<(String, String, Vec<ChatMessage>), String> {
+    let chat = client
+        .get_chat(chat_id)
+        .await?
+        .ok_or_else(|| format!("Chat {} not found", chat_id))?;
+    let messages = client.get_chat_messages(chat_id, DEEP_ANALYSIS_MAX_MESSAGES, None).await?;
+    let context = build_summary_context(&chat, &messages);
+    let window = fit_token_budget(&context.messages, DEEP_ANALYSIS_TOKEN_BUDGET).to_vec();
+    Ok((chat.title, chat.chat_type, window))
+}
+
+/// Size of the deep analysis for a chat. Call this first and let the user confirm.
+#[tauri::command]
+pub async fn estimate_deep_analysis(
+    client: State<'_, Arc<TelegramClient>>,
+    chat_id: i64,
+) -> Result<DeepAnalysisEstimate, String> {
+    let (_, _, messages) = load_conversation(&client, chat_id).await?;
+    let prompt_tokens: usize = estimate_tokens(DEEP_ANALYSIS_SYSTEM_PROMPT)
+        + messages
+            .iter()
+            .map(|m| estimate_tokens(&m.sender_name) + estimate_tokens(&m.text) + 5)
+            .sum::<usize>();
+
+    Ok(DeepAnalysisEstimate {
+        chat_id,
+        message_count: messages.len(),
+        estimated_tokens: prompt_tokens + DEEP_ANALYSIS_MAX_OUTPUT_TOKENS as usize,
+        confirmation_token: CONFIRMATIONS.issue(chat_id, Instant::now()),
+    })
+}
+
+/// In-depth analysis of a high-stakes conversation: timeline of positions, open
+/// questions, risks and a suggested strategy. Uses a much larger context than other
+/// features, so it only runs with the token from an estimate the user accepted.
+#[tauri::command]
+pub async fn deep_analysis(
+    client: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+    chat_id: i64,
+    confirmation_token: String,
+) -> Result<DeepAnalysis, String> {
+    CONFIRMATIONS.redeem(&confirmation_token, chat_id, Instant::now())?;
+    log::info!("Running deep analysis for chat {}", chat_id);
+
+    let (title, chat_type, messages) = load_conversation(&client, chat_id).await?;
+    if messages.is_empty() {
+        return Err("No messages to analyze".to_string());
+    }
+
+    let chat_title = sanitize_chat_title(&title);
+    let lines: Vec<(i64, String, String)> = messages
+        .iter()
+        .map(|m| {
+            let sender = if m.is_outgoing {
+                "You".to_string()
+            } else {
+                sanitize_sender_name(&m.sender_name)
+            };
+            (m.date, sender, sanitize_message_text(&m.text))
+        })
+        .collect();
+
+    let llm_messages = vec![
+        OpenAIMessage {
+            role: "system".to_string(),
+            content: DEEP_ANALYSIS_SYSTEM_PROMPT.to_string(),
+        },
+        OpenAIMessage {
+            role: "user".to_string(),
+            content: format_deep_analysis_user_prompt(&chat_title, &ChatType::from_str(&chat_type).to_string(), &lines),
+        },
+    ];
+
+    let mut names: Vec<String> = lines.iter().map(|(_, sender, _)| sender.clone()).collect();
+    if chat_type == "private" {
+        names.push(chat_title);
+    }
+
+    let response = llm
+        .chat_completion_private(llm_messages, &names, 0.4, DEEP_ANALYSIS_MAX_OUTPUT_TOKENS, true, CallTimeout::Long)
+        .await
+        .map_err(|e| format!("Deep analysis failed: {}", e))?;
+    let parsed: AIDeepAnalysisResponse = safe_json_parse(&response, "deep analysis")?;
+
+    Ok(DeepAnalysis {
+        chat_id,
+        chat_title: title,
+        overview: parsed.overview,
+        timeline: parsed.timeline,
+        open_questions: parsed.open_questions,
+        risks: parsed.risks,
+        suggested_strategy: parsed.suggested_strategy,
+        message_count: messages.len(),
+        generated_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirmation_tokens_are_single_use_per_chat() {
+        let confirmations = Confirmations::default();
+        let now = Instant::now();
+
+        let token = confirmations.issue(7, now);
+        assert!(confirmations.redeem(&token, 8, now).is_err());
+        // A token tried on the wrong chat is gone too
+        assert!(confirmations.redeem(&token, 7, now).is_err());
+
+        let token = confirmations.issue(7, now);
+        assert!(confirmations.redeem(&token, 7, now).is_ok());
+        assert!(confirmations.redeem(&token, 7, now).is_err());
+
+        let token = confirmations.issue(7, now);
+        assert!(confirmations.redeem(&token, 7, now + CONFIRMATION_TTL).is_err());
+        assert!(confirmations.redeem("made-up", 7, now).is_err());
+    }
+}
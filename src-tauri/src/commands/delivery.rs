@@ -0,0 +1,53 @@
+use crate::ai::types::BriefingV2Response;
+use crate::db::briefings as db_briefings;
+use crate::db::settings::{self as db_settings, BriefingDeliveryConfig};
+use crate::export::telegram::render_briefing;
+use crate::telegram::TelegramClient;
+use std::sync::Arc;
+use tauri::State;
+
+/// Post a briefing to Saved Messages (`chat_id: None`) or a chat, split into as many
+/// messages as needed. Returns the number of messages sent.
+pub async fn post_briefing(
+    client: &TelegramClient,
+    briefing: &BriefingV2Response,
+    chat_id: Option<i64>,
+) -> Result<usize, String> {
+    let messages = render_briefing(briefing);
+    for message in &messages {
+        client.send_formatted_message(chat_id, message).await?;
+    }
+    log::info!("Delivered briefing as {} message(s) to {:?}", messages.len(), chat_id);
+    Ok(messages.len())
+}
+
+/// Post a briefing using the saved delivery settings, if delivery is enabled.
+/// Returns the number of messages sent (0 when disabled).
+pub async fn deliver_if_enabled(client: &TelegramClient, briefing: &BriefingV2Response) -> Result<usize, String> {
+    let config = db_settings::load_briefing_delivery()?;
+    if !config.enabled {
+        return Ok(0);
+    }
+    post_briefing(client, briefing, config.chat_id).await
+}
+
+#[tauri::command]
+pub async fn get_briefing_delivery() -> Result<BriefingDeliveryConfig, String> {
+    db_settings::load_briefing_delivery()
+}
+
+#[tauri::command]
+pub async fn update_briefing_delivery(config: BriefingDeliveryConfig) -> Result<(), String> {
+    db_settings::save_briefing_delivery(&config)
+}
+
+/// Send a saved briefing (or the latest one) to the configured target right away
+#[tauri::command]
+pub async fn deliver_briefing(
+    client: State<'_, Arc<TelegramClient>>,
+    briefing_id: Option<String>,
+) -> Result<usize, String> {
+    let briefing = db_briefings::load_briefing_or_latest(briefing_id.as_deref())?;
+    let config = db_settings::load_briefing_delivery()?;
+    post_briefing(&client, &briefing, config.chat_id).await
+}
@@ -0,0 +1,328 @@
+//! Relationship dossier: everything known about one contact in a single document
+//! (profile, notes and tags, shared groups, DM history and an AI-written summary
+//! of the relationship), for reading before a meeting or call.
+
+use crate::ai::client::{safe_json_parse, CallTimeout};
+use crate::ai::context::{build_chat_context, fit_token_budget};
+use crate::ai::prompts::{format_dossier_user_prompt, DatedLine, DOSSIER_SYSTEM_PROMPT};
+use crate::ai::sanitize::{sanitize_message_text, sanitize_sender_name};
+use crate::ai::types::{ChatMessage, OpenAIMessage, RelationshipSummary};
+use crate::ai::LLMClient;
+use crate::commands::ai::contact_context;
+use crate::commands::offboard::{CommonGroup, UserAccessHashCache};
+use crate::db::contacts as db_contacts;
+use crate::export::markdown::escape_markdown;
+use crate::telegram::client::{Birthday, Chat};
+use crate::telegram::TelegramClient;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::State;
+
+/// DM messages included in a dossier (most recent)
+const DOSSIER_MESSAGE_LIMIT: i32 = 300;
+
+/// Token budget for the DM history sent to the LLM (newest messages are kept)
+const DOSSIER_TOKEN_BUDGET: usize = 6000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactDossier {
+    pub user_id: i64,
+    pub name: String,
+    pub username: Option<String>,
+    pub phone_number: Option<String>,
+    pub bio: Option<String>,
+    pub birthday: Option<Birthday>,
+    pub tags: Vec<String>,
+    pub notes: String,
+    pub shared_groups: Vec<CommonGroup>,
+    /// DM history, oldest first
+    pub messages: Vec<ChatMessage>,
+    pub first_message_at: Option<i64>,
+    pub last_message_at: Option<i64>,
+    /// None when there is no history to summarize or the LLM call failed
+    pub summary: Option<RelationshipSummary>,
+    /// The whole dossier as Markdown, for copying or saving
+    pub markdown: String,
+    pub generated_at: i64,
+}
+
+fn format_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Render a dossier as a Markdown document, escaping everything that came from Telegram or the LLM
+fn format_dossier_markdown(dossier: &ContactDossier) -> String {
+    let mut out = format!("# {}\n\n", escape_markdown(&dossier.name));
+
+    let mut details = vec![];
+    if let Some(username) = &dossier.username {
+        details.push(format!("- Username: @{}", escape_markdown(username)));
+    }
+    if let Some(phone) = &dossier.phone_number {
+        details.push(format!("- Phone: {}", phone));
+    }
+    if let Some(birthday) = &dossier.birthday {
+        let year = birthday.year.map(|y| format!("{}-", y)).unwrap_or_default();
+        details.push(format!("- Birthday: {}{:02}-{:02}", year, birthday.month, birthday.day));
+    }
+    if !dossier.tags.is_empty() {
+        details.push(format!("- Tags: {}", escape_markdown(&dossier.tags.join(", "))));
+    }
+    if let Some(bio) = dossier.bio.as_deref().filter(|b| !b.trim().is_empty()) {
+        details.push(format!("- Bio: {}", escape_markdown(bio.trim()).replace('\n', "\n  ")));
+    }
+    if let (Some(first), Some(last)) = (dossier.first_message_at, dossier.last_message_at) {
+        details.push(format!(
+            "- Messages: {} ({} to {})",
+            dossier.messages.len(),
+            format_date(first),
+            format_date(last)
+        ));
+    }
+    if !details.is_empty() {
+        out.push_str(&details.join("\n"));
+        out.push_str("\n\n");
+    }
+
+    if let Some(summary) = &dossier.summary {
+        out.push_str(&format!("## Relationship\n\n{}\n\n", escape_markdown(summary.summary.trim())));
+        if !summary.topics.is_empty() {
+            out.push_str(&format!("Topics: {}\n\n", escape_markdown(&summary.topics.join(", "))));
+        }
+        if !summary.open_items.is_empty() {
+            out.push_str("## Open items\n\n");
+            for item in &summary.open_items {
+                out.push_str(&format!("- {}\n", escape_markdown(item)));
+            }
+            out.push('\n');
+        }
+    }
+
+    if !dossier.notes.trim().is_empty() {
+        out.push_str(&format!("## Notes\n\n{}\n\n", escape_markdown(dossier.notes.trim())));
+    }
+
+    if !dossier.shared_groups.is_empty() {
+        out.push_str("## Shared groups\n\n");
+        for group in &dossier.shared_groups {
+            out.push_str(&format!("- {}\n", escape_markdown(&group.title)));
+        }
+        out.push('\n');
+    }
+
+    if !dossier.messages.is_empty() {
+        out.push_str("## Conversation\n\n");
+        for message in &dossier.messages {
+            out.push_str(&format!(
+                "- [{}] {}: {}\n",
+                format_date(message.date),
+                escape_markdown(&message.sender_name),
+                // Continuation lines stay inside the list item
+                escape_markdown(message.text.trim()).replace('\n', "\n  ")
+            ));
+        }
+    }
+
+    out.trim_end().to_string() + "\n"
+}
+
+/// Ask the LLM for a relationship summary of the DM history
+async fn summarize_relationship(
+    llm: &LLMClient,
+    name: &str,
+    user_id: i64,
+    shared_groups: &[CommonGroup],
+    messages: &[ChatMessage],
+) -> Result<RelationshipSummary, String> {
+    let contact_name = sanitize_sender_name(name);
+    let prompt_messages: Vec<DatedLine> = fit_token_budget(messages, DOSSIER_TOKEN_BUDGET)
+        .iter()
+        .map(|m| {
+            let sender = if m.is_outgoing { "You".to_string() } else { contact_name.clone() };
+            (format_date(m.date), sender, sanitize_message_text(&m.text))
+        })
+        .collect();
+    let groups: Vec<String> = shared_groups.iter().map(|g| g.title.clone()).collect();
+
+    let llm_messages = vec![
+        OpenAIMessage {
+            role: "system".to_string(),
+            content: DOSSIER_SYSTEM_PROMPT.to_string(),
+        },
+        OpenAIMessage {
+            role: "user".to_string(),
+            content: format_dossier_user_prompt(
+                &contact_name,
+                contact_context(user_id).as_deref(),
+                &groups,
+                &prompt_messages,
+            ),
+        },
+    ];
+
+    llm.complete_private(llm_messages, &[contact_name], 0.3, 800, true, CallTimeout::Long)
+        .await
+        .map_err(|e| format!("Failed to summarize relationship: {}", e))?
+        .parse_and_cache(|response| safe_json_parse(response, "dossier"))
+}
+
+/// Build a dossier for a contact: profile, notes and tags, shared groups, DM
+/// history and (when there is history) an AI-written relationship summary.
+#[tauri::command]
+pub async fn generate_contact_dossier(
+    client: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+    user_hash_cache: State<'_, Arc<UserAccessHashCache>>,
+    user_id: i64,
+) -> Result<ContactDossier, String> {
+    log::info!("Generating dossier for contact {}", user_id);
+
+    let contact = client.get_contacts().await?.into_iter().find(|u| u.id == user_id);
+    // Private chat id equals the user id, so non-contacts are found through their DM
+    let chat: Option<Chat> = client.get_chat(user_id).await.unwrap_or(None);
+    let name = match (&contact, &chat) {
+        (Some(user), _) => format!("{} {}", user.first_name, user.last_name).trim().to_string(),
+        (None, Some(chat)) => chat.title.clone(),
+        (None, None) => return Err(format!("Contact {} not found", user_id)),
+    };
+
+    let profile = db_contacts::get_contact_profile(user_id).unwrap_or(None);
+    let birthday = match &profile {
+        Some(p) => p.birthday,
+        None => db_contacts::get_contact_birthday(user_id).unwrap_or(None),
+    };
+
+    let shared_groups: Vec<CommonGroup> = match user_hash_cache.get_or_refresh(&client, user_id).await {
+        Ok(Some(access_hash)) => match client.get_common_chats(user_id, access_hash).await {
+            Ok(chats) => chats
+                .into_iter()
+                .map(|c| CommonGroup {
+                    id: c.id,
+                    title: c.title,
+                    can_remove: c.can_remove,
+                    member_count: c.member_count,
+                })
+                .collect(),
+            Err(e) => {
+                log::warn!("Failed to load shared groups for dossier of {}: {}", user_id, e);
+                vec![]
+            }
+        },
+        // Shared groups can only be looked up for contacts
+        Ok(None) => vec![],
+        Err(e) => {
+            log::warn!("Failed to look up access hash for dossier of {}: {}", user_id, e);
+            vec![]
+        }
+    };
+
+    let messages: Vec<ChatMessage> = match &chat {
+        Some(chat) => match client.get_chat_messages(user_id, DOSSIER_MESSAGE_LIMIT, None).await {
+            Ok(history) => build_chat_context(chat, &history).messages,
+            Err(e) => {
+                log::warn!("Failed to load DM history for dossier of {}: {}", user_id, e);
+                vec![]
+            }
+        },
+        None => vec![],
+    };
+
+    let summary = if messages.is_empty() {
+        None
+    } else {
+        match summarize_relationship(&llm, &name, user_id, &shared_groups, &messages).await {
+            Ok(summary) => Some(summary),
+            Err(e) => {
+                log::warn!("{}", e);
+                None
+            }
+        }
+    };
+
+    let mut dossier = ContactDossier {
+        user_id,
+        username: contact
+            .as_ref()
+            .and_then(|u| u.username.clone())
+            .or_else(|| profile.as_ref().and_then(|p| p.username.clone())),
+        phone_number: contact.as_ref().and_then(|u| u.phone_number.clone()),
+        bio: profile.as_ref().and_then(|p| p.bio.clone()),
+        name,
+        birthday,
+        tags: db_contacts::get_contact_tags(user_id).unwrap_or_default(),
+        notes: db_contacts::get_contact_notes(user_id).unwrap_or_default(),
+        shared_groups,
+        first_message_at: messages.first().map(|m| m.date),
+        last_message_at: messages.last().map(|m| m.date),
+        messages,
+        summary,
+        markdown: String::new(),
+        generated_at: chrono::Utc::now().timestamp(),
+    };
+    dossier.markdown = format_dossier_markdown(&dossier);
+    Ok(dossier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: i64, sender_name: &str, text: &str, date: i64, is_outgoing: bool) -> ChatMessage {
+        ChatMessage {
+            id,
+            sender_name: sender_name.to_string(),
+            text: text.to_string(),
+            date,
+            is_outgoing,
+            kind: None,
+        }
+    }
+
+    #[test]
+    fn test_dossier_markdown() {
+        let messages = vec![
+            message(1, "Ada", "Can you send the contract?", 1_700_000_000, false),
+            message(2, "Ada", "*Final* draft_v2\n# Terms [here]", 1_700_050_000, false),
+            message(3, "You", "Sure, tomorrow", 1_700_086_400, true),
+        ];
+        let dossier = ContactDossier {
+            user_id: 7441,
+            name: "Ada Lovelace".to_string(),
+            username: Some("ada".to_string()),
+            phone_number: None,
+            bio: Some("  ".to_string()),
+            birthday: Some(Birthday { day: 10, month: 12, year: None }),
+            tags: vec!["client".to_string()],
+            notes: "Prefers calls".to_string(),
+            shared_groups: vec![CommonGroup {
+                id: 1,
+                title: "Engines".to_string(),
+                can_remove: false,
+                member_count: Some(4),
+            }],
+            first_message_at: Some(1_700_000_000),
+            last_message_at: Some(1_700_086_400),
+            messages,
+            summary: Some(RelationshipSummary {
+                summary: "A client working on the engine project.".to_string(),
+                topics: vec!["contract".to_string()],
+                open_items: vec!["Send the contract".to_string()],
+            }),
+            markdown: String::new(),
+            generated_at: 0,
+        };
+
+        let markdown = format_dossier_markdown(&dossier);
+        assert!(markdown.starts_with("# Ada Lovelace\n\n- Username: @ada\n- Birthday: 12-10\n- Tags: client\n"));
+        assert!(markdown.contains("- Messages: 3 (2023-11-14 to 2023-11-15)"));
+        assert!(!markdown.contains("Bio:"));
+        assert!(markdown.contains("## Open items\n\n- Send the contract\n"));
+        assert!(markdown.contains("## Shared groups\n\n- Engines\n"));
+        // Message text can't add formatting or break out of its list item
+        assert!(markdown.contains("- [2023-11-15] Ada: \\*Final\\* draft\\_v2\n  \\# Terms \\[here\\]\n"));
+        assert!(markdown.ends_with("- [2023-11-15] You: Sure, tomorrow\n"));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::ai::LLMClient;
+use crate::cache::{BriefingCache, SummaryCache};
+use super::scopes::resolve_scope_chats;
+use crate::db::briefings as db_briefings;
+use crate::db::messages as db_messages;
+use crate::db::scopes as db_scopes;
+use crate::db::settings as db_settings;
+use crate::export::config::{self, ConfigBundle, ImportStats};
+use crate::export::dataset::{DatasetFormat, DatasetWriter};
+use crate::export::{self, briefing_file_name, debug_bundle};
+use crate::telegram::TelegramClient;
+use crate::utils::log_buffer;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+
+/// Most chats of a scope a dataset export covers
+const DATASET_CHAT_LIMIT: i32 = 500;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetExport {
+    pub path: String,
+    pub chats: usize,
+    pub messages: usize,
+}
+
+/// Export a saved briefing (or the latest one) as Markdown or PDF.
+/// `path` may be a file path or an existing directory; returns the written file path.
+#[tauri::command]
+pub async fn export_briefing(
+    briefing_id: Option<String>,
+    format: String,
+    path: String,
+) -> Result<String, String> {
+    let briefing = db_briefings::load_briefing_or_latest(briefing_id.as_deref())?;
+
+    let (bytes, extension) = match format.to_lowercase().as_str() {
+        "markdown" | "md" => (export::markdown::render_briefing(&briefing).into_bytes(), "md"),
+        "pdf" => (export::pdf::render_briefing(&briefing), "pdf"),
+        other => return Err(format!("Unsupported export format: {}", other)),
+    };
+
+    let mut target = PathBuf::from(&path);
+    if target.is_dir() {
+        target = target.join(briefing_file_name(&briefing, extension));
+    }
+
+    std::fs::write(&target, bytes)
+        .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+
+    log::info!("Exported briefing to {}", target.display());
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Write the archived messages of a saved scope's chats as "jsonl" or "csv", with
+/// each chat's title and type on every row. Only archived history is exported;
+/// sync or backfill chats first to include more.
+/// `path` may be a file path or an existing directory; returns what was written.
+#[tauri::command]
+pub async fn export_scope_messages(
+    client: State<'_, Arc<TelegramClient>>,
+    scope: String,
+    format: String,
+    path: String,
+) -> Result<DatasetExport, String> {
+    let format = DatasetFormat::parse(&format)?;
+    let profile = db_scopes::load_scope(&scope)?.ok_or_else(|| format!("Scope not found: {}", scope))?;
+    let chats = resolve_scope_chats(&client, &profile.config, DATASET_CHAT_LIMIT).await?;
+
+    let mut target = PathBuf::from(&path);
+    if target.is_dir() {
+        target = target.join(format!(
+            "messages-{}-{}.{}",
+            scope.replace(|c: char| !c.is_alphanumeric(), "-"),
+            chrono::Local::now().format("%Y-%m-%d-%H%M"),
+            format.extension()
+        ));
+    }
+    let file = std::fs::File::create(&target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+
+    let mut writer = DatasetWriter::new(std::io::BufWriter::new(file), format)?;
+    for chat in &chats {
+        writer.write_chat(chat, &db_messages::load_messages(chat.id, i32::MAX, None)?)?;
+    }
+    let messages = writer.finish()?;
+
+    log::info!("Exported {} messages of {} chats to {}", messages, chats.len(), target.display());
+    Ok(DatasetExport { path: target.to_string_lossy().to_string(), chats: chats.len(), messages })
+}
+
+/// Set (or clear with `None`) the folder that generated summaries are written into
+/// as Markdown notes with YAML frontmatter, e.g. an Obsidian vault directory.
+#[tauri::command]
+pub async fn sync_summaries_to_folder(path: Option<String>) -> Result<(), String> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+
+    if let Some(path) = &path {
+        if !PathBuf::from(path).is_dir() {
+            return Err(format!("Folder does not exist: {}", path));
+        }
+    }
+
+    db_settings::save_summary_sync_folder(path.as_deref())?;
+    log::info!("Summary sync folder set to {:?}", path);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_summary_sync_folder() -> Result<Option<String>, String> {
+    db_settings::load_summary_sync_folder()
+}
+
+/// Write a zip of diagnostics (masked config, schema, table counts, scrubbed logs)
+/// that is safe to attach to a bug report. `path` may be a file path or a directory.
+#[tauri::command]
+pub async fn create_debug_bundle(
+    telegram: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+    path: String,
+) -> Result<String, String> {
+    let mut target = PathBuf::from(&path);
+    if target.is_dir() {
+        target = target.join(format!(
+            "telegram-copilot-debug-{}.zip",
+            chrono::Local::now().format("%Y-%m-%d-%H%M")
+        ));
+    }
+
+    let mut names = telegram.known_names().await;
+    // Group members who only appear in archived messages
+    names.extend(db_messages::sender_names()?);
+
+    let context = debug_bundle::BundleContext {
+        names,
+        auth_state: serde_json::to_value(telegram.get_auth_state().await).unwrap_or_default(),
+        llm_config: serde_json::to_value(llm.get_config().await).unwrap_or_default(),
+        log_lines: log_buffer::recent_lines(),
+    };
+
+    let files = debug_bundle::write_bundle(&target, context)?;
+    log::info!("Wrote debug bundle with {} files to {}", files.len(), target.display());
+    Ok(target.to_string_lossy().to_string())
+}
+
+fn config_file_path(path: &str) -> PathBuf {
+    let target = PathBuf::from(path);
+    if target.is_dir() {
+        target.join(format!(
+            "telegram-copilot-config-{}.json",
+            chrono::Local::now().format("%Y-%m-%d-%H%M")
+        ))
+    } else {
+        target
+    }
+}
+
+/// Write scopes, canned responses, provider and app settings to a JSON file.
+/// API keys and the sync password are included (encrypted) only when `password` is set.
+/// `path` may be a file path or a directory; returns the written file path.
+#[tauri::command]
+pub async fn export_config(
+    llm: State<'_, Arc<LLMClient>>,
+    path: String,
+    password: Option<String>,
+) -> Result<String, String> {
+    let bundle = config::collect_config(llm.get_config().await, password.as_deref())?;
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    let target = config_file_path(&path);
+    std::fs::write(&target, json)
+        .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+
+    log::info!("Exported app config to {}", target.display());
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Load a file written by `export_config` and apply it. `password` decrypts the
+/// bundled secrets; without it the local API keys and sync password are kept.
+#[tauri::command]
+pub async fn import_config(
+    llm: State<'_, Arc<LLMClient>>,
+    briefing_cache: State<'_, Arc<BriefingCache>>,
+    summary_cache: State<'_, Arc<SummaryCache>>,
+    path: String,
+    password: Option<String>,
+) -> Result<ImportStats, String> {
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let bundle: ConfigBundle = serde_json::from_str(&json)
+        .map_err(|e| format!("Not a valid config file: {}", e))?;
+
+    let (llm_config, stats) = config::apply_config(bundle, &llm.get_config().await, password.as_deref())?;
+    llm.update_config(llm_config).await;
+    briefing_cache.0.invalidate_all().await;
+    summary_cache.0.invalidate_all().await;
+
+    log::info!(
+        "Imported app config from {} ({} scopes, {} canned responses, {} notification rules)",
+        path,
+        stats.scopes,
+        stats.canned_responses,
+        stats.notification_rules
+    );
+    Ok(stats)
+}
@@ -0,0 +1,84 @@
+use crate::db;
+use crate::db::settings::FocusConfig;
+use crate::commands::notifications::{in_daily_window, parse_hhmm};
+use chrono::Timelike;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusState {
+    pub active: bool,
+    /// "manual" or "schedule" while active
+    pub reason: Option<String>,
+    #[serde(flatten)]
+    pub config: FocusConfig,
+}
+
+/// Why focus mode is on at `now` (unix seconds) / `minute` (local minutes since midnight), if it is
+fn focus_reason(config: &FocusConfig, now: i64, minute: u32) -> Option<&'static str> {
+    if config.manual && config.manual_until.map_or(true, |until| until > now) {
+        Some("manual")
+    } else if in_daily_window(config.schedule_start.as_deref(), config.schedule_end.as_deref(), minute) {
+        Some("schedule")
+    } else {
+        None
+    }
+}
+
+fn current_state(config: FocusConfig) -> FocusState {
+    let reason = focus_reason(&config, chrono::Utc::now().timestamp(), chrono::Local::now().num_seconds_from_midnight() / 60);
+    FocusState {
+        active: reason.is_some(),
+        reason: reason.map(str::to_string),
+        config,
+    }
+}
+
+/// Whether focus mode is on right now. Notifications are suppressed and scheduled
+/// outreach/briefings wait while it is; a config that can't be read counts as off.
+pub(crate) fn focus_active() -> bool {
+    match db::settings::load_focus() {
+        Ok(config) => current_state(config).active,
+        Err(e) => {
+            log::warn!("Failed to load focus config: {}", e);
+            false
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_focus_state() -> Result<FocusState, String> {
+    Ok(current_state(db::settings::load_focus()?))
+}
+
+/// Save the focus config and return the resulting state
+#[tauri::command]
+pub async fn set_focus_state(config: FocusConfig) -> Result<FocusState, String> {
+    for time in [&config.schedule_start, &config.schedule_end].into_iter().flatten() {
+        if parse_hhmm(time).is_none() {
+            return Err(format!("Invalid focus schedule time (expected HH:MM): {}", time));
+        }
+    }
+    db::settings::save_focus(&config)?;
+    Ok(current_state(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_reason() {
+        let manual = FocusConfig { manual: true, manual_until: Some(1_000), ..Default::default() };
+        assert_eq!(focus_reason(&manual, 999, 0), Some("manual"));
+        assert_eq!(focus_reason(&manual, 1_000, 0), None);
+
+        let scheduled = FocusConfig {
+            schedule_start: Some("09:00".to_string()),
+            schedule_end: Some("11:00".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(focus_reason(&scheduled, 0, 10 * 60), Some("schedule"));
+        assert_eq!(focus_reason(&scheduled, 0, 12 * 60), None);
+    }
+}
@@ -0,0 +1,193 @@
+use crate::ai::context::{is_question_or_request, message_text_for_ai};
+use crate::telegram::client::{BatchMessageRequest, ChatFilters, Message};
+use crate::telegram::TelegramClient;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::State;
+
+/// Conversations idle for this many days past the threshold are treated as dead threads
+const MAX_FOLLOWUP_WINDOW_DAYS: i64 = 30;
+
+/// Recent messages fetched per candidate chat to find the trailing run of incoming messages
+const MESSAGES_PER_CHAT: i32 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AwaitingReply {
+    pub chat_id: i64,
+    pub chat_name: String,
+    /// Our message that asked something
+    pub message_id: i64,
+    pub message_text: String,
+    pub message_date: i64,
+    pub days_waiting: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverdueReply {
+    pub chat_id: i64,
+    pub chat_name: String,
+    /// The incoming message that asked something
+    pub message_id: i64,
+    pub message_text: String,
+    pub message_date: i64,
+    pub hours_waiting: i64,
+    pub unread_count: i32,
+}
+
+/// Private, non-bot dialogs (including archived and muted ones)
+pub(crate) fn private_dialog_filters() -> ChatFilters {
+    ChatFilters {
+        include_private_chats: true,
+        include_non_contacts: true,
+        include_groups: false,
+        include_channels: false,
+        include_bots: false,
+        include_archived: true,
+        include_muted: true,
+        ..Default::default()
+    }
+}
+
+/// The unanswered tail of a conversation: the trailing run of messages sent by one side
+/// (ours when `outgoing` is true, theirs otherwise). Messages are in chronological order.
+pub(crate) fn trailing_run(messages: &[Message], outgoing: bool) -> &[Message] {
+    let start = messages
+        .iter()
+        .rposition(|m| m.is_outgoing != outgoing)
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    &messages[start..]
+}
+
+/// DMs where the other person asked a question or made a request, the last message
+/// is theirs and it has been waiting longer than `hours_threshold`.
+/// Purely deterministic (no LLM), as a safety net under the briefing.
+#[tauri::command]
+pub async fn get_overdue_replies(
+    client: State<'_, Arc<TelegramClient>>,
+    hours_threshold: i64,
+) -> Result<Vec<OverdueReply>, String> {
+    log::info!("Scanning for overdue replies (threshold: {}h)", hours_threshold);
+
+    let now = chrono::Utc::now().timestamp();
+    let newest_allowed = now - hours_threshold.max(0) * 3600;
+    let oldest_allowed = newest_allowed - MAX_FOLLOWUP_WINDOW_DAYS * 86400;
+
+    let chats = client.get_chats(200, Some(private_dialog_filters())).await?;
+    let candidates: Vec<_> = chats
+        .into_iter()
+        .filter(|chat| match &chat.last_message {
+            Some(m) => !m.is_outgoing && m.date <= newest_allowed && m.date >= oldest_allowed,
+            None => false,
+        })
+        .collect();
+
+    if candidates.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let requests = candidates
+        .iter()
+        .map(|c| BatchMessageRequest {
+            chat_id: c.id,
+            limit: MESSAGES_PER_CHAT,
+        })
+        .collect();
+    let results = client.get_batch_messages(requests).await?;
+
+    let mut overdue: Vec<OverdueReply> = candidates
+        .iter()
+        .filter_map(|chat| {
+            let result = results.iter().find(|r| r.chat_id == chat.id)?;
+            // Latest question/request among the messages we haven't answered
+            let asked = trailing_run(&result.messages, false)
+                .iter()
+                .rev()
+                .find(|m| is_question_or_request(&message_text_for_ai(&m.content)))?;
+
+            Some(OverdueReply {
+                chat_id: chat.id,
+                chat_name: chat.title.trim().to_string(),
+                message_id: asked.id,
+                message_text: message_text_for_ai(&asked.content),
+                message_date: asked.date,
+                hours_waiting: (now - asked.date) / 3600,
+                unread_count: chat.unread_count,
+            })
+        })
+        .collect();
+
+    // Longest-waiting first
+    overdue.sort_by_key(|o| o.message_date);
+
+    log::info!("Found {} overdue replies", overdue.len());
+    Ok(overdue)
+}
+
+/// DMs where the user asked a question or made a request and the other person hasn't
+/// replied in `days_threshold` days ("waiting on Bob since Tuesday").
+#[tauri::command]
+pub async fn get_awaiting_replies(
+    client: State<'_, Arc<TelegramClient>>,
+    days_threshold: i64,
+) -> Result<Vec<AwaitingReply>, String> {
+    log::info!("Scanning for awaiting replies (threshold: {}d)", days_threshold);
+
+    let now = chrono::Utc::now().timestamp();
+    let newest_allowed = now - days_threshold.max(0) * 86400;
+    let oldest_allowed = newest_allowed - MAX_FOLLOWUP_WINDOW_DAYS * 86400;
+
+    let chats = client.get_chats(200, Some(private_dialog_filters())).await?;
+    let candidates: Vec<_> = chats
+        .into_iter()
+        .filter(|chat| match &chat.last_message {
+            Some(m) => m.is_outgoing && m.date <= newest_allowed && m.date >= oldest_allowed,
+            None => false,
+        })
+        .collect();
+
+    if candidates.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let current_user_id = client.get_current_user().await.map(|u| u.id);
+
+    let requests = candidates
+        .iter()
+        // Saved Messages never gets a reply
+        .filter(|c| Some(c.id) != current_user_id)
+        .map(|c| BatchMessageRequest {
+            chat_id: c.id,
+            limit: MESSAGES_PER_CHAT,
+        })
+        .collect();
+    let results = client.get_batch_messages(requests).await?;
+
+    let mut awaiting: Vec<AwaitingReply> = candidates
+        .iter()
+        .filter_map(|chat| {
+            let result = results.iter().find(|r| r.chat_id == chat.id)?;
+            // Earliest unanswered question, so "waiting since" reflects the first ask
+            let asked = trailing_run(&result.messages, true)
+                .iter()
+                .find(|m| is_question_or_request(&message_text_for_ai(&m.content)))?;
+
+            Some(AwaitingReply {
+                chat_id: chat.id,
+                chat_name: chat.title.trim().to_string(),
+                message_id: asked.id,
+                message_text: message_text_for_ai(&asked.content),
+                message_date: asked.date,
+                days_waiting: (now - asked.date) / 86400,
+            })
+        })
+        .collect();
+
+    // Longest-waiting first
+    awaiting.sort_by_key(|a| a.message_date);
+
+    log::info!("Found {} awaiting replies", awaiting.len());
+    Ok(awaiting)
+}
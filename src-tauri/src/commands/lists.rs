@@ -0,0 +1,61 @@
+use crate::db::lists::{self as db_lists, ContactList};
+
+fn require_list(list_id: &str) -> Result<ContactList, String> {
+    db_lists::load_list(list_id)?.ok_or_else(|| format!("List {} not found", list_id))
+}
+
+#[tauri::command]
+pub async fn list_contact_lists() -> Result<Vec<ContactList>, String> {
+    db_lists::list_lists()
+}
+
+#[tauri::command]
+pub async fn create_contact_list(name: String) -> Result<ContactList, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("List name is empty".to_string());
+    }
+    db_lists::create_list(name)
+}
+
+#[tauri::command]
+pub async fn rename_contact_list(list_id: String, name: String) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("List name is empty".to_string());
+    }
+    db_lists::rename_list(&list_id, name)
+}
+
+#[tauri::command]
+pub async fn delete_contact_list(list_id: String) -> Result<(), String> {
+    db_lists::delete_list(&list_id)
+}
+
+#[tauri::command]
+pub async fn add_to_contact_list(list_id: String, user_ids: Vec<i64>) -> Result<ContactList, String> {
+    require_list(&list_id)?;
+    db_lists::add_members(&list_id, &user_ids)?;
+    require_list(&list_id)
+}
+
+#[tauri::command]
+pub async fn remove_from_contact_list(list_id: String, user_ids: Vec<i64>) -> Result<ContactList, String> {
+    db_lists::remove_members(&list_id, &user_ids)?;
+    require_list(&list_id)
+}
+
+/// Reorder a list. Members missing from `user_ids` keep their relative order after the given ones.
+#[tauri::command]
+pub async fn reorder_contact_list(list_id: String, user_ids: Vec<i64>) -> Result<ContactList, String> {
+    let list = require_list(&list_id)?;
+    let mut order: Vec<i64> = Vec::with_capacity(list.user_ids.len());
+    for user_id in user_ids.into_iter().chain(list.user_ids.iter().copied()) {
+        if list.user_ids.contains(&user_id) && !order.contains(&user_id) {
+            order.push(user_id);
+        }
+    }
+
+    db_lists::reorder_members(&list_id, &order)?;
+    require_list(&list_id)
+}
@@ -0,0 +1,164 @@
+//! Attachments: download a message's photo, document or voice note into the app
+//! data dir, with `media://progress` events so the frontend can show a progress bar.
+//! The folder is a cache: it is capped at `MAX_MEDIA_BYTES` and wiped on logout.
+
+use crate::telegram::client::DownloadedMedia;
+use crate::telegram::TelegramClient;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Subdirectory of the app data dir downloads are saved in, one folder per chat
+const MEDIA_DIR: &str = "media";
+
+/// Downloads are kept up to this size in total; the least recently used go first
+const MAX_MEDIA_BYTES: u64 = 500 * 1024 * 1024;
+
+/// Partial downloads older than this were cut off and are deleted
+const STALE_PARTIAL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub chat_id: i64,
+    pub message_id: i64,
+    pub downloaded: i64,
+    pub total: i64,
+}
+
+fn media_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?
+        .join(MEDIA_DIR))
+}
+
+/// Download the media of a message (reusing an earlier download) and return where it is
+#[tauri::command]
+pub async fn download_chat_media(
+    app: AppHandle,
+    client: State<'_, Arc<TelegramClient>>,
+    chat_id: i64,
+    message_id: i64,
+) -> Result<DownloadedMedia, String> {
+    let dest_dir = media_dir(&app)?;
+
+    let media = client
+        .download_media(chat_id, message_id, &dest_dir, |downloaded, total| {
+            let _ = app.emit("media://progress", DownloadProgress { chat_id, message_id, downloaded, total });
+        })
+        .await?;
+
+    let kept = PathBuf::from(&media.path);
+    let pruned = tokio::task::spawn_blocking(move || {
+        // Mark it used so a reused download isn't the first to be evicted
+        if let Ok(file) = std::fs::File::options().write(true).open(&kept) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        prune_media_dir(&dest_dir, MAX_MEDIA_BYTES, &kept)
+    })
+    .await
+    .map_err(|e| format!("Media cleanup task failed: {}", e))?;
+    match pruned {
+        Ok(0) => {}
+        Ok(removed) => log::info!("Removed {} old media downloads to stay under the size cap", removed),
+        Err(e) => log::warn!("Failed to prune media downloads: {}", e),
+    }
+
+    Ok(media)
+}
+
+/// Delete every downloaded attachment (on logout, so files don't outlive the account)
+pub fn clear_media(app: &AppHandle) -> Result<(), String> {
+    let dir = media_dir(app)?;
+    match std::fs::remove_dir_all(&dir) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to delete {:?}: {}", dir, e)),
+    }
+}
+
+/// Delete the least recently used downloads until `dir` holds at most `max_bytes`,
+/// never touching `keep`. Cut-off partial downloads are removed as well.
+/// Returns the number of files deleted.
+fn prune_media_dir(dir: &Path, max_bytes: u64, keep: &Path) -> Result<usize, String> {
+    let read = |dir: &Path| std::fs::read_dir(dir).map_err(|e| format!("Failed to read {:?}: {}", dir, e));
+    let now = SystemTime::now();
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = Vec::new();
+    let mut removed = 0;
+
+    for chat_dir in read(dir)?.flatten() {
+        if !chat_dir.path().is_dir() {
+            continue;
+        }
+        for entry in read(&chat_dir.path())?.flatten() {
+            let Ok(meta) = entry.metadata() else { continue };
+            let modified = meta.modified().unwrap_or(now);
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "part") {
+                if now.duration_since(modified).unwrap_or_default() > STALE_PARTIAL && std::fs::remove_file(&path).is_ok() {
+                    removed += 1;
+                }
+            } else if meta.is_file() {
+                files.push((modified, meta.len(), path));
+            }
+        }
+    }
+
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    files.sort();
+    for (_, size, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        if path == keep {
+            continue;
+        }
+        std::fs::remove_file(&path).map_err(|e| format!("Failed to delete {:?}: {}", path, e))?;
+        total -= size;
+        removed += 1;
+        // Drop the chat's folder once it's empty
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::remove_dir(parent);
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, size: usize, age_secs: u64) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; size]).unwrap();
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(age_secs)).unwrap();
+    }
+
+    #[test]
+    fn test_prune_evicts_least_recently_used() {
+        let dir = std::env::temp_dir().join(format!("media-prune-{}", uuid::Uuid::new_v4()));
+        let (oldest, served, older, fresh) =
+            (dir.join("1/1.jpg"), dir.join("2/2.ogg"), dir.join("2/3_report.pdf"), dir.join("3/4.jpg"));
+        write(&oldest, 400, 500);
+        write(&served, 400, 400);
+        write(&older, 400, 300);
+        write(&fresh, 400, 10);
+        let (stale_part, live_part) = (dir.join("3/5.jpg.part"), dir.join("3/6.jpg.part"));
+        write(&stale_part, 10, 7200);
+        write(&live_part, 10, 5);
+
+        // 1600 bytes of files under a 900 byte cap: the two oldest go, except the one just served
+        assert_eq!(prune_media_dir(&dir, 900, &served).unwrap(), 3);
+        assert!(!oldest.exists() && !dir.join("1").exists());
+        assert!(served.exists() && !older.exists() && fresh.exists());
+        assert!(!stale_part.exists() && live_part.exists());
+
+        assert_eq!(prune_media_dir(&dir, 10_000, &fresh).unwrap(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
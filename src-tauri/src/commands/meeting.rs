@@ -0,0 +1,321 @@
+//! Pre-meeting brief: the latest exchanges with the people you're about to meet
+//! and what's still open with them, condensed into a short "where we left off".
+//! Unlike the daily briefing it isn't limited to unread chats.
+
+use crate::ai::client::{safe_json_parse, CallTimeout};
+use crate::ai::context::{build_chat_context, fit_token_budget};
+use crate::ai::prompts::{format_meeting_prep_user_prompt, DatedLine, MEETING_PREP_SYSTEM_PROMPT};
+use crate::ai::sanitize::{sanitize_chat_title, sanitize_message_text, sanitize_sender_name};
+use crate::ai::types::{AIMeetingBriefResponse, FailedChat, OpenAIMessage};
+use crate::ai::LLMClient;
+use crate::db::decisions::{self as db_decisions, DecisionRecord, KIND_COMMITMENT};
+use crate::db::reminders::{self as db_reminders, ChatReminder};
+use crate::telegram::client::{Chat, Message};
+use crate::telegram::{TelegramApi, TelegramClient};
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::State;
+
+/// Recent messages fetched per chat
+const MEETING_MESSAGES_PER_CHAT: i32 = 40;
+
+/// Token budget for all chats together, split evenly between them
+const MEETING_TOKEN_BUDGET: usize = 6000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingBrief {
+    pub participants: Vec<String>,
+    /// Chats the brief was built from (the group and/or participants' DMs)
+    pub chat_ids: Vec<i64>,
+    /// Where the conversation left off
+    pub summary: String,
+    pub open_items: Vec<String>,
+    pub talking_points: Vec<String>,
+    /// Commitments already tracked for these chats
+    pub action_items: Vec<DecisionRecord>,
+    /// Pending chat reminders for these chats
+    pub reminders: Vec<ChatReminder>,
+    pub message_count: usize,
+    /// Chats that couldn't be read and were left out of the brief
+    pub failed_chats: Vec<FailedChat>,
+    pub generated_at: i64,
+}
+
+/// Everything the brief is built from
+struct MeetingContext {
+    participants: Vec<String>,
+    chats: Vec<(Chat, Vec<Message>)>,
+    action_items: Vec<DecisionRecord>,
+    reminders: Vec<ChatReminder>,
+    failed_chats: Vec<FailedChat>,
+}
+
+/// Collect the chats, recent messages and open items for a meeting with
+/// `user_ids` (through their DMs) and/or in the group `chat_id`
+async fn gather_meeting_context(
+    client: &dyn TelegramApi,
+    user_ids: &[i64],
+    chat_id: Option<i64>,
+) -> Result<MeetingContext, String> {
+    if user_ids.is_empty() && chat_id.is_none() {
+        return Err("Pick the participants or the chat of the meeting".to_string());
+    }
+
+    // Private chat id equals the user id
+    let mut chat_ids: Vec<i64> = chat_id.into_iter().collect();
+    for id in user_ids {
+        if !chat_ids.contains(id) {
+            chat_ids.push(*id);
+        }
+    }
+
+    // A chat that can't be read is reported and left out rather than failing the brief
+    let mut participants = vec![];
+    let mut chats = vec![];
+    let mut failed_chats = vec![];
+    for id in &chat_ids {
+        let chat = match client.get_chat(*id).await {
+            Ok(Some(chat)) => chat,
+            Ok(None) => {
+                log::warn!("Chat {} not found, leaving it out of the meeting brief", id);
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Failed to load chat {} for the meeting brief: {}", id, e);
+                failed_chats.push(FailedChat {
+                    chat_id: *id,
+                    chat_name: format!("Chat {}", id),
+                    unread_count: 0,
+                    reason: e,
+                });
+                continue;
+            }
+        };
+        let messages = match client.get_chat_messages(*id, MEETING_MESSAGES_PER_CHAT, None).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                log::warn!("Failed to load messages of {} for the meeting brief: {}", chat.title, e);
+                failed_chats.push(FailedChat {
+                    chat_id: chat.id,
+                    chat_name: chat.title,
+                    unread_count: chat.unread_count,
+                    reason: e,
+                });
+                continue;
+            }
+        };
+        if user_ids.contains(id) {
+            participants.push(chat.title.clone());
+        }
+        chats.push((chat, messages));
+    }
+
+    // Participants we have no DM with are named from the contact list
+    let found: Vec<i64> = chats.iter().map(|(c, _)| c.id).collect();
+    if user_ids.iter().any(|id| !found.contains(id)) {
+        for contact in client.get_contacts().await? {
+            if user_ids.contains(&contact.id) && !found.contains(&contact.id) {
+                participants.push(format!("{} {}", contact.first_name, contact.last_name).trim().to_string());
+            }
+        }
+    }
+
+    // Without named participants, the people who spoke in the chat are the participants
+    if participants.is_empty() {
+        for (_, messages) in &chats {
+            for message in messages.iter().filter(|m| !m.is_outgoing) {
+                if !participants.contains(&message.sender_name) {
+                    participants.push(message.sender_name.clone());
+                }
+            }
+        }
+    }
+
+    let mut action_items = vec![];
+    for (chat, _) in &chats {
+        action_items.extend(
+            db_decisions::load_decisions(Some(chat.id))?
+                .into_iter()
+                .filter(|d| d.kind == KIND_COMMITMENT),
+        );
+    }
+    let reminders = db_reminders::load_pending(None)?
+        .into_iter()
+        .filter(|r| chat_ids.contains(&r.chat_id))
+        .collect();
+
+    Ok(MeetingContext {
+        participants,
+        chats,
+        action_items,
+        reminders,
+        failed_chats,
+    })
+}
+
+fn format_date(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Short "where we left off" brief before a meeting with `user_ids` (their DMs)
+/// and/or in the group `chat_id`, with the open items tracked for those chats.
+#[tauri::command]
+pub async fn prepare_for_meeting(
+    client: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+    user_ids: Option<Vec<i64>>,
+    chat_id: Option<i64>,
+) -> Result<MeetingBrief, String> {
+    let user_ids = user_ids.unwrap_or_default();
+    log::info!("Preparing meeting brief (users: {:?}, chat: {:?})", user_ids, chat_id);
+
+    let context = gather_meeting_context(client.inner().as_ref(), &user_ids, chat_id).await?;
+    let message_count = context.chats.iter().map(|(_, m)| m.len()).sum();
+    let mut brief = MeetingBrief {
+        participants: context.participants.clone(),
+        chat_ids: context.chats.iter().map(|(c, _)| c.id).collect(),
+        summary: String::new(),
+        open_items: vec![],
+        talking_points: vec![],
+        action_items: context.action_items,
+        reminders: context.reminders,
+        message_count,
+        failed_chats: context.failed_chats,
+        generated_at: chrono::Utc::now().timestamp(),
+    };
+    if message_count == 0 {
+        brief.summary = "No recent messages with these participants.".to_string();
+        return Ok(brief);
+    }
+
+    let per_chat_budget = MEETING_TOKEN_BUDGET / context.chats.len();
+    let participants: Vec<String> = context.participants.iter().map(|p| sanitize_sender_name(p)).collect();
+    let mut names = participants.clone();
+    let prompt_chats: Vec<(String, Vec<DatedLine>)> = context
+        .chats
+        .iter()
+        .filter(|(_, messages)| !messages.is_empty())
+        .map(|(chat, messages)| {
+            let chat_messages = build_chat_context(chat, messages).messages;
+            let lines = fit_token_budget(&chat_messages, per_chat_budget)
+                .iter()
+                .map(|m| {
+                    let sender = if m.is_outgoing {
+                        "You".to_string()
+                    } else {
+                        sanitize_sender_name(&m.sender_name)
+                    };
+                    names.push(sender.clone());
+                    (format_date(m.date), sender, sanitize_message_text(&m.text))
+                })
+                .collect();
+            (sanitize_chat_title(&chat.title), lines)
+        })
+        .collect();
+    let action_items: Vec<String> = brief
+        .action_items
+        .iter()
+        .map(|d| match &d.owner {
+            Some(owner) => format!("{} ({})", d.text, owner),
+            None => d.text.clone(),
+        })
+        .chain(brief.reminders.iter().filter_map(|r| r.note.clone()))
+        .map(|item| sanitize_message_text(&item))
+        .collect();
+
+    let llm_messages = vec![
+        OpenAIMessage {
+            role: "system".to_string(),
+            content: MEETING_PREP_SYSTEM_PROMPT.to_string(),
+        },
+        OpenAIMessage {
+            role: "user".to_string(),
+            content: format_meeting_prep_user_prompt(&participants, &prompt_chats, &action_items),
+        },
+    ];
+
+    let parsed: AIMeetingBriefResponse = llm
+        .complete_private(llm_messages, &names, 0.3, 800, true, CallTimeout::Long)
+        .await
+        .map_err(|e| format!("Failed to prepare meeting brief: {}", e))?
+        .parse_and_cache(|response| safe_json_parse(response, "meeting brief"))?;
+
+    brief.summary = parsed.summary;
+    brief.open_items = parsed.open_items;
+    brief.talking_points = parsed.talking_points;
+    Ok(brief)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::decisions::NewDecision;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_meeting_context_gathers_chats_and_open_items() {
+        crate::db::init_test_db();
+        let me = fake::user(1, "Me", "");
+        let ivan = fake::user(7451, "Ivan", "");
+        let judy = fake::user(7452, "Judy", "");
+        let client = FakeTelegram::new(me)
+            .with_chat(fake::chat(7451, "private", "Ivan", 0, 0))
+            .with_chat(fake::chat(7453, "group", "Launch", 0, 1))
+            .with_contact(judy.clone())
+            .with_messages(7451, vec![fake::text_message(1, 7451, &ivan, "Did you get the deck?", 100)])
+            .with_messages(7453, vec![
+                fake::text_message(2, 7453, &ivan, "Launch is Friday", 200),
+                fake::text_message(3, 7453, &judy, "I'll do the post", 300),
+            ]);
+
+        db_decisions::save_decisions(7453, "Launch", &[
+            NewDecision {
+                kind: KIND_COMMITMENT.to_string(),
+                text: "Write the launch post".to_string(),
+                owner: Some("Judy".to_string()),
+                due: None,
+                message_id: Some(3),
+                sender_name: Some("Judy".to_string()),
+                message_date: Some(300),
+            },
+            NewDecision {
+                kind: db_decisions::KIND_DECISION.to_string(),
+                text: "Launch on Friday".to_string(),
+                owner: None,
+                due: None,
+                message_id: Some(2),
+                sender_name: Some("Ivan".to_string()),
+                message_date: Some(200),
+            },
+        ])
+        .unwrap();
+        db_reminders::add_reminder(7451, "Ivan", "private", 0, Some("Send Ivan the deck")).unwrap();
+
+        // Judy has no DM: she is still a participant, through her contact
+        let context = gather_meeting_context(&client, &[7451, 7452], Some(7453)).await.unwrap();
+        assert_eq!(context.participants, vec!["Ivan", "Judy"]);
+        let chat_ids: Vec<i64> = context.chats.iter().map(|(c, _)| c.id).collect();
+        assert_eq!(chat_ids, vec![7453, 7451]);
+        assert_eq!(context.action_items.len(), 1);
+        assert_eq!(context.action_items[0].text, "Write the launch post");
+        assert_eq!(context.reminders.len(), 1);
+        assert!(context.failed_chats.is_empty());
+
+        // Only the group: whoever spoke in it
+        let context = gather_meeting_context(&client, &[], Some(7453)).await.unwrap();
+        assert_eq!(context.participants, vec!["Ivan", "Judy"]);
+
+        // A chat that can't be read is reported, the rest still make the brief
+        let client = client.with_fetch_error(7453, "CHANNEL_PRIVATE");
+        let context = gather_meeting_context(&client, &[7451], Some(7453)).await.unwrap();
+        assert_eq!(context.chats.iter().map(|(c, _)| c.id).collect::<Vec<_>>(), vec![7451]);
+        assert_eq!(context.failed_chats.len(), 1);
+        assert_eq!(context.failed_chats[0].chat_id, 7453);
+        assert_eq!(context.failed_chats[0].reason, "CHANNEL_PRIVATE");
+
+        assert!(gather_meeting_context(&client, &[], None).await.is_err());
+    }
+}
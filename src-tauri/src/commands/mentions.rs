@@ -0,0 +1,105 @@
+use crate::ai::context::message_text_for_ai;
+use crate::db::mentions::{self as db_mentions, MentionRecord, NewMention, KIND_MENTION, KIND_REPLY};
+use crate::telegram::{TelegramApi, client::Message};
+use std::collections::HashSet;
+
+const DEFAULT_MENTIONS_LIMIT: i64 = 200;
+
+/// Incoming messages Telegram flagged as mentioning the user. Replies to one of the
+/// user's messages in the same batch are tagged as replies; the rest as mentions.
+fn collect_mentions(chat_id: i64, chat_title: &str, messages: &[Message]) -> Vec<NewMention> {
+    let own_ids: HashSet<i64> = messages
+        .iter()
+        .filter(|m| m.is_outgoing)
+        .map(|m| m.id)
+        .collect();
+
+    messages
+        .iter()
+        .filter(|m| m.mentions_me && !m.is_outgoing)
+        .map(|m| {
+            let is_reply = m.reply_to_message_id.is_some_and(|id| own_ids.contains(&id));
+            NewMention {
+                chat_id,
+                chat_title: chat_title.to_string(),
+                message_id: m.id,
+                kind: if is_reply { KIND_REPLY } else { KIND_MENTION }.to_string(),
+                sender_id: m.sender_id,
+                sender_name: m.sender_name.clone(),
+                text: message_text_for_ai(&m.content),
+                message_date: m.date,
+            }
+        })
+        .collect()
+}
+
+/// Record mentions found in fetched history or live updates for one chat.
+/// Best-effort: failures are logged and never fail the caller.
+pub(crate) async fn record_mentions_from(client: &dyn TelegramApi, chat_id: i64, messages: &[Message]) {
+    if !messages.iter().any(|m| m.mentions_me && !m.is_outgoing) {
+        return;
+    }
+
+    let chat_title = match client.get_chat(chat_id).await {
+        Ok(Some(chat)) => chat.title,
+        _ => String::new(),
+    };
+
+    match db_mentions::record_mentions(&collect_mentions(chat_id, &chat_title, messages)) {
+        Ok(0) => {}
+        Ok(count) => log::info!("Recorded {} new mention(s) in chat {}", count, chat_id),
+        Err(e) => log::warn!("Failed to record mentions for chat {}: {}", chat_id, e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_mentions(
+    unseen_only: Option<bool>,
+    limit: Option<i64>,
+) -> Result<Vec<MentionRecord>, String> {
+    db_mentions::load_mentions(unseen_only.unwrap_or(false), limit.unwrap_or(DEFAULT_MENTIONS_LIMIT))
+}
+
+/// Mark mentions as seen; all of them when `ids` is omitted
+#[tauri::command]
+pub async fn mark_mentions_seen(ids: Option<Vec<i64>>) -> Result<usize, String> {
+    db_mentions::mark_mentions_seen(ids.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::client::MessageContent;
+
+    fn message(id: i64, is_outgoing: bool, reply_to: Option<i64>, mentions_me: bool) -> Message {
+        Message {
+            id,
+            chat_id: 1,
+            sender_id: if is_outgoing { 1 } else { 2 },
+            sender_name: "Bob".to_string(),
+            content: MessageContent::Text { text: format!("message {}", id) },
+            date: id,
+            is_outgoing,
+            is_read: true,
+            reply_to_message_id: reply_to,
+            mentions_me,
+            inline_keyboard: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_collect_mentions_tags_replies_to_own_messages() {
+        let messages = vec![
+            message(10, true, None, false),
+            message(11, false, Some(10), true),
+            message(12, false, None, true),
+            message(13, false, Some(99), true),
+            message(14, false, None, false),
+        ];
+
+        let mentions = collect_mentions(1, "Team", &messages);
+        let kinds: Vec<(i64, &str)> = mentions.iter().map(|m| (m.message_id, m.kind.as_str())).collect();
+        assert_eq!(kinds, vec![(11, KIND_REPLY), (12, KIND_MENTION), (13, KIND_MENTION)]);
+        assert_eq!(mentions[0].chat_title, "Team");
+    }
+}
@@ -1,7 +1,42 @@
 pub mod ai;
+pub mod archive;
 pub mod auth;
+pub mod away;
+pub mod bot_rules;
+pub mod calls;
+pub mod canned;
+pub mod channel_stats;
 pub mod chats;
 pub mod contacts;
+pub mod crosspost;
+pub mod decisions;
+pub mod deep_analysis;
+pub mod delivery;
+pub mod dossier;
+pub mod export;
+pub mod focus;
+pub mod followups;
+pub mod lists;
+pub mod media;
+pub mod meeting;
+pub mod mentions;
+pub mod notifications;
 pub mod offboard;
+pub mod onboarding;
+pub mod outbox;
 pub mod outreach;
+pub mod pending_drafts;
+pub mod pipelines;
+pub mod reminders;
+pub mod retention;
+pub mod rsvp;
+pub mod scheduled_posts;
+pub mod scope_suggestions;
 pub mod scopes;
+pub mod search;
+pub mod send_queue;
+pub mod standup;
+pub mod sync;
+pub mod triage;
+pub mod unread;
+pub mod welcome;
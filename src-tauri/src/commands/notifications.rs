@@ -0,0 +1,194 @@
+use crate::commands::calls::is_missed_call;
+use crate::commands::focus::focus_active;
+use crate::db;
+use crate::db::notifications::{NotificationRule, MODES, MODE_NONE, MODE_URGENT, MODE_VIP};
+use crate::db::settings::NotificationConfig;
+use crate::telegram::client::Message;
+use chrono::Timelike;
+use tauri::{AppHandle, Emitter};
+
+/// Facts about an incoming message that the rules are checked against
+struct NotifyContext {
+    /// The chat is classified urgent in the latest briefing, or the message is a missed call
+    is_urgent: bool,
+    sender_is_vip: bool,
+    /// Local time as minutes since midnight
+    minute_of_day: u32,
+}
+
+/// Parse a local "HH:MM" time into minutes since midnight
+pub(crate) fn parse_hhmm(value: &str) -> Option<u32> {
+    let (hours, minutes) = value.trim().split_once(':')?;
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// Whether `minute` falls in the configured quiet hours; a start after the end wraps past midnight
+fn in_quiet_hours(config: &NotificationConfig, minute: u32) -> bool {
+    in_daily_window(config.quiet_hours_start.as_deref(), config.quiet_hours_end.as_deref(), minute)
+}
+
+/// Whether `minute` falls in [start, end) of a daily "HH:MM" window; missing bounds mean no window
+pub(crate) fn in_daily_window(start: Option<&str>, end: Option<&str>, minute: u32) -> bool {
+    let (Some(start), Some(end)) = (start.and_then(parse_hhmm), end.and_then(parse_hhmm)) else {
+        return false;
+    };
+
+    if start <= end {
+        minute >= start && minute < end
+    } else {
+        minute >= start || minute < end
+    }
+}
+
+/// Apply the app-wide settings and the chat's rule (if any) to one message
+fn allows_notification(config: &NotificationConfig, rule: Option<&NotificationRule>, ctx: &NotifyContext) -> bool {
+    if !config.enabled {
+        return false;
+    }
+
+    let ignore_quiet_hours = rule.map(|r| r.ignore_quiet_hours).unwrap_or(false);
+    if !ignore_quiet_hours && in_quiet_hours(config, ctx.minute_of_day) {
+        return false;
+    }
+
+    match rule.map(|r| r.mode.as_str()).unwrap_or(config.default_mode.as_str()) {
+        MODE_URGENT => ctx.is_urgent,
+        MODE_VIP => ctx.sender_is_vip,
+        MODE_NONE => false,
+        _ => true,
+    }
+}
+
+fn chat_is_urgent(chat_id: i64) -> bool {
+    db::briefings::load_latest_briefing()
+        .ok()
+        .flatten()
+        .map(|briefing| {
+            briefing
+                .needs_response
+                .iter()
+                .any(|item| item.chat_id == chat_id && item.priority == "urgent")
+        })
+        .unwrap_or(false)
+}
+
+fn sender_is_vip(sender_id: i64, vip_tag: &str) -> bool {
+    db::contacts::get_contact_tags(sender_id)
+        .unwrap_or_default()
+        .iter()
+        .any(|tag| tag.eq_ignore_ascii_case(vip_tag))
+}
+
+/// Whether an incoming message should raise an in-app notification under the local rules
+/// (never while focus mode is on)
+pub(crate) fn should_notify(message: &Message) -> Result<bool, String> {
+    if message.is_outgoing || focus_active() {
+        return Ok(false);
+    }
+
+    let config = db::settings::load_notification_config()?;
+    let rule = db::notifications::load_rule(message.chat_id)?;
+    let ctx = NotifyContext {
+        is_urgent: is_missed_call(message) || chat_is_urgent(message.chat_id),
+        sender_is_vip: sender_is_vip(message.sender_id, &config.vip_tag),
+        minute_of_day: chrono::Local::now().num_seconds_from_midnight() / 60,
+    };
+    Ok(allows_notification(&config, rule.as_ref(), &ctx))
+}
+
+/// Emit `notify://message` for an incoming message if the rules allow it
+pub(crate) fn notify_if_allowed(app: &AppHandle, message: &Message) {
+    match should_notify(message) {
+        Ok(true) => {
+            let _ = app.emit("notify://message", message);
+        }
+        Ok(false) => {}
+        Err(e) => log::warn!("Failed to evaluate notification rules: {}", e),
+    }
+}
+
+#[tauri::command]
+pub async fn get_notification_config() -> Result<NotificationConfig, String> {
+    db::settings::load_notification_config()
+}
+
+#[tauri::command]
+pub async fn update_notification_config(config: NotificationConfig) -> Result<(), String> {
+    if !MODES.contains(&config.default_mode.as_str()) {
+        return Err(format!("Unknown notification mode: {}", config.default_mode));
+    }
+    for time in [&config.quiet_hours_start, &config.quiet_hours_end].into_iter().flatten() {
+        if parse_hhmm(time).is_none() {
+            return Err(format!("Invalid quiet hours time (expected HH:MM): {}", time));
+        }
+    }
+    db::settings::save_notification_config(&config)
+}
+
+#[tauri::command]
+pub async fn get_notification_rules() -> Result<Vec<NotificationRule>, String> {
+    db::notifications::load_rules()
+}
+
+/// Set the notification mode for one chat ("all", "urgent", "vip" or "none")
+#[tauri::command]
+pub async fn set_notification_rule(
+    chat_id: i64,
+    mode: String,
+    ignore_quiet_hours: Option<bool>,
+) -> Result<NotificationRule, String> {
+    if !MODES.contains(&mode.as_str()) {
+        return Err(format!("Unknown notification mode: {}", mode));
+    }
+    let rule = NotificationRule {
+        chat_id,
+        mode,
+        ignore_quiet_hours: ignore_quiet_hours.unwrap_or(false),
+    };
+    db::notifications::save_rule(&rule)?;
+    Ok(rule)
+}
+
+/// Remove a chat's rule so it falls back to the default mode
+#[tauri::command]
+pub async fn delete_notification_rule(chat_id: i64) -> Result<(), String> {
+    db::notifications::delete_rule(chat_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::notifications::MODE_ALL;
+
+    fn rule(mode: &str, ignore_quiet_hours: bool) -> NotificationRule {
+        NotificationRule { chat_id: 1, mode: mode.to_string(), ignore_quiet_hours }
+    }
+
+    #[test]
+    fn test_notification_rules() {
+        let config = NotificationConfig {
+            quiet_hours_start: Some("22:00".to_string()),
+            quiet_hours_end: Some("07:30".to_string()),
+            ..Default::default()
+        };
+        let ctx = |is_urgent, sender_is_vip, minute_of_day| NotifyContext { is_urgent, sender_is_vip, minute_of_day };
+
+        // Quiet hours wrap past midnight
+        assert!(allows_notification(&config, None, &ctx(false, false, 12 * 60)));
+        assert!(!allows_notification(&config, None, &ctx(false, false, 23 * 60)));
+        assert!(!allows_notification(&config, None, &ctx(false, false, 7 * 60)));
+        assert!(allows_notification(&config, None, &ctx(false, false, 7 * 60 + 30)));
+        assert!(allows_notification(&config, Some(&rule(MODE_ALL, true)), &ctx(false, false, 23 * 60)));
+
+        assert!(!allows_notification(&config, Some(&rule(MODE_URGENT, false)), &ctx(false, true, 12 * 60)));
+        assert!(allows_notification(&config, Some(&rule(MODE_URGENT, false)), &ctx(true, false, 12 * 60)));
+        assert!(allows_notification(&config, Some(&rule(MODE_VIP, false)), &ctx(false, true, 12 * 60)));
+        assert!(!allows_notification(&config, Some(&rule(MODE_NONE, false)), &ctx(true, true, 12 * 60)));
+
+        let disabled = NotificationConfig { enabled: false, ..Default::default() };
+        assert!(!allows_notification(&disabled, None, &ctx(true, true, 12 * 60)));
+        assert_eq!(parse_hhmm("24:00"), None);
+    }
+}
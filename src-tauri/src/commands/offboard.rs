@@ -1,3 +1,5 @@
+use crate::commands::contacts::log_event;
+use crate::db::contacts as db_contacts;
 use crate::telegram::TelegramClient;
 use grammers_tl_types as tl;
 use serde::{Deserialize, Serialize};
@@ -47,6 +49,16 @@ impl UserAccessHashCache {
         log::info!("[Offboard] Cached {} user access hashes", cache.len());
         Ok(())
     }
+
+    /// A user's access hash, refreshing the cache from contacts when it's missing
+    pub async fn get_or_refresh(&self, client: &TelegramClient, user_id: i64) -> Result<Option<i64>, String> {
+        if let Some(access_hash) = self.get(user_id).await {
+            return Ok(Some(access_hash));
+        }
+        log::info!("[Offboard] Access hash not found, refreshing contacts cache");
+        self.populate_from_contacts(client).await?;
+        Ok(self.get(user_id).await)
+    }
 }
 
 impl Default for UserAccessHashCache {
@@ -91,17 +103,7 @@ pub async fn get_common_groups(
 ) -> Result<Vec<CommonGroup>, String> {
     log::info!("[Offboard] Getting common groups for user {}", user_id);
 
-    // Try to get access hash from cache
-    let mut access_hash = user_hash_cache.get(user_id).await;
-
-    // If not in cache, populate cache from contacts
-    if access_hash.is_none() {
-        log::info!("[Offboard] Access hash not found, refreshing contacts cache");
-        user_hash_cache.populate_from_contacts(&client).await?;
-        access_hash = user_hash_cache.get(user_id).await;
-    }
-
-    let access_hash = access_hash.ok_or_else(|| {
+    let access_hash = user_hash_cache.get_or_refresh(&client, user_id).await?.ok_or_else(|| {
         format!("User {} not found in contacts. Cannot lookup common groups.", user_id)
     })?;
 
@@ -151,6 +153,13 @@ pub async fn remove_from_group(
     // Perform the kick
     client.kick_chat_member(&chat, user_id, user_access_hash).await?;
 
+    let title = match &chat {
+        tl::enums::Chat::Chat(c) => c.title.clone(),
+        tl::enums::Chat::Channel(c) => c.title.clone(),
+        _ => chat_id.to_string(),
+    };
+    log_event(user_id, db_contacts::EVENT_REMOVED_FROM_GROUP, &title);
+
     log::info!("[Offboard] Successfully removed user {} from chat {}", user_id, chat_id);
     Ok(())
 }
@@ -0,0 +1,140 @@
+use crate::ai::LLMClient;
+use crate::db::{self, settings as db_settings};
+use crate::telegram::{TelegramClient, client::AuthState};
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::State;
+
+pub const STEP_TELEGRAM_CREDENTIALS: &str = "telegram_credentials";
+pub const STEP_LOGGED_IN: &str = "logged_in";
+pub const STEP_CHAT_FILTERS: &str = "chat_filters";
+pub const STEP_LLM_CONFIGURED: &str = "llm_configured";
+pub const STEP_SCOPE_CREATED: &str = "scope_created";
+
+/// Setup steps in the order the flow presents them
+const STEPS: [(&str, &str); 5] = [
+    (STEP_TELEGRAM_CREDENTIALS, "Telegram API credentials"),
+    (STEP_LOGGED_IN, "Sign in to Telegram"),
+    (STEP_CHAT_FILTERS, "Choose which chats to include"),
+    (STEP_LLM_CONFIGURED, "Configure an AI provider"),
+    (STEP_SCOPE_CREATED, "Create a scope"),
+];
+
+/// Steps the user can't skip: without them the app can't run at all
+const REQUIRED_STEPS: [&str; 2] = [STEP_TELEGRAM_CREDENTIALS, STEP_LOGGED_IN];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingStep {
+    pub id: String,
+    pub label: String,
+    /// The prerequisite is met (checked live; None when it can only be marked complete)
+    pub satisfied: Option<bool>,
+    /// Marked complete or skipped by the user
+    pub completed: bool,
+    pub done: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingState {
+    pub steps: Vec<OnboardingStep>,
+    /// First step that isn't done, if any
+    pub next_step: Option<String>,
+    pub complete: bool,
+}
+
+fn build_state(checks: &[(&str, Option<bool>)], completed: &[String]) -> OnboardingState {
+    let steps: Vec<OnboardingStep> = STEPS
+        .iter()
+        .map(|(id, label)| {
+            let satisfied = checks.iter().find(|(check, _)| check == id).and_then(|(_, ok)| *ok);
+            let completed = completed.iter().any(|step| step == id);
+            OnboardingStep {
+                id: id.to_string(),
+                label: label.to_string(),
+                satisfied,
+                completed,
+                done: satisfied == Some(true) || completed,
+            }
+        })
+        .collect();
+
+    let next_step = steps.iter().find(|s| !s.done).map(|s| s.id.clone());
+    OnboardingState { complete: next_step.is_none(), next_step, steps }
+}
+
+async fn current_state(client: &TelegramClient, llm: &LLMClient) -> Result<OnboardingState, String> {
+    let logged_in = matches!(client.get_auth_state().await, AuthState::Ready);
+    let checks = [
+        (STEP_TELEGRAM_CREDENTIALS, Some(client.has_api_credentials())),
+        (STEP_LOGGED_IN, Some(logged_in)),
+        (STEP_CHAT_FILTERS, None),
+        (STEP_LLM_CONFIGURED, Some(llm.is_configured().await)),
+        (STEP_SCOPE_CREATED, Some(!db::scopes::list_scopes()?.is_empty())),
+    ];
+    Ok(build_state(&checks, &db_settings::load_onboarding_steps()?))
+}
+
+/// Which setup prerequisites are met and which step comes next
+#[tauri::command]
+pub async fn get_onboarding_state(
+    client: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+) -> Result<OnboardingState, String> {
+    current_state(&client, &llm).await
+}
+
+/// Mark a step complete (or skipped) and return the updated state
+#[tauri::command]
+pub async fn complete_onboarding_step(
+    client: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+    step: String,
+) -> Result<OnboardingState, String> {
+    if !STEPS.iter().any(|(id, _)| *id == step) {
+        return Err(format!("Unknown onboarding step: {}", step));
+    }
+    if REQUIRED_STEPS.contains(&step.as_str()) {
+        return Err(format!("Onboarding step {} can't be skipped", step));
+    }
+
+    let mut completed = db_settings::load_onboarding_steps()?;
+    if !completed.contains(&step) {
+        completed.push(step);
+        db_settings::save_onboarding_steps(&completed)?;
+    }
+    current_state(&client, &llm).await
+}
+
+/// Forget completed steps, e.g. after signing in with a different account
+#[tauri::command]
+pub async fn reset_onboarding() -> Result<(), String> {
+    db_settings::clear_onboarding_steps()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_state() {
+        let checks = [
+            (STEP_TELEGRAM_CREDENTIALS, Some(true)),
+            (STEP_LOGGED_IN, Some(true)),
+            (STEP_CHAT_FILTERS, None),
+            (STEP_LLM_CONFIGURED, Some(false)),
+            (STEP_SCOPE_CREATED, Some(false)),
+        ];
+
+        let state = build_state(&checks, &[]);
+        assert_eq!(state.next_step.as_deref(), Some(STEP_CHAT_FILTERS));
+        assert!(!state.complete);
+
+        let completed = vec![STEP_CHAT_FILTERS.to_string(), STEP_LLM_CONFIGURED.to_string(), STEP_SCOPE_CREATED.to_string()];
+        let state = build_state(&checks, &completed);
+        assert!(state.complete);
+        assert_eq!(state.steps[3].satisfied, Some(false));
+        assert!(state.steps[3].done);
+    }
+}
@@ -0,0 +1,208 @@
+//! Outbox for messages sent while offline: a send that fails with a connection
+//! error is stored and retried when the client reconnects (and periodically while
+//! anything is waiting), with `outbox://sent` / `outbox://failed` events reporting
+//! the final outcome. A connection error doesn't prove the message never arrived,
+//! so each retry first checks the chat for a copy that already went out.
+
+use crate::db::outbox::{self as db_outbox, OutboxEntry, STATUS_FAILED, STATUS_PENDING, STATUS_SENT};
+use crate::telegram::client::{AuthState, Message, MessageContent};
+use crate::telegram::{TelegramApi, TelegramClient};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+/// Send attempts, including the first one, before a message is given up on
+const MAX_ATTEMPTS: i64 = 10;
+
+/// How often waiting messages are retried when no reconnect has happened
+const RETRY_INTERVAL_SECS: u64 = 60;
+
+/// Recent messages searched for a copy of a queued message before it's retried
+const DELIVERED_LOOKBACK: i32 = 20;
+
+/// Only one flush at a time, so a message can't go out twice
+static FLUSH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Final outcome of an outbox message
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxResult {
+    /// Outbox id; the placeholder returned when it was queued has id `-id`
+    pub id: i64,
+    pub chat_id: i64,
+    pub message: Option<Message>,
+    pub error: Option<String>,
+}
+
+/// Send a message, queueing it in the outbox if the client is offline. A queued
+/// message comes back as a placeholder whose id is the negated outbox id.
+pub(crate) async fn send_or_queue_offline(
+    client: &dyn TelegramApi,
+    chat_id: i64,
+    text: &str,
+) -> Result<Message, String> {
+    match client.send_message(chat_id, text).await {
+        Err(e) if TelegramClient::is_connection_error(&e) => {
+            let id = db_outbox::add_entry(chat_id, text, &e)?;
+            log::warn!("Offline, queued message to chat {} in outbox ({}): {}", chat_id, id, e);
+            Ok(Message {
+                id: -id,
+                chat_id,
+                sender_id: client.get_current_user().await.map(|u| u.id).unwrap_or(0),
+                sender_name: "You".to_string(),
+                content: MessageContent::Text { text: text.to_string() },
+                date: chrono::Utc::now().timestamp(),
+                is_outgoing: true,
+                is_read: false,
+                reply_to_message_id: None,
+                mentions_me: false,
+                inline_keyboard: Vec::new(),
+            })
+        }
+        result => result,
+    }
+}
+
+/// The outgoing message with the entry's text sent since it was queued, if an
+/// attempt that reported a connection error actually got through
+async fn delivered_copy(client: &dyn TelegramApi, entry: &OutboxEntry) -> Result<Option<Message>, String> {
+    let recent = client.get_chat_messages(entry.chat_id, DELIVERED_LOOKBACK, None).await?;
+    Ok(recent.into_iter().find(|m| {
+        m.is_outgoing
+            && m.date >= entry.created_at - RETRY_INTERVAL_SECS as i64
+            && matches!(&m.content, MessageContent::Text { text } if *text == entry.text)
+    }))
+}
+
+/// Retry waiting messages in order, stopping at the first one that still can't
+/// connect. Returns the messages that were sent or given up on.
+pub(crate) async fn flush_outbox(client: &dyn TelegramApi) -> Result<Vec<OutboxResult>, String> {
+    let Ok(_guard) = FLUSH_LOCK.try_lock() else {
+        return Ok(Vec::new());
+    };
+
+    let mut results = Vec::new();
+    for entry in db_outbox::load_pending()? {
+        let result = |message, error| OutboxResult { id: entry.id, chat_id: entry.chat_id, message, error };
+        let sent = match delivered_copy(client, &entry).await {
+            Ok(Some(message)) => {
+                log::info!("Outbox message {} was already delivered, not resending", entry.id);
+                Ok(message)
+            }
+            Ok(None) => client.send_message(entry.chat_id, &entry.text).await,
+            Err(e) => Err(e),
+        };
+        match sent {
+            Ok(message) => {
+                db_outbox::record_attempt(entry.id, STATUS_SENT, None)?;
+                results.push(result(Some(message), None));
+            }
+            Err(e) if TelegramClient::is_connection_error(&e) && entry.attempts + 1 < MAX_ATTEMPTS => {
+                db_outbox::record_attempt(entry.id, STATUS_PENDING, Some(&e))?;
+                break;
+            }
+            Err(e) => {
+                log::warn!("Giving up on outbox message {}: {}", entry.id, e);
+                db_outbox::record_attempt(entry.id, STATUS_FAILED, Some(&e))?;
+                results.push(result(None, Some(e)));
+            }
+        }
+    }
+    Ok(results)
+}
+
+/// Flush the outbox and report each final outcome to the frontend
+pub(crate) async fn flush_and_emit(app: &AppHandle, client: &dyn TelegramApi) {
+    match flush_outbox(client).await {
+        Ok(results) => {
+            for result in results {
+                let event = if result.error.is_none() { "outbox://sent" } else { "outbox://failed" };
+                let _ = app.emit(event, result);
+            }
+        }
+        Err(e) => log::warn!("Failed to flush outbox: {}", e),
+    }
+}
+
+/// Retry waiting messages periodically, for outages that end without a reconnect event
+pub async fn outbox_loop(app: AppHandle, client: Arc<TelegramClient>) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(RETRY_INTERVAL_SECS)).await;
+        let waiting = db_outbox::load_pending().map(|p| !p.is_empty()).unwrap_or(false);
+        if waiting && matches!(client.get_auth_state().await, AuthState::Ready) {
+            flush_and_emit(&app, client.as_ref()).await;
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_outbox() -> Result<Vec<OutboxEntry>, String> {
+    db_outbox::load_pending()
+}
+
+/// Retry waiting messages now
+#[tauri::command]
+pub async fn retry_outbox(app: AppHandle, client: State<'_, Arc<TelegramClient>>) -> Result<(), String> {
+    flush_and_emit(&app, client.inner().as_ref()).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn cancel_outbox_message(id: i64) -> Result<(), String> {
+    db_outbox::cancel_entry(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_offline_sends_wait_in_outbox() {
+        crate::db::init_test_db();
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(fake::chat(7431, "private", "Grace", 0, 0))
+            .with_chat(fake::chat(7432, "private", "Heidi", 0, 1))
+            .with_send_error(7431, "IO failed: connection reset")
+            .with_send_error(7432, "USER_PRIVACY_RESTRICTED");
+
+        // Only connection failures are queued
+        assert!(send_or_queue_offline(&client, 7432, "Hi").await.is_err());
+        let placeholder = send_or_queue_offline(&client, 7431, "See you at 5").await.unwrap();
+        assert!(placeholder.id < 0 && placeholder.is_outgoing);
+
+        // Still offline: nothing sent, the message keeps waiting
+        assert!(flush_outbox(&client).await.unwrap().is_empty());
+        let waiting: Vec<OutboxEntry> = db_outbox::load_pending().unwrap().into_iter().filter(|e| e.chat_id == 7431).collect();
+        assert_eq!(waiting.len(), 1);
+        assert_eq!(waiting[0].attempts, 2);
+
+        client.clear_send_error(7431);
+        let results = flush_outbox(&client).await.unwrap();
+        assert!(results.iter().any(|r| r.id == -placeholder.id && r.message.is_some()));
+        assert_eq!(client.sent(), vec![(7431, "See you at 5".to_string())]);
+        assert!(db_outbox::load_pending().unwrap().iter().all(|e| e.chat_id != 7431));
+    }
+
+    #[tokio::test]
+    async fn test_outbox_does_not_resend_a_delivered_message() {
+        crate::db::init_test_db();
+        let me = fake::user(1, "Me", "");
+        // The send timed out, but the message reached Telegram
+        let delivered = Message {
+            is_outgoing: true,
+            ..fake::text_message(50, 7433, &me, "On my way", chrono::Utc::now().timestamp())
+        };
+        let client = FakeTelegram::new(me)
+            .with_chat(fake::chat(7433, "private", "Ivan", 0, 0))
+            .with_messages(7433, vec![delivered])
+            .with_send_error(7433, "request timed out");
+
+        let placeholder = send_or_queue_offline(&client, 7433, "On my way").await.unwrap();
+        let results = flush_outbox(&client).await.unwrap();
+        let result = results.iter().find(|r| r.id == -placeholder.id).unwrap();
+        assert_eq!(result.message.as_ref().map(|m| m.id), Some(50));
+        assert!(db_outbox::load_pending().unwrap().iter().all(|e| e.chat_id != 7433));
+    }
+}
@@ -1,18 +1,25 @@
+use crate::commands::focus::focus_active;
 use crate::db;
-use crate::telegram::TelegramClient;
+use crate::telegram::{TelegramApi, TelegramClient};
 use crate::utils::rate_limiter::RateLimiter;
+use crate::utils::template::{contact_variables, fill_placeholders};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tauri::State;
 use tokio::sync::RwLock;
 use tokio::time::{sleep, Duration, Instant};
 
+/// How often a queue held by focus mode checks whether it can resume
+const FOCUS_RECHECK_SECS: u64 = 5;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OutreachRecipient {
     pub user_id: i64,
     pub first_name: String,
     pub last_name: String,
+    #[serde(default)]
+    pub username: Option<String>,
     pub status: String,
     pub error: Option<String>,
     pub sent_at: Option<i64>,
@@ -190,7 +197,7 @@ impl Default for OutreachManager {
 }
 
 /// Extract flood wait seconds from error message
-fn extract_flood_wait_seconds(error_msg: &str) -> Option<u64> {
+pub(crate) fn extract_flood_wait_seconds(error_msg: &str) -> Option<u64> {
     // Look for patterns like "FLOOD_WAIT_60" or "wait for 60 seconds"
     let error_lower = error_msg.to_lowercase();
 
@@ -217,21 +224,16 @@ fn extract_flood_wait_seconds(error_msg: &str) -> Option<u64> {
     Some(60)
 }
 
+/// Log line for an outreach send. Logs end up in debug bundles, so it carries
+/// the chat id and length, never the message itself.
+pub(crate) fn sending_log_line(user_id: i64, message: &str) -> String {
+    format!("[Outreach] Sending to {} ({} chars)", user_id, message.chars().count())
+}
+
 /// Personalize a message template with contact info
-fn personalize_message(template: &str, first_name: &str, last_name: &str) -> String {
-    let first = if first_name.is_empty() { "there" } else { first_name };
-    let last = last_name;
-    let full = if last.is_empty() {
-        first.to_string()
-    } else {
-        format!("{} {}", first, last)
-    };
-
-    template
-        .replace("{name}", first)
-        .replace("{first_name}", first)
-        .replace("{last_name}", last)
-        .replace("{full_name}", &full)
+fn personalize_message(template: &str, recipient: &OutreachRecipient) -> String {
+    let variables = contact_variables(&recipient.first_name, &recipient.last_name, recipient.username.as_deref());
+    fill_placeholders(template, &variables)
 }
 
 #[tauri::command]
@@ -241,7 +243,18 @@ pub async fn queue_outreach_messages(
     rate_limiter: State<'_, Arc<RateLimiter>>,
     recipient_ids: Vec<i64>,
     template: String,
+    list_id: Option<String>,
 ) -> Result<String, String> {
+    // Members of a contact list are appended (in list order) to the explicit recipients
+    let mut recipient_ids = recipient_ids;
+    if let Some(list_id) = list_id {
+        for user_id in db::lists::members_of(&[list_id])? {
+            if !recipient_ids.contains(&user_id) {
+                recipient_ids.push(user_id);
+            }
+        }
+    }
+
     log::info!("[Outreach] Starting outreach to {} recipients", recipient_ids.len());
 
     if recipient_ids.is_empty() {
@@ -252,117 +265,146 @@ pub async fn queue_outreach_messages(
         return Err("Message template is empty".to_string());
     }
 
-    // Fetch contacts to get names for personalization
-    let contacts = client.get_contacts().await?;
-
-    // Build recipient list with names
-    let recipients: Vec<OutreachRecipient> = recipient_ids
-        .iter()
-        .map(|&user_id| {
-            let contact = contacts.iter().find(|c| c.id == user_id);
-            OutreachRecipient {
-                user_id,
-                first_name: contact.map(|c| c.first_name.clone()).unwrap_or_default(),
-                last_name: contact.map(|c| c.last_name.clone()).unwrap_or_default(),
-                status: "pending".to_string(),
-                error: None,
-                sent_at: None,
-            }
-        })
-        .collect();
+    let recipients = build_recipients(client.inner().as_ref(), &recipient_ids).await?;
 
     // Create the queue
     let queue_id = manager.create_queue(recipients.clone(), template.clone()).await?;
     log::info!("[Outreach] Created queue {}", queue_id);
 
     // Clone what we need for the background task
-    let client = Arc::clone(&client);
+    let client: Arc<dyn TelegramApi> = client.inner().clone();
     let manager = Arc::clone(&manager);
     let limiter = Arc::clone(&rate_limiter);
-    let queue_id_clone = queue_id.clone();
 
     // Spawn background task to process the queue
-    tauri::async_runtime::spawn(async move {
-        log::info!("[Outreach] Starting to process queue {}", queue_id_clone);
-
-        for recipient in recipients.iter() {
-            // Check if cancelled
-            if manager.is_cancelled(&queue_id_clone).await {
-                log::info!("[Outreach] Queue {} was cancelled", queue_id_clone);
-                break;
-            }
+    tauri::async_runtime::spawn(process_queue(client, manager, limiter, queue_id.clone(), template, recipients));
 
-            // Use rate limiter to wait for appropriate time
-            let wait_result = limiter.can_send(recipient.user_id);
-            if let Err(wait_secs) = wait_result {
-                log::info!("[Outreach] Rate limiter: waiting {} seconds for user {}", wait_secs, recipient.user_id);
-
-                // Wait in small increments to check for cancellation
-                let target_time = Instant::now() + Duration::from_secs(wait_secs);
-                while Instant::now() < target_time {
-                    if manager.is_cancelled(&queue_id_clone).await {
-                        log::info!("[Outreach] Queue {} was cancelled during rate limit wait", queue_id_clone);
-                        return;
-                    }
-                    sleep(Duration::from_secs(1)).await;
+    Ok(queue_id)
+}
+
+/// Pending recipients, named from the user's contacts (or, for non-contacts, their
+/// chat title) for personalization. The names are stored with the queue.
+async fn build_recipients(client: &dyn TelegramApi, recipient_ids: &[i64]) -> Result<Vec<OutreachRecipient>, String> {
+    let contacts = client.get_contacts().await?;
+
+    let mut recipients = Vec::with_capacity(recipient_ids.len());
+    for &user_id in recipient_ids {
+        let (first_name, last_name, username) = match contacts.iter().find(|c| c.id == user_id) {
+            Some(c) => (c.first_name.clone(), c.last_name.clone(), c.username.clone()),
+            None => match client.get_chat(user_id).await.ok().flatten() {
+                Some(chat) => (chat.title, String::new(), None),
+                None => (String::new(), String::new(), None),
+            },
+        };
+        recipients.push(OutreachRecipient {
+            user_id,
+            first_name,
+            last_name,
+            username,
+            status: "pending".to_string(),
+            error: None,
+            sent_at: None,
+        });
+    }
+    Ok(recipients)
+}
+
+/// Send a queue's messages one by one, honouring cancellation, focus mode and rate limits
+async fn process_queue(
+    client: Arc<dyn TelegramApi>,
+    manager: Arc<OutreachManager>,
+    limiter: Arc<RateLimiter>,
+    queue_id: String,
+    template: String,
+    recipients: Vec<OutreachRecipient>,
+) {
+    log::info!("[Outreach] Starting to process queue {}", queue_id);
+
+    for recipient in recipients.iter() {
+        // Check if cancelled
+        if manager.is_cancelled(&queue_id).await {
+            log::info!("[Outreach] Queue {} was cancelled", queue_id);
+            break;
+        }
+
+        // Hold the queue while focus mode is on
+        if focus_active() {
+            log::info!("[Outreach] Focus mode on, deferring queue {}", queue_id);
+            while focus_active() {
+                if manager.is_cancelled(&queue_id).await {
+                    log::info!("[Outreach] Queue {} was cancelled during focus mode", queue_id);
+                    return;
                 }
+                sleep(Duration::from_secs(FOCUS_RECHECK_SECS)).await;
             }
+        }
 
-            // Final cancellation check before sending
-            if manager.is_cancelled(&queue_id_clone).await {
-                log::info!("[Outreach] Queue {} was cancelled before sending", queue_id_clone);
-                break;
+        // Use rate limiter to wait for appropriate time
+        let wait_result = limiter.can_send(recipient.user_id);
+        if let Err(wait_secs) = wait_result {
+            log::info!("[Outreach] Rate limiter: waiting {} seconds for user {}", wait_secs, recipient.user_id);
+
+            // Wait in small increments to check for cancellation
+            let target_time = Instant::now() + Duration::from_secs(wait_secs);
+            while Instant::now() < target_time {
+                if manager.is_cancelled(&queue_id).await {
+                    log::info!("[Outreach] Queue {} was cancelled during rate limit wait", queue_id);
+                    return;
+                }
+                sleep(Duration::from_secs(1)).await;
             }
+        }
+
+        // Final cancellation check before sending
+        if manager.is_cancelled(&queue_id).await {
+            log::info!("[Outreach] Queue {} was cancelled before sending", queue_id);
+            break;
+        }
 
-            // Personalize the message
-            let message = personalize_message(&template, &recipient.first_name, &recipient.last_name);
-            log::info!(
-                "[Outreach] Sending to {} ({}): {}",
-                recipient.first_name,
-                recipient.user_id,
-                &message[..message.floor_char_boundary(50)]
-            );
-
-            // Send the message - user_id is the chat_id for DMs
-            match client.send_message(recipient.user_id, &message).await {
-                Ok(_) => {
-                    log::info!("[Outreach] Successfully sent to {}", recipient.user_id);
-                    limiter.record_send(recipient.user_id);
-                    manager
-                        .update_recipient_status(&queue_id_clone, recipient.user_id, "sent", None)
-                        .await;
+        // Personalize the message
+        let message = personalize_message(&template, recipient);
+        log::info!("{}", sending_log_line(recipient.user_id, &message));
+
+        // Send the message - user_id is the chat_id for DMs
+        match client.send_message(recipient.user_id, &message).await {
+            Ok(_) => {
+                log::info!("[Outreach] Successfully sent to {}", recipient.user_id);
+                limiter.record_send(recipient.user_id);
+                manager
+                    .update_recipient_status(&queue_id, recipient.user_id, "sent", None)
+                    .await;
+                if let Err(e) = db::pipelines::advance_to_role(recipient.user_id, db::pipelines::ROLE_CONTACTED) {
+                    log::warn!("[Outreach] Failed to advance pipeline stage for {}: {}", recipient.user_id, e);
                 }
-                Err(e) => {
-                    log::error!("[Outreach] Failed to send to {}: {}", recipient.user_id, e);
-
-                    // Check for flood wait errors
-                    let error_msg = e.to_string();
-                    if error_msg.to_lowercase().contains("flood") {
-                        // Extract wait time from error message (e.g., "FLOOD_WAIT_X")
-                        if let Some(wait_secs) = extract_flood_wait_seconds(&error_msg) {
-                            log::warn!("[Outreach] FLOOD_WAIT received, adding {} seconds to rate limiter", wait_secs);
-                            limiter.handle_flood_wait(wait_secs);
-                        }
+            }
+            Err(e) => {
+                log::error!("[Outreach] Failed to send to {}: {}", recipient.user_id, e);
+
+                // Check for flood wait errors
+                let error_msg = e.to_string();
+                if error_msg.to_lowercase().contains("flood") {
+                    // Extract wait time from error message (e.g., "FLOOD_WAIT_X")
+                    if let Some(wait_secs) = extract_flood_wait_seconds(&error_msg) {
+                        log::warn!("[Outreach] FLOOD_WAIT received, adding {} seconds to rate limiter", wait_secs);
+                        limiter.handle_flood_wait(wait_secs);
                     }
-
-                    manager
-                        .update_recipient_status(
-                            &queue_id_clone,
-                            recipient.user_id,
-                            "failed",
-                            Some(error_msg),
-                        )
-                        .await;
                 }
+
+                manager
+                    .update_recipient_status(
+                        &queue_id,
+                        recipient.user_id,
+                        "failed",
+                        Some(error_msg),
+                    )
+                    .await;
             }
         }
+    }
 
-        // Mark queue as completed
-        manager.complete_queue(&queue_id_clone).await;
-        log::info!("[Outreach] Queue {} completed", queue_id_clone);
-    });
-
-    Ok(queue_id)
+    // Mark queue as completed
+    manager.complete_queue(&queue_id).await;
+    log::info!("[Outreach] Queue {} completed", queue_id);
 }
 
 #[tauri::command]
@@ -380,3 +422,44 @@ pub async fn cancel_outreach(
 ) -> Result<(), String> {
     manager.cancel(&queue_id).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::client::User;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_outreach_queue_against_fake_backend() {
+        db::init_test_db();
+        let ann = User { username: Some("ann_lee".to_string()), ..fake::user(20, "Ann", "Lee") };
+        let telegram = Arc::new(
+            FakeTelegram::new(fake::user(1, "Me", ""))
+                .with_contact(ann)
+                .with_contact(fake::user(21, "Bob", ""))
+                .with_send_error(21, "USER_PRIVACY_RESTRICTED"),
+        );
+        let manager = Arc::new(OutreachManager::new());
+
+        let recipients = build_recipients(telegram.as_ref(), &[20, 21, 22]).await.unwrap();
+        assert_eq!(recipients[0].first_name, "Ann");
+        assert_eq!(recipients[2].first_name, "");
+
+        let template = "Hi {name} ({username})!".to_string();
+        let queue_id = manager.create_queue(recipients.clone(), template.clone()).await.unwrap();
+        process_queue(telegram.clone(), manager.clone(), Arc::new(RateLimiter::new(0)), queue_id.clone(), template, recipients)
+            .await;
+
+        assert_eq!(telegram.sent(), vec![(20, "Hi Ann (@ann_lee)!".to_string())]);
+        let queue = manager.get_status(&queue_id).await.unwrap();
+        assert_eq!((queue.status.as_str(), queue.sent_count, queue.failed_count), ("completed", 1, 2));
+        let failure = queue.recipients.iter().find(|r| r.user_id == 21).and_then(|r| r.error.clone());
+        assert_eq!(failure.as_deref(), Some("USER_PRIVACY_RESTRICTED"));
+
+        // Names survive a reload from the database
+        let stored = db::with_db(|conn| db::outreach::load_queue(conn, &queue_id)).unwrap().unwrap();
+        let names: Vec<(&str, &str)> =
+            stored.recipients.iter().map(|r| (r.first_name.as_str(), r.last_name.as_str())).collect();
+        assert_eq!(names, vec![("Ann", "Lee"), ("Bob", ""), ("", "")]);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::commands::outreach::extract_flood_wait_seconds;
+use crate::db;
+use crate::db::pending_drafts::{self as db_drafts, PendingDraft, SOURCE_AI, STATUS_REJECTED, STATUS_SENT};
+use crate::telegram::client::Message;
+use crate::telegram::{TelegramApi, TelegramClient};
+use crate::utils::rate_limiter::RateLimiter;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+
+/// What happened to an automated reply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Delivery {
+    Sent,
+    /// Held in the pending drafts queue for approval
+    Queued,
+}
+
+/// Send an automated reply, or queue it as a pending draft when approval is required
+pub(crate) async fn send_or_queue(
+    client: &dyn TelegramApi,
+    chat_id: i64,
+    text: &str,
+    source: &str,
+) -> Result<Delivery, String> {
+    deliver(client, chat_id, text, source, db::settings::load_draft_approval()?).await
+}
+
+async fn deliver(
+    client: &dyn TelegramApi,
+    chat_id: i64,
+    text: &str,
+    source: &str,
+    require_approval: bool,
+) -> Result<Delivery, String> {
+    if require_approval {
+        db_drafts::add_draft(chat_id, text, source)?;
+        log::info!("Queued {} reply to chat {} for approval", source, chat_id);
+        return Ok(Delivery::Queued);
+    }
+    client.send_message(chat_id, text).await?;
+    Ok(Delivery::Sent)
+}
+
+/// Emit `drafts://pending-count` with the number of drafts awaiting approval
+pub(crate) fn emit_pending_count(app: &AppHandle) {
+    match db_drafts::count_pending() {
+        Ok(count) => {
+            let _ = app.emit("drafts://pending-count", count);
+        }
+        Err(e) => log::warn!("Failed to count pending drafts: {}", e),
+    }
+}
+
+/// Send a pending draft, optionally with edited text. Approved drafts share the
+/// app-wide send budget, so a burst of approvals is paced like any other sends.
+pub(crate) async fn approve(
+    client: &dyn TelegramApi,
+    limiter: &RateLimiter,
+    id: i64,
+    text: Option<String>,
+) -> Result<Message, String> {
+    let draft = db_drafts::load_draft(id)?.ok_or_else(|| format!("Draft {} not found", id))?;
+    let text = text.filter(|t| !t.trim().is_empty()).unwrap_or(draft.text);
+    // Claim the draft before sending so a double click can't send it twice, and
+    // put it back in the queue (with the edited text) if it can't go out
+    db_drafts::resolve_draft(id, STATUS_SENT, &text)?;
+    if let Err(wait) = limiter.can_send(draft.chat_id) {
+        db_drafts::reopen_draft(id)?;
+        return Err(format!("Rate limited for {}s", wait));
+    }
+    let result = client.send_message(draft.chat_id, &text).await;
+    match &result {
+        Ok(_) => limiter.record_send(draft.chat_id),
+        Err(e) => {
+            if e.to_lowercase().contains("flood") {
+                if let Some(wait_secs) = extract_flood_wait_seconds(e) {
+                    limiter.handle_flood_wait(wait_secs);
+                }
+            }
+            db_drafts::reopen_draft(id)?;
+        }
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn get_draft_approval() -> Result<bool, String> {
+    db::settings::load_draft_approval()
+}
+
+/// When on, automated replies (away mode, reply rules) wait in the pending drafts queue
+#[tauri::command]
+pub async fn set_draft_approval(enabled: bool) -> Result<(), String> {
+    db::settings::save_draft_approval(enabled)
+}
+
+#[tauri::command]
+pub async fn get_pending_drafts() -> Result<Vec<PendingDraft>, String> {
+    db_drafts::load_pending()
+}
+
+/// Queue a reply for approval instead of sending it (e.g. an AI suggestion)
+#[tauri::command]
+pub async fn queue_draft(app: AppHandle, chat_id: i64, text: String, source: Option<String>) -> Result<i64, String> {
+    if text.trim().is_empty() {
+        return Err("Draft is empty".to_string());
+    }
+    let id = db_drafts::add_draft(chat_id, &text, source.as_deref().unwrap_or(SOURCE_AI))?;
+    emit_pending_count(&app);
+    Ok(id)
+}
+
+/// Approve a pending draft and send it, with `text` replacing the drafted text if given
+#[tauri::command]
+pub async fn approve_draft(
+    app: AppHandle,
+    client: State<'_, Arc<TelegramClient>>,
+    limiter: State<'_, Arc<RateLimiter>>,
+    id: i64,
+    text: Option<String>,
+) -> Result<Message, String> {
+    let result = approve(client.inner().as_ref(), limiter.inner().as_ref(), id, text).await;
+    emit_pending_count(&app);
+    result
+}
+
+#[tauri::command]
+pub async fn reject_draft(app: AppHandle, id: i64) -> Result<(), String> {
+    let draft = db_drafts::load_draft(id)?.ok_or_else(|| format!("Draft {} not found", id))?;
+    db_drafts::resolve_draft(id, STATUS_REJECTED, &draft.text)?;
+    emit_pending_count(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::pending_drafts::SOURCE_RULE;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_drafts_wait_for_approval() {
+        crate::db::init_test_db();
+        let client = FakeTelegram::new(fake::user(1, "Me", "")).with_chat(fake::chat(7411, "private", "Eve", 0, 0));
+        let limiter = RateLimiter::new(0);
+
+        assert_eq!(deliver(&client, 7411, "Thanks!", SOURCE_RULE, true).await.unwrap(), Delivery::Queued);
+        assert_eq!(deliver(&client, 7411, "Got it", SOURCE_RULE, true).await.unwrap(), Delivery::Queued);
+        assert!(client.sent().is_empty());
+
+        let drafts: Vec<PendingDraft> = db_drafts::load_pending().unwrap().into_iter().filter(|d| d.chat_id == 7411).collect();
+        assert_eq!(drafts.len(), 2);
+
+        approve(&client, &limiter, drafts[0].id, Some("Thanks a lot!".to_string())).await.unwrap();
+        assert!(approve(&client, &limiter, drafts[0].id, None).await.is_err());
+        db_drafts::resolve_draft(drafts[1].id, STATUS_REJECTED, &drafts[1].text).unwrap();
+
+        assert_eq!(client.sent(), vec![(7411, "Thanks a lot!".to_string())]);
+        assert!(db_drafts::load_pending().unwrap().iter().all(|d| d.chat_id != 7411));
+    }
+
+    #[tokio::test]
+    async fn test_failed_approval_keeps_the_draft_pending() {
+        crate::db::init_test_db();
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(fake::chat(7412, "private", "Finn", 0, 0))
+            .with_send_error(7412, "CHAT_WRITE_FORBIDDEN");
+        let limiter = RateLimiter::new(0);
+
+        let id = db_drafts::add_draft(7412, "See you then", SOURCE_RULE).unwrap();
+        assert!(approve(&client, &limiter, id, Some("See you at 5".to_string())).await.is_err());
+        let draft = db_drafts::load_draft(id).unwrap().unwrap();
+        assert_eq!(draft.status, db_drafts::STATUS_PENDING);
+        assert_eq!(draft.text, "See you at 5");
+
+        client.clear_send_error(7412);
+        approve(&client, &limiter, id, None).await.unwrap();
+        assert_eq!(client.sent(), vec![(7412, "See you at 5".to_string())]);
+        assert_eq!(db_drafts::load_draft(id).unwrap().unwrap().status, STATUS_SENT);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_approval_keeps_the_draft_pending() {
+        crate::db::init_test_db();
+        let client = FakeTelegram::new(fake::user(1, "Me", "")).with_chat(fake::chat(7413, "private", "Gus", 0, 0));
+        let limiter = RateLimiter::new(60);
+        limiter.record_send(7413);
+
+        let id = db_drafts::add_draft(7413, "On my way", SOURCE_RULE).unwrap();
+        let err = approve(&client, &limiter, id, Some("On my way!".to_string())).await.unwrap_err();
+        assert!(err.contains("Rate limited"), "{}", err);
+        assert!(client.sent().is_empty());
+        let draft = db_drafts::load_draft(id).unwrap().unwrap();
+        assert_eq!((draft.status.as_str(), draft.text.as_str()), (db_drafts::STATUS_PENDING, "On my way!"));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::commands::contacts::log_event;
+use crate::commands::followups::private_dialog_filters;
+use crate::db::contacts as db_contacts;
+use crate::db::pipelines::{
+    self as db_pipelines, Pipeline, PipelineEntry, PipelineStage, ROLE_REPLIED,
+};
+use crate::telegram::TelegramClient;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineColumn {
+    pub stage: PipelineStage,
+    pub contacts: Vec<PipelineEntry>,
+}
+
+/// Kanban-style snapshot of a pipeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineBoard {
+    pub pipeline: Pipeline,
+    pub columns: Vec<PipelineColumn>,
+}
+
+/// Advance contacts in a "Contacted" stage to "Replied" when their DM's last
+/// message is an incoming one newer than when they entered the stage.
+async fn advance_replied_contacts(client: &TelegramClient) -> Result<usize, String> {
+    let waiting = db_pipelines::entries_with_role(db_pipelines::ROLE_CONTACTED)?;
+    if waiting.is_empty() {
+        return Ok(0);
+    }
+
+    let chats = client.get_chats(200, Some(private_dialog_filters())).await?;
+    let last_incoming: HashMap<i64, i64> = chats
+        .iter()
+        .filter_map(|chat| {
+            let msg = chat.last_message.as_ref()?;
+            (!msg.is_outgoing).then_some((chat.id, msg.date))
+        })
+        .collect();
+
+    let mut advanced = 0;
+    for (_, user_id, entered_at) in waiting {
+        if last_incoming.get(&user_id).is_some_and(|date| *date > entered_at) {
+            advanced += db_pipelines::advance_to_role(user_id, ROLE_REPLIED)?;
+        }
+    }
+
+    if advanced > 0 {
+        log::info!("[Pipelines] Advanced {} contacts to Replied", advanced);
+    }
+    Ok(advanced)
+}
+
+#[tauri::command]
+pub async fn list_pipelines() -> Result<Vec<Pipeline>, String> {
+    db_pipelines::ensure_default_pipeline()?;
+    db_pipelines::list_pipelines()
+}
+
+#[tauri::command]
+pub async fn create_pipeline(name: String, stages: Vec<String>) -> Result<Pipeline, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Pipeline name is empty".to_string());
+    }
+    let stages: Vec<String> = stages
+        .into_iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if stages.is_empty() {
+        return Err("A pipeline needs at least one stage".to_string());
+    }
+    db_pipelines::create_pipeline(name, &stages)
+}
+
+#[tauri::command]
+pub async fn delete_pipeline(pipeline_id: String) -> Result<(), String> {
+    db_pipelines::delete_pipeline(&pipeline_id)
+}
+
+#[tauri::command]
+pub async fn move_contact_to_stage(
+    pipeline_id: String,
+    user_id: i64,
+    stage_id: i64,
+) -> Result<(), String> {
+    db_pipelines::set_contact_stage(&pipeline_id, user_id, stage_id)?;
+
+    if let Some(pipeline) = db_pipelines::load_pipeline(&pipeline_id)? {
+        if let Some(stage) = pipeline.stages.iter().find(|s| s.id == stage_id) {
+            let detail = format!("{}: {}", pipeline.name, stage.name);
+            log_event(user_id, db_contacts::EVENT_PIPELINE_STAGE, &detail);
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_contact_from_pipeline(pipeline_id: String, user_id: i64) -> Result<(), String> {
+    db_pipelines::remove_contact(&pipeline_id, user_id)
+}
+
+/// Pipeline with contacts grouped by stage. With `check_replies`, contacts in the
+/// "Contacted" stage who have since replied are advanced first.
+#[tauri::command]
+pub async fn get_pipeline_board(
+    client: State<'_, Arc<TelegramClient>>,
+    pipeline_id: String,
+    check_replies: Option<bool>,
+) -> Result<PipelineBoard, String> {
+    if check_replies.unwrap_or(false) {
+        if let Err(e) = advance_replied_contacts(&client).await {
+            log::warn!("[Pipelines] Failed to check replies: {}", e);
+        }
+    }
+
+    let pipeline = db_pipelines::load_pipeline(&pipeline_id)?
+        .ok_or_else(|| format!("Pipeline not found: {}", pipeline_id))?;
+    let entries = db_pipelines::list_entries(&pipeline_id)?;
+
+    let columns = pipeline
+        .stages
+        .iter()
+        .map(|stage| PipelineColumn {
+            stage: stage.clone(),
+            contacts: entries
+                .iter()
+                .filter(|e| e.stage_id == stage.id)
+                .cloned()
+                .collect(),
+        })
+        .collect();
+
+    Ok(PipelineBoard { pipeline, columns })
+}
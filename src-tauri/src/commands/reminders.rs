@@ -0,0 +1,72 @@
+use crate::db;
+use crate::db::reminders::ChatReminder;
+use crate::telegram::{TelegramApi, TelegramClient};
+use std::sync::Arc;
+use tauri::State;
+
+/// Reminders due now, for the briefing
+pub fn due_reminders() -> Result<Vec<ChatReminder>, String> {
+    db::reminders::load_pending(Some(chrono::Utc::now().timestamp()))
+}
+
+async fn add_chat_reminder(
+    client: &dyn TelegramApi,
+    chat_id: i64,
+    at: i64,
+    note: Option<String>,
+) -> Result<ChatReminder, String> {
+    let mut chat = client
+        .get_chat(chat_id)
+        .await?
+        .ok_or_else(|| format!("Chat {} not found", chat_id))?;
+    db::apply_local_chat_state(std::slice::from_mut(&mut chat));
+    let note = note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    db::reminders::add_reminder(chat_id, &chat.title, &chat.chat_type, at, note)
+}
+
+/// Resurface a chat in the briefing at `at` (unix seconds), with an optional note
+#[tauri::command]
+pub async fn remind_about_chat(
+    client: State<'_, Arc<TelegramClient>>,
+    chat_id: i64,
+    at: i64,
+    note: Option<String>,
+) -> Result<ChatReminder, String> {
+    add_chat_reminder(client.inner().as_ref(), chat_id, at, note).await
+}
+
+#[tauri::command]
+pub async fn get_due_chat_reminders() -> Result<Vec<ChatReminder>, String> {
+    due_reminders()
+}
+
+#[tauri::command]
+pub async fn complete_chat_reminder(id: i64) -> Result<(), String> {
+    db::reminders::complete_reminder(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_reminders_come_due() {
+        db::init_test_db();
+        let telegram = FakeTelegram::new(fake::user(1, "Me", "")).with_chat(fake::chat(7361, "group", "Sponsors", 0, 1));
+        let now = chrono::Utc::now().timestamp();
+
+        let due = add_chat_reminder(&telegram, 7361, now - 60, Some(" ask about March ".to_string())).await.unwrap();
+        let later = add_chat_reminder(&telegram, 7361, now + 3600, None).await.unwrap();
+        assert_eq!((due.chat_title.as_str(), due.note.as_deref()), ("Sponsors", Some("ask about March")));
+        assert!(add_chat_reminder(&telegram, 7362, now, None).await.is_err());
+
+        let ids = |reminders: Vec<ChatReminder>| reminders.iter().filter(|r| r.chat_id == 7361).map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(ids(due_reminders().unwrap()), vec![due.id]);
+        assert_eq!(ids(db::reminders::load_pending(None).unwrap()), vec![due.id, later.id]);
+
+        db::reminders::complete_reminder(due.id).unwrap();
+        assert!(ids(due_reminders().unwrap()).is_empty());
+        assert!(db::reminders::complete_reminder(due.id).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use crate::db;
+use crate::db::integrity::IntegrityReport;
+use crate::db::retention::PurgeStats;
+use crate::db::settings::RetentionConfig;
+use tokio::time::{sleep, Duration};
+
+/// How often the background task enforces the retention policy
+const RETENTION_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+/// Apply the saved retention policy once
+pub fn run_retention() -> Result<PurgeStats, String> {
+    let config = db::settings::load_retention()?;
+    let stats = db::retention::purge_expired(&config)?;
+    log::info!(
+        "[Retention] Purged {} briefings, {} outreach queues, {} activity events, {} triage entries, {} cached LLM responses, {} archived messages, {} derived rows",
+        stats.briefings,
+        stats.outreach_queues,
+        stats.activity_events,
+        stats.triage_entries,
+        stats.llm_cache_entries,
+        stats.archived_messages,
+        stats.derived_rows
+    );
+    Ok(stats)
+}
+
+/// Enforce the retention policy now and then every few hours for the life of the app
+pub async fn retention_loop() {
+    loop {
+        if let Err(e) = run_retention() {
+            log::warn!("[Retention] Cleanup failed: {}", e);
+        }
+        sleep(Duration::from_secs(RETENTION_INTERVAL_SECS)).await;
+    }
+}
+
+#[tauri::command]
+pub async fn get_retention_config() -> Result<RetentionConfig, String> {
+    db::settings::load_retention()
+}
+
+/// Save the retention policy and apply it right away
+#[tauri::command]
+pub async fn update_retention_config(config: RetentionConfig) -> Result<PurgeStats, String> {
+    db::settings::save_retention(&config)?;
+    run_retention()
+}
+
+#[tauri::command]
+pub async fn purge_expired_data() -> Result<PurgeStats, String> {
+    run_retention()
+}
+
+/// Check the database for corruption, reindexing it or replacing it with a
+/// fresh one (the old file is kept alongside) when needed
+#[tauri::command]
+pub async fn repair_database() -> Result<IntegrityReport, String> {
+    let report = db::repair_db()?;
+    if report.lost_data() {
+        db::settings::save_integrity_notice(&report)?;
+    }
+    Ok(report)
+}
+
+/// The last check that moved data aside, if the user hasn't acknowledged it yet
+#[tauri::command]
+pub async fn get_integrity_notice() -> Result<Option<IntegrityReport>, String> {
+    db::settings::load_integrity_notice()
+}
+
+#[tauri::command]
+pub async fn acknowledge_integrity_notice() -> Result<(), String> {
+    db::settings::clear_integrity_notice()
+}
@@ -0,0 +1,263 @@
+//! Event RSVPs for community managers: post an announcement to several groups,
+//! then collect who's coming from replies to it (picked up from incoming
+//! messages) and reactions on it (read from Telegram when the RSVPs are viewed).
+
+use crate::db::rsvp::{
+    self as db_rsvp, RsvpEvent, RsvpResponse, SOURCE_REACTION, SOURCE_REPLY, STATUS_GOING, STATUS_MAYBE,
+    STATUS_NOT_GOING,
+};
+use crate::telegram::client::{Message, MessageContent};
+use crate::telegram::{TelegramApi, TelegramClient};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+
+/// Pause between posting the announcement to consecutive groups
+const POST_DELAY: Duration = Duration::from_secs(2);
+
+const GOING_EMOJI: [&str; 7] = ["👍", "❤", "❤️", "🔥", "🎉", "👌", "✅"];
+const NOT_GOING_EMOJI: [&str; 2] = ["👎", "❌"];
+const MAYBE_EMOJI: [&str; 2] = ["🤔", "🤷"];
+
+/// The longest phrase found in a reply decides, so "not sure" is a maybe, "not going"
+/// isn't a yes and "no problem" isn't a no. Equal lengths are checked in this order.
+const MAYBE_PHRASES: [&str; 5] = ["maybe", "not sure", "might", "tentative", "possibly"];
+const NOT_GOING_PHRASES: [&str; 9] = ["no", "nope", "-1", "can't", "cannot", "cant", "won't", "not going", "not coming"];
+const GOING_PHRASES: [&str; 12] = [
+    "yes", "yep", "yeah", "+1", "going", "coming", "i'm in", "im in", "count me in", "i'll be there", "sure",
+    "no problem",
+];
+
+fn status_from_reaction(emoji: &str) -> Option<&'static str> {
+    if GOING_EMOJI.contains(&emoji) {
+        Some(STATUS_GOING)
+    } else if NOT_GOING_EMOJI.contains(&emoji) {
+        Some(STATUS_NOT_GOING)
+    } else if MAYBE_EMOJI.contains(&emoji) {
+        Some(STATUS_MAYBE)
+    } else {
+        None
+    }
+}
+
+/// RSVP status of a reply, or None when it doesn't read as an answer
+fn status_from_reply(text: &str) -> Option<&'static str> {
+    let trimmed = text.trim();
+    if let Some(status) = status_from_reaction(trimmed) {
+        return Some(status);
+    }
+
+    // Words separated by single spaces, keeping the characters phrases are made of
+    let normalized: String = trimmed
+        .to_lowercase()
+        .replace('’', "'")
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '\'' | '+' | '-') { c } else { ' ' })
+        .collect();
+    let padded = format!(" {} ", normalized.split_whitespace().collect::<Vec<_>>().join(" "));
+    let tagged = |phrases: &'static [&'static str], status: &'static str| phrases.iter().map(move |p| (*p, status));
+
+    tagged(&MAYBE_PHRASES, STATUS_MAYBE)
+        .chain(tagged(&NOT_GOING_PHRASES, STATUS_NOT_GOING))
+        .chain(tagged(&GOING_PHRASES, STATUS_GOING))
+        .filter(|(phrase, _)| padded.contains(&format!(" {} ", phrase)))
+        .min_by_key(|(phrase, _)| std::cmp::Reverse(phrase.len()))
+        .map(|(_, status)| status)
+}
+
+/// Post an announcement to each chat, `delay` apart. Chats that fail are skipped;
+/// it's an error only if the announcement couldn't be posted anywhere.
+pub(crate) async fn post_event_to_chats(
+    client: &dyn TelegramApi,
+    title: &str,
+    text: &str,
+    chat_ids: &[i64],
+    delay: Duration,
+) -> Result<RsvpEvent, String> {
+    if chat_ids.is_empty() {
+        return Err("Pick at least one chat to post the event to".to_string());
+    }
+    let event_id = db_rsvp::create_event(title, text)?;
+
+    let mut last_error = None;
+    for (idx, chat_id) in chat_ids.iter().enumerate() {
+        if idx > 0 {
+            tokio::time::sleep(delay).await;
+        }
+        let chat_title = client
+            .get_chat(*chat_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|c| c.title)
+            .unwrap_or_else(|| chat_id.to_string());
+        match client.send_message(*chat_id, text).await {
+            Ok(message) => db_rsvp::add_post(event_id, *chat_id, &chat_title, message.id)?,
+            Err(e) => {
+                log::warn!("Failed to post event {} to {}: {}", event_id, chat_title, e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    let event = db_rsvp::load_event(event_id)?.ok_or("Event disappeared while posting")?;
+    if event.posts.is_empty() {
+        db_rsvp::delete_event(event_id)?;
+        return Err(format!(
+            "Failed to post the event: {}",
+            last_error.unwrap_or_else(|| "no chat accepted it".to_string())
+        ));
+    }
+    Ok(event)
+}
+
+/// Record a reply to an event announcement as an RSVP, if it reads as one
+pub(crate) fn record_rsvp_reply(message: &Message) -> Result<Option<RsvpResponse>, String> {
+    let Some(reply_to) = message.reply_to_message_id.filter(|_| !message.is_outgoing) else {
+        return Ok(None);
+    };
+    let MessageContent::Text { text } = &message.content else {
+        return Ok(None);
+    };
+    let Some(event_id) = db_rsvp::event_for_message(message.chat_id, reply_to)? else {
+        return Ok(None);
+    };
+    let Some(status) = status_from_reply(text) else {
+        return Ok(None);
+    };
+
+    let response = RsvpResponse {
+        event_id,
+        user_id: message.sender_id,
+        name: message.sender_name.clone(),
+        chat_id: message.chat_id,
+        status: status.to_string(),
+        source: SOURCE_REPLY.to_string(),
+        response: text.clone(),
+        responded_at: message.date,
+    };
+    db_rsvp::save_response(&response)?;
+    log::info!("RSVP {} from {} for event {}", status, message.sender_id, event_id);
+    Ok(Some(response))
+}
+
+/// Record an incoming message as an RSVP if it answers an event announcement
+pub(crate) fn apply_rsvp_replies(message: &Message) {
+    if let Err(e) = record_rsvp_reply(message) {
+        log::warn!("Failed to record RSVP reply: {}", e);
+    }
+}
+
+/// Re-read the reactions on each of an event's announcements
+async fn refresh_reactions(client: &TelegramClient, event: &RsvpEvent) -> Result<(), String> {
+    for post in &event.posts {
+        let reactions = client.get_message_reactions(post.chat_id, post.message_id).await?;
+        db_rsvp::clear_reactions(event.id, post.chat_id)?;
+        for reaction in reactions {
+            let Some(emoji) = reaction.emoji else { continue };
+            let Some(status) = status_from_reaction(&emoji) else { continue };
+            db_rsvp::save_response(&RsvpResponse {
+                event_id: event.id,
+                user_id: reaction.user_id,
+                name: reaction.name,
+                chat_id: post.chat_id,
+                status: status.to_string(),
+                source: SOURCE_REACTION.to_string(),
+                response: emoji,
+                responded_at: reaction.date,
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Post an event announcement to the given groups to collect RSVPs
+#[tauri::command]
+pub async fn post_event(
+    client: State<'_, Arc<TelegramClient>>,
+    title: String,
+    text: String,
+    chat_ids: Vec<i64>,
+) -> Result<RsvpEvent, String> {
+    log::info!("Posting event '{}' to {} chats", title, chat_ids.len());
+    post_event_to_chats(client.inner().as_ref(), &title, &text, &chat_ids, POST_DELAY).await
+}
+
+#[tauri::command]
+pub async fn get_events() -> Result<Vec<RsvpEvent>, String> {
+    db_rsvp::load_events()
+}
+
+/// Who answered an event, with reactions re-read from Telegram first
+#[tauri::command]
+pub async fn get_event_rsvps(
+    client: State<'_, Arc<TelegramClient>>,
+    event_id: i64,
+) -> Result<Vec<RsvpResponse>, String> {
+    let event = db_rsvp::load_event(event_id)?.ok_or_else(|| format!("Event {} not found", event_id))?;
+    // Replies are already recorded; stale reactions are better than none
+    if let Err(e) = refresh_reactions(&client, &event).await {
+        log::warn!("Failed to refresh reactions for event {}: {}", event_id, e);
+    }
+    db_rsvp::load_responses(event_id)
+}
+
+#[tauri::command]
+pub async fn delete_event(event_id: i64) -> Result<(), String> {
+    db_rsvp::delete_event(event_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[test]
+    fn test_status_from_reply() {
+        assert_eq!(status_from_reply("Yes!"), Some(STATUS_GOING));
+        assert_eq!(status_from_reply("count me in"), Some(STATUS_GOING));
+        assert_eq!(status_from_reply("+1"), Some(STATUS_GOING));
+        assert_eq!(status_from_reply(" 👍 "), Some(STATUS_GOING));
+        assert_eq!(status_from_reply("Sorry, can’t make it"), Some(STATUS_NOT_GOING));
+        assert_eq!(status_from_reply("not going this time"), Some(STATUS_NOT_GOING));
+        assert_eq!(status_from_reply("Not sure yet"), Some(STATUS_MAYBE));
+        assert_eq!(status_from_reply("Yes, no problem"), Some(STATUS_GOING));
+        assert_eq!(status_from_reply("No, not sure I can"), Some(STATUS_MAYBE));
+        assert_eq!(status_from_reply("What time does it start?"), None);
+        assert_eq!(status_from_reply("I know the venue"), None);
+    }
+
+    #[tokio::test]
+    async fn test_replies_to_event_posts_are_rsvps() {
+        crate::db::init_test_db();
+        let carol = fake::user(7483, "Carol", "");
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(fake::chat(7481, "group", "Hikers", 0, 0))
+            .with_chat(fake::chat(7482, "group", "Climbers", 0, 1))
+            .with_send_error(7482, "CHAT_WRITE_FORBIDDEN");
+
+        let event = post_event_to_chats(&client, "Hike", "Hike on Saturday, who's in?", &[7481, 7482], Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(event.posts.len(), 1);
+        let post = &event.posts[0];
+        assert_eq!((post.chat_id, post.chat_title.as_str()), (7481, "Hikers"));
+
+        let reply = |id, text: &str, date| Message {
+            reply_to_message_id: Some(post.message_id),
+            ..fake::text_message(id, 7481, &carol, text, date)
+        };
+        assert!(record_rsvp_reply(&reply(1, "What should I bring?", 100)).unwrap().is_none());
+        assert!(record_rsvp_reply(&reply(2, "Yes, I'm in", 200)).unwrap().is_some());
+        // A later change of mind wins; an older answer arriving late doesn't
+        record_rsvp_reply(&reply(3, "Sorry, can't make it", 300)).unwrap();
+        record_rsvp_reply(&reply(4, "maybe", 250)).unwrap();
+
+        let responses = db_rsvp::load_responses(event.id).unwrap();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].status, STATUS_NOT_GOING);
+        assert_eq!(responses[0].name, "Carol");
+
+        assert!(post_event_to_chats(&client, "Climb", "Climb?", &[7482], Duration::ZERO).await.is_err());
+    }
+}
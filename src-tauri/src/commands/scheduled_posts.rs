@@ -0,0 +1,228 @@
+//! Content calendar for channels: posts (text, or a file with a caption) planned
+//! for channels and groups the user administers, published by a background loop
+//! when their time comes. Failed attempts are retried with backoff before a post
+//! is marked failed; `posts://published` / `posts://failed` report the outcome.
+
+use crate::commands::outreach::extract_flood_wait_seconds;
+use crate::db::scheduled_posts::{self as db_posts, ScheduledPost, STATUS_SENT};
+use crate::telegram::client::AuthState;
+use crate::telegram::{TelegramApi, TelegramClient};
+use crate::utils::rate_limiter::RateLimiter;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+/// Attempts, including the first one, before a post is marked failed
+const MAX_ATTEMPTS: i64 = 5;
+
+/// First retry delay; each later one is three times longer, so the retries of a
+/// post span over three hours (5m, 15m, 45m, 2h15m)
+const RETRY_BASE_SECS: i64 = 300;
+
+/// How often the calendar is checked for posts that are due
+const POLL_INTERVAL_SECS: u64 = 30;
+
+/// Only one publishing run at a time, so a post can't go out twice
+static PUBLISH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Delay before retrying a post that has now failed `failures` times
+fn retry_delay_secs(failures: i64) -> i64 {
+    RETRY_BASE_SECS * 3i64.pow(failures.clamp(1, MAX_ATTEMPTS) as u32 - 1)
+}
+
+/// Publish the posts that are due. Returns those that went out or were given up
+/// on; posts held by the rate limiter or waiting for a retry stay scheduled.
+pub(crate) async fn publish_due_posts(
+    client: &dyn TelegramApi,
+    limiter: &RateLimiter,
+    now: i64,
+) -> Result<Vec<ScheduledPost>, String> {
+    let Ok(_guard) = PUBLISH_LOCK.try_lock() else {
+        return Ok(Vec::new());
+    };
+
+    let mut finished = Vec::new();
+    for post in db_posts::load_due(now)? {
+        if limiter.can_send(post.chat_id).is_err() {
+            continue;
+        }
+
+        let result = match post.media_path.as_deref() {
+            Some(path) => client.send_media(post.chat_id, &post.text, path).await,
+            None => client.send_message(post.chat_id, &post.text).await,
+        };
+        match result {
+            Ok(message) => {
+                log::info!("Published scheduled post {} to {}", post.id, post.chat_title);
+                limiter.record_send(post.chat_id);
+                db_posts::record_sent(post.id, message.id)?;
+            }
+            Err(e) => {
+                let retry_at = if post.attempts + 1 >= MAX_ATTEMPTS {
+                    None
+                } else if e.to_lowercase().contains("flood") {
+                    let wait_secs = extract_flood_wait_seconds(&e).unwrap_or(60);
+                    limiter.handle_flood_wait(wait_secs);
+                    Some(now + wait_secs as i64)
+                } else {
+                    Some(now + retry_delay_secs(post.attempts + 1))
+                };
+                match retry_at {
+                    Some(at) => log::warn!("Scheduled post {} failed, retrying at {}: {}", post.id, at, e),
+                    None => log::error!("Giving up on scheduled post {}: {}", post.id, e),
+                }
+                db_posts::record_failure(post.id, &e, retry_at)?;
+                if retry_at.is_some() {
+                    continue;
+                }
+            }
+        }
+        finished.extend(db_posts::load_post(post.id)?);
+    }
+    Ok(finished)
+}
+
+/// Publish due posts whenever the client is signed in
+pub async fn scheduled_posts_loop(app: AppHandle, client: Arc<TelegramClient>, limiter: Arc<RateLimiter>) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)).await;
+        if !matches!(client.get_auth_state().await, AuthState::Ready) {
+            continue;
+        }
+        match publish_due_posts(client.as_ref(), &limiter, chrono::Utc::now().timestamp()).await {
+            Ok(posts) => {
+                for post in posts {
+                    let event = if post.status == STATUS_SENT { "posts://published" } else { "posts://failed" };
+                    let _ = app.emit(event, post);
+                }
+            }
+            Err(e) => log::warn!("Failed to publish scheduled posts: {}", e),
+        }
+    }
+}
+
+/// Check that a post has something to send and that its file is there
+fn validate_post(text: &str, media_path: Option<&str>) -> Result<(), String> {
+    match media_path {
+        Some(path) if !std::path::Path::new(path).is_file() => Err(format!("File not found: {}", path)),
+        Some(_) => Ok(()),
+        None if text.trim().is_empty() => Err("The post is empty".to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Channels and groups the user can schedule posts for
+#[tauri::command]
+pub async fn get_postable_chats(client: State<'_, Arc<TelegramClient>>) -> Result<Vec<i64>, String> {
+    client.get_postable_chat_ids().await
+}
+
+/// Schedule a post for `send_at` (unix seconds) in a channel or group the user administers
+#[tauri::command]
+pub async fn schedule_post(
+    client: State<'_, Arc<TelegramClient>>,
+    chat_id: i64,
+    text: String,
+    media_path: Option<String>,
+    send_at: i64,
+) -> Result<ScheduledPost, String> {
+    let media_path = media_path.filter(|p| !p.trim().is_empty());
+    validate_post(&text, media_path.as_deref())?;
+    if !client.get_postable_chat_ids().await?.contains(&chat_id) {
+        return Err("You can only schedule posts for channels and groups you administer".to_string());
+    }
+
+    let chat_title = client
+        .get_chat(chat_id)
+        .await?
+        .map(|c| c.title)
+        .unwrap_or_else(|| chat_id.to_string());
+    let id = db_posts::create_post(chat_id, &chat_title, &text, media_path.as_deref(), send_at)?;
+    log::info!("Scheduled post {} for {} at {}", id, chat_title, send_at);
+    db_posts::load_post(id)?.ok_or_else(|| "Scheduled post disappeared".to_string())
+}
+
+/// Posts planned between `from` and `to` (unix seconds), for the calendar view
+#[tauri::command]
+pub async fn get_scheduled_posts(from: i64, to: i64) -> Result<Vec<ScheduledPost>, String> {
+    db_posts::load_posts(from, to)
+}
+
+#[tauri::command]
+pub async fn update_scheduled_post(
+    id: i64,
+    text: String,
+    media_path: Option<String>,
+    send_at: i64,
+) -> Result<ScheduledPost, String> {
+    let media_path = media_path.filter(|p| !p.trim().is_empty());
+    validate_post(&text, media_path.as_deref())?;
+    db_posts::update_post(id, &text, media_path.as_deref(), send_at)?;
+    db_posts::load_post(id)?.ok_or_else(|| format!("Post {} not found", id))
+}
+
+/// Send a failed post again now
+#[tauri::command]
+pub async fn retry_scheduled_post(id: i64) -> Result<(), String> {
+    db_posts::retry_post(id)
+}
+
+#[tauri::command]
+pub async fn cancel_scheduled_post(id: i64) -> Result<(), String> {
+    db_posts::cancel_post(id)
+}
+
+#[tauri::command]
+pub async fn delete_scheduled_post(id: i64) -> Result<(), String> {
+    db_posts::delete_post(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::scheduled_posts::{STATUS_FAILED, STATUS_SCHEDULED};
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_publish_due_posts_with_retries() {
+        crate::db::init_test_db();
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(fake::chat(7501, "channel", "Release Notes", 0, 0))
+            .with_chat(fake::chat(7502, "channel", "Dev Blog", 0, 1))
+            .with_send_error(7502, "CHAT_ADMIN_REQUIRED");
+        let limiter = RateLimiter::new(0);
+        let now = 1_900_000_000;
+
+        let photo = db_posts::create_post(7501, "Release Notes", "v2 is out", Some("/tmp/banner.png"), now - 10).unwrap();
+        let later = db_posts::create_post(7501, "Release Notes", "Tomorrow's post", None, now + 3600).unwrap();
+        let blocked = db_posts::create_post(7502, "Dev Blog", "Write-up", None, now - 5).unwrap();
+
+        let finished = publish_due_posts(&client, &limiter, now).await.unwrap();
+        assert_eq!(finished.iter().map(|p| p.id).collect::<Vec<_>>(), vec![photo]);
+        assert_eq!(client.sent(), vec![(7501, "[banner.png] v2 is out".to_string())]);
+        assert_eq!(db_posts::load_post(later).unwrap().unwrap().status, STATUS_SCHEDULED);
+
+        // The failing post waits out its backoff, then is retried until it runs out of attempts
+        let post = db_posts::load_post(blocked).unwrap().unwrap();
+        assert_eq!((post.status.as_str(), post.attempts), (STATUS_SCHEDULED, 1));
+        publish_due_posts(&client, &limiter, now + 60).await.unwrap();
+        assert_eq!(db_posts::load_post(blocked).unwrap().unwrap().attempts, 1);
+        let mut at = now;
+        for failures in 1..MAX_ATTEMPTS {
+            at += retry_delay_secs(failures);
+            publish_due_posts(&client, &limiter, at).await.unwrap();
+        }
+        assert_eq!(at - now, 300 + 900 + 2700 + 8100);
+        let post = db_posts::load_post(blocked).unwrap().unwrap();
+        assert_eq!((post.status.as_str(), post.last_error.as_deref()), (STATUS_FAILED, Some("CHAT_ADMIN_REQUIRED")));
+
+        // A manual retry goes through once the channel accepts posts again
+        client.clear_send_error(7502);
+        db_posts::retry_post(blocked).unwrap();
+        publish_due_posts(&client, &limiter, chrono::Utc::now().timestamp()).await.unwrap();
+        assert_eq!(db_posts::load_post(blocked).unwrap().unwrap().status, STATUS_SENT);
+
+        assert!(validate_post("  ", None).is_err());
+        assert!(validate_post("", Some("/nonexistent/banner.png")).is_err());
+    }
+}
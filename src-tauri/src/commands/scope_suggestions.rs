@@ -0,0 +1,183 @@
+use crate::ai::client::{safe_json_parse, CallTimeout};
+use crate::ai::cluster::{kmeans, suggested_cluster_count};
+use crate::ai::context::message_text_for_ai;
+use crate::ai::prompts::{format_scope_naming_user_prompt, SCOPE_NAMING_SYSTEM_PROMPT};
+use crate::ai::sanitize::{sanitize_chat_title, sanitize_message_text};
+use crate::ai::types::{AIScopeNamesResponse, OpenAIMessage};
+use crate::ai::LLMClient;
+use crate::db::scopes::ScopeConfig;
+use crate::telegram::client::{BatchMessageRequest, Chat, ChatFilters};
+use crate::telegram::TelegramClient;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::State;
+
+/// Recent messages per chat that go into its embedding
+const MESSAGES_PER_CHAT: i32 = 20;
+
+/// Characters of title + messages embedded per chat
+const EMBEDDING_TEXT_MAX_CHARS: usize = 2000;
+
+const MAX_SUGGESTIONS: usize = 12;
+
+/// Chat titles per group shown to the LLM when naming it
+const TITLES_PER_GROUP: usize = 15;
+
+/// Proposed scope; `config` can be passed straight to `save_scope`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeSuggestion {
+    pub name: String,
+    pub chat_ids: Vec<i64>,
+    pub chat_titles: Vec<String>,
+    pub config: ScopeConfig,
+}
+
+/// Title plus recent message text, the input embedded for one chat
+fn chat_embedding_text(chat: &Chat, messages: &[crate::telegram::client::Message]) -> String {
+    let mut text = sanitize_chat_title(&chat.title);
+    for message in messages.iter().rev() {
+        if text.chars().count() >= EMBEDDING_TEXT_MAX_CHARS {
+            break;
+        }
+        text.push('\n');
+        text.push_str(&sanitize_message_text(&message_text_for_ai(&message.content)));
+    }
+    text.chars().take(EMBEDDING_TEXT_MAX_CHARS).collect()
+}
+
+/// Cluster recent chats by topic (embeddings of their titles and recent messages)
+/// and propose a named scope for each cluster, to bootstrap scope setup.
+#[tauri::command]
+pub async fn suggest_scopes(
+    client: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+    limit: Option<i32>,
+) -> Result<Vec<ScopeSuggestion>, String> {
+    let limit = limit.unwrap_or(200);
+    log::info!("Suggesting scopes from up to {} chats", limit);
+
+    let filters = ChatFilters {
+        include_private_chats: true,
+        include_non_contacts: true,
+        include_groups: true,
+        include_channels: true,
+        include_archived: true,
+        include_muted: true,
+        ..Default::default()
+    };
+    let chats = client.get_chats(limit, Some(filters)).await?;
+    if chats.len() < 4 {
+        return Ok(vec![]);
+    }
+
+    let requests = chats
+        .iter()
+        .map(|c| BatchMessageRequest {
+            chat_id: c.id,
+            limit: MESSAGES_PER_CHAT,
+        })
+        .collect();
+    let results = client.get_batch_messages(requests).await?;
+
+    let texts: Vec<String> = chats
+        .iter()
+        .map(|chat| {
+            let messages = results
+                .iter()
+                .find(|r| r.chat_id == chat.id)
+                .map(|r| r.messages.as_slice())
+                .unwrap_or_default();
+            chat_embedding_text(chat, messages)
+        })
+        .collect();
+    let names: Vec<String> = chats
+        .iter()
+        .filter(|c| c.chat_type == "private")
+        .map(|c| sanitize_chat_title(&c.title))
+        .collect();
+
+    let embeddings = llm
+        .embed(&texts, &names)
+        .await
+        .map_err(|e| format!("Failed to embed chats: {}", e))?;
+
+    let k = suggested_cluster_count(chats.len(), MAX_SUGGESTIONS);
+    let clusters = kmeans(&embeddings, k, 2);
+    if clusters.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let groups: Vec<Vec<String>> = clusters
+        .iter()
+        .map(|members| {
+            members
+                .iter()
+                .take(TITLES_PER_GROUP)
+                .map(|&idx| sanitize_chat_title(&chats[idx].title))
+                .collect()
+        })
+        .collect();
+
+    let llm_messages = vec![
+        OpenAIMessage {
+            role: "system".to_string(),
+            content: SCOPE_NAMING_SYSTEM_PROMPT.to_string(),
+        },
+        OpenAIMessage {
+            role: "user".to_string(),
+            content: format_scope_naming_user_prompt(&groups),
+        },
+    ];
+    let scope_names = match llm
+        .complete_private(llm_messages, &names, 0.3, 400, true, CallTimeout::Default)
+        .await
+        .and_then(|completion| {
+            completion.parse_and_cache(|response| safe_json_parse::<AIScopeNamesResponse>(response, "scope names"))
+        })
+    {
+        Ok(parsed) => parsed.groups,
+        Err(e) => {
+            log::warn!("Failed to name scope suggestions: {}", e);
+            vec![]
+        }
+    };
+
+    let mut used: Vec<String> = vec![];
+    let suggestions = clusters
+        .iter()
+        .enumerate()
+        .map(|(idx, members)| {
+            let base = scope_names
+                .iter()
+                .find(|n| n.group == idx + 1)
+                .map(|n| n.name.trim().to_string())
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| format!("Group {}", idx + 1));
+            // Scope names are unique keys
+            let mut name = base.clone();
+            let mut n = 2;
+            while used.iter().any(|u| u.eq_ignore_ascii_case(&name)) {
+                name = format!("{} {}", base, n);
+                n += 1;
+            }
+            used.push(name.clone());
+
+            let chat_ids: Vec<i64> = members.iter().map(|&i| chats[i].id).collect();
+            ScopeSuggestion {
+                name,
+                chat_titles: members.iter().map(|&i| chats[i].title.clone()).collect(),
+                config: ScopeConfig {
+                    folder_ids: vec![],
+                    chat_types: vec![],
+                    excluded_chat_ids: vec![],
+                    included_chat_ids: chat_ids.clone(),
+                    contact_list_ids: vec![],
+                },
+                chat_ids,
+            }
+        })
+        .collect();
+
+    Ok(suggestions)
+}
@@ -1,8 +1,82 @@
+use crate::db::lists as db_lists;
 use crate::db::scopes as db_scopes;
-use crate::telegram::{TelegramClient, client::Folder};
+use crate::telegram::{TelegramClient, client::{Chat, ChatFilters, Folder}};
 use tauri::State;
 use std::sync::Arc;
 
+/// Resolve the chats covered by a scope config (folders, chat types, contact lists,
+/// manual includes/excludes). A scope that selects nothing resolves to no chats:
+/// no chat types, or folders that contain no chats, match nothing.
+pub async fn resolve_scope_chats(
+    client: &TelegramClient,
+    config: &db_scopes::ScopeConfig,
+    limit: i32,
+) -> Result<Vec<Chat>, String> {
+    let has_type = |types: &[&str]| {
+        config.chat_types.iter().any(|t| types.contains(&t.as_str()))
+    };
+
+    let folder_chat_ids: Vec<i64> = if config.folder_ids.is_empty() {
+        vec![]
+    } else {
+        client
+            .get_folders()
+            .await?
+            .into_iter()
+            .filter(|f| config.folder_ids.contains(&f.id))
+            .flat_map(|f| f.included_chat_ids)
+            .collect()
+    };
+
+    let filters = ChatFilters {
+        include_private_chats: has_type(&["private"]),
+        include_non_contacts: has_type(&["private"]),
+        include_groups: has_type(&["group", "supergroup"]),
+        include_channels: has_type(&["channel"]),
+        include_bots: has_type(&["private"]),
+        include_archived: true,
+        include_muted: true,
+        selected_folder_ids: config.folder_ids.clone(),
+        folder_chat_ids,
+        ..Default::default()
+    };
+
+    // An empty folder filter would let every chat through
+    let selects_nothing =
+        config.chat_types.is_empty() || (!config.folder_ids.is_empty() && filters.folder_chat_ids.is_empty());
+    let mut chats: Vec<Chat> = if selects_nothing {
+        vec![]
+    } else {
+        client
+            .get_chats(limit, Some(filters))
+            .await?
+            .into_iter()
+            .filter(|c| !config.excluded_chat_ids.contains(&c.id))
+            .collect()
+    };
+
+    // Manually included chats and list members' DMs (chat id = user id) are added
+    // even if filters would drop them
+    let mut extra_chat_ids = config.included_chat_ids.clone();
+    if !config.contact_list_ids.is_empty() {
+        extra_chat_ids.extend(
+            db_lists::members_of(&config.contact_list_ids)?
+                .into_iter()
+                .filter(|id| !config.excluded_chat_ids.contains(id)),
+        );
+    }
+    for chat_id in &extra_chat_ids {
+        if chats.iter().any(|c| c.id == *chat_id) {
+            continue;
+        }
+        if let Some(chat) = client.get_chat(*chat_id).await? {
+            chats.push(chat);
+        }
+    }
+
+    Ok(chats)
+}
+
 #[tauri::command]
 pub async fn get_folders(
     client: State<'_, Arc<TelegramClient>>,
@@ -0,0 +1,220 @@
+use crate::db::{self, messages::LocalSearchHit};
+use crate::telegram::{TelegramApi, TelegramClient, client::{Chat, Message, MessageSearch, MessageSearchPage, User}};
+use serde::Serialize;
+use tauri::State;
+use std::sync::Arc;
+
+/// Results kept per group unless the caller asks for more
+const DEFAULT_GROUP_LIMIT: usize = 10;
+
+/// Results of `universal_search`, one typed group per source.
+/// `messages` is only filled when server search was requested.
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UniversalSearchResults {
+    pub query: String,
+    pub contacts: Vec<User>,
+    pub chats: Vec<Chat>,
+    /// Matches in the local message archive
+    pub archived_messages: Vec<LocalSearchHit>,
+    pub messages: Vec<Message>,
+    /// Sources that failed; the other groups are still returned
+    pub errors: Vec<String>,
+}
+
+/// Rank how well `text` matches `query` (both lowercased); lower is better.
+/// Exact match, then prefix, then word prefix, then any substring.
+fn match_rank(text: &str, query: &str) -> Option<u8> {
+    if text == query {
+        Some(0)
+    } else if text.starts_with(query) {
+        Some(1)
+    } else if text.split_whitespace().any(|word| word.starts_with(query)) {
+        Some(2)
+    } else if text.contains(query) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+/// Keep the items whose best field matches, ordered by rank (stable within a rank)
+fn rank_matches<T>(items: Vec<T>, query: &str, limit: usize, fields: impl Fn(&T) -> Vec<String>) -> Vec<T> {
+    let mut ranked: Vec<(u8, T)> = items
+        .into_iter()
+        .filter_map(|item| {
+            fields(&item)
+                .iter()
+                .filter_map(|field| match_rank(&field.to_lowercase(), query))
+                .min()
+                .map(|rank| (rank, item))
+        })
+        .collect();
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked.into_iter().take(limit).map(|(_, item)| item).collect()
+}
+
+/// Command palette entry point: match contacts by name/username, cached chats by
+/// title and the local message archive, and optionally run Telegram's server-side
+/// message search, concurrently
+#[tauri::command]
+pub async fn universal_search(
+    client: State<'_, Arc<TelegramClient>>,
+    query: String,
+    include_server: Option<bool>,
+    limit: Option<usize>,
+) -> Result<UniversalSearchResults, String> {
+    Ok(search_everything(client.inner().as_ref(), query, include_server, limit).await)
+}
+
+async fn search_everything(
+    client: &dyn TelegramApi,
+    query: String,
+    include_server: Option<bool>,
+    limit: Option<usize>,
+) -> UniversalSearchResults {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return UniversalSearchResults { query, ..Default::default() };
+    }
+    let limit = limit.unwrap_or(DEFAULT_GROUP_LIMIT).max(1);
+    let include_server = include_server.unwrap_or(false);
+
+    log::info!("Universal search (server: {}, limit: {})", include_server, limit);
+
+    // Titles come from the dialog cache, loaded once, rather than a dialog scan per keystroke
+    let chats = async {
+        client.load_chat_cache().await?;
+        Ok::<_, String>(client.cached_chats().await)
+    };
+
+    let server_search = async {
+        if include_server {
+            client.search_messages(query.trim(), None, limit as i32).await
+        } else {
+            Ok(vec![])
+        }
+    };
+
+    let (contacts, chats, messages) = tokio::join!(
+        client.get_contacts(),
+        chats,
+        server_search,
+    );
+
+    let mut results = UniversalSearchResults { query: query.clone(), ..Default::default() };
+
+    match contacts {
+        Ok(users) => {
+            results.contacts = rank_matches(users, &needle, limit, |user| {
+                let full_name = format!("{} {}", user.first_name, user.last_name);
+                let mut fields = vec![full_name.trim().to_string()];
+                fields.extend(user.username.clone());
+                fields
+            });
+        }
+        Err(e) => results.errors.push(format!("contacts: {}", e)),
+    }
+
+    match chats {
+        Ok(chats) => {
+            results.chats = rank_matches(chats, &needle, limit, |chat| vec![chat.title.clone()]);
+        }
+        Err(e) => results.errors.push(format!("chats: {}", e)),
+    }
+
+    match db::messages::search_archive(query.trim(), None, limit as i64, 0) {
+        Ok(hits) => results.archived_messages = hits,
+        Err(e) => results.errors.push(format!("archive: {}", e)),
+    }
+
+    match messages {
+        Ok(messages) => results.messages = messages,
+        Err(e) => results.errors.push(format!("messages: {}", e)),
+    }
+
+    results
+}
+
+/// Reject searches Telegram can't run: searching across chats needs text, and
+/// the date range must not be reversed
+fn validate_search(search: &MessageSearch) -> Result<(), String> {
+    if search.query.trim().is_empty() && search.chat_id.is_none() {
+        return Err("Enter text to search for, or pick a chat".to_string());
+    }
+    if let (Some(min), Some(max)) = (search.min_date, search.max_date) {
+        if min > max {
+            return Err("The start date is after the end date".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Full-text message search across all chats or within one, filtered by sender
+/// and date range, one page at a time
+#[tauri::command]
+pub async fn search_messages(
+    client: State<'_, Arc<TelegramClient>>,
+    search: MessageSearch,
+) -> Result<MessageSearchPage, String> {
+    validate_search(&search)?;
+    let search = MessageSearch { query: search.query.trim().to_string(), ..search };
+    client.search_messages_page(&search).await
+}
+
+/// Search the local message archive: ranked, with highlighted snippets, and
+/// without calling Telegram. Only messages that were fetched before are found.
+#[tauri::command]
+pub async fn search_local_messages(
+    query: String,
+    chat_id: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<LocalSearchHit>, String> {
+    let limit = limit.unwrap_or(50).clamp(1, 200);
+    db::messages::search_archive(&query, chat_id, limit, offset.unwrap_or(0).max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[test]
+    fn test_rank_matches_orders_by_match_quality() {
+        let titles = vec!["Team Design", "design", "Redesign ideas", "Designers", "Marketing"];
+        let ranked = rank_matches(titles, "design", 10, |t| vec![t.to_string()]);
+        assert_eq!(ranked, vec!["design", "Designers", "Team Design", "Redesign ideas"]);
+
+        let limited = rank_matches(vec!["a1", "a2", "a3"], "a", 2, |t| vec![t.to_string()]);
+        assert_eq!(limited, vec!["a1", "a2"]);
+    }
+
+    #[tokio::test]
+    async fn test_universal_search_merges_cached_chats_and_archive() {
+        crate::db::init_test_db();
+        let ann = fake::user(7581, "Ann", "");
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_contact(ann.clone())
+            .with_chat(fake::chat(7581, "private", "Ann", 0, 0))
+            .with_chat(fake::chat(7582, "group", "Kayak trip 7581", 0, 1));
+        db::messages::store_messages(7581, &[fake::text_message(1, 7581, &ann, "Kayak rental 7581 is booked", 100)], None, 0)
+            .unwrap();
+
+        let results = search_everything(&client, "kayak".to_string(), None, None).await;
+        assert_eq!(results.chats.iter().map(|c| c.id).collect::<Vec<_>>(), vec![7582]);
+        assert!(results.archived_messages.iter().any(|h| h.chat_id == 7581 && h.message_id == 1));
+        assert!(results.messages.is_empty() && results.errors.is_empty());
+    }
+
+    #[test]
+    fn test_validate_search() {
+        let search = |query: &str, chat_id| MessageSearch { query: query.to_string(), chat_id, ..Default::default() };
+        assert!(validate_search(&search("invoice", None)).is_ok());
+        assert!(validate_search(&search("  ", None)).is_err());
+        // Everything from one sender in a chat needs no text
+        assert!(validate_search(&MessageSearch { sender_id: Some(5), ..search("", Some(42)) }).is_ok());
+        let reversed = MessageSearch { min_date: Some(200), max_date: Some(100), ..search("invoice", None) };
+        assert!(validate_search(&reversed).is_err());
+    }
+}
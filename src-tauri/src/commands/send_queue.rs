@@ -0,0 +1,293 @@
+//! Delayed sends with an undo window: a message waits `send_delay_secs` before it
+//! goes to Telegram, and `undo_send` drops it if it hasn't gone out yet. Sends go
+//! through the shared `RateLimiter` like every other send the app makes.
+
+use crate::cache::{BriefingCache, SummaryCache};
+use crate::commands::chats::invalidate_chat_analysis;
+use crate::commands::outbox;
+use crate::commands::outreach::extract_flood_wait_seconds;
+use crate::db;
+use crate::telegram::client::Message;
+use crate::telegram::{TelegramApi, TelegramClient};
+use crate::utils::rate_limiter::RateLimiter;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Longest allowed undo window
+const MAX_SEND_DELAY_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingSend {
+    pub id: i64,
+    pub chat_id: i64,
+    pub text: String,
+    /// Unix seconds when it goes out
+    pub send_at: i64,
+}
+
+/// Outcome of a delayed send, emitted as `send://completed`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendCompleted {
+    pub id: i64,
+    pub chat_id: i64,
+    pub message: Option<Message>,
+    pub error: Option<String>,
+}
+
+/// A message to send after the undo window
+pub(crate) struct SendRequest {
+    pub chat_id: i64,
+    pub text: String,
+    /// Once sent, mark the chat read up to this message (the newest one the reply
+    /// was written against)
+    pub read_up_to: Option<i64>,
+}
+
+#[derive(Default)]
+pub struct SendQueue {
+    next_id: AtomicI64,
+    pending: Mutex<HashMap<i64, PendingSend>>,
+}
+
+impl SendQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove a pending send; whoever takes it first (the timer or an undo) wins
+    fn take(&self, id: i64) -> Option<PendingSend> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner()).remove(&id)
+    }
+
+    /// Hold the message for `delay` (and for as long as the rate limiter says),
+    /// then send it unless it was undone. `on_sent` gets the result once the
+    /// message has gone out or failed.
+    pub(crate) fn schedule<F>(
+        self: &Arc<Self>,
+        client: Arc<dyn TelegramApi>,
+        limiter: Arc<RateLimiter>,
+        request: SendRequest,
+        delay: Duration,
+        on_sent: F,
+    ) -> PendingSend
+    where
+        F: FnOnce(&PendingSend, Result<Message, String>) + Send + 'static,
+    {
+        let SendRequest { chat_id, text, read_up_to } = request;
+        let pending = PendingSend {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed) + 1,
+            chat_id,
+            text,
+            send_at: chrono::Utc::now().timestamp() + delay.as_secs() as i64,
+        };
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(pending.id, pending.clone());
+
+        let queue = Arc::clone(self);
+        let id = pending.id;
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(delay).await;
+            // Still undoable while it waits for the limiter
+            while let Err(wait) = limiter.can_send(chat_id) {
+                tokio::time::sleep(Duration::from_secs(wait.max(1))).await;
+            }
+            let Some(pending) = queue.take(id) else {
+                return; // undone
+            };
+            let result = outbox::send_or_queue_offline(client.as_ref(), pending.chat_id, &pending.text).await;
+            match &result {
+                // Queued offline messages (negative ids) haven't been sent yet
+                Ok(message) if message.id > 0 => {
+                    limiter.record_send(pending.chat_id);
+                    if let Some(up_to) = read_up_to {
+                        // Best effort: the reply went out either way
+                        if let Err(e) = client.mark_chat_read(pending.chat_id, Some(up_to)).await {
+                            log::warn!("Failed to mark chat {} as read: {}", pending.chat_id, e);
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    if let Some(wait_secs) = extract_flood_wait_seconds(e) {
+                        limiter.handle_flood_wait(wait_secs);
+                    }
+                }
+            }
+            on_sent(&pending, result);
+        });
+        pending
+    }
+
+    /// Cancel a send that hasn't gone out yet
+    pub(crate) fn undo(&self, id: i64) -> Result<PendingSend, String> {
+        self.take(id)
+            .ok_or_else(|| format!("Send {} was already sent or undone", id))
+    }
+
+    pub(crate) fn list(&self) -> Vec<PendingSend> {
+        let mut pending: Vec<PendingSend> =
+            self.pending.lock().unwrap_or_else(|e| e.into_inner()).values().cloned().collect();
+        pending.sort_by_key(|p| p.id);
+        pending
+    }
+}
+
+#[tauri::command]
+pub async fn get_send_delay() -> Result<u64, String> {
+    db::settings::load_send_delay()
+}
+
+#[tauri::command]
+pub async fn set_send_delay(secs: u64) -> Result<(), String> {
+    if secs > MAX_SEND_DELAY_SECS {
+        return Err(format!("Send delay can be at most {} seconds", MAX_SEND_DELAY_SECS));
+    }
+    db::settings::save_send_delay(secs)
+}
+
+/// Send a message after the configured delay. The result arrives as a
+/// `send://completed` event; until then `undo_send` can cancel it. With
+/// `read_up_to`, a successful send also marks the chat read up to that message.
+#[tauri::command]
+pub async fn send_message_with_undo(
+    app: AppHandle,
+    client: State<'_, Arc<TelegramClient>>,
+    queue: State<'_, Arc<SendQueue>>,
+    rate_limiter: State<'_, Arc<RateLimiter>>,
+    chat_id: i64,
+    text: String,
+    read_up_to: Option<i64>,
+) -> Result<PendingSend, String> {
+    if text.trim().is_empty() {
+        return Err("Message is empty".to_string());
+    }
+    let delay = Duration::from_secs(db::settings::load_send_delay()?);
+    let client: Arc<dyn TelegramApi> = client.inner().clone();
+    let request = SendRequest { chat_id, text, read_up_to };
+    Ok(queue.schedule(client, rate_limiter.inner().clone(), request, delay, move |pending, result| {
+        if let Err(e) = &result {
+            log::warn!("Delayed send {} to chat {} failed: {}", pending.id, pending.chat_id, e);
+        }
+        let (message, error) = match result {
+            Ok(message) => (Some(message), None),
+            Err(e) => (None, Some(e)),
+        };
+        if message.is_some() {
+            let (app, chat_id) = (app.clone(), pending.chat_id);
+            tauri::async_runtime::spawn(async move {
+                let briefing_cache = app.state::<Arc<BriefingCache>>();
+                let summary_cache = app.state::<Arc<SummaryCache>>();
+                invalidate_chat_analysis(&briefing_cache, &summary_cache, chat_id).await;
+            });
+        }
+        let _ = app.emit(
+            "send://completed",
+            SendCompleted { id: pending.id, chat_id: pending.chat_id, message, error },
+        );
+    }))
+}
+
+#[tauri::command]
+pub async fn undo_send(queue: State<'_, Arc<SendQueue>>, pending_id: i64) -> Result<PendingSend, String> {
+    queue.undo(pending_id)
+}
+
+/// Sends still inside their undo window
+#[tauri::command]
+pub async fn get_pending_sends(queue: State<'_, Arc<SendQueue>>) -> Result<Vec<PendingSend>, String> {
+    Ok(queue.list())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    fn request(chat_id: i64, text: &str, read_up_to: Option<i64>) -> SendRequest {
+        SendRequest { chat_id, text: text.to_string(), read_up_to }
+    }
+
+    #[tokio::test]
+    async fn test_undo_before_delay_cancels_send() {
+        let client = Arc::new(
+            FakeTelegram::new(fake::user(1, "Me", "")).with_chat(fake::chat(7421, "private", "Frank", 0, 0)),
+        );
+        let limiter = Arc::new(RateLimiter::new(0));
+        let queue = Arc::new(SendQueue::new());
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        let undone = queue.schedule(
+            client.clone(),
+            limiter.clone(),
+            request(7421, "Oops", Some(5)),
+            Duration::from_millis(200),
+            |_, _| {},
+        );
+        let kept = queue.schedule(
+            client.clone(),
+            limiter,
+            request(7421, "Hello", Some(9)),
+            Duration::from_millis(50),
+            move |p, r| {
+                let _ = tx.send((p.id, r.is_ok()));
+            },
+        );
+        assert_eq!(queue.list().len(), 2);
+        assert!(queue.undo(undone.id).is_ok());
+
+        assert_eq!(rx.await.unwrap(), (kept.id, true));
+        assert!(queue.undo(kept.id).is_err());
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(client.sent(), vec![(7421, "Hello".to_string())]);
+        assert!(queue.list().is_empty());
+        // Only the send that went out marks the chat read
+        assert_eq!(client.read_marks(), vec![(7421, Some(9))]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_send_leaves_the_chat_unread() {
+        let client = Arc::new(
+            FakeTelegram::new(fake::user(1, "Me", ""))
+                .with_chat(fake::chat(7423, "private", "Heidi", 2, 0))
+                .with_send_error(7423, "CHAT_WRITE_FORBIDDEN"),
+        );
+        let queue = Arc::new(SendQueue::new());
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        queue.schedule(
+            client.clone(),
+            Arc::new(RateLimiter::new(0)),
+            request(7423, "Sure", Some(12)),
+            Duration::ZERO,
+            move |_, r| {
+                let _ = tx.send(r.is_ok());
+            },
+        );
+        assert!(!rx.await.unwrap());
+        assert!(client.read_marks().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sends_wait_for_the_rate_limiter() {
+        let client = Arc::new(
+            FakeTelegram::new(fake::user(1, "Me", "")).with_chat(fake::chat(7422, "private", "Grace", 0, 0)),
+        );
+        let limiter = Arc::new(RateLimiter::new(60));
+        limiter.record_send(7422);
+        let queue = Arc::new(SendQueue::new());
+
+        // Past its delay but held by the limiter: not sent, and still undoable
+        let held = queue.schedule(client.clone(), limiter, request(7422, "Again", None), Duration::from_millis(10), |_, _| {});
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(client.sent().is_empty());
+        assert!(queue.undo(held.id).is_ok());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::ai::client::{safe_json_parse, CallTimeout};
+use crate::ai::context::{build_summary_context, fit_token_budget};
+use crate::ai::prompts::{format_standup_user_prompt, STANDUP_SYSTEM_PROMPT};
+use crate::ai::sanitize::{sanitize_chat_title, sanitize_message_text, sanitize_sender_name};
+use crate::ai::types::{AIStandupResponse, OpenAIMessage, StandupItem};
+use crate::ai::LLMClient;
+use crate::commands::scopes::resolve_scope_chats;
+use crate::db;
+use crate::telegram::client::{BatchMessageRequest, Chat, ChatFilters};
+use crate::telegram::TelegramClient;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::State;
+
+/// Chats considered per scope (most recent first)
+const STANDUP_CHAT_LIMIT: i32 = 200;
+
+/// Messages fetched per chat; the user's own are picked out of these
+const STANDUP_MESSAGES_PER_CHAT: i32 = 100;
+
+/// Token budget for all chats together, split evenly between them
+const STANDUP_TOKEN_BUDGET: usize = 10000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StandupReport {
+    pub done: Vec<StandupItem>,
+    pub pending: Vec<StandupItem>,
+    pub days: i64,
+    /// Chats where the user sent at least one message in the period
+    pub chat_count: usize,
+    pub message_count: usize,
+    pub generated_at: i64,
+}
+
+/// Chats in the given scopes, or all recent dialogs when no scope is named
+async fn standup_chats(client: &TelegramClient, scopes: &[String]) -> Result<Vec<Chat>, String> {
+    if scopes.is_empty() {
+        let filters = ChatFilters {
+            include_private_chats: true,
+            include_non_contacts: true,
+            include_groups: true,
+            include_channels: false,
+            include_archived: true,
+            include_muted: true,
+            ..Default::default()
+        };
+        return client.get_chats(STANDUP_CHAT_LIMIT, Some(filters)).await;
+    }
+
+    let mut chats: Vec<Chat> = vec![];
+    for name in scopes {
+        let scope = db::scopes::load_scope(name)?.ok_or_else(|| format!("Scope '{}' not found", name))?;
+        for chat in resolve_scope_chats(client, &scope.config, STANDUP_CHAT_LIMIT).await? {
+            if !chats.iter().any(|c| c.id == chat.id) {
+                chats.push(chat);
+            }
+        }
+    }
+    Ok(chats)
+}
+
+/// Standup ("what I did / what's pending") from the user's own messages over the
+/// last `days` days in the given work scopes (all recent chats if none are named).
+#[tauri::command]
+pub async fn generate_standup(
+    client: State<'_, Arc<TelegramClient>>,
+    llm: State<'_, Arc<LLMClient>>,
+    days: i64,
+    scopes: Option<Vec<String>>,
+) -> Result<StandupReport, String> {
+    let days = days.max(1);
+    let scopes = scopes.unwrap_or_default();
+    log::info!("Generating standup for the last {}d (scopes: {:?})", days, scopes);
+
+    let now = chrono::Utc::now().timestamp();
+    let since = now - days * 86400;
+
+    // Only chats with activity in the period can contain our messages
+    let chats: Vec<Chat> = standup_chats(&client, &scopes)
+        .await?
+        .into_iter()
+        .filter(|c| c.last_message.as_ref().is_some_and(|m| m.date >= since))
+        .collect();
+
+    let requests = chats
+        .iter()
+        .map(|c| BatchMessageRequest {
+            chat_id: c.id,
+            limit: STANDUP_MESSAGES_PER_CHAT,
+        })
+        .collect();
+    let results = client.get_batch_messages(requests).await?;
+
+    // Keep chats where we wrote something in the period, with what others said for context
+    let contexts: Vec<_> = chats
+        .iter()
+        .filter_map(|chat| {
+            let result = results.iter().find(|r| r.chat_id == chat.id)?;
+            let recent: Vec<_> = result.messages.iter().filter(|m| m.date >= since).cloned().collect();
+            if !recent.iter().any(|m| m.is_outgoing) {
+                return None;
+            }
+            Some(build_summary_context(chat, &recent))
+        })
+        .collect();
+
+    let message_count = contexts
+        .iter()
+        .map(|c| c.messages.iter().filter(|m| m.is_outgoing).count())
+        .sum();
+
+    if contexts.is_empty() {
+        return Ok(StandupReport {
+            done: vec![],
+            pending: vec![],
+            days,
+            chat_count: 0,
+            message_count: 0,
+            generated_at: now,
+        });
+    }
+
+    let per_chat_budget = STANDUP_TOKEN_BUDGET / contexts.len();
+    let mut names: Vec<String> = vec![];
+    let prompt_chats: Vec<(String, Vec<(String, String)>)> = contexts
+        .iter()
+        .map(|context| {
+            let title = sanitize_chat_title(&context.chat_title);
+            if context.chat_type == "private" {
+                names.push(title.clone());
+            }
+            let messages = fit_token_budget(&context.messages, per_chat_budget)
+                .iter()
+                .map(|m| {
+                    let sender = if m.is_outgoing {
+                        "You".to_string()
+                    } else {
+                        sanitize_sender_name(&m.sender_name)
+                    };
+                    names.push(sender.clone());
+                    (sender, sanitize_message_text(&m.text))
+                })
+                .collect();
+            (title, messages)
+        })
+        .collect();
+
+    let llm_messages = vec![
+        OpenAIMessage {
+            role: "system".to_string(),
+            content: STANDUP_SYSTEM_PROMPT.to_string(),
+        },
+        OpenAIMessage {
+            role: "user".to_string(),
+            content: format_standup_user_prompt(days, &prompt_chats),
+        },
+    ];
+
+    let parsed: AIStandupResponse = llm
+        .complete_private(llm_messages, &names, 0.3, 1200, true, CallTimeout::Long)
+        .await
+        .map_err(|e| format!("Failed to generate standup: {}", e))?
+        .parse_and_cache(|response| safe_json_parse(response, "standup"))?;
+
+    Ok(StandupReport {
+        done: parsed.done,
+        pending: parsed.pending,
+        days,
+        chat_count: contexts.len(),
+        message_count,
+        generated_at: now,
+    })
+}
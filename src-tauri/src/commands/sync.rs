@@ -0,0 +1,248 @@
+use crate::db::settings::{self as db_settings, SyncConfig};
+use crate::sync::{self, crypto, keychain, MergeStats, SyncSnapshot};
+use crate::telegram::{TelegramApi, TelegramClient, client::{FormattedText, Message, MessageContent}};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tauri::State;
+
+/// Sync messages scanned when pulling (each snapshot is usually one message)
+const SYNC_SEARCH_LIMIT: i32 = 100;
+
+/// Snapshots of its own each device keeps in Saved Messages after a push
+const KEEP_OWN_SNAPSHOTS: usize = 3;
+
+/// Sync settings as shown to the frontend, which never sees the password
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub enabled: bool,
+    pub password_set: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConfigUpdate {
+    pub enabled: bool,
+    /// New password; None keeps the saved one and an empty one removes it
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncPushResult {
+    pub snapshot_id: String,
+    pub messages: usize,
+    pub contacts: usize,
+    pub scopes: usize,
+}
+
+/// Password for an enabled sync config, or an error explaining what's missing
+fn sync_password(config: &SyncConfig) -> Result<String, String> {
+    if !config.enabled {
+        return Err("Multi-device sync is disabled".to_string());
+    }
+    keychain::load_password()?.ok_or_else(|| "Set a sync password first".to_string())
+}
+
+#[tauri::command]
+pub async fn get_sync_config() -> Result<SyncStatus, String> {
+    let config = db_settings::load_sync_config()?;
+    Ok(SyncStatus { enabled: config.enabled, password_set: keychain::load_password()?.is_some() })
+}
+
+#[tauri::command]
+pub async fn update_sync_config(config: SyncConfigUpdate) -> Result<(), String> {
+    match config.password.as_deref() {
+        Some("") => keychain::delete_password()?,
+        Some(password) => keychain::save_password(password)?,
+        None => {}
+    }
+    db_settings::save_sync_config(&SyncConfig::new(config.enabled))
+}
+
+/// Encrypt this device's tags, notes and scopes and post them to Saved Messages
+#[tauri::command]
+pub async fn push_sync_snapshot(
+    client: State<'_, Arc<TelegramClient>>,
+) -> Result<SyncPushResult, String> {
+    let config = db_settings::load_sync_config()?;
+    let password = sync_password(&config)?;
+
+    let snapshot = sync::collect_snapshot(&db_settings::sync_device_id()?)?;
+    let json = serde_json::to_vec(&snapshot)
+        .map_err(|e| format!("Failed to serialize sync snapshot: {}", e))?;
+    let payload = crypto::encrypt(&password, &json)?;
+
+    let snapshot_id = uuid::Uuid::new_v4().simple().to_string();
+    let messages = sync::encode_messages(&snapshot_id, &payload);
+    for text in &messages {
+        let message = FormattedText { text: text.clone(), entities: vec![] };
+        client.send_formatted_message(None, &message).await?;
+    }
+
+    log::info!(
+        "[Sync] Pushed snapshot {} ({} contacts, {} scopes) as {} message(s)",
+        snapshot_id,
+        snapshot.contacts.len(),
+        snapshot.scopes.len(),
+        messages.len()
+    );
+
+    // Older snapshots of this device are superseded; failing to prune isn't fatal
+    match prune_snapshots(client.inner().as_ref(), &password, &snapshot.device_id).await {
+        Ok(0) => {}
+        Ok(deleted) => log::info!("[Sync] Pruned {} old sync message(s)", deleted),
+        Err(e) => log::warn!("[Sync] Failed to prune old snapshots: {}", e),
+    }
+
+    Ok(SyncPushResult {
+        snapshot_id,
+        messages: messages.len(),
+        contacts: snapshot.contacts.len(),
+        scopes: snapshot.scopes.len(),
+    })
+}
+
+fn message_text(message: &Message) -> Option<&str> {
+    match &message.content {
+        MessageContent::Text { text } => Some(text),
+        _ => None,
+    }
+}
+
+/// The sync messages this account posted to Saved Messages
+async fn find_sync_messages(client: &dyn TelegramApi) -> Result<Vec<Message>, String> {
+    let me = client.get_current_user().await.ok_or("Not logged in")?;
+    let found = client.search_messages(sync::SYNC_TAG, None, SYNC_SEARCH_LIMIT).await?;
+    Ok(found
+        .into_iter()
+        .filter(|m| m.chat_id == me.id && m.is_outgoing && message_text(m).is_some())
+        .collect())
+}
+
+/// Decrypt the complete snapshots among `messages`, skipping unreadable ones
+fn read_snapshots(messages: &[Message], password: &str) -> Vec<(String, SyncSnapshot)> {
+    sync::decode_messages(messages.iter().filter_map(message_text))
+        .into_iter()
+        .filter_map(|(snapshot_id, payload)| {
+            match crypto::decrypt(password, &payload)
+                .and_then(|json| serde_json::from_slice::<SyncSnapshot>(&json).map_err(|e| e.to_string()))
+            {
+                Ok(snapshot) => Some((snapshot_id, snapshot)),
+                Err(e) => {
+                    log::warn!("[Sync] Skipping unreadable snapshot: {}", e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Delete this device's snapshots beyond the newest few so Saved Messages
+/// doesn't fill up and pulls stay within the search limit
+async fn prune_snapshots(client: &dyn TelegramApi, password: &str, device_id: &str) -> Result<usize, String> {
+    let messages = find_sync_messages(client).await?;
+    let stale: HashSet<String> =
+        sync::stale_snapshots(&read_snapshots(&messages, password), device_id, KEEP_OWN_SNAPSHOTS)
+            .into_iter()
+            .collect();
+    let Some(chat_id) = messages.first().map(|m| m.chat_id) else {
+        return Ok(0);
+    };
+    let ids: Vec<i32> = messages
+        .iter()
+        .filter(|m| message_text(m).and_then(sync::snapshot_id_of).is_some_and(|id| stale.contains(&id)))
+        .map(|m| m.id as i32)
+        .collect();
+    if ids.is_empty() {
+        return Ok(0);
+    }
+    client.delete_messages(chat_id, &ids).await
+}
+
+/// Merge the newest snapshot from each other device found in Saved Messages
+#[tauri::command]
+pub async fn pull_sync_snapshots(
+    client: State<'_, Arc<TelegramClient>>,
+) -> Result<MergeStats, String> {
+    let config = db_settings::load_sync_config()?;
+    let password = sync_password(&config)?;
+    let device_id = db_settings::sync_device_id()?;
+
+    let messages = find_sync_messages(client.inner().as_ref()).await?;
+
+    let mut latest: HashMap<String, SyncSnapshot> = HashMap::new();
+    for (_, snapshot) in read_snapshots(&messages, &password) {
+        if snapshot.device_id == device_id {
+            continue;
+        }
+        match latest.get(&snapshot.device_id) {
+            Some(existing) if existing.created_at >= snapshot.created_at => {}
+            _ => {
+                latest.insert(snapshot.device_id.clone(), snapshot);
+            }
+        }
+    }
+
+    let mut remotes: Vec<SyncSnapshot> = latest.into_values().collect();
+    remotes.sort_by_key(|s| s.created_at);
+
+    let mut stats = MergeStats::default();
+    for remote in &remotes {
+        // Re-read local state so each merge sees the previous one's changes
+        let local = sync::collect_snapshot(&device_id)?;
+        sync::apply_merge(&sync::plan_merge(&local, remote), &mut stats)?;
+        stats.snapshots += 1;
+    }
+
+    log::info!(
+        "[Sync] Merged {} snapshot(s): {} tags, {} notes, {} scopes",
+        stats.snapshots,
+        stats.tags_added,
+        stats.notes_updated,
+        stats.scopes_updated
+    );
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    fn snapshot_messages(me: &crate::telegram::client::User, first_id: i64, device_id: &str, created_at: i64) -> Vec<Message> {
+        let snapshot = SyncSnapshot { version: 1, device_id: device_id.to_string(), created_at, contacts: vec![], scopes: vec![] };
+        let payload = crypto::encrypt("pw", &serde_json::to_vec(&snapshot).unwrap()).unwrap();
+        sync::encode_messages(&format!("snap{}", first_id), &payload)
+            .iter()
+            .enumerate()
+            .map(|(i, text)| Message {
+                is_outgoing: true,
+                ..fake::text_message(first_id + i as i64, me.id, me, text, created_at)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_push_prunes_only_old_own_snapshots() {
+        let me = fake::user(1, "Me", "");
+        let mut history = Vec::new();
+        for (i, created_at) in [100, 200, 300, 400, 500].into_iter().enumerate() {
+            history.extend(snapshot_messages(&me, (i as i64 + 1) * 10, "this", created_at));
+        }
+        history.extend(snapshot_messages(&me, 90, "other", 50));
+        let client = FakeTelegram::new(me.clone()).with_messages(me.id, history);
+
+        assert!(prune_snapshots(&client, "pw", "this").await.unwrap() > 0);
+
+        let left = read_snapshots(&find_sync_messages(&client).await.unwrap(), "pw");
+        let mut kept: Vec<(String, i64)> = left.iter().map(|(_, s)| (s.device_id.clone(), s.created_at)).collect();
+        kept.sort();
+        assert_eq!(
+            kept,
+            vec![("other".to_string(), 50), ("this".to_string(), 300), ("this".to_string(), 400), ("this".to_string(), 500)]
+        );
+        assert_eq!(prune_snapshots(&client, "pw", "this").await.unwrap(), 0);
+    }
+}
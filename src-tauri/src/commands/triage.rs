@@ -0,0 +1,129 @@
+use crate::ai::context::message_text_for_ai;
+use crate::commands::followups::private_dialog_filters;
+use crate::db::triage::{self as db_triage, TriageEntry};
+use crate::telegram::client::{Chat, Message};
+use crate::telegram::{TelegramApi, TelegramClient};
+use std::sync::Arc;
+use tauri::State;
+
+/// Queue an incoming DM from a non-contact, or resolve a pending entry as
+/// "replied" once we've answered
+fn record_last_message(chat: &Chat, msg: &Message) -> Result<(), String> {
+    if msg.is_outgoing {
+        let pending = db_triage::load_entry(chat.id)?.is_some_and(|e| e.status == db_triage::STATUS_PENDING);
+        if pending {
+            db_triage::set_status(chat.id, db_triage::STATUS_REPLIED)?;
+        }
+        Ok(())
+    } else {
+        db_triage::record_message(chat.id, &chat.title, &message_text_for_ai(&msg.content), msg.date)
+    }
+}
+
+fn is_unknown_sender(chat: &Chat, me: Option<i64>) -> bool {
+    chat.chat_type == "private" && !chat.is_contact && !chat.is_bot && Some(chat.id) != me
+}
+
+/// Triage a message as it arrives, so senders are queued while the triage view is closed
+pub(crate) async fn record_from(client: &dyn TelegramApi, message: &Message) {
+    let chat = match client.get_chat(message.chat_id).await {
+        Ok(Some(chat)) => chat,
+        Ok(None) => return,
+        Err(e) => {
+            log::warn!("Failed to look up chat {} for triage: {}", message.chat_id, e);
+            return;
+        }
+    };
+    let me = client.get_current_user().await.map(|u| u.id);
+    if !is_unknown_sender(&chat, me) {
+        return;
+    }
+    if let Err(e) = record_last_message(&chat, message) {
+        log::warn!("Failed to triage message in chat {}: {}", message.chat_id, e);
+    }
+}
+
+/// Catch up on DMs from non-contacts that arrived while the app wasn't running
+async fn scan_unknown_senders(client: &dyn TelegramApi) -> Result<(), String> {
+    let me = client.get_current_user().await.map(|u| u.id);
+    let chats = client.get_chats(200, Some(private_dialog_filters())).await?;
+
+    for chat in chats.iter().filter(|c| is_unknown_sender(c, me)) {
+        if let Some(msg) = &chat.last_message {
+            record_last_message(chat, msg)?;
+        }
+    }
+    Ok(())
+}
+
+/// DMs from non-contacts awaiting a decision. New messages are queued as they
+/// arrive; `refresh` also scans dialogs for ones received while the app was closed.
+#[tauri::command]
+pub async fn get_triage_queue(
+    client: State<'_, Arc<TelegramClient>>,
+    refresh: Option<bool>,
+) -> Result<Vec<TriageEntry>, String> {
+    if refresh.unwrap_or(false) {
+        scan_unknown_senders(client.inner().as_ref()).await?;
+    }
+    db_triage::load_pending()
+}
+
+/// Add a queued sender to contacts, defaulting to the name Telegram shows
+#[tauri::command]
+pub async fn triage_add_contact(
+    client: State<'_, Arc<TelegramClient>>,
+    user_id: i64,
+    first_name: Option<String>,
+    last_name: Option<String>,
+) -> Result<(), String> {
+    let entry = db_triage::load_entry(user_id)?
+        .ok_or_else(|| format!("User {} is not in the triage queue", user_id))?;
+
+    let first_name = first_name.filter(|n| !n.trim().is_empty()).unwrap_or(entry.name);
+    client
+        .add_contact(user_id, first_name.trim(), last_name.as_deref().unwrap_or("").trim())
+        .await?;
+    db_triage::set_status(user_id, db_triage::STATUS_ADDED)
+}
+
+#[tauri::command]
+pub async fn triage_block_sender(
+    client: State<'_, Arc<TelegramClient>>,
+    user_id: i64,
+) -> Result<(), String> {
+    client.block_user(user_id).await?;
+    db_triage::set_status(user_id, db_triage::STATUS_BLOCKED)
+}
+
+/// Drop a sender from the queue without acting; later messages won't re-queue them
+#[tauri::command]
+pub async fn triage_dismiss(user_id: i64) -> Result<(), String> {
+    db_triage::set_status(user_id, db_triage::STATUS_DISMISSED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_new_messages_from_strangers_are_queued() {
+        crate::db::init_test_db();
+        let me = fake::user(1, "Me", "");
+        let stranger = fake::user(7497, "Stranger", "");
+        let client = FakeTelegram::new(me.clone())
+            .with_chat(Chat { is_contact: false, ..fake::chat(7497, "private", "Stranger", 1, 0) })
+            .with_chat(fake::chat(7498, "private", "Friend", 1, 1));
+
+        record_from(&client, &fake::text_message(1, 7497, &stranger, "Hi, got your number from Ann", 100)).await;
+        record_from(&client, &fake::text_message(1, 7498, &fake::user(7498, "Friend", ""), "Lunch?", 100)).await;
+        let entry = db_triage::load_entry(7497).unwrap().unwrap();
+        assert_eq!((entry.status.as_str(), entry.preview.as_str()), (db_triage::STATUS_PENDING, "Hi, got your number from Ann"));
+        assert!(db_triage::load_entry(7498).unwrap().is_none());
+
+        let reply = Message { is_outgoing: true, ..fake::text_message(2, 7497, &me, "Hi!", 200) };
+        record_from(&client, &reply).await;
+        assert_eq!(db_triage::load_entry(7497).unwrap().unwrap().status, db_triage::STATUS_REPLIED);
+    }
+}
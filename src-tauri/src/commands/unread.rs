@@ -0,0 +1,217 @@
+//! Unread-zero sweep: every chat with unread messages, grouped into needs reply,
+//! FYI and muted noise, with a rough time to clear. A quick check-in that needs
+//! no model: chats classified by the latest briefing keep that classification,
+//! the rest are sorted by simple rules.
+
+use crate::ai::context::{message_text_for_ai, unread_backlog};
+use crate::db;
+use crate::telegram::client::{Chat, ChatFilters};
+use crate::telegram::TelegramClient;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::State;
+
+pub const CLASS_NEEDS_REPLY: &str = "needs_reply";
+pub const CLASS_FYI: &str = "fyi";
+pub const CLASS_NOISE: &str = "noise";
+
+pub const SOURCE_BRIEFING: &str = "briefing";
+pub const SOURCE_RULES: &str = "rules";
+
+/// Dialogs scanned for unread messages
+const CHAT_SCAN_LIMIT: i32 = 500;
+
+/// Time spent on a muted chat, capped since noise is rarely read in full
+const SKIM_MAX_SECS: i64 = 30;
+
+/// Writing a reply
+const REPLY_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadChat {
+    pub chat_id: i64,
+    pub title: String,
+    pub chat_type: String,
+    pub unread_count: i32,
+    pub last_message: Option<String>,
+    pub last_message_date: Option<i64>,
+    /// "needs_reply", "fyi" or "noise"
+    pub classification: String,
+    /// "briefing" when taken from the latest briefing, otherwise "rules"
+    pub source: String,
+    pub estimated_secs: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadGroup {
+    pub classification: String,
+    pub chats: Vec<UnreadChat>,
+    pub unread_count: i64,
+    pub estimated_secs: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadReport {
+    /// Needs reply, FYI and noise, in that order (empty groups included)
+    pub groups: Vec<UnreadGroup>,
+    pub total_chats: usize,
+    pub total_unread: i64,
+    pub estimated_secs: i64,
+    pub generated_at: i64,
+}
+
+/// Classification of a chat the briefing didn't cover
+fn classify_by_rules(chat: &Chat) -> &'static str {
+    if chat.is_muted || chat.is_archived {
+        return CLASS_NOISE;
+    }
+    let Some(last) = &chat.last_message else {
+        return CLASS_FYI;
+    };
+    let waiting_on_me = match chat.chat_type.as_str() {
+        "private" => !chat.is_bot && !last.is_outgoing,
+        "group" => last.mentions_me,
+        _ => false,
+    };
+    if waiting_on_me {
+        CLASS_NEEDS_REPLY
+    } else {
+        CLASS_FYI
+    }
+}
+
+/// Time to clear a chat, reading it the same way briefing items estimate their backlog
+fn estimate_secs(classification: &str, unread_count: i32) -> i64 {
+    let (_, reading_secs) = unread_backlog(&[], unread_count.max(0));
+    let reading_secs = reading_secs as i64;
+    match classification {
+        CLASS_NOISE => reading_secs.min(SKIM_MAX_SECS),
+        CLASS_NEEDS_REPLY => reading_secs + REPLY_SECS,
+        _ => reading_secs,
+    }
+}
+
+/// Group unread chats by classification. Muted and archived chats are always
+/// noise; otherwise `briefing` (chat id to classification) wins over the rules.
+fn build_unread_report(chats: Vec<Chat>, briefing: &HashMap<i64, &'static str>, now: i64) -> UnreadReport {
+    let mut groups: Vec<UnreadGroup> = [CLASS_NEEDS_REPLY, CLASS_FYI, CLASS_NOISE]
+        .iter()
+        .map(|class| UnreadGroup {
+            classification: class.to_string(),
+            chats: vec![],
+            unread_count: 0,
+            estimated_secs: 0,
+        })
+        .collect();
+
+    for chat in chats.into_iter().filter(|c| c.unread_count > 0) {
+        let (classification, source) = match briefing.get(&chat.id) {
+            Some(class) if !chat.is_muted && !chat.is_archived => (*class, SOURCE_BRIEFING),
+            _ => (classify_by_rules(&chat), SOURCE_RULES),
+        };
+        let item = UnreadChat {
+            chat_id: chat.id,
+            title: chat.title,
+            chat_type: chat.chat_type,
+            unread_count: chat.unread_count,
+            last_message: chat.last_message.as_ref().map(|m| message_text_for_ai(&m.content)),
+            last_message_date: chat.last_message.as_ref().map(|m| m.date),
+            classification: classification.to_string(),
+            source: source.to_string(),
+            estimated_secs: estimate_secs(classification, chat.unread_count),
+        };
+        let group = groups
+            .iter_mut()
+            .find(|g| g.classification == classification)
+            .expect("every classification has a group");
+        group.unread_count += item.unread_count as i64;
+        group.estimated_secs += item.estimated_secs;
+        group.chats.push(item);
+    }
+
+    // Within a group, the chat waiting longest comes first
+    for group in &mut groups {
+        group.chats.sort_by_key(|c| c.last_message_date.unwrap_or(i64::MAX));
+    }
+
+    UnreadReport {
+        total_chats: groups.iter().map(|g| g.chats.len()).sum(),
+        total_unread: groups.iter().map(|g| g.unread_count).sum(),
+        estimated_secs: groups.iter().map(|g| g.estimated_secs).sum(),
+        groups,
+        generated_at: now,
+    }
+}
+
+/// Classifications from the latest briefing, by chat id
+fn briefing_classifications() -> HashMap<i64, &'static str> {
+    let Ok(Some(briefing)) = db::briefings::load_latest_briefing() else {
+        return HashMap::new();
+    };
+    let mut classes: HashMap<i64, &'static str> =
+        briefing.fyi_summaries.iter().map(|item| (item.chat_id, CLASS_FYI)).collect();
+    classes.extend(briefing.needs_response.iter().map(|item| (item.chat_id, CLASS_NEEDS_REPLY)));
+    classes
+}
+
+/// Every chat with unread messages, grouped by what it needs, with a time to clear
+#[tauri::command]
+pub async fn generate_unread_report(client: State<'_, Arc<TelegramClient>>) -> Result<UnreadReport, String> {
+    let filters = ChatFilters {
+        include_private_chats: true,
+        include_non_contacts: true,
+        include_groups: true,
+        include_channels: true,
+        include_bots: true,
+        include_archived: true,
+        include_muted: true,
+        include_unread_only: true,
+        ..Default::default()
+    };
+    let chats = client.get_chats(CHAT_SCAN_LIMIT, Some(filters)).await?;
+    Ok(build_unread_report(chats, &briefing_classifications(), chrono::Utc::now().timestamp()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::client::Message;
+    use crate::telegram::fake;
+
+    #[test]
+    fn test_unread_report_groups_chats() {
+        let friend = fake::user(7521, "Ann", "");
+        let with_last = |chat: Chat, message: Message| Chat { last_message: Some(message), ..chat };
+        let dm = with_last(
+            fake::chat(7521, "private", "Ann", 2, 0),
+            fake::text_message(1, 7521, &friend, "Lunch?", 100),
+        );
+        let group = with_last(
+            fake::chat(7522, "group", "Team", 10, 1),
+            fake::text_message(2, 7522, &friend, "FYI", 50),
+        );
+        let muted = Chat { is_muted: true, ..fake::chat(7523, "channel", "Deals", 200, 2) };
+        let read = fake::chat(7524, "private", "Bob", 0, 3);
+        let briefed = fake::chat(7525, "group", "Investors", 1, 4);
+
+        let briefing = HashMap::from([(7525, CLASS_NEEDS_REPLY), (7523, CLASS_NEEDS_REPLY)]);
+        let report = build_unread_report(vec![dm, group, muted, read, briefed], &briefing, 1000);
+
+        let ids = |class: usize| report.groups[class].chats.iter().map(|c| c.chat_id).collect::<Vec<_>>();
+        assert_eq!(ids(0), vec![7521, 7525]);
+        assert_eq!(ids(1), vec![7522]);
+        // Muted chats stay noise even if the briefing flagged them
+        assert_eq!(ids(2), vec![7523]);
+        assert_eq!(report.groups[0].chats[1].source, SOURCE_BRIEFING);
+
+        assert_eq!((report.total_chats, report.total_unread), (4, 213));
+        assert_eq!(report.groups[2].estimated_secs, SKIM_MAX_SECS);
+        let reading_secs = |unread| unread_backlog(&[], unread).1 as i64;
+        assert_eq!(report.groups[0].estimated_secs, reading_secs(2) + reading_secs(1) + 2 * REPLY_SECS);
+        assert_eq!(report.groups[1].estimated_secs, reading_secs(10));
+    }
+}
@@ -0,0 +1,180 @@
+//! Group onboarding, the community-manager counterpart of offboarding: send a
+//! group's welcome kit to a new member (rules and resources posted in the group,
+//! then an optional intro DM), paced so a wave of joins can't trigger a FLOOD_WAIT.
+
+use crate::commands::contacts::log_event;
+use crate::commands::outreach::extract_flood_wait_seconds;
+use crate::db::contacts::EVENT_WELCOMED_TO_GROUP;
+use crate::db::welcome_kits::{self as db_welcome, WelcomeKit};
+use crate::telegram::{TelegramApi, TelegramClient};
+use crate::utils::rate_limiter::RateLimiter;
+use crate::utils::template::fill_placeholders;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+
+/// Pause between the messages of a welcome sequence
+const WELCOME_STEP_DELAY: Duration = Duration::from_secs(3);
+
+const STEP_RULES: &str = "rules";
+const STEP_RESOURCES: &str = "resources";
+const STEP_INTRO_DM: &str = "intro_dm";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WelcomeResult {
+    pub chat_id: i64,
+    pub user_id: i64,
+    /// Steps sent, in order ("rules", "resources", "intro_dm")
+    pub sent: Vec<String>,
+}
+
+/// Name of a user from their DM or the contact list
+async fn member_name(client: &dyn TelegramApi, user_id: i64) -> Result<String, String> {
+    // Private chat id equals the user id
+    if let Some(dm) = client.get_chat(user_id).await? {
+        return Ok(dm.title);
+    }
+    Ok(client
+        .get_contacts()
+        .await?
+        .into_iter()
+        .find(|u| u.id == user_id)
+        .map(|u| format!("{} {}", u.first_name, u.last_name).trim().to_string())
+        .unwrap_or_else(|| "new member".to_string()))
+}
+
+/// Send the steps of `kit` to new member `user_id`, `step_delay` apart. Stops at
+/// the first failure; a FLOOD_WAIT pauses the shared rate limiter.
+pub(crate) async fn send_welcome_sequence(
+    client: &dyn TelegramApi,
+    limiter: &RateLimiter,
+    kit: &WelcomeKit,
+    user_id: i64,
+    step_delay: Duration,
+) -> Result<Vec<String>, String> {
+    let group = client
+        .get_chat(kit.chat_id)
+        .await?
+        .ok_or_else(|| format!("Chat {} not found", kit.chat_id))?;
+    if let Err(wait) = limiter.can_send(user_id) {
+        return Err(format!("Rate limited, try again in {}s", wait));
+    }
+
+    let name = member_name(client, user_id).await?;
+    let values = HashMap::from([
+        ("first_name".to_string(), name.split_whitespace().next().unwrap_or("").to_string()),
+        ("name".to_string(), name),
+        ("group".to_string(), group.title.clone()),
+    ]);
+
+    let mut steps: Vec<(&str, i64, String)> = vec![];
+    if !kit.rules_message.trim().is_empty() {
+        steps.push((STEP_RULES, group.id, fill_placeholders(&kit.rules_message, &values)));
+    }
+    let resources: Vec<&String> = kit.resources.iter().filter(|r| !r.trim().is_empty()).collect();
+    if !resources.is_empty() {
+        let list: Vec<String> = resources.iter().map(|r| format!("• {}", fill_placeholders(r, &values))).collect();
+        steps.push((STEP_RESOURCES, group.id, format!("Resources:\n{}", list.join("\n"))));
+    }
+    if let Some(intro) = kit.intro_dm.as_deref().filter(|t| !t.trim().is_empty()) {
+        steps.push((STEP_INTRO_DM, user_id, fill_placeholders(intro, &values)));
+    }
+    if steps.is_empty() {
+        return Err(format!("The welcome kit for {} is empty", group.title));
+    }
+
+    let total = steps.len();
+    let mut sent = vec![];
+    for (idx, (step, chat_id, text)) in steps.into_iter().enumerate() {
+        if idx > 0 {
+            tokio::time::sleep(step_delay).await;
+        }
+        if let Err(e) = client.send_message(chat_id, &text).await {
+            if let Some(wait) = extract_flood_wait_seconds(&e) {
+                limiter.handle_flood_wait(wait);
+            }
+            return Err(format!("Failed to send {} ({} of {} sent): {}", step, idx, total, e));
+        }
+        if chat_id == user_id {
+            limiter.record_send(user_id);
+        }
+        sent.push(step.to_string());
+    }
+
+    log_event(user_id, EVENT_WELCOMED_TO_GROUP, &group.title);
+    log::info!("Welcomed {} to {} ({} steps)", user_id, group.title, total);
+    Ok(sent)
+}
+
+#[tauri::command]
+pub async fn get_welcome_kits() -> Result<Vec<WelcomeKit>, String> {
+    db_welcome::load_kits()
+}
+
+#[tauri::command]
+pub async fn save_welcome_kit(kit: WelcomeKit) -> Result<(), String> {
+    db_welcome::save_kit(&kit)
+}
+
+#[tauri::command]
+pub async fn delete_welcome_kit(chat_id: i64) -> Result<(), String> {
+    db_welcome::delete_kit(chat_id)
+}
+
+/// Send the group's welcome kit to a new member
+#[tauri::command]
+pub async fn send_welcome_kit(
+    client: State<'_, Arc<TelegramClient>>,
+    limiter: State<'_, Arc<RateLimiter>>,
+    chat_id: i64,
+    user_id: i64,
+) -> Result<WelcomeResult, String> {
+    let kit = db_welcome::load_kit(chat_id)?
+        .ok_or_else(|| format!("No welcome kit is set up for chat {}", chat_id))?;
+    let sent = send_welcome_sequence(client.inner().as_ref(), limiter.inner().as_ref(), &kit, user_id, WELCOME_STEP_DELAY)
+        .await?;
+    Ok(WelcomeResult { chat_id, user_id, sent })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_welcome_sequence() {
+        crate::db::init_test_db();
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(fake::chat(7471, "group", "Rust Meetup", 0, 0))
+            .with_contact(fake::user(7472, "Grace", "Hopper"));
+        let kit = WelcomeKit {
+            chat_id: 7471,
+            rules_message: "Welcome {first_name} to {group}! Be kind.".to_string(),
+            resources: vec!["https://example.com/guide".to_string(), " ".to_string()],
+            intro_dm: Some("Hi {name}, glad you joined".to_string()),
+        };
+        db_welcome::save_kit(&kit).unwrap();
+        let kit = db_welcome::load_kit(7471).unwrap().unwrap();
+        let limiter = RateLimiter::new(60);
+
+        let sent = send_welcome_sequence(&client, &limiter, &kit, 7472, Duration::ZERO).await.unwrap();
+        assert_eq!(sent, vec![STEP_RULES, STEP_RESOURCES, STEP_INTRO_DM]);
+        assert_eq!(
+            client.sent(),
+            vec![
+                (7471, "Welcome Grace to Rust Meetup! Be kind.".to_string()),
+                (7471, "Resources:\n• https://example.com/guide".to_string()),
+                (7472, "Hi Grace Hopper, glad you joined".to_string()),
+            ]
+        );
+
+        // Just DMed: a repeat is held back
+        assert!(send_welcome_sequence(&client, &limiter, &kit, 7472, Duration::ZERO).await.is_err());
+
+        let empty = WelcomeKit { chat_id: 7471, rules_message: String::new(), resources: vec![], intro_dm: None };
+        assert!(send_welcome_sequence(&client, &RateLimiter::new(0), &empty, 7472, Duration::ZERO).await.is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use super::with_db;
+use serde::Serialize;
+
+/// Entries kept; older ones are pruned on insert
+const MAX_ENTRIES: i64 = 2000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AICallRecord {
+    pub id: i64,
+    pub provider: String,
+    pub model: String,
+    /// Request messages as JSON
+    pub prompt: String,
+    pub response: Option<String>,
+    pub error: Option<String>,
+    pub latency_ms: i64,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub created_at: i64,
+}
+
+pub struct NewAICall<'a> {
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub prompt: &'a str,
+    pub response: Option<&'a str>,
+    pub error: Option<&'a str>,
+    pub latency_ms: i64,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+}
+
+pub fn record_call(call: &NewAICall) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO ai_call_log (provider, model, prompt, response, error, latency_ms, prompt_tokens, completion_tokens)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            rusqlite::params![
+                call.provider,
+                call.model,
+                call.prompt,
+                call.response,
+                call.error,
+                call.latency_ms,
+                call.prompt_tokens,
+                call.completion_tokens
+            ],
+        )
+        .map_err(|e| format!("Failed to record AI call: {}", e))?;
+        conn.execute(
+            "DELETE FROM ai_call_log WHERE id <= (SELECT MAX(id) FROM ai_call_log) - ?",
+            [MAX_ENTRIES],
+        )
+        .map_err(|e| format!("Failed to prune AI call log: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Most recent calls first
+pub fn load_calls(limit: i64) -> Result<Vec<AICallRecord>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, provider, model, prompt, response, error, latency_ms, prompt_tokens, completion_tokens, created_at
+                 FROM ai_call_log ORDER BY id DESC LIMIT ?",
+            )
+            .map_err(|e| format!("Failed to prepare AI call log query: {}", e))?;
+        let rows = stmt
+            .query_map([limit], |row| {
+                Ok(AICallRecord {
+                    id: row.get(0)?,
+                    provider: row.get(1)?,
+                    model: row.get(2)?,
+                    prompt: row.get(3)?,
+                    response: row.get(4)?,
+                    error: row.get(5)?,
+                    latency_ms: row.get(6)?,
+                    prompt_tokens: row.get(7)?,
+                    completion_tokens: row.get(8)?,
+                    created_at: row.get(9)?,
+                })
+            })
+            .map_err(|e| format!("Failed to load AI call log: {}", e))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read AI call log: {}", e))
+    })
+}
+
+/// Remove every entry; returns the number removed
+pub fn clear() -> Result<usize, String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM ai_call_log", [])
+            .map_err(|e| format!("Failed to clear AI call log: {}", e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call<'a>(model: &'a str, error: Option<&'a str>) -> NewAICall<'a> {
+        NewAICall {
+            provider: "OpenAI",
+            model,
+            prompt: "[]",
+            response: error.is_none().then_some("{}"),
+            error,
+            latency_ms: 120,
+            prompt_tokens: Some(50),
+            completion_tokens: error.is_none().then_some(10),
+        }
+    }
+
+    #[test]
+    fn test_record_prune_and_clear() {
+        crate::db::init_test_db();
+        record_call(&call("gpt-4o-mini", None)).unwrap();
+        record_call(&call("gpt-4o", Some("Request failed: timeout"))).unwrap();
+
+        let calls = load_calls(2).unwrap();
+        assert_eq!(calls[0].model, "gpt-4o");
+        assert_eq!((calls[0].response.as_deref(), calls[0].error.as_deref()), (None, Some("Request failed: timeout")));
+        assert_eq!((calls[1].prompt_tokens, calls[1].completion_tokens), (Some(50), Some(10)));
+
+        // Only the newest entries are kept
+        for _ in 0..MAX_ENTRIES {
+            record_call(&call("llama3.2", None)).unwrap();
+        }
+        let calls = load_calls(MAX_ENTRIES + 10).unwrap();
+        assert_eq!(calls.len() as i64, MAX_ENTRIES);
+        assert!(calls.iter().all(|c| c.model == "llama3.2"));
+
+        assert_eq!(clear().unwrap() as i64, MAX_ENTRIES);
+        assert!(load_calls(10).unwrap().is_empty());
+    }
+}
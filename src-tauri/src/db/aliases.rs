@@ -0,0 +1,80 @@
+//! Local nicknames for chats, shown in place of their Telegram titles
+
+use super::with_db;
+use crate::telegram::client::Chat;
+use std::collections::HashMap;
+
+pub fn load_aliases() -> Result<HashMap<i64, String>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT chat_id, alias FROM chat_aliases")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let aliases = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to query chat aliases: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(aliases)
+    })
+}
+
+/// Set a chat's nickname; None or a blank alias removes it
+pub fn set_alias(chat_id: i64, alias: Option<&str>) -> Result<(), String> {
+    let alias = alias.map(str::trim).filter(|a| !a.is_empty());
+    with_db(|conn| {
+        match alias {
+            Some(alias) => conn.execute(
+                r#"
+                INSERT INTO chat_aliases (chat_id, alias, updated_at)
+                VALUES (?1, ?2, strftime('%s', 'now'))
+                ON CONFLICT(chat_id) DO UPDATE SET alias = ?2, updated_at = strftime('%s', 'now')
+                "#,
+                rusqlite::params![chat_id, alias],
+            ),
+            None => conn.execute("DELETE FROM chat_aliases WHERE chat_id = ?", [chat_id]),
+        }
+        .map_err(|e| format!("Failed to save chat alias: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Replace titles with nicknames, keeping the Telegram title in `original_title`
+pub fn apply(chats: &mut [Chat]) {
+    let aliases = match load_aliases() {
+        Ok(aliases) => aliases,
+        Err(e) => {
+            log::warn!("Failed to load chat aliases: {}", e);
+            return;
+        }
+    };
+    for chat in chats.iter_mut() {
+        if let Some(alias) = aliases.get(&chat.id) {
+            chat.original_title = Some(std::mem::replace(&mut chat.title, alias.clone()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake;
+
+    #[test]
+    fn test_aliases_replace_titles() {
+        crate::db::init_test_db();
+        set_alias(7301, Some("  Work: Frontend ")).unwrap();
+        set_alias(7302, Some("Temp")).unwrap();
+        set_alias(7302, Some("   ")).unwrap();
+
+        let mut chats = vec![
+            fake::chat(7301, "group", "Frontend Guild 🚀🔥", 0, 1),
+            fake::chat(7302, "group", "Other", 0, 2),
+        ];
+        apply(&mut chats);
+        assert_eq!(chats[0].title, "Work: Frontend");
+        assert_eq!(chats[0].original_title.as_deref(), Some("Frontend Guild 🚀🔥"));
+        assert_eq!((chats[1].title.as_str(), chats[1].original_title.as_deref()), ("Other", None));
+    }
+}
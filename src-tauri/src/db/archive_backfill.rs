@@ -0,0 +1,109 @@
+//! Checkpoints of the background archive backfill: how far each scoped chat has
+//! got. Where a chat's archive starts is `message_sync_state`'s oldest message.
+
+use super::with_db;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillCheckpoint {
+    pub chat_id: i64,
+    pub pages: i64,
+    /// Archived back to the chat's first message or the retention window
+    pub complete: bool,
+    pub last_error: Option<String>,
+    pub updated_at: i64,
+}
+
+pub fn load_checkpoints() -> Result<Vec<BackfillCheckpoint>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT chat_id, pages, complete, last_error, updated_at FROM archive_backfill")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let checkpoints = stmt
+            .query_map([], |row| {
+                Ok(BackfillCheckpoint {
+                    chat_id: row.get(0)?,
+                    pages: row.get(1)?,
+                    complete: row.get(2)?,
+                    last_error: row.get(3)?,
+                    updated_at: row.get(4)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query backfill checkpoints: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(checkpoints)
+    })
+}
+
+/// Count a page archived for a chat
+pub fn record_page(chat_id: i64, complete: bool, now: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO archive_backfill (chat_id, pages, complete, last_error, updated_at)
+            VALUES (?1, 1, ?2, NULL, ?3)
+            ON CONFLICT(chat_id) DO UPDATE SET
+                pages = pages + 1,
+                complete = excluded.complete,
+                last_error = NULL,
+                updated_at = excluded.updated_at
+            "#,
+            rusqlite::params![chat_id, complete, now],
+        )
+        .map_err(|e| format!("Failed to save backfill checkpoint: {}", e))?;
+        Ok(())
+    })
+}
+
+pub fn record_error(chat_id: i64, error: &str, now: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO archive_backfill (chat_id, pages, complete, last_error, updated_at)
+            VALUES (?1, 0, 0, ?2, ?3)
+            ON CONFLICT(chat_id) DO UPDATE SET last_error = excluded.last_error, updated_at = excluded.updated_at
+            "#,
+            rusqlite::params![chat_id, error, now],
+        )
+        .map_err(|e| format!("Failed to save backfill checkpoint: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Forget all progress, so the next pass starts every chat over
+pub fn clear() -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM archive_backfill", [])
+            .map_err(|e| format!("Failed to clear backfill checkpoints: {}", e))?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint(chat_id: i64) -> Option<BackfillCheckpoint> {
+        load_checkpoints().unwrap().into_iter().find(|c| c.chat_id == chat_id)
+    }
+
+    #[test]
+    fn test_pages_and_errors_are_checkpointed() {
+        crate::db::init_test_db();
+
+        record_error(7541, "CHANNEL_PRIVATE", 100).unwrap();
+        let failed = checkpoint(7541).unwrap();
+        assert_eq!((failed.pages, failed.complete), (0, false));
+        assert_eq!(failed.last_error.as_deref(), Some("CHANNEL_PRIVATE"));
+
+        record_page(7541, false, 200).unwrap();
+        record_page(7541, true, 300).unwrap();
+        let done = checkpoint(7541).unwrap();
+        assert_eq!((done.pages, done.complete, done.updated_at), (2, true, 300));
+        assert!(done.last_error.is_none());
+    }
+}
@@ -0,0 +1,81 @@
+use super::with_db;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InjectionAuditEntry {
+    pub id: i64,
+    pub chat_id: i64,
+    pub chat_title: String,
+    pub sender_name: String,
+    pub excerpt: String,
+    pub signals: Vec<String>,
+    pub score: u32,
+    pub detected_at: i64,
+}
+
+/// Record a flagged message; the same message seen again (e.g. in the next briefing) is not duplicated
+pub fn record_injection(
+    chat_id: i64,
+    chat_title: &str,
+    sender_name: &str,
+    excerpt: &str,
+    signals: &[&str],
+    score: u32,
+) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT OR IGNORE INTO injection_audit (chat_id, chat_title, sender_name, excerpt, signals, score)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            rusqlite::params![chat_id, chat_title, sender_name, excerpt, signals.join(","), score],
+        )
+        .map_err(|e| format!("Failed to record injection audit: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Most recent audit entries first
+pub fn load_injection_audit(limit: i64) -> Result<Vec<InjectionAuditEntry>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT id, chat_id, chat_title, sender_name, excerpt, signals, score, detected_at
+                FROM injection_audit
+                ORDER BY detected_at DESC, id DESC
+                LIMIT ?
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let entries = stmt
+            .query_map([limit], |row| {
+                let signals: String = row.get(5)?;
+                Ok(InjectionAuditEntry {
+                    id: row.get(0)?,
+                    chat_id: row.get(1)?,
+                    chat_title: row.get(2)?,
+                    sender_name: row.get(3)?,
+                    excerpt: row.get(4)?,
+                    signals: signals.split(',').filter(|s| !s.is_empty()).map(String::from).collect(),
+                    score: row.get(6)?,
+                    detected_at: row.get(7)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query injection audit: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    })
+}
+
+pub fn clear_injection_audit() -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM injection_audit", [])
+            .map_err(|e| format!("Failed to clear injection audit: {}", e))?;
+        Ok(())
+    })
+}
@@ -0,0 +1,66 @@
+use super::with_db;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AwayReply {
+    pub user_id: i64,
+    pub name: String,
+    pub text: String,
+    pub replied_at: i64,
+}
+
+pub fn record_reply(user_id: i64, name: &str, text: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO away_replies (user_id, name, text) VALUES (?, ?, ?)",
+            rusqlite::params![user_id, name, text],
+        )
+        .map_err(|e| format!("Failed to record away reply: {}", e))?;
+        Ok(())
+    })
+}
+
+pub fn replied_since(user_id: i64, since: i64) -> Result<bool, String> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM away_replies WHERE user_id = ? AND replied_at >= ?)",
+            rusqlite::params![user_id, since],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to check away replies: {}", e))
+    })
+}
+
+pub fn count_since(since: i64) -> Result<i64, String> {
+    with_db(|conn| {
+        conn.query_row("SELECT COUNT(*) FROM away_replies WHERE replied_at >= ?", [since], |row| row.get(0))
+            .map_err(|e| format!("Failed to count away replies: {}", e))
+    })
+}
+
+/// Replies sent since `since`, newest first
+pub fn load_replies(since: i64) -> Result<Vec<AwayReply>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                "SELECT user_id, name, text, replied_at FROM away_replies WHERE replied_at >= ? ORDER BY replied_at DESC, id DESC",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let replies = stmt
+            .query_map([since], |row| {
+                Ok(AwayReply {
+                    user_id: row.get(0)?,
+                    name: row.get(1)?,
+                    text: row.get(2)?,
+                    replied_at: row.get(3)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query away replies: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(replies)
+    })
+}
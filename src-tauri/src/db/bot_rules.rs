@@ -0,0 +1,261 @@
+use super::with_db;
+use serde::Serialize;
+
+/// Messages from one bot
+pub const TRIGGER_BOT: &str = "bot";
+/// Incoming DMs from people
+pub const TRIGGER_DM: &str = "dm";
+
+pub const ACTION_FORWARD: &str = "forward";
+pub const ACTION_TAG: &str = "tag";
+pub const ACTION_NOTIFY: &str = "notify";
+pub const ACTION_EXTRACT: &str = "extract";
+
+/// Reply in the chat with a canned response
+pub const ACTION_REPLY: &str = "reply";
+
+pub const ACTIONS: [&str; 4] = [ACTION_FORWARD, ACTION_TAG, ACTION_NOTIFY, ACTION_EXTRACT];
+
+/// Routes messages that match `pattern` (a regex over the message text), from one
+/// bot or from any person in a DM. `target` depends on the action: the chat id to
+/// forward to, the tag to apply, the extraction instruction for the LLM or the
+/// canned response shortcut to reply with; unused for "notify".
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BotRule {
+    pub id: i64,
+    pub name: String,
+    pub trigger: String,
+    /// The sending bot for "bot" rules; 0 for "dm" rules
+    pub bot_id: i64,
+    pub pattern: String,
+    pub action: String,
+    pub target: Option<String>,
+    /// Most successful runs per local day; None for no limit
+    pub daily_cap: Option<i64>,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+pub struct NewBotRule {
+    pub name: String,
+    pub trigger: String,
+    pub bot_id: i64,
+    pub pattern: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub daily_cap: Option<i64>,
+}
+
+/// A message that matched a rule, with what the action produced
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BotRuleHit {
+    pub id: i64,
+    pub rule_id: i64,
+    pub rule_name: String,
+    pub action: String,
+    pub chat_id: i64,
+    pub message_id: i64,
+    pub text: String,
+    /// The tag for "tag" rules, the LLM's answer for "extract" rules, the sent
+    /// text for "reply" rules
+    pub result: Option<String>,
+    /// Set when the action failed
+    pub error: Option<String>,
+    pub matched_at: i64,
+}
+
+const RULE_COLUMNS: &str = "id, name, trigger, bot_id, pattern, action, target, daily_cap, enabled, created_at";
+
+fn row_to_rule(row: &rusqlite::Row) -> rusqlite::Result<BotRule> {
+    Ok(BotRule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        trigger: row.get(2)?,
+        bot_id: row.get(3)?,
+        pattern: row.get(4)?,
+        action: row.get(5)?,
+        target: row.get(6)?,
+        daily_cap: row.get(7)?,
+        enabled: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+pub fn add_rule(rule: &NewBotRule) -> Result<BotRule, String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO bot_rules (name, trigger, bot_id, pattern, action, target, daily_cap)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            rusqlite::params![
+                rule.name,
+                rule.trigger,
+                rule.bot_id,
+                rule.pattern,
+                rule.action,
+                rule.target,
+                rule.daily_cap
+            ],
+        )
+        .map_err(|e| format!("Failed to save bot rule: {}", e))?;
+        conn.query_row(
+            &format!("SELECT {} FROM bot_rules WHERE id = ?", RULE_COLUMNS),
+            [conn.last_insert_rowid()],
+            row_to_rule,
+        )
+        .map_err(|e| format!("Failed to load bot rule: {}", e))
+    })
+}
+
+pub fn load_rules() -> Result<Vec<BotRule>, String> {
+    load_rules_where("1 = 1", &[])
+}
+
+/// Enabled rules that can fire for a message from `sender_id`: its bot rules and
+/// all DM rules, oldest first
+pub fn load_enabled_for_sender(sender_id: i64) -> Result<Vec<BotRule>, String> {
+    load_rules_where(
+        "enabled = 1 AND ((trigger = ? AND bot_id = ?) OR trigger = ?)",
+        &[&TRIGGER_BOT, &sender_id, &TRIGGER_DM],
+    )
+}
+
+fn load_rules_where(condition: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<BotRule>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(&format!("SELECT {} FROM bot_rules WHERE {} ORDER BY id", RULE_COLUMNS, condition))
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rules = stmt
+            .query_map(params, row_to_rule)
+            .map_err(|e| format!("Failed to query bot rules: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(rules)
+    })
+}
+
+pub fn set_enabled(id: i64, enabled: bool) -> Result<(), String> {
+    with_db(|conn| {
+        let updated = conn
+            .execute("UPDATE bot_rules SET enabled = ? WHERE id = ?", rusqlite::params![enabled, id])
+            .map_err(|e| format!("Failed to update bot rule: {}", e))?;
+        if updated == 0 {
+            return Err(format!("Bot rule {} not found", id));
+        }
+        Ok(())
+    })
+}
+
+/// Delete a rule along with its recorded hits
+pub fn delete_rule(id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM bot_rules WHERE id = ?", [id])
+            .map_err(|e| format!("Failed to delete bot rule: {}", e))?;
+        Ok(())
+    })
+}
+
+pub fn record_hit(
+    rule: &BotRule,
+    chat_id: i64,
+    message_id: i64,
+    text: &str,
+    result: Option<&str>,
+    error: Option<&str>,
+) -> Result<BotRuleHit, String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO bot_rule_hits (rule_id, chat_id, message_id, text, result, error)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+            rusqlite::params![rule.id, chat_id, message_id, text, result, error],
+        )
+        .map_err(|e| format!("Failed to record bot rule hit: {}", e))?;
+
+        Ok(BotRuleHit {
+            id: conn.last_insert_rowid(),
+            rule_id: rule.id,
+            rule_name: rule.name.clone(),
+            action: rule.action.clone(),
+            chat_id,
+            message_id,
+            text: text.to_string(),
+            result: result.map(str::to_string),
+            error: error.map(str::to_string),
+            matched_at: chrono::Utc::now().timestamp(),
+        })
+    })
+}
+
+/// Hits of a rule since `since` whose action succeeded
+pub fn count_successful_hits(rule_id: i64, since: i64) -> Result<i64, String> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT COUNT(*) FROM bot_rule_hits WHERE rule_id = ? AND matched_at >= ? AND error IS NULL",
+            rusqlite::params![rule_id, since],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to count bot rule hits: {}", e))
+    })
+}
+
+/// Whether a reply rule already answered this message
+pub fn replied_to_message(chat_id: i64, message_id: i64) -> Result<bool, String> {
+    with_db(|conn| {
+        conn.query_row(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM bot_rule_hits h JOIN bot_rules r ON r.id = h.rule_id
+                WHERE h.chat_id = ? AND h.message_id = ? AND r.action = ? AND h.error IS NULL
+            )
+            "#,
+            rusqlite::params![chat_id, message_id, ACTION_REPLY],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to check bot rule replies: {}", e))
+    })
+}
+
+/// Most recent hits first
+pub fn load_hits(limit: i64) -> Result<Vec<BotRuleHit>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT h.id, h.rule_id, r.name, r.action, h.chat_id, h.message_id, h.text, h.result, h.error, h.matched_at
+                FROM bot_rule_hits h
+                JOIN bot_rules r ON r.id = h.rule_id
+                ORDER BY h.matched_at DESC, h.id DESC
+                LIMIT ?
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let hits = stmt
+            .query_map([limit], |row| {
+                Ok(BotRuleHit {
+                    id: row.get(0)?,
+                    rule_id: row.get(1)?,
+                    rule_name: row.get(2)?,
+                    action: row.get(3)?,
+                    chat_id: row.get(4)?,
+                    message_id: row.get(5)?,
+                    text: row.get(6)?,
+                    result: row.get(7)?,
+                    error: row.get(8)?,
+                    matched_at: row.get(9)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query bot rule hits: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(hits)
+    })
+}
@@ -0,0 +1,132 @@
+//! User-defined briefing categories ("Sales leads", "Support", ...) that the
+//! model tags chats with, on top of urgent / needs reply / FYI
+
+use super::with_db;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BriefingCategory {
+    pub id: i64,
+    pub name: String,
+    /// What belongs in the category, shown to the model
+    pub description: String,
+    /// Order of the category's section in the briefing
+    pub position: i64,
+    pub created_at: i64,
+}
+
+fn row_to_category(row: &rusqlite::Row) -> rusqlite::Result<BriefingCategory> {
+    Ok(BriefingCategory {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        position: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+/// Categories in section order
+pub fn load_categories() -> Result<Vec<BriefingCategory>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT id, name, description, position, created_at FROM briefing_categories ORDER BY position, id")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let categories = stmt
+            .query_map([], row_to_category)
+            .map_err(|e| format!("Failed to query briefing categories: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(categories)
+    })
+}
+
+/// Add a category after the existing ones, or update the category `id`.
+/// Names are unique regardless of case.
+pub fn save_category(id: Option<i64>, name: &str, description: &str) -> Result<i64, String> {
+    with_db(|conn| {
+        let result = match id {
+            Some(id) => conn
+                .execute(
+                    "UPDATE briefing_categories SET name = ?, description = ? WHERE id = ?",
+                    rusqlite::params![name, description, id],
+                )
+                .map(|updated| (updated > 0).then_some(id)),
+            None => conn
+                .execute(
+                    r#"
+                    INSERT INTO briefing_categories (name, description, position)
+                    VALUES (?, ?, (SELECT COALESCE(MAX(position), -1) + 1 FROM briefing_categories))
+                    "#,
+                    rusqlite::params![name, description],
+                )
+                .map(|_| Some(conn.last_insert_rowid())),
+        };
+
+        match result {
+            Ok(Some(id)) => Ok(id),
+            Ok(None) => Err(format!("Category {} not found", id.unwrap_or_default())),
+            Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == rusqlite::ErrorCode::ConstraintViolation => {
+                Err(format!("A category named \"{}\" already exists", name))
+            }
+            Err(e) => Err(format!("Failed to save briefing category: {}", e)),
+        }
+    })
+}
+
+/// Put the categories in the order of `ids`; categories not listed keep their place after them
+pub fn reorder_categories(ids: &[i64]) -> Result<(), String> {
+    with_db(|conn| {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        tx.execute("UPDATE briefing_categories SET position = position + ?", [ids.len() as i64])
+            .map_err(|e| format!("Failed to reorder briefing categories: {}", e))?;
+        for (position, id) in ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE briefing_categories SET position = ? WHERE id = ?",
+                rusqlite::params![position as i64, id],
+            )
+            .map_err(|e| format!("Failed to reorder briefing categories: {}", e))?;
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit category order: {}", e))
+    })
+}
+
+pub fn delete_category(id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM briefing_categories WHERE id = ?", [id])
+            .map_err(|e| format!("Failed to delete briefing category: {}", e))?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categories_keep_their_order() {
+        crate::db::init_test_db();
+        let leads = save_category(None, "Leads 7541", "Potential customers").unwrap();
+        let support = save_category(None, "Support 7541", "Customers with problems").unwrap();
+        assert!(save_category(None, "leads 7541", "Duplicate").is_err());
+
+        save_category(Some(support), "Support 7541", "Customers asking for help").unwrap();
+        reorder_categories(&[support, leads]).unwrap();
+        let ours = || -> Vec<BriefingCategory> {
+            load_categories().unwrap().into_iter().filter(|c| c.name.ends_with("7541")).collect()
+        };
+        let categories = ours();
+        assert_eq!(categories.iter().map(|c| c.id).collect::<Vec<_>>(), vec![support, leads]);
+        assert_eq!(categories[0].description, "Customers asking for help");
+
+        delete_category(support).unwrap();
+        delete_category(leads).unwrap();
+        assert!(ours().is_empty());
+    }
+}
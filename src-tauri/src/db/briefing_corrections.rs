@@ -0,0 +1,86 @@
+//! The user's corrections of briefing classifications, fed back to the model as
+//! a hint the next time the chat is classified
+
+use super::with_db;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BriefingCorrection {
+    pub chat_id: i64,
+    /// "urgent", "needs_reply" or "fyi"
+    pub priority: String,
+    /// Corrections made to this chat in total
+    pub times: i64,
+    pub corrected_at: i64,
+}
+
+/// Remember the priority the user says a chat should have
+pub fn record_correction(chat_id: i64, priority: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO briefing_corrections (chat_id, priority)
+            VALUES (?, ?)
+            ON CONFLICT(chat_id) DO UPDATE SET
+                priority = excluded.priority,
+                times = times + 1,
+                corrected_at = strftime('%s', 'now')
+            "#,
+            rusqlite::params![chat_id, priority],
+        )
+        .map_err(|e| format!("Failed to save briefing correction: {}", e))?;
+        Ok(())
+    })
+}
+
+pub fn load_correction(chat_id: i64) -> Result<Option<BriefingCorrection>, String> {
+    with_db(|conn| {
+        let result = conn.query_row(
+            "SELECT chat_id, priority, times, corrected_at FROM briefing_corrections WHERE chat_id = ?",
+            [chat_id],
+            |row| {
+                Ok(BriefingCorrection {
+                    chat_id: row.get(0)?,
+                    priority: row.get(1)?,
+                    times: row.get(2)?,
+                    corrected_at: row.get(3)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(correction) => Ok(Some(correction)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load briefing correction: {}", e)),
+        }
+    })
+}
+
+/// Stop hinting the model about a chat
+pub fn delete_correction(chat_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM briefing_corrections WHERE chat_id = ?", [chat_id])
+            .map_err(|e| format!("Failed to delete briefing correction: {}", e))?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_correction_wins() {
+        crate::db::init_test_db();
+        assert!(load_correction(7561).unwrap().is_none());
+
+        record_correction(7561, "needs_reply").unwrap();
+        record_correction(7561, "fyi").unwrap();
+        let correction = load_correction(7561).unwrap().unwrap();
+        assert_eq!((correction.priority.as_str(), correction.times), ("fyi", 2));
+
+        delete_correction(7561).unwrap();
+        assert!(load_correction(7561).unwrap().is_none());
+    }
+}
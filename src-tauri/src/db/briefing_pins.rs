@@ -0,0 +1,93 @@
+//! Priorities the user has pinned chats to ("always FYI"). Pinned chats are
+//! classified without asking the model.
+
+use super::with_db;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BriefingPin {
+    pub chat_id: i64,
+    /// "urgent", "needs_reply" or "fyi"
+    pub priority: String,
+    pub pinned_at: i64,
+}
+
+pub fn load_pins() -> Result<Vec<BriefingPin>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT chat_id, priority, pinned_at FROM briefing_pins ORDER BY pinned_at DESC")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let pins = stmt
+            .query_map([], |row| {
+                Ok(BriefingPin {
+                    chat_id: row.get(0)?,
+                    priority: row.get(1)?,
+                    pinned_at: row.get(2)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query briefing pins: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(pins)
+    })
+}
+
+/// The priority a chat is pinned to, if any
+pub fn load_pin(chat_id: i64) -> Result<Option<String>, String> {
+    with_db(|conn| {
+        let result = conn.query_row(
+            "SELECT priority FROM briefing_pins WHERE chat_id = ?",
+            [chat_id],
+            |row| row.get(0),
+        );
+
+        match result {
+            Ok(priority) => Ok(Some(priority)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load briefing pin: {}", e)),
+        }
+    })
+}
+
+/// Pin a chat to `priority`, or unpin it with None
+pub fn set_pin(chat_id: i64, priority: Option<&str>) -> Result<(), String> {
+    with_db(|conn| {
+        let result = match priority {
+            Some(priority) => conn.execute(
+                r#"
+                INSERT INTO briefing_pins (chat_id, priority)
+                VALUES (?, ?)
+                ON CONFLICT(chat_id) DO UPDATE SET
+                    priority = excluded.priority,
+                    pinned_at = strftime('%s', 'now')
+                "#,
+                rusqlite::params![chat_id, priority],
+            ),
+            None => conn.execute("DELETE FROM briefing_pins WHERE chat_id = ?", [chat_id]),
+        };
+        result.map_err(|e| format!("Failed to save briefing pin: {}", e))?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_and_unpin() {
+        crate::db::init_test_db();
+        assert!(load_pin(7571).unwrap().is_none());
+
+        set_pin(7571, Some("needs_reply")).unwrap();
+        set_pin(7571, Some("fyi")).unwrap();
+        assert_eq!(load_pin(7571).unwrap().as_deref(), Some("fyi"));
+        assert!(load_pins().unwrap().iter().any(|p| p.chat_id == 7571 && p.priority == "fyi"));
+
+        set_pin(7571, None).unwrap();
+        assert!(load_pin(7571).unwrap().is_none());
+    }
+}
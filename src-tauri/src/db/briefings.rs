@@ -0,0 +1,61 @@
+use super::with_db;
+use crate::ai::types::BriefingV2Response;
+
+/// Save a generated briefing to history, returning its new id
+pub fn save_briefing(briefing: &BriefingV2Response) -> Result<String, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let stored = BriefingV2Response {
+        briefing_id: Some(id.clone()),
+        cached: false,
+        cache_age: None,
+        ..briefing.clone()
+    };
+    let json = serde_json::to_string(&stored)
+        .map_err(|e| format!("Failed to serialize briefing: {}", e))?;
+
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO briefing_history (id, generated_at, data) VALUES (?1, ?2, ?3)",
+            rusqlite::params![id, stored.generated_at, json],
+        )
+        .map_err(|e| format!("Failed to save briefing: {}", e))?;
+        Ok(())
+    })?;
+
+    Ok(id)
+}
+
+/// Load a briefing by id
+pub fn load_briefing(id: &str) -> Result<Option<BriefingV2Response>, String> {
+    query_one("SELECT data FROM briefing_history WHERE id = ?1", rusqlite::params![id])
+}
+
+/// Load the most recently generated briefing
+pub fn load_latest_briefing() -> Result<Option<BriefingV2Response>, String> {
+    query_one(
+        "SELECT data FROM briefing_history ORDER BY created_at DESC, rowid DESC LIMIT 1",
+        rusqlite::params![],
+    )
+}
+
+fn query_one(sql: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Option<BriefingV2Response>, String> {
+    with_db(|conn| {
+        let result = conn.query_row(sql, params, |row| row.get::<_, String>(0));
+
+        match result {
+            Ok(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|e| format!("Failed to parse saved briefing: {}", e)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load briefing: {}", e)),
+        }
+    })
+}
+
+/// Resolve a briefing by id, or the latest one when the id is None or "latest"
+pub fn load_briefing_or_latest(id: Option<&str>) -> Result<BriefingV2Response, String> {
+    match id {
+        Some(id) if id != "latest" => load_briefing(id)?.ok_or_else(|| format!("Briefing not found: {}", id)),
+        _ => load_latest_briefing()?.ok_or_else(|| "No briefing has been generated yet".to_string()),
+    }
+}
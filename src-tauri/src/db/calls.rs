@@ -0,0 +1,106 @@
+use super::with_db;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallRecord {
+    pub id: i64,
+    pub chat_id: i64,
+    pub chat_title: String,
+    pub message_id: i64,
+    pub caller_id: i64,
+    pub caller_name: String,
+    pub is_outgoing: bool,
+    pub video: bool,
+    pub missed: bool,
+    /// Seconds; None when the call never connected
+    pub duration: Option<i32>,
+    pub call_date: i64,
+}
+
+pub struct NewCall {
+    pub chat_id: i64,
+    pub chat_title: String,
+    pub message_id: i64,
+    pub caller_id: i64,
+    pub caller_name: String,
+    pub is_outgoing: bool,
+    pub video: bool,
+    pub missed: bool,
+    pub duration: Option<i32>,
+    pub call_date: i64,
+}
+
+/// Store calls, ignoring ones already recorded. Returns the number of new rows.
+pub fn record_calls(calls: &[NewCall]) -> Result<usize, String> {
+    with_db(|conn| {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let mut inserted = 0;
+        for c in calls {
+            inserted += tx
+                .execute(
+                    r#"
+                    INSERT OR IGNORE INTO calls
+                        (chat_id, chat_title, message_id, caller_id, caller_name, is_outgoing, video, missed, duration, call_date)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                    rusqlite::params![
+                        c.chat_id,
+                        c.chat_title,
+                        c.message_id,
+                        c.caller_id,
+                        c.caller_name,
+                        c.is_outgoing,
+                        c.video,
+                        c.missed,
+                        c.duration,
+                        c.call_date
+                    ],
+                )
+                .map_err(|e| format!("Failed to record call: {}", e))?;
+        }
+        tx.commit().map_err(|e| format!("Failed to commit calls: {}", e))?;
+        Ok(inserted)
+    })
+}
+
+/// Calls since `since` (unix seconds), newest first. With `missed_only`, only
+/// incoming calls the user missed.
+pub fn load_calls(since: i64, missed_only: bool) -> Result<Vec<CallRecord>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT id, chat_id, chat_title, message_id, caller_id, caller_name, is_outgoing, video, missed, duration, call_date
+                FROM calls
+                WHERE call_date >= ?1 AND (?2 = 0 OR (missed = 1 AND is_outgoing = 0))
+                ORDER BY call_date DESC, id DESC
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let calls = stmt
+            .query_map(rusqlite::params![since, missed_only], |row| {
+                Ok(CallRecord {
+                    id: row.get(0)?,
+                    chat_id: row.get(1)?,
+                    chat_title: row.get(2)?,
+                    message_id: row.get(3)?,
+                    caller_id: row.get(4)?,
+                    caller_name: row.get(5)?,
+                    is_outgoing: row.get(6)?,
+                    video: row.get(7)?,
+                    missed: row.get(8)?,
+                    duration: row.get(9)?,
+                    call_date: row.get(10)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query calls: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(calls)
+    })
+}
@@ -0,0 +1,84 @@
+use super::with_db;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CannedResponse {
+    pub shortcut: String,
+    pub text: String,
+    /// `{placeholder}` names used in the text
+    pub variables: Vec<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Insert or replace the response for a shortcut
+pub fn save_canned(shortcut: &str, text: &str, variables: &[String]) -> Result<(), String> {
+    let variables_json =
+        serde_json::to_string(variables).map_err(|e| format!("Failed to serialize variables: {}", e))?;
+
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO canned_responses (shortcut, text, variables)
+            VALUES (?, ?, ?)
+            ON CONFLICT(shortcut) DO UPDATE SET
+                text = excluded.text,
+                variables = excluded.variables,
+                updated_at = strftime('%s', 'now')
+            "#,
+            rusqlite::params![shortcut, text, variables_json],
+        )
+        .map_err(|e| format!("Failed to save canned response: {}", e))?;
+        Ok(())
+    })
+}
+
+fn row_to_canned(row: &rusqlite::Row) -> rusqlite::Result<CannedResponse> {
+    let variables_json: String = row.get(2)?;
+    Ok(CannedResponse {
+        shortcut: row.get(0)?,
+        text: row.get(1)?,
+        variables: serde_json::from_str(&variables_json).unwrap_or_default(),
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+pub fn load_canned(shortcut: &str) -> Result<Option<CannedResponse>, String> {
+    with_db(|conn| {
+        match conn.query_row(
+            "SELECT shortcut, text, variables, created_at, updated_at FROM canned_responses WHERE shortcut = ?",
+            [shortcut],
+            row_to_canned,
+        ) {
+            Ok(canned) => Ok(Some(canned)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load canned response: {}", e)),
+        }
+    })
+}
+
+pub fn list_canned() -> Result<Vec<CannedResponse>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT shortcut, text, variables, created_at, updated_at FROM canned_responses ORDER BY shortcut")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let responses = stmt
+            .query_map([], row_to_canned)
+            .map_err(|e| format!("Failed to query canned responses: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(responses)
+    })
+}
+
+pub fn delete_canned(shortcut: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM canned_responses WHERE shortcut = ?", [shortcut])
+            .map_err(|e| format!("Failed to delete canned response: {}", e))?;
+        Ok(())
+    })
+}
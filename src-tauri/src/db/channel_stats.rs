@@ -0,0 +1,121 @@
+//! Snapshots of channel subscriber counts and post views, so growth can be shown
+//! without Telegram's own (Premium-only) analytics
+
+use super::with_db;
+use crate::telegram::client::PostViews;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelSnapshot {
+    pub channel_id: i64,
+    pub taken_at: i64,
+    pub subscriber_count: i64,
+    /// Average views of the posts read with the snapshot
+    pub avg_views: Option<i64>,
+}
+
+/// Record a snapshot and the latest view counts of the posts it read
+pub fn save_snapshot(snapshot: &ChannelSnapshot, posts: &[PostViews]) -> Result<(), String> {
+    with_db(|conn| {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        tx.execute(
+            "INSERT OR REPLACE INTO channel_snapshots (channel_id, taken_at, subscriber_count, avg_views) VALUES (?, ?, ?, ?)",
+            rusqlite::params![snapshot.channel_id, snapshot.taken_at, snapshot.subscriber_count, snapshot.avg_views],
+        )
+        .map_err(|e| format!("Failed to save channel snapshot: {}", e))?;
+
+        for post in posts {
+            tx.execute(
+                r#"
+                INSERT INTO channel_post_views (channel_id, message_id, posted_at, views, updated_at)
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT(channel_id, message_id) DO UPDATE SET
+                    views = excluded.views,
+                    updated_at = excluded.updated_at
+                "#,
+                rusqlite::params![snapshot.channel_id, post.message_id, post.date, post.views, snapshot.taken_at],
+            )
+            .map_err(|e| format!("Failed to save post views: {}", e))?;
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit channel snapshot: {}", e))
+    })
+}
+
+/// When the channel was last snapshotted, if ever
+pub fn latest_snapshot_at(channel_id: i64) -> Result<Option<i64>, String> {
+    with_db(|conn| {
+        conn.query_row(
+            "SELECT MAX(taken_at) FROM channel_snapshots WHERE channel_id = ?",
+            [channel_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to load latest channel snapshot: {}", e))
+    })
+}
+
+/// Snapshots of a channel taken since `since`, oldest first
+pub fn load_snapshots(channel_id: i64, since: i64) -> Result<Vec<ChannelSnapshot>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT channel_id, taken_at, subscriber_count, avg_views
+                FROM channel_snapshots
+                WHERE channel_id = ? AND taken_at >= ?
+                ORDER BY taken_at
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let snapshots = stmt
+            .query_map([channel_id, since], |row| {
+                Ok(ChannelSnapshot {
+                    channel_id: row.get(0)?,
+                    taken_at: row.get(1)?,
+                    subscriber_count: row.get(2)?,
+                    avg_views: row.get(3)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query channel snapshots: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(snapshots)
+    })
+}
+
+/// The most viewed posts published since `since`
+pub fn load_top_posts(channel_id: i64, since: i64, limit: i64) -> Result<Vec<PostViews>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT message_id, posted_at, views
+                FROM channel_post_views
+                WHERE channel_id = ? AND posted_at >= ?
+                ORDER BY views DESC, posted_at DESC
+                LIMIT ?
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let posts = stmt
+            .query_map([channel_id, since, limit], |row| {
+                Ok(PostViews {
+                    message_id: row.get(0)?,
+                    date: row.get(1)?,
+                    views: row.get(2)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query post views: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(posts)
+    })
+}
@@ -0,0 +1,88 @@
+//! Free-form notes on chats (groups and channels included), the chat
+//! counterpart of contact notes
+
+use super::with_db;
+use crate::telegram::client::Chat;
+use std::collections::HashMap;
+
+pub fn get_chat_notes(chat_id: i64) -> Result<String, String> {
+    with_db(|conn| {
+        let notes: Option<String> = conn
+            .query_row("SELECT notes FROM chat_notes WHERE chat_id = ?", [chat_id], |row| row.get(0))
+            .ok();
+        Ok(notes.unwrap_or_default())
+    })
+}
+
+/// Save a chat's notes; blank notes delete them
+pub fn update_chat_notes(chat_id: i64, notes: &str) -> Result<(), String> {
+    with_db(|conn| {
+        if notes.trim().is_empty() {
+            conn.execute("DELETE FROM chat_notes WHERE chat_id = ?", [chat_id])
+        } else {
+            conn.execute(
+                r#"
+                INSERT INTO chat_notes (chat_id, notes, updated_at)
+                VALUES (?, ?, strftime('%s', 'now'))
+                ON CONFLICT(chat_id) DO UPDATE SET
+                    notes = excluded.notes,
+                    updated_at = excluded.updated_at
+                "#,
+                rusqlite::params![chat_id, notes],
+            )
+        }
+        .map_err(|e| format!("Failed to update chat notes: {}", e))?;
+        Ok(())
+    })
+}
+
+pub fn load_all_chat_notes() -> Result<HashMap<i64, String>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT chat_id, notes FROM chat_notes")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let notes = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to query chat notes: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(notes)
+    })
+}
+
+/// Attach each chat's notes
+pub fn apply(chats: &mut [Chat]) {
+    let notes = match load_all_chat_notes() {
+        Ok(notes) => notes,
+        Err(e) => {
+            log::warn!("Failed to load chat notes: {}", e);
+            return;
+        }
+    };
+    for chat in chats.iter_mut() {
+        chat.note = notes.get(&chat.id).cloned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake;
+
+    #[test]
+    fn test_chat_notes_round_trip() {
+        crate::db::init_test_db();
+        update_chat_notes(7351, "ask about sponsorship in March").unwrap();
+        assert_eq!(get_chat_notes(7351).unwrap(), "ask about sponsorship in March");
+
+        let mut chats = vec![fake::chat(7351, "channel", "Meetup", 0, 1), fake::chat(7352, "group", "Other", 0, 2)];
+        apply(&mut chats);
+        assert_eq!(chats[0].note.as_deref(), Some("ask about sponsorship in March"));
+        assert_eq!(chats[1].note, None);
+
+        update_chat_notes(7351, "  ").unwrap();
+        assert_eq!(get_chat_notes(7351).unwrap(), "");
+    }
+}
@@ -1,4 +1,5 @@
 use super::with_db;
+use crate::telegram::client::{Birthday, UserProfile};
 use serde::{Deserialize, Serialize};
 
 /// Contact data structure for potential bulk operations.
@@ -80,6 +81,58 @@ pub fn update_contact_notes(user_id: i64, notes: &str) -> Result<(), String> {
     })
 }
 
+/// Every (user_id, tag) pair
+pub fn load_all_contact_tags() -> Result<Vec<(i64, String)>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT user_id, tag FROM contact_tags ORDER BY user_id, tag")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let tags = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("Failed to query tags: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(tags)
+    })
+}
+
+/// Every non-empty note as (user_id, notes, updated_at)
+pub fn load_all_contact_notes() -> Result<Vec<(i64, String, i64)>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT user_id, notes, updated_at FROM contact_notes WHERE notes != '' ORDER BY user_id")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let notes = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| format!("Failed to query notes: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(notes)
+    })
+}
+
+/// Write notes keeping the edit time they came with (used when merging synced notes)
+pub fn set_contact_notes_at(user_id: i64, notes: &str, updated_at: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO contact_notes (user_id, notes, updated_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(user_id) DO UPDATE SET
+                notes = excluded.notes,
+                updated_at = excluded.updated_at
+            "#,
+            rusqlite::params![user_id, notes, updated_at],
+        )
+        .map_err(|e| format!("Failed to update notes: {}", e))?;
+        Ok(())
+    })
+}
+
 pub fn get_all_tags() -> Result<Vec<(String, i32)>, String> {
     with_db(|conn| {
         let mut stmt = conn
@@ -128,3 +181,209 @@ pub fn update_last_contact_date(user_id: i64, date: i64) -> Result<(), String> {
         Ok(())
     })
 }
+
+pub fn get_contact_birthday(user_id: i64) -> Result<Option<Birthday>, String> {
+    with_db(|conn| {
+        let birthday = conn
+            .query_row(
+                "SELECT day, month, year FROM contact_birthdays WHERE user_id = ? AND day IS NOT NULL",
+                [user_id],
+                |row| {
+                    Ok(Birthday {
+                        day: row.get(0)?,
+                        month: row.get(1)?,
+                        year: row.get(2)?,
+                    })
+                },
+            )
+            .ok();
+        Ok(birthday)
+    })
+}
+
+/// All known birthdays as (user_id, birthday)
+pub fn get_all_birthdays() -> Result<Vec<(i64, Birthday)>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT user_id, day, month, year FROM contact_birthdays WHERE day IS NOT NULL")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let birthdays = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    Birthday {
+                        day: row.get(1)?,
+                        month: row.get(2)?,
+                        year: row.get(3)?,
+                    },
+                ))
+            })
+            .map_err(|e| format!("Failed to query birthdays: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(birthdays)
+    })
+}
+
+/// Record a user's birthday (None = the profile has no birthday set)
+pub fn set_contact_birthday(user_id: i64, birthday: Option<Birthday>) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO contact_birthdays (user_id, day, month, year, updated_at)
+            VALUES (?, ?, ?, ?, strftime('%s', 'now'))
+            ON CONFLICT(user_id) DO UPDATE SET
+                day = excluded.day,
+                month = excluded.month,
+                year = excluded.year,
+                updated_at = excluded.updated_at
+            "#,
+            rusqlite::params![
+                user_id,
+                birthday.map(|b| b.day),
+                birthday.map(|b| b.month),
+                birthday.and_then(|b| b.year)
+            ],
+        )
+        .map_err(|e| format!("Failed to save birthday: {}", e))?;
+        Ok(())
+    })
+}
+
+/// User ids whose birthday was checked after `since` (unix seconds)
+pub fn get_birthday_checked_since(since: i64) -> Result<Vec<i64>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT user_id FROM contact_birthdays WHERE updated_at >= ?")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let ids = stmt
+            .query_map([since], |row| row.get(0))
+            .map_err(|e| format!("Failed to query birthdays: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(ids)
+    })
+}
+
+/// Store profile details fetched with users.getFullUser (including the birthday)
+pub fn save_contact_profile(profile: &UserProfile) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO contact_profiles (user_id, bio, username, is_premium, is_verified, updated_at)
+            VALUES (?, ?, ?, ?, ?, strftime('%s', 'now'))
+            ON CONFLICT(user_id) DO UPDATE SET
+                bio = excluded.bio,
+                username = excluded.username,
+                is_premium = excluded.is_premium,
+                is_verified = excluded.is_verified,
+                updated_at = excluded.updated_at
+            "#,
+            rusqlite::params![
+                profile.user_id,
+                profile.bio,
+                profile.username,
+                profile.is_premium as i32,
+                profile.is_verified as i32
+            ],
+        )
+        .map_err(|e| format!("Failed to save contact profile: {}", e))?;
+        Ok(())
+    })?;
+
+    set_contact_birthday(profile.user_id, profile.birthday)
+}
+
+pub fn get_contact_profile(user_id: i64) -> Result<Option<UserProfile>, String> {
+    let birthday = get_contact_birthday(user_id)?;
+    with_db(|conn| {
+        let profile = conn
+            .query_row(
+                "SELECT bio, username, is_premium, is_verified FROM contact_profiles WHERE user_id = ?",
+                [user_id],
+                |row| {
+                    Ok(UserProfile {
+                        user_id,
+                        bio: row.get(0)?,
+                        username: row.get(1)?,
+                        is_premium: row.get::<_, i32>(2)? != 0,
+                        is_verified: row.get::<_, i32>(3)? != 0,
+                        birthday,
+                    })
+                },
+            )
+            .ok();
+        Ok(profile)
+    })
+}
+
+/// User ids whose bio contains any of the keywords (case-insensitive)
+pub fn find_user_ids_by_bio(keywords: &[String]) -> Result<Vec<i64>, String> {
+    let keywords: Vec<String> = keywords
+        .iter()
+        .map(|k| k.trim().to_lowercase())
+        .filter(|k| !k.is_empty())
+        .collect();
+    if keywords.is_empty() {
+        return Ok(vec![]);
+    }
+
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT user_id, bio FROM contact_profiles WHERE bio IS NOT NULL")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let ids = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| format!("Failed to query bios: {}", e))?
+            .filter_map(|r| r.ok())
+            .filter(|(_, bio)| {
+                let bio = bio.to_lowercase();
+                keywords.iter().any(|k| bio.contains(k.as_str()))
+            })
+            .map(|(user_id, _)| user_id)
+            .collect();
+
+        Ok(ids)
+    })
+}
+
+pub const EVENT_TAG_ADDED: &str = "tag_added";
+pub const EVENT_TAG_REMOVED: &str = "tag_removed";
+pub const EVENT_NOTES_UPDATED: &str = "notes_updated";
+pub const EVENT_REMOVED_FROM_GROUP: &str = "removed_from_group";
+pub const EVENT_WELCOMED_TO_GROUP: &str = "welcomed_to_group";
+pub const EVENT_PIPELINE_STAGE: &str = "pipeline_stage";
+
+/// Record an interaction with a contact for their activity timeline
+pub fn log_contact_event(user_id: i64, kind: &str, detail: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO contact_events (user_id, kind, detail) VALUES (?, ?, ?)",
+            rusqlite::params![user_id, kind, detail],
+        )
+        .map_err(|e| format!("Failed to log contact event: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Logged events for a contact as (kind, detail, created_at), oldest first
+pub fn get_contact_events(user_id: i64) -> Result<Vec<(String, String, i64)>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT kind, detail, created_at FROM contact_events WHERE user_id = ? ORDER BY created_at, id")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let events = stmt
+            .query_map([user_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| format!("Failed to query contact events: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(events)
+    })
+}
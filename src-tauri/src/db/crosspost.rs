@@ -0,0 +1,186 @@
+//! Cross-post jobs: one text, or one forwarded message, going out to several
+//! channels and groups, each at its own time
+
+use super::with_db;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+pub const STATUS_RUNNING: &str = "running";
+pub const STATUS_COMPLETED: &str = "completed";
+pub const STATUS_CANCELLED: &str = "cancelled";
+
+pub const TARGET_PENDING: &str = "pending";
+pub const TARGET_SENT: &str = "sent";
+pub const TARGET_FAILED: &str = "failed";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrosspostTarget {
+    pub chat_id: i64,
+    pub chat_title: String,
+    /// Unix seconds; None sends right away
+    pub send_at: Option<i64>,
+    pub status: String,
+    pub error: Option<String>,
+    pub sent_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrosspostJob {
+    pub id: String,
+    /// Text to send, or None when forwarding the source message
+    pub text: Option<String>,
+    pub source_chat_id: Option<i64>,
+    pub source_message_id: Option<i64>,
+    pub status: String,
+    pub targets: Vec<CrosspostTarget>,
+    pub sent_count: i32,
+    pub failed_count: i32,
+    pub created_at: i64,
+    pub completed_at: Option<i64>,
+}
+
+/// Save a new job with its targets (all pending)
+pub fn save_job(job: &CrosspostJob) -> Result<(), String> {
+    with_db(|conn| {
+        // The job and its targets are written together or not at all
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        tx.execute(
+            "INSERT INTO crosspost_jobs (id, text, source_chat_id, source_message_id, status) VALUES (?, ?, ?, ?, ?)",
+            params![job.id, job.text, job.source_chat_id, job.source_message_id, job.status],
+        )
+        .map_err(|e| format!("Failed to save crosspost: {}", e))?;
+
+        for target in &job.targets {
+            tx.execute(
+                "INSERT INTO crosspost_targets (job_id, chat_id, chat_title, send_at, status) VALUES (?, ?, ?, ?, ?)",
+                params![job.id, target.chat_id, target.chat_title, target.send_at, target.status],
+            )
+            .map_err(|e| format!("Failed to save crosspost target: {}", e))?;
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit crosspost: {}", e))
+    })
+}
+
+pub fn update_target_status(job_id: &str, chat_id: i64, status: &str, error: Option<&str>) -> Result<(), String> {
+    let sent_at = (status == TARGET_SENT).then(|| chrono::Utc::now().timestamp());
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE crosspost_targets SET status = ?, error = ?, sent_at = ? WHERE job_id = ? AND chat_id = ?",
+            params![status, error, sent_at, job_id, chat_id],
+        )
+        .map_err(|e| format!("Failed to update crosspost target: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Finish a job as completed or cancelled
+pub fn update_job_status(job_id: &str, status: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE crosspost_jobs SET status = ?, completed_at = strftime('%s', 'now') WHERE id = ?",
+            params![status, job_id],
+        )
+        .map_err(|e| format!("Failed to update crosspost: {}", e))?;
+        Ok(())
+    })
+}
+
+fn load_targets(conn: &Connection, job_id: &str) -> Result<Vec<CrosspostTarget>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT chat_id, chat_title, send_at, status, error, sent_at
+            FROM crosspost_targets
+            WHERE job_id = ?
+            ORDER BY COALESCE(send_at, 0), rowid
+            "#,
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let targets = stmt
+        .query_map([job_id], |row| {
+            Ok(CrosspostTarget {
+                chat_id: row.get(0)?,
+                chat_title: row.get(1)?,
+                send_at: row.get(2)?,
+                status: row.get(3)?,
+                error: row.get(4)?,
+                sent_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query crosspost targets: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(targets)
+}
+
+/// Jobs matching `filter` (a WHERE clause on crosspost_jobs), newest first
+/// Jobs matching `filter`, newest first; a negative `limit` means all of them
+fn load_jobs(
+    conn: &Connection,
+    filter: &str,
+    args: &[&dyn rusqlite::ToSql],
+    limit: i64,
+) -> Result<Vec<CrosspostJob>, String> {
+    let sql = format!(
+        r#"
+        SELECT id, text, source_chat_id, source_message_id, status, created_at, completed_at
+        FROM crosspost_jobs
+        WHERE {}
+        ORDER BY created_at DESC, rowid DESC
+        LIMIT {}
+        "#,
+        filter, limit
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let jobs: Vec<CrosspostJob> = stmt
+        .query_map(args, |row| {
+            Ok(CrosspostJob {
+                id: row.get(0)?,
+                text: row.get(1)?,
+                source_chat_id: row.get(2)?,
+                source_message_id: row.get(3)?,
+                status: row.get(4)?,
+                targets: vec![],
+                sent_count: 0,
+                failed_count: 0,
+                created_at: row.get(5)?,
+                completed_at: row.get(6)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query crossposts: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    jobs.into_iter()
+        .map(|mut job| {
+            job.targets = load_targets(conn, &job.id)?;
+            job.sent_count = job.targets.iter().filter(|t| t.status == TARGET_SENT).count() as i32;
+            job.failed_count = job.targets.iter().filter(|t| t.status == TARGET_FAILED).count() as i32;
+            Ok(job)
+        })
+        .collect()
+}
+
+pub fn load_job(job_id: &str) -> Result<Option<CrosspostJob>, String> {
+    with_db(|conn| Ok(load_jobs(conn, "id = ?", &[&job_id], 1)?.into_iter().next()))
+}
+
+/// Jobs still sending, e.g. to resume after a restart
+pub fn load_running_jobs() -> Result<Vec<CrosspostJob>, String> {
+    with_db(|conn| load_jobs(conn, "status = ?", &[&STATUS_RUNNING], -1))
+}
+
+pub fn load_recent_jobs(limit: i64) -> Result<Vec<CrosspostJob>, String> {
+    with_db(|conn| load_jobs(conn, "1 = 1", &[], limit.max(0)))
+}
@@ -0,0 +1,149 @@
+use super::with_db;
+use serde::{Deserialize, Serialize};
+
+pub const KIND_DECISION: &str = "decision";
+pub const KIND_COMMITMENT: &str = "commitment";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecisionRecord {
+    pub id: i64,
+    pub chat_id: i64,
+    pub chat_title: String,
+    pub kind: String,
+    pub text: String,
+    pub owner: Option<String>,
+    pub due: Option<String>,
+    /// Source message (None if the model cited a message we didn't send it)
+    pub message_id: Option<i64>,
+    pub sender_name: Option<String>,
+    pub message_date: Option<i64>,
+    pub extracted_at: i64,
+}
+
+/// Decision or commitment to store; provenance is filled in by the caller
+pub struct NewDecision {
+    pub kind: String,
+    pub text: String,
+    pub owner: Option<String>,
+    pub due: Option<String>,
+    pub message_id: Option<i64>,
+    pub sender_name: Option<String>,
+    pub message_date: Option<i64>,
+}
+
+/// Store extracted items; ones already recorded for the chat are kept as-is.
+/// Returns the number of new rows.
+pub fn save_decisions(chat_id: i64, chat_title: &str, decisions: &[NewDecision]) -> Result<usize, String> {
+    with_db(|conn| {
+        let mut inserted = 0;
+        for d in decisions {
+            inserted += conn
+                .execute(
+                    r#"
+                    INSERT OR IGNORE INTO chat_decisions
+                        (chat_id, chat_title, kind, text, owner, due, message_id, sender_name, message_date)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                    rusqlite::params![
+                        chat_id,
+                        chat_title,
+                        d.kind,
+                        d.text,
+                        d.owner,
+                        d.due,
+                        d.message_id,
+                        d.sender_name,
+                        d.message_date
+                    ],
+                )
+                .map_err(|e| format!("Failed to save decision: {}", e))?;
+        }
+        Ok(inserted)
+    })
+}
+
+/// Stored decisions, newest message first; all chats when `chat_id` is None
+pub fn load_decisions(chat_id: Option<i64>) -> Result<Vec<DecisionRecord>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT id, chat_id, chat_title, kind, text, owner, due, message_id, sender_name, message_date, extracted_at
+                FROM chat_decisions
+                WHERE ?1 IS NULL OR chat_id = ?1
+                ORDER BY COALESCE(message_date, extracted_at) DESC, id DESC
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let decisions = stmt
+            .query_map([chat_id], |row| {
+                Ok(DecisionRecord {
+                    id: row.get(0)?,
+                    chat_id: row.get(1)?,
+                    chat_title: row.get(2)?,
+                    kind: row.get(3)?,
+                    text: row.get(4)?,
+                    owner: row.get(5)?,
+                    due: row.get(6)?,
+                    message_id: row.get(7)?,
+                    sender_name: row.get(8)?,
+                    message_date: row.get(9)?,
+                    extracted_at: row.get(10)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query decisions: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(decisions)
+    })
+}
+
+pub fn delete_decision(id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM chat_decisions WHERE id = ?", [id])
+            .map_err(|e| format!("Failed to delete decision: {}", e))?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(kind: &str, text: &str, message_date: Option<i64>) -> NewDecision {
+        NewDecision {
+            kind: kind.to_string(),
+            text: text.to_string(),
+            owner: None,
+            due: None,
+            message_id: None,
+            sender_name: None,
+            message_date,
+        }
+    }
+
+    #[test]
+    fn test_save_load_and_delete_decisions() {
+        crate::db::init_test_db();
+        let saved = save_decisions(7641, "Board", &[
+            decision(KIND_DECISION, "Hire a designer", Some(100)),
+            decision(KIND_COMMITMENT, "Draft the offer", Some(200)),
+        ])
+        .unwrap();
+        assert_eq!(saved, 2);
+        // Extracting the same items again adds nothing
+        assert_eq!(save_decisions(7641, "Board", &[decision(KIND_DECISION, "Hire a designer", Some(100))]).unwrap(), 0);
+        save_decisions(7642, "Other", &[decision(KIND_DECISION, "Hire a designer", None)]).unwrap();
+
+        let stored = load_decisions(Some(7641)).unwrap();
+        let texts: Vec<&str> = stored.iter().map(|d| d.text.as_str()).collect();
+        assert_eq!(texts, vec!["Draft the offer", "Hire a designer"]);
+        assert!(load_decisions(None).unwrap().iter().any(|d| d.chat_id == 7642));
+
+        delete_decision(stored[0].id).unwrap();
+        assert_eq!(load_decisions(Some(7641)).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,175 @@
+//! Startup checks for the files in the app data directory. A corrupted database
+//! is first reindexed and, if that doesn't help, moved aside so a fresh one is
+//! created; an unreadable session file is moved aside so the user can log in
+//! again. Nothing is deleted: quarantined files keep a `.corrupt-<timestamp>` suffix.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const DB_FILE: &str = "telegram_copilot.db";
+pub const SESSION_FILE: &str = "telegram.session";
+
+/// SQLite files that belong to a database and move with it
+const DB_SIDECARS: [&str; 3] = ["-wal", "-shm", "-journal"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    /// The database passed the integrity check, possibly after a reindex
+    pub database_ok: bool,
+    /// The database had errors that a reindex fixed
+    pub database_repaired: bool,
+    /// The database was beyond repair and replaced with an empty one; its data
+    /// is only in the quarantined file now
+    #[serde(default)]
+    pub database_replaced: bool,
+    /// The session file was readable, or there was none yet
+    pub session_ok: bool,
+    /// New paths of corrupted files that were moved aside
+    pub quarantined: Vec<String>,
+    pub problems: Vec<String>,
+}
+
+/// Errors reported by `PRAGMA integrity_check` (empty when the database is fine)
+fn integrity_errors(path: &Path) -> Vec<String> {
+    let conn = match Connection::open(path) {
+        Ok(conn) => conn,
+        Err(e) => return vec![format!("Failed to open database: {}", e)],
+    };
+    let rows = conn.prepare("PRAGMA integrity_check").and_then(|mut stmt| {
+        stmt.query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()
+    });
+    match rows {
+        Ok(rows) => rows.into_iter().filter(|r| r != "ok").collect(),
+        Err(e) => vec![format!("Integrity check failed: {}", e)],
+    }
+}
+
+fn reindex(path: &Path) -> Result<(), String> {
+    let conn = Connection::open(path).map_err(|e| format!("Failed to open database: {}", e))?;
+    conn.execute_batch("REINDEX")
+        .map_err(|e| format!("Failed to reindex database: {}", e))
+}
+
+/// Rename `path` to `<path>.corrupt-<timestamp>`, returning the new path
+fn quarantine(path: &Path) -> Result<PathBuf, String> {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".corrupt-{}", chrono::Utc::now().timestamp()));
+    let target = PathBuf::from(name);
+    std::fs::rename(path, &target).map_err(|e| format!("Failed to move {:?} aside: {}", path, e))?;
+    log::warn!("Moved corrupted file {:?} to {:?}", path, target);
+    Ok(target)
+}
+
+/// Check the database at `path`, reindexing or quarantining it when corrupted
+pub fn repair_database_file(path: &Path, report: &mut IntegrityReport) {
+    if !path.exists() {
+        report.database_ok = true;
+        return;
+    }
+
+    let errors = integrity_errors(path);
+    if errors.is_empty() {
+        report.database_ok = true;
+        return;
+    }
+    log::warn!("Database integrity check found {} problem(s): {:?}", errors.len(), errors);
+    report.problems.extend(errors);
+
+    if reindex(path).is_ok() && integrity_errors(path).is_empty() {
+        report.database_ok = true;
+        report.database_repaired = true;
+        return;
+    }
+
+    match quarantine(path) {
+        Ok(target) => {
+            report.quarantined.push(target.to_string_lossy().to_string());
+            for suffix in DB_SIDECARS {
+                let mut sidecar = path.as_os_str().to_owned();
+                sidecar.push(suffix);
+                let sidecar = PathBuf::from(sidecar);
+                if sidecar.exists() {
+                    if let Ok(target) = quarantine(&sidecar) {
+                        report.quarantined.push(target.to_string_lossy().to_string());
+                    }
+                }
+            }
+            // A fresh database is created in its place
+            report.database_replaced = true;
+        }
+        Err(e) => report.problems.push(e),
+    }
+}
+
+/// Check the Telegram session file can be read, quarantining it when it can't
+fn check_session_file(path: &Path, report: &mut IntegrityReport) {
+    if !path.exists() {
+        report.session_ok = true;
+        return;
+    }
+    let error = match grammers_session::Session::load_file(path) {
+        Ok(_) => {
+            report.session_ok = true;
+            return;
+        }
+        Err(e) => format!("Session file is unreadable: {}", e),
+    };
+    log::warn!("{}", error);
+    report.problems.push(error);
+
+    match quarantine(path) {
+        // The user logs in again with a new session
+        Ok(target) => {
+            report.quarantined.push(target.to_string_lossy().to_string());
+        }
+        Err(e) => report.problems.push(e),
+    }
+}
+
+impl IntegrityReport {
+    /// Files were moved aside, so the user has lost data or has to log in again
+    pub fn lost_data(&self) -> bool {
+        !self.quarantined.is_empty()
+    }
+}
+
+/// Check the database and session file in `app_dir` before they are opened
+pub fn check_on_startup(app_dir: &Path) -> IntegrityReport {
+    let mut report = IntegrityReport::default();
+    repair_database_file(&app_dir.join(DB_FILE), &mut report);
+    check_session_file(&app_dir.join(SESSION_FILE), &mut report);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corrupted_files_are_quarantined() {
+        let dir = std::env::temp_dir().join(format!("integrity-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Healthy database, no session yet
+        let conn = Connection::open(dir.join(DB_FILE)).unwrap();
+        conn.execute_batch("CREATE TABLE t (x INTEGER); INSERT INTO t VALUES (1);").unwrap();
+        drop(conn);
+        let report = check_on_startup(&dir);
+        assert!(report.database_ok && report.session_ok);
+        assert!(report.quarantined.is_empty() && report.problems.is_empty());
+
+        // Garbage in both files
+        std::fs::write(dir.join(DB_FILE), vec![0xAB; 8192]).unwrap();
+        std::fs::write(dir.join(SESSION_FILE), b"not a session").unwrap();
+        let report = check_on_startup(&dir);
+        assert!(!report.database_ok && report.database_replaced && !report.session_ok);
+        assert_eq!(report.quarantined.len(), 2);
+        assert!(!dir.join(DB_FILE).exists() && !dir.join(SESSION_FILE).exists());
+        assert!(report.quarantined.iter().all(|p| Path::new(p).exists()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use super::with_db;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactList {
+    pub id: String,
+    pub name: String,
+    /// Members in list order
+    pub user_ids: Vec<i64>,
+    pub created_at: i64,
+}
+
+fn load_members(conn: &rusqlite::Connection, list_id: &str) -> Result<Vec<i64>, String> {
+    let mut stmt = conn
+        .prepare("SELECT user_id FROM contact_list_members WHERE list_id = ? ORDER BY position, added_at")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let members = stmt
+        .query_map([list_id], |row| row.get(0))
+        .map_err(|e| format!("Failed to query list members: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(members)
+}
+
+pub fn create_list(name: &str) -> Result<ContactList, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    with_db(|conn| {
+        conn.execute("INSERT INTO contact_lists (id, name) VALUES (?, ?)", rusqlite::params![id, name])
+            .map_err(|e| format!("Failed to create list: {}", e))?;
+        Ok(())
+    })?;
+
+    load_list(&id)?.ok_or_else(|| "List not found after create".to_string())
+}
+
+pub fn load_list(id: &str) -> Result<Option<ContactList>, String> {
+    with_db(|conn| {
+        let result = conn.query_row(
+            "SELECT id, name, created_at FROM contact_lists WHERE id = ?",
+            [id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?)),
+        );
+
+        match result {
+            Ok((id, name, created_at)) => Ok(Some(ContactList {
+                user_ids: load_members(conn, &id)?,
+                id,
+                name,
+                created_at,
+            })),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load list: {}", e)),
+        }
+    })
+}
+
+pub fn list_lists() -> Result<Vec<ContactList>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT id, name, created_at FROM contact_lists ORDER BY name")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows: Vec<(String, String, i64)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| format!("Failed to query lists: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        rows.into_iter()
+            .map(|(id, name, created_at)| {
+                Ok(ContactList {
+                    user_ids: load_members(conn, &id)?,
+                    id,
+                    name,
+                    created_at,
+                })
+            })
+            .collect()
+    })
+}
+
+pub fn rename_list(id: &str, name: &str) -> Result<(), String> {
+    with_db(|conn| {
+        let updated = conn
+            .execute("UPDATE contact_lists SET name = ? WHERE id = ?", rusqlite::params![name, id])
+            .map_err(|e| format!("Failed to rename list: {}", e))?;
+        if updated == 0 {
+            return Err(format!("List {} not found", id));
+        }
+        Ok(())
+    })
+}
+
+pub fn delete_list(id: &str) -> Result<(), String> {
+    with_db(|conn| {
+        // Members are deleted via CASCADE
+        conn.execute("DELETE FROM contact_lists WHERE id = ?", [id])
+            .map_err(|e| format!("Failed to delete list: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Append users to the end of a list; existing members keep their position
+pub fn add_members(list_id: &str, user_ids: &[i64]) -> Result<(), String> {
+    with_db(|conn| {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        let mut next: i64 = tx
+            .query_row(
+                "SELECT COALESCE(MAX(position) + 1, 0) FROM contact_list_members WHERE list_id = ?",
+                [list_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to read list positions: {}", e))?;
+
+        for user_id in user_ids {
+            let inserted = tx
+                .execute(
+                    "INSERT OR IGNORE INTO contact_list_members (list_id, user_id, position) VALUES (?, ?, ?)",
+                    rusqlite::params![list_id, user_id, next],
+                )
+                .map_err(|e| format!("Failed to add list member: {}", e))?;
+            next += inserted as i64;
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit list members: {}", e))?;
+        Ok(())
+    })
+}
+
+pub fn remove_members(list_id: &str, user_ids: &[i64]) -> Result<(), String> {
+    with_db(|conn| {
+        for user_id in user_ids {
+            conn.execute(
+                "DELETE FROM contact_list_members WHERE list_id = ? AND user_id = ?",
+                rusqlite::params![list_id, user_id],
+            )
+            .map_err(|e| format!("Failed to remove list member: {}", e))?;
+        }
+        Ok(())
+    })
+}
+
+/// Set member order; `user_ids` must list the current members
+pub fn reorder_members(list_id: &str, user_ids: &[i64]) -> Result<(), String> {
+    with_db(|conn| {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        for (position, user_id) in user_ids.iter().enumerate() {
+            tx.execute(
+                "UPDATE contact_list_members SET position = ? WHERE list_id = ? AND user_id = ?",
+                rusqlite::params![position as i64, list_id, user_id],
+            )
+            .map_err(|e| format!("Failed to reorder list: {}", e))?;
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit list order: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Members of several lists in list order, without duplicates
+pub fn members_of(list_ids: &[String]) -> Result<Vec<i64>, String> {
+    let mut members: Vec<i64> = vec![];
+    with_db(|conn| {
+        for list_id in list_ids {
+            for user_id in load_members(conn, list_id)? {
+                if !members.contains(&user_id) {
+                    members.push(user_id);
+                }
+            }
+        }
+        Ok(())
+    })?;
+    Ok(members)
+}
@@ -0,0 +1,91 @@
+use super::with_db;
+
+/// Cached response for a prompt hash, bumping its hit count. Entries older than
+/// `max_age_secs` are ignored (they are deleted by the next retention pass).
+pub fn load_response(prompt_hash: &str, max_age_secs: Option<i64>) -> Result<Option<String>, String> {
+    let since = max_age_secs.map_or(i64::MIN, |secs| chrono::Utc::now().timestamp() - secs);
+    with_db(|conn| {
+        let result = conn.query_row(
+            "SELECT response FROM llm_response_cache WHERE prompt_hash = ? AND created_at >= ?",
+            rusqlite::params![prompt_hash, since],
+            |row| row.get::<_, String>(0),
+        );
+
+        match result {
+            Ok(response) => {
+                conn.execute(
+                    "UPDATE llm_response_cache SET hits = hits + 1, last_hit_at = strftime('%s', 'now') WHERE prompt_hash = ?",
+                    [prompt_hash],
+                )
+                .map_err(|e| format!("Failed to update LLM cache hit: {}", e))?;
+                Ok(Some(response))
+            }
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load cached LLM response: {}", e)),
+        }
+    })
+}
+
+pub fn store_response(prompt_hash: &str, model: &str, response: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO llm_response_cache (prompt_hash, model, response)
+            VALUES (?, ?, ?)
+            ON CONFLICT(prompt_hash) DO UPDATE SET
+                response = excluded.response,
+                created_at = strftime('%s', 'now')
+            "#,
+            rusqlite::params![prompt_hash, model, response],
+        )
+        .map_err(|e| format!("Failed to cache LLM response: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Remove every cached response; returns the number removed
+pub fn clear() -> Result<usize, String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM llm_response_cache", [])
+            .map_err(|e| format!("Failed to clear LLM cache: {}", e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hit_and_miss() {
+        crate::db::init_test_db();
+        assert!(load_response("hash-hit", None).unwrap().is_none());
+
+        store_response("hash-hit", "model", "{\"ok\":true}").unwrap();
+        assert_eq!(load_response("hash-hit", Some(3600)).unwrap().as_deref(), Some("{\"ok\":true}"));
+        assert!(load_response("hash-other", Some(3600)).unwrap().is_none());
+
+        let hits: i64 = with_db(|conn| {
+            conn.query_row("SELECT hits FROM llm_response_cache WHERE prompt_hash = 'hash-hit'", [], |row| row.get(0))
+                .map_err(|e| e.to_string())
+        })
+        .unwrap();
+        assert_eq!(hits, 1);
+    }
+
+    #[test]
+    fn test_expired_entries_are_not_served() {
+        crate::db::init_test_db();
+        store_response("hash-old", "model", "stale").unwrap();
+        with_db(|conn| {
+            conn.execute(
+                "UPDATE llm_response_cache SET created_at = strftime('%s', 'now') - 8 * 86400 WHERE prompt_hash = 'hash-old'",
+                [],
+            )
+            .map_err(|e| e.to_string())
+        })
+        .unwrap();
+
+        assert!(load_response("hash-old", Some(7 * 86400)).unwrap().is_none());
+        assert_eq!(load_response("hash-old", None).unwrap().as_deref(), Some("stale"));
+    }
+}
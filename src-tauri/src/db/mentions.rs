@@ -0,0 +1,121 @@
+use super::with_db;
+use serde::{Deserialize, Serialize};
+
+pub const KIND_MENTION: &str = "mention";
+pub const KIND_REPLY: &str = "reply";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MentionRecord {
+    pub id: i64,
+    pub chat_id: i64,
+    pub chat_title: String,
+    pub message_id: i64,
+    pub kind: String,
+    pub sender_id: i64,
+    pub sender_name: String,
+    pub text: String,
+    pub message_date: i64,
+    pub seen: bool,
+}
+
+/// Mention to store; `seen` starts false
+pub struct NewMention {
+    pub chat_id: i64,
+    pub chat_title: String,
+    pub message_id: i64,
+    pub kind: String,
+    pub sender_id: i64,
+    pub sender_name: String,
+    pub text: String,
+    pub message_date: i64,
+}
+
+/// Store mentions; ones already recorded keep their seen state.
+/// Returns the number of new rows.
+pub fn record_mentions(mentions: &[NewMention]) -> Result<usize, String> {
+    with_db(|conn| {
+        let mut inserted = 0;
+        for m in mentions {
+            inserted += conn
+                .execute(
+                    r#"
+                    INSERT OR IGNORE INTO mentions
+                        (chat_id, chat_title, message_id, kind, sender_id, sender_name, text, message_date)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                    rusqlite::params![
+                        m.chat_id,
+                        m.chat_title,
+                        m.message_id,
+                        m.kind,
+                        m.sender_id,
+                        m.sender_name,
+                        m.text,
+                        m.message_date
+                    ],
+                )
+                .map_err(|e| format!("Failed to record mention: {}", e))?;
+        }
+        Ok(inserted)
+    })
+}
+
+/// Stored mentions, newest first
+pub fn load_mentions(unseen_only: bool, limit: i64) -> Result<Vec<MentionRecord>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT id, chat_id, chat_title, message_id, kind, sender_id, sender_name, text, message_date, seen
+                FROM mentions
+                WHERE ?1 = 0 OR seen = 0
+                ORDER BY message_date DESC, id DESC
+                LIMIT ?2
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let mentions = stmt
+            .query_map(rusqlite::params![unseen_only, limit], |row| {
+                Ok(MentionRecord {
+                    id: row.get(0)?,
+                    chat_id: row.get(1)?,
+                    chat_title: row.get(2)?,
+                    message_id: row.get(3)?,
+                    kind: row.get(4)?,
+                    sender_id: row.get(5)?,
+                    sender_name: row.get(6)?,
+                    text: row.get(7)?,
+                    message_date: row.get(8)?,
+                    seen: row.get(9)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query mentions: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(mentions)
+    })
+}
+
+/// Mark the given mentions as seen, or every mention when `ids` is None.
+/// Returns the number of rows changed.
+pub fn mark_mentions_seen(ids: Option<&[i64]>) -> Result<usize, String> {
+    with_db(|conn| {
+        match ids {
+            Some(ids) => {
+                let mut changed = 0;
+                for id in ids {
+                    changed += conn
+                        .execute("UPDATE mentions SET seen = 1 WHERE id = ? AND seen = 0", [id])
+                        .map_err(|e| format!("Failed to mark mention as seen: {}", e))?;
+                }
+                Ok(changed)
+            }
+            None => conn
+                .execute("UPDATE mentions SET seen = 1 WHERE seen = 0", [])
+                .map_err(|e| format!("Failed to mark mentions as seen: {}", e)),
+        }
+    })
+}
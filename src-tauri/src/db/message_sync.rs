@@ -0,0 +1,364 @@
+//! Keeps the message archive (`db::messages`) in step with Telegram. History
+//! fetched through here is archived as it arrives; recent messages are served
+//! from the archive while it's current, and from the archive alone when offline,
+//! so briefings, summaries and drafts keep working without a connection.
+
+use super::messages as db_messages;
+use crate::telegram::client::{BatchMessageRequest, BatchMessageResult, Message};
+use crate::telegram::{TelegramApi, TelegramClient};
+use serde::Serialize;
+
+/// How long archived recent messages are trusted before being fetched again
+/// (edits and deletions only show up on a fetch)
+const MAX_ARCHIVE_AGE_SECS: i64 = 10 * 60;
+
+fn is_offline(error: &str) -> bool {
+    TelegramClient::is_connection_error(error) || error.contains("not connected")
+}
+
+fn archive(chat_id: i64, messages: &[Message], from_message_id: Option<i64>) {
+    let now = chrono::Utc::now().timestamp();
+    if let Err(e) = db_messages::store_messages(chat_id, messages, from_message_id, now) {
+        log::warn!("Failed to archive messages of chat {}: {}", chat_id, e);
+    }
+}
+
+/// Fetch a page of history from Telegram and archive it, falling back to the
+/// archive when offline
+pub async fn fetch_messages(
+    client: &dyn TelegramApi,
+    chat_id: i64,
+    limit: i32,
+    from_message_id: Option<i64>,
+) -> Result<Vec<Message>, String> {
+    match client.get_chat_messages(chat_id, limit, from_message_id).await {
+        Ok(messages) => {
+            archive(chat_id, &messages, from_message_id);
+            Ok(messages)
+        }
+        Err(e) if is_offline(&e) => {
+            let archived = db_messages::load_messages(chat_id, limit, from_message_id)?;
+            if archived.is_empty() {
+                return Err(e);
+            }
+            log::warn!("Offline, using {} archived messages of chat {}: {}", archived.len(), chat_id, e);
+            Ok(archived)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Pause between chats fetched from Telegram in a batch, to stay within rate limits
+const BATCH_FETCH_DELAY_MS: u64 = 50;
+
+/// The latest `limit` messages of a chat. Served from the archive when it already
+/// ends at the chat's last message and was fetched recently; otherwise fetched.
+pub async fn recent_messages(client: &dyn TelegramApi, chat_id: i64, limit: i32) -> Result<Vec<Message>, String> {
+    if let Err(e) = client.load_chat_cache().await {
+        log::debug!("Chat cache unavailable, fetching chat {}: {}", chat_id, e);
+    }
+    recent_messages_cached(client, chat_id, limit).await.map(|(messages, _)| messages)
+}
+
+/// `recent_messages` with the chat looked up in the already loaded dialog cache;
+/// also says whether Telegram had to be asked
+async fn recent_messages_cached(
+    client: &dyn TelegramApi,
+    chat_id: i64,
+    limit: i32,
+) -> Result<(Vec<Message>, bool), String> {
+    let latest_id = client.cached_chat(chat_id).await.and_then(|chat| chat.last_message).map(|m| m.id);
+    if let Some(latest_id) = latest_id {
+        let synced_since = chrono::Utc::now().timestamp() - MAX_ARCHIVE_AGE_SECS;
+        if let Some(messages) = db_messages::load_current(chat_id, latest_id, limit, synced_since)? {
+            log::debug!("Serving {} messages of chat {} from the archive", messages.len(), chat_id);
+            return Ok((messages, false));
+        }
+    }
+    fetch_messages(client, chat_id, limit, None).await.map(|messages| (messages, true))
+}
+
+/// `recent_messages` for several chats. Like `TelegramClient::get_batch_messages`,
+/// loads the dialog cache once, pauses between fetches and stops at a FLOOD_WAIT,
+/// returning the partial results.
+pub async fn batch_messages(
+    client: &dyn TelegramApi,
+    requests: Vec<BatchMessageRequest>,
+) -> Vec<BatchMessageResult> {
+    // Offline, the archive can still serve chats, so a failed load isn't fatal
+    if let Err(e) = client.load_chat_cache().await {
+        log::warn!("Failed to load chat cache for batch: {}", e);
+    }
+
+    let mut results = Vec::new();
+    for req in &requests {
+        let fetched = match recent_messages_cached(client, req.chat_id, req.limit).await {
+            Ok((messages, fetched)) => {
+                results.push(BatchMessageResult { chat_id: req.chat_id, messages, error: None });
+                fetched
+            }
+            Err(e) => {
+                let flood = e.to_lowercase().contains("flood");
+                if flood {
+                    log::warn!(
+                        "FLOOD_WAIT detected at chat {}, returning partial results ({}/{})",
+                        req.chat_id,
+                        results.len(),
+                        requests.len()
+                    );
+                }
+                results.push(BatchMessageResult { chat_id: req.chat_id, messages: vec![], error: Some(e) });
+                if flood {
+                    break;
+                }
+                true
+            }
+        };
+        if fetched {
+            tokio::time::sleep(std::time::Duration::from_millis(BATCH_FETCH_DELAY_MS)).await;
+        }
+    }
+    results
+}
+
+/// Messages fetched per page when syncing a chat's archive
+const SYNC_PAGE_SIZE: i32 = 100;
+
+/// Most pages of newer messages one sync fetches; a chat further behind catches
+/// up over the next syncs
+const MAX_NEWER_PAGES: u32 = 10;
+
+/// Sent after each page a sync archives
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    pub chat_id: i64,
+    /// "newer" while catching up, "older" while backfilling
+    pub phase: &'static str,
+    pub page: u32,
+    pub archived: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub chat_id: i64,
+    pub new_messages: usize,
+    pub backfilled_messages: usize,
+    /// Every message since the previous sync is archived
+    pub caught_up: bool,
+    /// The archive goes back to the chat's first message
+    pub reached_start: bool,
+    pub oldest_message_id: Option<i64>,
+    pub newest_message_id: Option<i64>,
+}
+
+fn store(chat_id: i64, messages: &[Message], from_message_id: Option<i64>) -> Result<(), String> {
+    db_messages::store_messages(chat_id, messages, from_message_id, chrono::Utc::now().timestamp())
+}
+
+/// Archive a chat's messages newer than the newest archived one, then up to
+/// `backfill_pages` pages older than the oldest. A chat never archived gets its
+/// latest page only.
+pub async fn sync_chat(
+    client: &dyn TelegramApi,
+    chat_id: i64,
+    backfill_pages: u32,
+    on_progress: impl Fn(SyncProgress) + Send + Sync,
+) -> Result<SyncReport, String> {
+    let previous = db_messages::load_sync_state(chat_id)?;
+    let mut report = SyncReport { chat_id, ..Default::default() };
+
+    let mut from = None;
+    for page in 1..=MAX_NEWER_PAGES {
+        let messages = client.get_chat_messages(chat_id, SYNC_PAGE_SIZE, from).await?;
+        let Some(page_oldest) = messages.iter().map(|m| m.id).min() else {
+            report.caught_up = true;
+            break;
+        };
+        store(chat_id, &messages, from)?;
+        report.new_messages += messages
+            .iter()
+            .filter(|m| previous.as_ref().map_or(true, |state| m.id > state.newest_message_id))
+            .count();
+        on_progress(SyncProgress { chat_id, phase: "newer", page, archived: report.new_messages });
+
+        if messages.len() < SYNC_PAGE_SIZE as usize {
+            report.reached_start = true;
+        }
+        match &previous {
+            Some(state) if page_oldest <= state.newest_message_id => {
+                db_messages::extend_range_down(chat_id, state.oldest_message_id)?;
+                report.caught_up = true;
+                break;
+            }
+            None => {
+                report.caught_up = true;
+                break;
+            }
+            Some(_) if report.reached_start => {
+                report.caught_up = true;
+                break;
+            }
+            Some(_) => {}
+        }
+        from = Some(page_oldest);
+        tokio::time::sleep(std::time::Duration::from_millis(BATCH_FETCH_DELAY_MS)).await;
+    }
+
+    if backfill_pages > 0 && !report.reached_start {
+        let (backfilled, reached_start) = backfill_chat(client, chat_id, backfill_pages, &on_progress).await?;
+        report.backfilled_messages = backfilled;
+        report.reached_start = reached_start;
+    }
+
+    if let Some(state) = db_messages::load_sync_state(chat_id)? {
+        report.oldest_message_id = Some(state.oldest_message_id);
+        report.newest_message_id = Some(state.newest_message_id);
+    }
+    Ok(report)
+}
+
+/// Archive up to `pages` pages older than a chat's oldest archived message.
+/// Returns how many messages were archived and whether the chat's first message
+/// was reached.
+pub async fn backfill_chat(
+    client: &dyn TelegramApi,
+    chat_id: i64,
+    pages: u32,
+    on_progress: &(impl Fn(SyncProgress) + Send + Sync),
+) -> Result<(usize, bool), String> {
+    let mut archived = 0;
+    for page in 1..=pages {
+        let Some(state) = db_messages::load_sync_state(chat_id)? else {
+            return Ok((archived, false));
+        };
+        if page > 1 {
+            tokio::time::sleep(std::time::Duration::from_millis(BATCH_FETCH_DELAY_MS)).await;
+        }
+        let from = Some(state.oldest_message_id);
+        let messages = client.get_chat_messages(chat_id, SYNC_PAGE_SIZE, from).await?;
+        store(chat_id, &messages, from)?;
+        archived += messages.len();
+        on_progress(SyncProgress { chat_id, phase: "older", page, archived });
+        if messages.len() < SYNC_PAGE_SIZE as usize {
+            return Ok((archived, true));
+        }
+    }
+    Ok((archived, false))
+}
+
+/// Archive one more page of a chat's older history (its latest page if it was
+/// never archived). Returns true once the archive reaches the chat's first message
+/// or a message from before `keep_since`, so there's nothing left worth fetching.
+pub async fn backfill_step(client: &dyn TelegramApi, chat_id: i64, keep_since: Option<i64>) -> Result<bool, String> {
+    let reached_start = if db_messages::load_sync_state(chat_id)?.is_none() {
+        sync_chat(client, chat_id, 0, |_| {}).await?.reached_start
+    } else {
+        backfill_chat(client, chat_id, 1, &|_: SyncProgress| {}).await?.1
+    };
+    if reached_start {
+        return Ok(true);
+    }
+    let Some(state) = db_messages::load_sync_state(chat_id)? else {
+        return Ok(true);
+    };
+    let oldest = db_messages::load_messages(chat_id, 1, Some(state.oldest_message_id + 1))?;
+    Ok(keep_since.is_some_and(|since| oldest.first().is_some_and(|m| m.date < since)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::client::Chat;
+    use crate::telegram::fake::{self, FakeTelegram};
+
+    #[tokio::test]
+    async fn test_recent_messages_use_the_archive() {
+        crate::db::init_test_db();
+        let ann = fake::user(7531, "Ann", "");
+        let history: Vec<Message> = (1..=4)
+            .map(|id| fake::text_message(id, 7531, &ann, &format!("Message {}", id), 100 + id))
+            .collect();
+        let chat = |last: &Message| Chat {
+            last_message: Some(last.clone()),
+            ..fake::chat(7531, "private", "Ann", 1, 0)
+        };
+
+        // First fetch goes to Telegram and is archived
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(chat(&history[2]))
+            .with_messages(7531, history[..3].to_vec());
+        let ids = |messages: Vec<Message>| messages.iter().map(|m| m.id).collect::<Vec<_>>();
+        assert_eq!(ids(recent_messages(&client, 7531, 2).await.unwrap()), vec![2, 3]);
+
+        // Nothing new: served from the archive, even though Telegram has no history now
+        let client = FakeTelegram::new(fake::user(1, "Me", "")).with_chat(chat(&history[2]));
+        assert_eq!(ids(recent_messages(&client, 7531, 2).await.unwrap()), vec![2, 3]);
+        // More than the archived range holds: fetched again
+        assert!(recent_messages(&client, 7531, 3).await.unwrap().is_empty());
+
+        // A new message extends the archived range
+        let client = FakeTelegram::new(fake::user(1, "Me", ""))
+            .with_chat(chat(&history[3]))
+            .with_messages(7531, history.clone());
+        assert_eq!(ids(recent_messages(&client, 7531, 2).await.unwrap()), vec![3, 4]);
+        let state = db_messages::load_sync_state(7531).unwrap().unwrap();
+        assert_eq!((state.oldest_message_id, state.newest_message_id), (2, 4));
+        assert_eq!(ids(db_messages::load_messages(7531, 10, Some(4)).unwrap()), vec![2, 3]);
+
+        // Paging back from the oldest archived message extends the range downwards
+        archive(7531, &history[..1], Some(2));
+        assert_eq!(db_messages::load_sync_state(7531).unwrap().unwrap().oldest_message_id, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sync_chat_fetches_only_newer_messages() {
+        crate::db::init_test_db();
+        let ann = fake::user(7532, "Ann", "");
+        let history: Vec<Message> = (1..=330)
+            .map(|id| fake::text_message(id, 7532, &ann, &format!("Message {}", id), 100 + id))
+            .collect();
+        let client = |upto: usize| {
+            FakeTelegram::new(fake::user(1, "Me", "")).with_messages(7532, history[..upto].to_vec())
+        };
+
+        // Never archived: just the latest page
+        let report = sync_chat(&client(200), 7532, 0, |_| {}).await.unwrap();
+        assert_eq!((report.new_messages, report.caught_up), (100, true));
+        assert_eq!((report.oldest_message_id, report.newest_message_id), (Some(101), Some(200)));
+
+        // 130 new messages span two pages; the range stays contiguous
+        let pages = std::sync::Mutex::new(Vec::new());
+        let report = sync_chat(&client(330), 7532, 0, |p| pages.lock().unwrap().push((p.phase, p.page)))
+            .await
+            .unwrap();
+        assert_eq!((report.new_messages, report.caught_up), (130, true));
+        assert_eq!((report.oldest_message_id, report.newest_message_id), (Some(101), Some(330)));
+        assert_eq!(*pages.lock().unwrap(), vec![("newer", 1), ("newer", 2)]);
+
+        // Nothing new; backfilling pages back to the first message
+        let report = sync_chat(&client(330), 7532, 5, |_| {}).await.unwrap();
+        assert_eq!((report.new_messages, report.backfilled_messages), (0, 100));
+        assert!(report.reached_start);
+        assert_eq!(report.oldest_message_id, Some(1));
+        assert_eq!(db_messages::load_messages(7532, 500, None).unwrap().len(), 330);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_step_stops_at_the_retention_window() {
+        crate::db::init_test_db();
+        let ann = fake::user(7533, "Ann", "");
+        let history: Vec<Message> = (1..=250)
+            .map(|id| fake::text_message(id, 7533, &ann, &format!("Message {}", id), 1000 + id))
+            .collect();
+        let client = FakeTelegram::new(fake::user(1, "Me", "")).with_messages(7533, history);
+
+        // Latest page first, then one older page per step
+        assert!(!backfill_step(&client, 7533, Some(1120)).await.unwrap());
+        assert_eq!(db_messages::load_sync_state(7533).unwrap().unwrap().oldest_message_id, 151);
+        // Messages 51..150 go back past the window's start (date 1120 = message 120)
+        assert!(backfill_step(&client, 7533, Some(1120)).await.unwrap());
+        assert_eq!(db_messages::load_sync_state(7533).unwrap().unwrap().oldest_message_id, 51);
+    }
+}
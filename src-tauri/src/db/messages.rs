@@ -0,0 +1,685 @@
+//! Local archive of fetched messages. Each chat keeps the contiguous range of
+//! its history that has been archived, so recent messages can be served without
+//! asking Telegram again (see `db::message_sync`).
+
+use super::with_db;
+use crate::telegram::client::{Message, MessageContent};
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// Marks put around matched terms in search snippets, turned into `SnippetPart`s
+const MATCH_START: char = '\u{1}';
+const MATCH_END: char = '\u{2}';
+
+/// Words of context in a search snippet
+const SNIPPET_WORDS: i64 = 16;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveChatStats {
+    pub chat_id: i64,
+    pub messages: i64,
+    pub oldest_date: i64,
+    pub newest_date: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveStats {
+    /// Largest chats first
+    pub chats: Vec<ArchiveChatStats>,
+    pub total_messages: i64,
+    /// Size of the whole database file
+    pub db_size_bytes: i64,
+    pub oldest_date: Option<i64>,
+    pub newest_date: Option<i64>,
+}
+
+/// An archived message Telegram reported deleted
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedMessage {
+    pub message: Message,
+    pub deleted_at: i64,
+    /// Texts it had before being edited, oldest first
+    pub earlier_versions: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncState {
+    /// Newest and oldest message of the contiguous archived range
+    pub newest_message_id: i64,
+    pub oldest_message_id: i64,
+    /// When the newest end of the range was last fetched
+    pub synced_at: i64,
+}
+
+/// A piece of a search snippet; `highlight` marks the matched terms
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetPart {
+    pub text: String,
+    pub highlight: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalSearchHit {
+    pub chat_id: i64,
+    pub message_id: i64,
+    pub sender_id: i64,
+    pub sender_name: String,
+    pub date: i64,
+    pub is_outgoing: bool,
+    pub snippet: Vec<SnippetPart>,
+    /// BM25 score; lower is a better match
+    pub rank: f64,
+}
+
+fn row_to_message(row: &rusqlite::Row) -> rusqlite::Result<Message> {
+    let content: String = row.get(4)?;
+    Ok(Message {
+        id: row.get(0)?,
+        chat_id: row.get(1)?,
+        sender_id: row.get(2)?,
+        sender_name: row.get(3)?,
+        content: serde_json::from_str(&content).unwrap_or(MessageContent::Unknown),
+        date: row.get(5)?,
+        is_outgoing: row.get(6)?,
+        is_read: true,
+        reply_to_message_id: row.get(7)?,
+        mentions_me: row.get(8)?,
+        inline_keyboard: Vec::new(),
+    })
+}
+
+pub fn load_sync_state(chat_id: i64) -> Result<Option<SyncState>, String> {
+    with_db(|conn| {
+        let result = conn.query_row(
+            "SELECT newest_message_id, oldest_message_id, synced_at FROM message_sync_state WHERE chat_id = ?",
+            [chat_id],
+            |row| {
+                Ok(SyncState {
+                    newest_message_id: row.get(0)?,
+                    oldest_message_id: row.get(1)?,
+                    synced_at: row.get(2)?,
+                })
+            },
+        );
+
+        match result {
+            Ok(state) => Ok(Some(state)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load message sync state: {}", e)),
+        }
+    })
+}
+
+/// The archived range after storing a page of history. `from_message_id` is the
+/// page's offset (None for the latest messages); the page holds every message
+/// just below it, so it extends the range when the two overlap. A latest page
+/// that doesn't overlap starts a new range; an older one leaves it as it is.
+fn merge_range(
+    state: Option<&SyncState>,
+    page_oldest: i64,
+    page_newest: i64,
+    from_message_id: Option<i64>,
+) -> Option<(i64, i64)> {
+    let Some(state) = state else {
+        return Some((page_oldest, page_newest));
+    };
+    let overlaps = page_oldest <= state.newest_message_id
+        && from_message_id.map_or(true, |from| from >= state.oldest_message_id);
+    match (overlaps, from_message_id) {
+        (true, _) => Some((
+            page_oldest.min(state.oldest_message_id),
+            page_newest.max(state.newest_message_id),
+        )),
+        (false, None) => Some((page_oldest, page_newest)),
+        (false, Some(_)) => None,
+    }
+}
+
+/// Archive a page of a chat's history (chronological, as returned by Telegram)
+pub fn store_messages(
+    chat_id: i64,
+    messages: &[Message],
+    from_message_id: Option<i64>,
+    now: i64,
+) -> Result<(), String> {
+    let (Some(page_oldest), Some(page_newest)) =
+        (messages.iter().map(|m| m.id).min(), messages.iter().map(|m| m.id).max())
+    else {
+        return Ok(());
+    };
+    let state = load_sync_state(chat_id)?;
+
+    with_db(|conn| {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        for message in messages {
+            let content = serde_json::to_string(&message.content)
+                .map_err(|e| format!("Failed to serialize message: {}", e))?;
+            tx.execute(
+                r#"
+                INSERT INTO messages
+                    (chat_id, message_id, sender_id, sender_name, content, text, date, is_outgoing,
+                     reply_to_message_id, mentions_me, archived_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(chat_id, message_id) DO UPDATE SET
+                    sender_id = excluded.sender_id,
+                    sender_name = excluded.sender_name,
+                    content = excluded.content,
+                    text = excluded.text,
+                    date = excluded.date,
+                    is_outgoing = excluded.is_outgoing,
+                    reply_to_message_id = excluded.reply_to_message_id,
+                    mentions_me = excluded.mentions_me,
+                    archived_at = excluded.archived_at
+                "#,
+                rusqlite::params![
+                    chat_id,
+                    message.id,
+                    message.sender_id,
+                    message.sender_name,
+                    content,
+                    message.content.describe(),
+                    message.date,
+                    message.is_outgoing,
+                    message.reply_to_message_id,
+                    message.mentions_me,
+                    now,
+                ],
+            )
+            .map_err(|e| format!("Failed to archive message: {}", e))?;
+        }
+
+        if let Some((oldest, newest)) = merge_range(state.as_ref(), page_oldest, page_newest, from_message_id) {
+            // Only a page of the latest messages says the newest end is current
+            let synced_at = match (&state, from_message_id) {
+                (Some(state), Some(_)) => state.synced_at,
+                _ => now,
+            };
+            tx.execute(
+                r#"
+                INSERT OR REPLACE INTO message_sync_state (chat_id, newest_message_id, oldest_message_id, synced_at)
+                VALUES (?, ?, ?, ?)
+                "#,
+                rusqlite::params![chat_id, newest, oldest, synced_at],
+            )
+            .map_err(|e| format!("Failed to save message sync state: {}", e))?;
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit archived messages: {}", e))
+    })
+}
+
+/// Up to `limit` archived messages of a chat, older than `before_id` when given,
+/// in chronological order
+pub fn load_messages(chat_id: i64, limit: i32, before_id: Option<i64>) -> Result<Vec<Message>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT message_id, chat_id, sender_id, sender_name, content, date, is_outgoing,
+                       reply_to_message_id, mentions_me
+                FROM messages
+                WHERE chat_id = ? AND message_id < ? AND deleted_at IS NULL
+                ORDER BY message_id DESC
+                LIMIT ?
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let mut messages: Vec<Message> = stmt
+            .query_map(rusqlite::params![chat_id, before_id.unwrap_or(i64::MAX), limit], row_to_message)
+            .map_err(|e| format!("Failed to query archived messages: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        messages.reverse();
+        Ok(messages)
+    })
+}
+
+/// The latest `limit` messages of a chat from the archive, if the archived range
+/// ends at `latest_message_id`, was fetched since `synced_since` and holds enough
+/// messages. None means the chat has to be fetched from Telegram.
+pub fn load_current(
+    chat_id: i64,
+    latest_message_id: i64,
+    limit: i32,
+    synced_since: i64,
+) -> Result<Option<Vec<Message>>, String> {
+    let Some(state) = load_sync_state(chat_id)? else {
+        return Ok(None);
+    };
+    if state.newest_message_id != latest_message_id || state.synced_at < synced_since {
+        return Ok(None);
+    }
+
+    let messages = load_messages(chat_id, limit, Some(state.newest_message_id + 1))?;
+    let in_range = messages.iter().filter(|m| m.id >= state.oldest_message_id).count();
+    Ok((in_range >= limit.max(1) as usize).then_some(messages))
+}
+
+/// Extend a chat's archived range down to `oldest_message_id`. Used once a sync
+/// has fetched every message between the new latest page and the range archived
+/// before, which `store_messages` can't tell from a single page.
+pub fn extend_range_down(chat_id: i64, oldest_message_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE message_sync_state SET oldest_message_id = MIN(oldest_message_id, ?) WHERE chat_id = ?",
+            rusqlite::params![oldest_message_id, chat_id],
+        )
+        .map_err(|e| format!("Failed to update message sync state: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Apply an edit to an archived message; the old text goes to `message_edits`.
+/// Messages that aren't archived are left alone.
+pub fn record_edit(message: &Message, now: i64) -> Result<bool, String> {
+    let content = serde_json::to_string(&message.content)
+        .map_err(|e| format!("Failed to serialize message: {}", e))?;
+    with_db(|conn| {
+        let updated = conn
+            .execute(
+                "UPDATE messages SET content = ?, text = ?, archived_at = ? WHERE chat_id = ? AND message_id = ?",
+                rusqlite::params![content, message.content.describe(), now, message.chat_id, message.id],
+            )
+            .map_err(|e| format!("Failed to archive message edit: {}", e))?;
+        Ok(updated > 0)
+    })
+}
+
+/// Mark archived messages deleted. Telegram names the chat only for channels and
+/// supergroups; other ids are shared by every private chat and basic group, so
+/// `chat_ids` lists the chats they may belong to.
+pub fn mark_deleted(chat_ids: &[i64], message_ids: &[i64], now: i64) -> Result<usize, String> {
+    with_db(|conn| {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let mut marked = 0;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "UPDATE messages SET deleted_at = ? WHERE chat_id = ? AND message_id = ? AND deleted_at IS NULL",
+                )
+                .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+            for chat_id in chat_ids {
+                for message_id in message_ids {
+                    marked += stmt
+                        .execute(rusqlite::params![now, chat_id, message_id])
+                        .map_err(|e| format!("Failed to mark message deleted: {}", e))?;
+                }
+            }
+        }
+        tx.commit().map_err(|e| format!("Failed to commit deleted messages: {}", e))?;
+        Ok(marked)
+    })
+}
+
+/// A chat's archived messages that were deleted, most recently deleted first
+pub fn load_deleted(chat_id: i64, limit: i32) -> Result<Vec<DeletedMessage>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT message_id, chat_id, sender_id, sender_name, content, date, is_outgoing,
+                       reply_to_message_id, mentions_me, deleted_at
+                FROM messages
+                WHERE chat_id = ? AND deleted_at IS NOT NULL
+                ORDER BY deleted_at DESC, message_id DESC
+                LIMIT ?
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+        let deleted: Vec<(Message, i64)> = stmt
+            .query_map(rusqlite::params![chat_id, limit], |row| Ok((row_to_message(row)?, row.get(9)?)))
+            .map_err(|e| format!("Failed to query deleted messages: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut edits = conn
+            .prepare("SELECT text FROM message_edits WHERE chat_id = ? AND message_id = ? ORDER BY id")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+        deleted
+            .into_iter()
+            .map(|(message, deleted_at)| {
+                let earlier_versions = edits
+                    .query_map(rusqlite::params![chat_id, message.id], |row| row.get(0))
+                    .map_err(|e| format!("Failed to query message edits: {}", e))?
+                    .filter_map(|r| r.ok())
+                    .collect();
+                Ok(DeletedMessage { message, deleted_at, earlier_versions })
+            })
+            .collect()
+    })
+}
+
+/// Stop serving a chat's recent messages from the archive until they are fetched again
+pub fn mark_stale(chat_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("UPDATE message_sync_state SET synced_at = 0 WHERE chat_id = ?", [chat_id])
+            .map_err(|e| format!("Failed to update message sync state: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Start archived ranges at their oldest remaining message, and drop the ranges
+/// of chats with none left, after archived messages were deleted
+pub(super) fn trim_sync_state(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+        DELETE FROM message_sync_state WHERE chat_id NOT IN (SELECT chat_id FROM messages);
+        UPDATE message_sync_state SET oldest_message_id = MAX(
+            oldest_message_id,
+            (SELECT MIN(message_id) FROM messages m WHERE m.chat_id = message_sync_state.chat_id)
+        );
+        "#,
+    )
+    .map_err(|e| format!("Failed to update archived ranges: {}", e))
+}
+
+/// Distinct sender names in the archive (to scrub them from diagnostics)
+pub fn sender_names() -> Result<Vec<String>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT DISTINCT sender_name FROM messages WHERE sender_name != ''")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+        let names = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| format!("Failed to query sender names: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(names)
+    })
+}
+
+pub fn archive_stats() -> Result<ArchiveStats, String> {
+    with_db(stats)
+}
+
+fn stats(conn: &Connection) -> Result<ArchiveStats, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT chat_id, COUNT(*), MIN(date), MAX(date)
+            FROM messages
+            GROUP BY chat_id
+            ORDER BY COUNT(*) DESC
+            "#,
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let chats: Vec<ArchiveChatStats> = stmt
+        .query_map([], |row| {
+            Ok(ArchiveChatStats {
+                chat_id: row.get(0)?,
+                messages: row.get(1)?,
+                oldest_date: row.get(2)?,
+                newest_date: row.get(3)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query archive stats: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let db_size_bytes = conn
+        .query_row(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("Failed to read database size: {}", e))?;
+
+    Ok(ArchiveStats {
+        total_messages: chats.iter().map(|c| c.messages).sum(),
+        oldest_date: chats.iter().map(|c| c.oldest_date).min(),
+        newest_date: chats.iter().map(|c| c.newest_date).max(),
+        chats,
+        db_size_bytes,
+    })
+}
+
+/// Delete archived messages of `chat_ids` (every chat if None) dated before
+/// `older_than` (any date if None), then reclaim the space. Returns how many
+/// messages were deleted.
+pub fn compact_archive(chat_ids: Option<&[i64]>, older_than: Option<i64>) -> Result<usize, String> {
+    with_db(|conn| compact(conn, chat_ids, older_than))
+}
+
+fn compact(conn: &Connection, chat_ids: Option<&[i64]>, older_than: Option<i64>) -> Result<usize, String> {
+    if chat_ids.is_none() && older_than.is_none() {
+        return Err("Choose chats or a date to compact the archive by".to_string());
+    }
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let older_than = older_than.unwrap_or(i64::MAX);
+    let deleted = match chat_ids {
+        Some(chat_ids) => {
+            let mut deleted = 0;
+            for chat_id in chat_ids {
+                deleted += tx
+                    .execute(
+                        "DELETE FROM messages WHERE chat_id = ? AND date < ?",
+                        rusqlite::params![chat_id, older_than],
+                    )
+                    .map_err(|e| format!("Failed to compact archive: {}", e))?;
+            }
+            deleted
+        }
+        None => tx
+            .execute("DELETE FROM messages WHERE date < ?", [older_than])
+            .map_err(|e| format!("Failed to compact archive: {}", e))?,
+    };
+    trim_sync_state(&tx)?;
+    tx.commit().map_err(|e| format!("Failed to commit compaction: {}", e))?;
+
+    if deleted > 0 {
+        conn.execute_batch("INSERT INTO messages_fts(messages_fts) VALUES('optimize'); VACUUM;")
+            .map_err(|e| format!("Failed to reclaim archive space: {}", e))?;
+    }
+    Ok(deleted)
+}
+
+/// FTS5 query for what the user typed: every word must match, as a prefix.
+/// Words are quoted so FTS5 operators and punctuation are taken literally.
+pub fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+fn snippet_parts(snippet: &str) -> Vec<SnippetPart> {
+    let mut parts = Vec::new();
+    let mut highlight = false;
+    for piece in snippet.split([MATCH_START, MATCH_END]) {
+        if !piece.is_empty() {
+            parts.push(SnippetPart { text: piece.to_string(), highlight });
+        }
+        highlight = !highlight;
+    }
+    parts
+}
+
+/// Archived messages matching `query` (see `fts_query`), best matches first
+pub fn search_archive(
+    query: &str,
+    chat_id: Option<i64>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<LocalSearchHit>, String> {
+    let Some(fts) = fts_query(query) else {
+        return Ok(vec![]);
+    };
+
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT m.chat_id, m.message_id, m.sender_id, m.sender_name, m.date, m.is_outgoing,
+                       snippet(messages_fts, 0, ?, ?, '…', ?), bm25(messages_fts, 1.0, 0.5) AS rank
+                FROM messages_fts
+                JOIN messages m ON m.rowid = messages_fts.rowid
+                WHERE messages_fts MATCH ? AND (? IS NULL OR m.chat_id = ?)
+                ORDER BY rank, m.date DESC
+                LIMIT ? OFFSET ?
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let hits = stmt
+            .query_map(
+                rusqlite::params![
+                    MATCH_START.to_string(),
+                    MATCH_END.to_string(),
+                    SNIPPET_WORDS,
+                    fts,
+                    chat_id,
+                    chat_id,
+                    limit,
+                    offset
+                ],
+                |row| {
+                    let snippet: String = row.get(6)?;
+                    Ok(LocalSearchHit {
+                        chat_id: row.get(0)?,
+                        message_id: row.get(1)?,
+                        sender_id: row.get(2)?,
+                        sender_name: row.get(3)?,
+                        date: row.get(4)?,
+                        is_outgoing: row.get(5)?,
+                        snippet: snippet_parts(&snippet),
+                        rank: row.get(7)?,
+                    })
+                },
+            )
+            .map_err(|e| format!("Failed to search archived messages: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(hits)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake;
+
+    #[test]
+    fn test_search_archive() {
+        crate::db::init_test_db();
+        let ann = fake::user(7551, "Ann", "");
+        let message = |id, chat_id, text: &str| fake::text_message(id, chat_id, &ann, text, 1_000 + id);
+        let invoice = message(1, 7551, "Invoice 7551 for the café renovation is attached");
+        store_messages(7551, &[invoice], None, 0).unwrap();
+        store_messages(7552, &[message(1, 7552, "Did the invoices 7551 arrive?")], None, 0).unwrap();
+
+        // Prefix and accent-insensitive matching, across chats or within one
+        let hits = search_archive("invoice cafe 7551", None, 10, 0).unwrap();
+        assert_eq!(hits.iter().map(|h| h.chat_id).collect::<Vec<_>>(), vec![7551]);
+        assert_eq!(search_archive("INVOICE 7551", None, 10, 0).unwrap().len(), 2);
+        assert_eq!(search_archive("invoice 7551", Some(7552), 10, 0).unwrap()[0].message_id, 1);
+
+        let highlighted: Vec<&str> = hits[0].snippet.iter().filter(|p| p.highlight).map(|p| p.text.as_str()).collect();
+        assert_eq!(highlighted, vec!["Invoice", "7551", "café"]);
+
+        // Re-archiving an edited message replaces it in the index
+        store_messages(7551, &[message(1, 7551, "Receipt 7551 attached")], None, 0).unwrap();
+        assert!(search_archive("invoice cafe 7551", None, 10, 0).unwrap().is_empty());
+
+        // Operators and quotes are searched literally instead of breaking the query
+        assert_eq!(fts_query(r#"say "hi" OR -"#).as_deref(), Some(r#""say"* """hi"""* "OR"*"#));
+        assert!(search_archive("\"", None, 10, 0).unwrap().is_empty());
+    }
+
+    fn archive_into(conn: &Connection, chat_id: i64, ids: std::ops::RangeInclusive<i64>) {
+        for id in ids.clone() {
+            conn.execute(
+                r#"
+                INSERT INTO messages (chat_id, message_id, sender_id, sender_name, content, text, date, is_outgoing, archived_at)
+                VALUES (?1, ?2, 1, 'Ann', '{}', 'hi', ?2 * 100, 0, 0)
+                "#,
+                rusqlite::params![chat_id, id],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO message_sync_state (chat_id, newest_message_id, oldest_message_id, synced_at) VALUES (?, ?, ?, 0)",
+            rusqlite::params![chat_id, ids.end(), ids.start()],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_archive_stats_and_compaction() {
+        let conn = crate::db::open_test_db();
+        archive_into(&conn, 7553, 1..=10);
+        archive_into(&conn, 7554, 1..=4);
+
+        let before = stats(&conn).unwrap();
+        assert_eq!(before.chats.iter().map(|c| (c.chat_id, c.messages)).collect::<Vec<_>>(), vec![(7553, 10), (7554, 4)]);
+        assert_eq!((before.total_messages, before.oldest_date, before.newest_date), (14, Some(100), Some(1000)));
+        assert!(before.db_size_bytes > 0);
+
+        // By date: ranges start at the oldest remaining message
+        assert_eq!(compact(&conn, None, Some(600)).unwrap(), 9);
+        let oldest: i64 = conn
+            .query_row("SELECT oldest_message_id FROM message_sync_state WHERE chat_id = 7553", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(oldest, 6);
+        // By chat: the chat's range goes with its messages
+        assert_eq!(compact(&conn, Some(&[7553]), None).unwrap(), 5);
+        let ranges: i64 = conn.query_row("SELECT COUNT(*) FROM message_sync_state", [], |row| row.get(0)).unwrap();
+        assert_eq!(ranges, 0);
+        assert_eq!(stats(&conn).unwrap().total_messages, 0);
+        assert!(compact(&conn, None, None).is_err());
+    }
+
+    #[test]
+    fn test_edits_and_deletions_are_kept() {
+        crate::db::init_test_db();
+        let ann = fake::user(7555, "Ann", "");
+        let message = |id, text: &str| fake::text_message(id, 7555, &ann, text, 1_000 + id);
+        store_messages(7555, &[message(1, "See you at 5"), message(2, "Bring snacks")], None, 10).unwrap();
+
+        // An edit update and a refetch with new text both keep the earlier text
+        assert!(record_edit(&message(1, "See you at 6"), 20).unwrap());
+        store_messages(7555, &[message(1, "See you at 7")], None, 30).unwrap();
+        assert!(!record_edit(&message(9, "Not archived"), 20).unwrap());
+
+        // Deleted ids can belong to any of the candidate chats
+        assert_eq!(mark_deleted(&[7556, 7555], &[1], 40).unwrap(), 1);
+        assert_eq!(mark_deleted(&[7555], &[1], 50).unwrap(), 0);
+        assert_eq!(load_messages(7555, 10, None).unwrap().iter().map(|m| m.id).collect::<Vec<_>>(), vec![2]);
+
+        let deleted = load_deleted(7555, 10).unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!((deleted[0].message.id, deleted[0].deleted_at), (1, 40));
+        assert_eq!(deleted[0].earlier_versions, vec!["See you at 5", "See you at 6"]);
+    }
+
+    #[test]
+    fn test_mark_stale_refetches_the_latest_page() {
+        crate::db::init_test_db();
+        let ann = fake::user(7557, "Ann", "");
+        let page: Vec<Message> = (1..=3).map(|id| fake::text_message(id, 7557, &ann, "hi", 1_000 + id)).collect();
+        store_messages(7557, &page, None, 100).unwrap();
+        assert_eq!(load_current(7557, 3, 3, 50).unwrap().map(|m| m.len()), Some(3));
+
+        // The archived messages stay, but are no longer served as current
+        mark_stale(7557).unwrap();
+        assert!(load_current(7557, 3, 3, 50).unwrap().is_none());
+        assert_eq!(load_messages(7557, 10, None).unwrap().len(), 3);
+    }
+}
@@ -1,30 +1,134 @@
 pub mod schema;
+pub mod ai_call_log;
+pub mod aliases;
+pub mod archive_backfill;
+pub mod audit;
+pub mod away;
+pub mod bot_rules;
+pub mod briefing_categories;
+pub mod briefing_corrections;
+pub mod briefing_pins;
+pub mod briefings;
+pub mod calls;
+pub mod chat_notes;
+pub mod canned;
+pub mod channel_stats;
 pub mod contacts;
+pub mod crosspost;
+pub mod decisions;
+pub mod integrity;
+pub mod lists;
+pub mod llm_cache;
+pub mod mentions;
+pub mod message_sync;
+pub mod messages;
+pub mod notifications;
+pub mod outbox;
 pub mod outreach;
+pub mod pending_drafts;
+pub mod pipelines;
+pub mod reminders;
+pub mod retention;
+pub mod rsvp;
+pub mod scheduled_posts;
 pub mod scopes;
 pub mod settings;
+pub mod stars;
+pub mod triage;
+pub mod welcome_kits;
 
 use rusqlite::Connection;
 use std::path::PathBuf;
 use std::sync::Mutex;
+use std::time::Duration;
 use once_cell::sync::Lazy;
 
+/// How long a write waits for a lock held by another connection
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub static DB: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
 
-pub fn init_db(app_dir: PathBuf) -> Result<(), String> {
-    let db_path = app_dir.join("telegram_copilot.db");
+/// Path of the open database, for `repair_db`
+static DB_PATH: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
 
-    let conn = Connection::open(&db_path)
-        .map_err(|e| format!("Failed to open database: {}", e))?;
+/// WAL so a crash mid-write can't corrupt the file and readers don't block the
+/// writer; a busy timeout instead of failing when another connection (the CLI)
+/// holds the lock; and enforced foreign keys so deletes cascade
+fn configure(conn: &Connection) -> Result<(), String> {
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+        .map_err(|e| format!("Failed to enable WAL: {}", e))?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| format!("Failed to set busy timeout: {}", e))?;
+    conn.pragma_update(None, "foreign_keys", true)
+        .map_err(|e| format!("Failed to enable foreign keys: {}", e))
+}
 
+fn open_db(db_path: &PathBuf) -> Result<Connection, String> {
+    let conn = Connection::open(db_path)
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+    configure(&conn)?;
     schema::create_tables(&conn)?;
+    Ok(conn)
+}
+
+pub fn init_db(app_dir: PathBuf) -> Result<(), String> {
+    let db_path = app_dir.join(integrity::DB_FILE);
+
+    let conn = open_db(&db_path)?;
 
     *DB.lock().unwrap() = Some(conn);
+    *DB_PATH.lock().unwrap() = Some(db_path.clone());
 
     log::info!("Database initialized at {:?}", db_path);
     Ok(())
 }
 
+/// Close the database, check and repair it, then reopen it
+pub fn repair_db() -> Result<integrity::IntegrityReport, String> {
+    let db_path = DB_PATH
+        .lock()
+        .map_err(|e| format!("Failed to lock database path: {}", e))?
+        .clone()
+        .ok_or("Database not initialized")?;
+    let mut guard = DB.lock().map_err(|e| format!("Failed to lock database: {}", e))?;
+    // Dropping the connection closes it so the file can be moved aside
+    *guard = None;
+
+    let mut report = integrity::IntegrityReport { session_ok: true, ..Default::default() };
+    integrity::repair_database_file(&db_path, &mut report);
+    *guard = Some(open_db(&db_path)?);
+    Ok(report)
+}
+
+/// Apply the copilot's own per-chat state (nicknames, stars, notes) to chats from Telegram
+pub fn apply_local_chat_state(chats: &mut [crate::telegram::client::Chat]) {
+    aliases::apply(chats);
+    stars::apply(chats);
+    chat_notes::apply(chats);
+}
+
+/// Shared in-memory database for tests that exercise commands end-to-end
+#[cfg(test)]
+pub fn init_test_db() {
+    let mut db = DB.lock().unwrap();
+    if db.is_none() {
+        let conn = Connection::open_in_memory().expect("Failed to open in-memory database");
+        configure(&conn).expect("Failed to configure database");
+        schema::create_tables(&conn).expect("Failed to create tables");
+        *db = Some(conn);
+    }
+}
+
+/// A fresh in-memory database, for tests whose writes would disturb tests
+/// sharing `init_test_db`
+#[cfg(test)]
+pub fn open_test_db() -> Connection {
+    let conn = Connection::open_in_memory().expect("Failed to open in-memory database");
+    configure(&conn).expect("Failed to configure database");
+    schema::create_tables(&conn).expect("Failed to create tables");
+    conn
+}
+
 pub fn with_db<F, T>(f: F) -> Result<T, String>
 where
     F: FnOnce(&Connection) -> Result<T, String>,
@@ -33,3 +137,31 @@ where
     let conn = guard.as_ref().ok_or("Database not initialized")?;
     f(conn)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_db_enables_wal_and_foreign_keys() {
+        let path = std::env::temp_dir().join(format!("db-{}.db", uuid::Uuid::new_v4()));
+        let conn = open_db(&path).unwrap();
+        let mode: String = conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+
+        // Deleting a queue removes its recipients
+        conn.execute_batch(
+            "INSERT INTO outreach_queue (id, template, status, created_at) VALUES ('q', 't', 'completed', 0);
+             INSERT INTO outreach_recipients (queue_id, user_id, status) VALUES ('q', 1, 'sent');
+             DELETE FROM outreach_queue WHERE id = 'q';",
+        )
+        .unwrap();
+        let left: i64 = conn.query_row("SELECT COUNT(*) FROM outreach_recipients", [], |row| row.get(0)).unwrap();
+        assert_eq!(left, 0);
+
+        drop(conn);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use super::with_db;
+use serde::{Deserialize, Serialize};
+
+pub const MODE_ALL: &str = "all";
+pub const MODE_URGENT: &str = "urgent";
+pub const MODE_VIP: &str = "vip";
+pub const MODE_NONE: &str = "none";
+
+pub const MODES: [&str; 4] = [MODE_ALL, MODE_URGENT, MODE_VIP, MODE_NONE];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationRule {
+    pub chat_id: i64,
+    pub mode: String,
+    /// Notify for this chat even during quiet hours
+    pub ignore_quiet_hours: bool,
+}
+
+fn row_to_rule(row: &rusqlite::Row) -> rusqlite::Result<NotificationRule> {
+    Ok(NotificationRule {
+        chat_id: row.get(0)?,
+        mode: row.get(1)?,
+        ignore_quiet_hours: row.get(2)?,
+    })
+}
+
+pub fn load_rule(chat_id: i64) -> Result<Option<NotificationRule>, String> {
+    with_db(|conn| {
+        let result = conn.query_row(
+            "SELECT chat_id, mode, ignore_quiet_hours FROM notification_rules WHERE chat_id = ?",
+            [chat_id],
+            row_to_rule,
+        );
+
+        match result {
+            Ok(rule) => Ok(Some(rule)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load notification rule: {}", e)),
+        }
+    })
+}
+
+pub fn load_rules() -> Result<Vec<NotificationRule>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT chat_id, mode, ignore_quiet_hours FROM notification_rules ORDER BY chat_id")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rules = stmt
+            .query_map([], row_to_rule)
+            .map_err(|e| format!("Failed to query notification rules: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(rules)
+    })
+}
+
+pub fn save_rule(rule: &NotificationRule) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO notification_rules (chat_id, mode, ignore_quiet_hours, updated_at)
+            VALUES (?1, ?2, ?3, strftime('%s', 'now'))
+            ON CONFLICT(chat_id) DO UPDATE SET
+                mode = ?2, ignore_quiet_hours = ?3, updated_at = strftime('%s', 'now')
+            "#,
+            rusqlite::params![rule.chat_id, rule.mode, rule.ignore_quiet_hours],
+        )
+        .map_err(|e| format!("Failed to save notification rule: {}", e))?;
+        Ok(())
+    })
+}
+
+pub fn delete_rule(chat_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM notification_rules WHERE chat_id = ?", [chat_id])
+            .map_err(|e| format!("Failed to delete notification rule: {}", e))?;
+        Ok(())
+    })
+}
@@ -0,0 +1,94 @@
+use super::with_db;
+use serde::Serialize;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_SENT: &str = "sent";
+pub const STATUS_FAILED: &str = "failed";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxEntry {
+    pub id: i64,
+    pub chat_id: i64,
+    pub text: String,
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+}
+
+fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<OutboxEntry> {
+    Ok(OutboxEntry {
+        id: row.get(0)?,
+        chat_id: row.get(1)?,
+        text: row.get(2)?,
+        status: row.get(3)?,
+        attempts: row.get(4)?,
+        last_error: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+pub fn add_entry(chat_id: i64, text: &str, error: &str) -> Result<i64, String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO outbox (chat_id, text, attempts, last_error) VALUES (?, ?, 1, ?)",
+            rusqlite::params![chat_id, text, error],
+        )
+        .map_err(|e| format!("Failed to queue message in outbox: {}", e))?;
+        Ok(conn.last_insert_rowid())
+    })
+}
+
+/// Messages still waiting to be sent, oldest first
+pub fn load_pending() -> Result<Vec<OutboxEntry>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT id, chat_id, text, status, attempts, last_error, created_at
+                FROM outbox WHERE status = ? ORDER BY id
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let entries = stmt
+            .query_map([STATUS_PENDING], row_to_entry)
+            .map_err(|e| format!("Failed to query outbox: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    })
+}
+
+/// Record a send attempt; `status` is "pending" to keep retrying
+pub fn record_attempt(id: i64, status: &str, error: Option<&str>) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            UPDATE outbox SET status = ?, attempts = attempts + 1, last_error = ?, updated_at = strftime('%s', 'now')
+            WHERE id = ?
+            "#,
+            rusqlite::params![status, error, id],
+        )
+        .map_err(|e| format!("Failed to update outbox: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Give up on a pending message without another attempt
+pub fn cancel_entry(id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        let updated = conn
+            .execute(
+                "DELETE FROM outbox WHERE id = ? AND status = ?",
+                rusqlite::params![id, STATUS_PENDING],
+            )
+            .map_err(|e| format!("Failed to cancel outbox message: {}", e))?;
+        if updated == 0 {
+            return Err(format!("Outbox message {} is not pending", id));
+        }
+        Ok(())
+    })
+}
@@ -4,7 +4,12 @@ use crate::commands::outreach::{OutreachQueue, OutreachRecipient};
 
 /// Save a new outreach queue to the database
 pub fn save_queue(conn: &Connection, queue: &OutreachQueue) -> Result<(), String> {
-    conn.execute(
+    // The queue and its recipients are written together or not at all
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+    tx.execute(
         r#"
         INSERT INTO outreach_queue (id, template, status, created_at, started_at, completed_at)
         VALUES (?1, ?2, ?3, strftime('%s', 'now'), ?4, ?5)
@@ -25,10 +30,10 @@ pub fn save_queue(conn: &Connection, queue: &OutreachQueue) -> Result<(), String
 
     // Save recipients
     for recipient in &queue.recipients {
-        save_recipient(conn, &queue.id, recipient)?;
+        save_recipient(&tx, &queue.id, recipient)?;
     }
 
-    Ok(())
+    tx.commit().map_err(|e| format!("Failed to commit queue: {}", e))
 }
 
 /// Save or update a single recipient
@@ -58,15 +63,18 @@ pub fn save_recipient(
     if updated == 0 {
         conn.execute(
             r#"
-            INSERT INTO outreach_recipients (queue_id, user_id, status, error, sent_at)
-            VALUES (?1, ?2, ?3, ?4, ?5)
+            INSERT INTO outreach_recipients (queue_id, user_id, status, error, sent_at, first_name, last_name, username)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
             "#,
             params![
                 queue_id,
                 recipient.user_id,
                 recipient.status,
                 recipient.error,
-                recipient.sent_at
+                recipient.sent_at,
+                recipient.first_name,
+                recipient.last_name,
+                recipient.username
             ],
         )
         .map_err(|e| format!("Failed to insert recipient: {}", e))?;
@@ -166,7 +174,7 @@ pub fn load_recipients(conn: &Connection, queue_id: &str) -> Result<Vec<Outreach
     let mut stmt = conn
         .prepare(
             r#"
-            SELECT user_id, status, error, sent_at
+            SELECT user_id, status, error, sent_at, first_name, last_name, username
             FROM outreach_recipients
             WHERE queue_id = ?1
             ORDER BY id ASC
@@ -178,8 +186,9 @@ pub fn load_recipients(conn: &Connection, queue_id: &str) -> Result<Vec<Outreach
         .query_map(params![queue_id], |row| {
             Ok(OutreachRecipient {
                 user_id: row.get(0)?,
-                first_name: String::new(), // Not stored in DB, will be fetched from contacts
-                last_name: String::new(),
+                first_name: row.get(4)?,
+                last_name: row.get(5)?,
+                username: row.get(6)?,
                 status: row.get(1)?,
                 error: row.get(2)?,
                 sent_at: row.get(3)?,
@@ -244,6 +253,58 @@ pub fn load_incomplete_queues(conn: &Connection) -> Result<Vec<OutreachQueue>, S
     Ok(queues)
 }
 
+/// Load the most recent queues regardless of status, newest first
+pub fn load_recent_queues(conn: &Connection, limit: i64) -> Result<Vec<OutreachQueue>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT id
+            FROM outreach_queue
+            ORDER BY created_at DESC
+            LIMIT ?1
+            "#,
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let ids: Vec<String> = stmt
+        .query_map(params![limit], |row| row.get(0))
+        .map_err(|e| format!("Failed to query queues: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut queues = Vec::new();
+    for id in ids {
+        if let Some(queue) = load_queue(conn, &id)? {
+            queues.push(queue);
+        }
+    }
+
+    Ok(queues)
+}
+
+/// Outreach messages sent to a user as (template, sent_at), oldest first
+pub fn load_sent_to_user(conn: &Connection, user_id: i64) -> Result<Vec<(String, i64)>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+            SELECT q.template, r.sent_at
+            FROM outreach_recipients r
+            JOIN outreach_queue q ON q.id = r.queue_id
+            WHERE r.user_id = ?1 AND r.status = 'sent' AND r.sent_at IS NOT NULL
+            ORDER BY r.sent_at
+            "#,
+        )
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let sent = stmt
+        .query_map(params![user_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| format!("Failed to query sent outreach: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(sent)
+}
+
 /// Delete a queue and its recipients.
 /// TODO: Expose as a Tauri command for cleaning up old/completed queues.
 #[allow(dead_code)]
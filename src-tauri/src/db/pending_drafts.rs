@@ -0,0 +1,118 @@
+use super::with_db;
+use serde::Serialize;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_SENT: &str = "sent";
+pub const STATUS_REJECTED: &str = "rejected";
+
+pub const SOURCE_AWAY: &str = "away";
+pub const SOURCE_RULE: &str = "rule";
+pub const SOURCE_AI: &str = "ai";
+/// Proposed by an external assistant over MCP
+pub const SOURCE_MCP: &str = "mcp";
+
+/// An automated reply held back until the user approves it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingDraft {
+    pub id: i64,
+    pub chat_id: i64,
+    pub text: String,
+    /// What produced it: "away", "rule", "ai" or "mcp"
+    pub source: String,
+    pub status: String,
+    pub created_at: i64,
+}
+
+fn row_to_draft(row: &rusqlite::Row) -> rusqlite::Result<PendingDraft> {
+    Ok(PendingDraft {
+        id: row.get(0)?,
+        chat_id: row.get(1)?,
+        text: row.get(2)?,
+        source: row.get(3)?,
+        status: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+pub fn add_draft(chat_id: i64, text: &str, source: &str) -> Result<i64, String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO pending_drafts (chat_id, text, source) VALUES (?, ?, ?)",
+            rusqlite::params![chat_id, text, source],
+        )
+        .map_err(|e| format!("Failed to queue draft: {}", e))?;
+        Ok(conn.last_insert_rowid())
+    })
+}
+
+pub fn load_draft(id: i64) -> Result<Option<PendingDraft>, String> {
+    with_db(|conn| {
+        match conn.query_row(
+            "SELECT id, chat_id, text, source, status, created_at FROM pending_drafts WHERE id = ?",
+            [id],
+            row_to_draft,
+        ) {
+            Ok(draft) => Ok(Some(draft)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load draft: {}", e)),
+        }
+    })
+}
+
+/// Drafts awaiting a decision, oldest first
+pub fn load_pending() -> Result<Vec<PendingDraft>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, chat_id, text, source, status, created_at FROM pending_drafts WHERE status = ? ORDER BY id",
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let drafts = stmt
+            .query_map([STATUS_PENDING], row_to_draft)
+            .map_err(|e| format!("Failed to query drafts: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(drafts)
+    })
+}
+
+pub fn count_pending() -> Result<i64, String> {
+    with_db(|conn| {
+        conn.query_row("SELECT COUNT(*) FROM pending_drafts WHERE status = ?", [STATUS_PENDING], |row| row.get(0))
+            .map_err(|e| format!("Failed to count drafts: {}", e))
+    })
+}
+
+/// Resolve a pending draft, recording the text that was actually sent
+pub fn resolve_draft(id: i64, status: &str, text: &str) -> Result<(), String> {
+    with_db(|conn| {
+        let updated = conn
+            .execute(
+                r#"
+                UPDATE pending_drafts SET status = ?, text = ?, resolved_at = strftime('%s', 'now')
+                WHERE id = ? AND status = 'pending'
+                "#,
+                rusqlite::params![status, text, id],
+            )
+            .map_err(|e| format!("Failed to update draft: {}", e))?;
+        if updated == 0 {
+            return Err(format!("Draft {} is not pending", id));
+        }
+        Ok(())
+    })
+}
+
+/// Put a draft whose send failed back in the queue, keeping the text it was approved with
+pub fn reopen_draft(id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE pending_drafts SET status = 'pending', resolved_at = NULL WHERE id = ? AND status = 'sent'",
+            [id],
+        )
+        .map_err(|e| format!("Failed to reopen draft: {}", e))?;
+        Ok(())
+    })
+}
@@ -0,0 +1,321 @@
+use super::with_db;
+use serde::{Deserialize, Serialize};
+
+/// Stage reached automatically when an outreach message is sent
+pub const ROLE_CONTACTED: &str = "contacted";
+/// Stage reached automatically when the contact replies
+pub const ROLE_REPLIED: &str = "replied";
+
+pub const DEFAULT_PIPELINE_NAME: &str = "Default";
+pub const DEFAULT_STAGES: [&str; 5] = ["Lead", "Contacted", "Replied", "Meeting", "Closed"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineStage {
+    pub id: i64,
+    pub name: String,
+    pub position: i32,
+    pub auto_role: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pipeline {
+    pub id: String,
+    pub name: String,
+    pub stages: Vec<PipelineStage>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineEntry {
+    pub user_id: i64,
+    pub stage_id: i64,
+    pub updated_at: i64,
+}
+
+/// Stages named like the automatic roles get them (e.g. "Contacted", "Replied")
+fn auto_role_for(stage_name: &str) -> Option<&'static str> {
+    match stage_name.trim().to_lowercase().as_str() {
+        "contacted" => Some(ROLE_CONTACTED),
+        "replied" => Some(ROLE_REPLIED),
+        _ => None,
+    }
+}
+
+pub fn create_pipeline(name: &str, stages: &[String]) -> Result<Pipeline, String> {
+    let id = uuid::Uuid::new_v4().to_string();
+
+    with_db(|conn| {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        tx.execute("INSERT INTO pipelines (id, name) VALUES (?, ?)", rusqlite::params![id, name])
+            .map_err(|e| format!("Failed to create pipeline: {}", e))?;
+
+        for (position, stage) in stages.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO pipeline_stages (pipeline_id, name, position, auto_role) VALUES (?, ?, ?, ?)",
+                rusqlite::params![id, stage.trim(), position as i32, auto_role_for(stage)],
+            )
+            .map_err(|e| format!("Failed to create stage: {}", e))?;
+        }
+
+        tx.commit().map_err(|e| format!("Failed to commit pipeline: {}", e))?;
+        Ok(())
+    })?;
+
+    load_pipeline(&id)?.ok_or_else(|| "Pipeline not found after create".to_string())
+}
+
+/// Create the default Lead → Closed pipeline if no pipeline exists yet
+pub fn ensure_default_pipeline() -> Result<(), String> {
+    let count: i64 = with_db(|conn| {
+        conn.query_row("SELECT COUNT(*) FROM pipelines", [], |row| row.get(0))
+            .map_err(|e| format!("Failed to count pipelines: {}", e))
+    })?;
+
+    if count == 0 {
+        let stages: Vec<String> = DEFAULT_STAGES.iter().map(|s| s.to_string()).collect();
+        create_pipeline(DEFAULT_PIPELINE_NAME, &stages)?;
+    }
+    Ok(())
+}
+
+fn load_stages(conn: &rusqlite::Connection, pipeline_id: &str) -> Result<Vec<PipelineStage>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, name, position, auto_role FROM pipeline_stages WHERE pipeline_id = ? ORDER BY position")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let stages = stmt
+        .query_map([pipeline_id], |row| {
+            Ok(PipelineStage {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                position: row.get(2)?,
+                auto_role: row.get(3)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query stages: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(stages)
+}
+
+pub fn load_pipeline(id: &str) -> Result<Option<Pipeline>, String> {
+    with_db(|conn| {
+        let result = conn.query_row(
+            "SELECT id, name, created_at FROM pipelines WHERE id = ?",
+            [id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?)),
+        );
+
+        match result {
+            Ok((id, name, created_at)) => Ok(Some(Pipeline {
+                stages: load_stages(conn, &id)?,
+                id,
+                name,
+                created_at,
+            })),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load pipeline: {}", e)),
+        }
+    })
+}
+
+pub fn list_pipelines() -> Result<Vec<Pipeline>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT id, name, created_at FROM pipelines ORDER BY created_at, name")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let rows: Vec<(String, String, i64)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| format!("Failed to query pipelines: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        rows.into_iter()
+            .map(|(id, name, created_at)| {
+                Ok(Pipeline {
+                    stages: load_stages(conn, &id)?,
+                    id,
+                    name,
+                    created_at,
+                })
+            })
+            .collect()
+    })
+}
+
+pub fn delete_pipeline(id: &str) -> Result<(), String> {
+    with_db(|conn| {
+        // Stages and contacts are deleted via CASCADE
+        conn.execute("DELETE FROM pipelines WHERE id = ?", [id])
+            .map_err(|e| format!("Failed to delete pipeline: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Put a contact into a stage (adding them to the pipeline if needed)
+pub fn set_contact_stage(pipeline_id: &str, user_id: i64, stage_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        let updated = conn
+            .execute(
+                r#"
+                INSERT INTO pipeline_contacts (pipeline_id, user_id, stage_id, updated_at)
+                SELECT ?1, ?2, id, strftime('%s', 'now') FROM pipeline_stages
+                WHERE id = ?3 AND pipeline_id = ?1
+                ON CONFLICT(pipeline_id, user_id) DO UPDATE SET
+                    stage_id = excluded.stage_id,
+                    updated_at = excluded.updated_at
+                "#,
+                rusqlite::params![pipeline_id, user_id, stage_id],
+            )
+            .map_err(|e| format!("Failed to move contact: {}", e))?;
+
+        if updated == 0 {
+            return Err(format!("Stage {} not found in pipeline {}", stage_id, pipeline_id));
+        }
+        Ok(())
+    })
+}
+
+pub fn remove_contact(pipeline_id: &str, user_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "DELETE FROM pipeline_contacts WHERE pipeline_id = ? AND user_id = ?",
+            rusqlite::params![pipeline_id, user_id],
+        )
+        .map_err(|e| format!("Failed to remove contact from pipeline: {}", e))?;
+        Ok(())
+    })
+}
+
+pub fn list_entries(pipeline_id: &str) -> Result<Vec<PipelineEntry>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT user_id, stage_id, updated_at FROM pipeline_contacts WHERE pipeline_id = ? ORDER BY updated_at DESC")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let entries = stmt
+            .query_map([pipeline_id], |row| {
+                Ok(PipelineEntry {
+                    user_id: row.get(0)?,
+                    stage_id: row.get(1)?,
+                    updated_at: row.get(2)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query pipeline contacts: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    })
+}
+
+/// Move a contact forward to the stage with `role` in every pipeline where they
+/// are currently in an earlier stage. Never moves anyone backwards.
+/// Returns the number of pipelines updated.
+pub fn advance_to_role(user_id: i64, role: &str) -> Result<usize, String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            UPDATE pipeline_contacts SET
+                stage_id = (
+                    SELECT target.id FROM pipeline_stages target
+                    WHERE target.pipeline_id = pipeline_contacts.pipeline_id AND target.auto_role = ?2
+                ),
+                updated_at = strftime('%s', 'now')
+            WHERE user_id = ?1 AND EXISTS (
+                SELECT 1 FROM pipeline_stages target, pipeline_stages current
+                WHERE target.pipeline_id = pipeline_contacts.pipeline_id
+                  AND target.auto_role = ?2
+                  AND current.id = pipeline_contacts.stage_id
+                  AND current.position < target.position
+            )
+            "#,
+            rusqlite::params![user_id, role],
+        )
+        .map_err(|e| format!("Failed to advance pipeline stage: {}", e))
+    })
+}
+
+/// Contacts sitting in a stage with the given role, as (pipeline_id, user_id, entered_at)
+pub fn entries_with_role(role: &str) -> Result<Vec<(String, i64, i64)>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT c.pipeline_id, c.user_id, c.updated_at FROM pipeline_contacts c
+                JOIN pipeline_stages s ON s.id = c.stage_id
+                WHERE s.auto_role = ?
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let entries = stmt
+            .query_map([role], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| format!("Failed to query pipeline contacts: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage_id(pipeline: &Pipeline, name: &str) -> i64 {
+        pipeline.stages.iter().find(|s| s.name == name).unwrap().id
+    }
+
+    #[test]
+    fn test_create_and_delete_pipeline() {
+        crate::db::init_test_db();
+        let stages = vec!["Lead".to_string(), " Contacted ".to_string(), "Replied".to_string()];
+        let pipeline = create_pipeline("Fundraising", &stages).unwrap();
+
+        let names: Vec<&str> = pipeline.stages.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Lead", "Contacted", "Replied"]);
+        let roles: Vec<Option<&str>> = pipeline.stages.iter().map(|s| s.auto_role.as_deref()).collect();
+        assert_eq!(roles, vec![None, Some(ROLE_CONTACTED), Some(ROLE_REPLIED)]);
+        assert!(list_pipelines().unwrap().iter().any(|p| p.id == pipeline.id));
+
+        // Deleting the pipeline takes its stages and contacts with it
+        set_contact_stage(&pipeline.id, 7611, stage_id(&pipeline, "Lead")).unwrap();
+        delete_pipeline(&pipeline.id).unwrap();
+        assert!(load_pipeline(&pipeline.id).unwrap().is_none());
+        assert!(list_entries(&pipeline.id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_contact_stages_only_advance() {
+        crate::db::init_test_db();
+        let stages: Vec<String> = DEFAULT_STAGES.iter().map(|s| s.to_string()).collect();
+        let pipeline = create_pipeline("Sales", &stages).unwrap();
+        let other = create_pipeline("Hiring", &stages).unwrap();
+
+        set_contact_stage(&pipeline.id, 7612, stage_id(&pipeline, "Lead")).unwrap();
+        // A stage of another pipeline is refused
+        assert!(set_contact_stage(&pipeline.id, 7612, stage_id(&other, "Meeting")).is_err());
+
+        assert_eq!(advance_to_role(7612, ROLE_REPLIED).unwrap(), 1);
+        assert_eq!(list_entries(&pipeline.id).unwrap()[0].stage_id, stage_id(&pipeline, "Replied"));
+        assert!(entries_with_role(ROLE_REPLIED).unwrap().iter().any(|(id, user, _)| *id == pipeline.id && *user == 7612));
+
+        // Never backwards
+        assert_eq!(advance_to_role(7612, ROLE_CONTACTED).unwrap(), 0);
+        assert_eq!(list_entries(&pipeline.id).unwrap()[0].stage_id, stage_id(&pipeline, "Replied"));
+
+        remove_contact(&pipeline.id, 7612).unwrap();
+        assert!(list_entries(&pipeline.id).unwrap().is_empty());
+        delete_pipeline(&pipeline.id).unwrap();
+        delete_pipeline(&other.id).unwrap();
+    }
+}
@@ -0,0 +1,89 @@
+use super::with_db;
+use serde::Serialize;
+
+/// "Remind me about this chat": resurfaces in the briefing once `remind_at` has passed
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatReminder {
+    pub id: i64,
+    pub chat_id: i64,
+    pub chat_title: String,
+    pub chat_type: String,
+    /// Unix seconds
+    pub remind_at: i64,
+    pub note: Option<String>,
+    pub created_at: i64,
+}
+
+fn row_to_reminder(row: &rusqlite::Row) -> rusqlite::Result<ChatReminder> {
+    Ok(ChatReminder {
+        id: row.get(0)?,
+        chat_id: row.get(1)?,
+        chat_title: row.get(2)?,
+        chat_type: row.get(3)?,
+        remind_at: row.get(4)?,
+        note: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+pub fn add_reminder(
+    chat_id: i64,
+    chat_title: &str,
+    chat_type: &str,
+    remind_at: i64,
+    note: Option<&str>,
+) -> Result<ChatReminder, String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO chat_reminders (chat_id, chat_title, chat_type, remind_at, note) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![chat_id, chat_title, chat_type, remind_at, note],
+        )
+        .map_err(|e| format!("Failed to save reminder: {}", e))?;
+        conn.query_row(
+            "SELECT id, chat_id, chat_title, chat_type, remind_at, note, created_at FROM chat_reminders WHERE id = ?",
+            [conn.last_insert_rowid()],
+            row_to_reminder,
+        )
+        .map_err(|e| format!("Failed to load reminder: {}", e))
+    })
+}
+
+/// Reminders not yet marked done, soonest first. With `due_by`, only those due by then.
+pub fn load_pending(due_by: Option<i64>) -> Result<Vec<ChatReminder>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT id, chat_id, chat_title, chat_type, remind_at, note, created_at
+                FROM chat_reminders
+                WHERE done_at IS NULL AND (?1 IS NULL OR remind_at <= ?1)
+                ORDER BY remind_at ASC
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let reminders = stmt
+            .query_map([due_by], row_to_reminder)
+            .map_err(|e| format!("Failed to query reminders: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(reminders)
+    })
+}
+
+pub fn complete_reminder(id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        let updated = conn
+            .execute(
+                "UPDATE chat_reminders SET done_at = strftime('%s', 'now') WHERE id = ? AND done_at IS NULL",
+                [id],
+            )
+            .map_err(|e| format!("Failed to complete reminder: {}", e))?;
+        if updated == 0 {
+            return Err(format!("Reminder {} not found", id));
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,311 @@
+use super::settings::RetentionConfig;
+use super::with_db;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+/// Rows removed by a retention pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeStats {
+    pub briefings: usize,
+    pub outreach_queues: usize,
+    pub activity_events: usize,
+    pub triage_entries: usize,
+    pub llm_cache_entries: usize,
+    pub archived_messages: usize,
+    /// Mentions, calls, audit excerpts and other rows derived from archived messages
+    pub derived_rows: usize,
+}
+
+/// Tables holding data derived from messages, with the rows that expire along
+/// with the archive. Unresolved drafts, sends and reminders are never purged.
+const DERIVED_TABLES: &[(&str, &str)] = &[
+    ("mentions", "message_date < ?1"),
+    ("calls", "call_date < ?1"),
+    ("chat_decisions", "COALESCE(message_date, extracted_at) < ?1"),
+    ("injection_audit", "detected_at < ?1"),
+    ("bot_rule_hits", "matched_at < ?1"),
+    ("away_replies", "replied_at < ?1"),
+    ("chat_reminders", "done_at < ?1"),
+    ("pending_drafts", "status != 'pending' AND resolved_at < ?1"),
+    ("outbox", "status != 'pending' AND updated_at < ?1"),
+    ("scheduled_posts", "status IN ('sent', 'cancelled') AND COALESCE(sent_at, send_at) < ?1"),
+    // Targets are deleted via CASCADE
+    ("crosspost_jobs", "status != 'running' AND completed_at < ?1"),
+    // Posts and responses are deleted via CASCADE
+    ("rsvp_events", "created_at < ?1"),
+    ("channel_snapshots", "taken_at < ?1"),
+    ("channel_post_views", "posted_at < ?1"),
+];
+
+fn cutoff(days: u32) -> i64 {
+    chrono::Utc::now().timestamp() - days as i64 * 86400
+}
+
+/// Delete local data older than the configured retention windows
+pub fn purge_expired(config: &RetentionConfig) -> Result<PurgeStats, String> {
+    with_db(|conn| purge(conn, config))
+}
+
+fn purge(conn: &Connection, config: &RetentionConfig) -> Result<PurgeStats, String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let conn = &tx;
+    let mut stats = PurgeStats::default();
+
+    if let Some(days) = config.briefing_history_days {
+        stats.briefings = conn
+            .execute("DELETE FROM briefing_history WHERE created_at < ?", [cutoff(days)])
+            .map_err(|e| format!("Failed to purge briefing history: {}", e))?;
+    }
+
+    if let Some(days) = config.outreach_history_days {
+        // Recipients are deleted via CASCADE
+        stats.outreach_queues = conn
+            .execute(
+                "DELETE FROM outreach_queue WHERE status IN ('completed', 'cancelled') AND completed_at < ?1",
+                [cutoff(days)],
+            )
+            .map_err(|e| format!("Failed to purge outreach queues: {}", e))?;
+    }
+
+    if let Some(days) = config.activity_log_days {
+        stats.activity_events = conn
+            .execute("DELETE FROM contact_events WHERE created_at < ?", [cutoff(days)])
+            .map_err(|e| format!("Failed to purge contact events: {}", e))?;
+    }
+
+    if let Some(days) = config.triage_days {
+        stats.triage_entries = conn
+            .execute(
+                "DELETE FROM triage_queue WHERE status != 'pending' AND updated_at < ?",
+                [cutoff(days)],
+            )
+            .map_err(|e| format!("Failed to purge triage entries: {}", e))?;
+    }
+
+    if let Some(days) = config.llm_cache_days {
+        stats.llm_cache_entries = conn
+            .execute("DELETE FROM llm_response_cache WHERE created_at < ?", [cutoff(days)])
+            .map_err(|e| format!("Failed to purge LLM cache: {}", e))?;
+    }
+
+    if let Some(days) = config.archive_days {
+        stats.archived_messages = conn
+            .execute("DELETE FROM messages WHERE date < ?", [cutoff(days)])
+            .map_err(|e| format!("Failed to purge archived messages: {}", e))?;
+        super::messages::trim_sync_state(conn)?;
+
+        for (table, expired) in DERIVED_TABLES {
+            stats.derived_rows += conn
+                .execute(&format!("DELETE FROM {} WHERE {}", table, expired), [cutoff(days)])
+                .map_err(|e| format!("Failed to purge {}: {}", table, e))?;
+        }
+    }
+
+    tx.commit().map_err(|e| format!("Failed to commit purge: {}", e))?;
+    Ok(stats)
+}
+
+/// Delete everything copied from or derived from the signed-in account's chats,
+/// so none of it is served after logging in to another account
+pub fn wipe_account_data() -> Result<(), String> {
+    with_db(wipe)
+}
+
+/// Tables holding the account's messages, data derived from them, and settings
+/// keyed by its chats. The contact CRM (tags, notes, pipelines, lists) is the
+/// user's own and is kept.
+const ACCOUNT_TABLES: &[&str] = &[
+    "message_edits",
+    "messages",
+    "message_sync_state",
+    "archive_backfill",
+    "mentions",
+    "calls",
+    "chat_decisions",
+    "injection_audit",
+    "bot_rule_hits",
+    "away_replies",
+    "chat_reminders",
+    "pending_drafts",
+    "outbox",
+    "scheduled_posts",
+    "crosspost_targets",
+    "crosspost_jobs",
+    "rsvp_responses",
+    "rsvp_posts",
+    "rsvp_events",
+    "channel_snapshots",
+    "channel_post_views",
+    "briefing_history",
+    "llm_response_cache",
+    "ai_call_log",
+    "triage_queue",
+    "last_contact",
+    "contact_profiles",
+    "briefing_corrections",
+    "briefing_pins",
+    "chat_aliases",
+    "chat_notes",
+    "chat_stars",
+    "notification_rules",
+    "welcome_kits",
+];
+
+fn wipe(conn: &Connection) -> Result<(), String> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("Failed to start transaction: {}", e))?;
+    for table in ACCOUNT_TABLES {
+        tx.execute(&format!("DELETE FROM {}", table), [])
+            .map_err(|e| format!("Failed to wipe {}: {}", table, e))?;
+    }
+    tx.commit().map_err(|e| format!("Failed to commit wipe: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_purge_expired_keeps_recent_and_unset() {
+        let conn = crate::db::open_test_db();
+        let (old, recent) = (cutoff(100), chrono::Utc::now().timestamp());
+        conn.execute_batch(&format!(
+            "INSERT INTO briefing_history (id, generated_at, data, created_at) VALUES ('old', '', '{{}}', {old}), ('new', '', '{{}}', {recent});
+             INSERT INTO outreach_queue (id, template, status, completed_at)
+             VALUES ('done', 'Hi', 'completed', {old}), ('running', 'Hi', 'running', NULL);
+             INSERT INTO outreach_recipients (queue_id, user_id, status) VALUES ('done', 7631, 'sent');
+             INSERT INTO contact_events (user_id, kind, created_at) VALUES (7631, 'tag_added', {old});
+             INSERT INTO triage_queue (user_id, name, first_seen_at, last_message_at, status, updated_at)
+             VALUES (7631, 'Ann', 0, 0, 'dismissed', {old}), (7632, 'Bob', 0, 0, 'pending', {old});
+             INSERT INTO llm_response_cache (prompt_hash, model, response, created_at) VALUES ('h', 'm', 'r', {old});"
+        ))
+        .unwrap();
+
+        // The activity log is kept forever unless a window is set
+        let config = RetentionConfig { archive_days: None, ..RetentionConfig::default() };
+        let stats = purge(&conn, &config).unwrap();
+        assert_eq!(
+            (stats.briefings, stats.activity_events, stats.triage_entries, stats.llm_cache_entries),
+            (1, 0, 1, 1)
+        );
+        assert_eq!(count(&conn, "briefing_history"), 1);
+        assert_eq!(count(&conn, "contact_events"), 1);
+
+        // Finished queues go with their recipients; running ones stay, as do pending triage entries
+        let config = RetentionConfig { outreach_history_days: Some(30), activity_log_days: Some(30), ..config };
+        let stats = purge(&conn, &config).unwrap();
+        assert_eq!((stats.outreach_queues, stats.activity_events), (1, 1));
+        assert_eq!(count(&conn, "outreach_queue"), 1);
+        assert_eq!(count(&conn, "outreach_recipients"), 0);
+        assert_eq!(count(&conn, "triage_queue"), 1);
+    }
+
+    #[test]
+    fn test_purge_expired_archived_messages() {
+        // Own database: the purge is global and would race other tests' archives
+        let conn = crate::db::open_test_db();
+        let (old, recent) = (cutoff(100), chrono::Utc::now().timestamp());
+        conn.execute_batch(&format!(
+            "INSERT INTO messages (chat_id, message_id, sender_id, sender_name, content, text, date, is_outgoing, archived_at)
+             VALUES (7081, 1, 1, 'Ann', '{{}}', 'Old', {old}, 0, 0),
+                    (7081, 2, 1, 'Ann', '{{}}', 'Recent', {recent}, 0, 0),
+                    (7082, 1, 1, 'Ann', '{{}}', 'Old too', {old}, 0, 0);
+             INSERT INTO message_sync_state (chat_id, newest_message_id, oldest_message_id, synced_at)
+             VALUES (7081, 2, 1, 0), (7082, 1, 1, 0);"
+        ))
+        .unwrap();
+
+        let config = RetentionConfig { archive_days: Some(90), ..RetentionConfig::default() };
+        assert_eq!(purge(&conn, &config).unwrap().archived_messages, 2);
+
+        let ranges: Vec<(i64, i64)> = conn
+            .prepare("SELECT chat_id, oldest_message_id FROM message_sync_state")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(ranges, vec![(7081, 2)]);
+        let found: i64 = conn
+            .query_row("SELECT COUNT(*) FROM messages_fts WHERE messages_fts MATCH 'old'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(found, 0);
+    }
+
+    #[test]
+    fn test_purge_expired_derived_rows() {
+        let conn = crate::db::open_test_db();
+        let (old, recent) = (cutoff(100), chrono::Utc::now().timestamp());
+        conn.execute_batch(&format!(
+            "INSERT INTO mentions (chat_id, chat_title, message_id, kind, sender_id, sender_name, text, message_date)
+             VALUES (7651, 'Team', 1, 'mention', 1, 'Ann', '@me', {old}), (7651, 'Team', 2, 'reply', 1, 'Ann', 'ok', {recent});
+             INSERT INTO calls (chat_id, chat_title, message_id, caller_id, caller_name, is_outgoing, video, missed, call_date)
+             VALUES (7651, 'Ann', 3, 1, 'Ann', 0, 0, 1, {old});
+             INSERT INTO chat_reminders (chat_id, chat_title, chat_type, remind_at, done_at)
+             VALUES (7651, 'Team', 'group', {old}, {old}), (7652, 'Bob', 'private', {old}, NULL);
+             INSERT INTO pending_drafts (chat_id, text, source, status, created_at, resolved_at)
+             VALUES (7651, 'Sent', 'ai', 'sent', {old}, {old}), (7652, 'Waiting', 'ai', 'pending', {old}, NULL);
+             INSERT INTO crosspost_jobs (id, text, status, created_at, completed_at)
+             VALUES ('done', 'Hi', 'completed', {old}, {old}), ('live', 'Hi', 'running', {old}, NULL);
+             INSERT INTO crosspost_targets (job_id, chat_id) VALUES ('done', 7651), ('live', 7652);
+             INSERT INTO rsvp_events (id, title, text, created_at) VALUES (7651, 'Meetup', 'Coming?', {old});
+             INSERT INTO rsvp_responses (event_id, user_id, name, chat_id, status, source, response, responded_at)
+             VALUES (7651, 1, 'Ann', 7651, 'yes', 'reply', 'yes', {old});"
+        ))
+        .unwrap();
+
+        let stats = purge(&conn, &RetentionConfig::default()).unwrap();
+        assert_eq!(stats.derived_rows, 6);
+        assert_eq!(count(&conn, "mentions"), 1);
+        assert_eq!(count(&conn, "calls"), 0);
+
+        // Open reminders, drafts and jobs stay however old they are; children go with their parents
+        assert_eq!(count(&conn, "chat_reminders"), 1);
+        assert_eq!(count(&conn, "pending_drafts"), 1);
+        assert_eq!(count(&conn, "crosspost_targets"), 1);
+        assert_eq!(count(&conn, "rsvp_responses"), 0);
+
+        // Nothing derived expires while the archive is kept forever
+        conn.execute("UPDATE mentions SET message_date = ?", [old]).unwrap();
+        let config = RetentionConfig { archive_days: None, ..RetentionConfig::default() };
+        assert_eq!(purge(&conn, &config).unwrap().derived_rows, 0);
+    }
+
+    #[test]
+    fn test_wipe_account_data() {
+        let conn = crate::db::open_test_db();
+        conn.execute_batch(
+            "INSERT INTO messages (chat_id, message_id, sender_id, sender_name, content, text, date, is_outgoing, archived_at)
+             VALUES (7083, 1, 1, 'Ann', '{}', 'Hello', 0, 0, 0);
+             INSERT INTO message_sync_state (chat_id, newest_message_id, oldest_message_id, synced_at)
+             VALUES (7083, 1, 1, 0);
+             INSERT INTO mentions (chat_id, chat_title, message_id, kind, sender_id, sender_name, text, message_date)
+             VALUES (7083, 'Team', 1, 'mention', 1, 'Ann', '@me', 0);
+             INSERT INTO rsvp_events (id, title, text) VALUES (7083, 'Meetup', 'Coming?');
+             INSERT INTO rsvp_posts (event_id, chat_id, chat_title, message_id) VALUES (7083, 7083, 'Team', 2);
+             INSERT INTO briefing_history (id, generated_at, data) VALUES ('b', '', '{}');
+             INSERT INTO llm_response_cache (prompt_hash, model, response) VALUES ('h', 'm', 'r');
+             INSERT INTO ai_call_log (provider, model, prompt, latency_ms) VALUES ('openai', 'm', 'Ann: Hello', 1);
+             INSERT INTO triage_queue (user_id, name, first_seen_at, last_message_at) VALUES (7083, 'Ann', 0, 0);
+             INSERT INTO briefing_corrections (chat_id, priority) VALUES (7083, 'low');
+             INSERT INTO briefing_pins (chat_id, priority) VALUES (7083, 'high');
+             INSERT INTO chat_notes (chat_id, notes) VALUES (7083, 'Board');
+             INSERT INTO contact_tags (user_id, tag) VALUES (7083, 'VIP');",
+        )
+        .unwrap();
+
+        wipe(&conn).unwrap();
+        for table in ACCOUNT_TABLES {
+            assert_eq!(count(&conn, table), 0, "{}", table);
+        }
+        assert_eq!(count(&conn, "contact_tags"), 1);
+    }
+}
@@ -0,0 +1,239 @@
+//! Event announcements posted to groups and the RSVPs collected from replies and
+//! reactions to them
+
+use super::with_db;
+use serde::Serialize;
+
+pub const STATUS_GOING: &str = "going";
+pub const STATUS_NOT_GOING: &str = "not_going";
+pub const STATUS_MAYBE: &str = "maybe";
+
+pub const SOURCE_REPLY: &str = "reply";
+pub const SOURCE_REACTION: &str = "reaction";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RsvpPost {
+    pub chat_id: i64,
+    pub chat_title: String,
+    pub message_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RsvpEvent {
+    pub id: i64,
+    pub title: String,
+    pub text: String,
+    pub created_at: i64,
+    /// Where the announcement was posted
+    pub posts: Vec<RsvpPost>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RsvpResponse {
+    pub event_id: i64,
+    pub user_id: i64,
+    pub name: String,
+    /// Chat the answer was given in
+    pub chat_id: i64,
+    /// "going", "not_going" or "maybe"
+    pub status: String,
+    /// "reply" or "reaction"
+    pub source: String,
+    /// The reply text or reaction emoji
+    pub response: String,
+    pub responded_at: i64,
+}
+
+pub fn create_event(title: &str, text: &str) -> Result<i64, String> {
+    with_db(|conn| {
+        conn.execute("INSERT INTO rsvp_events (title, text) VALUES (?, ?)", [title, text])
+            .map_err(|e| format!("Failed to create event: {}", e))?;
+        Ok(conn.last_insert_rowid())
+    })
+}
+
+pub fn add_post(event_id: i64, chat_id: i64, chat_title: &str, message_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO rsvp_posts (event_id, chat_id, chat_title, message_id) VALUES (?, ?, ?, ?)",
+            rusqlite::params![event_id, chat_id, chat_title, message_id],
+        )
+        .map_err(|e| format!("Failed to save event post: {}", e))?;
+        Ok(())
+    })
+}
+
+/// The event announced by a message, if it is one
+pub fn event_for_message(chat_id: i64, message_id: i64) -> Result<Option<i64>, String> {
+    with_db(|conn| {
+        match conn.query_row(
+            "SELECT event_id FROM rsvp_posts WHERE chat_id = ? AND message_id = ?",
+            [chat_id, message_id],
+            |row| row.get(0),
+        ) {
+            Ok(id) => Ok(Some(id)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to look up event post: {}", e)),
+        }
+    })
+}
+
+fn load_posts(conn: &rusqlite::Connection, event_id: i64) -> Result<Vec<RsvpPost>, String> {
+    let mut stmt = conn
+        .prepare("SELECT chat_id, chat_title, message_id FROM rsvp_posts WHERE event_id = ? ORDER BY rowid")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let posts = stmt
+        .query_map([event_id], |row| {
+            Ok(RsvpPost {
+                chat_id: row.get(0)?,
+                chat_title: row.get(1)?,
+                message_id: row.get(2)?,
+            })
+        })
+        .map_err(|e| format!("Failed to query event posts: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(posts)
+}
+
+/// All events, newest first
+pub fn load_events() -> Result<Vec<RsvpEvent>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT id, title, text, created_at FROM rsvp_events ORDER BY created_at DESC, id DESC")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let events: Vec<(i64, String, String, i64)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+            .map_err(|e| format!("Failed to query events: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        events
+            .into_iter()
+            .map(|(id, title, text, created_at)| {
+                Ok(RsvpEvent {
+                    id,
+                    title,
+                    text,
+                    created_at,
+                    posts: load_posts(conn, id)?,
+                })
+            })
+            .collect()
+    })
+}
+
+pub fn load_event(event_id: i64) -> Result<Option<RsvpEvent>, String> {
+    with_db(|conn| {
+        let result = conn.query_row(
+            "SELECT title, text, created_at FROM rsvp_events WHERE id = ?",
+            [event_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?)),
+        );
+
+        match result {
+            Ok((title, text, created_at)) => Ok(Some(RsvpEvent {
+                id: event_id,
+                title,
+                text,
+                created_at,
+                posts: load_posts(conn, event_id)?,
+            })),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load event: {}", e)),
+        }
+    })
+}
+
+/// Save an answer unless the person already gave a later one
+pub fn save_response(response: &RsvpResponse) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO rsvp_responses (event_id, user_id, name, chat_id, status, source, response, responded_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(event_id, user_id) DO UPDATE SET
+                name = excluded.name,
+                chat_id = excluded.chat_id,
+                status = excluded.status,
+                source = excluded.source,
+                response = excluded.response,
+                responded_at = excluded.responded_at
+            WHERE excluded.responded_at >= rsvp_responses.responded_at
+            "#,
+            rusqlite::params![
+                response.event_id,
+                response.user_id,
+                response.name,
+                response.chat_id,
+                response.status,
+                response.source,
+                response.response,
+                response.responded_at
+            ],
+        )
+        .map_err(|e| format!("Failed to save RSVP: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Forget the reaction-based answers from one chat, before re-reading its reactions
+pub fn clear_reactions(event_id: i64, chat_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "DELETE FROM rsvp_responses WHERE event_id = ? AND chat_id = ? AND source = ?",
+            rusqlite::params![event_id, chat_id, SOURCE_REACTION],
+        )
+        .map_err(|e| format!("Failed to clear reactions: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Answers for an event, grouped by status and then by name
+pub fn load_responses(event_id: i64) -> Result<Vec<RsvpResponse>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(
+                r#"
+                SELECT event_id, user_id, name, chat_id, status, source, response, responded_at
+                FROM rsvp_responses
+                WHERE event_id = ?
+                ORDER BY status, name COLLATE NOCASE
+                "#,
+            )
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let responses = stmt
+            .query_map([event_id], |row| {
+                Ok(RsvpResponse {
+                    event_id: row.get(0)?,
+                    user_id: row.get(1)?,
+                    name: row.get(2)?,
+                    chat_id: row.get(3)?,
+                    status: row.get(4)?,
+                    source: row.get(5)?,
+                    response: row.get(6)?,
+                    responded_at: row.get(7)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query RSVPs: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(responses)
+    })
+}
+
+pub fn delete_event(event_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM rsvp_events WHERE id = ?", [event_id])
+            .map_err(|e| format!("Failed to delete event: {}", e))?;
+        Ok(())
+    })
+}
@@ -0,0 +1,199 @@
+//! Content calendar: posts scheduled for channels and groups the user runs, with
+//! per-post status and retries
+
+use super::with_db;
+use serde::Serialize;
+
+pub const STATUS_SCHEDULED: &str = "scheduled";
+pub const STATUS_SENT: &str = "sent";
+pub const STATUS_FAILED: &str = "failed";
+pub const STATUS_CANCELLED: &str = "cancelled";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledPost {
+    pub id: i64,
+    pub chat_id: i64,
+    pub chat_title: String,
+    /// Message text, or the caption when there's a file
+    pub text: String,
+    /// Local file sent with the post (photo or document)
+    pub media_path: Option<String>,
+    /// Unix seconds the post is planned for
+    pub send_at: i64,
+    /// "scheduled", "sent", "failed" or "cancelled"
+    pub status: String,
+    pub attempts: i64,
+    /// When a failed attempt is retried, if later than `send_at`
+    pub retry_at: Option<i64>,
+    pub last_error: Option<String>,
+    /// Telegram message id once posted
+    pub message_id: Option<i64>,
+    pub sent_at: Option<i64>,
+    pub created_at: i64,
+}
+
+const COLUMNS: &str = "id, chat_id, chat_title, text, media_path, send_at, status, attempts, retry_at, \
+                       last_error, message_id, sent_at, created_at";
+
+fn row_to_post(row: &rusqlite::Row) -> rusqlite::Result<ScheduledPost> {
+    Ok(ScheduledPost {
+        id: row.get(0)?,
+        chat_id: row.get(1)?,
+        chat_title: row.get(2)?,
+        text: row.get(3)?,
+        media_path: row.get(4)?,
+        send_at: row.get(5)?,
+        status: row.get(6)?,
+        attempts: row.get(7)?,
+        retry_at: row.get(8)?,
+        last_error: row.get(9)?,
+        message_id: row.get(10)?,
+        sent_at: row.get(11)?,
+        created_at: row.get(12)?,
+    })
+}
+
+fn query_posts(sql: &str, params: impl rusqlite::Params) -> Result<Vec<ScheduledPost>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let posts = stmt
+            .query_map(params, row_to_post)
+            .map_err(|e| format!("Failed to query scheduled posts: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(posts)
+    })
+}
+
+pub fn create_post(
+    chat_id: i64,
+    chat_title: &str,
+    text: &str,
+    media_path: Option<&str>,
+    send_at: i64,
+) -> Result<i64, String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO scheduled_posts (chat_id, chat_title, text, media_path, send_at) VALUES (?, ?, ?, ?, ?)",
+            rusqlite::params![chat_id, chat_title, text, media_path, send_at],
+        )
+        .map_err(|e| format!("Failed to schedule post: {}", e))?;
+        Ok(conn.last_insert_rowid())
+    })
+}
+
+/// Change a post that hasn't gone out yet; a failed post is rescheduled from scratch
+pub fn update_post(id: i64, text: &str, media_path: Option<&str>, send_at: i64) -> Result<(), String> {
+    with_db(|conn| {
+        let updated = conn
+            .execute(
+                r#"
+                UPDATE scheduled_posts
+                SET text = ?, media_path = ?, send_at = ?, status = ?, attempts = 0, retry_at = NULL, last_error = NULL
+                WHERE id = ? AND status IN (?, ?)
+                "#,
+                rusqlite::params![text, media_path, send_at, STATUS_SCHEDULED, id, STATUS_SCHEDULED, STATUS_FAILED],
+            )
+            .map_err(|e| format!("Failed to update scheduled post: {}", e))?;
+        if updated == 0 {
+            return Err(format!("Post {} was already sent or cancelled", id));
+        }
+        Ok(())
+    })
+}
+
+pub fn load_post(id: i64) -> Result<Option<ScheduledPost>, String> {
+    let sql = format!("SELECT {} FROM scheduled_posts WHERE id = ?", COLUMNS);
+    Ok(query_posts(&sql, [id])?.into_iter().next())
+}
+
+/// Posts planned between `from` and `to` (unix seconds), in calendar order
+pub fn load_posts(from: i64, to: i64) -> Result<Vec<ScheduledPost>, String> {
+    let sql = format!(
+        "SELECT {} FROM scheduled_posts WHERE send_at BETWEEN ? AND ? ORDER BY send_at, id",
+        COLUMNS
+    );
+    query_posts(&sql, [from, to])
+}
+
+/// Scheduled posts whose time (or retry time) has come, oldest first
+pub fn load_due(now: i64) -> Result<Vec<ScheduledPost>, String> {
+    let sql = format!(
+        "SELECT {} FROM scheduled_posts WHERE status = ? AND COALESCE(retry_at, send_at) <= ? ORDER BY send_at, id",
+        COLUMNS
+    );
+    query_posts(&sql, rusqlite::params![STATUS_SCHEDULED, now])
+}
+
+pub fn record_sent(id: i64, message_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            UPDATE scheduled_posts
+            SET status = ?, attempts = attempts + 1, message_id = ?, sent_at = strftime('%s', 'now'),
+                retry_at = NULL, last_error = NULL
+            WHERE id = ?
+            "#,
+            rusqlite::params![STATUS_SENT, message_id, id],
+        )
+        .map_err(|e| format!("Failed to update scheduled post: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Record a failed attempt: retried at `retry_at`, or marked failed when None
+pub fn record_failure(id: i64, error: &str, retry_at: Option<i64>) -> Result<(), String> {
+    let status = if retry_at.is_some() { STATUS_SCHEDULED } else { STATUS_FAILED };
+    with_db(|conn| {
+        conn.execute(
+            "UPDATE scheduled_posts SET status = ?, attempts = attempts + 1, retry_at = ?, last_error = ? WHERE id = ?",
+            rusqlite::params![status, retry_at, error, id],
+        )
+        .map_err(|e| format!("Failed to update scheduled post: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Put a failed post back in line to go out right away
+pub fn retry_post(id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        let updated = conn
+            .execute(
+                "UPDATE scheduled_posts SET status = ?, attempts = 0, retry_at = strftime('%s', 'now') WHERE id = ? AND status = ?",
+                rusqlite::params![STATUS_SCHEDULED, id, STATUS_FAILED],
+            )
+            .map_err(|e| format!("Failed to retry scheduled post: {}", e))?;
+        if updated == 0 {
+            return Err(format!("Post {} hasn't failed", id));
+        }
+        Ok(())
+    })
+}
+
+pub fn cancel_post(id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        let updated = conn
+            .execute(
+                "UPDATE scheduled_posts SET status = ? WHERE id = ? AND status IN (?, ?)",
+                rusqlite::params![STATUS_CANCELLED, id, STATUS_SCHEDULED, STATUS_FAILED],
+            )
+            .map_err(|e| format!("Failed to cancel scheduled post: {}", e))?;
+        if updated == 0 {
+            return Err(format!("Post {} was already sent or cancelled", id));
+        }
+        Ok(())
+    })
+}
+
+pub fn delete_post(id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM scheduled_posts WHERE id = ?", [id])
+            .map_err(|e| format!("Failed to delete scheduled post: {}", e))?;
+        Ok(())
+    })
+}
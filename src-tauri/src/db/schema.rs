@@ -22,6 +22,25 @@ pub fn create_tables(conn: &Connection) -> Result<(), String> {
             updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
         );
 
+        -- Contact birthdays from Telegram profiles (day/month NULL = checked, none set)
+        CREATE TABLE IF NOT EXISTS contact_birthdays (
+            user_id INTEGER PRIMARY KEY,
+            day INTEGER,
+            month INTEGER,
+            year INTEGER,
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Contact profile details from users.getFullUser
+        CREATE TABLE IF NOT EXISTS contact_profiles (
+            user_id INTEGER PRIMARY KEY,
+            bio TEXT,
+            username TEXT,
+            is_premium INTEGER NOT NULL DEFAULT 0,
+            is_verified INTEGER NOT NULL DEFAULT 0,
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
         -- Scope profiles
         CREATE TABLE IF NOT EXISTS scope_profiles (
             id TEXT PRIMARY KEY,
@@ -50,12 +69,166 @@ pub fn create_tables(conn: &Connection) -> Result<(), String> {
             status TEXT NOT NULL DEFAULT 'pending',
             error TEXT,
             sent_at INTEGER,
+            first_name TEXT NOT NULL DEFAULT '',
+            last_name TEXT NOT NULL DEFAULT '',
+            username TEXT,
             FOREIGN KEY (queue_id) REFERENCES outreach_queue(id) ON DELETE CASCADE,
             UNIQUE(queue_id, user_id)
         );
 
         CREATE INDEX IF NOT EXISTS idx_outreach_recipients_queue_id ON outreach_recipients(queue_id);
 
+        -- Cross-posts: one text (or forwarded message) sent to several channels/groups
+        CREATE TABLE IF NOT EXISTS crosspost_jobs (
+            id TEXT PRIMARY KEY,
+            text TEXT,
+            source_chat_id INTEGER,
+            source_message_id INTEGER,
+            status TEXT NOT NULL DEFAULT 'running',
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            completed_at INTEGER
+        );
+
+        CREATE TABLE IF NOT EXISTS crosspost_targets (
+            job_id TEXT NOT NULL REFERENCES crosspost_jobs(id) ON DELETE CASCADE,
+            chat_id INTEGER NOT NULL,
+            chat_title TEXT NOT NULL DEFAULT '',
+            send_at INTEGER,
+            status TEXT NOT NULL DEFAULT 'pending',
+            error TEXT,
+            sent_at INTEGER,
+            UNIQUE(job_id, chat_id)
+        );
+
+        -- Content calendar: posts (optionally with a file) scheduled for channels the user runs
+        CREATE TABLE IF NOT EXISTS scheduled_posts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chat_id INTEGER NOT NULL,
+            chat_title TEXT NOT NULL DEFAULT '',
+            text TEXT NOT NULL DEFAULT '',
+            media_path TEXT,
+            send_at INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'scheduled',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            retry_at INTEGER,
+            last_error TEXT,
+            message_id INTEGER,
+            sent_at INTEGER,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_scheduled_posts_status ON scheduled_posts(status, send_at);
+
+        -- Periodic subscriber counts of the user's channels, for growth trends
+        CREATE TABLE IF NOT EXISTS channel_snapshots (
+            channel_id INTEGER NOT NULL,
+            taken_at INTEGER NOT NULL,
+            subscriber_count INTEGER NOT NULL,
+            avg_views INTEGER,
+            PRIMARY KEY (channel_id, taken_at)
+        );
+
+        -- Latest known view count of each channel post
+        CREATE TABLE IF NOT EXISTS channel_post_views (
+            channel_id INTEGER NOT NULL,
+            message_id INTEGER NOT NULL,
+            posted_at INTEGER NOT NULL,
+            views INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            PRIMARY KEY (channel_id, message_id)
+        );
+
+        -- Custom briefing categories, listed as sections in position order
+        CREATE TABLE IF NOT EXISTS briefing_categories (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            description TEXT NOT NULL DEFAULT '',
+            position INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Priority the user gave a chat when correcting a briefing, hinted to the model
+        CREATE TABLE IF NOT EXISTS briefing_corrections (
+            chat_id INTEGER PRIMARY KEY,
+            priority TEXT NOT NULL,
+            times INTEGER NOT NULL DEFAULT 1,
+            corrected_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Priority a chat is always given in briefings, without asking the model
+        CREATE TABLE IF NOT EXISTS briefing_pins (
+            chat_id INTEGER PRIMARY KEY,
+            priority TEXT NOT NULL,
+            pinned_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Archive of fetched messages (content is the MessageContent JSON, text its plain description)
+        CREATE TABLE IF NOT EXISTS messages (
+            chat_id INTEGER NOT NULL,
+            message_id INTEGER NOT NULL,
+            sender_id INTEGER NOT NULL,
+            sender_name TEXT NOT NULL,
+            content TEXT NOT NULL,
+            text TEXT NOT NULL,
+            date INTEGER NOT NULL,
+            is_outgoing INTEGER NOT NULL,
+            reply_to_message_id INTEGER,
+            mentions_me INTEGER NOT NULL DEFAULT 0,
+            archived_at INTEGER NOT NULL,
+            -- Set when Telegram reports the message deleted
+            deleted_at INTEGER,
+            PRIMARY KEY (chat_id, message_id)
+        );
+
+        -- Earlier versions of edited archived messages
+        CREATE TABLE IF NOT EXISTS message_edits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chat_id INTEGER NOT NULL,
+            message_id INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            edited_at INTEGER NOT NULL,
+            FOREIGN KEY (chat_id, message_id) REFERENCES messages(chat_id, message_id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_message_edits_message ON message_edits(chat_id, message_id);
+
+        -- Whatever changes a message's text (an edit update or a refetch) keeps the old text
+        CREATE TRIGGER IF NOT EXISTS messages_keep_edits AFTER UPDATE OF text ON messages
+        WHEN old.text != new.text BEGIN
+            INSERT INTO message_edits (chat_id, message_id, text, edited_at)
+            VALUES (old.chat_id, old.message_id, old.text, new.archived_at);
+        END;
+
+        CREATE INDEX IF NOT EXISTS idx_messages_date ON messages(date);
+
+        -- Contiguous range of each chat's history held in the archive
+        CREATE TABLE IF NOT EXISTS message_sync_state (
+            chat_id INTEGER PRIMARY KEY,
+            newest_message_id INTEGER NOT NULL,
+            oldest_message_id INTEGER NOT NULL,
+            synced_at INTEGER NOT NULL
+        );
+
+        -- Background archive backfill progress per chat
+        CREATE TABLE IF NOT EXISTS archive_backfill (
+            chat_id INTEGER PRIMARY KEY,
+            pages INTEGER NOT NULL DEFAULT 0,
+            complete INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            updated_at INTEGER NOT NULL
+        );
+
+        -- Contact activity log (tag/note edits, offboarding, pipeline moves) for timelines
+        CREATE TABLE IF NOT EXISTS contact_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            detail TEXT NOT NULL DEFAULT '',
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_contact_events_user_id ON contact_events(user_id);
+
         -- Last contact tracking
         CREATE TABLE IF NOT EXISTS last_contact (
             user_id INTEGER PRIMARY KEY,
@@ -63,15 +236,394 @@ pub fn create_tables(conn: &Connection) -> Result<(), String> {
             updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
         );
 
+        -- CRM pipelines
+        CREATE TABLE IF NOT EXISTS pipelines (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Pipeline stages (auto_role marks stages advanced automatically: 'contacted', 'replied')
+        CREATE TABLE IF NOT EXISTS pipeline_stages (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            pipeline_id TEXT NOT NULL,
+            name TEXT NOT NULL,
+            position INTEGER NOT NULL,
+            auto_role TEXT,
+            FOREIGN KEY (pipeline_id) REFERENCES pipelines(id) ON DELETE CASCADE,
+            UNIQUE(pipeline_id, name)
+        );
+
+        -- Contact placement in pipelines (one stage per pipeline)
+        CREATE TABLE IF NOT EXISTS pipeline_contacts (
+            pipeline_id TEXT NOT NULL,
+            user_id INTEGER NOT NULL,
+            stage_id INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            PRIMARY KEY (pipeline_id, user_id),
+            FOREIGN KEY (pipeline_id) REFERENCES pipelines(id) ON DELETE CASCADE,
+            FOREIGN KEY (stage_id) REFERENCES pipeline_stages(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_pipeline_contacts_user_id ON pipeline_contacts(user_id);
+
+        -- Curated contact lists (ordered, independent of Telegram folders)
+        CREATE TABLE IF NOT EXISTS contact_lists (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS contact_list_members (
+            list_id TEXT NOT NULL,
+            user_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            added_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            PRIMARY KEY (list_id, user_id),
+            FOREIGN KEY (list_id) REFERENCES contact_lists(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_contact_list_members_user_id ON contact_list_members(user_id);
+
+        -- DMs from non-contacts awaiting a decision (status: pending, replied, added, blocked, dismissed)
+        CREATE TABLE IF NOT EXISTS triage_queue (
+            user_id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            preview TEXT NOT NULL DEFAULT '',
+            first_seen_at INTEGER NOT NULL,
+            last_message_at INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Canned quick-reply responses (variables: JSON array of {placeholder} names)
+        CREATE TABLE IF NOT EXISTS canned_responses (
+            shortcut TEXT PRIMARY KEY,
+            text TEXT NOT NULL,
+            variables TEXT NOT NULL DEFAULT '[]',
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Messages flagged as likely prompt-injection attempts
+        CREATE TABLE IF NOT EXISTS injection_audit (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chat_id INTEGER NOT NULL,
+            chat_title TEXT NOT NULL,
+            sender_name TEXT NOT NULL,
+            excerpt TEXT NOT NULL,
+            signals TEXT NOT NULL,
+            score INTEGER NOT NULL,
+            detected_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            UNIQUE(chat_id, excerpt)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_injection_audit_detected_at ON injection_audit(detected_at);
+
+        -- LLM responses for deterministic (low-temperature) prompts, keyed by request hash
+        CREATE TABLE IF NOT EXISTS llm_response_cache (
+            prompt_hash TEXT PRIMARY KEY,
+            model TEXT NOT NULL,
+            response TEXT NOT NULL,
+            hits INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            last_hit_at INTEGER
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_llm_response_cache_created_at ON llm_response_cache(created_at);
+
+        -- Opt-in log of AI calls (prompts and responses after redaction) for prompt tuning
+        CREATE TABLE IF NOT EXISTS ai_call_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            prompt TEXT NOT NULL,
+            response TEXT,
+            error TEXT,
+            latency_ms INTEGER NOT NULL,
+            prompt_tokens INTEGER,
+            completion_tokens INTEGER,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Decisions and commitments extracted from chats, with the message they came from
+        CREATE TABLE IF NOT EXISTS chat_decisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chat_id INTEGER NOT NULL,
+            chat_title TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            text TEXT NOT NULL,
+            owner TEXT,
+            due TEXT,
+            message_id INTEGER,
+            sender_name TEXT,
+            message_date INTEGER,
+            extracted_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            UNIQUE(chat_id, kind, text)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_chat_decisions_chat ON chat_decisions(chat_id);
+
+        -- Messages that mention the user or reply to them, collected as history is fetched
+        CREATE TABLE IF NOT EXISTS mentions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chat_id INTEGER NOT NULL,
+            chat_title TEXT NOT NULL,
+            message_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            sender_id INTEGER NOT NULL,
+            sender_name TEXT NOT NULL,
+            text TEXT NOT NULL,
+            message_date INTEGER NOT NULL,
+            seen INTEGER NOT NULL DEFAULT 0,
+            recorded_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            UNIQUE(chat_id, message_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_mentions_seen ON mentions(seen, message_date);
+
+        -- One-to-one Telegram calls, collected from call service messages as history is fetched
+        CREATE TABLE IF NOT EXISTS calls (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chat_id INTEGER NOT NULL,
+            chat_title TEXT NOT NULL,
+            message_id INTEGER NOT NULL,
+            caller_id INTEGER NOT NULL,
+            caller_name TEXT NOT NULL,
+            is_outgoing INTEGER NOT NULL,
+            video INTEGER NOT NULL,
+            missed INTEGER NOT NULL,
+            duration INTEGER,
+            call_date INTEGER NOT NULL,
+            UNIQUE(chat_id, message_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_calls_date ON calls(call_date);
+
+        -- Per-chat in-app notification rules (independent of Telegram mutes)
+        CREATE TABLE IF NOT EXISTS notification_rules (
+            chat_id INTEGER PRIMARY KEY,
+            mode TEXT NOT NULL,
+            ignore_quiet_hours INTEGER NOT NULL DEFAULT 0,
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Local chat nicknames, shown instead of the Telegram title
+        CREATE TABLE IF NOT EXISTS chat_aliases (
+            chat_id INTEGER PRIMARY KEY,
+            alias TEXT NOT NULL,
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Free-form notes on chats (groups and channels too)
+        CREATE TABLE IF NOT EXISTS chat_notes (
+            chat_id INTEGER PRIMARY KEY,
+            notes TEXT NOT NULL DEFAULT '',
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- "Remind me about this chat"; done_at is set once handled
+        CREATE TABLE IF NOT EXISTS chat_reminders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chat_id INTEGER NOT NULL,
+            chat_title TEXT NOT NULL,
+            chat_type TEXT NOT NULL,
+            remind_at INTEGER NOT NULL,
+            note TEXT,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            done_at INTEGER
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_chat_reminders_remind_at ON chat_reminders(remind_at);
+
+        -- Per-group welcome sequence for new members; resources is a JSON array
+        CREATE TABLE IF NOT EXISTS welcome_kits (
+            chat_id INTEGER PRIMARY KEY,
+            rules_message TEXT NOT NULL DEFAULT '',
+            resources TEXT NOT NULL DEFAULT '[]',
+            intro_dm TEXT,
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Event announcements posted to groups to collect RSVPs
+        CREATE TABLE IF NOT EXISTS rsvp_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            text TEXT NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        CREATE TABLE IF NOT EXISTS rsvp_posts (
+            event_id INTEGER NOT NULL REFERENCES rsvp_events(id) ON DELETE CASCADE,
+            chat_id INTEGER NOT NULL,
+            chat_title TEXT NOT NULL,
+            message_id INTEGER NOT NULL,
+            PRIMARY KEY (chat_id, message_id)
+        );
+
+        -- One answer per person and event; a later reply or reaction replaces an earlier one
+        CREATE TABLE IF NOT EXISTS rsvp_responses (
+            event_id INTEGER NOT NULL REFERENCES rsvp_events(id) ON DELETE CASCADE,
+            user_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            chat_id INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            source TEXT NOT NULL,
+            response TEXT NOT NULL,
+            responded_at INTEGER NOT NULL,
+            PRIMARY KEY (event_id, user_id)
+        );
+
+        -- Messages that couldn't be sent while offline, retried on reconnect
+        CREATE TABLE IF NOT EXISTS outbox (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chat_id INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Automated replies waiting for the user's approval
+        CREATE TABLE IF NOT EXISTS pending_drafts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            chat_id INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            source TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'pending',
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+            resolved_at INTEGER
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_pending_drafts_status ON pending_drafts(status);
+
+        -- Who received the away-mode auto-reply
+        CREATE TABLE IF NOT EXISTS away_replies (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            user_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            text TEXT NOT NULL,
+            replied_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_away_replies_user ON away_replies(user_id, replied_at);
+
+        -- Automation for messages from bots (e.g. monitoring alerts)
+        CREATE TABLE IF NOT EXISTS bot_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            trigger TEXT NOT NULL DEFAULT 'bot',
+            bot_id INTEGER NOT NULL,
+            pattern TEXT NOT NULL,
+            action TEXT NOT NULL,
+            target TEXT,
+            daily_cap INTEGER,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_bot_rules_bot ON bot_rules(bot_id);
+
+        CREATE TABLE IF NOT EXISTS bot_rule_hits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            rule_id INTEGER NOT NULL REFERENCES bot_rules(id) ON DELETE CASCADE,
+            chat_id INTEGER NOT NULL,
+            message_id INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            result TEXT,
+            error TEXT,
+            matched_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        -- Chats starred in the copilot (independent of Telegram pins)
+        CREATE TABLE IF NOT EXISTS chat_stars (
+            chat_id INTEGER PRIMARY KEY,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
         -- App settings (key-value store for config like LLM provider settings)
         CREATE TABLE IF NOT EXISTS app_settings (
             key TEXT PRIMARY KEY,
             value TEXT NOT NULL,
             updated_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
         );
+
+        -- Generated briefings (for export and history)
+        CREATE TABLE IF NOT EXISTS briefing_history (
+            id TEXT PRIMARY KEY,
+            generated_at TEXT NOT NULL,
+            data TEXT NOT NULL,
+            created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_briefing_history_created_at ON briefing_history(created_at);
         "#,
     )
     .map_err(|e| format!("Failed to create tables: {}", e))?;
 
+    // Columns added after a table was first released
+    add_column(conn, "outreach_recipients", "first_name", "TEXT NOT NULL DEFAULT ''")?;
+    add_column(conn, "outreach_recipients", "last_name", "TEXT NOT NULL DEFAULT ''")?;
+    add_column(conn, "outreach_recipients", "username", "TEXT")?;
+
+    create_message_search_index(conn)?;
+
+    Ok(())
+}
+
+/// Full-text index over the message archive, kept in step by triggers. Messages
+/// archived before the index existed are indexed when it's created.
+fn create_message_search_index(conn: &Connection) -> Result<(), String> {
+    let exists = conn
+        .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'messages_fts'")
+        .and_then(|mut stmt| stmt.exists([]))
+        .map_err(|e| format!("Failed to check message search index: {}", e))?;
+    if exists {
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        r#"
+        CREATE VIRTUAL TABLE messages_fts USING fts5(
+            text,
+            sender_name,
+            content = 'messages',
+            content_rowid = 'rowid',
+            tokenize = 'unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS messages_fts_insert AFTER INSERT ON messages BEGIN
+            INSERT INTO messages_fts (rowid, text, sender_name) VALUES (new.rowid, new.text, new.sender_name);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS messages_fts_delete AFTER DELETE ON messages BEGIN
+            INSERT INTO messages_fts (messages_fts, rowid, text, sender_name)
+            VALUES ('delete', old.rowid, old.text, old.sender_name);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS messages_fts_update AFTER UPDATE ON messages BEGIN
+            INSERT INTO messages_fts (messages_fts, rowid, text, sender_name)
+            VALUES ('delete', old.rowid, old.text, old.sender_name);
+            INSERT INTO messages_fts (rowid, text, sender_name) VALUES (new.rowid, new.text, new.sender_name);
+        END;
+
+        INSERT INTO messages_fts (messages_fts) VALUES ('rebuild');
+        "#,
+    )
+    .map_err(|e| format!("Failed to create message search index: {}", e))
+}
+
+/// Add a column to a table created by an older version, if it isn't there yet
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<(), String> {
+    let exists = conn
+        .prepare(&format!("SELECT 1 FROM pragma_table_info('{}') WHERE name = ?", table))
+        .and_then(|mut stmt| stmt.exists([column]))
+        .map_err(|e| format!("Failed to read columns of {}: {}", table, e))?;
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .map_err(|e| format!("Failed to add column {}.{}: {}", table, column, e))?;
+    }
     Ok(())
 }
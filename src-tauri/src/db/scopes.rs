@@ -17,6 +17,9 @@ pub struct ScopeConfig {
     pub chat_types: Vec<String>,
     pub excluded_chat_ids: Vec<i64>,
     pub included_chat_ids: Vec<i64>,
+    /// Contact lists whose members' DMs are part of the scope
+    #[serde(default)]
+    pub contact_list_ids: Vec<String>,
 }
 
 pub fn save_scope(profile: &ScopeProfile) -> Result<(), String> {
@@ -85,6 +88,17 @@ pub fn load_scope(name: &str) -> Result<Option<ScopeProfile>, String> {
     })
 }
 
+/// Every saved scope, by name
+pub fn load_all_scopes() -> Result<Vec<ScopeProfile>, String> {
+    let mut scopes = Vec::new();
+    for name in list_scopes()? {
+        if let Some(scope) = load_scope(&name)? {
+            scopes.push(scope);
+        }
+    }
+    Ok(scopes)
+}
+
 pub fn list_scopes() -> Result<Vec<String>, String> {
     with_db(|conn| {
         let mut stmt = conn
@@ -1,5 +1,8 @@
 use crate::ai::client::LLMConfig;
+use crate::ai::sanitize::SanitizationConfig;
+use crate::db::integrity::IntegrityReport;
 use crate::db::with_db;
+use serde::{Deserialize, Serialize};
 
 const LLM_CONFIG_KEY: &str = "llm_config";
 
@@ -40,3 +43,449 @@ pub fn load_llm_config() -> Result<Option<LLMConfig>, String> {
         }
     })
 }
+
+/// Read a raw value from the app_settings key-value store
+pub fn load_setting(key: &str) -> Result<Option<String>, String> {
+    with_db(|conn| {
+        let result = conn.query_row(
+            "SELECT value FROM app_settings WHERE key = ?1",
+            rusqlite::params![key],
+            |row| row.get::<_, String>(0),
+        );
+
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load setting {}: {}", key, e)),
+        }
+    })
+}
+
+/// Write a raw value to the app_settings key-value store
+pub fn save_setting(key: &str, value: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "INSERT INTO app_settings (key, value, updated_at) VALUES (?1, ?2, strftime('%s', 'now'))
+             ON CONFLICT(key) DO UPDATE SET value = ?2, updated_at = strftime('%s', 'now')",
+            rusqlite::params![key, value],
+        )
+        .map_err(|e| format!("Failed to save setting {}: {}", key, e))?;
+        Ok(())
+    })
+}
+
+/// Remove a key from the app_settings key-value store
+pub fn delete_setting(key: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM app_settings WHERE key = ?1", rusqlite::params![key])
+            .map_err(|e| format!("Failed to delete setting {}: {}", key, e))?;
+        Ok(())
+    })
+}
+
+const SUMMARY_SYNC_FOLDER_KEY: &str = "summary_sync_folder";
+
+/// Folder that generated summaries are mirrored into as Markdown notes (None = disabled)
+pub fn load_summary_sync_folder() -> Result<Option<String>, String> {
+    load_setting(SUMMARY_SYNC_FOLDER_KEY)
+}
+
+pub fn save_summary_sync_folder(path: Option<&str>) -> Result<(), String> {
+    match path {
+        Some(path) => save_setting(SUMMARY_SYNC_FOLDER_KEY, path),
+        None => delete_setting(SUMMARY_SYNC_FOLDER_KEY),
+    }
+}
+
+const BRIEFING_DELIVERY_KEY: &str = "briefing_delivery";
+
+/// Where generated briefings are auto-posted after scheduled (CLI) generation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BriefingDeliveryConfig {
+    pub enabled: bool,
+    /// Target chat (e.g. a private channel); None = Saved Messages
+    pub chat_id: Option<i64>,
+}
+
+pub fn load_briefing_delivery() -> Result<BriefingDeliveryConfig, String> {
+    match load_setting(BRIEFING_DELIVERY_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse briefing delivery config: {}", e)),
+        None => Ok(BriefingDeliveryConfig::default()),
+    }
+}
+
+pub fn save_briefing_delivery(config: &BriefingDeliveryConfig) -> Result<(), String> {
+    let json = serde_json::to_string(config)
+        .map_err(|e| format!("Failed to serialize briefing delivery config: {}", e))?;
+    save_setting(BRIEFING_DELIVERY_KEY, &json)
+}
+
+const RETENTION_KEY: &str = "retention";
+
+/// How long local data is kept; None = keep forever
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionConfig {
+    #[serde(default)]
+    pub briefing_history_days: Option<u32>,
+    /// Finished (completed/cancelled) outreach queues
+    #[serde(default)]
+    pub outreach_history_days: Option<u32>,
+    /// Contact activity log (tag/note edits, offboarding, pipeline moves)
+    #[serde(default)]
+    pub activity_log_days: Option<u32>,
+    /// Resolved triage entries (replied, added, blocked, dismissed)
+    #[serde(default)]
+    pub triage_days: Option<u32>,
+    /// Cached LLM responses
+    #[serde(default = "default_llm_cache_days")]
+    pub llm_cache_days: Option<u32>,
+    /// Archived message history by message date, and the mentions, calls, etc. derived from it
+    #[serde(default = "default_archive_days")]
+    pub archive_days: Option<u32>,
+}
+
+fn default_llm_cache_days() -> Option<u32> {
+    Some(7)
+}
+
+fn default_archive_days() -> Option<u32> {
+    Some(90)
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            briefing_history_days: Some(30),
+            outreach_history_days: None,
+            activity_log_days: None,
+            triage_days: Some(90),
+            llm_cache_days: default_llm_cache_days(),
+            archive_days: default_archive_days(),
+        }
+    }
+}
+
+pub fn load_retention() -> Result<RetentionConfig, String> {
+    match load_setting(RETENTION_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse retention config: {}", e)),
+        None => Ok(RetentionConfig::default()),
+    }
+}
+
+pub fn save_retention(config: &RetentionConfig) -> Result<(), String> {
+    let json = serde_json::to_string(config)
+        .map_err(|e| format!("Failed to serialize retention config: {}", e))?;
+    save_setting(RETENTION_KEY, &json)
+}
+
+const NOTIFICATIONS_KEY: &str = "notifications";
+
+/// App-wide notification settings; per-chat modes live in `notification_rules`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationConfig {
+    pub enabled: bool,
+    /// Mode for chats without a rule: "all", "urgent", "vip" or "none"
+    pub default_mode: String,
+    /// Local "HH:MM"; quiet hours may wrap past midnight
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+    /// Contact tag that marks VIP senders (case-insensitive)
+    pub vip_tag: String,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            default_mode: super::notifications::MODE_ALL.to_string(),
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+            vip_tag: "VIP".to_string(),
+        }
+    }
+}
+
+pub fn load_notification_config() -> Result<NotificationConfig, String> {
+    match load_setting(NOTIFICATIONS_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse notification config: {}", e)),
+        None => Ok(NotificationConfig::default()),
+    }
+}
+
+pub fn save_notification_config(config: &NotificationConfig) -> Result<(), String> {
+    let json = serde_json::to_string(config)
+        .map_err(|e| format!("Failed to serialize notification config: {}", e))?;
+    save_setting(NOTIFICATIONS_KEY, &json)
+}
+
+const FOCUS_KEY: &str = "focus";
+
+/// Focus mode: on manually (optionally until a time) or on a daily schedule
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusConfig {
+    pub manual: bool,
+    /// Unix timestamp when manual focus ends; None = until turned off
+    pub manual_until: Option<i64>,
+    /// Local "HH:MM"; the schedule may wrap past midnight
+    pub schedule_start: Option<String>,
+    pub schedule_end: Option<String>,
+}
+
+pub fn load_focus() -> Result<FocusConfig, String> {
+    match load_setting(FOCUS_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse focus config: {}", e)),
+        None => Ok(FocusConfig::default()),
+    }
+}
+
+pub fn save_focus(config: &FocusConfig) -> Result<(), String> {
+    let json = serde_json::to_string(config)
+        .map_err(|e| format!("Failed to serialize focus config: {}", e))?;
+    save_setting(FOCUS_KEY, &json)
+}
+
+const AWAY_KEY: &str = "away";
+
+/// Away mode: auto-reply once per contact to incoming DMs while it's on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AwayConfig {
+    pub enabled: bool,
+    /// Reply text; `{name}` and `{first_name}` are filled from the sender
+    pub template: String,
+    /// Chats (user ids) that never get the auto-reply
+    #[serde(default)]
+    pub excluded_chat_ids: Vec<i64>,
+    /// Set when away mode is turned on; contacts get one reply per away period
+    #[serde(default)]
+    pub started_at: Option<i64>,
+}
+
+impl Default for AwayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            template: "Hi {first_name}, I'm away at the moment and will get back to you when I return.".to_string(),
+            excluded_chat_ids: Vec::new(),
+            started_at: None,
+        }
+    }
+}
+
+pub fn load_away_config() -> Result<AwayConfig, String> {
+    match load_setting(AWAY_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse away config: {}", e)),
+        None => Ok(AwayConfig::default()),
+    }
+}
+
+pub fn save_away_config(config: &AwayConfig) -> Result<(), String> {
+    let json = serde_json::to_string(config)
+        .map_err(|e| format!("Failed to serialize away config: {}", e))?;
+    save_setting(AWAY_KEY, &json)
+}
+
+const DRAFT_APPROVAL_KEY: &str = "draft_approval";
+
+/// Whether automated replies are queued as pending drafts instead of being sent
+pub fn load_draft_approval() -> Result<bool, String> {
+    Ok(load_setting(DRAFT_APPROVAL_KEY)?.as_deref() == Some("true"))
+}
+
+pub fn save_draft_approval(enabled: bool) -> Result<(), String> {
+    save_setting(DRAFT_APPROVAL_KEY, if enabled { "true" } else { "false" })
+}
+
+const SEND_DELAY_KEY: &str = "send_delay_secs";
+
+/// Seconds a send waits before going out, during which it can be undone (0 = off)
+pub fn load_send_delay() -> Result<u64, String> {
+    Ok(load_setting(SEND_DELAY_KEY)?.and_then(|v| v.parse().ok()).unwrap_or(0))
+}
+
+pub fn save_send_delay(secs: u64) -> Result<(), String> {
+    save_setting(SEND_DELAY_KEY, &secs.to_string())
+}
+
+const ARCHIVE_BACKFILL_KEY: &str = "archive_backfill";
+
+/// Background archiving of a scope's older history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveBackfillConfig {
+    pub enabled: bool,
+    /// Saved scope whose chats are archived
+    pub scope_name: Option<String>,
+    /// Pages of history fetched per minute, across all chats
+    pub requests_per_minute: u32,
+}
+
+impl Default for ArchiveBackfillConfig {
+    fn default() -> Self {
+        Self { enabled: false, scope_name: None, requests_per_minute: 6 }
+    }
+}
+
+pub fn load_archive_backfill() -> Result<ArchiveBackfillConfig, String> {
+    match load_setting(ARCHIVE_BACKFILL_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse archive backfill config: {}", e)),
+        None => Ok(ArchiveBackfillConfig::default()),
+    }
+}
+
+pub fn save_archive_backfill(config: &ArchiveBackfillConfig) -> Result<(), String> {
+    let json = serde_json::to_string(config)
+        .map_err(|e| format!("Failed to serialize archive backfill config: {}", e))?;
+    save_setting(ARCHIVE_BACKFILL_KEY, &json)
+}
+
+const SYNC_KEY: &str = "sync";
+const SYNC_DEVICE_ID_KEY: &str = "sync_device_id";
+
+/// Multi-device sync through encrypted payloads in Saved Messages
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConfig {
+    pub enabled: bool,
+    /// Only in configs saved before the password moved to the OS keychain
+    /// (see `sync::keychain`); moved there on load
+    #[serde(default, rename = "password", skip_serializing)]
+    legacy_password: Option<String>,
+}
+
+impl SyncConfig {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, legacy_password: None }
+    }
+}
+
+pub fn load_sync_config() -> Result<SyncConfig, String> {
+    let mut config: SyncConfig = match load_setting(SYNC_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse sync config: {}", e))?,
+        None => return Ok(SyncConfig::default()),
+    };
+    if let Some(password) = config.legacy_password.take() {
+        crate::sync::keychain::save_password(&password)?;
+        save_sync_config(&config)?;
+        log::info!("[Sync] Moved the sync password to the OS keychain");
+    }
+    Ok(config)
+}
+
+pub fn save_sync_config(config: &SyncConfig) -> Result<(), String> {
+    let json = serde_json::to_string(config)
+        .map_err(|e| format!("Failed to serialize sync config: {}", e))?;
+    save_setting(SYNC_KEY, &json)
+}
+
+/// Stable id for this installation, created on first use
+pub fn sync_device_id() -> Result<String, String> {
+    if let Some(id) = load_setting(SYNC_DEVICE_ID_KEY)? {
+        return Ok(id);
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    save_setting(SYNC_DEVICE_ID_KEY, &id)?;
+    Ok(id)
+}
+
+const AI_CALL_LOG_KEY: &str = "ai_call_log_enabled";
+
+/// Whether AI calls are recorded in `ai_call_log` (off unless the user opts in)
+pub fn load_ai_call_log_enabled() -> Result<bool, String> {
+    Ok(load_setting(AI_CALL_LOG_KEY)?.as_deref() == Some("true"))
+}
+
+pub fn save_ai_call_log_enabled(enabled: bool) -> Result<(), String> {
+    save_setting(AI_CALL_LOG_KEY, if enabled { "true" } else { "false" })
+}
+
+const LOCAL_ONLY_AI_KEY: &str = "local_only_ai";
+
+/// Whether AI requests are restricted to localhost (see `ai::local_only`)
+pub fn load_local_only_ai() -> Result<bool, String> {
+    Ok(load_setting(LOCAL_ONLY_AI_KEY)?.as_deref() == Some("true"))
+}
+
+pub fn save_local_only_ai(enabled: bool) -> Result<(), String> {
+    save_setting(LOCAL_ONLY_AI_KEY, if enabled { "true" } else { "false" })?;
+    crate::ai::local_only::set_enabled(enabled);
+    Ok(())
+}
+
+/// Load the saved local-only switch and make it active
+pub fn apply_saved_local_only_ai() -> Result<(), String> {
+    crate::ai::local_only::set_enabled(load_local_only_ai()?);
+    Ok(())
+}
+
+const ONBOARDING_KEY: &str = "onboarding_completed_steps";
+
+/// Onboarding steps the user has explicitly completed or skipped
+pub fn load_onboarding_steps() -> Result<Vec<String>, String> {
+    match load_setting(ONBOARDING_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse onboarding steps: {}", e)),
+        None => Ok(vec![]),
+    }
+}
+
+pub fn save_onboarding_steps(steps: &[String]) -> Result<(), String> {
+    let json = serde_json::to_string(steps)
+        .map_err(|e| format!("Failed to serialize onboarding steps: {}", e))?;
+    save_setting(ONBOARDING_KEY, &json)
+}
+
+pub fn clear_onboarding_steps() -> Result<(), String> {
+    delete_setting(ONBOARDING_KEY)
+}
+
+const INTEGRITY_NOTICE_KEY: &str = "integrity_notice";
+
+/// The last startup check that moved files aside, until the user acknowledges it
+pub fn load_integrity_notice() -> Result<Option<IntegrityReport>, String> {
+    load_setting(INTEGRITY_NOTICE_KEY)?
+        .map(|json| serde_json::from_str(&json).map_err(|e| format!("Failed to parse integrity notice: {}", e)))
+        .transpose()
+}
+
+pub fn save_integrity_notice(report: &IntegrityReport) -> Result<(), String> {
+    let json = serde_json::to_string(report)
+        .map_err(|e| format!("Failed to serialize integrity notice: {}", e))?;
+    save_setting(INTEGRITY_NOTICE_KEY, &json)
+}
+
+pub fn clear_integrity_notice() -> Result<(), String> {
+    delete_setting(INTEGRITY_NOTICE_KEY)
+}
+
+const SANITIZATION_KEY: &str = "sanitization";
+
+pub fn load_sanitization() -> Result<SanitizationConfig, String> {
+    match load_setting(SANITIZATION_KEY)? {
+        Some(json) => serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse sanitization config: {}", e)),
+        None => Ok(SanitizationConfig::default()),
+    }
+}
+
+pub fn save_sanitization(config: &SanitizationConfig) -> Result<(), String> {
+    let json = serde_json::to_string(config)
+        .map_err(|e| format!("Failed to serialize sanitization config: {}", e))?;
+    save_setting(SANITIZATION_KEY, &json)
+}
+
+/// Load the saved sanitization config and make it active
+pub fn apply_saved_sanitization() -> Result<(), String> {
+    crate::ai::sanitize::configure(&load_sanitization()?)
+}
@@ -0,0 +1,72 @@
+//! Chats the user starred in the copilot, independent of Telegram pins
+
+use super::with_db;
+use crate::telegram::client::Chat;
+use std::collections::HashSet;
+
+pub fn load_starred() -> Result<HashSet<i64>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT chat_id FROM chat_stars")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let starred = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| format!("Failed to query starred chats: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(starred)
+    })
+}
+
+pub fn set_starred(chat_id: i64, starred: bool) -> Result<(), String> {
+    with_db(|conn| {
+        if starred {
+            conn.execute("INSERT OR IGNORE INTO chat_stars (chat_id) VALUES (?)", [chat_id])
+        } else {
+            conn.execute("DELETE FROM chat_stars WHERE chat_id = ?", [chat_id])
+        }
+        .map_err(|e| format!("Failed to save starred chat: {}", e))?;
+        Ok(())
+    })
+}
+
+/// Mark starred chats
+pub fn apply(chats: &mut [Chat]) {
+    let starred = match load_starred() {
+        Ok(starred) => starred,
+        Err(e) => {
+            log::warn!("Failed to load starred chats: {}", e);
+            return;
+        }
+    };
+    for chat in chats.iter_mut() {
+        chat.is_starred = starred.contains(&chat.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake;
+
+    #[test]
+    fn test_star_and_unstar() {
+        crate::db::init_test_db();
+        set_starred(7601, true).unwrap();
+        // Starring twice is a no-op
+        set_starred(7601, true).unwrap();
+        assert!(load_starred().unwrap().contains(&7601));
+
+        let mut chats = vec![
+            fake::chat(7601, "private", "Ada", 0, 0),
+            fake::chat(7602, "group", "Team", 0, 1),
+        ];
+        apply(&mut chats);
+        assert!(chats[0].is_starred && !chats[1].is_starred);
+
+        set_starred(7601, false).unwrap();
+        assert!(!load_starred().unwrap().contains(&7601));
+    }
+}
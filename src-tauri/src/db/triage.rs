@@ -0,0 +1,94 @@
+use super::with_db;
+use serde::{Deserialize, Serialize};
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_REPLIED: &str = "replied";
+pub const STATUS_ADDED: &str = "added";
+pub const STATUS_BLOCKED: &str = "blocked";
+pub const STATUS_DISMISSED: &str = "dismissed";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriageEntry {
+    pub user_id: i64,
+    pub name: String,
+    /// Latest message from the sender
+    pub preview: String,
+    pub first_seen_at: i64,
+    pub last_message_at: i64,
+    pub status: String,
+}
+
+/// Record an incoming DM from a non-contact. New senders are queued; for known
+/// senders only the preview is refreshed, so dismissed/blocked ones stay resolved.
+pub fn record_message(user_id: i64, name: &str, preview: &str, date: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO triage_queue (user_id, name, preview, first_seen_at, last_message_at, status)
+            VALUES (?1, ?2, ?3, ?4, ?4, 'pending')
+            ON CONFLICT(user_id) DO UPDATE SET
+                name = excluded.name,
+                preview = excluded.preview,
+                last_message_at = excluded.last_message_at,
+                updated_at = strftime('%s', 'now')
+            WHERE excluded.last_message_at > triage_queue.last_message_at
+            "#,
+            rusqlite::params![user_id, name, preview, date],
+        )
+        .map_err(|e| format!("Failed to record triage entry: {}", e))?;
+        Ok(())
+    })
+}
+
+pub fn set_status(user_id: i64, status: &str) -> Result<(), String> {
+    with_db(|conn| {
+        let updated = conn
+            .execute(
+                "UPDATE triage_queue SET status = ?, updated_at = strftime('%s', 'now') WHERE user_id = ?",
+                rusqlite::params![status, user_id],
+            )
+            .map_err(|e| format!("Failed to update triage entry: {}", e))?;
+        if updated == 0 {
+            return Err(format!("User {} is not in the triage queue", user_id));
+        }
+        Ok(())
+    })
+}
+
+pub fn load_entry(user_id: i64) -> Result<Option<TriageEntry>, String> {
+    Ok(load_where("user_id = ?", rusqlite::params![user_id])?.into_iter().next())
+}
+
+/// Entries still awaiting a decision, newest message first
+pub fn load_pending() -> Result<Vec<TriageEntry>, String> {
+    load_where("status = ?", rusqlite::params![STATUS_PENDING])
+}
+
+fn load_where(condition: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<TriageEntry>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT user_id, name, preview, first_seen_at, last_message_at, status FROM triage_queue WHERE {} ORDER BY last_message_at DESC",
+                condition
+            ))
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let entries = stmt
+            .query_map(params, |row| {
+                Ok(TriageEntry {
+                    user_id: row.get(0)?,
+                    name: row.get(1)?,
+                    preview: row.get(2)?,
+                    first_seen_at: row.get(3)?,
+                    last_message_at: row.get(4)?,
+                    status: row.get(5)?,
+                })
+            })
+            .map_err(|e| format!("Failed to query triage queue: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(entries)
+    })
+}
@@ -0,0 +1,89 @@
+//! Per-group welcome sequences for new members, the counterpart of offboarding
+
+use super::with_db;
+use serde::{Deserialize, Serialize};
+
+/// What a new member of a group gets. Texts may use `{name}`, `{first_name}` and `{group}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WelcomeKit {
+    pub chat_id: i64,
+    /// Posted in the group; skipped when empty
+    #[serde(default)]
+    pub rules_message: String,
+    /// Links or notes posted in the group as one list message
+    #[serde(default)]
+    pub resources: Vec<String>,
+    /// Sent to the new member directly
+    #[serde(default)]
+    pub intro_dm: Option<String>,
+}
+
+fn row_to_kit(row: &rusqlite::Row) -> rusqlite::Result<WelcomeKit> {
+    let resources: String = row.get(2)?;
+    Ok(WelcomeKit {
+        chat_id: row.get(0)?,
+        rules_message: row.get(1)?,
+        resources: serde_json::from_str(&resources).unwrap_or_default(),
+        intro_dm: row.get(3)?,
+    })
+}
+
+pub fn save_kit(kit: &WelcomeKit) -> Result<(), String> {
+    let resources = serde_json::to_string(&kit.resources)
+        .map_err(|e| format!("Failed to serialize resources: {}", e))?;
+    with_db(|conn| {
+        conn.execute(
+            r#"
+            INSERT INTO welcome_kits (chat_id, rules_message, resources, intro_dm, updated_at)
+            VALUES (?, ?, ?, ?, strftime('%s', 'now'))
+            ON CONFLICT(chat_id) DO UPDATE SET
+                rules_message = excluded.rules_message,
+                resources = excluded.resources,
+                intro_dm = excluded.intro_dm,
+                updated_at = excluded.updated_at
+            "#,
+            rusqlite::params![kit.chat_id, kit.rules_message, resources, kit.intro_dm],
+        )
+        .map_err(|e| format!("Failed to save welcome kit: {}", e))?;
+        Ok(())
+    })
+}
+
+pub fn load_kit(chat_id: i64) -> Result<Option<WelcomeKit>, String> {
+    with_db(|conn| {
+        match conn.query_row(
+            "SELECT chat_id, rules_message, resources, intro_dm FROM welcome_kits WHERE chat_id = ?",
+            [chat_id],
+            row_to_kit,
+        ) {
+            Ok(kit) => Ok(Some(kit)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to load welcome kit: {}", e)),
+        }
+    })
+}
+
+pub fn load_kits() -> Result<Vec<WelcomeKit>, String> {
+    with_db(|conn| {
+        let mut stmt = conn
+            .prepare("SELECT chat_id, rules_message, resources, intro_dm FROM welcome_kits ORDER BY chat_id")
+            .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+        let kits = stmt
+            .query_map([], row_to_kit)
+            .map_err(|e| format!("Failed to query welcome kits: {}", e))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(kits)
+    })
+}
+
+pub fn delete_kit(chat_id: i64) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute("DELETE FROM welcome_kits WHERE chat_id = ?", [chat_id])
+            .map_err(|e| format!("Failed to delete welcome kit: {}", e))?;
+        Ok(())
+    })
+}
@@ -0,0 +1,213 @@
+//! Portable app configuration: scopes, canned responses, the LLM provider,
+//! notification rules and the app settings blobs, as one JSON file.
+//! API keys and the sync password are left out unless an export password is
+//! given, in which case they travel encrypted alongside the plain settings.
+
+use crate::ai::client::LLMConfig;
+use crate::ai::sanitize::SanitizationConfig;
+use crate::db;
+use crate::db::canned::CannedResponse;
+use crate::db::notifications::NotificationRule;
+use crate::db::scopes::ScopeProfile;
+use crate::db::settings::{BriefingDeliveryConfig, FocusConfig, NotificationConfig, RetentionConfig, SyncConfig};
+use crate::sync::{crypto, keychain};
+use serde::{Deserialize, Serialize};
+
+const CONFIG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundle {
+    pub version: u32,
+    pub app_version: String,
+    pub exported_at: String,
+    /// Provider settings with the API keys removed
+    pub llm: LLMConfig,
+    pub briefing_delivery: BriefingDeliveryConfig,
+    pub retention: RetentionConfig,
+    pub notifications: NotificationConfig,
+    pub focus: FocusConfig,
+    pub sanitization: SanitizationConfig,
+    /// Sync settings; the password is in the OS keychain, never in here
+    pub sync: SyncConfig,
+    pub scopes: Vec<ScopeProfile>,
+    pub canned_responses: Vec<CannedResponse>,
+    pub notification_rules: Vec<NotificationRule>,
+    /// Encrypted `Secrets`, present when exported with a password
+    #[serde(default)]
+    pub encrypted_secrets: Option<String>,
+}
+
+/// Values never written to the file in plain text
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Secrets {
+    llm_api_key: Option<String>,
+    fallback_api_key: Option<String>,
+    sync_password: Option<String>,
+}
+
+impl Secrets {
+    fn is_empty(&self) -> bool {
+        self.llm_api_key.is_none() && self.fallback_api_key.is_none() && self.sync_password.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportStats {
+    pub scopes: usize,
+    pub canned_responses: usize,
+    pub notification_rules: usize,
+    /// Whether encrypted API keys / sync password were restored
+    pub secrets_restored: bool,
+}
+
+/// Move the API keys out of the config, leaving None behind
+fn take_secrets(llm: &mut LLMConfig, sync_password: Option<String>) -> Secrets {
+    Secrets {
+        llm_api_key: llm.api_key.take(),
+        fallback_api_key: llm.fallback.as_mut().and_then(|f| f.api_key.take()),
+        sync_password,
+    }
+}
+
+/// Put the API keys back, returning the sync password for the keychain
+fn put_secrets(llm: &mut LLMConfig, secrets: Secrets) -> Option<String> {
+    llm.api_key = secrets.llm_api_key;
+    if let Some(fallback) = llm.fallback.as_mut() {
+        fallback.api_key = secrets.fallback_api_key;
+    }
+    secrets.sync_password
+}
+
+/// Collect the current configuration; secrets are encrypted with `password` or dropped
+pub fn collect_config(mut llm: LLMConfig, password: Option<&str>) -> Result<ConfigBundle, String> {
+    let sync = db::settings::load_sync_config()?;
+    let sync_password = keychain::load_password().unwrap_or_else(|e| {
+        log::warn!("Exporting config without the sync password: {}", e);
+        None
+    });
+    let secrets = take_secrets(&mut llm, sync_password);
+
+    let encrypted_secrets = match password.filter(|p| !p.is_empty()) {
+        Some(password) if !secrets.is_empty() => {
+            let json = serde_json::to_vec(&secrets)
+                .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+            Some(crypto::encrypt(password, &json)?)
+        }
+        _ => None,
+    };
+
+    Ok(ConfigBundle {
+        version: CONFIG_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        llm,
+        briefing_delivery: db::settings::load_briefing_delivery()?,
+        retention: db::settings::load_retention()?,
+        notifications: db::settings::load_notification_config()?,
+        focus: db::settings::load_focus()?,
+        sanitization: db::settings::load_sanitization()?,
+        sync,
+        scopes: db::scopes::load_all_scopes()?,
+        canned_responses: db::canned::list_canned()?,
+        notification_rules: db::notifications::load_rules()?,
+        encrypted_secrets,
+    })
+}
+
+/// Write an imported bundle to the database. Scopes are matched by name and canned
+/// responses by shortcut; existing entries not in the bundle are kept. Without the
+/// export password the local API keys and sync password stay in place.
+/// Returns the LLM config to activate along with the stats.
+pub fn apply_config(
+    bundle: ConfigBundle,
+    current_llm: &LLMConfig,
+    password: Option<&str>,
+) -> Result<(LLMConfig, ImportStats), String> {
+    if bundle.version > CONFIG_VERSION {
+        return Err(format!(
+            "Config file version {} is newer than this app supports ({})",
+            bundle.version, CONFIG_VERSION
+        ));
+    }
+
+    let mut llm = bundle.llm;
+    let mut stats = ImportStats::default();
+
+    let secrets = match (&bundle.encrypted_secrets, password.filter(|p| !p.is_empty())) {
+        (Some(encrypted), Some(password)) => {
+            let json = crypto::decrypt(password, encrypted)?;
+            stats.secrets_restored = true;
+            serde_json::from_slice(&json).map_err(|e| format!("Failed to parse secrets: {}", e))?
+        }
+        // The local sync password stays in the keychain untouched
+        _ => take_secrets(&mut current_llm.clone(), None),
+    };
+    let sync_password = put_secrets(&mut llm, secrets);
+
+    db::settings::save_llm_config(&llm)?;
+    db::settings::save_briefing_delivery(&bundle.briefing_delivery)?;
+    db::settings::save_retention(&bundle.retention)?;
+    db::settings::save_notification_config(&bundle.notifications)?;
+    db::settings::save_focus(&bundle.focus)?;
+    db::settings::save_sanitization(&bundle.sanitization)?;
+    db::settings::apply_saved_sanitization()?;
+    db::settings::save_sync_config(&bundle.sync)?;
+    if let Some(password) = sync_password {
+        keychain::save_password(&password)?;
+    }
+
+    for scope in &bundle.scopes {
+        let scope = match db::scopes::load_scope(&scope.name)? {
+            Some(existing) => ScopeProfile { id: existing.id, ..scope.clone() },
+            None => scope.clone(),
+        };
+        db::scopes::save_scope(&scope)?;
+        stats.scopes += 1;
+    }
+    for canned in &bundle.canned_responses {
+        db::canned::save_canned(&canned.shortcut, &canned.text, &canned.variables)?;
+        stats.canned_responses += 1;
+    }
+    for rule in &bundle.notification_rules {
+        db::notifications::save_rule(rule)?;
+        stats.notification_rules += 1;
+    }
+
+    Ok((llm, stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::client::{LLMFallback, LLMProvider};
+
+    #[test]
+    fn test_secrets_are_split_out() {
+        let mut llm = LLMConfig {
+            api_key: Some("sk-main".to_string()),
+            fallback: Some(LLMFallback {
+                provider: LLMProvider::OpenAI,
+                base_url: "https://api.openai.com".to_string(),
+                api_key: Some("sk-fallback".to_string()),
+                model: "gpt-4o-mini".to_string(),
+                timeout_secs: None,
+            }),
+            ..LLMConfig::default()
+        };
+        let sync = SyncConfig::new(true);
+
+        let secrets = take_secrets(&mut llm, Some("hunter2".to_string()));
+        let plain = serde_json::to_string(&llm).unwrap() + &serde_json::to_string(&sync).unwrap();
+        assert!(!plain.contains("sk-") && !plain.contains("hunter2"));
+
+        let encrypted = crypto::encrypt("pw", &serde_json::to_vec(&secrets).unwrap()).unwrap();
+        let restored: Secrets = serde_json::from_slice(&crypto::decrypt("pw", &encrypted).unwrap()).unwrap();
+        let sync_password = put_secrets(&mut llm, restored);
+        assert_eq!(llm.api_key.as_deref(), Some("sk-main"));
+        assert_eq!(llm.fallback.unwrap().api_key.as_deref(), Some("sk-fallback"));
+        assert_eq!(sync_password.as_deref(), Some("hunter2"));
+    }
+}
@@ -0,0 +1,145 @@
+//! Archived messages as a dataset for analysis outside the app: JSON Lines (one
+//! message per line) or CSV, every row carrying its chat's metadata.
+
+use crate::telegram::client::{Chat, Message};
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DatasetFormat {
+    JsonLines,
+    Csv,
+}
+
+impl DatasetFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.to_lowercase().as_str() {
+            "jsonl" | "json" => Ok(Self::JsonLines),
+            "csv" => Ok(Self::Csv),
+            other => Err(format!("Unsupported dataset format: {}", other)),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::JsonLines => "jsonl",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+const CSV_HEADER: &str =
+    "chat_id,chat_title,chat_type,message_id,date,sender_id,sender_name,is_outgoing,reply_to_message_id,text";
+
+#[derive(Serialize)]
+struct Row<'a> {
+    chat_id: i64,
+    chat_title: &'a str,
+    chat_type: &'a str,
+    message_id: i64,
+    /// RFC 3339, UTC
+    date: String,
+    sender_id: i64,
+    sender_name: &'a str,
+    is_outgoing: bool,
+    reply_to_message_id: Option<i64>,
+    text: String,
+}
+
+/// Quote a CSV field when it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub struct DatasetWriter<W: Write> {
+    out: W,
+    format: DatasetFormat,
+    rows: usize,
+}
+
+impl<W: Write> DatasetWriter<W> {
+    pub fn new(mut out: W, format: DatasetFormat) -> Result<Self, String> {
+        if format == DatasetFormat::Csv {
+            writeln!(out, "{}", CSV_HEADER).map_err(|e| format!("Failed to write dataset: {}", e))?;
+        }
+        Ok(Self { out, format, rows: 0 })
+    }
+
+    pub fn write_chat(&mut self, chat: &Chat, messages: &[Message]) -> Result<(), String> {
+        for message in messages {
+            let row = Row {
+                chat_id: chat.id,
+                chat_title: &chat.title,
+                chat_type: &chat.chat_type,
+                message_id: message.id,
+                date: chrono::DateTime::from_timestamp(message.date, 0).unwrap_or_default().to_rfc3339(),
+                sender_id: message.sender_id,
+                sender_name: &message.sender_name,
+                is_outgoing: message.is_outgoing,
+                reply_to_message_id: message.reply_to_message_id,
+                text: message.content.describe(),
+            };
+            let line = match self.format {
+                DatasetFormat::JsonLines => {
+                    serde_json::to_string(&row).map_err(|e| format!("Failed to serialize message: {}", e))?
+                }
+                DatasetFormat::Csv => [
+                    row.chat_id.to_string(),
+                    csv_field(row.chat_title),
+                    csv_field(row.chat_type),
+                    row.message_id.to_string(),
+                    row.date,
+                    row.sender_id.to_string(),
+                    csv_field(row.sender_name),
+                    row.is_outgoing.to_string(),
+                    row.reply_to_message_id.map(|id| id.to_string()).unwrap_or_default(),
+                    csv_field(&row.text),
+                ]
+                .join(","),
+            };
+            writeln!(self.out, "{}", line).map_err(|e| format!("Failed to write dataset: {}", e))?;
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    /// Flush and return how many messages were written
+    pub fn finish(mut self) -> Result<usize, String> {
+        self.out.flush().map_err(|e| format!("Failed to write dataset: {}", e))?;
+        Ok(self.rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::fake;
+
+    #[test]
+    fn test_dataset_rows_carry_chat_metadata() {
+        let ann = fake::user(7561, "Ann", "");
+        let chat = fake::chat(7561, "group", "Launch, \"v2\"", 0, 0);
+        let messages = vec![fake::text_message(1, 7561, &ann, "Ship it\non Friday", 0)];
+
+        let mut jsonl = DatasetWriter::new(Vec::new(), DatasetFormat::JsonLines).unwrap();
+        jsonl.write_chat(&chat, &messages).unwrap();
+        let out = String::from_utf8(jsonl.out.clone()).unwrap();
+        assert_eq!(jsonl.finish().unwrap(), 1);
+        let row: serde_json::Value = serde_json::from_str(out.lines().next().unwrap()).unwrap();
+        assert_eq!(row["chat_title"], "Launch, \"v2\"");
+        assert_eq!(row["text"], "Ship it\non Friday");
+        assert_eq!(row["date"], "1970-01-01T00:00:00+00:00");
+
+        let mut csv = DatasetWriter::new(Vec::new(), DatasetFormat::Csv).unwrap();
+        csv.write_chat(&chat, &messages).unwrap();
+        let out = String::from_utf8(csv.out).unwrap();
+        assert_eq!(out.lines().next(), Some(CSV_HEADER));
+        assert!(out.contains("7561,\"Launch, \"\"v2\"\"\",group,1,"));
+        assert!(out.ends_with(",\"Ship it\non Friday\"\n"));
+        assert!(DatasetFormat::parse("xml").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+//! Zip archive of diagnostics that users can attach to bug reports.
+//! It never includes chat content: only masked settings, table counts and
+//! recent log lines with names, phone numbers and emails scrubbed.
+
+use crate::ai::sanitize::{preset_redaction_rules, Redactor};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::Path;
+
+/// Settings fields whose names contain one of these are replaced wholesale
+const SECRET_FIELD_PARTS: [&str; 5] = ["key", "token", "secret", "password", "hash"];
+const MASK: &str = "***";
+
+/// Longest log line kept; anything past it is most likely a response body
+const MAX_LOG_LINE_CHARS: usize = 500;
+
+/// Runtime state the caller collects from managed Tauri state
+pub struct BundleContext {
+    /// Chat titles and the user's own name/phone, scrubbed wherever they appear
+    pub names: Vec<String>,
+    pub auth_state: Value,
+    pub llm_config: Value,
+    pub log_lines: Vec<String>,
+}
+
+fn is_secret_field(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SECRET_FIELD_PARTS.iter().any(|part| name.contains(part))
+}
+
+/// Replace every non-null value stored under a secret-looking field name
+pub fn mask_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (field, inner) in map.iter_mut() {
+                if is_secret_field(field) && !inner.is_null() {
+                    *inner = Value::String(MASK.to_string());
+                } else {
+                    mask_secrets(inner);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(mask_secrets),
+        _ => {}
+    }
+}
+
+/// Removes personal data from free text
+pub struct Scrubber {
+    redactor: Redactor,
+    rules: Vec<(Regex, String)>,
+    home_dir: Option<String>,
+}
+
+impl Scrubber {
+    pub fn new(names: &[String]) -> Self {
+        let mut redactor = Redactor::new();
+        for name in names {
+            redactor.add_name(name);
+        }
+
+        let rules = preset_redaction_rules()
+            .into_iter()
+            .filter_map(|rule| Regex::new(&rule.pattern).ok().map(|re| (re, rule.replacement)))
+            .collect();
+
+        let home_dir = dirs::home_dir()
+            .map(|p| p.to_string_lossy().to_string())
+            .filter(|p| p.len() > 1);
+
+        Self { redactor, rules, home_dir }
+    }
+
+    pub fn scrub(&mut self, text: &str) -> String {
+        let mut out = self.redactor.redact(text);
+        for (pattern, replacement) in &self.rules {
+            out = pattern.replace_all(&out, replacement.as_str()).into_owned();
+        }
+        // Home paths usually contain the OS user name
+        if let Some(home) = &self.home_dir {
+            out = out.replace(home.as_str(), "~");
+        }
+        out
+    }
+}
+
+/// All app settings as JSON, with secrets masked
+fn collect_settings(conn: &rusqlite::Connection) -> Result<Value, String> {
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM app_settings ORDER BY key")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+
+    let rows: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| format!("Failed to query settings: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut settings = serde_json::Map::new();
+    for (key, raw) in rows {
+        let value = if is_secret_field(&key) {
+            Value::String(MASK.to_string())
+        } else {
+            serde_json::from_str(&raw).unwrap_or(Value::String(raw))
+        };
+        settings.insert(key, value);
+    }
+
+    let mut settings = Value::Object(settings);
+    mask_secrets(&mut settings);
+    Ok(settings)
+}
+
+/// SQLite version, schema version and the shape of each table
+fn collect_schema(conn: &rusqlite::Connection) -> Result<(Value, Value), String> {
+    let sqlite_version: String = conn
+        .query_row("SELECT sqlite_version()", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read SQLite version: {}", e))?;
+    let user_version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read schema version: {}", e))?;
+
+    let mut stmt = conn
+        .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+        .map_err(|e| format!("Failed to prepare statement: {}", e))?;
+    let tables: Vec<String> = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| format!("Failed to list tables: {}", e))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut table_info = Vec::new();
+    let mut row_counts = serde_json::Map::new();
+    for table in &tables {
+        let mut columns_stmt = conn
+            .prepare(&format!("PRAGMA table_info(\"{}\")", table))
+            .map_err(|e| format!("Failed to inspect table {}: {}", table, e))?;
+        let columns: Vec<String> = columns_stmt
+            .query_map([], |row| row.get(1))
+            .map_err(|e| format!("Failed to inspect table {}: {}", table, e))?
+            .filter_map(|r| r.ok())
+            .collect();
+        table_info.push(json!({ "name": table, "columns": columns }));
+
+        let rows: i64 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table), [], |row| row.get(0))
+            .unwrap_or(-1);
+        row_counts.insert(table.clone(), json!(rows));
+    }
+
+    let schema = json!({
+        "sqliteVersion": sqlite_version,
+        "userVersion": user_version,
+        "tables": table_info,
+    });
+    Ok((schema, Value::Object(row_counts)))
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// The log section: each line truncated and scrubbed
+fn scrub_logs(scrubber: &mut Scrubber, lines: &[String]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            let line: String = line.chars().take(MAX_LOG_LINE_CHARS).collect();
+            scrubber.scrub(&line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Write the bundle to `path` and return the list of files it contains
+pub fn write_bundle(path: &Path, context: BundleContext) -> Result<Vec<String>, String> {
+    let (settings, (schema, row_counts)) = crate::db::with_db(|conn| {
+        Ok((collect_settings(conn)?, collect_schema(conn)?))
+    })?;
+
+    let mut scrubber = Scrubber::new(&context.names);
+
+    let mut llm_config = context.llm_config;
+    mask_secrets(&mut llm_config);
+    let config = json!({ "llm": llm_config, "settings": settings });
+
+    let system = json!({
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "generatedAt": chrono::Utc::now().to_rfc3339(),
+        "authState": context.auth_state,
+    });
+
+    let metrics = json!({
+        "rowCounts": row_counts,
+        "logLines": context.log_lines.len(),
+    });
+
+    let logs = scrub_logs(&mut scrubber, &context.log_lines);
+
+    let files = [
+        ("system.json", scrubber.scrub(&pretty(&system))),
+        ("config.json", scrubber.scrub(&pretty(&config))),
+        ("schema.json", pretty(&schema)),
+        ("metrics.json", pretty(&metrics)),
+        ("logs.txt", logs),
+    ];
+
+    let file = std::fs::File::create(path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for (name, contents) in &files {
+        zip.start_file(*name, options)
+            .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+        zip.write_all(contents.as_bytes())
+            .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to finish bundle: {}", e))?;
+
+    Ok(files.iter().map(|(name, _)| name.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_secrets_and_scrub() {
+        let mut config = json!({
+            "provider": "openai",
+            "api_key": "sk-123",
+            "nested": [{ "accessToken": "abc", "model": "gpt" }],
+            "unset_key": null,
+        });
+        mask_secrets(&mut config);
+        assert_eq!(config["api_key"], "***");
+        assert_eq!(config["nested"][0]["accessToken"], "***");
+        assert_eq!(config["nested"][0]["model"], "gpt");
+        assert!(config["unset_key"].is_null());
+        assert_eq!(config["provider"], "openai");
+
+        let mut scrubber = Scrubber::new(&["Alice Smith".to_string()]);
+        let line = scrubber.scrub("Generating draft for chat 42 (Alice Smith), call +1 415 555 0100 or alice@example.com");
+        assert!(!line.contains("Alice"));
+        assert!(!line.contains("555"));
+        assert!(!line.contains("alice@example.com"));
+        assert!(line.contains("chat 42"));
+    }
+
+    #[test]
+    fn test_outreach_sends_leave_no_message_text_in_logs() {
+        let message = "Hi Alice, the launch moves to Friday";
+        let lines = vec![crate::commands::outreach::sending_log_line(7671, message)];
+        let logs = scrub_logs(&mut Scrubber::new(&["Alice Smith".to_string()]), &lines);
+        assert!(logs.contains("7671"));
+        assert!(!logs.contains("launch"));
+        assert!(!logs.contains("Friday"));
+    }
+}
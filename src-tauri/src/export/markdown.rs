@@ -0,0 +1,110 @@
+use super::{chat_type_label, format_report_date};
+use crate::ai::types::BriefingV2Response;
+
+/// Escape characters that would otherwise be read as Markdown formatting,
+/// including list and heading markers at the start of a line
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            escaped.push('\n');
+        }
+        let body = line.trim_start();
+        escaped.push_str(&line[..line.len() - body.len()]);
+        // "- item", "+ item" and "1. item" would start a list
+        let digits = body.chars().take_while(char::is_ascii_digit).count();
+        if body.starts_with(['-', '+']) {
+            escaped.push('\\');
+        } else if digits > 0 && body[digits..].starts_with(['.', ')']) {
+            escaped.push_str(&body[..digits]);
+            escaped.push('\\');
+            escaped.push_str(&escape_inline(&body[digits..]));
+            continue;
+        }
+        escaped.push_str(&escape_inline(body));
+    }
+    escaped
+}
+
+fn escape_inline(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '#' | '<' | '>' | '|' | '~' | '!') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Render a briefing as a Markdown report
+pub fn render_briefing(briefing: &BriefingV2Response) -> String {
+    let mut out = String::new();
+
+    out.push_str(&format!("# Briefing — {}\n\n", format_report_date(&briefing.generated_at)));
+    out.push_str(&format!(
+        "**{}** need a response · **{}** FYI · **{}** unread messages\n",
+        briefing.stats.needs_response_count, briefing.stats.fyi_count, briefing.stats.total_unread
+    ));
+
+    out.push_str("\n## Needs response\n");
+    if briefing.needs_response.is_empty() {
+        out.push_str("\nNothing needs a response.\n");
+    }
+    for item in &briefing.needs_response {
+        let priority = if item.priority == "urgent" { "Urgent" } else { "Needs reply" };
+        out.push_str(&format!("\n### {} ({})\n\n", escape_markdown(&item.chat_name), priority));
+
+        let mut meta = format!("{} · {} unread", chat_type_label(&item.chat_type), item.unread_count);
+        if let Some(date) = &item.last_message_date {
+            meta.push_str(&format!(" · last message {}", format_report_date(date)));
+        }
+        out.push_str(&format!("_{}_\n\n", meta));
+        out.push_str(&format!("{}\n", escape_markdown(item.summary.trim())));
+
+        if let Some(reply) = item.suggested_reply.as_deref().filter(|r| !r.trim().is_empty()) {
+            out.push_str(&format!("\n> **Suggested reply:** {}\n", escape_markdown(reply.trim()).replace('\n', "\n> ")));
+        }
+    }
+
+    out.push_str("\n## FYI\n\n");
+    if briefing.fyi_summaries.is_empty() {
+        out.push_str("No FYI items.\n");
+    }
+    for item in &briefing.fyi_summaries {
+        out.push_str(&format!(
+            "- **{}** ({}, {} unread): {}\n",
+            escape_markdown(&item.chat_name),
+            chat_type_label(&item.chat_type),
+            item.unread_count,
+            escape_markdown(item.summary.trim())
+        ));
+    }
+
+    if !briefing.failed_chats.is_empty() {
+        out.push_str("\n## Could not analyze\n\n");
+        for chat in &briefing.failed_chats {
+            out.push_str(&format!(
+                "- **{}** ({} unread): {}\n",
+                escape_markdown(&chat.chat_name),
+                chat.unread_count,
+                escape_markdown(chat.reason.trim())
+            ));
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_markdown() {
+        assert_eq!(escape_markdown("*bold* _it_ [link](x) #tag"), "\\*bold\\* \\_it\\_ \\[link\\](x) \\#tag");
+        assert_eq!(escape_markdown("- not a list\n  + nor this\n2. or this"), "\\- not a list\n  \\+ nor this\n2\\. or this");
+        assert_eq!(escape_markdown("well-known 3.5 items"), "well-known 3.5 items");
+        assert_eq!(escape_markdown("Привет, <b>мир</b>"), "Привет, \\<b\\>мир\\</b\\>");
+    }
+}
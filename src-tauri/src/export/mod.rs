@@ -0,0 +1,37 @@
+pub mod config;
+pub mod dataset;
+pub mod debug_bundle;
+pub mod markdown;
+pub mod notes;
+pub mod pdf;
+pub mod telegram;
+
+use crate::ai::types::BriefingV2Response;
+
+/// Human-readable chat type label for reports
+pub(crate) fn chat_type_label(chat_type: &str) -> &'static str {
+    match chat_type {
+        "dm" | "private" => "DM",
+        "channel" => "Channel",
+        _ => "Group",
+    }
+}
+
+/// Format an RFC 3339 timestamp as "YYYY-MM-DD HH:MM" in local time, falling back to the raw string
+pub(crate) fn format_report_date(rfc3339: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(rfc3339)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|_| rfc3339.to_string())
+}
+
+/// Default file name for an exported briefing, e.g. `briefing-2024-05-01-0930.md`
+pub fn briefing_file_name(briefing: &BriefingV2Response, extension: &str) -> String {
+    let stamp = chrono::DateTime::parse_from_rfc3339(&briefing.generated_at)
+        .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d-%H%M").to_string())
+        .unwrap_or_else(|_| chrono::Local::now().format("%Y-%m-%d-%H%M").to_string());
+    format!("briefing-{}.{}", stamp, extension)
+}
@@ -0,0 +1,153 @@
+//! Per-chat summary notes for Obsidian-style Markdown vaults.
+
+use super::chat_type_label;
+use super::markdown::escape_markdown;
+use crate::ai::types::ChatSummaryResult;
+use std::path::Path;
+
+/// Quote a value for YAML frontmatter (JSON strings are valid YAML double-quoted scalars)
+fn yaml_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+/// Escape chat or LLM text for a heading or list item, which must stay on one line
+fn escape_line(text: &str) -> String {
+    escape_markdown(&text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// File name for a chat's note. The chat id keeps names unique across chats with the same title.
+pub fn note_file_name(summary: &ChatSummaryResult) -> String {
+    let title: String = summary
+        .chat_title
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    let title = title.trim().trim_matches('.');
+    let title = if title.is_empty() { "Chat" } else { title };
+    format!("{} ({}).md", title, summary.chat_id)
+}
+
+/// Render a chat summary as a Markdown note with YAML frontmatter
+pub fn render_summary_note(summary: &ChatSummaryResult, generated_at: i64) -> String {
+    let generated = chrono::DateTime::from_timestamp(generated_at, 0)
+        .map(|dt| dt.with_timezone(&chrono::Local))
+        .unwrap_or_else(chrono::Local::now);
+    let last_message = chrono::DateTime::from_timestamp(summary.last_message_date, 0)
+        .map(|dt| dt.with_timezone(&chrono::Local).to_rfc3339());
+
+    let mut out = String::from("---\n");
+    out.push_str(&format!("chat: {}\n", yaml_string(&summary.chat_title)));
+    out.push_str(&format!("chat_id: {}\n", summary.chat_id));
+    out.push_str(&format!("chat_type: {}\n", chat_type_label(&summary.chat_type).to_lowercase()));
+    out.push_str(&format!("date: {}\n", generated.format("%Y-%m-%d")));
+    out.push_str(&format!("updated: {}\n", generated.to_rfc3339()));
+    if let Some(last_message) = last_message {
+        out.push_str(&format!("last_message: {}\n", last_message));
+    }
+    out.push_str(&format!("sentiment: {}\n", summary.sentiment));
+    out.push_str(&format!("needs_response: {}\n", summary.needs_response));
+    if summary.action_items.is_empty() {
+        out.push_str("action_items: []\n");
+    } else {
+        out.push_str("action_items:\n");
+        for item in &summary.action_items {
+            out.push_str(&format!("  - {}\n", yaml_string(item)));
+        }
+    }
+    out.push_str("tags:\n  - telegram\n  - telegram-summary\n");
+    out.push_str("---\n\n");
+
+    out.push_str(&format!("# {}\n\n", escape_line(&summary.chat_title)));
+    out.push_str(&format!("{}\n", escape_markdown(summary.summary.trim())));
+
+    if !summary.key_points.is_empty() {
+        out.push_str("\n## Key points\n\n");
+        for point in &summary.key_points {
+            out.push_str(&format!("- {}\n", escape_line(point)));
+        }
+    }
+
+    if !summary.topics.is_empty() {
+        out.push_str("\n## Topics\n\n");
+        for topic in &summary.topics {
+            out.push_str(&format!("### {}\n\n{}\n", escape_line(&topic.title), escape_markdown(topic.summary.trim())));
+            if !topic.participants.is_empty() {
+                out.push_str(&format!("\nParticipants: {}\n", escape_line(&topic.participants.join(", "))));
+            }
+            out.push('\n');
+        }
+    }
+
+    if !summary.action_items.is_empty() {
+        out.push_str("\n## Action items\n\n");
+        for item in &summary.action_items {
+            out.push_str(&format!("- [ ] {}\n", escape_line(item)));
+        }
+    }
+
+    out
+}
+
+/// Write one note per summary into `folder`, replacing each chat's previous note.
+/// Returns the number of notes written.
+pub fn write_summary_notes(
+    folder: &Path,
+    summaries: &[ChatSummaryResult],
+    generated_at: i64,
+) -> Result<usize, String> {
+    if !folder.is_dir() {
+        return Err(format!("Summary folder does not exist: {}", folder.display()));
+    }
+
+    let mut written = 0;
+    for summary in summaries {
+        let path = folder.join(note_file_name(summary));
+        std::fs::write(&path, render_summary_note(summary, generated_at))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::types::TopicSummary;
+
+    #[test]
+    fn test_hostile_chat_text_stays_inert() {
+        let summary = ChatSummaryResult {
+            chat_id: 7681,
+            chat_title: "[Free](http://evil.example) #deals\n---\n# Owned".to_string(),
+            chat_type: "group".to_string(),
+            summary: "---\ninjected: true\n---\nSee ![x](http://evil.example/x.png)".to_string(),
+            key_points: vec!["# Heading\n- nested".to_string()],
+            action_items: vec!["Pay [here](http://evil.example)".to_string()],
+            sentiment: "neutral".to_string(),
+            needs_response: false,
+            message_count: 3,
+            last_message_date: 0,
+            topics: vec![TopicSummary {
+                title: "## Fake section".to_string(),
+                summary: "1. not a list".to_string(),
+                participants: vec!["*Ann*".to_string()],
+                message_count: 3,
+            }],
+        };
+        let note = render_summary_note(&summary, 0);
+        let body = note.splitn(3, "---\n").nth(2).unwrap();
+
+        assert!(body.starts_with("\n# \\[Free\\](http://evil.example) \\#deals --- \\# Owned\n"));
+        // A stray "---" would end up as a second frontmatter block or a rule
+        assert!(!body.lines().any(|line| line == "---"));
+        assert!(body.contains("See \\!\\[x\\](http://evil.example/x.png)"));
+        assert!(body.contains("- \\# Heading - nested\n"));
+        assert!(body.contains("- [ ] Pay \\[here\\](http://evil.example)\n"));
+        assert!(body.contains("### \\#\\# Fake section\n\n1\\. not a list\n"));
+        assert!(body.contains("Participants: \\*Ann\\*"));
+    }
+}
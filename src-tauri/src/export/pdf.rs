@@ -0,0 +1,431 @@
+//! Minimal PDF writer for text reports.
+//!
+//! Text is set in DejaVu Sans, embedded as a CID font with Identity-H encoding
+//! so any script the font covers (Latin, Cyrillic, Greek, ...) renders as is.
+//! Characters the font lacks show as its missing-glyph box.
+
+use super::{chat_type_label, format_report_date};
+use crate::ai::types::BriefingV2Response;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::collections::BTreeMap;
+use std::io::Write;
+use ttf_parser::{Face, GlyphId};
+
+const PAGE_WIDTH: f32 = 595.0; // A4 in points
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+
+/// Average DejaVu Sans glyph width as a fraction of the font size, used for wrapping
+const AVG_CHAR_WIDTH: f32 = 0.55;
+
+const REGULAR_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+const BOLD_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans-Bold.ttf");
+
+#[derive(Clone, Copy)]
+enum Style {
+    Title,
+    Heading,
+    Subheading,
+    Body,
+    Muted,
+}
+
+impl Style {
+    /// Index into `PdfWriter::fonts`
+    fn font(self) -> usize {
+        match self {
+            Style::Title | Style::Heading | Style::Subheading => 1,
+            Style::Body | Style::Muted => 0,
+        }
+    }
+
+    fn size(self) -> f32 {
+        match self {
+            Style::Title => 20.0,
+            Style::Heading => 15.0,
+            Style::Subheading => 12.0,
+            Style::Body => 10.5,
+            Style::Muted => 9.5,
+        }
+    }
+
+    /// Extra space before a block in this style
+    fn space_before(self) -> f32 {
+        match self {
+            Style::Title => 0.0,
+            Style::Heading => 14.0,
+            Style::Subheading => 10.0,
+            Style::Body | Style::Muted => 2.0,
+        }
+    }
+}
+
+/// A TrueType font embedded whole, tracking the glyphs used for its widths and
+/// ToUnicode map (which keeps the text searchable and copyable)
+struct EmbeddedFont {
+    name: &'static str,
+    data: &'static [u8],
+    face: Face<'static>,
+    used: BTreeMap<u16, char>,
+}
+
+impl EmbeddedFont {
+    fn new(name: &'static str, data: &'static [u8]) -> Self {
+        let face = Face::parse(data, 0).expect("bundled font is a valid TrueType file");
+        Self { name, data, face, used: BTreeMap::new() }
+    }
+
+    /// Text as a hex string of glyph ids for `Tj`
+    fn encode(&mut self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len() * 4);
+        for c in text.chars() {
+            let c = if c.is_control() { ' ' } else { c };
+            let glyph = self.face.glyph_index(c).map_or(0, |g| g.0);
+            self.used.entry(glyph).or_insert(c);
+            out.push_str(&format!("{:04X}", glyph));
+        }
+        out
+    }
+
+    /// Glyph advance in PDF text space (1/1000 of the font size)
+    fn width(&self, glyph: u16) -> u32 {
+        let advance = self.face.glyph_hor_advance(GlyphId(glyph)).unwrap_or(0) as u32;
+        advance * 1000 / self.face.units_per_em() as u32
+    }
+
+    /// Scale a font unit value to PDF glyph space
+    fn scaled(&self, value: i16) -> i32 {
+        value as i32 * 1000 / self.face.units_per_em() as i32
+    }
+
+    /// The font's objects, numbered from `first_id`: Type0 font (the one pages
+    /// reference), CID font, descriptor, font file and ToUnicode map
+    fn objects(&self, first_id: usize) -> Vec<Vec<u8>> {
+        let [cid_id, descriptor_id, file_id, to_unicode_id] = [1, 2, 3, 4].map(|i| first_id + i);
+
+        let widths: Vec<String> = self.used.keys().map(|&g| format!("{} [{}]", g, self.width(g))).collect();
+        let bbox = self.face.global_bounding_box();
+        let cap_height = self.face.capital_height().unwrap_or(self.face.ascender());
+
+        vec![
+            format!(
+                "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>",
+                self.name, cid_id, to_unicode_id
+            )
+            .into_bytes(),
+            format!(
+                "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /{} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /FontDescriptor {} 0 R /CIDToGIDMap /Identity /W [{}] >>",
+                self.name,
+                descriptor_id,
+                widths.join(" ")
+            )
+            .into_bytes(),
+            format!(
+                "<< /Type /FontDescriptor /FontName /{} /Flags 32 /FontBBox [{} {} {} {}] /ItalicAngle 0 /Ascent {} /Descent {} /CapHeight {} /StemV 80 /FontFile2 {} 0 R >>",
+                self.name,
+                self.scaled(bbox.x_min),
+                self.scaled(bbox.y_min),
+                self.scaled(bbox.x_max),
+                self.scaled(bbox.y_max),
+                self.scaled(self.face.ascender()),
+                self.scaled(self.face.descender()),
+                self.scaled(cap_height),
+                file_id
+            )
+            .into_bytes(),
+            stream_object(&format!("/Filter /FlateDecode /Length1 {} ", self.data.len()), &deflate(self.data)),
+            stream_object("", self.to_unicode_cmap().as_bytes()),
+        ]
+    }
+
+    fn to_unicode_cmap(&self) -> String {
+        let mut cmap = String::from(
+            "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+             /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+             /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+             1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+        );
+        let entries: Vec<(&u16, &char)> = self.used.iter().collect();
+        // At most 100 entries per bfchar block
+        for chunk in entries.chunks(100) {
+            cmap.push_str(&format!("{} beginbfchar\n", chunk.len()));
+            for (glyph, c) in chunk {
+                let utf16: String = c.encode_utf16(&mut [0; 2]).iter().map(|u| format!("{:04X}", u)).collect();
+                cmap.push_str(&format!("<{:04X}> <{}>\n", glyph, utf16));
+            }
+            cmap.push_str("endbfchar\n");
+        }
+        cmap.push_str("endcmap\nCMapName currentdict /CMapResource defineresource pop\nend\nend\n");
+        cmap
+    }
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    // Writing to a Vec can't fail
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
+}
+
+/// A stream object; `extra` adds entries to its dictionary
+fn stream_object(extra: &str, data: &[u8]) -> Vec<u8> {
+    let mut object = format!("<< /Length {} {}>>\nstream\n", data.len(), extra).into_bytes();
+    object.extend_from_slice(data);
+    object.extend_from_slice(b"\nendstream");
+    object
+}
+
+/// Lays out wrapped text blocks into page content streams
+struct PdfWriter {
+    fonts: [EmbeddedFont; 2],
+    pages: Vec<String>,
+    current: String,
+    y: f32,
+}
+
+impl PdfWriter {
+    fn new() -> Self {
+        Self {
+            fonts: [
+                EmbeddedFont::new("DejaVuSans", REGULAR_FONT),
+                EmbeddedFont::new("DejaVuSans-Bold", BOLD_FONT),
+            ],
+            pages: vec![],
+            current: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn block(&mut self, style: Style, indent: f32, text: &str) {
+        let size = style.size();
+        let line_height = size * 1.35;
+        let max_chars = ((PAGE_WIDTH - 2.0 * MARGIN - indent) / (size * AVG_CHAR_WIDTH)) as usize;
+
+        self.y -= style.space_before();
+        let gray = if matches!(style, Style::Muted) { "0.4" } else { "0" };
+
+        for line in wrap_text(text, max_chars) {
+            if self.y - line_height < MARGIN {
+                self.new_page();
+            }
+            self.y -= line_height;
+            let glyphs = self.fonts[style.font()].encode(&line);
+            self.current.push_str(&format!(
+                "BT {gray} g /F{} {} Tf {:.1} {:.1} Td <{}> Tj ET\n",
+                style.font() + 1,
+                size,
+                MARGIN + indent,
+                self.y,
+                glyphs
+            ));
+        }
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(std::mem::take(&mut self.current));
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if !self.current.is_empty() || self.pages.is_empty() {
+            self.new_page();
+        }
+
+        // Object layout: 1 catalog, 2 page tree, five objects per font, then (page, content) pairs
+        let font_ids: Vec<usize> = (0..self.fonts.len()).map(|i| 3 + i * 5).collect();
+        let first_page_id = 3 + self.fonts.len() * 5;
+        let page_count = self.pages.len();
+        let page_ids: Vec<usize> = (0..page_count).map(|i| first_page_id + i * 2).collect();
+
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+                page_count
+            )
+            .into_bytes(),
+        ];
+        for (font, &id) in self.fonts.iter().zip(&font_ids) {
+            objects.extend(font.objects(id));
+        }
+        let font_resources: Vec<String> =
+            font_ids.iter().enumerate().map(|(i, id)| format!("/F{} {} 0 R", i + 1, id)).collect();
+        for (page_id, content) in page_ids.iter().zip(&self.pages) {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << {} >> >> /Contents {} 0 R >>",
+                    PAGE_WIDTH,
+                    PAGE_HEIGHT,
+                    font_resources.join(" "),
+                    page_id + 1
+                )
+                .into_bytes(),
+            );
+            objects.push(stream_object("", content.as_bytes()));
+        }
+
+        // The binary comment marks the file as binary for transfer tools
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (idx, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", idx + 1).as_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = out.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            trailer.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        trailer.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        ));
+        out.extend_from_slice(trailer.as_bytes());
+        out
+    }
+}
+
+/// Greedy word wrap; words longer than a line are hard-split
+fn wrap_text(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(10);
+    let mut lines = vec![];
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            while word.len() > max_chars {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                lines.push(word.drain(..max_chars).collect());
+            }
+            let word: String = word.into_iter().collect();
+
+            let line_len = line.chars().count();
+            if line_len > 0 && line_len + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&word);
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Render a briefing as a PDF document
+pub fn render_briefing(briefing: &BriefingV2Response) -> Vec<u8> {
+    let mut pdf = PdfWriter::new();
+
+    pdf.block(Style::Title, 0.0, &format!("Briefing — {}", format_report_date(&briefing.generated_at)));
+    pdf.block(
+        Style::Muted,
+        0.0,
+        &format!(
+            "{} need a response • {} FYI • {} unread messages",
+            briefing.stats.needs_response_count, briefing.stats.fyi_count, briefing.stats.total_unread
+        ),
+    );
+
+    pdf.block(Style::Heading, 0.0, "Needs response");
+    if briefing.needs_response.is_empty() {
+        pdf.block(Style::Body, 0.0, "Nothing needs a response.");
+    }
+    for item in &briefing.needs_response {
+        let priority = if item.priority == "urgent" { "Urgent" } else { "Needs reply" };
+        pdf.block(Style::Subheading, 0.0, &format!("{} ({})", item.chat_name, priority));
+
+        let mut meta = format!("{} • {} unread", chat_type_label(&item.chat_type), item.unread_count);
+        if let Some(date) = &item.last_message_date {
+            meta.push_str(&format!(" • last message {}", format_report_date(date)));
+        }
+        pdf.block(Style::Muted, 0.0, &meta);
+        pdf.block(Style::Body, 0.0, item.summary.trim());
+
+        if let Some(reply) = item.suggested_reply.as_deref().filter(|r| !r.trim().is_empty()) {
+            pdf.block(Style::Body, 14.0, &format!("Suggested reply: {}", reply.trim()));
+        }
+    }
+
+    pdf.block(Style::Heading, 0.0, "FYI");
+    if briefing.fyi_summaries.is_empty() {
+        pdf.block(Style::Body, 0.0, "No FYI items.");
+    }
+    for item in &briefing.fyi_summaries {
+        pdf.block(
+            Style::Body,
+            0.0,
+            &format!(
+                "• {} ({}, {} unread): {}",
+                item.chat_name,
+                chat_type_label(&item.chat_type),
+                item.unread_count,
+                item.summary.trim()
+            ),
+        );
+    }
+
+    pdf.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_text() {
+        let lines = wrap_text("the quick brown fox jumps over the lazy dog", 15);
+        assert!(lines.iter().all(|l| l.chars().count() <= 15));
+        assert_eq!(lines.join(" "), "the quick brown fox jumps over the lazy dog");
+    }
+
+    #[test]
+    fn test_wrap_splits_long_words() {
+        let lines = wrap_text(&"x".repeat(25), 10);
+        assert_eq!(lines, vec!["x".repeat(10), "x".repeat(10), "x".repeat(5)]);
+    }
+
+    #[test]
+    fn test_non_latin_text_keeps_its_glyphs() {
+        let mut font = EmbeddedFont::new("DejaVuSans", REGULAR_FONT);
+        let encoded = font.encode("привет (café) — Ωμέγα");
+
+        // Every character maps to a real glyph, none to the missing-glyph box
+        assert_eq!(encoded.len(), "привет (café) — Ωμέγα".chars().count() * 4);
+        assert!(!font.used.contains_key(&0));
+
+        // The ToUnicode map lets the text be searched and copied back out
+        let cmap = font.to_unicode_cmap();
+        let glyph = font.face.glyph_index('п').unwrap().0;
+        assert!(cmap.contains(&format!("<{:04X}> <043F>", glyph)), "{}", cmap);
+    }
+
+    #[test]
+    fn test_finish_writes_valid_structure() {
+        let mut pdf = PdfWriter::new();
+        pdf.block(Style::Body, 0.0, "hello");
+        pdf.block(Style::Heading, 0.0, "Привет");
+        let bytes = pdf.finish();
+
+        let text = String::from_utf8_lossy(&bytes);
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.contains("/Count 1"));
+        assert!(text.contains("/Encoding /Identity-H") && text.contains("/FontFile2"));
+        assert!(text.trim_end().ends_with("%%EOF"));
+
+        // xref offsets point at the objects
+        let xref = text.rfind("\nxref\n").unwrap() + 1;
+        let first_offset: usize = text[xref..].lines().nth(3).unwrap()[..10].parse().unwrap();
+        assert!(bytes[first_offset..].starts_with(b"1 0 obj"));
+    }
+}
@@ -0,0 +1,129 @@
+//! Briefing rendered as formatted Telegram messages (for delivery to Saved Messages or a channel).
+
+use super::{chat_type_label, format_report_date};
+use crate::ai::types::BriefingV2Response;
+use crate::telegram::client::{FormattedText, TextStyle};
+
+/// Telegram's limit is 4096 UTF-16 units; leave headroom for the part counter
+const MAX_MESSAGE_UTF16: i32 = 4000;
+
+fn item_block(title: &str, meta: &str, body: &str, reply: Option<&str>) -> FormattedText {
+    let mut block = FormattedText::default();
+    block.push("\n");
+    block.push_styled(title, TextStyle::Bold);
+    block.push(" ");
+    block.push_styled(meta, TextStyle::Italic);
+    block.push("\n");
+    block.push(body.trim());
+    block.push("\n");
+    if let Some(reply) = reply.map(str::trim).filter(|r| !r.is_empty()) {
+        block.push_styled("Suggested reply: ", TextStyle::Italic);
+        block.push(reply);
+        block.push("\n");
+    }
+    block
+}
+
+/// Cut an oversized block down to the limit, dropping entities past the cut
+fn truncate_block(mut block: FormattedText, max_utf16: i32) -> FormattedText {
+    let mut units = 0;
+    let mut cut = block.text.len();
+    for (idx, c) in block.text.char_indices() {
+        if units + c.len_utf16() as i32 > max_utf16 - 1 {
+            cut = idx;
+            break;
+        }
+        units += c.len_utf16() as i32;
+    }
+    block.text.truncate(cut);
+    block.text.push('…');
+    block.entities.retain(|e| e.offset < units);
+    for e in &mut block.entities {
+        e.length = e.length.min(units - e.offset);
+    }
+    block
+}
+
+/// Telegram strips leading whitespace, so a message must not start with the block separator
+fn trim_leading_newlines(mut block: FormattedText) -> FormattedText {
+    let removed = block.text.len() - block.text.trim_start_matches('\n').len();
+    if removed > 0 {
+        block.text.drain(..removed);
+        for e in &mut block.entities {
+            e.offset -= removed as i32;
+        }
+    }
+    block
+}
+
+/// Render a briefing as one or more formatted messages, each within Telegram's length limit
+pub fn render_briefing(briefing: &BriefingV2Response) -> Vec<FormattedText> {
+    let mut blocks = vec![];
+
+    let mut header = FormattedText::default();
+    header.push_styled(
+        &format!("Briefing — {}", format_report_date(&briefing.generated_at)),
+        TextStyle::Bold,
+    );
+    header.push(&format!(
+        "\n{} need a response · {} FYI · {} unread\n",
+        briefing.stats.needs_response_count, briefing.stats.fyi_count, briefing.stats.total_unread
+    ));
+    blocks.push(header);
+
+    if !briefing.needs_response.is_empty() {
+        let mut section = FormattedText::default();
+        section.push("\n");
+        section.push_styled("NEEDS RESPONSE", TextStyle::Bold);
+        section.push("\n");
+        blocks.push(section);
+    }
+    for item in &briefing.needs_response {
+        let marker = if item.priority == "urgent" { "🔴" } else { "🟡" };
+        blocks.push(item_block(
+            &format!("{} {}", marker, item.chat_name),
+            &format!("({}, {} unread)", chat_type_label(&item.chat_type), item.unread_count),
+            &item.summary,
+            item.suggested_reply.as_deref(),
+        ));
+    }
+
+    if !briefing.fyi_summaries.is_empty() {
+        let mut section = FormattedText::default();
+        section.push("\n");
+        section.push_styled("FYI", TextStyle::Bold);
+        section.push("\n");
+        blocks.push(section);
+    }
+    for item in &briefing.fyi_summaries {
+        let mut block = FormattedText::default();
+        block.push("• ");
+        block.push_styled(&item.chat_name, TextStyle::Bold);
+        block.push(&format!(": {}\n", item.summary.trim()));
+        blocks.push(block);
+    }
+
+    // Pack blocks into messages without splitting a block across messages
+    let mut messages: Vec<FormattedText> = vec![];
+    let mut current = FormattedText::default();
+    for block in blocks {
+        let block = if block.utf16_len() > MAX_MESSAGE_UTF16 {
+            truncate_block(block, MAX_MESSAGE_UTF16)
+        } else {
+            block
+        };
+        if current.utf16_len() + block.utf16_len() > MAX_MESSAGE_UTF16 {
+            messages.push(std::mem::take(&mut current));
+        }
+        if current.text.is_empty() {
+            current.append(trim_leading_newlines(block));
+        } else {
+            current.append(block);
+        }
+    }
+    if !current.text.trim().is_empty() {
+        messages.push(current);
+    }
+
+    messages
+}
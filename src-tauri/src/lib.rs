@@ -1,14 +1,18 @@
 mod ai;
 mod cache;
+pub mod cli;
 mod commands;
 mod db;
 pub mod error;
+mod export;
+mod mcp;
+mod sync;
 mod telegram;
 mod utils;
 
 use ai::{LLMClient, LLMConfig, LLMProvider};
-use cache::{BriefingCache, ContactsCache, SummaryCache};
-use commands::{ai as ai_commands, auth, chats, contacts, offboard, outreach, scopes};
+use cache::{BriefingCache, ChatListCache, ContactsCache, SummaryCache};
+use commands::{ai as ai_commands, archive, auth, away, bot_rules, calls, canned, channel_stats, chats, contacts, crosspost, decisions, deep_analysis, delivery, dossier, export as export_commands, focus, followups, lists, media, meeting, mentions, notifications, offboard, onboarding, outbox, outreach, pending_drafts, pipelines, reminders, retention, rsvp, scheduled_posts, scope_suggestions, scopes, search, send_queue, standup, sync as sync_commands, triage, unread, welcome};
 use utils::rate_limiter::RateLimiter;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -20,13 +24,38 @@ fn setup_telegram_events(app: &tauri::App, client: Arc<TelegramClient>) {
     let mut receiver = client.subscribe();
 
     tauri::async_runtime::spawn(async move {
-        while let Ok(event) = receiver.recv().await {
+        loop {
+            let event = match receiver.recv().await {
+                Ok(event) => event,
+                // A burst outran the forwarder; the UI catches up from later events
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Telegram event forwarder lagged, skipped {} events", skipped);
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
             match &event {
                 telegram::client::TelegramEvent::AuthStateChanged(state) => {
                     let _ = app_handle.emit("telegram://auth-state", state);
                 }
                 telegram::client::TelegramEvent::NewMessage(message) => {
                     let _ = app_handle.emit("telegram://new-message", message);
+                    // Side effects may call Telegram or the LLM, so they run off the
+                    // forwarder to keep it from lagging behind a burst of messages
+                    let (app, client, message) = (app_handle.clone(), client.clone(), message.clone());
+                    tauri::async_runtime::spawn(async move {
+                        handle_new_message(&app, client.as_ref(), &message).await;
+                    });
+                }
+                telegram::client::TelegramEvent::MessageEdited(message) => {
+                    if let Err(e) = db::messages::record_edit(message, chrono::Utc::now().timestamp()) {
+                        log::warn!("Failed to archive edit of message {}: {}", message.id, e);
+                    }
+                }
+                telegram::client::TelegramEvent::MessagesDeleted { chat_ids, message_ids } => {
+                    if let Err(e) = db::messages::mark_deleted(chat_ids, message_ids, chrono::Utc::now().timestamp()) {
+                        log::warn!("Failed to mark deleted messages: {}", e);
+                    }
                 }
                 telegram::client::TelegramEvent::ChatUpdated(chat) => {
                     let _ = app_handle.emit("telegram://chat-updated", chat);
@@ -37,17 +66,37 @@ fn setup_telegram_events(app: &tauri::App, client: Arc<TelegramClient>) {
                 telegram::client::TelegramEvent::Error(error) => {
                     let _ = app_handle.emit("telegram://error", error);
                 }
+                telegram::client::TelegramEvent::Reconnected => {
+                    let _ = app_handle.emit("telegram://reconnected", ());
+                    let (app, client) = (app_handle.clone(), client.clone());
+                    tauri::async_runtime::spawn(async move {
+                        outbox::flush_and_emit(&app, client.as_ref()).await;
+                    });
+                }
             }
         }
     });
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    // Initialize logging first
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+/// Everything a new message triggers besides the UI event
+async fn handle_new_message(app: &tauri::AppHandle, client: &TelegramClient, message: &telegram::client::Message) {
+    if message.is_outgoing {
+        // A reply sent from another device changes what the chat needs too
+        let briefing_cache = app.state::<Arc<BriefingCache>>();
+        let summary_cache = app.state::<Arc<SummaryCache>>();
+        commands::chats::invalidate_chat_analysis(&briefing_cache, &summary_cache, message.chat_id).await;
+    }
+    commands::mentions::record_mentions_from(client, message.chat_id, std::slice::from_ref(message)).await;
+    commands::calls::record_calls_from(client, message.chat_id, std::slice::from_ref(message)).await;
+    commands::triage::record_from(client, message).await;
+    commands::notifications::notify_if_allowed(app, message);
+    commands::bot_rules::apply_bot_rules(app, client, message).await;
+    commands::away::apply_away_mode(app, client, message).await;
+    commands::rsvp::apply_rsvp_replies(message);
+}
 
-    // Load .env file - try multiple locations (useful for development)
+/// Load a .env file - try multiple locations (useful for development)
+pub(crate) fn load_dotenv() {
     let env_paths = [
         std::path::PathBuf::from(".env"),
         std::path::PathBuf::from("../.env"),  // When running from src-tauri
@@ -72,8 +121,10 @@ pub fn run() {
     if !env_loaded {
         log::warn!("No .env file found. Using environment variables or compile-time defaults.");
     }
+}
 
-    // Load config: runtime env vars take priority, then compile-time env vars from .env
+/// Telegram config: runtime env vars take priority, then compile-time env vars from .env
+pub(crate) fn telegram_config_from_env() -> TelegramConfig {
     let api_id: i32 = std::env::var("TELEGRAM_API_ID")
         .ok()
         .and_then(|s| s.parse().ok())
@@ -105,22 +156,17 @@ pub fn run() {
         log::error!("  TELEGRAM_API_HASH=your_api_hash");
     }
 
-    // Create shared state - will be initialized with app data dir in setup
-    let telegram_config = TelegramConfig {
+    // Session file will be updated with the app data dir in setup
+    TelegramConfig {
         api_id,
         api_hash,
-        session_file: PathBuf::from("telegram.session"), // Will be updated in setup
+        session_file: PathBuf::from("telegram.session"),
         use_test_dc,
-    };
-
-    let telegram_client = Arc::new(TelegramClient::new(telegram_config));
-    let outreach_manager = Arc::new(outreach::OutreachManager::new());
-    let outreach_manager_clone = outreach_manager.clone();
-    let rate_limiter = Arc::new(RateLimiter::new(30)); // 30 seconds min interval between messages
-    let user_hash_cache = Arc::new(offboard::UserAccessHashCache::new());
-    let chat_data_cache = Arc::new(offboard::ChatDataCache::new());
+    }
+}
 
-    // Initialize LLM client with default OpenAI config (backward compatible with env var)
+/// Default LLM client config: OpenAI (backward compatible with the OPENAI_API_KEY env var)
+pub(crate) fn default_llm_config_from_env() -> LLMConfig {
     let openai_api_key = std::env::var("OPENAI_API_KEY")
         .unwrap_or_else(|_| option_env!("OPENAI_API_KEY").unwrap_or("").to_string());
 
@@ -130,31 +176,60 @@ pub fn run() {
         log::info!("OpenAI API key configured: {}...", &openai_api_key[..8.min(openai_api_key.len())]);
     }
 
-    let default_llm_config = LLMConfig {
+    LLMConfig {
         provider: LLMProvider::OpenAI,
         base_url: "https://api.openai.com".to_string(),
         api_key: if openai_api_key.is_empty() { None } else { Some(openai_api_key) },
         model: "gpt-4o-mini".to_string(),
-    };
+        privacy_mode: false,
+        fallback: None,
+        timeout_secs: None,
+        embedding_model: None,
+        keep_alive: None,
+        extra_hosts: vec![],
+    }
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    // Initialize logging first
+    utils::log_buffer::init("info");
 
-    let llm_client = Arc::new(LLMClient::new(default_llm_config));
+    load_dotenv();
+
+    let telegram_config = telegram_config_from_env();
+    let api_id = telegram_config.api_id;
+    let use_test_dc = telegram_config.use_test_dc;
+
+    let telegram_client = Arc::new(TelegramClient::new(telegram_config));
+    let outreach_manager = Arc::new(outreach::OutreachManager::new());
+    let send_queue = Arc::new(send_queue::SendQueue::new());
+    let outreach_manager_clone = outreach_manager.clone();
+    let rate_limiter = Arc::new(RateLimiter::new(30)); // 30 seconds min interval between messages
+    let user_hash_cache = Arc::new(offboard::UserAccessHashCache::new());
+    let chat_data_cache = Arc::new(offboard::ChatDataCache::new());
+
+    let llm_client = Arc::new(LLMClient::new(default_llm_config_from_env()));
 
     // Initialize caches for AI responses and contacts
     let briefing_cache = Arc::new(BriefingCache::new());
     let summary_cache = Arc::new(SummaryCache::new());
     let contacts_cache = Arc::new(ContactsCache::new());
+    let chat_list_cache = Arc::new(ChatListCache::new());
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .manage(telegram_client.clone())
         .manage(outreach_manager.clone())
-        .manage(rate_limiter)
+        .manage(send_queue)
+        .manage(rate_limiter.clone())
         .manage(user_hash_cache)
         .manage(chat_data_cache)
         .manage(llm_client.clone())
         .manage(briefing_cache)
         .manage(summary_cache)
         .manage(contacts_cache)
+        .manage(chat_list_cache)
         .setup(move |app| {
             // Initialize database
             let app_dir = match app.path().app_data_dir() {
@@ -173,6 +248,16 @@ pub fn run() {
                 return Err(Box::new(e));
             }
 
+            // Repair or move aside corrupted files before opening them
+            let integrity = db::integrity::check_on_startup(&app_dir);
+            if !integrity.problems.is_empty() {
+                log::warn!(
+                    "Startup integrity check: problems={:?}, quarantined={:?}",
+                    integrity.problems,
+                    integrity.quarantined
+                );
+            }
+
             if let Err(e) = db::init_db(app_dir.clone()) {
                 log::error!("Failed to initialize database: {}", e);
                 return Err(Box::new(std::io::Error::new(
@@ -181,6 +266,13 @@ pub fn run() {
                 )));
             }
 
+            // Keep telling the user about lost data until they acknowledge it
+            if integrity.lost_data() {
+                if let Err(e) = db::settings::save_integrity_notice(&integrity) {
+                    log::error!("Failed to save integrity notice: {}", e);
+                }
+            }
+
             log::info!("App data directory: {:?}", app_dir);
             log::info!("Telegram Copilot started");
             log::info!("API ID configured: {}", api_id != 0);
@@ -205,8 +297,16 @@ pub fn run() {
                 }
             }
 
+            // Apply saved prompt sanitization rules
+            if let Err(e) = db::settings::apply_saved_sanitization() {
+                log::warn!("Failed to apply sanitization config: {}", e);
+            }
+            if let Err(e) = db::settings::apply_saved_local_only_ai() {
+                log::warn!("Failed to apply local-only AI setting: {}", e);
+            }
+
             // Set session file path in app data directory
-            let session_path = app_dir.join("telegram.session");
+            let session_path = app_dir.join(db::integrity::SESSION_FILE);
             telegram_client.set_session_file(session_path);
 
             // Restore outreach queues from database
@@ -217,9 +317,43 @@ pub fn run() {
                 }
             });
 
+            // Preload the local Ollama model so the first briefing doesn't wait on a cold load
+            let client = llm_client.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = client.warm_up().await {
+                    log::warn!("Failed to warm up LLM model: {}", e);
+                }
+            });
+
+            // Enforce the local data retention policy in the background
+            tauri::async_runtime::spawn(retention::retention_loop());
+
+            // Retry messages that were sent while offline
+            tauri::async_runtime::spawn(outbox::outbox_loop(app.handle().clone(), telegram_client.clone()));
+
+            // Publish content-calendar posts as they come due
+            tauri::async_runtime::spawn(scheduled_posts::scheduled_posts_loop(
+                app.handle().clone(),
+                telegram_client.clone(),
+                rate_limiter.clone(),
+            ));
+
+            // Snapshot the user's channels for growth trends
+            tauri::async_runtime::spawn(channel_stats::channel_stats_loop(telegram_client.clone()));
+
+            // Archive the chosen scope's older history, a little at a time
+            tauri::async_runtime::spawn(archive::archive_backfill_loop(app.handle().clone(), telegram_client.clone()));
+
+            // Resume cross-posts that were still sending, including scheduled ones
+            tauri::async_runtime::spawn(crosspost::resume_crossposts(telegram_client.clone(), rate_limiter.clone()));
+
             // Setup Telegram event forwarding to frontend
             setup_telegram_events(app, telegram_client.clone());
 
+            // Receive real-time updates (new messages, reads, renames) once signed in
+            let client = telegram_client.clone();
+            tauri::async_runtime::spawn(async move { client.run_update_loop().await });
+
             // Note: Telegram connection is initiated by the frontend via the `connect` IPC command.
             // Do NOT spawn a background connect here — it races with the frontend's connect call,
             // causing two simultaneous TCP connections that overwrite each other's client reference.
@@ -235,19 +369,105 @@ pub fn run() {
             auth::get_auth_state,
             auth::get_current_user,
             auth::logout,
+            // Onboarding commands
+            onboarding::get_onboarding_state,
+            onboarding::complete_onboarding_step,
+            onboarding::reset_onboarding,
             // Chat commands
             chats::get_chats,
+            chats::get_chats_page,
             chats::get_chat,
             chats::get_chat_messages,
             chats::get_batch_messages,
             chats::send_message,
+            send_queue::send_message_with_undo,
+            send_queue::undo_send,
+            send_queue::get_pending_sends,
+            send_queue::get_send_delay,
+            send_queue::set_send_delay,
+            chats::click_inline_button,
+            chats::delete_my_messages,
+            chats::mark_chat_read,
             chats::invalidate_chat_cache,
+            chats::invalidate_chat,
+            chats::set_chat_alias,
+            chats::update_chat_notes,
+            chats::set_chat_starred,
+            media::download_chat_media,
+            // Message archive commands
+            archive::sync_chat_history,
+            archive::get_archive_backfill_status,
+            archive::set_archive_backfill_config,
+            archive::get_archive_stats,
+            archive::compact_archive,
+            archive::get_recently_deleted,
+            // Away mode commands
+            away::get_away_config,
+            away::set_away_config,
+            away::get_away_replies,
+            // Pending draft commands
+            pending_drafts::get_draft_approval,
+            pending_drafts::set_draft_approval,
+            pending_drafts::get_pending_drafts,
+            pending_drafts::queue_draft,
+            pending_drafts::approve_draft,
+            pending_drafts::reject_draft,
+            // Outbox commands
+            outbox::get_outbox,
+            outbox::retry_outbox,
+            outbox::cancel_outbox_message,
+            // Bot rule commands
+            bot_rules::get_bot_rules,
+            bot_rules::add_bot_rule,
+            bot_rules::add_keyword_reply_rule,
+            bot_rules::set_bot_rule_enabled,
+            bot_rules::delete_bot_rule,
+            bot_rules::get_bot_rule_hits,
+            // Chat reminder commands
+            reminders::remind_about_chat,
+            reminders::get_due_chat_reminders,
+            reminders::complete_chat_reminder,
+            // Mention feed commands
+            mentions::get_mentions,
+            mentions::mark_mentions_seen,
+            // Call log commands
+            calls::get_missed_calls,
+            calls::get_call_log,
+            // Focus mode commands
+            focus::get_focus_state,
+            focus::set_focus_state,
+            // Notification rule commands
+            notifications::get_notification_config,
+            notifications::update_notification_config,
+            notifications::get_notification_rules,
+            notifications::set_notification_rule,
+            notifications::delete_notification_rule,
+            // Multi-device sync commands
+            sync_commands::get_sync_config,
+            sync_commands::update_sync_config,
+            sync_commands::push_sync_snapshot,
+            sync_commands::pull_sync_snapshots,
+            // Search commands
+            search::universal_search,
+            search::search_messages,
+            search::search_local_messages,
             // Contact commands
             contacts::get_contacts,
             contacts::add_contact_tag,
             contacts::remove_contact_tag,
             contacts::update_contact_notes,
             contacts::get_all_tags,
+            contacts::get_upcoming_birthdays,
+            contacts::refresh_contact_profiles,
+            contacts::find_contacts_by_bio,
+            contacts::get_contact_timeline,
+            contacts::get_person_activity,
+            contacts::suggest_contact_tags,
+            contacts::apply_contact_tags,
+            dossier::generate_contact_dossier,
+            // Follow-up commands
+            followups::get_overdue_replies,
+            followups::get_awaiting_replies,
             // Scope commands
             scopes::get_folders,
             scopes::save_scope,
@@ -258,18 +478,138 @@ pub fn run() {
             outreach::queue_outreach_messages,
             outreach::get_outreach_status,
             outreach::cancel_outreach,
+            // Crosspost commands
+            crosspost::crosspost,
+            crosspost::get_crosspost_status,
+            crosspost::get_crossposts,
+            crosspost::cancel_crosspost,
+            // Content calendar commands
+            scheduled_posts::get_postable_chats,
+            scheduled_posts::schedule_post,
+            scheduled_posts::get_scheduled_posts,
+            scheduled_posts::update_scheduled_post,
+            scheduled_posts::retry_scheduled_post,
+            scheduled_posts::cancel_scheduled_post,
+            scheduled_posts::delete_scheduled_post,
+            // Channel analytics commands
+            channel_stats::snapshot_channel,
+            channel_stats::get_channel_growth,
+            // Contact list commands
+            lists::list_contact_lists,
+            lists::create_contact_list,
+            lists::rename_contact_list,
+            lists::delete_contact_list,
+            lists::add_to_contact_list,
+            lists::remove_from_contact_list,
+            lists::reorder_contact_list,
+            // Pipeline commands
+            pipelines::list_pipelines,
+            pipelines::create_pipeline,
+            pipelines::delete_pipeline,
+            pipelines::move_contact_to_stage,
+            pipelines::remove_contact_from_pipeline,
+            pipelines::get_pipeline_board,
+            // Canned response commands
+            canned::list_canned_responses,
+            canned::save_canned_response,
+            canned::delete_canned_response,
+            canned::render_template,
+            canned::send_canned,
+            // Triage commands
+            triage::get_triage_queue,
+            triage::triage_add_contact,
+            triage::triage_block_sender,
+            triage::triage_dismiss,
+            // Unread sweep commands
+            unread::generate_unread_report,
+            // Decision tracking commands
+            decisions::extract_decisions,
+            decisions::get_decisions,
+            decisions::delete_decision,
+            // Deep analysis commands
+            deep_analysis::estimate_deep_analysis,
+            deep_analysis::deep_analysis,
+            // Scope suggestion commands
+            scope_suggestions::suggest_scopes,
+            // Standup commands
+            standup::generate_standup,
+            // Meeting prep commands
+            meeting::prepare_for_meeting,
+            // Retention commands
+            retention::get_retention_config,
+            retention::update_retention_config,
+            retention::purge_expired_data,
+            retention::repair_database,
+            retention::get_integrity_notice,
+            retention::acknowledge_integrity_notice,
             // Offboard commands
             offboard::get_common_groups,
             offboard::remove_from_group,
+            // Event RSVP commands
+            rsvp::post_event,
+            rsvp::get_events,
+            rsvp::get_event_rsvps,
+            rsvp::delete_event,
+            // Welcome kit commands
+            welcome::get_welcome_kits,
+            welcome::save_welcome_kit,
+            welcome::delete_welcome_kit,
+            welcome::send_welcome_kit,
             // AI commands
             ai_commands::generate_briefing_v2,
+            ai_commands::generate_briefing_v2_stream,
+            ai_commands::get_story_reaction_fyis,
             ai_commands::generate_batch_summaries,
+            ai_commands::summarize_chat,
+            ai_commands::analyze_message_intent,
             ai_commands::generate_draft,
             ai_commands::get_llm_config,
             ai_commands::update_llm_config,
             ai_commands::list_ollama_models_cmd,
+            ai_commands::list_local_models_cmd,
+            ai_commands::pull_ollama_model,
+            ai_commands::delete_ollama_model,
             ai_commands::test_llm_connection,
+            ai_commands::ping_llm,
+            ai_commands::check_llm_hosts,
+            ai_commands::warm_up_model,
+            ai_commands::unload_model,
+            ai_commands::get_ai_call_log,
+            ai_commands::get_ai_call_log_enabled,
+            ai_commands::set_ai_call_log_enabled,
+            ai_commands::clear_ai_call_log,
+            ai_commands::get_local_only_ai,
+            ai_commands::set_local_only_ai,
+            #[cfg(debug_assertions)]
+            ai_commands::run_briefing_eval,
             ai_commands::is_llm_configured,
+            ai_commands::correct_briefing_item,
+            ai_commands::clear_briefing_correction,
+            ai_commands::invalidate_briefing_for_chats,
+            ai_commands::get_briefing_pins,
+            ai_commands::set_briefing_pin,
+            ai_commands::get_briefing_categories,
+            ai_commands::save_briefing_category,
+            ai_commands::reorder_briefing_categories,
+            ai_commands::delete_briefing_category,
+            ai_commands::get_sanitization_config,
+            ai_commands::update_sanitization_config,
+            ai_commands::get_sanitization_presets,
+            ai_commands::clear_llm_cache,
+            ai_commands::get_injection_audit,
+            ai_commands::clear_injection_audit,
+            // Export commands
+            export_commands::export_briefing,
+            export_commands::export_scope_messages,
+            export_commands::sync_summaries_to_folder,
+            export_commands::get_summary_sync_folder,
+            export_commands::create_debug_bundle,
+            export_commands::export_config,
+            export_commands::import_config,
+            // Briefing delivery commands
+            delivery::get_briefing_delivery,
+            delivery::update_briefing_delivery,
+            delivery::deliver_briefing,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
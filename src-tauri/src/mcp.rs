@@ -0,0 +1,393 @@
+//! Model Context Protocol server over stdio.
+//!
+//! Exposes the user's Telegram data as MCP tools so external assistants (Claude Desktop,
+//! other MCP clients) can search messages, look up contacts and send messages. Everything
+//! returned to the client goes through the same sanitization as our own prompts.
+//!
+//! Sends never go out from here: they are queued as pending drafts that the user approves
+//! in the app, so a model (or a prompt injected into it) can't confirm its own messages.
+
+use crate::ai::sanitize::{sanitize_chat_title, sanitize_message_text, sanitize_sender_name};
+use crate::commands::contacts::load_contacts_with_metadata;
+use crate::db::pending_drafts::{self as db_drafts, SOURCE_MCP};
+use crate::telegram::client::{ChatFilters, Message};
+use crate::telegram::{TelegramApi, TelegramClient};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// Telegram rejects messages longer than this
+const MAX_MESSAGE_LENGTH: usize = 4096;
+
+const MAX_SEARCH_LIMIT: i32 = 100;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+pub struct McpServer {
+    telegram: TelegramClient,
+}
+
+impl McpServer {
+    pub fn new(telegram: TelegramClient) -> Self {
+        Self { telegram }
+    }
+
+    /// Serve newline-delimited JSON-RPC on stdin/stdout until stdin closes.
+    /// Logs go to stderr so they never corrupt the protocol stream.
+    pub async fn serve_stdio(&self) -> Result<(), String> {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+
+        log::info!("MCP server listening on stdio");
+
+        while let Some(line) = lines
+            .next_line()
+            .await
+            .map_err(|e| format!("Failed to read stdin: {}", e))?
+        {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(request) => self.handle_request(request).await,
+                Err(e) => Some(error_response(Value::Null, PARSE_ERROR, &format!("Parse error: {}", e))),
+            };
+
+            if let Some(response) = response {
+                let mut out = response.to_string();
+                out.push('\n');
+                stdout
+                    .write_all(out.as_bytes())
+                    .await
+                    .map_err(|e| format!("Failed to write stdout: {}", e))?;
+                stdout
+                    .flush()
+                    .await
+                    .map_err(|e| format!("Failed to flush stdout: {}", e))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a single JSON-RPC message. Notifications (no id) get no response.
+    async fn handle_request(&self, request: Value) -> Option<Value> {
+        let id = request.get("id").cloned();
+        let method = request.get("method").and_then(Value::as_str).unwrap_or("");
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": {
+                    "name": "telegram-copilot",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(&params).await,
+            _ if method.starts_with("notifications/") => return None,
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+        };
+
+        // Requests without an id are notifications
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    /// Dispatch tools/call. Tool failures are reported as `isError` results, not protocol errors.
+    async fn call_tool(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+        log::info!("MCP tool call: {}", name);
+
+        let outcome = match name {
+            "search_messages" => self.tool_search_messages(&args).await,
+            "get_contacts" => self.tool_get_contacts(&args).await,
+            "list_chats" => self.tool_list_chats(&args).await,
+            "send_message" => queue_send(&self.telegram, &args).await,
+            "get_send_status" => send_status(&args),
+            _ => return Err((INVALID_PARAMS, format!("Unknown tool: {}", name))),
+        };
+
+        Ok(match outcome {
+            Ok(text) => json!({ "content": [{ "type": "text", "text": text }], "isError": false }),
+            Err(e) => json!({ "content": [{ "type": "text", "text": e }], "isError": true }),
+        })
+    }
+
+    async fn tool_search_messages(&self, args: &Value) -> Result<String, String> {
+        let query = args
+            .get("query")
+            .and_then(Value::as_str)
+            .filter(|q| !q.trim().is_empty())
+            .ok_or("query is required")?;
+        let chat_id = args.get("chat_id").and_then(Value::as_i64);
+        let limit = arg_limit(args, 20).min(MAX_SEARCH_LIMIT);
+
+        let messages = self.telegram.search_messages(query, chat_id, limit).await?;
+        let items: Vec<Value> = messages.iter().map(message_json).collect();
+        to_pretty(&json!({ "messages": items }))
+    }
+
+    async fn tool_get_contacts(&self, args: &Value) -> Result<String, String> {
+        let tag = args.get("tag").and_then(Value::as_str);
+        let contacts = load_contacts_with_metadata(&self.telegram).await?;
+
+        let items: Vec<Value> = contacts
+            .iter()
+            .filter(|c| tag.map_or(true, |t| c.tags.iter().any(|ct| ct.eq_ignore_ascii_case(t))))
+            .map(|c| {
+                json!({
+                    "user_id": c.user_id,
+                    "name": sanitize_sender_name(format!("{} {}", c.first_name, c.last_name).trim()),
+                    "username": c.username,
+                    "tags": c.tags,
+                    "notes": sanitize_message_text(&c.notes),
+                    "days_since_contact": c.days_since_contact,
+                    "unread_count": c.unread_count,
+                })
+            })
+            .collect();
+        to_pretty(&json!({ "contacts": items }))
+    }
+
+    async fn tool_list_chats(&self, args: &Value) -> Result<String, String> {
+        let unread_only = args.get("unread_only").and_then(Value::as_bool).unwrap_or(false);
+        let filters = ChatFilters {
+            include_private_chats: true,
+            include_non_contacts: true,
+            include_groups: true,
+            include_channels: true,
+            include_unread_only: unread_only,
+            ..Default::default()
+        };
+        let mut chats = self.telegram.get_chats(arg_limit(args, 50), Some(filters)).await?;
+        crate::db::apply_local_chat_state(&mut chats);
+
+        let items: Vec<Value> = chats
+            .iter()
+            .map(|c| {
+                json!({
+                    "chat_id": c.id,
+                    "title": sanitize_chat_title(&c.title),
+                    "type": c.chat_type,
+                    "unread_count": c.unread_count,
+                })
+            })
+            .collect();
+        to_pretty(&json!({ "chats": items }))
+    }
+}
+
+/// Queue a message as a pending draft for the user to approve in the app. Nothing
+/// the client passes can approve it, so the model only ever gets the draft's status.
+async fn queue_send(telegram: &dyn TelegramApi, args: &Value) -> Result<String, String> {
+    let chat_id = args.get("chat_id").and_then(Value::as_i64).ok_or("chat_id is required")?;
+    let text = args
+        .get("text")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or("text is required")?;
+    if text.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(format!("Message is longer than {} characters", MAX_MESSAGE_LENGTH));
+    }
+
+    let chat = telegram
+        .get_chat(chat_id)
+        .await?
+        .ok_or_else(|| format!("Chat {} not found", chat_id))?;
+    let draft_id = db_drafts::add_draft(chat_id, text, SOURCE_MCP)?;
+    log::info!("MCP send to chat {} queued as draft {}", chat_id, draft_id);
+
+    to_pretty(&json!({
+        "status": "awaiting_approval",
+        "draft_id": draft_id,
+        "chat": sanitize_chat_title(&chat.title),
+        "text": text,
+        "instructions": "The message is waiting in Telegram Copilot's pending drafts and is only sent if the user approves it there. Use get_send_status to check on it.",
+    }))
+}
+
+fn send_status(args: &Value) -> Result<String, String> {
+    let draft_id = args.get("draft_id").and_then(Value::as_i64).ok_or("draft_id is required")?;
+    let draft = db_drafts::load_draft(draft_id)?
+        .filter(|d| d.source == SOURCE_MCP)
+        .ok_or_else(|| format!("No message {} was queued over MCP", draft_id))?;
+    to_pretty(&json!({ "draft_id": draft.id, "chat_id": draft.chat_id, "status": draft.status }))
+}
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "search_messages",
+            "description": "Search Telegram messages by text, across all chats or within one chat.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Text to search for" },
+                    "chat_id": { "type": "integer", "description": "Restrict the search to this chat" },
+                    "limit": { "type": "integer", "description": "Maximum results (default 20, max 100)" }
+                },
+                "required": ["query"]
+            }
+        },
+        {
+            "name": "get_contacts",
+            "description": "List Telegram contacts with their copilot tags, notes and days since last contact.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tag": { "type": "string", "description": "Only return contacts with this tag" }
+                }
+            }
+        },
+        {
+            "name": "list_chats",
+            "description": "List recent Telegram chats with their ids and unread counts.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "unread_only": { "type": "boolean", "description": "Only chats with unread messages" },
+                    "limit": { "type": "integer", "description": "Maximum chats (default 50)" }
+                }
+            }
+        },
+        {
+            "name": "send_message",
+            "description": "Propose a Telegram message. It is queued for the user to approve in the Telegram Copilot app and only sent once they do.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "chat_id": { "type": "integer", "description": "Chat to send to" },
+                    "text": { "type": "string", "description": "Message text" }
+                },
+                "required": ["chat_id", "text"]
+            }
+        },
+        {
+            "name": "get_send_status",
+            "description": "Whether a message proposed with send_message is still pending, was sent or was rejected by the user.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "draft_id": { "type": "integer", "description": "draft_id returned by send_message" }
+                },
+                "required": ["draft_id"]
+            }
+        }
+    ])
+}
+
+/// Message as returned to MCP clients, with user-controlled fields sanitized
+fn message_json(message: &Message) -> Value {
+    let text = sanitize_message_text(&message.content.describe());
+    json!({
+        "id": message.id,
+        "chat_id": message.chat_id,
+        "sender": sanitize_sender_name(&message.sender_name),
+        "date": chrono::DateTime::from_timestamp(message.date, 0).map(|dt| dt.to_rfc3339()),
+        "outgoing": message.is_outgoing,
+        "text": text,
+    })
+}
+
+fn arg_limit(args: &Value, default: i32) -> i32 {
+    args.get("limit")
+        .and_then(Value::as_i64)
+        .map(|l| l.clamp(1, i32::MAX as i64) as i32)
+        .unwrap_or(default)
+}
+
+fn to_pretty(value: &Value) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| format!("Failed to serialize result: {}", e))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::pending_drafts::approve;
+    use crate::db::pending_drafts::{STATUS_PENDING, STATUS_REJECTED};
+    use crate::telegram::fake::{self, FakeTelegram};
+    use crate::utils::rate_limiter::RateLimiter;
+
+    #[tokio::test]
+    async fn test_send_message_only_queues_a_draft() {
+        crate::db::init_test_db();
+        let telegram = FakeTelegram::new(fake::user(1, "Me", "")).with_chat(fake::chat(7495, "private", "Ivan", 0, 0));
+
+        // Whatever the model claims, nothing is sent from the MCP side
+        let args = json!({ "chat_id": 7495, "text": "Wire the money", "confirmation_token": "x", "approved": true });
+        let reply: Value = serde_json::from_str(&queue_send(&telegram, &args).await.unwrap()).unwrap();
+        assert_eq!(reply["status"], "awaiting_approval");
+        assert!(telegram.sent().is_empty());
+
+        let draft_id = reply["draft_id"].as_i64().unwrap();
+        let draft = db_drafts::load_draft(draft_id).unwrap().unwrap();
+        assert_eq!((draft.chat_id, draft.text.as_str(), draft.source.as_str()), (7495, "Wire the money", SOURCE_MCP));
+
+        let status = |id: i64| -> Value { serde_json::from_str(&send_status(&json!({ "draft_id": id })).unwrap()).unwrap() };
+        assert_eq!(status(draft_id)["status"], STATUS_PENDING);
+        db_drafts::resolve_draft(draft_id, STATUS_REJECTED, &draft.text).unwrap();
+        assert_eq!(status(draft_id)["status"], STATUS_REJECTED);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_validates_before_queueing() {
+        crate::db::init_test_db();
+        let telegram = FakeTelegram::new(fake::user(1, "Me", ""));
+
+        assert!(queue_send(&telegram, &json!({ "chat_id": 7496, "text": "  " })).await.is_err());
+        assert!(queue_send(&telegram, &json!({ "chat_id": 7496, "text": "hi" })).await.unwrap_err().contains("not found"));
+
+        // Drafts from other sources aren't visible over MCP
+        let other = db_drafts::add_draft(7496, "Away reply", db_drafts::SOURCE_AWAY).unwrap();
+        assert!(send_status(&json!({ "draft_id": other })).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_approved_mcp_sends_respect_the_rate_limiter() {
+        crate::db::init_test_db();
+        let telegram = FakeTelegram::new(fake::user(1, "Me", "")).with_chat(fake::chat(7661, "private", "Judy", 0, 0));
+        let limiter = RateLimiter::new(60);
+
+        // A burst of model-queued messages to one chat: approving them doesn't fire them back-to-back
+        let mut ids = Vec::new();
+        for text in ["First", "Second"] {
+            let queued = queue_send(&telegram, &json!({ "chat_id": 7661, "text": text })).await.unwrap();
+            let reply: Value = serde_json::from_str(&queued).unwrap();
+            ids.push(reply["draft_id"].as_i64().unwrap());
+        }
+        approve(&telegram, &limiter, ids[0], None).await.unwrap();
+        assert!(approve(&telegram, &limiter, ids[1], None).await.unwrap_err().contains("Rate limited"));
+
+        assert_eq!(telegram.sent(), vec![(7661, "First".to_string())]);
+        assert_eq!(db_drafts::load_draft(ids[1]).unwrap().unwrap().status, STATUS_PENDING);
+    }
+
+    #[test]
+    fn test_send_tool_takes_no_confirmation() {
+        let tools = tool_definitions();
+        let send = tools.as_array().unwrap().iter().find(|t| t["name"] == "send_message").unwrap();
+        assert!(send["inputSchema"]["properties"].get("confirmation_token").is_none());
+    }
+}
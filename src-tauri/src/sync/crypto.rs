@@ -0,0 +1,74 @@
+//! Password-based encryption for sync payloads: PBKDF2-HMAC-SHA256 derives an
+//! AES-256-GCM key, and the salt and nonce travel with the ciphertext.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::num::NonZeroU32;
+
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+const PBKDF2_ITERATIONS: u32 = 200_000;
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<LessSafeKey, String> {
+    let iterations = NonZeroU32::new(PBKDF2_ITERATIONS).expect("iterations are non-zero");
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, password.as_bytes(), &mut key);
+    let unbound = UnboundKey::new(&AES_256_GCM, &key).map_err(|_| "Failed to create encryption key".to_string())?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+/// Encrypt `plaintext` and return base64(salt || nonce || ciphertext)
+pub fn encrypt(password: &str, plaintext: &[u8]) -> Result<String, String> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut salt).map_err(|_| "Failed to generate salt".to_string())?;
+    rng.fill(&mut nonce).map_err(|_| "Failed to generate nonce".to_string())?;
+
+    let key = derive_key(password, &salt)?;
+    let mut sealed = plaintext.to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+        .map_err(|_| "Failed to encrypt sync payload".to_string())?;
+
+    let mut blob = Vec::with_capacity(SALT_LEN + NONCE_LEN + sealed.len());
+    blob.extend_from_slice(&salt);
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&sealed);
+    Ok(BASE64.encode(blob))
+}
+
+/// Reverse of `encrypt`; fails on a wrong password or a tampered blob
+pub fn decrypt(password: &str, encoded: &str) -> Result<Vec<u8>, String> {
+    let blob = BASE64
+        .decode(encoded.trim())
+        .map_err(|e| format!("Invalid sync payload encoding: {}", e))?;
+    if blob.len() < SALT_LEN + NONCE_LEN {
+        return Err("Sync payload is too short".to_string());
+    }
+
+    let (salt, rest) = blob.split_at(SALT_LEN);
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| "Invalid sync payload nonce".to_string())?;
+
+    let key = derive_key(password, salt)?;
+    let mut sealed = sealed.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::empty(), &mut sealed)
+        .map_err(|_| "Failed to decrypt sync payload (wrong sync password?)".to_string())?;
+    Ok(plaintext.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let blob = encrypt("correct horse", b"{\"tags\":[]}").unwrap();
+        assert_eq!(decrypt("correct horse", &blob).unwrap(), b"{\"tags\":[]}");
+        assert!(decrypt("wrong horse", &blob).is_err());
+    }
+}
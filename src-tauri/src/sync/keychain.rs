@@ -0,0 +1,30 @@
+//! The sync password lives in the OS keychain rather than in SQLite, so it
+//! never reaches the frontend or sits in plaintext next to the data it protects.
+
+const SERVICE: &str = "telegram-copilot";
+const ACCOUNT: &str = "sync-password";
+
+fn entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, ACCOUNT).map_err(|e| format!("Failed to open keychain: {}", e))
+}
+
+pub fn load_password() -> Result<Option<String>, String> {
+    match entry()?.get_password() {
+        Ok(password) => Ok(Some(password).filter(|p| !p.is_empty())),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read sync password from keychain: {}", e)),
+    }
+}
+
+pub fn save_password(password: &str) -> Result<(), String> {
+    entry()?
+        .set_password(password)
+        .map_err(|e| format!("Failed to save sync password to keychain: {}", e))
+}
+
+pub fn delete_password() -> Result<(), String> {
+    match entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete sync password from keychain: {}", e)),
+    }
+}
@@ -0,0 +1,298 @@
+//! Multi-device sync without a server: local metadata (contact tags, notes and
+//! scopes) is serialized, encrypted and posted to Saved Messages, where other
+//! devices signed in to the same account pick it up and merge it.
+//!
+//! Merging is additive: tags are unioned, the most recently edited note wins,
+//! and scopes are matched by name with the most recently updated one winning.
+//! Deletions are not propagated.
+
+pub mod crypto;
+pub mod keychain;
+
+use crate::db;
+use crate::db::scopes::ScopeProfile;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Hashtag that starts every sync message so it can be found with message search
+pub const SYNC_TAG: &str = "#tgcopilot_sync";
+
+/// Encrypted payload characters per message (Telegram caps messages at 4096)
+const CHUNK_CHARS: usize = 3500;
+
+const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactMeta {
+    pub user_id: i64,
+    pub tags: Vec<String>,
+    pub notes: String,
+    pub notes_updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSnapshot {
+    pub version: u32,
+    pub device_id: String,
+    pub created_at: i64,
+    pub contacts: Vec<ContactMeta>,
+    pub scopes: Vec<ScopeProfile>,
+}
+
+/// Changes a remote snapshot would make to local data
+#[derive(Debug, Default)]
+pub struct MergePlan {
+    pub tags: Vec<(i64, String)>,
+    /// (user_id, notes, updated_at)
+    pub notes: Vec<(i64, String, i64)>,
+    pub scopes: Vec<ScopeProfile>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeStats {
+    pub snapshots: usize,
+    pub tags_added: usize,
+    pub notes_updated: usize,
+    pub scopes_updated: usize,
+}
+
+/// Snapshot of this device's metadata
+pub fn collect_snapshot(device_id: &str) -> Result<SyncSnapshot, String> {
+    let mut contacts: HashMap<i64, ContactMeta> = HashMap::new();
+    let blank = |user_id| ContactMeta { user_id, tags: vec![], notes: String::new(), notes_updated_at: 0 };
+
+    for (user_id, tag) in db::contacts::load_all_contact_tags()? {
+        contacts.entry(user_id).or_insert_with(|| blank(user_id)).tags.push(tag);
+    }
+    for (user_id, notes, updated_at) in db::contacts::load_all_contact_notes()? {
+        let meta = contacts.entry(user_id).or_insert_with(|| blank(user_id));
+        meta.notes = notes;
+        meta.notes_updated_at = updated_at;
+    }
+
+    let mut contacts: Vec<ContactMeta> = contacts.into_values().collect();
+    contacts.sort_by_key(|c| c.user_id);
+
+    Ok(SyncSnapshot {
+        version: SNAPSHOT_VERSION,
+        device_id: device_id.to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+        contacts,
+        scopes: db::scopes::load_all_scopes()?,
+    })
+}
+
+/// Work out what `remote` adds to `local`
+pub fn plan_merge(local: &SyncSnapshot, remote: &SyncSnapshot) -> MergePlan {
+    let local_tags: HashSet<(i64, &str)> = local
+        .contacts
+        .iter()
+        .flat_map(|c| c.tags.iter().map(move |t| (c.user_id, t.as_str())))
+        .collect();
+    let local_notes: HashMap<i64, (&str, i64)> = local
+        .contacts
+        .iter()
+        .map(|c| (c.user_id, (c.notes.as_str(), c.notes_updated_at)))
+        .collect();
+    let local_scopes: HashMap<&str, &ScopeProfile> = local.scopes.iter().map(|s| (s.name.as_str(), s)).collect();
+
+    let mut plan = MergePlan::default();
+    for contact in &remote.contacts {
+        for tag in &contact.tags {
+            if !local_tags.contains(&(contact.user_id, tag.as_str())) {
+                plan.tags.push((contact.user_id, tag.clone()));
+            }
+        }
+
+        let newer = match local_notes.get(&contact.user_id) {
+            Some((notes, updated_at)) => contact.notes_updated_at > *updated_at && contact.notes != *notes,
+            None => true,
+        };
+        if newer && !contact.notes.is_empty() {
+            plan.notes.push((contact.user_id, contact.notes.clone(), contact.notes_updated_at));
+        }
+    }
+
+    for scope in &remote.scopes {
+        match local_scopes.get(scope.name.as_str()) {
+            // Keep the local id and default flag; only the filter config travels
+            Some(existing) if scope.updated_at > existing.updated_at => plan.scopes.push(ScopeProfile {
+                id: existing.id.clone(),
+                is_default: existing.is_default,
+                ..scope.clone()
+            }),
+            Some(_) => {}
+            None => plan.scopes.push(ScopeProfile { is_default: false, ..scope.clone() }),
+        }
+    }
+
+    plan
+}
+
+/// Write a merge plan to the database
+pub fn apply_merge(plan: &MergePlan, stats: &mut MergeStats) -> Result<(), String> {
+    for (user_id, tag) in &plan.tags {
+        db::contacts::add_contact_tag(*user_id, tag)?;
+    }
+    for (user_id, notes, updated_at) in &plan.notes {
+        db::contacts::set_contact_notes_at(*user_id, notes, *updated_at)?;
+    }
+    for scope in &plan.scopes {
+        db::scopes::save_scope(scope)?;
+    }
+
+    stats.tags_added += plan.tags.len();
+    stats.notes_updated += plan.notes.len();
+    stats.scopes_updated += plan.scopes.len();
+    Ok(())
+}
+
+/// Split an encrypted payload into message texts: "#tag <snapshot_id> <i>/<n>\n<chunk>"
+pub fn encode_messages(snapshot_id: &str, payload: &str) -> Vec<String> {
+    // The payload is base64, so byte chunks are valid UTF-8
+    let chunks: Vec<&str> = payload
+        .as_bytes()
+        .chunks(CHUNK_CHARS)
+        .map(|c| std::str::from_utf8(c).unwrap_or_default())
+        .collect();
+    let total = chunks.len();
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| format!("{} {} {}/{}\n{}", SYNC_TAG, snapshot_id, i + 1, total, chunk))
+        .collect()
+}
+
+/// Parse one sync message into (snapshot_id, index, total, chunk)
+fn parse_message(text: &str) -> Option<(String, usize, usize, String)> {
+    let (header, body) = text.split_once('\n')?;
+    let mut parts = header.split_whitespace();
+    if parts.next()? != SYNC_TAG {
+        return None;
+    }
+    let snapshot_id = parts.next()?.to_string();
+    let (index, total) = parts.next()?.split_once('/')?;
+    let (index, total) = (index.parse().ok()?, total.parse().ok()?);
+    (index >= 1 && index <= total).then(|| (snapshot_id, index, total, body.trim().to_string()))
+}
+
+/// The snapshot a sync message belongs to
+pub fn snapshot_id_of(text: &str) -> Option<String> {
+    parse_message(text).map(|(snapshot_id, ..)| snapshot_id)
+}
+
+/// Reassemble (snapshot_id, payload) pairs from sync messages; snapshots
+/// missing a part are skipped
+pub fn decode_messages<'a>(texts: impl IntoIterator<Item = &'a str>) -> Vec<(String, String)> {
+    let mut parts: HashMap<String, (usize, HashMap<usize, String>)> = HashMap::new();
+    for text in texts {
+        if let Some((snapshot_id, index, total, chunk)) = parse_message(text) {
+            let entry = parts.entry(snapshot_id).or_insert_with(|| (total, HashMap::new()));
+            entry.1.insert(index, chunk);
+        }
+    }
+
+    parts
+        .into_iter()
+        .filter_map(|(snapshot_id, (total, chunks))| {
+            (1..=total)
+                .map(|i| chunks.get(&i).map(String::as_str))
+                .collect::<Option<Vec<&str>>>()
+                .map(|chunks| (snapshot_id, chunks.concat()))
+        })
+        .collect()
+}
+
+/// This device's snapshots beyond the newest `keep`, which can be deleted.
+/// Other devices' snapshots are left for them to prune.
+pub fn stale_snapshots(snapshots: &[(String, SyncSnapshot)], device_id: &str, keep: usize) -> Vec<String> {
+    let mut own: Vec<&(String, SyncSnapshot)> = snapshots.iter().filter(|(_, s)| s.device_id == device_id).collect();
+    own.sort_by_key(|(_, s)| std::cmp::Reverse(s.created_at));
+    own.into_iter().skip(keep).map(|(id, _)| id.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::scopes::ScopeConfig;
+
+    fn scope(id: &str, name: &str, updated_at: i64) -> ScopeProfile {
+        ScopeProfile {
+            id: id.to_string(),
+            name: name.to_string(),
+            config: ScopeConfig {
+                folder_ids: vec![],
+                chat_types: vec![],
+                excluded_chat_ids: vec![],
+                included_chat_ids: vec![],
+                contact_list_ids: vec![],
+            },
+            is_default: true,
+            created_at: 0,
+            updated_at,
+        }
+    }
+
+    fn snapshot(contacts: Vec<ContactMeta>, scopes: Vec<ScopeProfile>) -> SyncSnapshot {
+        SyncSnapshot { version: 1, device_id: "d".to_string(), created_at: 0, contacts, scopes }
+    }
+
+    fn contact(user_id: i64, tags: &[&str], notes: &str, notes_updated_at: i64) -> ContactMeta {
+        ContactMeta {
+            user_id,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            notes: notes.to_string(),
+            notes_updated_at,
+        }
+    }
+
+    #[test]
+    fn test_plan_merge() {
+        let local = snapshot(
+            vec![contact(1, &["work"], "old", 100), contact(2, &[], "mine", 300)],
+            vec![scope("a", "Work", 100), scope("b", "Family", 500)],
+        );
+        let remote = snapshot(
+            vec![contact(1, &["work", "vip"], "new", 200), contact(2, &[], "theirs", 200), contact(3, &[], "", 0)],
+            vec![scope("x", "Work", 200), scope("y", "Family", 400), scope("z", "Investors", 1)],
+        );
+
+        let plan = plan_merge(&local, &remote);
+        assert_eq!(plan.tags, vec![(1, "vip".to_string())]);
+        assert_eq!(plan.notes, vec![(1, "new".to_string(), 200)]);
+
+        let scopes: Vec<(&str, &str, bool)> = plan.scopes.iter().map(|s| (s.id.as_str(), s.name.as_str(), s.is_default)).collect();
+        assert_eq!(scopes, vec![("a", "Work", true), ("z", "Investors", false)]);
+    }
+
+    #[test]
+    fn test_message_chunking() {
+        let payload = "A".repeat(CHUNK_CHARS * 2 + 10);
+        let mut messages = encode_messages("s1", &payload);
+        assert_eq!(messages.len(), 3);
+        assert!(messages[0].starts_with("#tgcopilot_sync s1 1/3\n"));
+
+        messages.reverse();
+        let decoded = decode_messages(messages.iter().map(String::as_str));
+        assert_eq!(decoded, vec![("s1".to_string(), payload)]);
+        assert_eq!(snapshot_id_of(&messages[0]).as_deref(), Some("s1"));
+
+        // Incomplete snapshots are dropped
+        assert!(decode_messages(messages[..2].iter().map(String::as_str)).is_empty());
+    }
+
+    #[test]
+    fn test_stale_snapshots_keeps_newest_per_device() {
+        let at = |id: &str, device_id: &str, created_at: i64| {
+            (id.to_string(), SyncSnapshot { device_id: device_id.to_string(), created_at, ..snapshot(vec![], vec![]) })
+        };
+        let snapshots = vec![at("a", "me", 100), at("b", "me", 300), at("c", "other", 50), at("d", "me", 200)];
+
+        assert_eq!(stale_snapshots(&snapshots, "me", 2), vec!["a".to_string()]);
+        assert_eq!(stale_snapshots(&snapshots, "me", 0).len(), 3);
+        assert!(stale_snapshots(&snapshots, "other", 1).is_empty());
+    }
+}
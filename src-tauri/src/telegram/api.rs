@@ -0,0 +1,98 @@
+//! The network calls commands make against Telegram, as a trait so they can run
+//! against an in-memory fake in tests (see `fake`).
+
+use super::client::{Chat, ChatFilters, Message, User};
+use super::TelegramClient;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait TelegramApi: Send + Sync {
+    async fn get_current_user(&self) -> Option<User>;
+    async fn get_chats(&self, limit: i32, filters: Option<ChatFilters>) -> Result<Vec<Chat>, String>;
+    async fn get_chat(&self, chat_id: i64) -> Result<Option<Chat>, String>;
+    /// Load the dialog cache unless it already is, so `cached_chat` finds chats
+    async fn load_chat_cache(&self) -> Result<(), String>;
+    /// A chat from the dialog cache, without any request to Telegram
+    async fn cached_chat(&self, chat_id: i64) -> Option<Chat>;
+    /// Every chat in the dialog cache, in dialog order
+    async fn cached_chats(&self) -> Vec<Chat>;
+    async fn get_chat_messages(
+        &self,
+        chat_id: i64,
+        limit: i32,
+        from_message_id: Option<i64>,
+    ) -> Result<Vec<Message>, String>;
+    async fn search_messages(&self, query: &str, chat_id: Option<i64>, limit: i32) -> Result<Vec<Message>, String>;
+    async fn get_contacts(&self) -> Result<Vec<User>, String>;
+    async fn send_message(&self, chat_id: i64, text: &str) -> Result<Message, String>;
+    async fn forward_message(&self, from_chat_id: i64, message_id: i64, to_chat_id: i64) -> Result<(), String>;
+    async fn send_media(&self, chat_id: i64, caption: &str, file_path: &str) -> Result<Message, String>;
+    /// Mark incoming messages read up to `up_to_message_id`, or all of them
+    async fn mark_chat_read(&self, chat_id: i64, up_to_message_id: Option<i64>) -> Result<(), String>;
+    /// Delete messages for everyone, returning how many were deleted
+    async fn delete_messages(&self, chat_id: i64, message_ids: &[i32]) -> Result<usize, String>;
+}
+
+#[async_trait]
+impl TelegramApi for TelegramClient {
+    async fn get_current_user(&self) -> Option<User> {
+        TelegramClient::get_current_user(self).await
+    }
+
+    async fn get_chats(&self, limit: i32, filters: Option<ChatFilters>) -> Result<Vec<Chat>, String> {
+        TelegramClient::get_chats(self, limit, filters).await
+    }
+
+    async fn get_chat(&self, chat_id: i64) -> Result<Option<Chat>, String> {
+        TelegramClient::get_chat(self, chat_id).await
+    }
+
+    async fn load_chat_cache(&self) -> Result<(), String> {
+        TelegramClient::load_chat_cache(self).await
+    }
+
+    async fn cached_chat(&self, chat_id: i64) -> Option<Chat> {
+        TelegramClient::cached_chat(self, chat_id).await
+    }
+
+    async fn cached_chats(&self) -> Vec<Chat> {
+        TelegramClient::cached_chats(self).await
+    }
+
+    async fn get_chat_messages(
+        &self,
+        chat_id: i64,
+        limit: i32,
+        from_message_id: Option<i64>,
+    ) -> Result<Vec<Message>, String> {
+        TelegramClient::get_chat_messages(self, chat_id, limit, from_message_id).await
+    }
+
+    async fn search_messages(&self, query: &str, chat_id: Option<i64>, limit: i32) -> Result<Vec<Message>, String> {
+        TelegramClient::search_messages(self, query, chat_id, limit).await
+    }
+
+    async fn get_contacts(&self) -> Result<Vec<User>, String> {
+        TelegramClient::get_contacts(self).await
+    }
+
+    async fn send_message(&self, chat_id: i64, text: &str) -> Result<Message, String> {
+        TelegramClient::send_message(self, chat_id, text).await
+    }
+
+    async fn forward_message(&self, from_chat_id: i64, message_id: i64, to_chat_id: i64) -> Result<(), String> {
+        TelegramClient::forward_message(self, from_chat_id, message_id, to_chat_id).await
+    }
+
+    async fn send_media(&self, chat_id: i64, caption: &str, file_path: &str) -> Result<Message, String> {
+        TelegramClient::send_media(self, chat_id, caption, file_path).await
+    }
+
+    async fn mark_chat_read(&self, chat_id: i64, up_to_message_id: Option<i64>) -> Result<(), String> {
+        TelegramClient::mark_chat_read(self, chat_id, up_to_message_id).await
+    }
+
+    async fn delete_messages(&self, chat_id: i64, message_ids: &[i32]) -> Result<usize, String> {
+        TelegramClient::delete_messages(self, chat_id, message_ids).await
+    }
+}
@@ -1,12 +1,16 @@
-use grammers_client::{Client, Config, InitParams, SignInError};
-use grammers_client::types::PasswordToken;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use grammers_client::{Client, Config, InitParams, SignInError, Update};
+use grammers_client::types::{ChatMap, PasswordToken};
 use grammers_session::Session;
 use grammers_tl_types as tl;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock as StdRwLock};
-use tokio::sync::{broadcast, RwLock, Mutex, Semaphore};
+use tokio::sync::{broadcast, watch, RwLock, Mutex, Semaphore};
+
+use super::dialogs::DialogMapper;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
@@ -51,6 +55,15 @@ pub struct Chat {
     pub is_bot: bool,
     #[serde(default)]
     pub is_contact: bool,
+    /// Telegram title when a local alias replaces `title`
+    #[serde(default)]
+    pub original_title: Option<String>,
+    /// Starred in the copilot (see `db::stars`)
+    #[serde(default)]
+    pub is_starred: bool,
+    /// The user's notes on this chat (see `db::chat_notes`)
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -103,6 +116,14 @@ pub struct Message {
     pub date: i64,
     pub is_outgoing: bool,
     pub is_read: bool,
+    #[serde(default)]
+    pub reply_to_message_id: Option<i64>,
+    /// Telegram flags messages that @mention the user or reply to one of their messages
+    #[serde(default)]
+    pub mentions_me: bool,
+    /// Rows of inline keyboard buttons attached by a bot (empty for most messages)
+    #[serde(default)]
+    pub inline_keyboard: Vec<Vec<InlineButton>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -114,12 +135,428 @@ pub enum MessageContent {
     Document {
         #[serde(rename = "fileName")]
         file_name: String,
+        #[serde(default)]
+        caption: Option<String>,
     },
     Voice { duration: i32 },
     Sticker { emoji: Option<String> },
+    /// Someone mentioned the user in their story
+    StoryMention {
+        #[serde(rename = "storyId")]
+        story_id: i32,
+    },
+    /// A story shared into the chat
+    Story {
+        #[serde(rename = "storyId")]
+        story_id: i32,
+    },
+    // Service messages
+    /// Empty `user_ids` means the sender joined (by invite link or join request)
+    MemberJoined {
+        #[serde(rename = "userIds")]
+        user_ids: Vec<i64>,
+    },
+    MemberLeft {
+        #[serde(rename = "userId")]
+        user_id: i64,
+    },
+    TitleChanged { title: String },
+    MessagePinned,
+    /// One-to-one call; `duration` is None when the call never connected
+    Call { video: bool, missed: bool, duration: Option<i32> },
+    /// Voice chat in a group: started, or ended after `duration` seconds
+    GroupCall { duration: Option<i32> },
+    /// Telegram Premium gifted directly or through a gift code
+    PremiumGift { months: i32 },
+    StarsGift { stars: i64 },
     Unknown,
 }
 
+impl MessageContent {
+    /// Map a message's text and media. For media messages the text is the caption.
+    pub fn from_parts(text: &str, media: Option<&tl::enums::MessageMedia>) -> Self {
+        let caption = (!text.is_empty()).then(|| text.to_string());
+        match media {
+            Some(tl::enums::MessageMedia::Photo(_)) => MessageContent::Photo { caption },
+            Some(tl::enums::MessageMedia::Document(document)) => Self::from_document(document, caption),
+            Some(tl::enums::MessageMedia::Story(story)) if story.via_mention => {
+                MessageContent::StoryMention { story_id: story.id }
+            }
+            Some(tl::enums::MessageMedia::Story(story)) => MessageContent::Story { story_id: story.id },
+            // Link previews, polls, locations, ... are shown as their text when there is any
+            _ => match caption {
+                Some(text) => MessageContent::Text { text },
+                None => MessageContent::Unknown,
+            },
+        }
+    }
+
+    /// Map a service message's action, if it's one we show
+    pub fn from_action(action: &tl::enums::MessageAction) -> Option<Self> {
+        use tl::enums::MessageAction as A;
+        Some(match action {
+            A::ChatAddUser(a) => MessageContent::MemberJoined { user_ids: a.users.clone() },
+            A::ChatJoinedByLink(_) | A::ChatJoinedByRequest => MessageContent::MemberJoined { user_ids: vec![] },
+            A::ChatDeleteUser(a) => MessageContent::MemberLeft { user_id: a.user_id },
+            A::ChatEditTitle(a) => MessageContent::TitleChanged { title: a.title.clone() },
+            A::PinMessage => MessageContent::MessagePinned,
+            A::PhoneCall(call) => MessageContent::Call {
+                video: call.video,
+                missed: matches!(call.reason, Some(tl::enums::PhoneCallDiscardReason::Missed)),
+                duration: call.duration,
+            },
+            A::GroupCall(call) => MessageContent::GroupCall { duration: call.duration },
+            A::GiftPremium(gift) => MessageContent::PremiumGift { months: gift.months },
+            A::GiftCode(gift) => MessageContent::PremiumGift { months: gift.months },
+            A::GiftStars(gift) => MessageContent::StarsGift { stars: gift.stars },
+            _ => return None,
+        })
+    }
+
+    fn from_document(media: &tl::types::MessageMediaDocument, caption: Option<String>) -> Self {
+        let Some(tl::enums::Document::Document(document)) = &media.document else {
+            return Self::from_parts(caption.as_deref().unwrap_or(""), None);
+        };
+
+        let mut file_name = None;
+        let mut audio = None;
+        let mut is_video = false;
+        for attribute in &document.attributes {
+            match attribute {
+                tl::enums::DocumentAttribute::Sticker(sticker) => {
+                    let emoji = Some(sticker.alt.clone()).filter(|e| !e.is_empty());
+                    return MessageContent::Sticker { emoji };
+                }
+                tl::enums::DocumentAttribute::Filename(f) => file_name = Some(f.file_name.clone()),
+                tl::enums::DocumentAttribute::Audio(a) => audio = Some(a),
+                tl::enums::DocumentAttribute::Video(_) => is_video = true,
+                _ => {}
+            }
+        }
+
+        match audio {
+            Some(a) if a.voice || media.voice => return MessageContent::Voice { duration: a.duration },
+            _ => {}
+        }
+        // Includes round video messages and GIFs
+        if is_video {
+            return MessageContent::Video { caption };
+        }
+
+        let file_name = file_name
+            .filter(|name| !name.is_empty())
+            .or_else(|| audio.and_then(|a| a.title.clone()))
+            .unwrap_or_else(|| document.mime_type.clone());
+        MessageContent::Document { file_name, caption }
+    }
+
+    /// The serialized `type` tag ("text", "photo", "storyMention", ...)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MessageContent::Text { .. } => "text",
+            MessageContent::Photo { .. } => "photo",
+            MessageContent::Video { .. } => "video",
+            MessageContent::Document { .. } => "document",
+            MessageContent::Voice { .. } => "voice",
+            MessageContent::Sticker { .. } => "sticker",
+            MessageContent::StoryMention { .. } => "storyMention",
+            MessageContent::Story { .. } => "story",
+            MessageContent::MemberJoined { .. } => "memberJoined",
+            MessageContent::MemberLeft { .. } => "memberLeft",
+            MessageContent::TitleChanged { .. } => "titleChanged",
+            MessageContent::MessagePinned => "messagePinned",
+            MessageContent::Call { .. } => "call",
+            MessageContent::GroupCall { .. } => "groupCall",
+            MessageContent::PremiumGift { .. } => "premiumGift",
+            MessageContent::StarsGift { .. } => "starsGift",
+            MessageContent::Unknown => "unknown",
+        }
+    }
+
+    /// One-line plain-text rendering (AI prompts, CLI output, MCP results)
+    pub fn describe(&self) -> String {
+        let with_caption = |label: &str, caption: &Option<String>| match caption {
+            Some(caption) => format!("[{}] {}", label, caption),
+            None => format!("[{}]", label),
+        };
+        match self {
+            MessageContent::Text { text } => text.clone(),
+            MessageContent::Photo { caption } => with_caption("Photo", caption),
+            MessageContent::Video { caption } => with_caption("Video", caption),
+            MessageContent::Document { file_name, caption } => {
+                with_caption(&format!("Document: {}", file_name), caption)
+            }
+            MessageContent::Voice { duration } => format!("[Voice message, {}s]", duration),
+            MessageContent::Sticker { emoji: Some(emoji) } => format!("[Sticker {}]", emoji),
+            MessageContent::Sticker { emoji: None } => "[Sticker]".to_string(),
+            MessageContent::StoryMention { .. } => "[Mentioned you in a story]".to_string(),
+            MessageContent::Story { .. } => "[Shared a story]".to_string(),
+            MessageContent::MemberJoined { user_ids } => match user_ids.len() {
+                0 => "[Joined the chat]".to_string(),
+                1 => "[Added a member]".to_string(),
+                n => format!("[Added {} members]", n),
+            },
+            MessageContent::MemberLeft { .. } => "[Member left]".to_string(),
+            MessageContent::TitleChanged { title } => format!("[Changed the chat title to \"{}\"]", title),
+            MessageContent::MessagePinned => "[Pinned a message]".to_string(),
+            MessageContent::Call { video, missed, duration } => {
+                let kind = if *video { "video call" } else { "call" };
+                match (missed, duration) {
+                    (true, _) => format!("[Missed {}]", kind),
+                    (false, Some(duration)) => format!("[Ended {}, {}s]", kind, duration),
+                    (false, None) => format!("[Cancelled {}]", kind),
+                }
+            }
+            MessageContent::GroupCall { duration: None } => "[Started a voice chat]".to_string(),
+            MessageContent::GroupCall { duration: Some(duration) } => {
+                format!("[Voice chat ended, {}s]", duration)
+            }
+            MessageContent::PremiumGift { months: 1 } => "[Gifted Telegram Premium for 1 month]".to_string(),
+            MessageContent::PremiumGift { months } => format!("[Gifted Telegram Premium for {} months]", months),
+            MessageContent::StarsGift { stars } => format!("[Gifted {} Telegram Stars]", stars),
+            MessageContent::Unknown => "[Media]".to_string(),
+        }
+    }
+}
+
+/// A button of a bot's inline keyboard. Callback buttons carry their payload as
+/// base64 in `data`, to be passed back to `click_inline_button`; URL buttons
+/// carry the link. Other kinds (games, payments, web apps) are shown but can't
+/// be pressed from here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineButton {
+    pub text: String,
+    #[serde(default)]
+    pub data: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl InlineButton {
+    fn from_raw(button: &tl::enums::KeyboardButton) -> Self {
+        let text = |text: &str| InlineButton { text: text.to_string(), data: None, url: None };
+        match button {
+            tl::enums::KeyboardButton::Callback(b) => InlineButton {
+                data: Some(BASE64.encode(&b.data)),
+                ..text(&b.text)
+            },
+            tl::enums::KeyboardButton::Url(b) => InlineButton { url: Some(b.url.clone()), ..text(&b.text) },
+            tl::enums::KeyboardButton::UrlAuth(b) => InlineButton { url: Some(b.url.clone()), ..text(&b.text) },
+            tl::enums::KeyboardButton::Button(b) => text(&b.text),
+            tl::enums::KeyboardButton::SwitchInline(b) => text(&b.text),
+            tl::enums::KeyboardButton::Game(b) => text(&b.text),
+            tl::enums::KeyboardButton::Buy(b) => text(&b.text),
+            tl::enums::KeyboardButton::WebView(b) => text(&b.text),
+            tl::enums::KeyboardButton::SimpleWebView(b) => text(&b.text),
+            tl::enums::KeyboardButton::UserProfile(b) => text(&b.text),
+            _ => text("[Button]"),
+        }
+    }
+
+    /// Rows of an inline keyboard; reply keyboards (shown in place of the
+    /// input field) aren't attached to the message and are dropped
+    pub fn keyboard(markup: Option<&tl::enums::ReplyMarkup>) -> Vec<Vec<InlineButton>> {
+        match markup {
+            Some(tl::enums::ReplyMarkup::ReplyInlineMarkup(markup)) => markup
+                .rows
+                .iter()
+                .map(|tl::enums::KeyboardButtonRow::Row(row)| row.buttons.iter().map(Self::from_raw).collect())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A bot's answer to an inline button press
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BotCallbackAnswer {
+    /// Toast or alert text, if the bot sent one
+    pub message: Option<String>,
+    /// Show `message` as a modal alert rather than a toast
+    pub alert: bool,
+    pub url: Option<String>,
+}
+
+/// One user's reaction to a message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerReaction {
+    pub user_id: i64,
+    pub name: String,
+    /// The emoji, or None for custom emoji and paid reactions
+    pub emoji: Option<String>,
+    pub date: i64,
+}
+
+/// Someone's reaction to one of the user's own stories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryReaction {
+    pub story_id: i32,
+    #[serde(flatten)]
+    pub reaction: PeerReaction,
+}
+
+/// Text search over messages, across all chats or within one, with optional
+/// sender and date filters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSearch {
+    pub query: String,
+    #[serde(default)]
+    pub chat_id: Option<i64>,
+    #[serde(default)]
+    pub sender_id: Option<i64>,
+    /// Unix seconds, inclusive
+    #[serde(default)]
+    pub min_date: Option<i64>,
+    /// Unix seconds, inclusive
+    #[serde(default)]
+    pub max_date: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<i32>,
+    /// `next_offset` of the previous page
+    #[serde(default)]
+    pub offset: Option<String>,
+}
+
+/// One page of search results, newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSearchPage {
+    pub messages: Vec<Message>,
+    /// Pass back as `offset` for the next page; None on the last page
+    pub next_offset: Option<String>,
+    /// Telegram's (possibly approximate) count of all matches
+    pub total: i32,
+}
+
+/// A message's photo, document or voice note saved to disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadedMedia {
+    pub chat_id: i64,
+    pub message_id: i64,
+    pub path: String,
+    pub file_name: String,
+    pub mime_type: Option<String>,
+    pub size: i64,
+}
+
+/// Local name for a message's file: `<message id>_<original name>`, or an
+/// extension guessed from the MIME type when the file has no name
+fn media_file_name(message_id: i64, name: &str, mime_type: Option<&str>) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| !c.is_control() && !matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|'))
+        .collect();
+    let name = name.trim().trim_start_matches('.');
+    if !name.is_empty() {
+        return format!("{}_{}", message_id, name);
+    }
+    let extension = match mime_type {
+        Some("image/jpeg") => "jpg",
+        Some("audio/ogg") => "ogg",
+        Some("audio/mpeg") => "mp3",
+        Some("video/mp4") => "mp4",
+        Some(mime) => mime
+            .rsplit('/')
+            .next()
+            .filter(|ext| !ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+            .unwrap_or("bin"),
+        None => "bin",
+    };
+    format!("{}.{}", message_id, extension)
+}
+
+/// View count of one channel post
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostViews {
+    pub message_id: i64,
+    pub date: i64,
+    pub views: i32,
+}
+
+/// Subscriber count of a broadcast channel and the views of its latest posts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelStats {
+    pub subscriber_count: i32,
+    pub posts: Vec<PostViews>,
+}
+
+/// Inline style for a range of outgoing message text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextStyle {
+    Bold,
+    Italic,
+}
+
+/// Styled range; offset and length are in UTF-16 code units, as Telegram expects
+#[derive(Debug, Clone)]
+pub struct TextEntity {
+    pub style: TextStyle,
+    pub offset: i32,
+    pub length: i32,
+}
+
+/// Message text with formatting entities
+#[derive(Debug, Clone, Default)]
+pub struct FormattedText {
+    pub text: String,
+    pub entities: Vec<TextEntity>,
+}
+
+impl FormattedText {
+    /// Length in UTF-16 code units (Telegram's unit for limits and entity offsets)
+    pub fn utf16_len(&self) -> i32 {
+        self.text.encode_utf16().count() as i32
+    }
+
+    pub fn push(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    pub fn push_styled(&mut self, text: &str, style: TextStyle) {
+        let length = text.encode_utf16().count() as i32;
+        if length > 0 {
+            self.entities.push(TextEntity { style, offset: self.utf16_len(), length });
+        }
+        self.text.push_str(text);
+    }
+
+    /// Append another formatted text, shifting its entities
+    pub fn append(&mut self, other: FormattedText) {
+        let shift = self.utf16_len();
+        self.text.push_str(&other.text);
+        self.entities.extend(other.entities.into_iter().map(|e| TextEntity { offset: e.offset + shift, ..e }));
+    }
+}
+
+/// Birthday from a user's profile (year is optional on Telegram)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Birthday {
+    pub day: u32,
+    pub month: u32,
+    pub year: Option<i32>,
+}
+
+/// Profile details only available through users.getFullUser
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfile {
+    pub user_id: i64,
+    pub bio: Option<String>,
+    pub username: Option<String>,
+    pub is_premium: bool,
+    pub is_verified: bool,
+    pub birthday: Option<Birthday>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Folder {
@@ -159,20 +596,40 @@ pub struct CommonChat {
     pub raw_chat: tl::enums::Chat,
 }
 
-/// Events emitted by the Telegram client.
-/// Note: Some variants (ChatUpdated, UserUpdated, Error) are set up for future
-/// real-time update handling. Handlers exist in lib.rs but emission isn't
-/// yet implemented for all update types.
+/// Events emitted by the Telegram client. Messages, chats and users are kept
+/// current by the update loop (`run_update_loop`).
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub enum TelegramEvent {
     AuthStateChanged(AuthState),
     NewMessage(Message),
+    /// An existing message's text or media changed
+    MessageEdited(Message),
+    /// Messages were deleted. Telegram names the chat only for channels and
+    /// supergroups, so `chat_ids` are the chats the messages may belong to.
+    MessagesDeleted { chat_ids: Vec<i64>, message_ids: Vec<i64> },
+    /// A chat's unread count or last message changed
     ChatUpdated(Chat),
+    /// A user changed their name or username
     UserUpdated(User),
+    /// The connection was re-established after a failure
+    Reconnected,
+    /// Handled in lib.rs, but nothing reports errors this way yet
+    #[allow(dead_code)]
     Error(String),
 }
 
+/// Pause after failing to receive updates, or while not signed in
+const UPDATE_RETRY_SECS: u64 = 5;
+
+/// The id `Chat::id` uses for a peer
+fn peer_id(peer: &tl::enums::Peer) -> i64 {
+    match peer {
+        tl::enums::Peer::User(p) => p.user_id,
+        tl::enums::Peer::Chat(p) => p.chat_id,
+        tl::enums::Peer::Channel(p) => p.channel_id,
+    }
+}
+
 /// Configuration for Telegram client
 #[derive(Debug, Clone)]
 pub struct TelegramConfig {
@@ -208,8 +665,12 @@ pub struct TelegramClient {
     // Chat cache to avoid repeated GetDialogs calls
     chat_cache: Arc<RwLock<HashMap<i64, grammers_client::types::Chat>>>,
     cache_loaded: Arc<RwLock<bool>>,
+    // Dialog state (unread, pinned, ...) for cached chats
+    dialogs: DialogMapper,
     // Semaphore to prevent concurrent dialog loading
     dialog_semaphore: Arc<Semaphore>,
+    // Bumped when the client is replaced or signed out, so the update loop lets go of the old one
+    client_generation: watch::Sender<u64>,
 }
 
 impl TelegramClient {
@@ -227,7 +688,9 @@ impl TelegramClient {
             phone_number: Arc::new(RwLock::new(None)),
             chat_cache: Arc::new(RwLock::new(HashMap::new())),
             cache_loaded: Arc::new(RwLock::new(false)),
+            dialogs: DialogMapper::default(),
             dialog_semaphore: Arc::new(Semaphore::new(1)), // Only one dialog load at a time
+            client_generation: watch::channel(0).0,
         }
     }
 
@@ -259,7 +722,7 @@ impl TelegramClient {
     }
 
     /// Check if an error message indicates a connection failure that can be retried
-    fn is_connection_error(error: &str) -> bool {
+    pub(crate) fn is_connection_error(error: &str) -> bool {
         error.contains("read error")
             || error.contains("IO failed")
             || error.contains("read 0 bytes")
@@ -304,13 +767,141 @@ impl TelegramClient {
         // Clear cache since connection was reset
         *self.cache_loaded.write().await = false;
         self.chat_cache.write().await.clear();
+        self.dialogs.clear();
 
         *self.client.write().await = Some(client);
+        self.client_generation.send_modify(|generation| *generation += 1);
         log::info!("Reconnected successfully");
+        self.emit_event(TelegramEvent::Reconnected);
 
         Ok(())
     }
 
+    /// Receive updates from Telegram for as long as the app runs, keeping the chat
+    /// cache current and emitting `NewMessage`, `ChatUpdated` and `UserUpdated`
+    pub async fn run_update_loop(&self) {
+        loop {
+            // Subscribed before reading the client, so a replacement in between isn't missed
+            let mut replaced = self.client_generation.subscribe();
+            let client = self.client.read().await.clone();
+            let ready = matches!(self.get_auth_state().await, AuthState::Ready);
+            let Some(client) = client.filter(|_| ready) else {
+                tokio::time::sleep(std::time::Duration::from_secs(UPDATE_RETRY_SECS)).await;
+                continue;
+            };
+
+            // Only a replaced client interrupts a pending update; a timeout could
+            // cancel it in the middle of catching up on missed updates
+            tokio::select! {
+                result = client.next_raw_update() => match result {
+                    Ok((update, chats)) => self.handle_update(&client, update, &chats).await,
+                    Err(e) => {
+                        log::warn!("Failed to receive Telegram updates: {}", e);
+                        tokio::time::sleep(std::time::Duration::from_secs(UPDATE_RETRY_SECS)).await;
+                    }
+                },
+                // Go round to pick up the new client, or wait for sign-in after a logout
+                _ = replaced.changed() => {}
+            }
+        }
+    }
+
+    async fn handle_update(&self, client: &Client, update: tl::enums::Update, chats: &Arc<ChatMap>) {
+        match update {
+            tl::enums::Update::ReadHistoryInbox(read) => {
+                self.apply_unread_count(peer_id(&read.peer), read.still_unread_count).await;
+            }
+            tl::enums::Update::ReadChannelInbox(read) => {
+                self.apply_unread_count(read.channel_id, read.still_unread_count).await;
+            }
+            tl::enums::Update::UserName(update) => self.apply_user_name(update).await,
+            update => match Update::new(client, update, chats) {
+                Some(Update::NewMessage(raw)) => {
+                    let message = Self::convert_message(&raw);
+                    // A chat we haven't seen yet (new DM, newly joined group)
+                    if let Some(chat) = chats.get(&raw.raw.peer_id) {
+                        self.chat_cache.write().await.entry(chat.id()).or_insert_with(|| chat.clone());
+                    }
+                    self.dialogs.apply_message(&message);
+                    self.emit_event(TelegramEvent::NewMessage(message.clone()));
+                    self.emit_chat_updated(message.chat_id).await;
+                }
+                Some(Update::MessageEdited(raw)) => {
+                    let message = Self::convert_message(&raw);
+                    if self.dialogs.apply_edit(&message) {
+                        self.emit_chat_updated(message.chat_id).await;
+                    }
+                    self.emit_event(TelegramEvent::MessageEdited(message));
+                }
+                Some(Update::MessageDeleted(deletion)) => {
+                    let chat_ids = match deletion.channel_id() {
+                        Some(channel_id) => vec![channel_id],
+                        // Private chats and basic groups number messages per account
+                        None => self
+                            .chat_cache
+                            .read()
+                            .await
+                            .values()
+                            .filter(|chat| match chat {
+                                grammers_client::types::Chat::User(_) => true,
+                                grammers_client::types::Chat::Group(group) => {
+                                    !matches!(group.raw, tl::enums::Chat::Channel(_) | tl::enums::Chat::ChannelForbidden(_))
+                                }
+                                grammers_client::types::Chat::Channel(_) => false,
+                            })
+                            .map(|chat| chat.id())
+                            .collect(),
+                    };
+                    let message_ids = deletion.messages().iter().map(|&id| id as i64).collect();
+                    self.emit_event(TelegramEvent::MessagesDeleted { chat_ids, message_ids });
+                }
+                _ => {}
+            },
+        }
+    }
+
+    async fn apply_unread_count(&self, chat_id: i64, unread_count: i32) {
+        if self.dialogs.set_unread_count(chat_id, unread_count) {
+            self.emit_chat_updated(chat_id).await;
+        }
+    }
+
+    /// Rename a cached user
+    async fn apply_user_name(&self, update: tl::types::UpdateUserName) {
+        let username = update.usernames.into_iter().find_map(|username| match username {
+            tl::enums::Username::Username(u) if u.active => Some(u.username),
+            _ => None,
+        });
+
+        let user = {
+            let mut cache = self.chat_cache.write().await;
+            let Some(grammers_client::types::Chat::User(cached)) = cache.get_mut(&update.user_id) else {
+                return;
+            };
+            cached.raw.first_name = Some(update.first_name.clone());
+            cached.raw.last_name = Some(update.last_name.clone());
+            cached.raw.username = username.clone();
+            User {
+                id: update.user_id,
+                first_name: update.first_name,
+                last_name: update.last_name,
+                username,
+                phone_number: cached.raw.phone.clone(),
+                profile_photo_url: None,
+            }
+        };
+
+        self.emit_event(TelegramEvent::UserUpdated(user));
+        self.emit_chat_updated(update.user_id).await;
+    }
+
+    /// Emit `ChatUpdated` with the cached chat's current state
+    async fn emit_chat_updated(&self, chat_id: i64) {
+        if let Some(chat) = self.get_cached_chat(chat_id).await {
+            self.emit_event(TelegramEvent::ChatUpdated(self.dialogs.map_cached(&chat)));
+        }
+    }
+
     /// Subscribe to Telegram events
     pub fn subscribe(&self) -> broadcast::Receiver<TelegramEvent> {
         self.event_tx.subscribe()
@@ -335,6 +926,12 @@ impl TelegramClient {
         self.current_user.read().await.clone()
     }
 
+    /// Whether TELEGRAM_API_ID / TELEGRAM_API_HASH were provided
+    pub fn has_api_credentials(&self) -> bool {
+        let config = self.config.read().unwrap();
+        config.api_id != 0 && !config.api_hash.is_empty()
+    }
+
     /// Connect to Telegram and check if already authorized
     pub async fn connect(&self) -> Result<bool, String> {
         log::info!("Connecting to Telegram...");
@@ -388,6 +985,7 @@ impl TelegramClient {
             .map_err(|e| format!("Failed to save session after connect: {}", e))?;
 
         *self.client.write().await = Some(client);
+        self.client_generation.send_modify(|generation| *generation += 1);
 
         Ok(is_authorized)
     }
@@ -532,9 +1130,11 @@ impl TelegramClient {
         // Clear chat cache to prevent data leaking between accounts
         *self.cache_loaded.write().await = false;
         self.chat_cache.write().await.clear();
+        self.dialogs.clear();
 
         *self.current_user.write().await = None;
         self.set_auth_state(AuthState::WaitPhoneNumber).await;
+        self.client_generation.send_modify(|generation| *generation += 1);
 
         Ok(())
     }
@@ -569,6 +1169,7 @@ impl TelegramClient {
                 break;
             }
 
+            self.dialogs.record(&dialog);
             let chat = dialog.chat;
             cache.insert(chat.id(), chat);
             count += 1;
@@ -580,6 +1181,24 @@ impl TelegramClient {
         Ok(())
     }
 
+    /// Load the chat cache for lookups through `cached_chat`
+    pub async fn load_chat_cache(&self) -> Result<(), String> {
+        self.ensure_cache_loaded(200).await
+    }
+
+    /// A chat with its dialog state from the cache only
+    pub async fn cached_chat(&self, chat_id: i64) -> Option<Chat> {
+        let chat = self.get_cached_chat(chat_id).await?;
+        Some(self.dialogs.map_cached(&chat))
+    }
+
+    /// Every cached chat with its dialog state, in dialog order
+    pub async fn cached_chats(&self) -> Vec<Chat> {
+        let mut chats: Vec<Chat> = self.chat_cache.read().await.values().map(|c| self.dialogs.map_cached(c)).collect();
+        chats.sort_by_key(|c| c.order);
+        chats
+    }
+
     /// Get a chat from cache by ID
     async fn get_cached_chat(&self, chat_id: i64) -> Option<grammers_client::types::Chat> {
         self.chat_cache.read().await.get(&chat_id).cloned()
@@ -587,10 +1206,84 @@ impl TelegramClient {
 
     /// Invalidate the chat cache (call when chats might have changed).
     /// TODO: Call this when receiving chat update events.
-    #[allow(dead_code)]
     pub async fn invalidate_cache(&self) {
         *self.cache_loaded.write().await = false;
         self.chat_cache.write().await.clear();
+        self.dialogs.clear();
+    }
+
+    /// Fetch one chat's dialog (unread count, last message, ...) again, leaving the
+    /// rest of the cache alone (with auto-reconnect on connection failure)
+    pub async fn refresh_chat(&self, chat_id: i64) -> Result<Option<Chat>, String> {
+        log::info!("Refreshing chat {}", chat_id);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.refresh_chat_inner(chat_id).await {
+            Ok(chat) => Ok(chat),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error refreshing chat, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.refresh_chat_inner(chat_id).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn refresh_chat_inner(&self, chat_id: i64) -> Result<Option<Chat>, String> {
+        self.ensure_cache_loaded(200).await?;
+        let Some(chat) = self.get_cached_chat(chat_id).await else {
+            return Ok(None);
+        };
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        let tl::enums::messages::PeerDialogs::Dialogs(peer_dialogs) = client
+            .invoke(&tl::functions::messages::GetPeerDialogs {
+                peers: vec![tl::enums::InputDialogPeer::Peer(tl::types::InputDialogPeer {
+                    peer: chat.pack().to_input_peer(),
+                })],
+            })
+            .await
+            .map_err(|e| format!("Failed to get dialog: {}", e))?;
+        let raw = peer_dialogs
+            .dialogs
+            .first()
+            .ok_or_else(|| format!("No dialog for chat {}", chat_id))?;
+        let last_message = client
+            .iter_messages(&chat)
+            .limit(1)
+            .next()
+            .await
+            .map_err(|e| format!("Failed to get last message: {}", e))?
+            .map(|m| Self::convert_message(&m));
+
+        self.dialogs.record_raw(chat_id, raw, last_message);
+        let refreshed = self.dialogs.map_cached(&chat);
+        self.emit_event(TelegramEvent::ChatUpdated(refreshed.clone()));
+        Ok(Some(refreshed))
+    }
+
+    /// Titles of cached chats, the senders of their last messages (group members)
+    /// and the signed-in user's name and phone, used to scrub personal data from diagnostics
+    pub async fn known_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = vec![];
+        for chat in self.cached_chats().await {
+            names.extend(chat.last_message.map(|m| m.sender_name));
+            names.push(chat.title);
+        }
+
+        if let Some(user) = self.current_user.read().await.as_ref() {
+            names.push(format!("{} {}", user.first_name, user.last_name).trim().to_string());
+            names.extend(user.username.clone());
+            names.extend(user.phone_number.clone());
+        }
+        if let Some(phone) = self.phone_number.read().await.as_ref() {
+            names.push(phone.clone());
+        }
+
+        names.retain(|name| !name.trim().is_empty());
+        names
     }
 
     /// Get a single chat by ID (optimized for fast lookups)
@@ -613,7 +1306,7 @@ impl TelegramClient {
     async fn get_chat_inner(&self, chat_id: i64) -> Result<Option<Chat>, String> {
         // 1. Try cache first (fast path)
         if let Some(chat) = self.get_cached_chat(chat_id).await {
-            return Ok(Some(self.convert_cached_chat_to_chat(&chat)));
+            return Ok(Some(self.dialogs.map_cached(&chat)));
         }
 
         // 2. Cache miss - load cache if not loaded
@@ -621,59 +1314,13 @@ impl TelegramClient {
 
         // 3. Try cache again
         if let Some(chat) = self.get_cached_chat(chat_id).await {
-            return Ok(Some(self.convert_cached_chat_to_chat(&chat)));
+            return Ok(Some(self.dialogs.map_cached(&chat)));
         }
 
         // Chat not found
         Ok(None)
     }
 
-    /// Convert a cached grammers chat to our Chat type
-    fn convert_cached_chat_to_chat(&self, chat: &grammers_client::types::Chat) -> Chat {
-        let (chat_type, is_bot, is_contact) = match chat {
-            grammers_client::types::Chat::User(u) => {
-                ("private", u.is_bot(), u.raw.contact)
-            }
-            grammers_client::types::Chat::Group(_) => ("group", false, false),
-            grammers_client::types::Chat::Channel(_) => ("channel", false, false),
-        };
-
-        let title = match chat {
-            grammers_client::types::Chat::User(u) => {
-                format!("{} {}", u.first_name(), u.last_name().unwrap_or(""))
-            }
-            grammers_client::types::Chat::Group(g) => g.title().to_string(),
-            grammers_client::types::Chat::Channel(c) => c.title().to_string(),
-        };
-
-        let member_count = match chat {
-            grammers_client::types::Chat::User(_) => None,
-            grammers_client::types::Chat::Group(g) => {
-                match &g.raw {
-                    tl::enums::Chat::Chat(c) => Some(c.participants_count),
-                    _ => None,
-                }
-            }
-            grammers_client::types::Chat::Channel(c) => c.raw.participants_count,
-        };
-
-        Chat {
-            id: chat.id(),
-            chat_type: chat_type.to_string(),
-            title: title.trim().to_string(),
-            unread_count: 0, // Not available from cached chat alone
-            is_pinned: false, // Not available from cached chat alone
-            order: 0,
-            photo: None,
-            last_message: None,
-            member_count,
-            is_muted: false,
-            is_archived: false,
-            is_bot,
-            is_contact,
-        }
-    }
-
     /// Get chat list (dialogs) with optional filters (with auto-reconnect on connection failure)
     pub async fn get_chats(&self, limit: i32, filters: Option<ChatFilters>) -> Result<Vec<Chat>, String> {
         log::info!("Getting chats, limit: {}", limit);
@@ -710,262 +1357,77 @@ impl TelegramClient {
                 break;
             }
 
-            // Check if this is an archived folder
-            let is_archived = match &dialog.raw {
-                tl::enums::Dialog::Dialog(d) => d.folder_id == Some(1),
-                tl::enums::Dialog::Folder(_) => continue, // Skip folder entries themselves
-            };
-
-            // Skip archived chats if not included (unless in a selected folder - checked below)
-            // Note: We check folder membership first to allow archived chats from selected folders
+            // Skip folder entries themselves
+            if let tl::enums::Dialog::Folder(_) = &dialog.raw {
+                continue;
+            }
 
-            let chat = dialog.chat();
+            // Every dialog is cached for message retrieval, whether or not it passes the filters
+            let chat = self.dialogs.map(&dialog);
+            cache.insert(chat.id, dialog.chat.clone());
 
             // EARLY EXIT: If chat is in selected folders, include it (bypass all other filters)
             // This implements OR logic: folder chats show regardless of type/muted/archived/size filters
-            if !filters.folder_chat_ids.is_empty() && filters.folder_chat_ids.contains(&chat.id()) {
-                // Chat is in a selected folder - extract info and add to results
-                let (chat_type, is_bot, is_contact) = match chat {
-                    grammers_client::types::Chat::User(u) => {
-                        ("private", u.is_bot(), u.raw.contact)
-                    }
-                    grammers_client::types::Chat::Group(_) => ("group", false, false),
-                    grammers_client::types::Chat::Channel(_) => ("channel", false, false),
-                };
-
-                let title = match chat {
-                    grammers_client::types::Chat::User(u) => {
-                        format!("{} {}", u.first_name(), u.last_name().unwrap_or(""))
-                    }
-                    grammers_client::types::Chat::Group(g) => g.title().to_string(),
-                    grammers_client::types::Chat::Channel(c) => c.title().to_string(),
-                };
-
-                let last_message = dialog.last_message.as_ref().map(|msg| {
-                    let text = msg.text();
-                    let content = if !text.is_empty() {
-                        MessageContent::Text { text: text.to_string() }
-                    } else if msg.photo().is_some() {
-                        MessageContent::Photo { caption: None }
-                    } else {
-                        MessageContent::Unknown
-                    };
-
-                    Message {
-                        id: msg.id() as i64,
-                        chat_id: chat.id(),
-                        sender_id: msg.sender().map(|s| s.id()).unwrap_or(0),
-                        sender_name: msg.sender().map(|s| s.name().to_string()).unwrap_or_default(),
-                        content,
-                        date: msg.date().timestamp(),
-                        is_outgoing: msg.outgoing(),
-                        is_read: true,
-                    }
-                });
-
-                let unread_count = match &dialog.raw {
-                    tl::enums::Dialog::Dialog(d) => d.unread_count,
-                    tl::enums::Dialog::Folder(_) => 0,
-                };
-
-                let is_pinned = match &dialog.raw {
-                    tl::enums::Dialog::Dialog(d) => d.pinned,
-                    tl::enums::Dialog::Folder(_) => false,
-                };
-
-                let is_muted = match &dialog.raw {
-                    tl::enums::Dialog::Dialog(d) => {
-                        match &d.notify_settings {
-                            tl::enums::PeerNotifySettings::Settings(settings) => {
-                                settings.mute_until.map(|t| t > 0).unwrap_or(false) || settings.silent.unwrap_or(false)
-                            }
-                        }
-                    }
-                    tl::enums::Dialog::Folder(_) => false,
-                };
-
-                let member_count = match chat {
-                    grammers_client::types::Chat::User(_) => None,
-                    grammers_client::types::Chat::Group(g) => {
-                        match &g.raw {
-                            tl::enums::Chat::Chat(c) => Some(c.participants_count),
-                            _ => None,
-                        }
-                    }
-                    grammers_client::types::Chat::Channel(c) => {
-                        c.raw.participants_count
-                    }
-                };
-
-                // Cache and add to results
-                cache.insert(chat.id(), dialog.chat.clone());
-
-                chats.push(Chat {
-                    id: chat.id(),
-                    chat_type: chat_type.to_string(),
-                    title: title.trim().to_string(),
-                    unread_count,
-                    is_pinned,
-                    order: -(dialog.last_message.as_ref().map(|m| m.date().timestamp()).unwrap_or(0)),
-                    photo: None,
-                    last_message,
-                    member_count,
-                    is_muted,
-                    is_archived,
-                    is_bot,
-                    is_contact,
-                });
-
+            if !filters.folder_chat_ids.is_empty() && filters.folder_chat_ids.contains(&chat.id) {
+                chats.push(chat);
                 count += 1;
                 continue;
             }
 
             // Skip archived chats if not included
-            if is_archived && !filters.include_archived {
-                // Still cache for message retrieval
-                cache.insert(dialog.chat.id(), dialog.chat.clone());
+            if chat.is_archived && !filters.include_archived {
                 continue;
             }
 
-            // Determine chat type, check if it's a bot, and check contact status
-            let (chat_type, is_bot, is_contact) = match chat {
-                grammers_client::types::Chat::User(u) => {
-                    let is_bot = u.is_bot();
-                    // Check if user is a contact from the raw User data
-                    let is_contact = u.raw.contact;
-                    ("private", is_bot, is_contact)
-                }
-                grammers_client::types::Chat::Group(_) => ("group", false, false),
-                grammers_client::types::Chat::Channel(_) => ("channel", false, false),
-            };
-
             // Apply type filters
-            match chat_type {
+            match chat.chat_type.as_str() {
                 "private" => {
-                    if is_bot {
+                    if chat.is_bot {
                         if !filters.include_bots {
-                            cache.insert(chat.id(), dialog.chat.clone());
                             continue;
                         }
                     } else {
                         // Non-bot private chat - contacts and non-contacts are independent filters
-                        if is_contact && !filters.include_private_chats {
+                        if chat.is_contact && !filters.include_private_chats {
                             // Contact but contacts filter is off
-                            cache.insert(chat.id(), dialog.chat.clone());
                             continue;
                         }
-                        if !is_contact && !filters.include_non_contacts {
+                        if !chat.is_contact && !filters.include_non_contacts {
                             // Non-contact but non-contacts filter is off
-                            cache.insert(chat.id(), dialog.chat.clone());
                             continue;
                         }
                     }
                 }
                 "group" => {
                     if !filters.include_groups {
-                        cache.insert(chat.id(), dialog.chat.clone());
                         continue;
                     }
                 }
                 "channel" => {
                     if !filters.include_channels {
-                        cache.insert(chat.id(), dialog.chat.clone());
                         continue;
                     }
                 }
                 _ => {}
             }
 
-            // Check muted status from notify settings
-            let is_muted = match &dialog.raw {
-                tl::enums::Dialog::Dialog(d) => {
-                    match &d.notify_settings {
-                        tl::enums::PeerNotifySettings::Settings(settings) => {
-                            // mute_until > 0 or silent = true means muted
-                            settings.mute_until.map(|t| t > 0).unwrap_or(false) || settings.silent.unwrap_or(false)
-                        }
-                    }
-                }
-                tl::enums::Dialog::Folder(_) => false,
-            };
-
             // Skip muted chats if not included
-            if is_muted && !filters.include_muted {
-                cache.insert(chat.id(), dialog.chat.clone());
+            if chat.is_muted && !filters.include_muted {
                 continue;
             }
 
-            let title = match chat {
-                grammers_client::types::Chat::User(u) => {
-                    format!("{} {}", u.first_name(), u.last_name().unwrap_or(""))
-                }
-                grammers_client::types::Chat::Group(g) => g.title().to_string(),
-                grammers_client::types::Chat::Channel(c) => c.title().to_string(),
-            };
-
-            let last_message = dialog.last_message.as_ref().map(|msg| {
-                let text = msg.text();
-                let content = if !text.is_empty() {
-                    MessageContent::Text { text: text.to_string() }
-                } else if msg.photo().is_some() {
-                    MessageContent::Photo { caption: None }
-                } else {
-                    MessageContent::Unknown
-                };
-
-                Message {
-                    id: msg.id() as i64,
-                    chat_id: chat.id(),
-                    sender_id: msg.sender().map(|s| s.id()).unwrap_or(0),
-                    sender_name: msg.sender().map(|s| s.name().to_string()).unwrap_or_default(),
-                    content,
-                    date: msg.date().timestamp(),
-                    is_outgoing: msg.outgoing(),
-                    is_read: true,
-                }
-            });
-
-            // Get unread count from the raw dialog data
-            let unread_count = match &dialog.raw {
-                tl::enums::Dialog::Dialog(d) => d.unread_count,
-                tl::enums::Dialog::Folder(_) => 0,
-            };
-
-            let is_pinned = match &dialog.raw {
-                tl::enums::Dialog::Dialog(d) => d.pinned,
-                tl::enums::Dialog::Folder(_) => false,
-            };
-
-            // Extract member count from chat type
-            let member_count = match chat {
-                grammers_client::types::Chat::User(_) => None,
-                grammers_client::types::Chat::Group(g) => {
-                    // Basic groups have participant count in raw data
-                    match &g.raw {
-                        tl::enums::Chat::Chat(c) => Some(c.participants_count),
-                        _ => None,
-                    }
-                }
-                grammers_client::types::Chat::Channel(c) => {
-                    // Channels/supergroups: raw is directly a Channel struct
-                    c.raw.participants_count
-                }
-            };
-
             // Check group size range filter (applies to groups and channels)
-            if chat_type == "group" || chat_type == "channel" {
-                if let Some(count) = member_count {
+            if chat.chat_type == "group" || chat.chat_type == "channel" {
+                if let Some(count) = chat.member_count {
                     // Check minimum size
                     if let Some(min_size) = filters.group_size_min {
                         if count < min_size {
-                            cache.insert(chat.id(), dialog.chat.clone());
                             continue;
                         }
                     }
                     // Check maximum size (1001+ means no limit)
                     if let Some(max_size) = filters.group_size_max {
                         if max_size <= 1000 && count > max_size {
-                            cache.insert(chat.id(), dialog.chat.clone());
                             continue;
                         }
                     }
@@ -974,90 +1436,1004 @@ impl TelegramClient {
             }
 
             // Check unread_only filter with early termination
-            if filters.include_unread_only && unread_count == 0 {
+            if filters.include_unread_only && chat.unread_count == 0 {
                 consecutive_read += 1;
                 if consecutive_read >= 50 && count > 0 {
                     log::info!("Early termination: {} consecutive read chats after {} unread", consecutive_read, count);
-                    cache.insert(chat.id(), dialog.chat.clone());
                     break;
                 }
-                cache.insert(chat.id(), dialog.chat.clone());
                 continue;
             } else if filters.include_unread_only {
                 consecutive_read = 0;
             }
 
-            // Note: Folder filter is now applied at the top as early exit (OR logic)
-            // Chats reaching this point either:
-            // 1. Have no folder filter active (folder_chat_ids is empty)
-            // 2. Are NOT in any selected folder but pass all type/muted/archived/size filters
-
-            // Cache the chat object for later use
-            cache.insert(chat.id(), dialog.chat.clone());
-
-            chats.push(Chat {
-                id: chat.id(),
-                chat_type: chat_type.to_string(),
-                title: title.trim().to_string(),
-                unread_count,
-                is_pinned,
-                order: -(dialog.last_message.as_ref().map(|m| m.date().timestamp()).unwrap_or(0)),
-                photo: None,
-                last_message,
-                member_count,
-                is_muted,
-                is_archived,
-                is_bot,
-                is_contact,
-            });
+            // Note: Folder filter is now applied at the top as early exit (OR logic)
+            // Chats reaching this point either:
+            // 1. Have no folder filter active (folder_chat_ids is empty)
+            // 2. Are NOT in any selected folder but pass all type/muted/archived/size filters
+            chats.push(chat);
+            count += 1;
+        }
+
+        *self.cache_loaded.write().await = true;
+        log::info!("Chat cache updated with {} chats", cache.len());
+
+        // Sort: pinned chats first, then by order
+        chats.sort_by(|a, b| {
+            b.is_pinned.cmp(&a.is_pinned)
+                .then(a.order.cmp(&b.order))
+        });
+
+        Ok(chats)
+    }
+
+    /// Get messages from a chat (with auto-reconnect on connection failure)
+    pub async fn get_chat_messages(
+        &self,
+        chat_id: i64,
+        limit: i32,
+        from_message_id: Option<i64>,
+    ) -> Result<Vec<Message>, String> {
+        log::info!("Getting messages for chat {}, limit: {}", chat_id, limit);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.get_chat_messages_inner(chat_id, limit, from_message_id).await {
+            Ok(messages) => Ok(messages),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error getting messages, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.get_chat_messages_inner(chat_id, limit, from_message_id).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// With `from_message_id`, only messages older than that id are returned (pagination)
+    async fn get_chat_messages_inner(
+        &self,
+        chat_id: i64,
+        limit: i32,
+        from_message_id: Option<i64>,
+    ) -> Result<Vec<Message>, String> {
+        // Try to get chat from cache first
+        let chat = match self.get_cached_chat(chat_id).await {
+            Some(c) => c,
+            None => {
+                // Cache miss - ensure cache is loaded
+                self.ensure_cache_loaded(200).await?;
+                self.get_cached_chat(chat_id).await
+                    .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?
+            }
+        };
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        let mut messages = Vec::new();
+        let mut history = client.iter_messages(&chat);
+        if let Some(from_id) = from_message_id {
+            history = history.offset_id(from_id as i32);
+        }
+        let mut count = 0;
+
+        while let Some(msg) = history.next().await.map_err(|e| e.to_string())? {
+            if count >= limit {
+                break;
+            }
+
+            messages.push(Message { chat_id, ..Self::convert_message(&msg) });
+
+            count += 1;
+        }
+
+        // Messages come newest first, reverse for chronological order
+        messages.reverse();
+        Ok(messages)
+    }
+
+    /// Get messages for multiple chats in one call (with rate limiting and FLOOD_WAIT detection)
+    pub async fn get_batch_messages(&self, requests: Vec<BatchMessageRequest>) -> Result<Vec<BatchMessageResult>, String> {
+        log::info!("Batch fetching messages for {} chats", requests.len());
+        self.ensure_cache_loaded(200).await?;
+
+        let mut results = Vec::new();
+
+        for req in &requests {
+            match self.get_chat_messages_inner(req.chat_id, req.limit, None).await {
+                Ok(msgs) => {
+                    results.push(BatchMessageResult {
+                        chat_id: req.chat_id,
+                        messages: msgs,
+                        error: None,
+                    });
+                }
+                Err(e) => {
+                    // Detect FLOOD_WAIT — stop and return partial results
+                    if e.contains("FLOOD") || e.contains("flood") {
+                        log::warn!("FLOOD_WAIT detected at chat {}, returning partial results ({}/{})", req.chat_id, results.len(), requests.len());
+                        results.push(BatchMessageResult {
+                            chat_id: req.chat_id,
+                            messages: vec![],
+                            error: Some(e),
+                        });
+                        break;
+                    }
+                    results.push(BatchMessageResult {
+                        chat_id: req.chat_id,
+                        messages: vec![],
+                        error: Some(e),
+                    });
+                }
+            }
+            // 50ms delay between requests to stay within rate limits
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        log::info!("Batch fetch complete: {}/{} chats processed", results.len(), requests.len());
+        Ok(results)
+    }
+
+    /// Convert a grammers message into our Message type, using the message's own chat id
+    pub(crate) fn convert_message(msg: &grammers_client::types::Message) -> Message {
+        let content = msg
+            .action()
+            .and_then(MessageContent::from_action)
+            .unwrap_or_else(|| MessageContent::from_parts(msg.text(), msg.raw.media.as_ref()));
+
+        Message {
+            id: msg.id() as i64,
+            chat_id: msg.chat().id(),
+            sender_id: msg.sender().map(|s| s.id()).unwrap_or(0),
+            sender_name: msg.sender().map(|s| s.name().to_string()).unwrap_or_default(),
+            content,
+            date: msg.date().timestamp(),
+            is_outgoing: msg.outgoing(),
+            is_read: true,
+            reply_to_message_id: msg.reply_to_message_id().map(i64::from),
+            mentions_me: msg.mentioned(),
+            inline_keyboard: InlineButton::keyboard(msg.raw.reply_markup.as_ref()),
+        }
+    }
+
+    /// Search messages by text, across all chats or within one chat (with auto-reconnect on connection failure)
+    pub async fn search_messages(
+        &self,
+        query: &str,
+        chat_id: Option<i64>,
+        limit: i32,
+    ) -> Result<Vec<Message>, String> {
+        log::info!("Searching messages (chat: {:?}, limit: {})", chat_id, limit);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.search_messages_inner(query, chat_id, limit).await {
+            Ok(messages) => Ok(messages),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error searching messages, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.search_messages_inner(query, chat_id, limit).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn search_messages_inner(
+        &self,
+        query: &str,
+        chat_id: Option<i64>,
+        limit: i32,
+    ) -> Result<Vec<Message>, String> {
+        let chat = match chat_id {
+            Some(id) => match self.get_cached_chat(id).await {
+                Some(c) => Some(c),
+                None => {
+                    self.ensure_cache_loaded(200).await?;
+                    Some(self.get_cached_chat(id).await
+                        .ok_or_else(|| format!("Chat {} not found in cache", id))?)
+                }
+            },
+            None => None,
+        };
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        let limit = limit.max(0) as usize;
+        let mut messages = Vec::new();
+
+        match chat {
+            Some(chat) => {
+                let mut results = client.search_messages(&chat).query(query).limit(limit);
+                while let Some(msg) = results.next().await.map_err(|e| format!("Failed to search messages: {}", e))? {
+                    messages.push(Self::convert_message(&msg));
+                }
+            }
+            None => {
+                let mut results = client.search_all_messages().query(query).limit(limit);
+                while let Some(msg) = results.next().await.map_err(|e| format!("Failed to search messages: {}", e))? {
+                    messages.push(Self::convert_message(&msg));
+                }
+            }
+        }
+
+        Ok(messages)
+    }
+
+    /// One page of a filtered message search (with auto-reconnect on connection failure).
+    /// Uses `messages.search` within a chat and `messages.searchGlobal` across chats.
+    pub async fn search_messages_page(&self, search: &MessageSearch) -> Result<MessageSearchPage, String> {
+        log::info!("Searching messages (chat: {:?}, sender: {:?})", search.chat_id, search.sender_id);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.search_messages_page_inner(search).await {
+            Ok(page) => Ok(page),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error searching messages, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.search_messages_page_inner(search).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn search_messages_page_inner(&self, search: &MessageSearch) -> Result<MessageSearchPage, String> {
+        use grammers_client::types::ChatMap;
+
+        let chat = match search.chat_id {
+            Some(id) => match self.get_cached_chat(id).await {
+                Some(c) => Some(c),
+                None => {
+                    self.ensure_cache_loaded(200).await?;
+                    Some(self.get_cached_chat(id).await
+                        .ok_or_else(|| format!("Chat {} not found in cache", id))?)
+                }
+            },
+            None => None,
+        };
+        // Telegram filters by sender only within a chat, and only for peers we can address;
+        // otherwise results are filtered here (so a page may come back short)
+        let me = self.current_user.read().await.as_ref().map(|u| u.id);
+        let from_id = match search.sender_id {
+            Some(id) if chat.is_some() && Some(id) == me => Some(tl::enums::InputPeer::PeerSelf),
+            Some(id) if chat.is_some() => self.get_cached_chat(id).await.map(|c| c.pack().to_input_peer()),
+            _ => None,
+        };
+        let filter_senders = search.sender_id.filter(|_| from_id.is_none());
+
+        let limit = search.limit.unwrap_or(50).clamp(1, 100);
+        let min_date = search.min_date.unwrap_or(0).clamp(0, i32::MAX as i64) as i32;
+        let max_date = search.max_date.map(|d| d.clamp(0, i32::MAX as i64) as i32).unwrap_or(0);
+        // Cursor: "<message id>" within a chat, "<rate>:<packed peer>:<message id>" across chats
+        let offset: Vec<&str> = search.offset.as_deref().map(|o| o.split(':').collect()).unwrap_or_default();
+        let bad_offset = || format!("Invalid search offset: {:?}", search.offset);
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        let result = match &chat {
+            Some(chat) => {
+                let offset_id = match offset.as_slice() {
+                    [] => 0,
+                    [id] => id.parse().map_err(|_| bad_offset())?,
+                    _ => return Err(bad_offset()),
+                };
+                client
+                    .invoke(&tl::functions::messages::Search {
+                        peer: chat.pack().to_input_peer(),
+                        q: search.query.clone(),
+                        from_id: from_id.clone(),
+                        saved_peer_id: None,
+                        saved_reaction: None,
+                        top_msg_id: None,
+                        filter: tl::enums::MessagesFilter::InputMessagesFilterEmpty,
+                        min_date,
+                        max_date,
+                        offset_id,
+                        add_offset: 0,
+                        limit,
+                        max_id: 0,
+                        min_id: 0,
+                        hash: 0,
+                    })
+                    .await
+            }
+            None => {
+                let (offset_rate, offset_peer, offset_id) = match offset.as_slice() {
+                    [] => (0, tl::enums::InputPeer::Empty, 0),
+                    [rate, peer, id] => (
+                        rate.parse().map_err(|_| bad_offset())?,
+                        grammers_session::PackedChat::from_hex(peer).map_err(|_| bad_offset())?.to_input_peer(),
+                        id.parse().map_err(|_| bad_offset())?,
+                    ),
+                    _ => return Err(bad_offset()),
+                };
+                client
+                    .invoke(&tl::functions::messages::SearchGlobal {
+                        broadcasts_only: false,
+                        folder_id: None,
+                        q: search.query.clone(),
+                        filter: tl::enums::MessagesFilter::InputMessagesFilterEmpty,
+                        min_date,
+                        max_date,
+                        offset_rate,
+                        offset_peer,
+                        offset_id,
+                        limit,
+                    })
+                    .await
+            }
+        }
+        .map_err(|e| format!("Failed to search messages: {}", e))?;
+
+        let (raw_messages, users, chats, total, next_rate) = match result {
+            tl::enums::messages::Messages::Messages(m) => (m.messages, m.users, m.chats, None, None),
+            tl::enums::messages::Messages::Slice(m) => (m.messages, m.users, m.chats, Some(m.count), m.next_rate),
+            tl::enums::messages::Messages::ChannelMessages(m) => (m.messages, m.users, m.chats, Some(m.count), None),
+            tl::enums::messages::Messages::NotModified(_) => (Vec::new(), Vec::new(), Vec::new(), None, None),
+        };
+        let full_page = raw_messages.len() as i32 >= limit;
+        let total = total.unwrap_or(raw_messages.len() as i32);
+        let chat_map = ChatMap::new(users, chats);
+        let found: Vec<grammers_client::types::Message> = raw_messages
+            .into_iter()
+            .filter_map(|m| grammers_client::types::Message::from_raw(client, m, &chat_map))
+            .collect();
+
+        let next_offset = found.last().filter(|_| full_page).map(|last| match &chat {
+            Some(_) => last.id().to_string(),
+            None => format!("{}:{}:{}", next_rate.unwrap_or(0), last.chat().pack().to_hex(), last.id()),
+        });
+        let messages = found
+            .iter()
+            .map(Self::convert_message)
+            .filter(|m| filter_senders.map_or(true, |id| m.sender_id == id))
+            .collect();
+
+        Ok(MessageSearchPage { messages, next_offset, total })
+    }
+
+    /// IDs of messages we sent in a chat, newest first, optionally only those before
+    /// `before_date` (unix seconds) (with auto-reconnect on connection failure)
+    pub async fn get_own_message_ids(&self, chat_id: i64, before_date: Option<i64>) -> Result<Vec<i32>, String> {
+        log::info!("Collecting own messages in chat {} (before: {:?})", chat_id, before_date);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.get_own_message_ids_inner(chat_id, before_date).await {
+            Ok(ids) => Ok(ids),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error collecting own messages, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.get_own_message_ids_inner(chat_id, before_date).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_own_message_ids_inner(&self, chat_id: i64, before_date: Option<i64>) -> Result<Vec<i32>, String> {
+        self.ensure_cache_loaded(200).await?;
+        let chat = self.get_cached_chat(chat_id).await
+            .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?;
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        let mut search = client.search_messages(&chat).sent_by_self();
+        if let Some(before) = before_date {
+            let max_date = chrono::DateTime::from_timestamp(before, 0)
+                .ok_or_else(|| format!("Invalid date: {}", before))?
+                .fixed_offset();
+            search = search.max_date(&max_date);
+        }
+
+        let mut ids = Vec::new();
+        while let Some(msg) = search.next().await.map_err(|e| format!("Failed to search messages: {}", e))? {
+            ids.push(msg.id());
+        }
+        Ok(ids)
+    }
+
+    /// Delete messages for everyone (with auto-reconnect on connection failure).
+    /// Returns how many messages Telegram reports as affected.
+    pub async fn delete_messages(&self, chat_id: i64, message_ids: &[i32]) -> Result<usize, String> {
+        // Try the operation, reconnect and retry once on connection error
+        match self.delete_messages_inner(chat_id, message_ids).await {
+            Ok(count) => Ok(count),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error deleting messages, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.delete_messages_inner(chat_id, message_ids).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn delete_messages_inner(&self, chat_id: i64, message_ids: &[i32]) -> Result<usize, String> {
+        self.ensure_cache_loaded(200).await?;
+        let chat = self.get_cached_chat(chat_id).await
+            .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?;
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        client
+            .delete_messages(&chat, message_ids)
+            .await
+            .map_err(|e| format!("Failed to delete messages: {}", e))
+    }
+
+    /// Mark a chat's incoming messages as read in Telegram, up to and including
+    /// `up_to_message_id` or all of them (with auto-reconnect on connection failure)
+    pub async fn mark_chat_read(&self, chat_id: i64, up_to_message_id: Option<i64>) -> Result<(), String> {
+        log::info!("Marking chat {} as read up to {:?}", chat_id, up_to_message_id);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.mark_chat_read_inner(chat_id, up_to_message_id).await {
+            Ok(()) => Ok(()),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error marking chat as read, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.mark_chat_read_inner(chat_id, up_to_message_id).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn mark_chat_read_inner(&self, chat_id: i64, up_to_message_id: Option<i64>) -> Result<(), String> {
+        let chat = match self.get_cached_chat(chat_id).await {
+            Some(c) => c,
+            None => {
+                self.ensure_cache_loaded(200).await?;
+                self.get_cached_chat(chat_id).await
+                    .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?
+            }
+        };
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        // 0 reads everything
+        let max_id = up_to_message_id.unwrap_or(0) as i32;
+        match &chat {
+            grammers_client::types::Chat::Channel(c) => {
+                let access_hash = c
+                    .raw
+                    .access_hash
+                    .ok_or_else(|| format!("Channel {} is missing access_hash", c.raw.title))?;
+                client
+                    .invoke(&tl::functions::channels::ReadHistory {
+                        channel: tl::enums::InputChannel::Channel(tl::types::InputChannel {
+                            channel_id: c.raw.id,
+                            access_hash,
+                        }),
+                        max_id,
+                    })
+                    .await
+                    .map_err(|e| format!("Failed to mark chat as read: {}", e))?;
+            }
+            _ => {
+                client
+                    .invoke(&tl::functions::messages::ReadHistory {
+                        peer: chat.pack().to_input_peer(),
+                        max_id,
+                    })
+                    .await
+                    .map_err(|e| format!("Failed to mark chat as read: {}", e))?;
+            }
+        }
+        drop(client_guard);
+
+        // Reading up to the last message clears the badge; otherwise Telegram
+        // reports the remaining count as an update. The chat is already read, so
+        // failing to look it up only leaves the badge for that update to fix.
+        let last_id = match self.get_chat_inner(chat_id).await {
+            Ok(chat) => chat.and_then(|c| c.last_message).map(|m| m.id),
+            Err(e) => {
+                log::warn!("Marked chat {} as read but couldn't refresh it: {}", chat_id, e);
+                return Ok(());
+            }
+        };
+        if up_to_message_id.map_or(true, |id| last_id.is_some_and(|last| id >= last)) {
+            self.apply_unread_count(chat_id, 0).await;
+        }
+        Ok(())
+    }
+
+    /// Send a text message (with auto-reconnect on connection failure)
+    pub async fn send_message(&self, chat_id: i64, text: &str) -> Result<Message, String> {
+        log::info!("Sending message to chat {}", chat_id);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.send_message_inner(chat_id, text).await {
+            Ok(message) => Ok(message),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error sending message, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.send_message_inner(chat_id, text).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn send_message_inner(&self, chat_id: i64, text: &str) -> Result<Message, String> {
+        // Get chat from cache
+        let chat = match self.get_cached_chat(chat_id).await {
+            Some(c) => c,
+            None => {
+                // Cache miss - ensure cache is loaded
+                self.ensure_cache_loaded(200).await?;
+                self.get_cached_chat(chat_id).await
+                    .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?
+            }
+        };
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        let sent_msg = client
+            .send_message(&chat, text)
+            .await
+            .map_err(|e| format!("Failed to send message: {}", e))?;
+
+        let message = Message {
+            id: sent_msg.id() as i64,
+            chat_id,
+            sender_id: self.current_user.read().await.as_ref().map(|u| u.id).unwrap_or(0),
+            sender_name: "You".to_string(),
+            content: MessageContent::Text { text: text.to_string() },
+            date: sent_msg.date().timestamp(),
+            is_outgoing: true,
+            is_read: false,
+            reply_to_message_id: None,
+            mentions_me: false,
+            inline_keyboard: Vec::new(),
+        };
+
+        self.emit_event(TelegramEvent::NewMessage(message.clone()));
+        Ok(message)
+    }
+
+    /// Forward one message to another chat (with auto-reconnect on connection failure)
+    pub async fn forward_message(&self, from_chat_id: i64, message_id: i64, to_chat_id: i64) -> Result<(), String> {
+        log::info!("Forwarding message {} from chat {} to chat {}", message_id, from_chat_id, to_chat_id);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.forward_message_inner(from_chat_id, message_id, to_chat_id).await {
+            Ok(()) => Ok(()),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error forwarding message, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.forward_message_inner(from_chat_id, message_id, to_chat_id).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn forward_message_inner(&self, from_chat_id: i64, message_id: i64, to_chat_id: i64) -> Result<(), String> {
+        let mut chats = Vec::with_capacity(2);
+        for chat_id in [from_chat_id, to_chat_id] {
+            let chat = match self.get_cached_chat(chat_id).await {
+                Some(c) => c,
+                None => {
+                    self.ensure_cache_loaded(200).await?;
+                    self.get_cached_chat(chat_id).await
+                        .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?
+                }
+            };
+            chats.push(chat);
+        }
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        client
+            .forward_messages(&chats[1], &[message_id as i32], &chats[0])
+            .await
+            .map_err(|e| format!("Failed to forward message: {}", e))?;
+        Ok(())
+    }
+
+    /// Send a file with an optional caption: images go as photos, anything else as
+    /// a document (with auto-reconnect on connection failure)
+    pub async fn send_media(&self, chat_id: i64, caption: &str, file_path: &str) -> Result<Message, String> {
+        log::info!("Sending media to chat {}", chat_id);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.send_media_inner(chat_id, caption, file_path).await {
+            Ok(message) => Ok(message),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error sending media, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.send_media_inner(chat_id, caption, file_path).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn send_media_inner(&self, chat_id: i64, caption: &str, file_path: &str) -> Result<Message, String> {
+        let chat = match self.get_cached_chat(chat_id).await {
+            Some(c) => c,
+            None => {
+                self.ensure_cache_loaded(200).await?;
+                self.get_cached_chat(chat_id).await
+                    .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?
+            }
+        };
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        let uploaded = client
+            .upload_file(file_path)
+            .await
+            .map_err(|e| format!("Failed to upload {}: {}", file_path, e))?;
+        let path = std::path::Path::new(file_path);
+        let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let is_image = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "jpg" | "jpeg" | "png" | "webp"));
+
+        let input = grammers_client::InputMessage::text(caption);
+        let caption = Some(caption.to_string()).filter(|c| !c.is_empty());
+        let (input, content) = if is_image {
+            (input.photo(uploaded), MessageContent::Photo { caption })
+        } else {
+            (input.file(uploaded), MessageContent::Document { file_name, caption })
+        };
+
+        let sent_msg = client
+            .send_message(&chat, input)
+            .await
+            .map_err(|e| format!("Failed to send media: {}", e))?;
+
+        let message = Message {
+            id: sent_msg.id() as i64,
+            chat_id,
+            sender_id: self.current_user.read().await.as_ref().map(|u| u.id).unwrap_or(0),
+            sender_name: "You".to_string(),
+            content,
+            date: sent_msg.date().timestamp(),
+            is_outgoing: true,
+            is_read: false,
+            reply_to_message_id: None,
+            mentions_me: false,
+            inline_keyboard: Vec::new(),
+        };
+
+        self.emit_event(TelegramEvent::NewMessage(message.clone()));
+        Ok(message)
+    }
+
+    /// Ids of the channels the user can post to (creator or admin with posting
+    /// rights) and of the groups they administer
+    pub async fn get_postable_chat_ids(&self) -> Result<Vec<i64>, String> {
+        self.ensure_cache_loaded(200).await?;
+
+        let cache = self.chat_cache.read().await;
+        let ids = cache
+            .values()
+            .filter(|chat| match chat {
+                grammers_client::types::Chat::Channel(c) if c.raw.broadcast => {
+                    c.raw.creator || c.raw.admin_rights.as_ref().is_some_and(|r| match r {
+                        tl::enums::ChatAdminRights::Rights(r) => r.post_messages,
+                    })
+                }
+                grammers_client::types::Chat::Channel(c) => c.raw.creator || c.raw.admin_rights.is_some(),
+                grammers_client::types::Chat::Group(g) => match &g.raw {
+                    tl::enums::Chat::Chat(c) => c.creator || c.admin_rights.is_some(),
+                    _ => false,
+                },
+                grammers_client::types::Chat::User(_) => false,
+            })
+            .map(|chat| chat.id())
+            .collect();
+        Ok(ids)
+    }
+
+    /// Press an inline keyboard callback button via messages.getBotCallbackAnswer
+    /// (with auto-reconnect on connection failure). `data` is the button's base64 payload.
+    pub async fn click_inline_button(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        data: &str,
+    ) -> Result<BotCallbackAnswer, String> {
+        log::info!("Clicking inline button on message {} in chat {}", message_id, chat_id);
+        let data = BASE64
+            .decode(data)
+            .map_err(|e| format!("Invalid button data: {}", e))?;
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.click_inline_button_inner(chat_id, message_id, &data).await {
+            Ok(answer) => Ok(answer),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error clicking inline button, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.click_inline_button_inner(chat_id, message_id, &data).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn click_inline_button_inner(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        data: &[u8],
+    ) -> Result<BotCallbackAnswer, String> {
+        let chat = match self.get_cached_chat(chat_id).await {
+            Some(c) => c,
+            None => {
+                self.ensure_cache_loaded(200).await?;
+                self.get_cached_chat(chat_id).await
+                    .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?
+            }
+        };
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        let tl::enums::messages::BotCallbackAnswer::Answer(answer) = client
+            .invoke(&tl::functions::messages::GetBotCallbackAnswer {
+                game: false,
+                peer: chat.pack().to_input_peer(),
+                msg_id: message_id as i32,
+                data: Some(data.to_vec()),
+                password: None,
+            })
+            .await
+            .map_err(|e| format!("Failed to press button: {}", e))?;
+
+        Ok(BotCallbackAnswer {
+            message: answer.message.filter(|m| !m.is_empty()),
+            alert: answer.alert,
+            url: answer.url,
+        })
+    }
+
+    /// Save the photo, document or voice note of a message into `dest_dir`,
+    /// calling `on_progress(downloaded, total)` after each chunk. A file already
+    /// downloaded in full is reused (with auto-reconnect on connection failure).
+    pub async fn download_media<F>(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        dest_dir: &std::path::Path,
+        on_progress: F,
+    ) -> Result<DownloadedMedia, String>
+    where
+        F: Fn(i64, i64) + Send + Sync,
+    {
+        log::info!("Downloading media of message {} in chat {}", message_id, chat_id);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.download_media_inner(chat_id, message_id, dest_dir, &on_progress).await {
+            Ok(media) => Ok(media),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error downloading media, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.download_media_inner(chat_id, message_id, dest_dir, &on_progress).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn download_media_inner<F>(
+        &self,
+        chat_id: i64,
+        message_id: i64,
+        dest_dir: &std::path::Path,
+        on_progress: &F,
+    ) -> Result<DownloadedMedia, String>
+    where
+        F: Fn(i64, i64) + Send + Sync,
+    {
+        use grammers_client::types::{Downloadable, Media};
+        use tokio::io::AsyncWriteExt;
+
+        let chat = match self.get_cached_chat(chat_id).await {
+            Some(c) => c,
+            None => {
+                self.ensure_cache_loaded(200).await?;
+                self.get_cached_chat(chat_id).await
+                    .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?
+            }
+        };
+
+        // Downloads can take a while; don't hold the lock a reconnect needs meanwhile
+        let client = {
+            let client_guard = self.client.read().await;
+            client_guard.as_ref().ok_or("Client not connected")?.clone()
+        };
+
+        let message = client
+            .get_messages_by_id(&chat, &[message_id as i32])
+            .await
+            .map_err(|e| format!("Failed to get message: {}", e))?
+            .into_iter()
+            .flatten()
+            .next()
+            .ok_or_else(|| format!("Message {} not found in chat {}", message_id, chat_id))?;
+        let media = message.media().ok_or_else(|| format!("Message {} has no media", message_id))?;
+
+        let (file_name, mime_type, size) = match &media {
+            Media::Photo(photo) => (
+                media_file_name(message_id, "", Some("image/jpeg")),
+                Some("image/jpeg".to_string()),
+                photo.size(),
+            ),
+            Media::Document(doc) => (
+                media_file_name(message_id, doc.name(), doc.mime_type()),
+                doc.mime_type().map(str::to_string),
+                doc.size(),
+            ),
+            _ => return Err("Only photos, documents and voice notes can be downloaded".to_string()),
+        };
+
+        let dir = dest_dir.join(chat_id.to_string());
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+        let path = dir.join(&file_name);
+        let downloaded = |size| DownloadedMedia {
+            chat_id,
+            message_id,
+            path: path.to_string_lossy().to_string(),
+            file_name: file_name.clone(),
+            mime_type: mime_type.clone(),
+            size,
+        };
+
+        if let Ok(meta) = tokio::fs::metadata(&path).await {
+            if size > 0 && meta.len() as i64 == size {
+                on_progress(size, size);
+                return Ok(downloaded(size));
+            }
+        }
+
+        // Written under a temporary name so a cut-off download is never mistaken for the file
+        let partial = dir.join(format!("{}.part", file_name));
+        let mut file = tokio::fs::File::create(&partial)
+            .await
+            .map_err(|e| format!("Failed to create {:?}: {}", partial, e))?;
+        let mut chunks = client.iter_download(&Downloadable::Media(media));
+        let mut written: i64 = 0;
+        while let Some(chunk) = chunks.next().await.map_err(|e| format!("Failed to download media: {}", e))? {
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| format!("Failed to write {:?}: {}", partial, e))?;
+            written += chunk.len() as i64;
+            on_progress(written, size.max(written));
+        }
+        file.flush().await.map_err(|e| format!("Failed to write {:?}: {}", partial, e))?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .map_err(|e| format!("Failed to save {:?}: {}", path, e))?;
+
+        Ok(downloaded(written))
+    }
+
+    /// Ids of the broadcast channels the user created or administers
+    pub async fn get_owned_channel_ids(&self) -> Result<Vec<i64>, String> {
+        self.ensure_cache_loaded(200).await?;
+
+        let cache = self.chat_cache.read().await;
+        let ids = cache
+            .values()
+            .filter(|chat| match chat {
+                grammers_client::types::Chat::Channel(c) => {
+                    c.raw.broadcast && (c.raw.creator || c.raw.admin_rights.is_some())
+                }
+                _ => false,
+            })
+            .map(|chat| chat.id())
+            .collect();
+        Ok(ids)
+    }
+
+    /// Subscriber count and views of the latest `post_limit` posts of a broadcast
+    /// channel (with auto-reconnect on connection failure)
+    pub async fn get_channel_stats(&self, chat_id: i64, post_limit: i32) -> Result<ChannelStats, String> {
+        // Try the operation, reconnect and retry once on connection error
+        match self.get_channel_stats_inner(chat_id, post_limit).await {
+            Ok(stats) => Ok(stats),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error getting channel stats, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.get_channel_stats_inner(chat_id, post_limit).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_channel_stats_inner(&self, chat_id: i64, post_limit: i32) -> Result<ChannelStats, String> {
+        let chat = match self.get_cached_chat(chat_id).await {
+            Some(c) => c,
+            None => {
+                self.ensure_cache_loaded(200).await?;
+                self.get_cached_chat(chat_id).await
+                    .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?
+            }
+        };
+        let channel = match &chat {
+            grammers_client::types::Chat::Channel(c) if c.raw.broadcast => c.raw.clone(),
+            _ => return Err(format!("{} is not a channel", chat.name())),
+        };
+        let access_hash = channel
+            .access_hash
+            .ok_or_else(|| format!("Channel {} is missing access_hash", channel.title))?;
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
 
-            count += 1;
+        let tl::enums::messages::ChatFull::Full(full) = client
+            .invoke(&tl::functions::channels::GetFullChannel {
+                channel: tl::enums::InputChannel::Channel(tl::types::InputChannel {
+                    channel_id: channel.id,
+                    access_hash,
+                }),
+            })
+            .await
+            .map_err(|e| format!("Failed to get channel info: {}", e))?;
+        let subscriber_count = match full.full_chat {
+            tl::enums::ChatFull::ChannelFull(f) => f.participants_count,
+            tl::enums::ChatFull::Full(_) => None,
         }
+        .or(channel.participants_count)
+        .unwrap_or(0);
+
+        let history = client
+            .invoke(&tl::functions::messages::GetHistory {
+                peer: chat.pack().to_input_peer(),
+                offset_id: 0,
+                offset_date: 0,
+                add_offset: 0,
+                limit: post_limit,
+                max_id: 0,
+                min_id: 0,
+                hash: 0,
+            })
+            .await
+            .map_err(|e| format!("Failed to get channel posts: {}", e))?;
+        let messages = match history {
+            tl::enums::messages::Messages::Messages(m) => m.messages,
+            tl::enums::messages::Messages::Slice(m) => m.messages,
+            tl::enums::messages::Messages::ChannelMessages(m) => m.messages,
+            tl::enums::messages::Messages::NotModified(_) => Vec::new(),
+        };
+        let posts = messages
+            .into_iter()
+            .filter_map(|m| match m {
+                tl::enums::Message::Message(m) => m.views.map(|views| PostViews {
+                    message_id: m.id as i64,
+                    date: m.date as i64,
+                    views,
+                }),
+                _ => None,
+            })
+            .collect();
 
-        *self.cache_loaded.write().await = true;
-        log::info!("Chat cache updated with {} chats", cache.len());
-
-        // Sort: pinned chats first, then by order
-        chats.sort_by(|a, b| {
-            b.is_pinned.cmp(&a.is_pinned)
-                .then(a.order.cmp(&b.order))
-        });
-
-        Ok(chats)
+        Ok(ChannelStats { subscriber_count, posts })
     }
 
-    /// Get messages from a chat (with auto-reconnect on connection failure)
-    pub async fn get_chat_messages(
-        &self,
-        chat_id: i64,
-        limit: i32,
-        from_message_id: Option<i64>,
-    ) -> Result<Vec<Message>, String> {
-        log::info!("Getting messages for chat {}, limit: {}", chat_id, limit);
-
+    /// Who reacted to a message and with what (with auto-reconnect on connection failure).
+    /// Only available where reactions are visible, i.e. groups but not channels.
+    pub async fn get_message_reactions(&self, chat_id: i64, message_id: i64) -> Result<Vec<PeerReaction>, String> {
         // Try the operation, reconnect and retry once on connection error
-        match self.get_chat_messages_inner(chat_id, limit, from_message_id).await {
-            Ok(messages) => Ok(messages),
+        match self.get_message_reactions_inner(chat_id, message_id).await {
+            Ok(reactions) => Ok(reactions),
             Err(e) if Self::is_connection_error(&e) => {
-                log::warn!("Connection error getting messages, attempting reconnect: {}", e);
+                log::warn!("Connection error getting reactions, attempting reconnect: {}", e);
                 self.reconnect().await?;
-                self.get_chat_messages_inner(chat_id, limit, from_message_id).await
+                self.get_message_reactions_inner(chat_id, message_id).await
             }
             Err(e) => Err(e),
         }
     }
 
-    async fn get_chat_messages_inner(
-        &self,
-        chat_id: i64,
-        limit: i32,
-        _from_message_id: Option<i64>,
-    ) -> Result<Vec<Message>, String> {
-        // Try to get chat from cache first
+    async fn get_message_reactions_inner(&self, chat_id: i64, message_id: i64) -> Result<Vec<PeerReaction>, String> {
         let chat = match self.get_cached_chat(chat_id).await {
             Some(c) => c,
             None => {
-                // Cache miss - ensure cache is loaded
                 self.ensure_cache_loaded(200).await?;
                 self.get_cached_chat(chat_id).await
                     .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?
@@ -1067,134 +2443,295 @@ impl TelegramClient {
         let client_guard = self.client.read().await;
         let client = client_guard.as_ref().ok_or("Client not connected")?;
 
-        let mut messages = Vec::new();
-        let mut history = client.iter_messages(&chat);
-        let mut count = 0;
+        let mut reactions = Vec::new();
+        let mut offset = None;
+        loop {
+            let tl::enums::messages::MessageReactionsList::List(list) = client
+                .invoke(&tl::functions::messages::GetMessageReactionsList {
+                    peer: chat.pack().to_input_peer(),
+                    id: message_id as i32,
+                    reaction: None,
+                    offset: offset.clone(),
+                    limit: 100,
+                })
+                .await
+                .map_err(|e| format!("Failed to get reactions: {}", e))?;
+
+            let names: HashMap<i64, String> = list
+                .users
+                .iter()
+                .filter_map(|u| match u {
+                    tl::enums::User::User(u) => {
+                        let name = format!(
+                            "{} {}",
+                            u.first_name.as_deref().unwrap_or(""),
+                            u.last_name.as_deref().unwrap_or("")
+                        );
+                        Some((u.id, name.trim().to_string()))
+                    }
+                    tl::enums::User::Empty(_) => None,
+                })
+                .collect();
 
-        while let Some(msg) = history.next().await.map_err(|e| e.to_string())? {
-            if count >= limit {
-                break;
+            for tl::enums::MessagePeerReaction::Reaction(reaction) in list.reactions {
+                let tl::enums::Peer::User(peer) = reaction.peer_id else {
+                    continue;
+                };
+                reactions.push(PeerReaction {
+                    user_id: peer.user_id,
+                    name: names.get(&peer.user_id).cloned().unwrap_or_default(),
+                    emoji: match reaction.reaction {
+                        tl::enums::Reaction::Emoji(e) => Some(e.emoticon),
+                        _ => None,
+                    },
+                    date: reaction.date as i64,
+                });
             }
 
-            let text = msg.text();
-            let content = if !text.is_empty() {
-                MessageContent::Text { text: text.to_string() }
-            } else if msg.photo().is_some() {
-                MessageContent::Photo { caption: None }
-            } else {
-                MessageContent::Unknown
-            };
-
-            messages.push(Message {
-                id: msg.id() as i64,
-                chat_id,
-                sender_id: msg.sender().map(|s| s.id()).unwrap_or(0),
-                sender_name: msg.sender().map(|s| s.name().to_string()).unwrap_or_default(),
-                content,
-                date: msg.date().timestamp(),
-                is_outgoing: msg.outgoing(),
-                is_read: true,
-            });
-
-            count += 1;
+            match list.next_offset {
+                Some(next) if !next.is_empty() => offset = Some(next),
+                _ => break,
+            }
         }
+        Ok(reactions)
+    }
 
-        // Messages come newest first, reverse for chronological order
-        messages.reverse();
-        Ok(messages)
+    /// Reactions to the user's active stories made at or after `since` (unix seconds).
+    /// Telegram sends no message for these, so they are read from the stories' viewer
+    /// lists (with auto-reconnect on connection failure).
+    pub async fn get_story_reactions(&self, since: i64) -> Result<Vec<StoryReaction>, String> {
+        // Try the operation, reconnect and retry once on connection error
+        match self.get_story_reactions_inner(since).await {
+            Ok(reactions) => Ok(reactions),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error getting story reactions, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.get_story_reactions_inner(since).await
+            }
+            Err(e) => Err(e),
+        }
     }
 
-    /// Get messages for multiple chats in one call (with rate limiting and FLOOD_WAIT detection)
-    pub async fn get_batch_messages(&self, requests: Vec<BatchMessageRequest>) -> Result<Vec<BatchMessageResult>, String> {
-        log::info!("Batch fetching messages for {} chats", requests.len());
-        self.ensure_cache_loaded(200).await?;
+    async fn get_story_reactions_inner(&self, since: i64) -> Result<Vec<StoryReaction>, String> {
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
 
-        let mut results = Vec::new();
+        let tl::enums::stories::PeerStories::Stories(own) = client
+            .invoke(&tl::functions::stories::GetPeerStories {
+                peer: tl::enums::InputPeer::PeerSelf,
+            })
+            .await
+            .map_err(|e| format!("Failed to get stories: {}", e))?;
+        let tl::enums::PeerStories::Stories(own) = own.stories;
 
-        for req in &requests {
-            match self.get_chat_messages_inner(req.chat_id, req.limit, None).await {
-                Ok(msgs) => {
-                    results.push(BatchMessageResult {
-                        chat_id: req.chat_id,
-                        messages: msgs,
-                        error: None,
-                    });
-                }
-                Err(e) => {
-                    // Detect FLOOD_WAIT — stop and return partial results
-                    if e.contains("FLOOD") || e.contains("flood") {
-                        log::warn!("FLOOD_WAIT detected at chat {}, returning partial results ({}/{})", req.chat_id, results.len(), requests.len());
-                        results.push(BatchMessageResult {
-                            chat_id: req.chat_id,
-                            messages: vec![],
-                            error: Some(e),
-                        });
-                        break;
+        let mut reactions = Vec::new();
+        for story in own.stories {
+            let tl::enums::StoryItem::Item(story) = story else {
+                continue;
+            };
+            let reacted = match &story.views {
+                Some(tl::enums::StoryViews::Views(views)) => views.reactions_count.unwrap_or(0) > 0,
+                None => false,
+            };
+            if !reacted {
+                continue;
+            }
+
+            // Reactions are listed first, newest first; one page is plenty for a briefing
+            let tl::enums::stories::StoryViewsList::List(list) = client
+                .invoke(&tl::functions::stories::GetStoryViewsList {
+                    just_contacts: false,
+                    reactions_first: true,
+                    forwards_first: false,
+                    peer: tl::enums::InputPeer::PeerSelf,
+                    q: None,
+                    id: story.id,
+                    offset: String::new(),
+                    limit: 100,
+                })
+                .await
+                .map_err(|e| format!("Failed to get story viewers: {}", e))?;
+
+            let names: HashMap<i64, String> = list
+                .users
+                .iter()
+                .filter_map(|u| match u {
+                    tl::enums::User::User(u) => {
+                        let name = format!(
+                            "{} {}",
+                            u.first_name.as_deref().unwrap_or(""),
+                            u.last_name.as_deref().unwrap_or("")
+                        );
+                        Some((u.id, name.trim().to_string()))
                     }
-                    results.push(BatchMessageResult {
-                        chat_id: req.chat_id,
-                        messages: vec![],
-                        error: Some(e),
-                    });
+                    tl::enums::User::Empty(_) => None,
+                })
+                .collect();
+
+            for view in list.views {
+                let tl::enums::StoryView::View(view) = view else {
+                    continue;
+                };
+                let Some(reaction) = view.reaction else {
+                    continue;
+                };
+                if (view.date as i64) < since {
+                    continue;
                 }
+                reactions.push(StoryReaction {
+                    story_id: story.id,
+                    reaction: PeerReaction {
+                        user_id: view.user_id,
+                        name: names.get(&view.user_id).cloned().unwrap_or_default(),
+                        emoji: match reaction {
+                            tl::enums::Reaction::Emoji(e) => Some(e.emoticon),
+                            _ => None,
+                        },
+                        date: view.date as i64,
+                    },
+                });
             }
-            // 50ms delay between requests to stay within rate limits
-            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
         }
-
-        log::info!("Batch fetch complete: {}/{} chats processed", results.len(), requests.len());
-        Ok(results)
+        Ok(reactions)
     }
 
-    /// Send a text message (with auto-reconnect on connection failure)
-    pub async fn send_message(&self, chat_id: i64, text: &str) -> Result<Message, String> {
-        log::info!("Sending message to chat {}", chat_id);
+    /// Send a message with bold/italic entities. `chat_id: None` sends to Saved Messages.
+    /// (with auto-reconnect on connection failure)
+    pub async fn send_formatted_message(
+        &self,
+        chat_id: Option<i64>,
+        message: &FormattedText,
+    ) -> Result<Message, String> {
+        log::info!("Sending formatted message to {:?}", chat_id);
 
         // Try the operation, reconnect and retry once on connection error
-        match self.send_message_inner(chat_id, text).await {
+        match self.send_formatted_message_inner(chat_id, message).await {
             Ok(message) => Ok(message),
             Err(e) if Self::is_connection_error(&e) => {
-                log::warn!("Connection error sending message, attempting reconnect: {}", e);
+                log::warn!("Connection error sending formatted message, attempting reconnect: {}", e);
                 self.reconnect().await?;
-                self.send_message_inner(chat_id, text).await
+                self.send_formatted_message_inner(chat_id, message).await
             }
             Err(e) => Err(e),
         }
     }
 
-    async fn send_message_inner(&self, chat_id: i64, text: &str) -> Result<Message, String> {
-        // Get chat from cache
-        let chat = match self.get_cached_chat(chat_id).await {
-            Some(c) => c,
-            None => {
-                // Cache miss - ensure cache is loaded
-                self.ensure_cache_loaded(200).await?;
-                self.get_cached_chat(chat_id).await
-                    .ok_or_else(|| format!("Chat {} not found in cache", chat_id))?
-            }
+    async fn send_formatted_message_inner(
+        &self,
+        chat_id: Option<i64>,
+        message: &FormattedText,
+    ) -> Result<Message, String> {
+        let target = match chat_id {
+            Some(id) => match self.get_cached_chat(id).await {
+                Some(c) => Some(c.pack()),
+                None => {
+                    self.ensure_cache_loaded(200).await?;
+                    Some(self.get_cached_chat(id).await
+                        .ok_or_else(|| format!("Chat {} not found in cache", id))?
+                        .pack())
+                }
+            },
+            None => None,
         };
 
         let client_guard = self.client.read().await;
         let client = client_guard.as_ref().ok_or("Client not connected")?;
 
+        // Saved Messages is the chat with ourselves
+        let target = match target {
+            Some(packed) => packed,
+            None => client
+                .get_me()
+                .await
+                .map_err(|e| format!("Failed to get current user: {}", e))?
+                .pack(),
+        };
+
+        let entities = message
+            .entities
+            .iter()
+            .map(|e| match e.style {
+                TextStyle::Bold => tl::enums::MessageEntity::Bold(tl::types::MessageEntityBold {
+                    offset: e.offset,
+                    length: e.length,
+                }),
+                TextStyle::Italic => tl::enums::MessageEntity::Italic(tl::types::MessageEntityItalic {
+                    offset: e.offset,
+                    length: e.length,
+                }),
+            })
+            .collect();
+
+        let input = grammers_client::types::InputMessage::text(&message.text).fmt_entities(entities);
         let sent_msg = client
-            .send_message(&chat, text)
+            .send_message(target, input)
             .await
             .map_err(|e| format!("Failed to send message: {}", e))?;
 
-        let message = Message {
+        Ok(Message {
             id: sent_msg.id() as i64,
-            chat_id,
+            chat_id: target.id,
             sender_id: self.current_user.read().await.as_ref().map(|u| u.id).unwrap_or(0),
             sender_name: "You".to_string(),
-            content: MessageContent::Text { text: text.to_string() },
+            content: MessageContent::Text { text: message.text.clone() },
             date: sent_msg.date().timestamp(),
             is_outgoing: true,
             is_read: false,
-        };
+            reply_to_message_id: None,
+            mentions_me: false,
+            inline_keyboard: Vec::new(),
+        })
+    }
 
-        self.emit_event(TelegramEvent::NewMessage(message.clone()));
-        Ok(message)
+    /// Get a user's full profile via users.getFullUser (with auto-reconnect on connection failure)
+    pub async fn get_full_user(&self, user_id: i64, access_hash: i64) -> Result<UserProfile, String> {
+        // Try the operation, reconnect and retry once on connection error
+        match self.get_full_user_inner(user_id, access_hash).await {
+            Ok(profile) => Ok(profile),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error getting full user, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.get_full_user_inner(user_id, access_hash).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_full_user_inner(&self, user_id: i64, access_hash: i64) -> Result<UserProfile, String> {
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        let input_user = tl::enums::InputUser::User(tl::types::InputUser {
+            user_id,
+            access_hash,
+        });
+
+        let tl::enums::users::UserFull::Full(full) = client
+            .invoke(&tl::functions::users::GetFullUser { id: input_user })
+            .await
+            .map_err(|e| format!("Failed to get full user: {}", e))?;
+        let tl::enums::UserFull::Full(user_full) = full.full_user;
+
+        let user = full.users.into_iter().find_map(|u| match u {
+            tl::enums::User::User(u) if u.id == user_id => Some(u),
+            _ => None,
+        });
+
+        Ok(UserProfile {
+            user_id,
+            bio: user_full.about.filter(|a| !a.trim().is_empty()),
+            username: user.as_ref().and_then(|u| u.username.clone()),
+            is_premium: user.as_ref().map(|u| u.premium).unwrap_or(false),
+            is_verified: user.as_ref().map(|u| u.verified).unwrap_or(false),
+            birthday: user_full.birthday.map(|b| {
+                let tl::enums::Birthday::Birthday(b) = b;
+                Birthday {
+                    day: b.day as u32,
+                    month: b.month as u32,
+                    year: b.year,
+                }
+            }),
+        })
     }
 
     /// Get contacts (with auto-reconnect on connection failure)
@@ -1282,6 +2819,89 @@ impl TelegramClient {
         Ok(users)
     }
 
+    /// Access hash of a user we have a dialog with, from the chat cache
+    async fn cached_user_access_hash(&self, user_id: i64) -> Result<i64, String> {
+        self.ensure_cache_loaded(200).await?;
+        match self.get_cached_chat(user_id).await {
+            Some(grammers_client::types::Chat::User(u)) => u
+                .raw
+                .access_hash
+                .ok_or_else(|| format!("User {} is missing access_hash", user_id)),
+            _ => Err(format!("User {} not found in dialogs", user_id)),
+        }
+    }
+
+    /// Add a user we have a dialog with to contacts (with auto-reconnect on connection failure)
+    pub async fn add_contact(&self, user_id: i64, first_name: &str, last_name: &str) -> Result<(), String> {
+        log::info!("Adding user {} to contacts", user_id);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.add_contact_inner(user_id, first_name, last_name).await {
+            Ok(()) => Ok(()),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error adding contact, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.add_contact_inner(user_id, first_name, last_name).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn add_contact_inner(&self, user_id: i64, first_name: &str, last_name: &str) -> Result<(), String> {
+        let access_hash = self.cached_user_access_hash(user_id).await?;
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        client
+            .invoke(&tl::functions::contacts::AddContact {
+                add_phone_privacy_exception: false,
+                id: tl::enums::InputUser::User(tl::types::InputUser { user_id, access_hash }),
+                first_name: first_name.to_string(),
+                last_name: last_name.to_string(),
+                phone: String::new(),
+            })
+            .await
+            .map_err(|e| format!("Failed to add contact: {}", e))?;
+
+        // The cached dialog still has the old contact flag
+        self.invalidate_cache().await;
+        Ok(())
+    }
+
+    /// Block a user we have a dialog with (with auto-reconnect on connection failure)
+    pub async fn block_user(&self, user_id: i64) -> Result<(), String> {
+        log::info!("Blocking user {}", user_id);
+
+        // Try the operation, reconnect and retry once on connection error
+        match self.block_user_inner(user_id).await {
+            Ok(()) => Ok(()),
+            Err(e) if Self::is_connection_error(&e) => {
+                log::warn!("Connection error blocking user, attempting reconnect: {}", e);
+                self.reconnect().await?;
+                self.block_user_inner(user_id).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn block_user_inner(&self, user_id: i64) -> Result<(), String> {
+        let access_hash = self.cached_user_access_hash(user_id).await?;
+
+        let client_guard = self.client.read().await;
+        let client = client_guard.as_ref().ok_or("Client not connected")?;
+
+        client
+            .invoke(&tl::functions::contacts::Block {
+                my_stories_from: false,
+                id: tl::enums::InputPeer::User(tl::types::InputPeerUser { user_id, access_hash }),
+            })
+            .await
+            .map_err(|e| format!("Failed to block user: {}", e))?;
+
+        Ok(())
+    }
+
     /// Get chat folders using MTProto GetDialogFilters (with auto-reconnect on connection failure)
     pub async fn get_folders(&self) -> Result<Vec<Folder>, String> {
         log::info!("Getting folders");
@@ -1554,3 +3174,178 @@ impl Default for TelegramClient {
         Self::new(TelegramConfig::default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_keyboard_from_reply_markup() {
+        let row = |buttons: Vec<tl::enums::KeyboardButton>| {
+            tl::enums::KeyboardButtonRow::Row(tl::types::KeyboardButtonRow { buttons })
+        };
+        let markup = tl::enums::ReplyMarkup::ReplyInlineMarkup(tl::types::ReplyInlineMarkup {
+            rows: vec![
+                row(vec![
+                    tl::enums::KeyboardButton::Callback(tl::types::KeyboardButtonCallback {
+                        requires_password: false,
+                        text: "Approve".to_string(),
+                        data: b"approve:42".to_vec(),
+                    }),
+                    tl::enums::KeyboardButton::Url(tl::types::KeyboardButtonUrl {
+                        text: "Docs".to_string(),
+                        url: "https://example.com".to_string(),
+                    }),
+                ]),
+                row(vec![tl::enums::KeyboardButton::Game(tl::types::KeyboardButtonGame {
+                    text: "Play".to_string(),
+                })]),
+            ],
+        });
+
+        let keyboard = InlineButton::keyboard(Some(&markup));
+        assert_eq!(keyboard.len(), 2);
+        assert_eq!(keyboard[0][0].text, "Approve");
+        assert_eq!(BASE64.decode(keyboard[0][0].data.as_ref().unwrap()).unwrap(), b"approve:42");
+        assert_eq!(keyboard[0][1].url.as_deref(), Some("https://example.com"));
+        assert!(keyboard[1][0].data.is_none() && keyboard[1][0].url.is_none());
+
+        let reply_keyboard = tl::enums::ReplyMarkup::ReplyKeyboardHide(tl::types::ReplyKeyboardHide { selective: false });
+        assert!(InlineButton::keyboard(Some(&reply_keyboard)).is_empty());
+        assert!(InlineButton::keyboard(None).is_empty());
+    }
+
+    #[test]
+    fn test_media_file_name() {
+        assert_eq!(media_file_name(7, "report.pdf", Some("application/pdf")), "7_report.pdf");
+        assert_eq!(media_file_name(7, "../../etc/passwd", None), "7_etcpasswd");
+        assert_eq!(media_file_name(8, "", Some("audio/ogg")), "8.ogg");
+        assert_eq!(media_file_name(9, "", Some("application/vnd.ms-excel")), "9.bin");
+        assert_eq!(media_file_name(9, "", None), "9.bin");
+    }
+
+    fn document(attributes: Vec<tl::enums::DocumentAttribute>) -> tl::enums::MessageMedia {
+        tl::enums::MessageMedia::Document(tl::types::MessageMediaDocument {
+            nopremium: false,
+            spoiler: false,
+            video: false,
+            round: false,
+            voice: false,
+            document: Some(tl::enums::Document::Document(tl::types::Document {
+                id: 1,
+                access_hash: 0,
+                file_reference: vec![],
+                date: 0,
+                mime_type: "application/pdf".to_string(),
+                size: 0,
+                thumbs: None,
+                video_thumbs: None,
+                dc_id: 2,
+                attributes,
+            })),
+            alt_document: None,
+            ttl_seconds: None,
+        })
+    }
+
+    fn audio(voice: bool, title: Option<&str>) -> tl::enums::DocumentAttribute {
+        tl::enums::DocumentAttribute::Audio(tl::types::DocumentAttributeAudio {
+            voice,
+            duration: 12,
+            title: title.map(str::to_string),
+            performer: None,
+            waveform: None,
+        })
+    }
+
+    #[test]
+    fn test_message_content_from_media() {
+        let content = |text: &str, media: &tl::enums::MessageMedia| {
+            MessageContent::from_parts(text, Some(media)).describe()
+        };
+
+        let photo = tl::enums::MessageMedia::Photo(tl::types::MessageMediaPhoto {
+            spoiler: false,
+            photo: None,
+            ttl_seconds: None,
+        });
+        assert_eq!(content("Look at this", &photo), "[Photo] Look at this");
+        assert_eq!(content("", &photo), "[Photo]");
+
+        let pdf = document(vec![tl::enums::DocumentAttribute::Filename(
+            tl::types::DocumentAttributeFilename { file_name: "contract.pdf".to_string() },
+        )]);
+        assert_eq!(content("Please sign", &pdf), "[Document: contract.pdf] Please sign");
+        assert_eq!(content("", &document(vec![])), "[Document: application/pdf]");
+        assert_eq!(content("", &document(vec![audio(false, Some("Song"))])), "[Document: Song]");
+        assert_eq!(content("", &document(vec![audio(true, None)])), "[Voice message, 12s]");
+
+        let video = document(vec![tl::enums::DocumentAttribute::Video(tl::types::DocumentAttributeVideo {
+            round_message: true,
+            supports_streaming: false,
+            nosound: false,
+            duration: 3.0,
+            w: 240,
+            h: 240,
+            preload_prefix_size: None,
+            video_start_ts: None,
+        })]);
+        assert_eq!(content("", &video), "[Video]");
+
+        let sticker = document(vec![tl::enums::DocumentAttribute::Sticker(tl::types::DocumentAttributeSticker {
+            mask: false,
+            alt: "👍".to_string(),
+            stickerset: tl::enums::InputStickerSet::Empty,
+            mask_coords: None,
+        })]);
+        assert_eq!(content("", &sticker), "[Sticker 👍]");
+
+        let story = tl::enums::MessageMedia::Story(Box::new(tl::types::MessageMediaStory {
+            via_mention: true,
+            peer: tl::enums::Peer::User(tl::types::PeerUser { user_id: 2 }),
+            id: 5,
+            story: None,
+        }));
+        let mention = MessageContent::from_parts("", Some(&story));
+        assert_eq!((mention.kind(), mention.describe().as_str()), ("storyMention", "[Mentioned you in a story]"));
+        assert_eq!(serde_json::to_value(&mention).unwrap()["type"], mention.kind());
+
+        assert_eq!(MessageContent::from_parts("", None).describe(), "[Media]");
+        assert_eq!(MessageContent::from_parts("hi", None).describe(), "hi");
+    }
+
+    #[test]
+    fn test_message_content_from_service_action() {
+        let call = |reason, duration| {
+            MessageContent::from_action(&tl::enums::MessageAction::PhoneCall(tl::types::MessageActionPhoneCall {
+                video: false,
+                call_id: 1,
+                reason,
+                duration,
+            }))
+            .unwrap()
+        };
+        let missed = call(Some(tl::enums::PhoneCallDiscardReason::Missed), None);
+        assert!(matches!(missed, MessageContent::Call { missed: true, .. }));
+        assert_eq!(missed.describe(), "[Missed call]");
+        assert_eq!(call(Some(tl::enums::PhoneCallDiscardReason::Hangup), Some(42)).describe(), "[Ended call, 42s]");
+
+        let joined = tl::enums::MessageAction::ChatAddUser(tl::types::MessageActionChatAddUser { users: vec![1, 2] });
+        assert_eq!(MessageContent::from_action(&joined).unwrap().describe(), "[Added 2 members]");
+        let title = tl::enums::MessageAction::ChatEditTitle(tl::types::MessageActionChatEditTitle {
+            title: "Launch".to_string(),
+        });
+        assert_eq!(MessageContent::from_action(&title).unwrap().describe(), "[Changed the chat title to \"Launch\"]");
+        assert!(MessageContent::from_action(&tl::enums::MessageAction::HistoryClear).is_none());
+
+        let gift = tl::enums::MessageAction::GiftPremium(tl::types::MessageActionGiftPremium {
+            currency: "USD".to_string(),
+            amount: 1399,
+            months: 3,
+            crypto_currency: None,
+            crypto_amount: None,
+        });
+        let gift = MessageContent::from_action(&gift).unwrap();
+        assert_eq!((gift.kind(), gift.describe().as_str()), ("premiumGift", "[Gifted Telegram Premium for 3 months]"));
+    }
+}
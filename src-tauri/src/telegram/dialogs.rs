@@ -0,0 +1,243 @@
+//! Conversion of Telegram dialogs into our `Chat` type. The mapper remembers
+//! each dialog's state (unread, pinned, muted, archived, last message) so chats
+//! later served from the chat cache match the ones `get_chats` returned.
+
+use super::client::{Chat, Message, TelegramClient};
+use grammers_client::types::{Chat as TgChat, Dialog};
+use grammers_tl_types as tl;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Per-dialog state that isn't part of the chat object itself
+#[derive(Debug, Clone, Default)]
+pub struct DialogState {
+    pub unread_count: i32,
+    pub is_pinned: bool,
+    pub is_muted: bool,
+    pub is_archived: bool,
+    pub last_message: Option<Message>,
+}
+
+impl DialogState {
+    fn from_raw(raw: &tl::enums::Dialog) -> Self {
+        match raw {
+            tl::enums::Dialog::Dialog(d) => {
+                let tl::enums::PeerNotifySettings::Settings(settings) = &d.notify_settings;
+                Self {
+                    unread_count: d.unread_count,
+                    is_pinned: d.pinned,
+                    // mute_until > 0 or silent = true means muted
+                    is_muted: settings.mute_until.map(|t| t > 0).unwrap_or(false)
+                        || settings.silent.unwrap_or(false),
+                    is_archived: d.folder_id == Some(1),
+                    last_message: None,
+                }
+            }
+            tl::enums::Dialog::Folder(_) => Self::default(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DialogMapper {
+    states: RwLock<HashMap<i64, DialogState>>,
+}
+
+impl DialogMapper {
+    /// Remember a dialog's state and convert it to a `Chat`
+    pub fn map(&self, dialog: &Dialog) -> Chat {
+        let state = self.record(dialog);
+        Self::to_chat(&dialog.chat, state)
+    }
+
+    /// Remember a dialog's state without converting it
+    pub fn record(&self, dialog: &Dialog) -> DialogState {
+        let last_message = dialog.last_message.as_ref().map(TelegramClient::convert_message);
+        self.record_raw(dialog.chat.id(), &dialog.raw, last_message)
+    }
+
+    /// Remember the state of a raw dialog, e.g. one fetched on its own
+    pub fn record_raw(&self, chat_id: i64, raw: &tl::enums::Dialog, last_message: Option<Message>) -> DialogState {
+        let state = DialogState { last_message, ..DialogState::from_raw(raw) };
+        self.states
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(chat_id, state.clone());
+        state
+    }
+
+    /// Convert a cached chat, using the state of its dialog when it has been seen
+    pub fn map_cached(&self, chat: &TgChat) -> Chat {
+        let state = self
+            .states
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&chat.id())
+            .cloned()
+            .unwrap_or_default();
+        Self::to_chat(chat, state)
+    }
+
+    /// Make a newly received message its dialog's last message, counting it as
+    /// unread if it's incoming
+    pub fn apply_message(&self, message: &Message) {
+        let mut states = self.states.write().unwrap_or_else(|e| e.into_inner());
+        let state = states.entry(message.chat_id).or_default();
+        if state.last_message.as_ref().is_some_and(|last| last.id >= message.id) {
+            return;
+        }
+        if !message.is_outgoing {
+            state.unread_count += 1;
+        }
+        state.last_message = Some(message.clone());
+    }
+
+    /// Replace the dialog's last message if `message` is an edit of it
+    pub fn apply_edit(&self, message: &Message) -> bool {
+        let mut states = self.states.write().unwrap_or_else(|e| e.into_inner());
+        match states.get_mut(&message.chat_id) {
+            Some(DialogState { last_message: Some(last), .. }) if last.id == message.id => {
+                *last = message.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Set the unread count after messages were read (possibly on another device).
+    /// Returns false for dialogs not seen yet.
+    pub fn set_unread_count(&self, chat_id: i64, unread_count: i32) -> bool {
+        let mut states = self.states.write().unwrap_or_else(|e| e.into_inner());
+        match states.get_mut(&chat_id) {
+            Some(state) => {
+                state.unread_count = unread_count;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&self) {
+        self.states.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    fn to_chat(chat: &TgChat, state: DialogState) -> Chat {
+        let (chat_type, is_bot, is_contact) = match chat {
+            // Contact status comes from the raw User data
+            TgChat::User(u) => ("private", u.is_bot(), u.raw.contact),
+            TgChat::Group(_) => ("group", false, false),
+            TgChat::Channel(_) => ("channel", false, false),
+        };
+
+        let title = match chat {
+            TgChat::User(u) => format!("{} {}", u.first_name(), u.last_name().unwrap_or("")),
+            TgChat::Group(g) => g.title().to_string(),
+            TgChat::Channel(c) => c.title().to_string(),
+        };
+
+        let member_count = match chat {
+            TgChat::User(_) => None,
+            // Basic groups have participant count in raw data
+            TgChat::Group(g) => match &g.raw {
+                tl::enums::Chat::Chat(c) => Some(c.participants_count),
+                _ => None,
+            },
+            // Channels/supergroups: raw is directly a Channel struct
+            TgChat::Channel(c) => c.raw.participants_count,
+        };
+
+        Chat {
+            id: chat.id(),
+            chat_type: chat_type.to_string(),
+            title: title.trim().to_string(),
+            unread_count: state.unread_count,
+            is_pinned: state.is_pinned,
+            order: -state.last_message.as_ref().map(|m| m.date).unwrap_or(0),
+            photo: None,
+            last_message: state.last_message,
+            member_count,
+            is_muted: state.is_muted,
+            is_archived: state.is_archived,
+            is_bot,
+            is_contact,
+            original_title: None,
+            is_starred: false,
+            note: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telegram::client::MessageContent;
+    use crate::telegram::fake;
+
+    fn raw_dialog(pinned: bool, unread_count: i32, mute_until: Option<i32>, folder_id: Option<i32>) -> tl::enums::Dialog {
+        tl::enums::Dialog::Dialog(tl::types::Dialog {
+            pinned,
+            unread_mark: false,
+            view_forum_as_messages: false,
+            peer: tl::enums::Peer::User(tl::types::PeerUser { user_id: 42 }),
+            top_message: 0,
+            read_inbox_max_id: 0,
+            read_outbox_max_id: 0,
+            unread_count,
+            unread_mentions_count: 0,
+            unread_reactions_count: 0,
+            notify_settings: tl::enums::PeerNotifySettings::Settings(tl::types::PeerNotifySettings {
+                show_previews: None,
+                silent: None,
+                mute_until,
+                ios_sound: None,
+                android_sound: None,
+                other_sound: None,
+                stories_muted: None,
+                stories_hide_sender: None,
+                stories_ios_sound: None,
+                stories_android_sound: None,
+                stories_other_sound: None,
+            }),
+            pts: None,
+            draft: None,
+            folder_id,
+            ttl_period: None,
+        })
+    }
+
+    #[test]
+    fn test_dialog_state_from_raw() {
+        let state = DialogState::from_raw(&raw_dialog(true, 3, None, None));
+        assert_eq!(state.unread_count, 3);
+        assert!(state.is_pinned && !state.is_muted && !state.is_archived);
+
+        let state = DialogState::from_raw(&raw_dialog(false, 0, Some(i32::MAX), Some(1)));
+        assert!(state.is_muted && state.is_archived && !state.is_pinned);
+
+        // mute_until of 0 means notifications are on
+        let state = DialogState::from_raw(&raw_dialog(false, 0, Some(0), Some(0)));
+        assert!(!state.is_muted && !state.is_archived);
+    }
+
+    #[test]
+    fn test_updates_change_dialog_state() {
+        let ann = fake::user(42, "Ann", "");
+        let mapper = DialogMapper::default();
+        assert!(!mapper.set_unread_count(42, 0));
+
+        mapper.apply_message(&fake::text_message(1, 42, &ann, "Hi", 100));
+        mapper.apply_message(&fake::text_message(2, 42, &ann, "Are you there?", 110));
+        // A message already seen doesn't count twice
+        mapper.apply_message(&fake::text_message(2, 42, &ann, "Are you there?", 110));
+        let state = || mapper.states.read().unwrap().get(&42).cloned().unwrap();
+        assert_eq!((state().unread_count, state().last_message.unwrap().id), (2, 2));
+
+        assert!(!mapper.apply_edit(&fake::text_message(1, 42, &ann, "Hello", 100)));
+        assert!(mapper.apply_edit(&fake::text_message(2, 42, &ann, "Still there?", 110)));
+        let edited = state().last_message.unwrap().content;
+        assert!(matches!(edited, MessageContent::Text { text } if text == "Still there?"));
+
+        assert!(mapper.set_unread_count(42, 0));
+        assert_eq!(state().unread_count, 0);
+    }
+}
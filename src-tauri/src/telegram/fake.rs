@@ -0,0 +1,295 @@
+//! In-memory `TelegramApi` for tests: seeded with dialogs, messages and contacts,
+//! and recording everything sent through it.
+
+use super::api::TelegramApi;
+use super::client::{Chat, ChatFilters, Message, MessageContent, User};
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+#[derive(Default)]
+pub struct FakeTelegram {
+    me: Option<User>,
+    chats: Vec<Chat>,
+    contacts: Vec<User>,
+    messages: Mutex<HashMap<i64, Vec<Message>>>,
+    /// Chats whose sends fail with this error (e.g. "FLOOD_WAIT_30")
+    send_errors: Mutex<HashMap<i64, String>>,
+    /// Chats whose history can't be fetched, with the error
+    fetch_errors: HashMap<i64, String>,
+    /// Chats where deleting messages fails, with the error
+    delete_errors: HashMap<i64, String>,
+    next_message_id: Mutex<i64>,
+    /// `mark_chat_read` calls, in order
+    read_marks: Mutex<Vec<(i64, Option<i64>)>>,
+}
+
+pub fn user(id: i64, first_name: &str, last_name: &str) -> User {
+    User {
+        id,
+        first_name: first_name.to_string(),
+        last_name: last_name.to_string(),
+        username: None,
+        phone_number: None,
+        profile_photo_url: None,
+    }
+}
+
+/// A dialog in Telegram's order `order`
+pub fn chat(id: i64, chat_type: &str, title: &str, unread_count: i32, order: i64) -> Chat {
+    Chat {
+        id,
+        chat_type: chat_type.to_string(),
+        title: title.to_string(),
+        unread_count,
+        is_pinned: false,
+        order,
+        photo: None,
+        last_message: None,
+        member_count: None,
+        is_muted: false,
+        is_archived: false,
+        is_bot: false,
+        is_contact: chat_type == "private",
+        original_title: None,
+        is_starred: false,
+        note: None,
+    }
+}
+
+pub fn text_message(id: i64, chat_id: i64, sender: &User, text: &str, date: i64) -> Message {
+    Message {
+        id,
+        chat_id,
+        sender_id: sender.id,
+        sender_name: sender.first_name.clone(),
+        content: MessageContent::Text { text: text.to_string() },
+        date,
+        is_outgoing: false,
+        is_read: false,
+        reply_to_message_id: None,
+        mentions_me: false,
+        inline_keyboard: Vec::new(),
+    }
+}
+
+/// The subset of `ChatFilters` that can be checked from a `Chat` alone
+fn matches_filters(chat: &Chat, filters: &ChatFilters) -> bool {
+    let type_allowed = match chat.chat_type.as_str() {
+        "private" if chat.is_bot => filters.include_bots,
+        "private" if chat.is_contact => filters.include_private_chats,
+        "private" => filters.include_non_contacts,
+        "group" => filters.include_groups,
+        "channel" => filters.include_channels,
+        _ => true,
+    };
+    let members = chat.member_count.unwrap_or(0);
+    type_allowed
+        && (filters.include_archived || !chat.is_archived)
+        && (filters.include_muted || !chat.is_muted)
+        && (!filters.include_unread_only || chat.unread_count > 0)
+        && filters.group_size_min.map_or(true, |min| chat.chat_type == "private" || members >= min)
+        && filters.group_size_max.map_or(true, |max| chat.chat_type == "private" || members <= max)
+        && (filters.folder_chat_ids.is_empty() || filters.folder_chat_ids.contains(&chat.id))
+}
+
+impl FakeTelegram {
+    pub fn new(me: User) -> Self {
+        Self { me: Some(me), next_message_id: Mutex::new(1000), ..Default::default() }
+    }
+
+    pub fn with_chat(mut self, chat: Chat) -> Self {
+        self.chats.push(chat);
+        self
+    }
+
+    pub fn with_contact(mut self, contact: User) -> Self {
+        self.contacts.push(contact);
+        self
+    }
+
+    /// Seed a chat's history (oldest first)
+    pub fn with_messages(self, chat_id: i64, messages: Vec<Message>) -> Self {
+        self.messages.lock().unwrap().entry(chat_id).or_default().extend(messages);
+        self
+    }
+
+    pub fn with_send_error(self, chat_id: i64, error: &str) -> Self {
+        self.send_errors.lock().unwrap().insert(chat_id, error.to_string());
+        self
+    }
+
+    pub fn with_fetch_error(mut self, chat_id: i64, error: &str) -> Self {
+        self.fetch_errors.insert(chat_id, error.to_string());
+        self
+    }
+
+    pub fn with_delete_error(mut self, chat_id: i64, error: &str) -> Self {
+        self.delete_errors.insert(chat_id, error.to_string());
+        self
+    }
+
+    /// Let sends to a chat succeed again (e.g. after a simulated outage)
+    pub fn clear_send_error(&self, chat_id: i64) {
+        self.send_errors.lock().unwrap().remove(&chat_id);
+    }
+
+    /// Chats marked read, with the message they were read up to
+    pub fn read_marks(&self) -> Vec<(i64, Option<i64>)> {
+        self.read_marks.lock().unwrap().clone()
+    }
+
+    /// Outgoing messages sent through the fake, per chat
+    pub fn sent(&self) -> Vec<(i64, String)> {
+        let messages = self.messages.lock().unwrap();
+        let mut sent: Vec<(i64, i64, String)> = messages
+            .values()
+            .flatten()
+            .filter(|m| m.is_outgoing)
+            .filter_map(|m| match &m.content {
+                MessageContent::Text { text } => Some((m.id, m.chat_id, text.clone())),
+                _ => None,
+            })
+            .collect();
+        sent.sort_by_key(|(id, _, _)| *id);
+        sent.into_iter().map(|(_, chat_id, text)| (chat_id, text)).collect()
+    }
+}
+
+#[async_trait]
+impl TelegramApi for FakeTelegram {
+    async fn get_current_user(&self) -> Option<User> {
+        self.me.clone()
+    }
+
+    async fn get_chats(&self, limit: i32, filters: Option<ChatFilters>) -> Result<Vec<Chat>, String> {
+        let filters = filters.unwrap_or_default();
+        let mut chats: Vec<Chat> = self.chats.iter().filter(|c| matches_filters(c, &filters)).cloned().collect();
+        chats.sort_by(|a, b| b.is_pinned.cmp(&a.is_pinned).then(a.order.cmp(&b.order)));
+        chats.truncate(limit.max(0) as usize);
+        Ok(chats)
+    }
+
+    async fn get_chat(&self, chat_id: i64) -> Result<Option<Chat>, String> {
+        Ok(self.chats.iter().find(|c| c.id == chat_id).cloned())
+    }
+
+    async fn load_chat_cache(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn cached_chat(&self, chat_id: i64) -> Option<Chat> {
+        self.chats.iter().find(|c| c.id == chat_id).cloned()
+    }
+
+    async fn cached_chats(&self) -> Vec<Chat> {
+        let mut chats = self.chats.clone();
+        chats.sort_by_key(|c| c.order);
+        chats
+    }
+
+    async fn get_chat_messages(
+        &self,
+        chat_id: i64,
+        limit: i32,
+        from_message_id: Option<i64>,
+    ) -> Result<Vec<Message>, String> {
+        if let Some(error) = self.fetch_errors.get(&chat_id) {
+            return Err(error.clone());
+        }
+        let messages = self.messages.lock().unwrap();
+        let history = messages.get(&chat_id).map(Vec::as_slice).unwrap_or_default();
+        let older: Vec<&Message> = history
+            .iter()
+            .filter(|m| from_message_id.map_or(true, |from| m.id < from))
+            .collect();
+        let skip = older.len().saturating_sub(limit.max(0) as usize);
+        Ok(older.into_iter().skip(skip).cloned().collect())
+    }
+
+    async fn search_messages(&self, query: &str, chat_id: Option<i64>, limit: i32) -> Result<Vec<Message>, String> {
+        let query = query.to_lowercase();
+        let messages = self.messages.lock().unwrap();
+        let mut found: Vec<Message> = messages
+            .values()
+            .flatten()
+            .filter(|m| chat_id.map_or(true, |id| m.chat_id == id))
+            .filter(|m| matches!(&m.content, MessageContent::Text { text } if text.to_lowercase().contains(&query)))
+            .cloned()
+            .collect();
+        found.sort_by_key(|m| std::cmp::Reverse(m.date));
+        found.truncate(limit.max(0) as usize);
+        Ok(found)
+    }
+
+    async fn get_contacts(&self) -> Result<Vec<User>, String> {
+        Ok(self.contacts.clone())
+    }
+
+    async fn send_message(&self, chat_id: i64, text: &str) -> Result<Message, String> {
+        if let Some(error) = self.send_errors.lock().unwrap().get(&chat_id) {
+            return Err(error.clone());
+        }
+        let known: HashSet<i64> = self.chats.iter().map(|c| c.id).chain(self.contacts.iter().map(|c| c.id)).collect();
+        if !known.contains(&chat_id) {
+            return Err(format!("Chat {} not found in cache", chat_id));
+        }
+
+        let id = {
+            let mut next = self.next_message_id.lock().unwrap();
+            *next += 1;
+            *next
+        };
+        let me = self.me.clone().unwrap_or_else(|| user(0, "You", ""));
+        let message = Message {
+            is_outgoing: true,
+            is_read: false,
+            sender_name: "You".to_string(),
+            ..text_message(id, chat_id, &me, text, chrono::Utc::now().timestamp())
+        };
+        self.messages.lock().unwrap().entry(chat_id).or_default().push(message.clone());
+        Ok(message)
+    }
+
+    /// Forwards show up in `sent()` as a copy of the original text
+    async fn forward_message(&self, from_chat_id: i64, message_id: i64, to_chat_id: i64) -> Result<(), String> {
+        let original = self
+            .messages
+            .lock()
+            .unwrap()
+            .get(&from_chat_id)
+            .and_then(|history| history.iter().find(|m| m.id == message_id).cloned())
+            .ok_or_else(|| format!("Message {} not found in chat {}", message_id, from_chat_id))?;
+        let MessageContent::Text { text } = &original.content else {
+            return Err("Only text messages can be forwarded by the fake".to_string());
+        };
+        self.send_message(to_chat_id, text).await.map(|_| ())
+    }
+
+    /// Media shows up in `sent()` as "[file name] caption"
+    async fn send_media(&self, chat_id: i64, caption: &str, file_path: &str) -> Result<Message, String> {
+        let file_name = std::path::Path::new(file_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.send_message(chat_id, format!("[{}] {}", file_name, caption).trim_end()).await
+    }
+
+    async fn mark_chat_read(&self, chat_id: i64, up_to_message_id: Option<i64>) -> Result<(), String> {
+        self.read_marks.lock().unwrap().push((chat_id, up_to_message_id));
+        Ok(())
+    }
+
+    async fn delete_messages(&self, chat_id: i64, message_ids: &[i32]) -> Result<usize, String> {
+        if let Some(error) = self.delete_errors.get(&chat_id) {
+            return Err(error.clone());
+        }
+        let mut messages = self.messages.lock().unwrap();
+        let Some(history) = messages.get_mut(&chat_id) else {
+            return Ok(0);
+        };
+        let before = history.len();
+        history.retain(|m| !message_ids.contains(&(m.id as i32)));
+        Ok(before - history.len())
+    }
+}
@@ -1,3 +1,8 @@
+pub mod api;
 pub mod client;
+pub mod dialogs;
+#[cfg(test)]
+pub mod fake;
 
+pub use api::TelegramApi;
 pub use client::TelegramClient;
@@ -0,0 +1,65 @@
+//! Keeps the most recent log lines in memory so they can be attached to a
+//! debug bundle (the app only logs to stderr otherwise).
+
+use once_cell::sync::Lazy;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+const MAX_LINES: usize = 2000;
+
+static RECENT_LINES: Lazy<Mutex<VecDeque<String>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(MAX_LINES)));
+
+/// env_logger wrapper that also records every emitted line
+struct BufferedLogger {
+    inner: env_logger::Logger,
+}
+
+impl log::Log for BufferedLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.inner.matches(record) {
+            return;
+        }
+
+        let line = format!(
+            "{} {} {}: {}",
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if let Ok(mut lines) = RECENT_LINES.lock() {
+            if lines.len() == MAX_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Install the logger (RUST_LOG overrides `default_filter`)
+pub fn init(default_filter: &str) {
+    let inner = env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter)).build();
+    let max_level = inner.filter();
+    if log::set_boxed_logger(Box::new(BufferedLogger { inner })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Snapshot of the buffered log lines, oldest first
+pub fn recent_lines() -> Vec<String> {
+    RECENT_LINES
+        .lock()
+        .map(|lines| lines.iter().cloned().collect())
+        .unwrap_or_default()
+}
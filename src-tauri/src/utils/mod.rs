@@ -1 +1,3 @@
+pub mod log_buffer;
 pub mod rate_limiter;
+pub mod template;
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+/// Names of `{placeholder}` variables in a template, in order of first use
+pub fn placeholders(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('}') else { break };
+        let name = &rest[..end];
+        if is_placeholder_name(name) && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+        rest = &rest[end + 1..];
+    }
+    names
+}
+
+/// Replace `{placeholder}` variables with their values; unknown ones are left as-is
+pub fn fill_placeholders(template: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}').map(|end| (end, &after[..end])) {
+            Some((end, name)) if values.contains_key(name) => {
+                out.push_str(&values[name]);
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Built-in contact variables: {name}/{first_name} (falling back to "there"),
+/// {last_name}, {full_name} and {username}
+pub fn contact_variables(first_name: &str, last_name: &str, username: Option<&str>) -> HashMap<String, String> {
+    let first = if first_name.trim().is_empty() { "there" } else { first_name.trim() };
+    let last = last_name.trim();
+    let full = if last.is_empty() {
+        first.to_string()
+    } else {
+        format!("{} {}", first, last)
+    };
+
+    HashMap::from([
+        ("name".to_string(), first.to_string()),
+        ("first_name".to_string(), first.to_string()),
+        ("last_name".to_string(), last.to_string()),
+        ("full_name".to_string(), full),
+        ("username".to_string(), username.map(|u| format!("@{}", u)).unwrap_or_default()),
+    ])
+}
+
+fn is_placeholder_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholders() {
+        assert_eq!(
+            placeholders("Hi {name}, see you {day}! {name} {not a var} {}"),
+            vec!["name", "day"]
+        );
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        let values = HashMap::from([("name".to_string(), "Ann".to_string())]);
+        assert_eq!(fill_placeholders("Hi {name}, {other} {", &values), "Hi Ann, {other} {");
+    }
+
+    #[test]
+    fn test_contact_variables() {
+        let vars = contact_variables("", "", None);
+        assert_eq!(fill_placeholders("Hi {name} {last_name}!", &vars), "Hi there !");
+
+        let vars = contact_variables("Ann", "Lee", Some("ann"));
+        assert_eq!(fill_placeholders("{full_name} ({username})", &vars), "Ann Lee (@ann)");
+    }
+}
@@ -5,9 +5,10 @@ import { useChatStore, DEFAULT_CHAT_LIMIT } from "@/stores/chatStore";
 import { useContactStore } from "@/stores/contactStore";
 import { useSummaryStore } from "@/stores/summaryStore";
 import { useBriefingStore } from "@/stores/briefingStore";
-import { chatFiltersFromSettings, getFolders } from "@/lib/tauri";
+import { chatFiltersFromSettings, getFolders, getOnboardingState, type OnboardingState } from "@/lib/tauri";
 import type { Folder } from "@/types/telegram";
 import { useTelegramEvents } from "@/hooks/useTelegram";
+import { useNotifications } from "@/hooks/useNotifications";
 import { useChats } from "@/hooks/useChats";
 import { LoginForm } from "@/components/auth/LoginForm";
 import { MainLayout } from "@/components/layout/MainLayout";
@@ -120,16 +121,24 @@ function LoadingScreen() {
   );
 }
 
+// Steps the onboarding flow walks through; the rest are handled elsewhere
+const FLOW_STEPS = ["chat_filters", "llm_configured"];
+
+function needsOnboarding(state: OnboardingState) {
+  return state.steps.some((step) => FLOW_STEPS.includes(step.id) && !step.done);
+}
+
 function App() {
   const { authState, isConnecting, connect } = useAuthStore();
-  const onboardingCompleted = useSettingsStore((s) => s.onboardingCompleted);
   const [currentView, setCurrentView] = useState<ViewType>("briefing");
   const [activeChatId, setActiveChatId] = useState<number | null>(null);
   const [activeChatName, setActiveChatName] = useState<string | undefined>(undefined);
   const [activeChatType, setActiveChatType] = useState<string | undefined>(undefined);
-  const [showOnboarding, setShowOnboarding] = useState(!onboardingCompleted);
+  // null until the backend has said whether setup is finished
+  const [showOnboarding, setShowOnboarding] = useState<boolean | null>(null);
 
   useTelegramEvents();
+  useNotifications();
 
   useEffect(() => {
     connect();
@@ -173,10 +182,22 @@ function App() {
     }
   }, [authState.type]);
 
-  // Sync showOnboarding state with store
+  // The backend tracks setup progress, so the gate survives a reinstall of the UI state
   useEffect(() => {
-    setShowOnboarding(!onboardingCompleted);
-  }, [onboardingCompleted]);
+    if (authState.type !== "ready") return;
+    let cancelled = false;
+    getOnboardingState()
+      .then((state) => {
+        if (!cancelled) setShowOnboarding(needsOnboarding(state));
+      })
+      .catch((e) => {
+        console.error("Failed to load onboarding state:", e);
+        if (!cancelled) setShowOnboarding(!useSettingsStore.getState().onboardingCompleted);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [authState.type]);
 
   const handleOpenChat = (chatId: number, chatName?: string, chatType?: string) => {
     setActiveChatId(chatId);
@@ -200,6 +221,10 @@ function App() {
     return <LoginForm />;
   }
 
+  if (showOnboarding === null) {
+    return <LoadingScreen />;
+  }
+
   // Show onboarding flow for first-time users after login
   if (showOnboarding) {
     return <OnboardingFlow onComplete={() => setShowOnboarding(false)} />;
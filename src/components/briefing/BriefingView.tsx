@@ -1,14 +1,22 @@
-import { useState, useEffect, useCallback } from "react";
+import { useState, useEffect, useCallback, useRef } from "react";
 import { Button } from "@/components/ui/button";
-import { ResponseCard } from "./ResponseCard";
+import { ResponseCard, SendUndoneError } from "./ResponseCard";
 import { FYIItem } from "./FYIItem";
+import { PendingDrafts } from "./PendingDrafts";
 import { Loader2, RefreshCw } from "lucide-react";
 import * as tauri from "@/lib/tauri";
 import { chatFiltersFromSettings } from "@/lib/tauri";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { useBriefingStore } from "@/stores/briefingStore";
 import { useChatStore } from "@/stores/chatStore";
+import { describeMessageContent } from "@/lib/utils";
 import type { Folder } from "@/types/telegram";
+import type { ChatReminder, FYIItemData, PendingSend, ResponseItem, SendCompleted } from "@/lib/tauri";
+
+interface SendWaiter {
+  resolve: () => void;
+  reject: (error: Error) => void;
+}
 
 interface BriefingViewProps {
   onOpenChat: (chatId: number, chatName: string, chatType?: string) => void;
@@ -17,6 +25,12 @@ interface BriefingViewProps {
 export function BriefingView({ onOpenChat }: BriefingViewProps) {
   const [folders, setFolders] = useState<Folder[]>([]);
   const [foldersLoaded, setFoldersLoaded] = useState(false);
+  const [reminders, setReminders] = useState<ChatReminder[]>([]);
+  const [pendingSends, setPendingSends] = useState<PendingSend[]>([]);
+  // Cards waiting for their delayed send to go out, by pending send id
+  const sendWaiters = useRef(new Map<number, SendWaiter>());
+  // Results that arrived before their send was registered (no undo window)
+  const earlyResults = useRef(new Map<number, SendCompleted>());
   const chatFilters = useSettingsStore((state) => state.chatFilters);
   const cacheTTL = useSettingsStore((state) => state.cacheTTL);
 
@@ -56,6 +70,23 @@ export function BriefingView({ onOpenChat }: BriefingViewProps) {
     };
   }, [chatFilters.selectedFolderIds]);
 
+  // Due chat reminders resurface with each briefing
+  const generatedAt = data?.generated_at;
+  useEffect(() => {
+    tauri.getDueChatReminders()
+      .then(setReminders)
+      .catch((err) => console.error("Failed to load reminders:", err));
+  }, [generatedAt]);
+
+  const handleCompleteReminder = useCallback(async (id: number) => {
+    try {
+      await tauri.completeChatReminder(id);
+      setReminders((prev) => prev.filter((r) => r.id !== id));
+    } catch (err) {
+      console.error("Failed to complete reminder:", err);
+    }
+  }, []);
+
   // Load briefing when filters/folders/TTL change (dedup handled inside loadBriefing)
   useEffect(() => {
     if (!foldersLoaded) return;
@@ -75,34 +106,102 @@ export function BriefingView({ onOpenChat }: BriefingViewProps) {
     });
   }, [chatFilters, folders, cacheTTL.briefingTTLMinutes, loadBriefing]);
 
-  const handleSend = useCallback(async (chatId: number, message: string) => {
-    await tauri.sendMessage(chatId, message);
+  const settleSend = useCallback((result: SendCompleted) => {
+    const waiter = sendWaiters.current.get(result.id);
+    if (!waiter) {
+      earlyResults.current.set(result.id, result);
+      return;
+    }
+    sendWaiters.current.delete(result.id);
+    if (result.error) {
+      waiter.reject(new Error(result.error));
+    } else {
+      waiter.resolve();
+    }
   }, []);
 
-  const handleGetDraft = useCallback(async (chatId: number): Promise<string> => {
+  // Drafts go out after the undo window; the card waiting on each one settles
+  // when it has actually been sent or has failed
+  useEffect(() => {
+    const unlisten = tauri.onSendCompleted((result) => {
+      setPendingSends((prev) => prev.filter((p) => p.id !== result.id));
+      settleSend(result);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [settleSend]);
+
+  // Resolves once the message is sent; rejects if it fails or is undone
+  // Replying handles the chat, so once the reply is sent the backend marks it
+  // read up to the last message the briefing showed
+  const handleSend = useCallback(async (chatId: number, message: string, lastMessageId?: number | null) => {
+    const pending = await tauri.sendMessageWithUndo(chatId, message, lastMessageId);
+    const sent = new Promise<void>((resolve, reject) => {
+      sendWaiters.current.set(pending.id, { resolve, reject });
+    });
+    const early = earlyResults.current.get(pending.id);
+    if (early) {
+      earlyResults.current.delete(pending.id);
+      settleSend(early);
+    } else {
+      setPendingSends((prev) => [...prev, pending]);
+    }
+    return sent;
+  }, [settleSend]);
+
+  const handleUndoSend = useCallback(async (id: number) => {
     try {
-      const store = useChatStore.getState();
-      const messages = await store.loadMessages(chatId, 20);
-      const filters = chatFiltersFromSettings(chatFilters, folders);
-      const chats = await store.loadChats(100, filters);
-      const chat = chats.find((c) => c.id === chatId);
-
-      const result = await tauri.generateDraft(
-        chatId,
-        chat?.title || "Chat",
-        messages.map((m) => ({
-          sender_name: m.senderName,
-          text: m.content.type === "text" ? m.content.text : "[Media]",
-          is_outgoing: m.isOutgoing,
-        }))
-      );
+      await tauri.undoSend(id);
+    } catch (err) {
+      // Already on its way; its completion event settles the card
+      console.error("Failed to undo send:", err);
+      return;
+    }
+    setPendingSends((prev) => prev.filter((p) => p.id !== id));
+    const waiter = sendWaiters.current.get(id);
+    sendWaiters.current.delete(id);
+    waiter?.reject(new SendUndoneError());
+  }, []);
+
+  const requestDraft = useCallback(async (chatId: number, mode: tauri.DraftMode) => {
+    const store = useChatStore.getState();
+    const messages = await store.loadMessages(chatId, 20);
+    const filters = chatFiltersFromSettings(chatFilters, folders);
+    const chats = await store.loadChats(100, filters);
+    const chat = chats.find((c) => c.id === chatId);
 
+    return tauri.generateDraft(
+      chatId,
+      chat?.title || "Chat",
+      messages.map((m) => ({
+        sender_name: m.senderName,
+        text: describeMessageContent(m.content),
+        is_outgoing: m.isOutgoing,
+      })),
+      { mode, useContactNotes: chat?.type === "private" }
+    );
+  }, [chatFilters, folders]);
+
+  const handleGetDraft = useCallback(async (chatId: number): Promise<string> => {
+    try {
+      const result = await requestDraft(chatId, "single");
       return result.draft || "";
     } catch (err) {
       console.error("Failed to generate draft:", err);
     }
     return "";
-  }, [chatFilters, folders]);
+  }, [requestDraft]);
+
+  const handleGetReplyOptions = useCallback(async (chatId: number): Promise<tauri.ReplyOption[]> => {
+    try {
+      const result = await requestDraft(chatId, "options");
+      return result.options ?? [];
+    } catch (err) {
+      console.error("Failed to generate reply options:", err);
+    }
+    return [];
+  }, [requestDraft]);
 
   // Calculate greeting based on time
   const getGreeting = () => {
@@ -122,9 +221,39 @@ export function BriefingView({ onOpenChat }: BriefingViewProps) {
     );
   }
 
-  const hasNeedsResponse = data && data.needs_response.length > 0;
-  const hasFYI = data && data.fyi_summaries.length > 0;
-  const isEmpty = !hasNeedsResponse && !hasFYI;
+  // Chats tagged with a custom category are listed in that category's section instead
+  const sections = (data?.sections ?? []).filter((section) => section.chat_ids.length > 0);
+  const sectionChatIds = new Set(sections.flatMap((section) => section.chat_ids));
+  const needsResponse = data?.needs_response.filter((item) => !sectionChatIds.has(item.chat_id)) ?? [];
+  const fyiSummaries = data?.fyi_summaries.filter((item) => !sectionChatIds.has(item.chat_id)) ?? [];
+
+  const hasNeedsResponse = needsResponse.length > 0;
+  const hasFYI = fyiSummaries.length > 0;
+  const hasFailed = !!data?.failed_chats?.length;
+  const isEmpty = !hasNeedsResponse && !hasFYI && sections.length === 0 && !hasFailed && reminders.length === 0;
+
+  const renderResponseCard = (item: ResponseItem) => (
+    <ResponseCard
+      key={item.id}
+      item={item}
+      onOpenChat={onOpenChat}
+      onSend={handleSend}
+      onDraft={handleGetDraft}
+      onReplyOptions={handleGetReplyOptions}
+      onRemove={removeItem}
+    />
+  );
+
+  const renderFYIItem = (item: FYIItemData) => (
+    <FYIItem
+      key={item.id}
+      item={item}
+      onOpenChat={() => {
+        const telegramType = item.chat_type === "dm" ? "private" : item.chat_type;
+        onOpenChat(item.chat_id, item.chat_name, telegramType);
+      }}
+    />
+  );
 
   // Error state UI
   if (error && !data) {
@@ -210,47 +339,116 @@ export function BriefingView({ onOpenChat }: BriefingViewProps) {
         </div>
       )}
 
+      {/* Chat reminders that came due */}
+      {reminders.length > 0 && (
+        <section className="space-y-3">
+          <h3 className="text-lg font-semibold flex items-center gap-2">
+            Reminders ({reminders.length})
+          </h3>
+          <div className="space-y-2">
+            {reminders.map((reminder) => (
+              <div
+                key={reminder.id}
+                className="p-3 rounded-lg border border-violet-200/50 dark:border-violet-800/40 bg-violet-100/30 dark:bg-violet-950/20 flex items-center gap-3"
+              >
+                <button
+                  className="flex-1 text-left min-w-0"
+                  onClick={() => onOpenChat(reminder.chatId, reminder.chatTitle, reminder.chatType)}
+                >
+                  <div className="font-medium truncate">{reminder.chatTitle}</div>
+                  <div className="text-sm text-muted-foreground truncate">
+                    {reminder.note || "Follow up on this chat"}
+                  </div>
+                </button>
+                <Button variant="ghost" size="sm" onClick={() => handleCompleteReminder(reminder.id)}>
+                  Done
+                </Button>
+              </div>
+            ))}
+          </div>
+        </section>
+      )}
+
+      {/* Automated replies awaiting approval */}
+      <PendingDrafts />
+
       {/* Needs Reply Section */}
       {hasNeedsResponse && (
         <section className="space-y-3">
           <h3 className="text-lg font-semibold flex items-center gap-2">
-            Needs Reply ({data.needs_response.length})
+            Needs Reply ({needsResponse.length})
+          </h3>
+          <div className="space-y-3">{needsResponse.map(renderResponseCard)}</div>
+        </section>
+      )}
+
+      {/* Custom category sections */}
+      {sections.map((section) => (
+        <section key={section.name} className="space-y-3">
+          <h3 className="text-lg font-semibold flex items-center gap-2" title={section.description}>
+            {section.name} ({section.chat_ids.length})
           </h3>
           <div className="space-y-3">
-            {data.needs_response.map((item) => (
-              <ResponseCard
-                key={item.id}
-                item={item}
-                onOpenChat={onOpenChat}
-                onSend={handleSend}
-                onDraft={handleGetDraft}
-                onRemove={removeItem}
-              />
-            ))}
+            {data?.needs_response
+              .filter((item) => section.chat_ids.includes(item.chat_id))
+              .map(renderResponseCard)}
+          </div>
+          <div className="space-y-2">
+            {data?.fyi_summaries
+              .filter((item) => section.chat_ids.includes(item.chat_id))
+              .map(renderFYIItem)}
           </div>
         </section>
-      )}
+      ))}
 
       {/* FYI Section */}
       {hasFYI && (
         <section className="space-y-3">
           <h3 className="text-lg font-semibold flex items-center gap-2">
-            FYI ({data.fyi_summaries.length})
+            FYI ({fyiSummaries.length})
+          </h3>
+          <div className="space-y-2">{fyiSummaries.map(renderFYIItem)}</div>
+        </section>
+      )}
+
+      {/* Chats that could not be analyzed */}
+      {hasFailed && data?.failed_chats && (
+        <section className="space-y-3">
+          <h3 className="text-lg font-semibold flex items-center gap-2">
+            Could not analyze ({data.failed_chats.length})
           </h3>
           <div className="space-y-2">
-            {data.fyi_summaries.map((item) => (
-              <FYIItem
-                key={item.id}
-                item={item}
-                onOpenChat={() => {
-                  const telegramType = item.chat_type === "dm" ? "private" : item.chat_type;
-                  onOpenChat(item.chat_id, item.chat_name, telegramType);
-                }}
-              />
+            {data.failed_chats.map((chat) => (
+              <button
+                key={chat.chat_id}
+                onClick={() => onOpenChat(chat.chat_id, chat.chat_name)}
+                className="w-full text-left p-2 rounded border border-border text-sm hover:bg-muted transition-colors"
+              >
+                <span className="font-medium">{chat.chat_name}</span>
+                <span className="text-muted-foreground"> · {chat.unread_count} unread</span>
+                <p className="text-xs text-muted-foreground line-clamp-1">{chat.reason}</p>
+              </button>
             ))}
           </div>
         </section>
       )}
+
+      {/* Sends that can still be undone */}
+      {pendingSends.length > 0 && (
+        <div className="fixed bottom-4 left-1/2 -translate-x-1/2 space-y-2 z-50">
+          {pendingSends.map((pending) => (
+            <div
+              key={pending.id}
+              className="flex items-center gap-3 rounded-lg border bg-background px-4 py-2 shadow-lg text-sm"
+            >
+              <span className="truncate max-w-xs">Sending "{pending.text}"</span>
+              <Button variant="outline" size="sm" onClick={() => handleUndoSend(pending.id)}>
+                Undo
+              </Button>
+            </div>
+          ))}
+        </div>
+      )}
     </div>
   );
 }
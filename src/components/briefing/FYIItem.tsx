@@ -1,3 +1,7 @@
+import { Button } from "@/components/ui/button";
+import { formatReadingTime } from "@/lib/utils";
+import { useBriefingCorrection } from "./useBriefingCorrection";
+
 interface FYIItemData {
   id: number;
   chat_id: number;
@@ -8,6 +12,10 @@ interface FYIItemData {
   last_message_date: string | null;
   priority: "fyi";
   summary: string;
+  chat_note?: string | null;
+  message_count?: number;
+  reading_time_secs?: number;
+  category?: string | null;
 }
 
 interface FYIItemProps {
@@ -16,27 +24,59 @@ interface FYIItemProps {
 }
 
 export function FYIItem({ item, onOpenChat }: FYIItemProps) {
+  // Teach future briefings that this chat does need a reply
+  const correction = useBriefingCorrection(item.chat_id);
+  const markedNeedsReply = correction.corrected === "needs_reply";
+
   return (
-    <button
-      onClick={onOpenChat}
-      className="w-full text-left p-3 rounded-lg border hover:bg-muted/50 transition-colors grid grid-cols-[1fr_auto_2fr] gap-4 items-center"
-    >
-      {/* Name (Left) */}
-      <div className="truncate">
-        <span className="font-medium">{item.chat_name}</span>
-      </div>
+    <div className="flex items-center gap-2 rounded-lg border hover:bg-muted/50 transition-colors">
+      <button
+        onClick={onOpenChat}
+        className="flex-1 min-w-0 text-left p-3 grid grid-cols-[1fr_auto_2fr] gap-4 items-center"
+      >
+        {/* Name (Left) */}
+        <div className="truncate">
+          <span className="font-medium">{item.chat_name}</span>
+        </div>
+
+        {/* Count (Center) */}
+        <div className="text-center">
+          <span
+            title={item.reading_time_secs ? formatReadingTime(item.reading_time_secs) : undefined}
+            className="text-sm bg-sky-100/50 dark:bg-sky-950/40 text-sky-700 dark:text-sky-400 border border-sky-200/50 dark:border-sky-800/40 px-2 py-0.5 rounded-full"
+          >
+            {item.unread_count}
+          </span>
+        </div>
 
-      {/* Count (Center) */}
-      <div className="text-center">
-        <span className="text-sm bg-sky-100/50 dark:bg-sky-950/40 text-sky-700 dark:text-sky-400 border border-sky-200/50 dark:border-sky-800/40 px-2 py-0.5 rounded-full">
-          {item.unread_count}
-        </span>
-      </div>
+        {/* Summary (Right) */}
+        <div className="truncate text-sm text-muted-foreground" title={correction.error ?? item.chat_note ?? undefined}>
+          {markedNeedsReply ? (
+            "Future briefings will ask you to reply"
+          ) : (
+            <>
+              {item.chat_note && <span className="mr-1">📝</span>}
+              {item.summary || "No action needed"}
+            </>
+          )}
+        </div>
+      </button>
 
-      {/* Summary (Right) */}
-      <div className="truncate text-sm text-muted-foreground">
-        {item.summary || "No action needed"}
-      </div>
-    </button>
+      {markedNeedsReply ? (
+        <Button variant="ghost" size="sm" className="mr-2 shrink-0" onClick={correction.undo}>
+          Undo
+        </Button>
+      ) : (
+        <Button
+          variant="ghost"
+          size="sm"
+          className="mr-2 shrink-0"
+          onClick={() => correction.correct("needs_reply")}
+          title="Needs a reply. Future briefings will treat this chat as needing one."
+        >
+          Needs reply
+        </Button>
+      )}
+    </div>
   );
 }
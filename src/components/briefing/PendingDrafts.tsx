@@ -0,0 +1,114 @@
+import { useCallback, useEffect, useState } from "react";
+import { Button } from "@/components/ui/button";
+import { Textarea } from "@/components/ui/textarea";
+import * as tauri from "@/lib/tauri";
+import type { PendingDraft } from "@/lib/tauri";
+import { useChatStore } from "@/stores/chatStore";
+
+const SOURCE_LABELS: Record<PendingDraft["source"], string> = {
+  away: "Away reply",
+  rule: "Rule reply",
+  ai: "AI suggestion",
+  mcp: "External assistant",
+};
+
+interface PendingDraftRowProps {
+  draft: PendingDraft;
+  chatTitle: string;
+  onResolved: () => void;
+}
+
+function PendingDraftRow({ draft, chatTitle, onResolved }: PendingDraftRowProps) {
+  const [text, setText] = useState(draft.text);
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const resolve = async (action: () => Promise<unknown>) => {
+    setBusy(true);
+    setError(null);
+    try {
+      await action();
+      onResolved();
+    } catch (err) {
+      // A failed send stays in the queue, so it can be retried or edited
+      setError(String(err));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="p-3 rounded-lg border space-y-2">
+      <div className="flex items-center justify-between text-sm">
+        <span className="font-medium truncate">{chatTitle}</span>
+        <span className="text-xs text-muted-foreground">{SOURCE_LABELS[draft.source]}</span>
+      </div>
+      <Textarea
+        value={text}
+        onChange={(e: React.ChangeEvent<HTMLTextAreaElement>) => setText(e.target.value)}
+        className="resize-none text-sm"
+        rows={2}
+      />
+      {error && <p className="text-sm text-red-700 dark:text-red-400">Failed to send: {error}</p>}
+      <div className="flex justify-end gap-2">
+        <Button
+          variant="ghost"
+          size="sm"
+          disabled={busy}
+          onClick={() => resolve(() => tauri.rejectDraft(draft.id))}
+        >
+          Discard
+        </Button>
+        <Button
+          size="sm"
+          disabled={busy || !text.trim()}
+          onClick={() => resolve(() => tauri.approveDraft(draft.id, text === draft.text ? undefined : text))}
+        >
+          {busy ? "⏳" : "📨 Send"}
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+/** Automated replies held back until the user approves them */
+export function PendingDrafts() {
+  const [drafts, setDrafts] = useState<PendingDraft[]>([]);
+  const chats = useChatStore((state) => state.chats);
+
+  const load = useCallback(() => {
+    tauri.getPendingDrafts()
+      .then(setDrafts)
+      .catch((err) => console.error("Failed to load pending drafts:", err));
+  }, []);
+
+  useEffect(() => {
+    load();
+    const unlisten = tauri.onPendingDraftCount(() => load());
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [load]);
+
+  if (drafts.length === 0) return null;
+
+  const chatTitle = (chatId: number) => chats.find((c) => c.id === chatId)?.title ?? `Chat ${chatId}`;
+
+  return (
+    <section className="space-y-3">
+      <h3 className="text-lg font-semibold flex items-center gap-2">
+        Waiting for approval ({drafts.length})
+      </h3>
+      <div className="space-y-2">
+        {drafts.map((draft) => (
+          <PendingDraftRow
+            key={draft.id}
+            draft={draft}
+            chatTitle={chatTitle(draft.chatId)}
+            onResolved={load}
+          />
+        ))}
+      </div>
+    </section>
+  );
+}
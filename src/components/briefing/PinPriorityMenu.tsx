@@ -0,0 +1,83 @@
+import { useState } from "react";
+import { Pin } from "lucide-react";
+import * as tauri from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+import { Button } from "@/components/ui/button";
+import { Popover, PopoverContent, PopoverTrigger } from "@/components/ui/popover";
+
+const PIN_OPTIONS: { value: tauri.BriefingPin["priority"]; label: string }[] = [
+  { value: "urgent", label: "Always urgent" },
+  { value: "needs_reply", label: "Always needs reply" },
+  { value: "fyi", label: "Always FYI" },
+];
+
+interface PinPriorityMenuProps {
+  chatId: number;
+  /** Called after the pin changes, with the new priority or null when unpinned */
+  onPinned?: (priority: tauri.BriefingPin["priority"] | null) => void;
+}
+
+/** Pin a chat to a briefing priority so the model no longer classifies it */
+export function PinPriorityMenu({ chatId, onPinned }: PinPriorityMenuProps) {
+  const [open, setOpen] = useState(false);
+  const [current, setCurrent] = useState<tauri.BriefingPin["priority"] | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleOpenChange = async (next: boolean) => {
+    setOpen(next);
+    if (!next) return;
+    setError(null);
+    try {
+      const pins = await tauri.getBriefingPins();
+      setCurrent(pins.find((p) => p.chatId === chatId)?.priority ?? null);
+    } catch (e) {
+      console.error("Failed to load briefing pins:", e);
+    }
+  };
+
+  const handlePin = async (priority: tauri.BriefingPin["priority"] | null) => {
+    setError(null);
+    try {
+      await tauri.setBriefingPin(chatId, priority);
+      setCurrent(priority);
+      setOpen(false);
+      onPinned?.(priority);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <Popover open={open} onOpenChange={handleOpenChange}>
+      <PopoverTrigger asChild>
+        <Button variant="ghost" size="icon" title="Pin briefing priority">
+          <Pin className={cn("h-4 w-4", current && "text-primary")} />
+        </Button>
+      </PopoverTrigger>
+      <PopoverContent className="w-52 p-1" align="end">
+        {PIN_OPTIONS.map((option) => (
+          <Button
+            key={option.value}
+            variant={current === option.value ? "secondary" : "ghost"}
+            size="sm"
+            className="w-full justify-start"
+            onClick={() => handlePin(option.value)}
+          >
+            {option.label}
+          </Button>
+        ))}
+        {current && (
+          <Button
+            variant="ghost"
+            size="sm"
+            className="w-full justify-start text-muted-foreground"
+            onClick={() => handlePin(null)}
+          >
+            Let the AI decide
+          </Button>
+        )}
+        {error && <p className="px-2 py-1 text-xs text-destructive">{error}</p>}
+      </PopoverContent>
+    </Popover>
+  );
+}
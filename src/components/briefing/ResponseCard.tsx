@@ -1,7 +1,14 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { Button } from "@/components/ui/button";
 import { Textarea } from "@/components/ui/textarea";
 import { Card, CardContent, CardHeader } from "@/components/ui/card";
+import type { ReplyOption } from "@/lib/tauri";
+import { formatReadingTime } from "@/lib/utils";
+import { PinPriorityMenu } from "./PinPriorityMenu";
+import { useBriefingCorrection } from "./useBriefingCorrection";
+
+// How long a card moved to FYI stays, offering undo
+const FYI_UNDO_MS = 5000;
 
 interface ResponseItem {
   id: number;
@@ -14,13 +21,29 @@ interface ResponseItem {
   priority: "urgent" | "needs_reply";
   summary: string;
   suggested_reply: string | null;
+  injection_warning?: string | null;
+  chat_note?: string | null;
+  message_count?: number;
+  reading_time_secs?: number;
+  category?: string | null;
+  last_message_id?: number | null;
+}
+
+/** Rejection of a send the user undid, so cards can tell it from a failure */
+export class SendUndoneError extends Error {
+  constructor() {
+    super("Send undone");
+    this.name = "SendUndoneError";
+  }
 }
 
 interface ResponseCardProps {
   item: ResponseItem;
   onOpenChat: (chatId: number, chatName: string, chatType?: string) => void;
-  onSend: (chatId: number, message: string) => Promise<void>;
+  /** Resolves once the message has gone out; rejects if it fails or is undone */
+  onSend: (chatId: number, message: string, lastMessageId?: number | null) => Promise<void>;
   onDraft: (chatId: number) => Promise<string>;
+  onReplyOptions?: (chatId: number) => Promise<ReplyOption[]>;
   onRemove: (chatId: number) => void;
 }
 
@@ -29,11 +52,14 @@ export function ResponseCard({
   onOpenChat,
   onSend,
   onDraft,
+  onReplyOptions,
   onRemove,
 }: ResponseCardProps) {
   const [draft, setDraft] = useState(item.suggested_reply || "");
   const [sending, setSending] = useState(false);
   const [loadingDraft, setLoadingDraft] = useState(false);
+  const [replyOptions, setReplyOptions] = useState<ReplyOption[]>([]);
+  const [loadingOptions, setLoadingOptions] = useState(false);
   const [sent, setSent] = useState(false);
   const [sendError, setSendError] = useState<string | null>(null);
 
@@ -55,23 +81,49 @@ export function ResponseCard({
     }
   };
 
-  const handleSend = async () => {
-    if (!draft.trim() || sending) return;
+  const handleReplyOptions = async () => {
+    if (!onReplyOptions) return;
+    setLoadingOptions(true);
+    try {
+      setReplyOptions(await onReplyOptions(item.chat_id));
+    } catch (err) {
+      console.error("Failed to generate reply options:", err);
+    } finally {
+      setLoadingOptions(false);
+    }
+  };
+
+  const handleSend = async (text: string = draft) => {
+    if (!text.trim() || sending) return;
 
+    setDraft(text);
     setSending(true);
     setSendError(null);
     try {
-      await onSend(item.chat_id, draft);
+      await onSend(item.chat_id, text, item.last_message_id);
       setSent(true);
       setTimeout(() => {
         onRemove(item.chat_id);
       }, 500);
     } catch (err) {
-      setSendError(err instanceof Error ? err.message : "Unknown error");
+      // Undone: back to editing the same draft
+      if (!(err instanceof SendUndoneError)) {
+        setSendError(err instanceof Error ? err.message : String(err));
+      }
       setSending(false);
     }
   };
 
+  // Teach future briefings that this chat doesn't need a reply; the card stays
+  // a few seconds so the correction can be undone
+  const correction = useBriefingCorrection(item.chat_id);
+  const markedFYI = correction.corrected === "fyi";
+  useEffect(() => {
+    if (!markedFYI) return;
+    const timer = setTimeout(() => onRemove(item.chat_id), FYI_UNDO_MS);
+    return () => clearTimeout(timer);
+  }, [markedFYI, item.chat_id, onRemove]);
+
   const handleRetry = () => {
     setSendError(null);
     handleSend();
@@ -90,6 +142,21 @@ export function ResponseCard({
     );
   }
 
+  if (markedFYI) {
+    return (
+      <Card>
+        <CardContent className="py-4 flex items-center justify-between gap-2">
+          <p className="text-sm text-muted-foreground">
+            Moved {item.chat_name} to FYI. Future briefings will treat it as FYI.
+          </p>
+          <Button variant="outline" size="sm" onClick={correction.undo}>
+            Undo
+          </Button>
+        </CardContent>
+      </Card>
+    );
+  }
+
   const chatTypeLabel = item.chat_type === "dm" ? "DM" : item.chat_type === "group" ? "Group" : "Channel";
 
   return (
@@ -104,6 +171,7 @@ export function ResponseCard({
             <h4 className="font-semibold">{item.chat_name}</h4>
             <p className="text-xs text-muted-foreground">
               {chatTypeLabel} · {item.unread_count} unread
+              {item.reading_time_secs ? ` · ${formatReadingTime(item.reading_time_secs)}` : ""}
             </p>
           </button>
           {/* Priority Badge */}
@@ -139,6 +207,22 @@ export function ResponseCard({
           </div>
         )}
 
+        {/* The user's notes on this chat */}
+        {item.chat_note && (
+          <div className="text-sm text-muted-foreground whitespace-pre-wrap">
+            <span className="font-medium">Note:</span> {item.chat_note}
+          </div>
+        )}
+
+        {/* Prompt-injection warning */}
+        {item.injection_warning && (
+          <div className="p-2 bg-amber-100/50 dark:bg-amber-950/30 border border-amber-200/50 dark:border-amber-800/50 rounded text-sm text-amber-700 dark:text-amber-400">
+            {item.injection_warning}
+          </div>
+        )}
+
+        {correction.error && <p className="text-sm text-destructive">{correction.error}</p>}
+
         {/* Send Error Banner */}
         {sendError && (
           <div className="flex items-center justify-between gap-2 p-2 bg-red-100/50 dark:bg-red-950/30 border border-red-200/50 dark:border-red-800/50 rounded text-sm">
@@ -154,6 +238,25 @@ export function ResponseCard({
           </div>
         )}
 
+        {/* Quick replies - tap to send */}
+        {replyOptions.length > 0 && (
+          <div className="flex flex-wrap gap-2">
+            {replyOptions.map((option, i) => (
+              <Button
+                key={i}
+                variant="secondary"
+                size="sm"
+                className="h-auto whitespace-normal text-left"
+                title={`Send (${option.intent.replace(/_/g, " ")})`}
+                onClick={() => handleSend(option.text)}
+                disabled={sending}
+              >
+                {option.text}
+              </Button>
+            ))}
+          </div>
+        )}
+
         {/* Draft Textarea */}
         <Textarea
           value={draft}
@@ -178,19 +281,47 @@ export function ResponseCard({
               {loadingDraft ? "⏳" : "✨ AI"}
             </Button>
 
+            {/* Quick reply options */}
+            {onReplyOptions && (
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={handleReplyOptions}
+                disabled={loadingOptions}
+              >
+                {loadingOptions ? "⏳" : "⚡ Quick"}
+              </Button>
+            )}
+
             {/* Open Button */}
             <Button variant="outline" size="sm" onClick={handleOpenChat}>
               💬 Open
             </Button>
+
+            {/* Reclassify as FYI */}
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() => correction.correct("fyi")}
+              title="Doesn't need a reply. Future briefings will treat this chat as FYI."
+            >
+              Just FYI
+            </Button>
+
+            {/* Pinned to FYI, the chat no longer belongs in this list */}
+            <PinPriorityMenu
+              chatId={item.chat_id}
+              onPinned={(priority) => priority === "fyi" && onRemove(item.chat_id)}
+            />
           </div>
 
           {/* Send Button */}
           <Button
             size="sm"
-            onClick={handleSend}
+            onClick={() => handleSend()}
             disabled={!draft.trim() || sending}
           >
-            {sending ? "⏳" : "📨 Send"}
+            {sending ? "⏳ Sending…" : "📨 Send"}
           </Button>
         </div>
       </CardContent>
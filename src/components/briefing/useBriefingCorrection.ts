@@ -0,0 +1,32 @@
+import { useState } from "react";
+import { clearBriefingCorrection, correctBriefingItem, invalidateBriefingForChats } from "@/lib/tauri";
+
+type Priority = "urgent" | "needs_reply" | "fyi";
+
+/**
+ * Correct a chat's briefing priority, with undo. Either way the cached
+ * briefings covering the chat are dropped, so the next one reflects it.
+ */
+export function useBriefingCorrection(chatId: number) {
+  const [corrected, setCorrected] = useState<Priority | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const run = async (change: () => Promise<void>, next: Priority | null) => {
+    setError(null);
+    try {
+      await change();
+      await invalidateBriefingForChats([chatId]);
+      setCorrected(next);
+    } catch (err) {
+      console.error("Failed to save briefing correction:", err);
+      setError(String(err));
+    }
+  };
+
+  const correct = (priority: Priority) => run(() => correctBriefingItem(chatId, priority), priority);
+
+  // Forgets the chat's correction altogether, so the model classifies it on its own
+  const undo = () => run(() => clearBriefingCorrection(chatId), null);
+
+  return { corrected, error, correct, undo };
+}
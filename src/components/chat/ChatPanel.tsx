@@ -1,11 +1,18 @@
 import { useState, useEffect, useRef } from "react";
-import { X, Send, Sparkles, Loader2 } from "lucide-react";
+import { X, Send, Sparkles, Loader2, Pencil, Star, StickyNote, AlarmClock, History, Trash2, RefreshCw } from "lucide-react";
 import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
 import { Textarea } from "@/components/ui/textarea";
-import { cn } from "@/lib/utils";
+import { Modal } from "@/components/common/Modal";
+import { cn, describeMessageContent } from "@/lib/utils";
 import * as tauri from "@/lib/tauri";
+import { useChatStore } from "@/stores/chatStore";
+import { PinPriorityMenu } from "@/components/briefing/PinPriorityMenu";
 import type { Chat, Message } from "@/types/telegram";
 
+// Pages of older history archived per "Sync history" click
+const HISTORY_BACKFILL_PAGES = 5;
+
 interface ChatPanelProps {
   chatId: number | null;
   chatName?: string;
@@ -19,13 +26,24 @@ export function ChatPanel({ chatId, chatName, chatType, onClose }: ChatPanelProp
   const [draft, setDraft] = useState("");
   const [isLoading, setIsLoading] = useState(false);
   const [isSending, setIsSending] = useState(false);
+  const [sendError, setSendError] = useState<string | null>(null);
   const [isGeneratingDraft, setIsGeneratingDraft] = useState(false);
+  const [syncProgress, setSyncProgress] = useState<string | null>(null);
+  const [deleted, setDeleted] = useState<tauri.DeletedMessage[] | null>(null);
+  const [isRefreshing, setIsRefreshing] = useState(false);
+  // The local chat setting being edited in a dialog
+  const [editing, setEditing] = useState<"nickname" | "note" | "reminder" | null>(null);
+  const [nickname, setNickname] = useState("");
+  const [note, setNote] = useState("");
+  const [remindHours, setRemindHours] = useState("24");
+  const [reminderNote, setReminderNote] = useState("");
   const messagesEndRef = useRef<HTMLDivElement>(null);
 
   const isOpen = chatId !== null;
 
   // Load chat and messages when chatId changes
   useEffect(() => {
+    setDeleted(null);
     if (chatId === null) {
       setChat(null);
       setMessages([]);
@@ -70,6 +88,26 @@ export function ChatPanel({ chatId, chatName, chatType, onClose }: ChatPanelProp
     };
   }, [chatId]);
 
+  // Messages sent while offline show as a placeholder (negative id) until the
+  // outbox delivers or gives up on them
+  useEffect(() => {
+    if (!chatId) return;
+    const unlistenSent = tauri.onOutboxSent((result) => {
+      if (result.chatId !== chatId || !result.message) return;
+      const delivered = result.message;
+      setMessages((prev) => prev.map((m) => (m.id === -result.id ? delivered : m)));
+    });
+    const unlistenFailed = tauri.onOutboxFailed((result) => {
+      if (result.chatId !== chatId) return;
+      setMessages((prev) => prev.filter((m) => m.id !== -result.id));
+      setSendError(`Message not sent: ${result.error ?? "gave up after repeated failures"}`);
+    });
+    return () => {
+      unlistenSent.then((fn) => fn());
+      unlistenFailed.then((fn) => fn());
+    };
+  }, [chatId]);
+
   // Auto-scroll to bottom when messages change
   useEffect(() => {
     messagesEndRef.current?.scrollIntoView({ behavior: "smooth" });
@@ -79,17 +117,153 @@ export function ChatPanel({ chatId, chatName, chatType, onClose }: ChatPanelProp
     if (!chatId || !draft.trim() || isSending) return;
 
     setIsSending(true);
+    setSendError(null);
     try {
       const newMessage = await tauri.sendMessage(chatId, draft.trim());
       setMessages((prev) => [...prev, newMessage]);
       setDraft("");
     } catch (error) {
       console.error("Failed to send message:", error);
+      setSendError(`Failed to send: ${error}`);
     } finally {
       setIsSending(false);
     }
   };
 
+  // Bring the local archive up to date and pull a few pages of older history
+  const handleSyncHistory = async () => {
+    if (!chatId || syncProgress !== null) return;
+    setSyncProgress("Syncing history…");
+    const unlisten = await tauri.onArchiveSyncProgress((progress) => {
+      if (progress.chatId !== chatId) return;
+      setSyncProgress(
+        progress.phase === "newer"
+          ? `Archived ${progress.archived} new messages`
+          : `Archived ${progress.archived} older messages`
+      );
+    });
+    try {
+      const report = await tauri.syncChatHistory(chatId, HISTORY_BACKFILL_PAGES);
+      if (report.newMessages > 0) {
+        setMessages(await tauri.getChatMessages(chatId, 50));
+      }
+    } catch (error) {
+      console.error("Failed to sync chat history:", error);
+    } finally {
+      unlisten();
+      setSyncProgress(null);
+    }
+  };
+
+  // Refetch just this chat, bypassing the dialog cache and the message archive
+  const handleRefresh = async () => {
+    if (!chatId || isRefreshing) return;
+    setIsRefreshing(true);
+    try {
+      const refreshed = await tauri.invalidateChat(chatId);
+      if (refreshed) {
+        setChat(refreshed);
+        useChatStore.getState().updateChat(refreshed);
+      }
+      setMessages(await tauri.getChatMessages(chatId, 50));
+    } catch (error) {
+      console.error("Failed to refresh chat:", error);
+    } finally {
+      setIsRefreshing(false);
+    }
+  };
+
+  // Switch between the chat and its archived messages that were deleted
+  const handleToggleDeleted = async () => {
+    if (!chatId) return;
+    if (deleted !== null) {
+      setDeleted(null);
+      return;
+    }
+    try {
+      setDeleted(await tauri.getRecentlyDeleted(chatId));
+    } catch (error) {
+      console.error("Failed to load deleted messages:", error);
+    }
+  };
+
+  const originalTitle = chat?.originalTitle ?? chat?.title ?? "";
+
+  const handleRename = () => {
+    if (!chat) return;
+    setNickname(chat.originalTitle ? chat.title : "");
+    setEditing("nickname");
+  };
+
+  const handleSaveNickname = async () => {
+    if (!chat) return;
+    try {
+      await tauri.setChatAlias(chat.id, nickname);
+      const renamed = nickname.trim()
+        ? { ...chat, title: nickname.trim(), originalTitle }
+        : { ...chat, title: originalTitle, originalTitle: null };
+      setChat(renamed);
+      useChatStore.getState().updateChat(renamed);
+      setEditing(null);
+    } catch (error) {
+      console.error("Failed to rename chat:", error);
+    }
+  };
+
+  const handleToggleStar = async () => {
+    if (!chat) return;
+    try {
+      await tauri.setChatStarred(chat.id, !chat.isStarred);
+      const updated = { ...chat, isStarred: !chat.isStarred };
+      setChat(updated);
+      useChatStore.getState().updateChat(updated);
+    } catch (error) {
+      console.error("Failed to star chat:", error);
+    }
+  };
+
+  const handleEditNote = () => {
+    if (!chat) return;
+    setNote(chat.note ?? "");
+    setEditing("note");
+  };
+
+  const handleSaveNote = async () => {
+    if (!chat) return;
+    try {
+      await tauri.updateChatNotes(chat.id, note);
+      const updated = { ...chat, note: note.trim() ? note : null };
+      setChat(updated);
+      useChatStore.getState().updateChat(updated);
+      setEditing(null);
+    } catch (error) {
+      console.error("Failed to save chat note:", error);
+    }
+  };
+
+  const handleRemind = () => {
+    setRemindHours("24");
+    setReminderNote("");
+    setEditing("reminder");
+  };
+
+  const remindDelay = Number(remindHours);
+  const isValidDelay = remindHours.trim() !== "" && Number.isFinite(remindDelay) && remindDelay >= 0;
+
+  const handleSaveReminder = async () => {
+    if (!chat || !isValidDelay) return;
+    try {
+      await tauri.remindAboutChat(
+        chat.id,
+        Math.floor(Date.now() / 1000 + remindDelay * 3600),
+        reminderNote.trim() || undefined
+      );
+      setEditing(null);
+    } catch (error) {
+      console.error("Failed to set reminder:", error);
+    }
+  };
+
   const handleKeyDown = (e: React.KeyboardEvent) => {
     if (e.key === "Enter" && !e.shiftKey) {
       e.preventDefault();
@@ -105,14 +279,15 @@ export function ChatPanel({ chatId, chatName, chatType, onClose }: ChatPanelProp
       // Prepare messages for the AI - take last 20 messages for context
       const recentMessages = messages.slice(-20).map((msg) => ({
         sender_name: msg.isOutgoing ? "You" : (msg.senderName || "User"),
-        text: msg.content.type === "text" ? msg.content.text : `[${msg.content.type}]`,
+        text: describeMessageContent(msg.content),
         is_outgoing: msg.isOutgoing,
       }));
 
       const result = await tauri.generateDraft(
         chatId,
         chat?.title || chatName || "Chat",
-        recentMessages
+        recentMessages,
+        { useContactNotes: chat?.type === "private" }
       );
 
       setDraft(result.draft);
@@ -152,16 +327,81 @@ export function ChatPanel({ chatId, chatName, chatType, onClose }: ChatPanelProp
              (chat?.type || chatType) === "channel" ? "📢" :
              (chat?.type || chatType) === "secret" ? "🔒" : "💬"}
           </span>
-          <span className="font-medium truncate">{chat?.title || chatName || "Chat"}</span>
+          <span className="font-medium truncate" title={chat?.originalTitle ?? undefined}>
+            {chat?.title || chatName || "Chat"}
+          </span>
+        </div>
+        <div className="flex items-center shrink-0">
+          {chat && (
+            <>
+              <PinPriorityMenu chatId={chat.id} />
+              <Button variant="ghost" size="icon" onClick={handleToggleStar} title={chat.isStarred ? "Unstar" : "Star"}>
+                <Star className={cn("h-4 w-4", chat.isStarred && "fill-yellow-400 text-yellow-400")} />
+              </Button>
+              <Button variant="ghost" size="icon" onClick={handleRemind} title="Remind me about this chat">
+                <AlarmClock className="h-4 w-4" />
+              </Button>
+              <Button variant="ghost" size="icon" onClick={handleEditNote} title={chat.note || "Add note"}>
+                <StickyNote className={cn("h-4 w-4", chat.note && "text-primary")} />
+              </Button>
+              <Button variant="ghost" size="icon" onClick={handleRename} title="Set nickname">
+                <Pencil className="h-4 w-4" />
+              </Button>
+              <Button variant="ghost" size="icon" onClick={handleRefresh} disabled={isRefreshing} title="Refresh this chat">
+                <RefreshCw className={cn("h-4 w-4", isRefreshing && "animate-spin")} />
+              </Button>
+              <Button
+                variant="ghost"
+                size="icon"
+                onClick={handleSyncHistory}
+                disabled={syncProgress !== null}
+                title={syncProgress ?? "Sync history to the local archive"}
+              >
+                {syncProgress !== null ? <Loader2 className="h-4 w-4 animate-spin" /> : <History className="h-4 w-4" />}
+              </Button>
+              <Button
+                variant="ghost"
+                size="icon"
+                onClick={handleToggleDeleted}
+                title={deleted !== null ? "Back to the chat" : "Recently deleted messages"}
+              >
+                <Trash2 className={cn("h-4 w-4", deleted !== null && "text-primary")} />
+              </Button>
+            </>
+          )}
+          <Button variant="ghost" size="icon" onClick={onClose}>
+            <X className="h-4 w-4" />
+          </Button>
         </div>
-        <Button variant="ghost" size="icon" onClick={onClose}>
-          <X className="h-4 w-4" />
-        </Button>
       </div>
 
       {/* Messages */}
       <div className="flex-1 overflow-y-auto p-4 space-y-4">
-        {isLoading ? (
+        {deleted !== null ? (
+          deleted.length === 0 ? (
+            <div className="flex items-center justify-center h-full text-muted-foreground">
+              No deleted messages in the archive
+            </div>
+          ) : (
+            deleted.map((item) => (
+              <div key={item.message.id} className="flex flex-col items-start">
+                <span className="text-xs font-medium mb-1 text-muted-foreground">
+                  {item.message.isOutgoing ? "You" : (item.message.senderName || chat?.title || "User")}
+                  {" · deleted "}
+                  {new Date(item.deletedAt * 1000).toLocaleString()}
+                </span>
+                <div className="rounded-lg px-3 py-2 text-sm bg-muted line-through decoration-muted-foreground/50">
+                  {describeMessageContent(item.message.content)}
+                </div>
+                {item.earlierVersions.map((text, i) => (
+                  <span key={i} className="text-xs text-muted-foreground mt-1">
+                    Earlier: {text}
+                  </span>
+                ))}
+              </div>
+            ))
+          )
+        ) : isLoading ? (
           <div className="flex items-center justify-center h-full">
             <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
           </div>
@@ -192,10 +432,10 @@ export function ChatPanel({ chatId, chatName, chatType, onClose }: ChatPanelProp
                     : "bg-muted"
                 )}
               >
-                {msg.content.type === "text" ? msg.content.text : "[Media]"}
+                {describeMessageContent(msg.content)}
               </div>
               <span className="text-xs text-muted-foreground mt-1">
-                {formatTime(msg.date)}
+                {msg.id < 0 ? "Waiting to send…" : formatTime(msg.date)}
               </span>
             </div>
           ))
@@ -205,6 +445,7 @@ export function ChatPanel({ chatId, chatName, chatType, onClose }: ChatPanelProp
 
       {/* Input Area */}
       <div className="border-t p-4 space-y-3 shrink-0">
+        {sendError && <p className="text-sm text-red-700 dark:text-red-400">{sendError}</p>}
         <Textarea
           value={draft}
           onChange={(e: React.ChangeEvent<HTMLTextAreaElement>) => setDraft(e.target.value)}
@@ -241,6 +482,91 @@ export function ChatPanel({ chatId, chatName, chatType, onClose }: ChatPanelProp
           </Button>
         </div>
       </div>
+
+      <Modal
+        open={editing === "nickname"}
+        onOpenChange={(open) => !open && setEditing(null)}
+        title="Set nickname"
+        description={`Shown instead of "${originalTitle}". Leave empty to use the Telegram title.`}
+      >
+        <div className="space-y-4">
+          <Input
+            placeholder={originalTitle}
+            value={nickname}
+            onChange={(e) => setNickname(e.target.value)}
+            onKeyDown={(e) => e.key === "Enter" && handleSaveNickname()}
+          />
+          <div className="flex justify-end gap-2">
+            <Button variant="outline" onClick={() => setEditing(null)}>
+              Cancel
+            </Button>
+            <Button onClick={handleSaveNickname}>Save</Button>
+          </div>
+        </div>
+      </Modal>
+
+      <Modal
+        open={editing === "note"}
+        onOpenChange={(open) => !open && setEditing(null)}
+        title="Chat note"
+        description={`Shown with "${chat?.title ?? ""}" in the briefing. Leave empty to remove it.`}
+      >
+        <div className="space-y-4">
+          <Textarea
+            value={note}
+            onChange={(e: React.ChangeEvent<HTMLTextAreaElement>) => setNote(e.target.value)}
+            className="resize-none"
+            rows={4}
+          />
+          <div className="flex justify-end gap-2">
+            <Button variant="outline" onClick={() => setEditing(null)}>
+              Cancel
+            </Button>
+            <Button onClick={handleSaveNote}>Save</Button>
+          </div>
+        </div>
+      </Modal>
+
+      <Modal
+        open={editing === "reminder"}
+        onOpenChange={(open) => !open && setEditing(null)}
+        title="Remind me"
+        description={`"${chat?.title ?? ""}" comes back in the briefing when the reminder is due.`}
+      >
+        <div className="space-y-4">
+          <div className="space-y-2">
+            <label htmlFor="remind-hours" className="text-sm font-medium">
+              In how many hours
+            </label>
+            <Input
+              id="remind-hours"
+              type="number"
+              min={0}
+              value={remindHours}
+              onChange={(e) => setRemindHours(e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <label htmlFor="reminder-note" className="text-sm font-medium">
+              Note (optional)
+            </label>
+            <Input
+              id="reminder-note"
+              value={reminderNote}
+              onChange={(e) => setReminderNote(e.target.value)}
+              onKeyDown={(e) => e.key === "Enter" && handleSaveReminder()}
+            />
+          </div>
+          <div className="flex justify-end gap-2">
+            <Button variant="outline" onClick={() => setEditing(null)}>
+              Cancel
+            </Button>
+            <Button onClick={handleSaveReminder} disabled={!isValidDelay}>
+              Set reminder
+            </Button>
+          </div>
+        </div>
+      </Modal>
     </div>
   );
 }
@@ -1,4 +1,4 @@
-import { cn } from "@/lib/utils";
+import { cn, describeMessageContent } from "@/lib/utils";
 import type { Chat } from "@/types/telegram";
 
 interface ChatItemProps {
@@ -57,6 +57,17 @@ function getLastMessagePreview(chat: Chat): string {
       return "Voice message";
     case "sticker":
       return content.emoji || "Sticker";
+    case "storyMention":
+    case "story":
+    case "memberJoined":
+    case "memberLeft":
+    case "titleChanged":
+    case "messagePinned":
+    case "call":
+    case "groupCall":
+    case "premiumGift":
+    case "starsGift":
+      return describeMessageContent(content).replace(/^\[|\]$/g, "");
     default:
       return "Message";
   }
@@ -104,6 +115,15 @@ export function ChatItem({ chat, isSelected, onClick }: ChatItemProps) {
         )}
       </div>
 
+      {/* Star indicator (copilot-only) */}
+      {chat.isStarred && (
+        <div className="flex-shrink-0 text-yellow-400">
+          <svg className="h-4 w-4" fill="currentColor" viewBox="0 0 20 20">
+            <path d="M9.049 2.927c.3-.921 1.603-.921 1.902 0l1.07 3.292a1 1 0 00.95.69h3.462c.969 0 1.371 1.24.588 1.81l-2.8 2.034a1 1 0 00-.364 1.118l1.07 3.292c.3.921-.755 1.688-1.54 1.118l-2.8-2.034a1 1 0 00-1.175 0l-2.8 2.034c-.784.57-1.838-.197-1.539-1.118l1.07-3.292a1 1 0 00-.364-1.118L2.98 8.72c-.783-.57-.38-1.81.588-1.81h3.461a1 1 0 00.951-.69l1.07-3.292z" />
+          </svg>
+        </div>
+      )}
+
       {/* Pin indicator */}
       {chat.isPinned && (
         <div className="flex-shrink-0 text-muted-foreground">
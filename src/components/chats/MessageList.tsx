@@ -1,7 +1,15 @@
-import { useRef, useEffect } from "react";
-import { cn } from "@/lib/utils";
-import type { Message } from "@/types/telegram";
-import { Check, CheckCheck } from "lucide-react";
+import { useRef, useEffect, useState } from "react";
+import { convertFileSrc } from "@tauri-apps/api/core";
+import { revealItemInDir } from "@tauri-apps/plugin-opener";
+import { cn, describeMessageContent } from "@/lib/utils";
+import {
+  clickInlineButton,
+  downloadChatMedia,
+  onDownloadProgress,
+  type DownloadedMedia,
+} from "@/lib/tauri";
+import type { InlineButton, Message } from "@/types/telegram";
+import { Check, CheckCheck, Download, FolderOpen, Loader2 } from "lucide-react";
 
 interface MessageListProps {
   messages: Message[];
@@ -14,15 +22,79 @@ function formatMessageTime(timestamp: number): string {
   return date.toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" });
 }
 
-function MessageContent({ content }: { content: Message["content"] }) {
+function formatBytes(bytes: number): string {
+  if (bytes < 1024 * 1024) return `${Math.max(1, Math.round(bytes / 1024))} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+/** Downloads a message's photo, document or voice note on demand and shows it */
+function Attachment({ message, label }: { message: Message; label: string }) {
+  const [media, setMedia] = useState<DownloadedMedia | null>(null);
+  const [progress, setProgress] = useState<{ downloaded: number; total: number } | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const handleDownload = async () => {
+    setError(null);
+    setProgress({ downloaded: 0, total: 0 });
+    const unlisten = await onDownloadProgress((p) => {
+      if (p.chatId === message.chatId && p.messageId === message.id) {
+        setProgress({ downloaded: p.downloaded, total: p.total });
+      }
+    });
+    try {
+      setMedia(await downloadChatMedia(message.chatId, message.id));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      unlisten();
+      setProgress(null);
+    }
+  };
+
+  if (media && message.content.type === "photo") {
+    return <img src={convertFileSrc(media.path)} alt={label} className="max-h-80 rounded" />;
+  }
+  if (media && message.content.type === "voice") {
+    return <audio controls src={convertFileSrc(media.path)} className="max-w-full" />;
+  }
+  if (media) {
+    return (
+      <button
+        onClick={() => revealItemInDir(media.path).catch((e) => setError(String(e)))}
+        className="flex items-center gap-2 text-sm underline-offset-2 hover:underline"
+      >
+        <FolderOpen className="h-4 w-4" />
+        Show in folder ({formatBytes(media.size)})
+      </button>
+    );
+  }
+
+  const percent = progress && progress.total > 0 ? Math.round((progress.downloaded / progress.total) * 100) : null;
+  return (
+    <div className="space-y-1">
+      <button
+        onClick={handleDownload}
+        disabled={progress !== null}
+        className="flex items-center gap-2 text-sm underline-offset-2 hover:underline disabled:opacity-70"
+      >
+        {progress ? <Loader2 className="h-4 w-4 animate-spin" /> : <Download className="h-4 w-4" />}
+        {progress ? `Downloading${percent !== null ? ` ${percent}%` : "..."}` : `Download ${label}`}
+      </button>
+      {error && <p className="text-xs text-destructive">{error}</p>}
+    </div>
+  );
+}
+
+function MessageContent({ message }: { message: Message }) {
+  const content = message.content;
   switch (content.type) {
     case "text":
       return <span className="whitespace-pre-wrap break-words">{content.text}</span>;
     case "photo":
       return (
         <div>
-          <div className="bg-muted rounded h-32 flex items-center justify-center text-muted-foreground">
-            [Photo]
+          <div className="bg-muted rounded min-h-32 flex items-center justify-center text-muted-foreground">
+            <Attachment message={message} label="photo" />
           </div>
           {content.caption && (
             <p className="mt-1 text-sm">{content.caption}</p>
@@ -42,27 +114,103 @@ function MessageContent({ content }: { content: Message["content"] }) {
       );
     case "document":
       return (
-        <div className="flex items-center gap-2 p-2 bg-muted/50 rounded">
-          <span className="text-2xl">📄</span>
-          <span className="truncate">{content.fileName}</span>
+        <div className="space-y-1 p-2 bg-muted/50 rounded">
+          <div className="flex items-center gap-2">
+            <span className="text-2xl">📄</span>
+            <span className="truncate">{content.fileName}</span>
+          </div>
+          <Attachment message={message} label="file" />
         </div>
       );
     case "voice":
       return (
-        <div className="flex items-center gap-2 p-2 bg-muted/50 rounded">
-          <span className="text-2xl">🎤</span>
-          <span>Voice message ({content.duration}s)</span>
+        <div className="space-y-1 p-2 bg-muted/50 rounded">
+          <div className="flex items-center gap-2">
+            <span className="text-2xl">🎤</span>
+            <span>Voice message ({content.duration}s)</span>
+          </div>
+          <Attachment message={message} label="voice message" />
         </div>
       );
     case "sticker":
       return (
         <div className="text-6xl">{content.emoji || "🎨"}</div>
       );
+    case "storyMention":
+    case "story":
+    case "memberJoined":
+    case "memberLeft":
+    case "titleChanged":
+    case "messagePinned":
+    case "call":
+    case "groupCall":
+    case "premiumGift":
+    case "starsGift":
+      return (
+        <span className="italic text-muted-foreground">
+          {describeMessageContent(content).replace(/^\[|\]$/g, "")}
+        </span>
+      );
     default:
       return <span className="text-muted-foreground">[Unsupported message]</span>;
   }
 }
 
+function InlineKeyboard({ message }: { message: Message }) {
+  const [pending, setPending] = useState<string | null>(null);
+  const [answer, setAnswer] = useState<string | null>(null);
+
+  const handleClick = async (button: InlineButton) => {
+    if (!button.data || pending) return;
+    setPending(button.data);
+    setAnswer(null);
+    try {
+      const result = await clickInlineButton(message.chatId, message.id, button.data);
+      if (result.message && result.alert) {
+        window.alert(result.message);
+      } else {
+        setAnswer(result.message);
+      }
+    } catch (error) {
+      setAnswer(String(error));
+    } finally {
+      setPending(null);
+    }
+  };
+
+  return (
+    <div className="mt-2 space-y-1">
+      {message.inlineKeyboard?.map((row, i) => (
+        <div key={i} className="flex gap-1">
+          {row.map((button, j) =>
+            button.url ? (
+              <a
+                key={j}
+                href={button.url}
+                target="_blank"
+                rel="noreferrer"
+                className="flex-1 truncate rounded border bg-background/60 px-2 py-1 text-center text-xs hover:bg-background"
+              >
+                {button.text} ↗
+              </a>
+            ) : (
+              <button
+                key={j}
+                disabled={!button.data || pending !== null}
+                onClick={() => handleClick(button)}
+                className="flex-1 truncate rounded border bg-background/60 px-2 py-1 text-xs hover:bg-background disabled:opacity-50"
+              >
+                {pending === button.data ? "..." : button.text}
+              </button>
+            )
+          )}
+        </div>
+      ))}
+      {answer && <p className="text-xs text-muted-foreground">{answer}</p>}
+    </div>
+  );
+}
+
 function MessageBubble({ message }: { message: Message }) {
   const isOutgoing = message.isOutgoing;
 
@@ -86,7 +234,10 @@ function MessageBubble({ message }: { message: Message }) {
             {message.senderName}
           </p>
         )}
-        <MessageContent content={message.content} />
+        <MessageContent message={message} />
+        {message.inlineKeyboard && message.inlineKeyboard.length > 0 && (
+          <InlineKeyboard message={message} />
+        )}
         <div
           className={cn(
             "flex items-center justify-end gap-1 mt-1",
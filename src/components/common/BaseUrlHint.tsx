@@ -0,0 +1,19 @@
+import type { LLMConfig } from "@/lib/tauri";
+import { cn } from "@/lib/utils";
+
+const HINTS: Partial<Record<LLMConfig["provider"], string>> = {
+  ollama: "Change if Ollama runs on a different host/port",
+  local_openai: "LM Studio defaults to port 1234, llama-server to 8080",
+};
+
+interface BaseUrlHintProps {
+  provider: LLMConfig["provider"];
+  className?: string;
+}
+
+/** Where a local provider's server usually listens, shown under the Base URL field */
+export function BaseUrlHint({ provider, className }: BaseUrlHintProps) {
+  const hint = HINTS[provider];
+  if (!hint) return null;
+  return <p className={cn("text-xs text-muted-foreground", className)}>{hint}</p>;
+}
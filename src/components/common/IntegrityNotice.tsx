@@ -0,0 +1,52 @@
+import { useEffect, useState } from "react";
+import { AlertCircle } from "lucide-react";
+import { Alert, AlertDescription, AlertTitle } from "@/components/ui/alert";
+import { Button } from "@/components/ui/button";
+import { acknowledgeIntegrityNotice, getIntegrityNotice, type IntegrityReport } from "@/lib/tauri";
+
+/** Tells the user that corrupted files were moved aside, until they acknowledge it */
+export function IntegrityNotice() {
+  const [report, setReport] = useState<IntegrityReport | null>(null);
+
+  useEffect(() => {
+    getIntegrityNotice()
+      .then(setReport)
+      .catch((e) => console.error("Failed to load integrity notice:", e));
+  }, []);
+
+  if (!report) return null;
+
+  const handleAcknowledge = async () => {
+    try {
+      await acknowledgeIntegrityNotice();
+      setReport(null);
+    } catch (e) {
+      console.error("Failed to acknowledge integrity notice:", e);
+    }
+  };
+
+  return (
+    <div className="border-b px-4 py-2">
+      <Alert variant="destructive">
+        <AlertCircle className="h-4 w-4" />
+        <AlertTitle>
+          {report.databaseReplaced
+            ? "Local data was lost: the database was corrupted and replaced with an empty one"
+            : "A corrupted file was moved aside"}
+        </AlertTitle>
+        <AlertDescription>
+          {!report.sessionOk && <p>Your Telegram session was unreadable, so you had to log in again.</p>}
+          <p>The damaged files were kept here and may be recoverable:</p>
+          <ul className="list-disc pl-4 font-mono text-xs">
+            {report.quarantined.map((path) => (
+              <li key={path}>{path}</li>
+            ))}
+          </ul>
+          <Button variant="outline" size="sm" className="mt-2" onClick={handleAcknowledge}>
+            Got it
+          </Button>
+        </AlertDescription>
+      </Alert>
+    </div>
+  );
+}
@@ -15,6 +15,10 @@ import {
   Filter,
   Clock,
   Brain,
+  Archive,
+  Undo2,
+  Bot,
+  LayoutList,
 } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import {
@@ -27,6 +31,10 @@ import { useThemeStore, type Theme } from "@/stores/themeStore";
 import { ChatFiltersDialog } from "@/components/settings/ChatFiltersDialog";
 import { CacheSettingsDialog } from "@/components/settings/CacheSettingsDialog";
 import { AIProviderDialog } from "@/components/settings/AIProviderDialog";
+import { ArchiveDialog } from "@/components/settings/ArchiveDialog";
+import { SendingDialog } from "@/components/settings/SendingDialog";
+import { BotRulesDialog } from "@/components/settings/BotRulesDialog";
+import { BriefingCategoriesDialog } from "@/components/settings/BriefingCategoriesDialog";
 
 export type ViewType = "briefing" | "summary" | "chats" | "contacts" | "outreach" | "offboard";
 
@@ -56,6 +64,10 @@ export function NavHeader({ currentView, onViewChange }: NavHeaderProps) {
   const [filtersOpen, setFiltersOpen] = useState(false);
   const [cacheSettingsOpen, setCacheSettingsOpen] = useState(false);
   const [aiProviderOpen, setAiProviderOpen] = useState(false);
+  const [archiveOpen, setArchiveOpen] = useState(false);
+  const [sendingOpen, setSendingOpen] = useState(false);
+  const [botRulesOpen, setBotRulesOpen] = useState(false);
+  const [categoriesOpen, setCategoriesOpen] = useState(false);
 
   return (
     <header className="sticky top-0 z-50 w-full border-b bg-background/95 backdrop-blur supports-[backdrop-filter]:bg-background/60">
@@ -153,6 +165,34 @@ export function NavHeader({ currentView, onViewChange }: NavHeaderProps) {
                     <Brain className="h-4 w-4" />
                     <span className="flex-1 text-left">AI Provider</span>
                   </button>
+                  <button
+                    onClick={() => setArchiveOpen(true)}
+                    className="flex w-full items-center gap-2 rounded-md px-2 py-1.5 text-sm transition-colors hover:bg-accent"
+                  >
+                    <Archive className="h-4 w-4" />
+                    <span className="flex-1 text-left">Message Archive</span>
+                  </button>
+                  <button
+                    onClick={() => setSendingOpen(true)}
+                    className="flex w-full items-center gap-2 rounded-md px-2 py-1.5 text-sm transition-colors hover:bg-accent"
+                  >
+                    <Undo2 className="h-4 w-4" />
+                    <span className="flex-1 text-left">Sending</span>
+                  </button>
+                  <button
+                    onClick={() => setBotRulesOpen(true)}
+                    className="flex w-full items-center gap-2 rounded-md px-2 py-1.5 text-sm transition-colors hover:bg-accent"
+                  >
+                    <Bot className="h-4 w-4" />
+                    <span className="flex-1 text-left">Automation Rules</span>
+                  </button>
+                  <button
+                    onClick={() => setCategoriesOpen(true)}
+                    className="flex w-full items-center gap-2 rounded-md px-2 py-1.5 text-sm transition-colors hover:bg-accent"
+                  >
+                    <LayoutList className="h-4 w-4" />
+                    <span className="flex-1 text-left">Briefing Categories</span>
+                  </button>
                 </div>
               </div>
             </PopoverContent>
@@ -160,6 +200,10 @@ export function NavHeader({ currentView, onViewChange }: NavHeaderProps) {
           <ChatFiltersDialog open={filtersOpen} onOpenChange={setFiltersOpen} />
           <CacheSettingsDialog open={cacheSettingsOpen} onOpenChange={setCacheSettingsOpen} />
           <AIProviderDialog open={aiProviderOpen} onOpenChange={setAiProviderOpen} />
+          <ArchiveDialog open={archiveOpen} onOpenChange={setArchiveOpen} />
+          <SendingDialog open={sendingOpen} onOpenChange={setSendingOpen} />
+          <BotRulesDialog open={botRulesOpen} onOpenChange={setBotRulesOpen} />
+          <BriefingCategoriesDialog open={categoriesOpen} onOpenChange={setCategoriesOpen} />
           <Button
             variant="ghost"
             size="icon"
@@ -1,5 +1,6 @@
 import { NavHeader, ViewType } from "./Header";
 import { ChatPanel } from "../chat/ChatPanel";
+import { IntegrityNotice } from "../common/IntegrityNotice";
 import { cn } from "@/lib/utils";
 
 interface MainLayoutProps {
@@ -26,6 +27,7 @@ export function MainLayout({
   return (
     <div className="flex h-screen w-screen flex-col overflow-hidden bg-background">
       <NavHeader currentView={currentView} onViewChange={onViewChange} />
+      <IntegrityNotice />
 
       <div className="flex flex-1 overflow-hidden relative">
         {/* Main Content */}
@@ -1,4 +1,5 @@
 import { useEffect, useState } from "react";
+import { BaseUrlHint } from "@/components/common/BaseUrlHint";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import {
@@ -11,6 +12,7 @@ import {
 import {
   getLLMConfig,
   listOllamaModels,
+  listLocalModels,
   testLLMConnection,
   isLLMConfigured,
   type LLMConfig,
@@ -29,14 +31,17 @@ const OPENAI_MODELS = [
 const DEFAULT_URLS: Record<string, string> = {
   openai: "https://api.openai.com",
   ollama: "http://localhost:11434",
+  local_openai: "http://localhost:1234",
 };
 
+type Provider = LLMConfig["provider"];
+
 interface AIProviderStepProps {
   onConfigChange: (config: LLMConfig) => void;
 }
 
 export function AIProviderStep({ onConfigChange }: AIProviderStepProps) {
-  const [provider, setProvider] = useState<"openai" | "ollama">("openai");
+  const [provider, setProvider] = useState<Provider>("openai");
   const [baseUrl, setBaseUrl] = useState(DEFAULT_URLS.openai);
   const [apiKey, setApiKey] = useState("");
   const [model, setModel] = useState("gpt-4o-mini");
@@ -53,8 +58,8 @@ export function AIProviderStep({ onConfigChange }: AIProviderStepProps) {
       setProvider(config.provider);
       setBaseUrl(config.base_url);
       setModel(config.model);
-      if (config.provider === "ollama") {
-        fetchOllamaModels(config.base_url);
+      if (config.provider !== "openai") {
+        fetchModels(config.base_url, config.provider, config.api_key);
       }
       // If api_key is masked, an env key was loaded
       if (config.api_key && config.api_key !== "") {
@@ -77,11 +82,13 @@ export function AIProviderStep({ onConfigChange }: AIProviderStepProps) {
     onConfigChange(buildConfig());
   }, [provider, baseUrl, apiKey, model]);
 
-  const fetchOllamaModels = async (url?: string) => {
+  const fetchModels = async (url?: string, forProvider: Provider = provider, key: string | null = apiKey) => {
     setLoadingModels(true);
     setOllamaError(null);
     try {
-      const models = await listOllamaModels(url || baseUrl);
+      const models = forProvider === "local_openai"
+        ? await listLocalModels(url || baseUrl, key || null)
+        : await listOllamaModels(url || baseUrl);
       setOllamaModels(models);
       if (models.length > 0 && !models.some((m) => m.name === model)) {
         setModel(models[0].name);
@@ -94,7 +101,7 @@ export function AIProviderStep({ onConfigChange }: AIProviderStepProps) {
     }
   };
 
-  const handleProviderChange = (newProvider: "openai" | "ollama") => {
+  const handleProviderChange = (newProvider: Provider) => {
     setProvider(newProvider);
     setBaseUrl(DEFAULT_URLS[newProvider] || DEFAULT_URLS.openai);
     setTestStatus("idle");
@@ -102,16 +109,18 @@ export function AIProviderStep({ onConfigChange }: AIProviderStepProps) {
     setOllamaError(null);
     if (newProvider === "openai") {
       setModel("gpt-4o-mini");
-    } else if (newProvider === "ollama") {
+    } else {
       setModel("");
-      fetchOllamaModels(DEFAULT_URLS.ollama);
+      // Don't carry an OpenAI key over to a local server
+      setApiKey("");
+      fetchModels(DEFAULT_URLS[newProvider], newProvider, null);
     }
   };
 
   const buildConfig = (): LLMConfig => ({
     provider,
     base_url: baseUrl,
-    api_key: provider === "openai" ? apiKey || null : null,
+    api_key: provider === "ollama" ? null : apiKey || null,
     model,
   });
 
@@ -149,6 +158,7 @@ export function AIProviderStep({ onConfigChange }: AIProviderStepProps) {
               <SelectContent>
                 <SelectItem value="openai">OpenAI</SelectItem>
                 <SelectItem value="ollama">Ollama (Local)</SelectItem>
+                <SelectItem value="local_openai">LM Studio / llama.cpp (Local)</SelectItem>
               </SelectContent>
             </Select>
           </div>
@@ -163,11 +173,7 @@ export function AIProviderStep({ onConfigChange }: AIProviderStepProps) {
               onChange={(e) => setBaseUrl(e.target.value)}
               placeholder={DEFAULT_URLS[provider]}
             />
-            {provider === "ollama" && (
-              <p className="text-xs text-muted-foreground mt-2">
-                Change if Ollama runs on a different host/port
-              </p>
-            )}
+            <BaseUrlHint provider={provider} className="mt-2" />
           </div>
         </div>
 
@@ -194,15 +200,30 @@ export function AIProviderStep({ onConfigChange }: AIProviderStepProps) {
           </div>
         )}
 
+        {/* API Key (optional for local OpenAI-compatible servers) */}
+        {provider === "local_openai" && (
+          <div className="space-y-2">
+            <h4 className="text-sm font-medium text-muted-foreground uppercase tracking-wide">API Key (optional)</h4>
+            <div className="p-3 rounded-lg border border-border bg-background">
+              <Input
+                type="password"
+                value={apiKey}
+                onChange={(e) => setApiKey(e.target.value)}
+                placeholder="Only if the server requires one"
+              />
+            </div>
+          </div>
+        )}
+
         {/* Model */}
         <div className="space-y-2">
           <div className="flex items-center justify-between">
             <h4 className="text-sm font-medium text-muted-foreground uppercase tracking-wide">Model</h4>
-            {provider === "ollama" && (
+            {provider !== "openai" && (
               <Button
                 variant="ghost"
                 size="sm"
-                onClick={() => fetchOllamaModels()}
+                onClick={() => fetchModels()}
                 disabled={loadingModels}
                 className="h-7 px-2"
               >
@@ -246,7 +267,11 @@ export function AIProviderStep({ onConfigChange }: AIProviderStepProps) {
                   </Select>
                 ) : (
                   <p className="text-sm text-muted-foreground">
-                    {loadingModels ? "Loading models..." : "No models found. Pull a model with: ollama pull llama3"}
+                    {loadingModels
+                      ? "Loading models..."
+                      : provider === "ollama"
+                        ? "No models found. Pull a model with: ollama pull llama3"
+                        : "No models found. Load a model in LM Studio or start llama-server with -m"}
                   </p>
                 )}
               </>
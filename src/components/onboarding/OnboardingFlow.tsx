@@ -1,7 +1,7 @@
 import { useState, useRef } from "react";
 import { Button } from "@/components/ui/button";
 import { useSettingsStore } from "@/stores/settingsStore";
-import { updateLLMConfig, type LLMConfig } from "@/lib/tauri";
+import { completeOnboardingStep, updateLLMConfig, type LLMConfig } from "@/lib/tauri";
 import { ChatFilterStep } from "./ChatFilterStep";
 import { AIProviderStep } from "./AIProviderStep";
 import { ArrowRight, ArrowLeft, SkipForward } from "lucide-react";
@@ -21,15 +21,23 @@ export function OnboardingFlow({ onComplete }: OnboardingFlowProps) {
   const handleComplete = async () => {
     setSaving(true);
     try {
-      // Save AI config if the user provided one (didn't skip)
+      // Save AI config if the user provided one, else the AI step counts as skipped
       if (aiConfigRef.current) {
         await updateLLMConfig(aiConfigRef.current);
       }
+      await completeOnboardingStep("chat_filters");
+      if (!aiConfigRef.current) {
+        await completeOnboardingStep("llm_configured");
+      }
       completeOnboarding();
       onComplete();
     } catch (e) {
       console.error("Failed to save AI config:", e);
       // Complete onboarding anyway — user can fix in Settings
+      await completeOnboardingStep("chat_filters").catch(() => {});
+      await completeOnboardingStep("llm_configured").catch((err) =>
+        console.error("Failed to record onboarding progress:", err)
+      );
       completeOnboarding();
       onComplete();
     } finally {
@@ -37,8 +45,14 @@ export function OnboardingFlow({ onComplete }: OnboardingFlowProps) {
     }
   };
 
-  const handleSkipAI = () => {
+  const handleSkipAI = async () => {
     aiConfigRef.current = null;
+    try {
+      await completeOnboardingStep("chat_filters");
+      await completeOnboardingStep("llm_configured");
+    } catch (e) {
+      console.error("Failed to record onboarding progress:", e);
+    }
     completeOnboarding();
     onComplete();
   };
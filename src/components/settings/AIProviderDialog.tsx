@@ -15,16 +15,25 @@ import {
   SelectTrigger,
   SelectValue,
 } from "@/components/ui/select";
+import { BaseUrlHint } from "@/components/common/BaseUrlHint";
+import { Checkbox } from "@/components/ui/checkbox";
 import { Input } from "@/components/ui/input";
 import {
   getLLMConfig,
   updateLLMConfig,
   listOllamaModels,
+  listLocalModels,
+  pullOllamaModel,
+  deleteOllamaModel,
+  onOllamaPullProgress,
   testLLMConnection,
+  getLocalOnlyAI,
+  setLocalOnlyAI,
   type LLMConfig,
+  type LLMFallback,
   type OllamaModel,
 } from "@/lib/tauri";
-import { RefreshCw, CheckCircle2, XCircle, Loader2 } from "lucide-react";
+import { RefreshCw, CheckCircle2, XCircle, Loader2, Download, Trash2 } from "lucide-react";
 
 const OPENAI_MODELS = [
   "gpt-4o-mini",
@@ -37,17 +46,38 @@ const OPENAI_MODELS = [
 const DEFAULT_URLS: Record<string, string> = {
   openai: "https://api.openai.com",
   ollama: "http://localhost:11434",
+  local_openai: "http://localhost:1234",
 };
 
+type Provider = LLMConfig["provider"];
+
+const LOOPBACK_HOSTS = ["localhost", "[::1]"];
+
+/** Whether a URL points at this machine (hostnames other than localhost count as remote) */
+function isLoopbackUrl(url: string): boolean {
+  try {
+    const host = new URL(url).hostname;
+    return LOOPBACK_HOSTS.includes(host) || host.startsWith("127.");
+  } catch {
+    return false;
+  }
+}
+
 interface AIProviderDialogProps {
   open: boolean;
   onOpenChange: (open: boolean) => void;
 }
 
 export function AIProviderDialog({ open, onOpenChange }: AIProviderDialogProps) {
-  const [provider, setProvider] = useState<"openai" | "ollama">("openai");
+  const [provider, setProvider] = useState<Provider>("openai");
   const [baseUrl, setBaseUrl] = useState(DEFAULT_URLS.openai);
   const [apiKey, setApiKey] = useState("");
+  const [privacyMode, setPrivacyMode] = useState(false);
+  const [fallback, setFallback] = useState<LLMFallback | null>(null);
+  const [timeoutSecs, setTimeoutSecs] = useState("");
+  const [keepAlive, setKeepAlive] = useState("");
+  const [extraHosts, setExtraHosts] = useState("");
+  const [localOnly, setLocalOnly] = useState(false);
   const [model, setModel] = useState("gpt-4o-mini");
   const [ollamaModels, setOllamaModels] = useState<OllamaModel[]>([]);
   const [ollamaError, setOllamaError] = useState<string | null>(null);
@@ -55,6 +85,9 @@ export function AIProviderDialog({ open, onOpenChange }: AIProviderDialogProps)
   const [testStatus, setTestStatus] = useState<"idle" | "testing" | "success" | "error">("idle");
   const [testMessage, setTestMessage] = useState("");
   const [saving, setSaving] = useState(false);
+  const [pullName, setPullName] = useState("");
+  const [pullStatus, setPullStatus] = useState<string | null>(null);
+  const [pulling, setPulling] = useState(false);
 
   // Load current config when dialog opens
   useEffect(() => {
@@ -65,21 +98,31 @@ export function AIProviderDialog({ open, onOpenChange }: AIProviderDialogProps)
         setProvider(config.provider);
         setBaseUrl(config.base_url);
         setApiKey(config.api_key || "");
+        setPrivacyMode(config.privacy_mode ?? false);
+        setFallback(config.fallback ?? null);
+        setTimeoutSecs(config.timeout_secs ? String(config.timeout_secs) : "");
+        setKeepAlive(config.keep_alive ?? "");
+        setExtraHosts((config.extra_hosts ?? []).join(", "));
         setModel(config.model);
-        if (config.provider === "ollama") {
-          fetchOllamaModels(config.base_url);
+        if (config.provider !== "openai") {
+          fetchModels(config.base_url, config.provider, config.api_key);
         }
       }).catch((e) => {
         console.error("Failed to load LLM config:", e);
       });
+      getLocalOnlyAI().then(setLocalOnly).catch((e) => {
+        console.error("Failed to load local-only setting:", e);
+      });
     }
   }, [open]);
 
-  const fetchOllamaModels = async (url?: string) => {
+  const fetchModels = async (url?: string, forProvider: Provider = provider, key: string | null = apiKey) => {
     setLoadingModels(true);
     setOllamaError(null);
     try {
-      const models = await listOllamaModels(url || baseUrl);
+      const models = forProvider === "local_openai"
+        ? await listLocalModels(url || baseUrl, key || null)
+        : await listOllamaModels(url || baseUrl);
       setOllamaModels(models);
       if (models.length > 0 && !models.some((m) => m.name === model)) {
         setModel(models[0].name);
@@ -92,7 +135,44 @@ export function AIProviderDialog({ open, onOpenChange }: AIProviderDialogProps)
     }
   };
 
-  const handleProviderChange = (newProvider: "openai" | "ollama") => {
+  const handlePull = async () => {
+    const name = pullName.trim();
+    if (!name) return;
+    setPulling(true);
+    setPullStatus("Starting download...");
+    const unlisten = await onOllamaPullProgress((progress) => {
+      if (progress.model !== name) return;
+      const percent = progress.total && progress.completed
+        ? ` ${Math.round((progress.completed / progress.total) * 100)}%`
+        : "";
+      setPullStatus(`${progress.status}${percent}`);
+    });
+    try {
+      await pullOllamaModel(name, baseUrl);
+      setPullStatus(null);
+      setPullName("");
+      await fetchModels();
+      setModel(name);
+    } catch (e) {
+      setPullStatus(String(e));
+    } finally {
+      unlisten();
+      setPulling(false);
+    }
+  };
+
+  const handleDeleteModel = async () => {
+    if (!model || !window.confirm(`Delete ${model} from Ollama?`)) return;
+    try {
+      await deleteOllamaModel(model, baseUrl);
+      setModel("");
+      await fetchModels();
+    } catch (e) {
+      setOllamaError(String(e));
+    }
+  };
+
+  const handleProviderChange = (newProvider: Provider) => {
     setProvider(newProvider);
     setBaseUrl(DEFAULT_URLS[newProvider] || DEFAULT_URLS.openai);
     setTestStatus("idle");
@@ -100,19 +180,36 @@ export function AIProviderDialog({ open, onOpenChange }: AIProviderDialogProps)
     setOllamaError(null);
     if (newProvider === "openai") {
       setModel("gpt-4o-mini");
-    } else if (newProvider === "ollama") {
+    } else {
       setModel("");
-      fetchOllamaModels(DEFAULT_URLS.ollama);
+      // Don't carry an OpenAI key over to a local server
+      setApiKey("");
+      fetchModels(DEFAULT_URLS[newProvider], newProvider, null);
     }
   };
 
   const buildConfig = (): LLMConfig => ({
     provider,
     base_url: baseUrl,
-    api_key: provider === "openai" ? apiKey || null : null,
+    api_key: provider === "ollama" ? null : apiKey || null,
     model,
+    privacy_mode: privacyMode,
+    fallback: provider === "openai" ? null : fallback,
+    timeout_secs: parseInt(timeoutSecs, 10) || null,
+    keep_alive: provider === "ollama" ? keepAlive.trim() || null : null,
+    extra_hosts:
+      provider === "ollama"
+        ? extraHosts.split(",").map((h) => h.trim()).filter(Boolean)
+        : [],
   });
 
+  // Mirrors the backend: privacy mode applies when any configured host is off this machine
+  const config = buildConfig();
+  const sendsRemotely =
+    provider !== "mock" &&
+    [...config.extra_hosts, config.base_url, config.fallback?.base_url]
+      .some((url) => url !== undefined && !isLoopbackUrl(url));
+
   const handleTest = async () => {
     setTestStatus("testing");
     setTestMessage("");
@@ -129,7 +226,10 @@ export function AIProviderDialog({ open, onOpenChange }: AIProviderDialogProps)
   const handleApply = async () => {
     setSaving(true);
     try {
+      // The config must already be local when the switch is turned on
+      if (!localOnly) await setLocalOnlyAI(false);
       await updateLLMConfig(buildConfig());
+      if (localOnly) await setLocalOnlyAI(true);
       onOpenChange(false);
     } catch (e) {
       console.error("Failed to save LLM config:", e);
@@ -165,6 +265,7 @@ export function AIProviderDialog({ open, onOpenChange }: AIProviderDialogProps)
               <SelectContent>
                 <SelectItem value="openai">OpenAI</SelectItem>
                 <SelectItem value="ollama">Ollama (Local)</SelectItem>
+                <SelectItem value="local_openai">LM Studio / llama.cpp (Local)</SelectItem>
               </SelectContent>
             </Select>
           </div>
@@ -177,11 +278,7 @@ export function AIProviderDialog({ open, onOpenChange }: AIProviderDialogProps)
               onChange={(e) => setBaseUrl(e.target.value)}
               placeholder={DEFAULT_URLS[provider]}
             />
-            {provider === "ollama" && (
-              <p className="text-xs text-muted-foreground">
-                Change if Ollama runs on a different host/port
-              </p>
-            )}
+            <BaseUrlHint provider={provider} />
           </div>
 
           {/* API Key (OpenAI only) */}
@@ -200,22 +297,49 @@ export function AIProviderDialog({ open, onOpenChange }: AIProviderDialogProps)
             </div>
           )}
 
+          {/* API Key (optional for local OpenAI-compatible servers) */}
+          {provider === "local_openai" && (
+            <div className="space-y-2">
+              <label className="text-sm font-medium">API Key (optional)</label>
+              <Input
+                type="password"
+                value={apiKey}
+                onChange={(e) => setApiKey(e.target.value)}
+                placeholder="Only if the server requires one"
+              />
+            </div>
+          )}
+
           {/* Model */}
           <div className="space-y-2">
             <div className="flex items-center justify-between">
               <label className="text-sm font-medium">Model</label>
-              {provider === "ollama" && (
-                <Button
-                  variant="ghost"
-                  size="sm"
-                  onClick={() => fetchOllamaModels()}
-                  disabled={loadingModels}
-                  className="h-7 px-2"
-                >
-                  <RefreshCw className={`h-3 w-3 mr-1 ${loadingModels ? "animate-spin" : ""}`} />
-                  Refresh
-                </Button>
-              )}
+              <div className="flex gap-1">
+                {provider !== "openai" && (
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={() => fetchModels()}
+                    disabled={loadingModels}
+                    className="h-7 px-2"
+                  >
+                    <RefreshCw className={`h-3 w-3 mr-1 ${loadingModels ? "animate-spin" : ""}`} />
+                    Refresh
+                  </Button>
+                )}
+                {provider === "ollama" && model && (
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={handleDeleteModel}
+                    disabled={pulling}
+                    className="h-7 px-2"
+                  >
+                    <Trash2 className="h-3 w-3 mr-1" />
+                    Delete
+                  </Button>
+                )}
+              </div>
             </div>
             {provider === "openai" ? (
               <Select value={model} onValueChange={setModel}>
@@ -251,13 +375,175 @@ export function AIProviderDialog({ open, onOpenChange }: AIProviderDialogProps)
                   </Select>
                 ) : (
                   <p className="text-sm text-muted-foreground">
-                    {loadingModels ? "Loading models..." : "No models found. Pull a model with: ollama pull llama3"}
+                    {loadingModels
+                      ? "Loading models..."
+                      : provider === "ollama"
+                        ? "No models found. Pull a model with: ollama pull llama3"
+                        : "No models found. Load a model in LM Studio or start llama-server with -m"}
                   </p>
                 )}
               </>
             )}
           </div>
 
+          {/* Request timeout */}
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Request Timeout (seconds)</label>
+            <Input
+              type="number"
+              min={1}
+              value={timeoutSecs}
+              onChange={(e) => setTimeoutSecs(e.target.value)}
+              placeholder={provider === "openai" ? "30" : "120"}
+            />
+            <p className="text-xs text-muted-foreground">
+              Briefings use half of this per chat, detailed summaries double
+            </p>
+          </div>
+
+          {/* Additional Ollama hosts */}
+          {provider === "ollama" && (
+            <div className="space-y-2">
+              <label className="text-sm font-medium">Additional Ollama Hosts</label>
+              <Input
+                value={extraHosts}
+                onChange={(e) => setExtraHosts(e.target.value)}
+                placeholder="http://192.168.1.20:11434"
+              />
+              <p className="text-xs text-muted-foreground">
+                Comma-separated. Briefing chats are spread across all hosts; unreachable ones are skipped
+              </p>
+            </div>
+          )}
+
+          {/* Ollama model residency */}
+          {provider === "ollama" && (
+            <div className="space-y-2">
+              <label className="text-sm font-medium">Keep Model Loaded</label>
+              <Input
+                value={keepAlive}
+                onChange={(e) => setKeepAlive(e.target.value)}
+                placeholder="5m"
+              />
+              <p className="text-xs text-muted-foreground">
+                How long the model stays in memory after a request: e.g. 30m, 2h, -1 (always) or 0 (free RAM immediately)
+              </p>
+            </div>
+          )}
+
+          {/* Local-only mode */}
+          <label className="flex items-start gap-3 cursor-pointer">
+            <Checkbox
+              checked={localOnly}
+              onCheckedChange={(checked) => setLocalOnly(checked === true)}
+              className="mt-0.5"
+            />
+            <div className="flex-1 space-y-0.5">
+              <div className="text-sm font-medium leading-none">Local-only AI</div>
+              <div className="text-xs text-muted-foreground">
+                Block every AI request that isn't to this machine, so chat content never leaves it
+              </div>
+            </div>
+          </label>
+
+          {/* Cloud fallback (local providers only) */}
+          {provider !== "openai" && (
+            <div className="space-y-2">
+              <label className="flex items-start gap-3 cursor-pointer">
+                <Checkbox
+                  checked={fallback !== null}
+                  onCheckedChange={(checked) =>
+                    setFallback(
+                      checked
+                        ? { provider: "openai", base_url: DEFAULT_URLS.openai, api_key: null, model: "gpt-4o-mini" }
+                        : null
+                    )
+                  }
+                  className="mt-0.5"
+                />
+                <div className="flex-1 space-y-0.5">
+                  <div className="text-sm font-medium leading-none">Fall back to OpenAI</div>
+                  <div className="text-xs text-muted-foreground">
+                    Used when the local model is down or times out
+                  </div>
+                </div>
+              </label>
+              {fallback && (
+                <div className="flex gap-2">
+                  <Input
+                    type="password"
+                    value={fallback.api_key ?? ""}
+                    onChange={(e) => setFallback({ ...fallback, api_key: e.target.value || null })}
+                    placeholder="sk-..."
+                  />
+                  <Select
+                    value={fallback.model}
+                    onValueChange={(m) => setFallback({ ...fallback, model: m })}
+                  >
+                    <SelectTrigger className="w-40">
+                      <SelectValue />
+                    </SelectTrigger>
+                    <SelectContent>
+                      {OPENAI_MODELS.map((m) => (
+                        <SelectItem key={m} value={m}>
+                          {m}
+                        </SelectItem>
+                      ))}
+                    </SelectContent>
+                  </Select>
+                </div>
+              )}
+            </div>
+          )}
+
+          {/* Privacy mode (whenever chats can leave this machine) */}
+          {sendsRemotely && (
+            <label className="flex items-start gap-3 cursor-pointer">
+              <Checkbox
+                checked={privacyMode}
+                onCheckedChange={(checked) => setPrivacyMode(!!checked)}
+                className="mt-0.5"
+              />
+              <div className="flex-1 space-y-0.5">
+                <div className="text-sm font-medium leading-none">Privacy mode</div>
+                <div className="text-xs text-muted-foreground">
+                  Replace names and phone numbers with placeholders before sending chats to a remote provider
+                </div>
+              </div>
+            </label>
+          )}
+
+          {/* Pull a model (Ollama only) */}
+          {provider === "ollama" && (
+            <div className="space-y-2">
+              <label className="text-sm font-medium">Download Model</label>
+              <div className="flex gap-2">
+                <Input
+                  value={pullName}
+                  onChange={(e) => setPullName(e.target.value)}
+                  placeholder="e.g. llama3.2"
+                  disabled={pulling}
+                />
+                <Button
+                  variant="outline"
+                  size="sm"
+                  onClick={handlePull}
+                  disabled={pulling || !pullName.trim()}
+                  className="h-9"
+                >
+                  {pulling ? (
+                    <Loader2 className="h-4 w-4 animate-spin" />
+                  ) : (
+                    <Download className="h-4 w-4" />
+                  )}
+                </Button>
+              </div>
+              {pullStatus && (
+                <p className="text-xs text-muted-foreground">{pullStatus}</p>
+              )}
+            </div>
+          )}
+
           {/* Test Connection */}
           <div className="space-y-2">
             <Button
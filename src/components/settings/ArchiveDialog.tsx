@@ -0,0 +1,349 @@
+import { useEffect, useState } from "react";
+import { Trash2 } from "lucide-react";
+import * as tauri from "@/lib/tauri";
+import { useChatStore } from "@/stores/chatStore";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import { Input } from "@/components/ui/input";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+
+const DEFAULT_BACKFILL: tauri.ArchiveBackfillConfig = {
+  enabled: false,
+  scopeName: null,
+  requestsPerMinute: 6,
+};
+
+// How far back "Delete older messages" keeps the archive
+const COMPACT_OPTIONS = [
+  { value: 30, label: "30 days" },
+  { value: 90, label: "90 days" },
+  { value: 180, label: "6 months" },
+  { value: 365, label: "1 year" },
+];
+
+// Largest chats listed with the archive stats
+const TOP_CHATS = 5;
+
+function formatBytes(bytes: number): string {
+  if (bytes < 1024 * 1024) return `${Math.round(bytes / 1024)} KB`;
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+}
+
+function formatDate(timestamp: number): string {
+  return new Date(timestamp * 1000).toLocaleDateString();
+}
+
+interface ArchiveDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+export function ArchiveDialog({ open, onOpenChange }: ArchiveDialogProps) {
+  const [scopes, setScopes] = useState<string[]>([]);
+  const [status, setStatus] = useState<tauri.BackfillStatus | null>(null);
+  const [backfill, setBackfill] = useState<tauri.ArchiveBackfillConfig>(DEFAULT_BACKFILL);
+  const [error, setError] = useState<string | null>(null);
+  const [stats, setStats] = useState<tauri.ArchiveStats | null>(null);
+  const [keepDays, setKeepDays] = useState("90");
+  const [isCompacting, setIsCompacting] = useState(false);
+  const [exportScope, setExportScope] = useState("");
+  const [exportFormat, setExportFormat] = useState<"jsonl" | "csv">("jsonl");
+  const [exportPath, setExportPath] = useState("");
+  const [exportResult, setExportResult] = useState<string | null>(null);
+  const [isExporting, setIsExporting] = useState(false);
+  const chats = useChatStore((state) => state.chats);
+
+  const loadStats = async () => {
+    try {
+      setStats(await tauri.getArchiveStats());
+    } catch (e) {
+      console.error("Failed to load archive stats:", e);
+    }
+  };
+
+  const compact = async (chatIds: number[] | null, olderThan: number | null) => {
+    setIsCompacting(true);
+    setError(null);
+    try {
+      await tauri.compactArchive(chatIds, olderThan);
+      await loadStats();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setIsCompacting(false);
+    }
+  };
+
+  const handleCompactOlder = () =>
+    compact(null, Math.floor(Date.now() / 1000) - Number(keepDays) * 86400);
+
+  const handleExport = async () => {
+    setIsExporting(true);
+    setError(null);
+    setExportResult(null);
+    try {
+      const result = await tauri.exportScopeMessages(exportScope, exportFormat, exportPath.trim());
+      setExportResult(`Exported ${result.messages} messages from ${result.chats} chats to ${result.path}`);
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setIsExporting(false);
+    }
+  };
+
+  const chatTitle = (chatId: number) => chats.find((c) => c.id === chatId)?.title ?? `Chat ${chatId}`;
+
+  // Load settings when the dialog opens and follow the backfill while it's open
+  useEffect(() => {
+    if (!open) return;
+    let cancelled = false;
+
+    const refreshStatus = async () => {
+      try {
+        const latest = await tauri.getArchiveBackfillStatus();
+        if (!cancelled) setStatus(latest);
+        return latest;
+      } catch (e) {
+        console.error("Failed to load archive backfill status:", e);
+        return null;
+      }
+    };
+
+    setError(null);
+    refreshStatus().then((latest) => {
+      if (latest && !cancelled) setBackfill(latest.config);
+    });
+    loadStats();
+    tauri.listScopes().then((names) => {
+      if (!cancelled) setScopes(names);
+    });
+    const unlisten = tauri.onArchiveBackfillProgress(() => refreshStatus());
+
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
+    };
+  }, [open]);
+
+  const handleApply = async () => {
+    try {
+      await tauri.setArchiveBackfillConfig(backfill);
+      onOpenChange(false);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const failed = status?.checkpoints.filter((c) => c.lastError) ?? [];
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-md">
+        <DialogHeader>
+          <DialogTitle>Message Archive</DialogTitle>
+          <DialogDescription>
+            Gradually archive the older history of a scope's chats in the background, so search
+            and analytics cover more than the latest messages.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-4 py-4">
+          <div className="flex items-center gap-2">
+            <Checkbox
+              id="backfill-enabled"
+              checked={backfill.enabled}
+              onCheckedChange={(checked) => setBackfill((prev) => ({ ...prev, enabled: checked === true }))}
+            />
+            <label htmlFor="backfill-enabled" className="text-sm cursor-pointer">
+              Archive history in the background
+            </label>
+          </div>
+
+          <div className="space-y-2">
+            <label htmlFor="backfill-scope" className="text-sm font-medium">
+              Chats to archive
+            </label>
+            <Select
+              value={backfill.scopeName ?? ""}
+              onValueChange={(value) => setBackfill((prev) => ({ ...prev, scopeName: value || null }))}
+            >
+              <SelectTrigger id="backfill-scope">
+                <SelectValue placeholder="Choose a saved scope" />
+              </SelectTrigger>
+              <SelectContent>
+                {scopes.map((name) => (
+                  <SelectItem key={name} value={name}>
+                    {name}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <p className="text-xs text-muted-foreground">
+              Choosing another scope starts the backfill over.
+            </p>
+          </div>
+
+          <div className="space-y-2">
+            <label htmlFor="backfill-rate" className="text-sm font-medium">
+              Pages per minute
+            </label>
+            <Input
+              id="backfill-rate"
+              type="number"
+              min={1}
+              max={60}
+              value={backfill.requestsPerMinute}
+              onChange={(e) =>
+                setBackfill((prev) => ({ ...prev, requestsPerMinute: Math.max(1, Number(e.target.value) || 1) }))
+              }
+            />
+            <p className="text-xs text-muted-foreground">
+              Each page is up to 100 messages. Lower is gentler on Telegram's rate limits.
+            </p>
+          </div>
+
+          {status && status.chatsStarted > 0 && (
+            <div className="rounded-md border p-3 text-sm space-y-1">
+              <p>
+                {status.chatsComplete} of {status.chatsStarted} chats archived ({status.pages} pages fetched)
+              </p>
+              {failed.length > 0 && (
+                <p className="text-xs text-muted-foreground">
+                  {failed.length} chats failed and will be retried: {failed[0].lastError}
+                </p>
+              )}
+            </div>
+          )}
+
+          {stats && (
+            <div className="border-t pt-4 space-y-3">
+              <div className="text-sm">
+                <p className="font-medium">Storage</p>
+                <p className="text-muted-foreground">
+                  {stats.totalMessages.toLocaleString()} messages in {stats.chats.length} chats,{" "}
+                  {formatBytes(stats.dbSizeBytes)} database
+                  {stats.oldestDate !== null && stats.newestDate !== null &&
+                    ` (${formatDate(stats.oldestDate)} – ${formatDate(stats.newestDate)})`}
+                </p>
+              </div>
+
+              {stats.chats.length > 0 && (
+                <div className="space-y-1">
+                  {stats.chats.slice(0, TOP_CHATS).map((chat) => (
+                    <div key={chat.chatId} className="flex items-center gap-2 text-sm">
+                      <span className="flex-1 truncate">{chatTitle(chat.chatId)}</span>
+                      <span className="text-muted-foreground">{chat.messages.toLocaleString()}</span>
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        className="h-6 w-6"
+                        disabled={isCompacting}
+                        onClick={() => compact([chat.chatId], null)}
+                        title="Delete this chat's archived messages"
+                      >
+                        <Trash2 className="h-3 w-3" />
+                      </Button>
+                    </div>
+                  ))}
+                </div>
+              )}
+
+              <div className="flex items-center gap-2">
+                <span className="text-sm shrink-0">Keep the last</span>
+                <Select value={keepDays} onValueChange={setKeepDays}>
+                  <SelectTrigger className="h-8">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    {COMPACT_OPTIONS.map((option) => (
+                      <SelectItem key={option.value} value={option.value.toString()}>
+                        {option.label}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+                <Button
+                  variant="outline"
+                  size="sm"
+                  disabled={isCompacting || stats.totalMessages === 0}
+                  onClick={handleCompactOlder}
+                >
+                  Delete older
+                </Button>
+              </div>
+            </div>
+          )}
+
+          <div className="border-t pt-4 space-y-2">
+            <p className="text-sm font-medium">Export for analysis</p>
+            <div className="flex gap-2">
+              <Select value={exportScope} onValueChange={setExportScope}>
+                <SelectTrigger className="h-8">
+                  <SelectValue placeholder="Scope" />
+                </SelectTrigger>
+                <SelectContent>
+                  {scopes.map((name) => (
+                    <SelectItem key={name} value={name}>
+                      {name}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+              <Select value={exportFormat} onValueChange={(value) => setExportFormat(value as "jsonl" | "csv")}>
+                <SelectTrigger className="h-8 w-28">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value="jsonl">JSONL</SelectItem>
+                  <SelectItem value="csv">CSV</SelectItem>
+                </SelectContent>
+              </Select>
+            </div>
+            <div className="flex gap-2">
+              <Input
+                className="h-8"
+                placeholder="Folder or file path"
+                value={exportPath}
+                onChange={(e) => setExportPath(e.target.value)}
+              />
+              <Button
+                variant="outline"
+                size="sm"
+                disabled={isExporting || !exportScope || !exportPath.trim()}
+                onClick={handleExport}
+              >
+                Export
+              </Button>
+            </div>
+            {exportResult && <p className="text-xs text-muted-foreground break-all">{exportResult}</p>}
+          </div>
+
+          {error && <p className="text-sm text-destructive">{error}</p>}
+        </div>
+
+        <DialogFooter className="gap-2 sm:gap-0">
+          <Button variant="outline" onClick={() => onOpenChange(false)}>
+            Cancel
+          </Button>
+          <Button onClick={handleApply} disabled={backfill.enabled && !backfill.scopeName}>
+            Apply
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
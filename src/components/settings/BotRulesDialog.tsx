@@ -0,0 +1,284 @@
+import { useEffect, useState } from "react";
+import { Trash2 } from "lucide-react";
+import * as tauri from "@/lib/tauri";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import { Input } from "@/components/ui/input";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+
+const ACTION_OPTIONS: { value: tauri.BotRuleAction; label: string; target: string | null }[] = [
+  { value: "notify", label: "Notify me", target: null },
+  { value: "forward", label: "Forward to chat", target: "Chat id" },
+  { value: "tag", label: "Tag the sender", target: "Contact tag" },
+  { value: "extract", label: "Extract with AI", target: "What to extract" },
+];
+
+// Matches shown under the rules
+const RECENT_HITS = 10;
+
+function describeRule(rule: tauri.BotRule): string {
+  if (rule.trigger === "dm") {
+    const cap = rule.dailyCap ? `, ${rule.dailyCap}/day` : "";
+    return `DMs matching ${rule.pattern} → /${rule.target}${cap}`;
+  }
+  const target = rule.target ? ` ${rule.target}` : "";
+  const cap = rule.dailyCap ? `, ${rule.dailyCap}/day` : "";
+  return `Bot ${rule.botId}: ${rule.pattern} → ${rule.action}${target}${cap}`;
+}
+
+interface BotRulesDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+export function BotRulesDialog({ open, onOpenChange }: BotRulesDialogProps) {
+  const [rules, setRules] = useState<tauri.BotRule[]>([]);
+  const [hits, setHits] = useState<tauri.BotRuleHit[]>([]);
+  const [canned, setCanned] = useState<tauri.CannedResponse[]>([]);
+  const [error, setError] = useState<string | null>(null);
+  const [name, setName] = useState("");
+  const [botId, setBotId] = useState("");
+  const [pattern, setPattern] = useState("");
+  const [action, setAction] = useState<tauri.BotRuleAction>("notify");
+  const [target, setTarget] = useState("");
+  const [keyword, setKeyword] = useState("");
+  const [shortcut, setShortcut] = useState("");
+  const [dailyCap, setDailyCap] = useState("");
+
+  const load = async () => {
+    try {
+      const [loadedRules, loadedHits] = await Promise.all([
+        tauri.getBotRules(),
+        tauri.getBotRuleHits(RECENT_HITS),
+      ]);
+      setRules(loadedRules);
+      setHits(loadedHits);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  useEffect(() => {
+    if (!open) return;
+    setError(null);
+    load();
+    tauri.listCannedResponses()
+      .then(setCanned)
+      .catch((e) => console.error("Failed to load canned responses:", e));
+  }, [open]);
+
+  const run = async (change: () => Promise<unknown>) => {
+    setError(null);
+    try {
+      await change();
+      await load();
+      return true;
+    } catch (e) {
+      setError(String(e));
+      return false;
+    }
+  };
+
+  const handleAddBotRule = async () => {
+    const added = await run(() =>
+      tauri.addBotRule(name, Number(botId), pattern, action, target.trim() || undefined)
+    );
+    if (added) {
+      setName("");
+      setPattern("");
+      setTarget("");
+    }
+  };
+
+  const handleAddKeywordRule = async () => {
+    const cap = dailyCap.trim() ? Number(dailyCap) : undefined;
+    const added = await run(() => tauri.addKeywordReplyRule(keyword, shortcut, cap));
+    if (added) {
+      setKeyword("");
+      setDailyCap("");
+    }
+  };
+
+  const targetLabel = ACTION_OPTIONS.find((o) => o.value === action)?.target ?? null;
+  const ruleName = (id: number) => rules.find((r) => r.id === id)?.name;
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-lg max-h-[85vh] overflow-y-auto">
+        <DialogHeader>
+          <DialogTitle>Automation Rules</DialogTitle>
+          <DialogDescription>
+            Act on incoming messages from bots, or answer DMs that mention a keyword with a canned
+            response.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-4 py-4">
+          {rules.length === 0 ? (
+            <p className="text-sm text-muted-foreground">No rules yet.</p>
+          ) : (
+            <div className="space-y-1">
+              {rules.map((rule) => (
+                <div key={rule.id} className="flex items-center gap-2 text-sm">
+                  <Checkbox
+                    checked={rule.enabled}
+                    onCheckedChange={(checked) => run(() => tauri.setBotRuleEnabled(rule.id, checked === true))}
+                    title={rule.enabled ? "Disable" : "Enable"}
+                  />
+                  <div className="flex-1 min-w-0">
+                    <p className="truncate">{rule.name}</p>
+                    <p className="text-xs text-muted-foreground truncate">{describeRule(rule)}</p>
+                  </div>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-6 w-6"
+                    onClick={() => run(() => tauri.deleteBotRule(rule.id))}
+                    title="Delete rule"
+                  >
+                    <Trash2 className="h-3 w-3" />
+                  </Button>
+                </div>
+              ))}
+            </div>
+          )}
+
+          <div className="border-t pt-4 space-y-2">
+            <p className="text-sm font-medium">Bot messages</p>
+            <div className="flex gap-2">
+              <Input
+                className="h-8"
+                placeholder="Name (optional)"
+                value={name}
+                onChange={(e) => setName(e.target.value)}
+              />
+              <Input
+                className="h-8 w-32"
+                placeholder="Bot id"
+                inputMode="numeric"
+                value={botId}
+                onChange={(e) => setBotId(e.target.value)}
+              />
+            </div>
+            <Input
+              className="h-8"
+              placeholder="Pattern (regex), e.g. ^CRITICAL"
+              value={pattern}
+              onChange={(e) => setPattern(e.target.value)}
+            />
+            <div className="flex gap-2">
+              <Select value={action} onValueChange={(value) => setAction(value as tauri.BotRuleAction)}>
+                <SelectTrigger className="h-8 w-44">
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {ACTION_OPTIONS.map((option) => (
+                    <SelectItem key={option.value} value={option.value}>
+                      {option.label}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+              {targetLabel && (
+                <Input
+                  className="h-8"
+                  placeholder={targetLabel}
+                  value={target}
+                  onChange={(e) => setTarget(e.target.value)}
+                />
+              )}
+            </div>
+            <Button
+              variant="outline"
+              size="sm"
+              disabled={!botId.trim() || !pattern.trim() || (targetLabel !== null && !target.trim())}
+              onClick={handleAddBotRule}
+            >
+              Add rule
+            </Button>
+          </div>
+
+          <div className="border-t pt-4 space-y-2">
+            <p className="text-sm font-medium">Keyword replies</p>
+            <Input
+              className="h-8"
+              placeholder="Keyword, e.g. price list"
+              value={keyword}
+              onChange={(e) => setKeyword(e.target.value)}
+            />
+            <div className="flex gap-2">
+              <Select value={shortcut} onValueChange={setShortcut}>
+                <SelectTrigger className="h-8">
+                  <SelectValue placeholder="Canned response" />
+                </SelectTrigger>
+                <SelectContent>
+                  {canned.map((response) => (
+                    <SelectItem key={response.shortcut} value={response.shortcut}>
+                      /{response.shortcut}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+              <Input
+                className="h-8 w-32"
+                type="number"
+                min={1}
+                placeholder="Max/day"
+                value={dailyCap}
+                onChange={(e) => setDailyCap(e.target.value)}
+              />
+            </div>
+            <Button
+              variant="outline"
+              size="sm"
+              disabled={!keyword.trim() || !shortcut}
+              onClick={handleAddKeywordRule}
+            >
+              Add reply
+            </Button>
+          </div>
+
+          {hits.length > 0 && (
+            <div className="border-t pt-4 space-y-1">
+              <p className="text-sm font-medium">Recent matches</p>
+              {hits.map((hit) => (
+                <div key={hit.id} className="text-xs">
+                  <span className="font-medium">{ruleName(hit.ruleId) ?? hit.ruleName}</span>
+                  <span className="text-muted-foreground">
+                    {" · "}
+                    {new Date(hit.matchedAt * 1000).toLocaleString()}
+                    {hit.result && ` · ${hit.result}`}
+                  </span>
+                  {hit.error && <span className="text-destructive"> · {hit.error}</span>}
+                  <p className="text-muted-foreground truncate">{hit.text}</p>
+                </div>
+              ))}
+            </div>
+          )}
+
+          {error && <p className="text-sm text-destructive">{error}</p>}
+        </div>
+
+        <DialogFooter>
+          <Button variant="outline" onClick={() => onOpenChange(false)}>
+            Close
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
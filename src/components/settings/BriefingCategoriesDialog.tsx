@@ -0,0 +1,190 @@
+import { useEffect, useState } from "react";
+import { ArrowDown, ArrowUp, Pencil, Trash2 } from "lucide-react";
+import * as tauri from "@/lib/tauri";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+
+interface BriefingCategoriesDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+export function BriefingCategoriesDialog({ open, onOpenChange }: BriefingCategoriesDialogProps) {
+  const [categories, setCategories] = useState<tauri.BriefingCategory[]>([]);
+  const [editingId, setEditingId] = useState<number | null>(null);
+  const [name, setName] = useState("");
+  const [description, setDescription] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  const load = async () => {
+    try {
+      setCategories(await tauri.getBriefingCategories());
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const resetForm = () => {
+    setEditingId(null);
+    setName("");
+    setDescription("");
+  };
+
+  useEffect(() => {
+    if (!open) return;
+    setError(null);
+    resetForm();
+    load();
+  }, [open]);
+
+  const run = async (change: () => Promise<unknown>) => {
+    setError(null);
+    try {
+      await change();
+      await load();
+      return true;
+    } catch (e) {
+      setError(String(e));
+      return false;
+    }
+  };
+
+  const handleSave = async () => {
+    if (await run(() => tauri.saveBriefingCategory(editingId, name, description))) {
+      resetForm();
+    }
+  };
+
+  const handleEdit = (category: tauri.BriefingCategory) => {
+    setEditingId(category.id);
+    setName(category.name);
+    setDescription(category.description);
+  };
+
+  const handleDelete = async (id: number) => {
+    if (await run(() => tauri.deleteBriefingCategory(id)) && editingId === id) {
+      resetForm();
+    }
+  };
+
+  // Swap a category with its neighbour; sections follow this order in the briefing
+  const handleMove = (index: number, offset: -1 | 1) => {
+    const ids = categories.map((c) => c.id);
+    const other = index + offset;
+    [ids[index], ids[other]] = [ids[other], ids[index]];
+    run(() => tauri.reorderBriefingCategories(ids));
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-md">
+        <DialogHeader>
+          <DialogTitle>Briefing Categories</DialogTitle>
+          <DialogDescription>
+            Chats the AI puts in a category get their own section in the briefing. Changes apply
+            from the next refresh.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-4 py-4">
+          {categories.length === 0 ? (
+            <p className="text-sm text-muted-foreground">No categories yet.</p>
+          ) : (
+            <div className="space-y-1">
+              {categories.map((category, index) => (
+                <div key={category.id} className="flex items-center gap-1 text-sm">
+                  <div className="flex-1 min-w-0">
+                    <p className="truncate font-medium">{category.name}</p>
+                    {category.description && (
+                      <p className="text-xs text-muted-foreground truncate">{category.description}</p>
+                    )}
+                  </div>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-6 w-6"
+                    disabled={index === 0}
+                    onClick={() => handleMove(index, -1)}
+                    title="Move up"
+                  >
+                    <ArrowUp className="h-3 w-3" />
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-6 w-6"
+                    disabled={index === categories.length - 1}
+                    onClick={() => handleMove(index, 1)}
+                    title="Move down"
+                  >
+                    <ArrowDown className="h-3 w-3" />
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-6 w-6"
+                    onClick={() => handleEdit(category)}
+                    title="Edit"
+                  >
+                    <Pencil className="h-3 w-3" />
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-6 w-6"
+                    onClick={() => handleDelete(category.id)}
+                    title="Delete"
+                  >
+                    <Trash2 className="h-3 w-3" />
+                  </Button>
+                </div>
+              ))}
+            </div>
+          )}
+
+          <div className="border-t pt-4 space-y-2">
+            <p className="text-sm font-medium">{editingId === null ? "New category" : "Edit category"}</p>
+            <Input
+              className="h-8"
+              placeholder="Name, e.g. Investors"
+              value={name}
+              onChange={(e) => setName(e.target.value)}
+            />
+            <Input
+              className="h-8"
+              placeholder="Which chats belong here, for the AI"
+              value={description}
+              onChange={(e) => setDescription(e.target.value)}
+            />
+            <div className="flex gap-2">
+              <Button variant="outline" size="sm" disabled={!name.trim()} onClick={handleSave}>
+                {editingId === null ? "Add" : "Save"}
+              </Button>
+              {editingId !== null && (
+                <Button variant="ghost" size="sm" onClick={resetForm}>
+                  Cancel
+                </Button>
+              )}
+            </div>
+          </div>
+
+          {error && <p className="text-sm text-destructive">{error}</p>}
+        </div>
+
+        <DialogFooter>
+          <Button variant="outline" onClick={() => onOpenChange(false)}>
+            Close
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -0,0 +1,203 @@
+import { useEffect, useState } from "react";
+import { Trash2 } from "lucide-react";
+import * as tauri from "@/lib/tauri";
+import { useChatStore } from "@/stores/chatStore";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+
+// Undo window options in seconds (the backend allows up to 60)
+const DELAY_OPTIONS = [
+  { value: 0, label: "Off" },
+  { value: 5, label: "5 seconds" },
+  { value: 10, label: "10 seconds" },
+  { value: 30, label: "30 seconds" },
+  { value: 60, label: "1 minute" },
+];
+
+interface SendingDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+export function SendingDialog({ open, onOpenChange }: SendingDialogProps) {
+  const [delay, setDelay] = useState(0);
+  const [draftApproval, setDraftApproval] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [isSaving, setIsSaving] = useState(false);
+  const [outbox, setOutbox] = useState<tauri.OutboxEntry[]>([]);
+  const [isRetrying, setIsRetrying] = useState(false);
+  const chats = useChatStore((state) => state.chats);
+
+  const loadOutbox = () =>
+    tauri.getOutbox()
+      .then(setOutbox)
+      .catch((e) => console.error("Failed to load outbox:", e));
+
+  // Keep the outbox list current while the dialog is open
+  useEffect(() => {
+    if (!open) return;
+    loadOutbox();
+    const unlisteners = [tauri.onOutboxSent(() => loadOutbox()), tauri.onOutboxFailed(() => loadOutbox())];
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()));
+    };
+  }, [open]);
+
+  const handleRetryOutbox = async () => {
+    setIsRetrying(true);
+    try {
+      await tauri.retryOutbox();
+      await loadOutbox();
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setIsRetrying(false);
+    }
+  };
+
+  const handleCancelOutbox = async (id: number) => {
+    try {
+      await tauri.cancelOutboxMessage(id);
+    } catch (e) {
+      setError(String(e));
+    }
+    await loadOutbox();
+  };
+
+  const chatTitle = (chatId: number) => chats.find((c) => c.id === chatId)?.title ?? `Chat ${chatId}`;
+
+  useEffect(() => {
+    if (!open) return;
+    setError(null);
+    tauri.getSendDelay()
+      .then(setDelay)
+      .catch((e) => console.error("Failed to load send delay:", e));
+    tauri.getDraftApproval()
+      .then(setDraftApproval)
+      .catch((e) => console.error("Failed to load draft approval:", e));
+  }, [open]);
+
+  const handleApply = async () => {
+    setIsSaving(true);
+    setError(null);
+    try {
+      await tauri.setSendDelay(delay);
+      await tauri.setDraftApproval(draftApproval);
+      onOpenChange(false);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="sm:max-w-md">
+        <DialogHeader>
+          <DialogTitle>Sending</DialogTitle>
+          <DialogDescription>
+            Control how replies from the briefing and automated replies go out.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-4 py-4">
+          <div className="space-y-2">
+            <label htmlFor="send-delay" className="text-sm font-medium">
+              Undo window
+            </label>
+            <Select
+              value={delay.toString()}
+              onValueChange={(value) => setDelay(parseInt(value))}
+            >
+              <SelectTrigger id="send-delay">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {DELAY_OPTIONS.map((option) => (
+                  <SelectItem key={option.value} value={option.value.toString()}>
+                    {option.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <p className="text-xs text-muted-foreground">
+              Replies from the briefing wait this long before going out, so you can undo them.
+            </p>
+          </div>
+
+          <div className="flex items-center gap-2">
+            <Checkbox
+              id="draft-approval"
+              checked={draftApproval}
+              onCheckedChange={(checked) => setDraftApproval(checked === true)}
+            />
+            <label htmlFor="draft-approval" className="text-sm cursor-pointer">
+              Hold away and rule replies for my approval
+            </label>
+          </div>
+
+          {outbox.length > 0 && (
+            <div className="border-t pt-4 space-y-2">
+              <div className="flex items-center justify-between">
+                <p className="text-sm font-medium">Waiting to send ({outbox.length})</p>
+                <Button variant="outline" size="sm" disabled={isRetrying} onClick={handleRetryOutbox}>
+                  Retry now
+                </Button>
+              </div>
+              {outbox.map((entry) => (
+                <div key={entry.id} className="flex items-center gap-2 text-sm">
+                  <div className="flex-1 min-w-0">
+                    <p className="truncate">
+                      <span className="font-medium">{chatTitle(entry.chatId)}:</span> {entry.text}
+                    </p>
+                    {entry.lastError && (
+                      <p className="text-xs text-muted-foreground truncate">
+                        {entry.attempts} attempts · {entry.lastError}
+                      </p>
+                    )}
+                  </div>
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-6 w-6"
+                    onClick={() => handleCancelOutbox(entry.id)}
+                    title="Don't send"
+                  >
+                    <Trash2 className="h-3 w-3" />
+                  </Button>
+                </div>
+              ))}
+            </div>
+          )}
+
+          {error && <p className="text-sm text-destructive">{error}</p>}
+        </div>
+
+        <DialogFooter className="gap-2 sm:gap-0">
+          <Button variant="outline" onClick={() => onOpenChange(false)}>
+            Cancel
+          </Button>
+          <Button onClick={handleApply} disabled={isSaving}>
+            Apply
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
@@ -126,6 +126,25 @@ export function SummaryCard({
       <CardContent className="space-y-3">
         <p className="text-sm">{summary.summary}</p>
 
+        {summary.topics && summary.topics.length > 0 && (
+          <div className="space-y-2">
+            <p className="text-xs font-medium text-muted-foreground">
+              Topics ({summary.topics.length})
+            </p>
+            {summary.topics.map((topic, i) => (
+              <div key={i} className="text-sm border-l-2 border-muted pl-2">
+                <p className="font-medium">{topic.title}</p>
+                <p className="text-muted-foreground">{topic.summary}</p>
+                {topic.participants.length > 0 && (
+                  <p className="text-xs text-muted-foreground mt-0.5">
+                    {topic.participants.join(", ")}
+                  </p>
+                )}
+              </div>
+            ))}
+          </div>
+        )}
+
         {summary.keyPoints.length > 0 && (
           <Collapsible
             open={expandedKeyPoints.has(summary.chatId)}
@@ -1,3 +1,5 @@
+import type { TopicSummary } from "@/lib/tauri";
+
 export type ChatTypeFilter = "all" | "private" | "group" | "channel";
 export type TimeFilter = "week" | "month" | "3months";
 export type SortOption = "recent" | "needs_response" | "sentiment" | "messages";
@@ -13,6 +15,7 @@ export interface ChatSummary {
   needsResponse: boolean;
   messageCount: number;
   lastMessageDate: number;
+  topics?: TopicSummary[];
   isLargeGroup?: boolean;
   memberCount?: number;
 }
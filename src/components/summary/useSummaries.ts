@@ -4,6 +4,7 @@ import { chatFiltersFromSettings } from "@/lib/tauri";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { useSummaryStore } from "@/stores/summaryStore";
 import { useChatStore, DEFAULT_CHAT_LIMIT } from "@/stores/chatStore";
+import { describeMessageContent } from "@/lib/utils";
 import type { Folder } from "@/types/telegram";
 import {
   ChatSummary,
@@ -235,7 +236,7 @@ export function useSummaries(options: UseSummariesOptions) {
               messages: messages.map((m: any) => ({
                 id: Number(m.id),
                 sender_name: m.senderName,
-                text: m.content.type === "text" ? m.content.text : "[Media]",
+                text: describeMessageContent(m.content),
                 date: m.date,
                 is_outgoing: m.isOutgoing,
               })),
@@ -283,6 +284,7 @@ export function useSummaries(options: UseSummariesOptions) {
             needsResponse: Boolean(s.needs_response),
             messageCount: typeof s.message_count === "number" ? s.message_count : 0,
             lastMessageDate: typeof s.last_message_date === "number" ? s.last_message_date : 0,
+            topics: Array.isArray(s.topics) ? s.topics : [],
           }));
 
         newSummaries = [...newSummaries, ...largeGroupSummaries];
@@ -332,7 +334,7 @@ export function useSummaries(options: UseSummariesOptions) {
               messages: messages.map((m: any) => ({
                 id: Number(m.id),
                 sender_name: m.senderName,
-                text: m.content.type === "text" ? m.content.text : "[Media]",
+                text: describeMessageContent(m.content),
                 date: m.date,
                 is_outgoing: m.isOutgoing,
               })),
@@ -355,6 +357,7 @@ export function useSummaries(options: UseSummariesOptions) {
               needsResponse: Boolean(newSummary.needs_response),
               messageCount: typeof newSummary.message_count === "number" ? newSummary.message_count : existingSummary.messageCount,
               lastMessageDate: typeof newSummary.last_message_date === "number" ? newSummary.last_message_date : existingSummary.lastMessageDate,
+              topics: Array.isArray(newSummary.topics) ? newSummary.topics : [],
             });
           }
         }
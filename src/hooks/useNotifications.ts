@@ -0,0 +1,46 @@
+import { useEffect } from "react";
+import { onBotRuleNotification, onNotifyMessage, onOutboxFailed } from "@/lib/tauri";
+import { describeMessageContent } from "@/lib/utils";
+import { useChatStore } from "@/stores/chatStore";
+
+/** Show a desktop notification, asking for permission the first time */
+export async function showNotification(title: string, body: string) {
+  if (typeof Notification === "undefined") return;
+  if (Notification.permission === "default") {
+    await Notification.requestPermission();
+  }
+  if (Notification.permission === "granted") {
+    new Notification(title, { body });
+  }
+}
+
+/** Surface the backend's notify:// events as desktop notifications */
+export function useNotifications() {
+  useEffect(() => {
+    const unlisteners = [
+      // Incoming messages that passed the notification rules (mode, quiet hours)
+      onNotifyMessage((message) => {
+        const chat = useChatStore.getState().chats.find((c) => c.id === message.chatId);
+        const title = chat && chat.type !== "private" ? `${message.senderName} in ${chat.title}` : message.senderName;
+        showNotification(title, describeMessageContent(message.content)).catch((e) =>
+          console.error("Failed to show notification:", e)
+        );
+      }),
+      onBotRuleNotification((hit) => {
+        showNotification(hit.ruleName, hit.text).catch((e) =>
+          console.error("Failed to show notification:", e)
+        );
+      }),
+      // A message queued while offline could not be sent after all
+      onOutboxFailed((result) => {
+        showNotification("Message not sent", result.error ?? "Gave up after repeated failures").catch((e) =>
+          console.error("Failed to show notification:", e)
+        );
+      }),
+    ];
+
+    return () => {
+      unlisteners.forEach((unlisten) => unlisten.then((fn) => fn()));
+    };
+  }, []);
+}
@@ -1,6 +1,14 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type { AuthState, User, Chat, Message, Folder } from "@/types/telegram";
-import type { Contact } from "@/types/contacts";
+import type {
+  Birthday,
+  Contact,
+  TagSuggestion,
+  TimelineEvent,
+  UpcomingBirthday,
+  UserProfile,
+} from "@/types/contacts";
 import type { ChatFilterSettings } from "@/stores/settingsStore";
 
 // Convert frontend filter settings to backend format
@@ -91,14 +99,280 @@ export async function logout(): Promise<void> {
 }
 
 // Chat commands
-export async function getChats(limit: number, filters?: ChatFilters): Promise<Chat[]> {
-  return invoke("get_chats", { limit, filters });
+export async function getChats(limit: number, filters?: ChatFilters, sort?: ChatSort): Promise<Chat[]> {
+  return invoke("get_chats", { limit, filters, sort });
+}
+
+export type ChatSort = "default" | "unread_desc" | "last_activity" | "member_count" | "starred_first";
+
+export interface ChatPage {
+  chats: Chat[];
+  total: number;
+  nextOffset: number | null;
+}
+
+// Sorted and paginated in Rust; pass nextOffset back as offset for the next page
+export async function getChatsPage(
+  limit: number,
+  options: { filters?: ChatFilters; sort?: ChatSort; offset?: number; pageSize?: number } = {}
+): Promise<ChatPage> {
+  return invoke("get_chats_page", {
+    limit,
+    filters: options.filters,
+    sort: options.sort ?? null,
+    offset: options.offset ?? null,
+    pageSize: options.pageSize ?? null,
+  });
+}
+
+// Command palette search: contacts, chat titles and the local archive, plus Telegram message search when includeServer is set
+export interface UniversalSearchResults {
+  query: string;
+  contacts: User[];
+  chats: Chat[];
+  archivedMessages: LocalSearchHit[];
+  messages: Message[];
+  errors: string[];
+}
+
+export async function universalSearch(
+  query: string,
+  options: { includeServer?: boolean; limit?: number } = {}
+): Promise<UniversalSearchResults> {
+  return invoke("universal_search", {
+    query,
+    includeServer: options.includeServer ?? null,
+    limit: options.limit ?? null,
+  });
+}
+
+export interface MessageSearch {
+  query: string;
+  chatId?: number;
+  senderId?: number;
+  /** Unix seconds, inclusive */
+  minDate?: number;
+  maxDate?: number;
+  limit?: number;
+  /** `nextOffset` of the previous page */
+  offset?: string;
+}
+
+export interface MessageSearchPage {
+  messages: Message[];
+  nextOffset: string | null;
+  total: number;
+}
+
+/** Full-text search across all chats (or one), newest first, a page at a time */
+export async function searchMessages(search: MessageSearch): Promise<MessageSearchPage> {
+  return invoke("search_messages", { search });
+}
+
+export interface SnippetPart {
+  text: string;
+  highlight: boolean;
+}
+
+export interface LocalSearchHit {
+  chatId: number;
+  messageId: number;
+  senderId: number;
+  senderName: string;
+  date: number;
+  isOutgoing: boolean;
+  snippet: SnippetPart[];
+  rank: number;
+}
+
+// Searches the local message archive only (no Telegram call)
+export async function searchLocalMessages(
+  query: string,
+  chatId?: number,
+  limit?: number,
+  offset?: number
+): Promise<LocalSearchHit[]> {
+  return invoke("search_local_messages", {
+    query,
+    chatId: chatId ?? null,
+    limit: limit ?? null,
+    offset: offset ?? null,
+  });
 }
 
 export async function getChat(chatId: number): Promise<Chat | null> {
   return invoke("get_chat", { chatId });
 }
 
+export async function setChatStarred(chatId: number, starred: boolean): Promise<void> {
+  return invoke("set_chat_starred", { chatId, starred });
+}
+
+/** Blank notes delete them */
+export async function updateChatNotes(chatId: number, notes: string): Promise<void> {
+  return invoke("update_chat_notes", { chatId, notes });
+}
+
+export interface DownloadedMedia {
+  chatId: number;
+  messageId: number;
+  /** Local file path (use convertFileSrc to display it) */
+  path: string;
+  fileName: string;
+  mimeType: string | null;
+  size: number;
+}
+
+export interface DownloadProgress {
+  chatId: number;
+  messageId: number;
+  downloaded: number;
+  total: number;
+}
+
+/** Save a message's photo, document or voice note to disk; earlier downloads are reused */
+export async function downloadChatMedia(chatId: number, messageId: number): Promise<DownloadedMedia> {
+  return invoke("download_chat_media", { chatId, messageId });
+}
+
+// Fired after each downloaded chunk of an attachment
+export function onDownloadProgress(callback: (progress: DownloadProgress) => void): Promise<UnlistenFn> {
+  return listen<DownloadProgress>("media://progress", (event) => callback(event.payload));
+}
+
+export interface ArchiveSyncReport {
+  chatId: number;
+  newMessages: number;
+  backfilledMessages: number;
+  caughtUp: boolean;
+  reachedStart: boolean;
+  oldestMessageId: number | null;
+  newestMessageId: number | null;
+}
+
+export interface ArchiveSyncProgress {
+  chatId: number;
+  phase: "newer" | "older";
+  page: number;
+  archived: number;
+}
+
+/** Archive a chat's messages newer than the newest archived one, then `backfillPages` pages of older history */
+export async function syncChatHistory(chatId: number, backfillPages?: number): Promise<ArchiveSyncReport> {
+  return invoke("sync_chat_history", { chatId, backfillPages });
+}
+
+// Fired after each page a chat sync archives
+export function onArchiveSyncProgress(callback: (progress: ArchiveSyncProgress) => void): Promise<UnlistenFn> {
+  return listen<ArchiveSyncProgress>("archive://sync-progress", (event) => callback(event.payload));
+}
+
+export interface ArchiveBackfillConfig {
+  enabled: boolean;
+  /** Saved scope whose chats are archived */
+  scopeName: string | null;
+  /** Pages of history fetched per minute, across all chats */
+  requestsPerMinute: number;
+}
+
+export interface BackfillCheckpoint {
+  chatId: number;
+  pages: number;
+  /** Archived back to the chat's first message or the retention window */
+  complete: boolean;
+  lastError: string | null;
+  updatedAt: number;
+}
+
+export interface BackfillStatus {
+  config: ArchiveBackfillConfig;
+  chatsStarted: number;
+  chatsComplete: number;
+  pages: number;
+  checkpoints: BackfillCheckpoint[];
+}
+
+export async function getArchiveBackfillStatus(): Promise<BackfillStatus> {
+  return invoke("get_archive_backfill_status");
+}
+
+/** Picking another scope starts its chats over */
+export async function setArchiveBackfillConfig(config: ArchiveBackfillConfig): Promise<void> {
+  return invoke("set_archive_backfill_config", { config });
+}
+
+// Fired after each page the background backfill archives (payload: chat id)
+export function onArchiveBackfillProgress(callback: (chatId: number) => void): Promise<UnlistenFn> {
+  return listen<number>("archive://backfill-progress", (event) => callback(event.payload));
+}
+
+export interface ArchiveChatStats {
+  chatId: number;
+  messages: number;
+  oldestDate: number;
+  newestDate: number;
+}
+
+export interface ArchiveStats {
+  /** Largest chats first */
+  chats: ArchiveChatStats[];
+  totalMessages: number;
+  /** Size of the whole database file */
+  dbSizeBytes: number;
+  oldestDate: number | null;
+  newestDate: number | null;
+}
+
+export async function getArchiveStats(): Promise<ArchiveStats> {
+  return invoke("get_archive_stats");
+}
+
+/** Delete archived messages of some chats and/or from before a date (unix seconds); returns how many were deleted */
+export async function compactArchive(chatIds: number[] | null, olderThan: number | null): Promise<number> {
+  return invoke("compact_archive", { chatIds, olderThan });
+}
+
+export interface DeletedMessage {
+  message: Message;
+  deletedAt: number;
+  /** Texts it had before being edited, oldest first */
+  earlierVersions: string[];
+}
+
+/** Archived messages of a chat that were deleted, most recently deleted first */
+export async function getRecentlyDeleted(chatId: number, limit?: number): Promise<DeletedMessage[]> {
+  return invoke("get_recently_deleted", { chatId, limit });
+}
+
+export interface ChatReminder {
+  id: number;
+  chatId: number;
+  chatTitle: string;
+  chatType: string;
+  /** Unix seconds */
+  remindAt: number;
+  note: string | null;
+  createdAt: number;
+}
+
+/** Resurface a chat in the briefing at `at` (unix seconds) */
+export async function remindAboutChat(chatId: number, at: number, note?: string): Promise<ChatReminder> {
+  return invoke("remind_about_chat", { chatId, at, note });
+}
+
+export async function getDueChatReminders(): Promise<ChatReminder[]> {
+  return invoke("get_due_chat_reminders");
+}
+
+export async function completeChatReminder(id: number): Promise<void> {
+  return invoke("complete_chat_reminder", { id });
+}
+
+/** A null or empty alias restores the Telegram title */
+export async function setChatAlias(chatId: number, alias: string | null): Promise<void> {
+  return invoke("set_chat_alias", { chatId, alias });
+}
+
 export async function getChatMessages(
   chatId: number,
   limit: number,
@@ -107,10 +381,127 @@ export async function getChatMessages(
   return invoke("get_chat_messages", { chatId, limit, fromMessageId });
 }
 
+/** While offline the message is queued in the outbox and comes back with a negative id */
 export async function sendMessage(chatId: number, text: string): Promise<Message> {
   return invoke("send_message", { chatId, text });
 }
 
+export interface PendingSend {
+  id: number;
+  chatId: number;
+  text: string;
+  /** Unix seconds when the message goes out */
+  sendAt: number;
+}
+
+export interface SendCompleted {
+  id: number;
+  chatId: number;
+  message: Message | null;
+  error: string | null;
+}
+
+/**
+ * Send after the configured delay; the result arrives via onSendCompleted.
+ * With readUpTo, a successful send also marks the chat read up to that message.
+ */
+export async function sendMessageWithUndo(
+  chatId: number,
+  text: string,
+  readUpTo?: number | null
+): Promise<PendingSend> {
+  return invoke("send_message_with_undo", { chatId, text, readUpTo: readUpTo ?? null });
+}
+
+/** Cancel a delayed send that hasn't gone out yet */
+export async function undoSend(pendingId: number): Promise<PendingSend> {
+  return invoke("undo_send", { pendingId });
+}
+
+export async function getPendingSends(): Promise<PendingSend[]> {
+  return invoke("get_pending_sends");
+}
+
+export async function getSendDelay(): Promise<number> {
+  return invoke("get_send_delay");
+}
+
+/** Seconds a send can be undone for (0 disables the delay, max 60) */
+export async function setSendDelay(secs: number): Promise<void> {
+  return invoke("set_send_delay", { secs });
+}
+
+// Fired when a delayed send has gone out (or failed)
+export function onSendCompleted(callback: (result: SendCompleted) => void): Promise<UnlistenFn> {
+  return listen<SendCompleted>("send://completed", (event) => callback(event.payload));
+}
+
+/** A message sent while offline, waiting to be retried on reconnect */
+export interface OutboxEntry {
+  id: number;
+  chatId: number;
+  text: string;
+  status: "pending" | "sent" | "failed";
+  attempts: number;
+  lastError: string | null;
+  createdAt: number;
+}
+
+export interface OutboxResult {
+  id: number;
+  chatId: number;
+  message: Message | null;
+  error: string | null;
+}
+
+export async function getOutbox(): Promise<OutboxEntry[]> {
+  return invoke("get_outbox");
+}
+
+/** Retry waiting messages now instead of on the next reconnect */
+export async function retryOutbox(): Promise<void> {
+  return invoke("retry_outbox");
+}
+
+export async function cancelOutboxMessage(id: number): Promise<void> {
+  return invoke("cancel_outbox_message", { id });
+}
+
+// Fired when a queued offline message finally goes out
+export function onOutboxSent(callback: (result: OutboxResult) => void): Promise<UnlistenFn> {
+  return listen<OutboxResult>("outbox://sent", (event) => callback(event.payload));
+}
+
+// Fired when a queued offline message is given up on
+export function onOutboxFailed(callback: (result: OutboxResult) => void): Promise<UnlistenFn> {
+  return listen<OutboxResult>("outbox://failed", (event) => callback(event.payload));
+}
+
+export interface BotCallbackAnswer {
+  message: string | null;
+  alert: boolean;
+  url: string | null;
+}
+
+/** Press a bot's inline callback button */
+export async function clickInlineButton(chatId: number, messageId: number, data: string): Promise<BotCallbackAnswer> {
+  return invoke("click_inline_button", { chatId, messageId, data });
+}
+
+export interface DeleteMessagesResult {
+  found: number;
+  deleted: number;
+  stoppedReason: string | null;
+}
+
+export async function deleteMyMessages(
+  chatId: number,
+  beforeDate?: number,
+  dryRun?: boolean
+): Promise<DeleteMessagesResult> {
+  return invoke("delete_my_messages", { chatId, beforeDate, dryRun });
+}
+
 export interface BatchMessageRequest {
   chatId: number;
   limit: number;
@@ -122,82 +513,1102 @@ export interface BatchMessageResult {
   error: string | null;
 }
 
-export async function getBatchMessages(
-  requests: BatchMessageRequest[]
-): Promise<BatchMessageResult[]> {
-  return invoke("get_batch_messages", { requests });
+export async function getBatchMessages(
+  requests: BatchMessageRequest[]
+): Promise<BatchMessageResult[]> {
+  return invoke("get_batch_messages", { requests });
+}
+
+/** Mark a chat as read in Telegram, up to a message or entirely */
+export async function markChatRead(chatId: number, upToMessageId?: number): Promise<void> {
+  return invoke("mark_chat_read", { chatId, upToMessageId: upToMessageId ?? null });
+}
+
+export async function invalidateChatCache(): Promise<void> {
+  return invoke("invalidate_chat_cache");
+}
+
+/** Refetch one chat and drop the cached briefings and summaries that cover it */
+export async function invalidateChat(chatId: number): Promise<Chat | null> {
+  return invoke("invalidate_chat", { chatId });
+}
+
+/** Drop cached briefings covering any of the chats; returns how many were dropped */
+export async function invalidateBriefingForChats(chatIds: number[]): Promise<number> {
+  return invoke("invalidate_briefing_for_chats", { chatIds });
+}
+
+// Follow-up commands
+export interface OverdueReply {
+  chatId: number;
+  chatName: string;
+  messageId: number;
+  messageText: string;
+  messageDate: number;
+  hoursWaiting: number;
+  unreadCount: number;
+}
+
+export async function getOverdueReplies(hoursThreshold: number): Promise<OverdueReply[]> {
+  return invoke("get_overdue_replies", { hoursThreshold });
+}
+
+export interface AwaitingReply {
+  chatId: number;
+  chatName: string;
+  messageId: number;
+  messageText: string;
+  messageDate: number;
+  daysWaiting: number;
+}
+
+export async function getAwaitingReplies(daysThreshold: number): Promise<AwaitingReply[]> {
+  return invoke("get_awaiting_replies", { daysThreshold });
+}
+
+// Contact commands
+export interface ContactsResponse {
+  contacts: Contact[];
+  cached: boolean;
+  cacheAge: string | null;
+}
+
+export async function getContacts(
+  forceRefresh?: boolean,
+  ttlMinutes?: number
+): Promise<ContactsResponse> {
+  return invoke("get_contacts", { forceRefresh, ttlMinutes });
+}
+
+export async function addContactTag(
+  userId: number,
+  tag: string
+): Promise<void> {
+  return invoke("add_contact_tag", { userId, tag });
+}
+
+export async function removeContactTag(
+  userId: number,
+  tag: string
+): Promise<void> {
+  return invoke("remove_contact_tag", { userId, tag });
+}
+
+export async function updateContactNotes(
+  userId: number,
+  notes: string
+): Promise<void> {
+  return invoke("update_contact_notes", { userId, notes });
+}
+
+// Cached birthdays; refresh re-fetches stale ones from Telegram profiles first (slow)
+export async function getUpcomingBirthdays(
+  days: number,
+  refresh?: boolean,
+  draftGreetings?: boolean
+): Promise<UpcomingBirthday[]> {
+  return invoke("get_upcoming_birthdays", { days, refresh, draftGreetings });
+}
+
+export async function refreshContactProfiles(userIds: number[]): Promise<UserProfile[]> {
+  return invoke("refresh_contact_profiles", { userIds });
+}
+
+export async function findContactsByBio(keywords: string[]): Promise<number[]> {
+  return invoke("find_contacts_by_bio", { keywords });
+}
+
+export async function suggestContactTags(userId: number): Promise<TagSuggestion[]> {
+  return invoke("suggest_contact_tags", { userId });
+}
+
+export async function applyContactTags(userId: number, tags: string[]): Promise<void> {
+  return invoke("apply_contact_tags", { userId, tags });
+}
+
+export async function getContactTimeline(
+  userId: number,
+  messageLimit?: number
+): Promise<TimelineEvent[]> {
+  return invoke("get_contact_timeline", { userId, messageLimit });
+}
+
+export interface PersonActivityItem {
+  chatId: number;
+  chatTitle: string;
+  message: Message;
+}
+
+/** What someone said recently across the archived chats (their DM and archived groups), newest first */
+export async function getPersonActivity(userId: number, days: number): Promise<PersonActivityItem[]> {
+  return invoke("get_person_activity", { userId, days });
+}
+
+/** A DM in a dossier (AI context format, hence the snake_case fields) */
+export interface DossierMessage {
+  id: number;
+  sender_name: string;
+  text: string;
+  date: number;
+  is_outgoing: boolean;
+  kind: string | null;
+}
+
+export interface RelationshipSummary {
+  summary: string;
+  topics: string[];
+  openItems: string[];
+}
+
+export interface ContactDossier {
+  userId: number;
+  name: string;
+  username: string | null;
+  phoneNumber: string | null;
+  bio: string | null;
+  birthday: Birthday | null;
+  tags: string[];
+  notes: string;
+  sharedGroups: CommonGroup[];
+  /** DM history, oldest first */
+  messages: DossierMessage[];
+  firstMessageAt: number | null;
+  lastMessageAt: number | null;
+  /** Null when there is no history or the AI call failed */
+  summary: RelationshipSummary | null;
+  /** The whole dossier as Markdown */
+  markdown: string;
+  generatedAt: number;
+}
+
+/** Profile, notes, shared groups, DM history and an AI relationship summary for one contact */
+export async function generateContactDossier(userId: number): Promise<ContactDossier> {
+  return invoke("generate_contact_dossier", { userId });
+}
+
+// Scope commands
+export async function getFolders(): Promise<Folder[]> {
+  return invoke("get_folders");
+}
+
+export async function saveScope(name: string, config: unknown): Promise<void> {
+  return invoke("save_scope", { name, config });
+}
+
+// Scope proposed from topic clusters; save it with saveScope(suggestion.name, suggestion.config)
+export interface ScopeSuggestion {
+  name: string;
+  chatIds: number[];
+  chatTitles: string[];
+  config: {
+    folder_ids: number[];
+    chat_types: string[];
+    excluded_chat_ids: number[];
+    included_chat_ids: number[];
+    contact_list_ids: string[];
+  };
+}
+
+export async function suggestScopes(limit?: number): Promise<ScopeSuggestion[]> {
+  return invoke("suggest_scopes", { limit: limit ?? null });
+}
+
+export async function loadScope(name: string): Promise<unknown> {
+  return invoke("load_scope", { name });
+}
+
+export async function listScopes(): Promise<string[]> {
+  return invoke("list_scopes");
+}
+
+// Outreach commands
+export async function queueOutreachMessages(
+  recipientIds: number[],
+  template: string,
+  listId?: string
+): Promise<string> {
+  return invoke("queue_outreach_messages", { recipientIds, template, listId });
+}
+
+export async function getOutreachStatus(queueId: string): Promise<unknown> {
+  return invoke("get_outreach_status", { queueId });
+}
+
+export async function cancelOutreach(queueId: string): Promise<void> {
+  return invoke("cancel_outreach", { queueId });
+}
+
+// Crosspost commands
+export interface CrosspostTarget {
+  chatId: number;
+  chatTitle: string;
+  sendAt: number | null;
+  status: "pending" | "sent" | "failed";
+  error: string | null;
+  sentAt: number | null;
+}
+
+export interface CrosspostJob {
+  id: string;
+  text: string | null;
+  sourceChatId: number | null;
+  sourceMessageId: number | null;
+  status: "running" | "completed" | "cancelled";
+  targets: CrosspostTarget[];
+  sentCount: number;
+  failedCount: number;
+  createdAt: number;
+  completedAt: number | null;
+}
+
+// Send `text`, or forward `messageId` from `sourceChatId`, to each target at its
+// `sendAt` (unix seconds) or right away. Returns the job id.
+export async function crosspost(args: {
+  text?: string;
+  sourceChatId?: number;
+  messageId?: number;
+  targets: { chatId: number; sendAt?: number }[];
+}): Promise<string> {
+  return invoke("crosspost", {
+    text: args.text ?? null,
+    sourceChatId: args.sourceChatId ?? null,
+    messageId: args.messageId ?? null,
+    targets: args.targets,
+  });
+}
+
+export async function getCrosspostStatus(jobId: string): Promise<CrosspostJob | null> {
+  return invoke("get_crosspost_status", { jobId });
+}
+
+export async function getCrossposts(): Promise<CrosspostJob[]> {
+  return invoke("get_crossposts");
+}
+
+export async function cancelCrosspost(jobId: string): Promise<void> {
+  return invoke("cancel_crosspost", { jobId });
+}
+
+// Content calendar commands
+export interface ScheduledPost {
+  id: number;
+  chatId: number;
+  chatTitle: string;
+  text: string;
+  mediaPath: string | null;
+  sendAt: number;
+  status: "scheduled" | "sent" | "failed" | "cancelled";
+  attempts: number;
+  retryAt: number | null;
+  lastError: string | null;
+  messageId: number | null;
+  sentAt: number | null;
+  createdAt: number;
+}
+
+// Ids of the channels and groups the user administers
+export async function getPostableChats(): Promise<number[]> {
+  return invoke("get_postable_chats");
+}
+
+export async function schedulePost(
+  chatId: number,
+  text: string,
+  sendAt: number,
+  mediaPath?: string
+): Promise<ScheduledPost> {
+  return invoke("schedule_post", { chatId, text, mediaPath: mediaPath ?? null, sendAt });
+}
+
+export async function getScheduledPosts(from: number, to: number): Promise<ScheduledPost[]> {
+  return invoke("get_scheduled_posts", { from, to });
+}
+
+export async function updateScheduledPost(
+  id: number,
+  text: string,
+  sendAt: number,
+  mediaPath?: string
+): Promise<ScheduledPost> {
+  return invoke("update_scheduled_post", { id, text, mediaPath: mediaPath ?? null, sendAt });
+}
+
+export async function retryScheduledPost(id: number): Promise<void> {
+  return invoke("retry_scheduled_post", { id });
+}
+
+export async function cancelScheduledPost(id: number): Promise<void> {
+  return invoke("cancel_scheduled_post", { id });
+}
+
+export async function deleteScheduledPost(id: number): Promise<void> {
+  return invoke("delete_scheduled_post", { id });
+}
+
+// Fired when a scheduled post is published
+export function onPostPublished(callback: (post: ScheduledPost) => void): Promise<UnlistenFn> {
+  return listen<ScheduledPost>("posts://published", (event) => callback(event.payload));
+}
+
+// Fired when a scheduled post is given up on after its retries
+export function onPostFailed(callback: (post: ScheduledPost) => void): Promise<UnlistenFn> {
+  return listen<ScheduledPost>("posts://failed", (event) => callback(event.payload));
+}
+
+// Channel analytics commands
+export interface PostViews {
+  messageId: number;
+  date: number;
+  views: number;
+}
+
+export interface ChannelSnapshot {
+  channelId: number;
+  takenAt: number;
+  subscriberCount: number;
+  avgViews: number | null;
+}
+
+export interface GrowthPoint {
+  date: number;
+  subscriberCount: number;
+  avgViews: number | null;
+}
+
+export type GrowthPeriod = "week" | "month" | "quarter" | "year";
+
+export interface ChannelGrowth {
+  channelId: number;
+  period: GrowthPeriod;
+  points: GrowthPoint[];
+  subscriberChange: number;
+  subscriberChangePercent: number | null;
+  topPosts: PostViews[];
+}
+
+// Record a channel's subscribers and post views now (also done every few hours)
+export async function snapshotChannel(channelId: number): Promise<ChannelSnapshot> {
+  return invoke("snapshot_channel", { channelId });
+}
+
+export async function getChannelGrowth(channelId: number, period: GrowthPeriod): Promise<ChannelGrowth> {
+  return invoke("get_channel_growth", { channelId, period });
+}
+
+// Contact list commands
+export interface ContactList {
+  id: string;
+  name: string;
+  userIds: number[];
+  createdAt: number;
+}
+
+export async function listContactLists(): Promise<ContactList[]> {
+  return invoke("list_contact_lists");
+}
+
+export async function createContactList(name: string): Promise<ContactList> {
+  return invoke("create_contact_list", { name });
+}
+
+export async function renameContactList(listId: string, name: string): Promise<void> {
+  return invoke("rename_contact_list", { listId, name });
+}
+
+export async function deleteContactList(listId: string): Promise<void> {
+  return invoke("delete_contact_list", { listId });
+}
+
+export async function addToContactList(listId: string, userIds: number[]): Promise<ContactList> {
+  return invoke("add_to_contact_list", { listId, userIds });
+}
+
+export async function removeFromContactList(listId: string, userIds: number[]): Promise<ContactList> {
+  return invoke("remove_from_contact_list", { listId, userIds });
+}
+
+export async function reorderContactList(listId: string, userIds: number[]): Promise<ContactList> {
+  return invoke("reorder_contact_list", { listId, userIds });
+}
+
+// Canned response commands
+export interface CannedResponse {
+  shortcut: string;
+  text: string;
+  variables: string[];
+  createdAt: number;
+  updatedAt: number;
+}
+
+export async function listCannedResponses(): Promise<CannedResponse[]> {
+  return invoke("list_canned_responses");
+}
+
+export async function saveCannedResponse(shortcut: string, text: string): Promise<CannedResponse> {
+  return invoke("save_canned_response", { shortcut, text });
+}
+
+export async function deleteCannedResponse(shortcut: string): Promise<void> {
+  return invoke("delete_canned_response", { shortcut });
+}
+
+export interface RenderedTemplate {
+  text: string;
+  missingVariables: string[];
+}
+
+export async function renderTemplate(
+  templateId: string,
+  chatId: number,
+  variables?: Record<string, string>
+): Promise<RenderedTemplate> {
+  return invoke("render_template", { templateId, chatId, variables });
+}
+
+export async function sendCanned(
+  chatId: number,
+  shortcut: string,
+  variables?: Record<string, string>
+): Promise<Message> {
+  return invoke("send_canned", { chatId, shortcut, variables });
+}
+
+// Pending draft commands
+export interface PendingDraft {
+  id: number;
+  chatId: number;
+  text: string;
+  source: "away" | "rule" | "ai" | "mcp";
+  status: "pending" | "sent" | "rejected";
+  createdAt: number;
+}
+
+export async function getDraftApproval(): Promise<boolean> {
+  return invoke("get_draft_approval");
+}
+
+/** When on, automated replies wait in the pending drafts queue instead of being sent */
+export async function setDraftApproval(enabled: boolean): Promise<void> {
+  return invoke("set_draft_approval", { enabled });
+}
+
+export async function getPendingDrafts(): Promise<PendingDraft[]> {
+  return invoke("get_pending_drafts");
+}
+
+export async function queueDraft(chatId: number, text: string, source?: PendingDraft["source"]): Promise<number> {
+  return invoke("queue_draft", { chatId, text, source });
+}
+
+/** Send a pending draft, optionally with edited text */
+export async function approveDraft(id: number, text?: string): Promise<Message> {
+  return invoke("approve_draft", { id, text });
+}
+
+export async function rejectDraft(id: number): Promise<void> {
+  return invoke("reject_draft", { id });
+}
+
+// Fired with the number of drafts awaiting approval whenever it may have changed
+export function onPendingDraftCount(callback: (count: number) => void): Promise<UnlistenFn> {
+  return listen<number>("drafts://pending-count", (event) => callback(event.payload));
+}
+
+// Away mode commands
+export interface AwayConfig {
+  enabled: boolean;
+  /** Reply text; {name} and {first_name} are filled from the sender */
+  template: string;
+  excludedChatIds: number[];
+  /** Set by the backend when away mode is turned on */
+  startedAt?: number | null;
+}
+
+export interface AwayReply {
+  userId: number;
+  name: string;
+  text: string;
+  repliedAt: number;
+}
+
+export async function getAwayConfig(): Promise<AwayConfig> {
+  return invoke("get_away_config");
+}
+
+export async function setAwayConfig(config: AwayConfig): Promise<AwayConfig> {
+  return invoke("set_away_config", { config });
+}
+
+/** Defaults to replies sent in the current away period */
+export async function getAwayReplies(since?: number): Promise<AwayReply[]> {
+  return invoke("get_away_replies", { since });
+}
+
+// Bot rule commands
+export type BotRuleAction = "forward" | "tag" | "notify" | "extract" | "reply";
+
+export interface BotRule {
+  id: number;
+  name: string;
+  /** "bot": messages from `botId`; "dm": incoming DMs from people */
+  trigger: "bot" | "dm";
+  botId: number;
+  /** Regex over the message text */
+  pattern: string;
+  action: BotRuleAction;
+  /** Chat id for "forward", sender's contact tag for "tag", instruction for "extract", canned shortcut for "reply" */
+  target: string | null;
+  dailyCap: number | null;
+  enabled: boolean;
+  createdAt: number;
+}
+
+export interface BotRuleHit {
+  id: number;
+  ruleId: number;
+  ruleName: string;
+  action: BotRuleAction;
+  chatId: number;
+  messageId: number;
+  text: string;
+  result: string | null;
+  error: string | null;
+  matchedAt: number;
+}
+
+export async function getBotRules(): Promise<BotRule[]> {
+  return invoke("get_bot_rules");
+}
+
+export async function addBotRule(
+  name: string,
+  botId: number,
+  pattern: string,
+  action: BotRuleAction,
+  target?: string,
+  dailyCap?: number
+): Promise<BotRule> {
+  return invoke("add_bot_rule", { name, botId, pattern, action, target, dailyCap });
+}
+
+/** Reply to DMs containing `keyword` with a canned response, at most `dailyCap` times a day */
+export async function addKeywordReplyRule(
+  keyword: string,
+  shortcut: string,
+  dailyCap?: number,
+  name?: string
+): Promise<BotRule> {
+  return invoke("add_keyword_reply_rule", { name, keyword, shortcut, dailyCap });
+}
+
+export async function setBotRuleEnabled(id: number, enabled: boolean): Promise<void> {
+  return invoke("set_bot_rule_enabled", { id, enabled });
+}
+
+export async function deleteBotRule(id: number): Promise<void> {
+  return invoke("delete_bot_rule", { id });
+}
+
+export async function getBotRuleHits(limit?: number): Promise<BotRuleHit[]> {
+  return invoke("get_bot_rule_hits", { limit });
+}
+
+// Fired when a "notify" bot rule matches an incoming message
+export function onBotRuleNotification(callback: (hit: BotRuleHit) => void): Promise<UnlistenFn> {
+  return listen<BotRuleHit>("notify://bot-rule", (event) => callback(event.payload));
+}
+
+// Triage commands
+export type TriageStatus = "pending" | "replied" | "added" | "blocked" | "dismissed";
+
+export interface TriageEntry {
+  userId: number;
+  name: string;
+  preview: string;
+  firstSeenAt: number;
+  lastMessageAt: number;
+  status: TriageStatus;
+}
+
+/** New DMs are queued as they arrive; `refresh` also scans dialogs for ones received while the app was closed */
+export async function getTriageQueue(refresh?: boolean): Promise<TriageEntry[]> {
+  return invoke("get_triage_queue", { refresh });
+}
+
+export async function triageAddContact(
+  userId: number,
+  firstName?: string,
+  lastName?: string
+): Promise<void> {
+  return invoke("triage_add_contact", { userId, firstName, lastName });
+}
+
+export async function triageBlockSender(userId: number): Promise<void> {
+  return invoke("triage_block_sender", { userId });
+}
+
+export async function triageDismiss(userId: number): Promise<void> {
+  return invoke("triage_dismiss", { userId });
+}
+
+// Unread sweep commands
+export type UnreadClassification = "needs_reply" | "fyi" | "noise";
+
+export interface UnreadChat {
+  chatId: number;
+  title: string;
+  chatType: string;
+  unreadCount: number;
+  lastMessage: string | null;
+  lastMessageDate: number | null;
+  classification: UnreadClassification;
+  source: "briefing" | "rules";
+  estimatedSecs: number;
+}
+
+export interface UnreadGroup {
+  classification: UnreadClassification;
+  chats: UnreadChat[];
+  unreadCount: number;
+  estimatedSecs: number;
+}
+
+export interface UnreadReport {
+  groups: UnreadGroup[];
+  totalChats: number;
+  totalUnread: number;
+  estimatedSecs: number;
+  generatedAt: number;
+}
+
+export async function generateUnreadReport(): Promise<UnreadReport> {
+  return invoke("generate_unread_report");
+}
+
+// Mention feed commands
+export interface MentionRecord {
+  id: number;
+  chatId: number;
+  chatTitle: string;
+  messageId: number;
+  kind: "mention" | "reply";
+  senderId: number;
+  senderName: string;
+  text: string;
+  messageDate: number;
+  seen: boolean;
+}
+
+// Mentions are recorded as chat history is fetched, newest first
+export async function getMentions(
+  options: { unseenOnly?: boolean; limit?: number } = {}
+): Promise<MentionRecord[]> {
+  return invoke("get_mentions", {
+    unseenOnly: options.unseenOnly ?? null,
+    limit: options.limit ?? null,
+  });
+}
+
+// Omit ids to mark every mention as seen; returns how many changed
+export async function markMentionsSeen(ids?: number[]): Promise<number> {
+  return invoke("mark_mentions_seen", { ids: ids ?? null });
+}
+
+// Call log commands
+export interface CallRecord {
+  id: number;
+  chatId: number;
+  chatTitle: string;
+  messageId: number;
+  callerId: number;
+  callerName: string;
+  isOutgoing: boolean;
+  video: boolean;
+  missed: boolean;
+  duration: number | null;
+  callDate: number;
+}
+
+// Incoming calls missed in the last `days` days (default 7)
+export async function getMissedCalls(days?: number): Promise<CallRecord[]> {
+  return invoke("get_missed_calls", { days: days ?? null });
+}
+
+export async function getCallLog(days?: number): Promise<CallRecord[]> {
+  return invoke("get_call_log", { days: days ?? null });
+}
+
+// Decision tracking commands
+export interface DecisionRecord {
+  id: number;
+  chatId: number;
+  chatTitle: string;
+  kind: "decision" | "commitment";
+  text: string;
+  owner: string | null;
+  due: string | null;
+  messageId: number | null;
+  senderName: string | null;
+  messageDate: number | null;
+  extractedAt: number;
+}
+
+// Extracts from the last `periodDays` days and returns everything stored for the chat
+export async function extractDecisions(chatId: number, periodDays: number): Promise<DecisionRecord[]> {
+  return invoke("extract_decisions", { chatId, periodDays });
+}
+
+export async function getDecisions(chatId?: number): Promise<DecisionRecord[]> {
+  return invoke("get_decisions", { chatId: chatId ?? null });
+}
+
+export async function deleteDecision(id: number): Promise<void> {
+  return invoke("delete_decision", { id });
+}
+
+// Deep analysis commands
+export interface DeepAnalysisEstimate {
+  chatId: number;
+  messageCount: number;
+  estimatedTokens: number;
+  // Pass to deepAnalysis once the user accepts the estimate; single use
+  confirmationToken: string;
+}
+
+export interface PositionChange {
+  date: string | null;
+  party: string;
+  position: string;
+}
+
+export interface DeepAnalysis {
+  chatId: number;
+  chatTitle: string;
+  overview: string;
+  timeline: PositionChange[];
+  openQuestions: string[];
+  risks: string[];
+  suggestedStrategy: string[];
+  messageCount: number;
+  generatedAt: number;
+}
+
+// Show this to the user before running deepAnalysis, which is expensive
+export async function estimateDeepAnalysis(chatId: number): Promise<DeepAnalysisEstimate> {
+  return invoke("estimate_deep_analysis", { chatId });
+}
+
+// Only call after the user accepts an estimate; the backend rejects runs without its token
+export async function deepAnalysis(chatId: number, confirmationToken: string): Promise<DeepAnalysis> {
+  return invoke("deep_analysis", { chatId, confirmationToken });
+}
+
+// Standup commands
+export interface StandupItem {
+  text: string;
+  chat: string | null;
+}
+
+export interface StandupReport {
+  done: StandupItem[];
+  pending: StandupItem[];
+  days: number;
+  chatCount: number;
+  messageCount: number;
+  generatedAt: number;
+}
+
+// Built from your own messages; with no scopes, all recently active chats are used
+export async function generateStandup(days: number, scopes?: string[]): Promise<StandupReport> {
+  return invoke("generate_standup", { days, scopes: scopes ?? null });
+}
+
+// Meeting prep commands
+export interface MeetingBrief {
+  participants: string[];
+  chatIds: number[];
+  /** Where the conversation left off */
+  summary: string;
+  openItems: string[];
+  talkingPoints: string[];
+  /** Commitments already tracked for these chats */
+  actionItems: DecisionRecord[];
+  reminders: ChatReminder[];
+  messageCount: number;
+  /** Chats that couldn't be read and were left out */
+  failedChats: FailedChat[];
+  generatedAt: number;
+}
+
+// Participants are read through their DMs; a group chat can be given instead or as well
+export async function prepareForMeeting(options: { userIds?: number[]; chatId?: number }): Promise<MeetingBrief> {
+  return invoke("prepare_for_meeting", { userIds: options.userIds ?? null, chatId: options.chatId ?? null });
+}
+
+// Retention commands
+export interface RetentionConfig {
+  briefingHistoryDays: number | null;
+  outreachHistoryDays: number | null;
+  activityLogDays: number | null;
+  triageDays: number | null;
+  llmCacheDays: number | null;
+  archiveDays: number | null;
+}
+
+export interface PurgeStats {
+  briefings: number;
+  outreachQueues: number;
+  activityEvents: number;
+  triageEntries: number;
+  llmCacheEntries: number;
+  archivedMessages: number;
+  /** Mentions, calls, audit excerpts and other rows derived from archived messages */
+  derivedRows: number;
+}
+
+export async function getRetentionConfig(): Promise<RetentionConfig> {
+  return invoke("get_retention_config");
+}
+
+export async function updateRetentionConfig(config: RetentionConfig): Promise<PurgeStats> {
+  return invoke("update_retention_config", { config });
+}
+
+export async function purgeExpiredData(): Promise<PurgeStats> {
+  return invoke("purge_expired_data");
+}
+
+export interface IntegrityReport {
+  databaseOk: boolean;
+  databaseRepaired: boolean;
+  /** The database was beyond repair and replaced with an empty one */
+  databaseReplaced: boolean;
+  sessionOk: boolean;
+  /** New paths of corrupted files that were moved aside */
+  quarantined: string[];
+  problems: string[];
+}
+
+/** Reindex the database, or replace it with a fresh one if it is beyond repair */
+export async function repairDatabase(): Promise<IntegrityReport> {
+  return invoke("repair_database");
+}
+
+/** The last check that moved data aside, until acknowledged */
+export async function getIntegrityNotice(): Promise<IntegrityReport | null> {
+  return invoke("get_integrity_notice");
+}
+
+export async function acknowledgeIntegrityNotice(): Promise<void> {
+  return invoke("acknowledge_integrity_notice");
+}
+
+// Multi-device sync commands (encrypted snapshots in Saved Messages)
+// The password itself stays in the OS keychain and is never returned
+export interface SyncConfig {
+  enabled: boolean;
+  passwordSet: boolean;
+}
+
+export interface SyncConfigUpdate {
+  enabled: boolean;
+  // Same password on every device; null keeps the saved one, "" removes it
+  password: string | null;
+}
+
+export interface SyncPushResult {
+  snapshotId: string;
+  messages: number;
+  contacts: number;
+  scopes: number;
+}
+
+export interface SyncMergeStats {
+  snapshots: number;
+  tagsAdded: number;
+  notesUpdated: number;
+  scopesUpdated: number;
+}
+
+export async function getSyncConfig(): Promise<SyncConfig> {
+  return invoke("get_sync_config");
+}
+
+export async function updateSyncConfig(config: SyncConfigUpdate): Promise<void> {
+  return invoke("update_sync_config", { config });
+}
+
+export async function pushSyncSnapshot(): Promise<SyncPushResult> {
+  return invoke("push_sync_snapshot");
+}
+
+// Merges the newest snapshot from each other device; deletions are not synced
+export async function pullSyncSnapshots(): Promise<SyncMergeStats> {
+  return invoke("pull_sync_snapshots");
+}
+
+// Onboarding commands (the backend tracks setup progress)
+export type OnboardingStepId =
+  | "telegram_credentials"
+  | "logged_in"
+  | "chat_filters"
+  | "llm_configured"
+  | "scope_created";
+
+export interface OnboardingStep {
+  id: OnboardingStepId;
+  label: string;
+  // Live prerequisite check; null for steps that can only be marked complete
+  satisfied: boolean | null;
+  completed: boolean;
+  done: boolean;
+}
+
+export interface OnboardingState {
+  steps: OnboardingStep[];
+  nextStep: OnboardingStepId | null;
+  complete: boolean;
+}
+
+export async function getOnboardingState(): Promise<OnboardingState> {
+  return invoke("get_onboarding_state");
+}
+
+// Marks a step complete or skipped; credentials and login can't be skipped
+export async function completeOnboardingStep(step: OnboardingStepId): Promise<OnboardingState> {
+  return invoke("complete_onboarding_step", { step });
+}
+
+export async function resetOnboarding(): Promise<void> {
+  return invoke("reset_onboarding");
+}
+
+// Focus mode commands
+export interface FocusConfig {
+  manual: boolean;
+  // Unix seconds when manual focus ends; null = until turned off
+  manualUntil: number | null;
+  // Local "HH:MM"; the schedule may wrap past midnight
+  scheduleStart: string | null;
+  scheduleEnd: string | null;
+}
+
+// While active, notifications are suppressed and outreach/scheduled briefings wait
+export interface FocusState extends FocusConfig {
+  active: boolean;
+  reason: "manual" | "schedule" | null;
+}
+
+export async function getFocusState(): Promise<FocusState> {
+  return invoke("get_focus_state");
+}
+
+export async function setFocusState(config: FocusConfig): Promise<FocusState> {
+  return invoke("set_focus_state", { config });
+}
+
+// Notification rule commands (local rules, independent of Telegram mutes)
+export type NotificationMode = "all" | "urgent" | "vip" | "none";
+
+export interface NotificationConfig {
+  enabled: boolean;
+  defaultMode: NotificationMode;
+  // Local "HH:MM"; a start after the end wraps past midnight
+  quietHoursStart: string | null;
+  quietHoursEnd: string | null;
+  vipTag: string;
+}
+
+export interface NotificationRule {
+  chatId: number;
+  mode: NotificationMode;
+  ignoreQuietHours: boolean;
+}
+
+export async function getNotificationConfig(): Promise<NotificationConfig> {
+  return invoke("get_notification_config");
+}
+
+export async function updateNotificationConfig(config: NotificationConfig): Promise<void> {
+  return invoke("update_notification_config", { config });
+}
+
+export async function getNotificationRules(): Promise<NotificationRule[]> {
+  return invoke("get_notification_rules");
 }
 
-export async function invalidateChatCache(): Promise<void> {
-  return invoke("invalidate_chat_cache");
+export async function setNotificationRule(
+  chatId: number,
+  mode: NotificationMode,
+  ignoreQuietHours = false
+): Promise<NotificationRule> {
+  return invoke("set_notification_rule", { chatId, mode, ignoreQuietHours });
 }
 
-// Contact commands
-export interface ContactsResponse {
-  contacts: Contact[];
-  cached: boolean;
-  cacheAge: string | null;
+export async function deleteNotificationRule(chatId: number): Promise<void> {
+  return invoke("delete_notification_rule", { chatId });
 }
 
-export async function getContacts(
-  forceRefresh?: boolean,
-  ttlMinutes?: number
-): Promise<ContactsResponse> {
-  return invoke("get_contacts", { forceRefresh, ttlMinutes });
+// Fired for incoming messages that pass the notification rules
+export function onNotifyMessage(callback: (message: Message) => void): Promise<UnlistenFn> {
+  return listen<Message>("notify://message", (event) => callback(event.payload));
 }
 
-export async function addContactTag(
-  userId: number,
-  tag: string
-): Promise<void> {
-  return invoke("add_contact_tag", { userId, tag });
+// Pipeline commands
+export interface PipelineStage {
+  id: number;
+  name: string;
+  position: number;
+  autoRole: "contacted" | "replied" | null;
 }
 
-export async function removeContactTag(
-  userId: number,
-  tag: string
-): Promise<void> {
-  return invoke("remove_contact_tag", { userId, tag });
+export interface Pipeline {
+  id: string;
+  name: string;
+  stages: PipelineStage[];
+  createdAt: number;
 }
 
-export async function updateContactNotes(
-  userId: number,
-  notes: string
-): Promise<void> {
-  return invoke("update_contact_notes", { userId, notes });
+export interface PipelineEntry {
+  userId: number;
+  stageId: number;
+  updatedAt: number;
 }
 
-// Scope commands
-export async function getFolders(): Promise<Folder[]> {
-  return invoke("get_folders");
+export interface PipelineBoard {
+  pipeline: Pipeline;
+  columns: { stage: PipelineStage; contacts: PipelineEntry[] }[];
 }
 
-export async function saveScope(name: string, config: unknown): Promise<void> {
-  return invoke("save_scope", { name, config });
+export async function listPipelines(): Promise<Pipeline[]> {
+  return invoke("list_pipelines");
 }
 
-export async function loadScope(name: string): Promise<unknown> {
-  return invoke("load_scope", { name });
+export async function createPipeline(name: string, stages: string[]): Promise<Pipeline> {
+  return invoke("create_pipeline", { name, stages });
 }
 
-export async function listScopes(): Promise<string[]> {
-  return invoke("list_scopes");
+export async function deletePipeline(pipelineId: string): Promise<void> {
+  return invoke("delete_pipeline", { pipelineId });
 }
 
-// Outreach commands
-export async function queueOutreachMessages(
-  recipientIds: number[],
-  template: string
-): Promise<string> {
-  return invoke("queue_outreach_messages", { recipientIds, template });
+export async function moveContactToStage(
+  pipelineId: string,
+  userId: number,
+  stageId: number
+): Promise<void> {
+  return invoke("move_contact_to_stage", { pipelineId, userId, stageId });
 }
 
-export async function getOutreachStatus(queueId: string): Promise<unknown> {
-  return invoke("get_outreach_status", { queueId });
+export async function removeContactFromPipeline(pipelineId: string, userId: number): Promise<void> {
+  return invoke("remove_contact_from_pipeline", { pipelineId, userId });
 }
 
-export async function cancelOutreach(queueId: string): Promise<void> {
-  return invoke("cancel_outreach", { queueId });
+export async function getPipelineBoard(
+  pipelineId: string,
+  checkReplies?: boolean
+): Promise<PipelineBoard> {
+  return invoke("get_pipeline_board", { pipelineId, checkReplies });
 }
 
 // Offboard commands
@@ -216,6 +1627,82 @@ export async function removeFromGroup(chatId: number, userId: number): Promise<v
   return invoke("remove_from_group", { chatId, userId });
 }
 
+// Event RSVP commands
+export interface RsvpPost {
+  chatId: number;
+  chatTitle: string;
+  messageId: number;
+}
+
+export interface RsvpEvent {
+  id: number;
+  title: string;
+  text: string;
+  createdAt: number;
+  posts: RsvpPost[];
+}
+
+export interface RsvpResponse {
+  eventId: number;
+  userId: number;
+  name: string;
+  chatId: number;
+  status: "going" | "not_going" | "maybe";
+  source: "reply" | "reaction";
+  /** The reply text or reaction emoji */
+  response: string;
+  respondedAt: number;
+}
+
+/** Post an announcement to groups; replies and reactions to it become RSVPs */
+export async function postEvent(title: string, text: string, chatIds: number[]): Promise<RsvpEvent> {
+  return invoke("post_event", { title, text, chatIds });
+}
+
+export async function getEvents(): Promise<RsvpEvent[]> {
+  return invoke("get_events");
+}
+
+/** Reactions are re-read from Telegram on every call */
+export async function getEventRsvps(eventId: number): Promise<RsvpResponse[]> {
+  return invoke("get_event_rsvps", { eventId });
+}
+
+export async function deleteEvent(eventId: number): Promise<void> {
+  return invoke("delete_event", { eventId });
+}
+
+// Welcome kit commands
+/** Texts may use {name}, {first_name} and {group} */
+export interface WelcomeKit {
+  chatId: number;
+  rulesMessage: string;
+  resources: string[];
+  introDm: string | null;
+}
+
+export interface WelcomeResult {
+  chatId: number;
+  userId: number;
+  sent: ("rules" | "resources" | "intro_dm")[];
+}
+
+export async function getWelcomeKits(): Promise<WelcomeKit[]> {
+  return invoke("get_welcome_kits");
+}
+
+export async function saveWelcomeKit(kit: WelcomeKit): Promise<void> {
+  return invoke("save_welcome_kit", { kit });
+}
+
+export async function deleteWelcomeKit(chatId: number): Promise<void> {
+  return invoke("delete_welcome_kit", { chatId });
+}
+
+export async function sendWelcomeKit(chatId: number, userId: number): Promise<WelcomeResult> {
+  return invoke("send_welcome_kit", { chatId, userId });
+}
+
 // AI commands
 
 export interface ChatContext {
@@ -228,12 +1715,15 @@ export interface ChatContext {
     text: string;
     date: number;
     is_outgoing: boolean;
+    // MessageContent type ("text", "storyMention", ...)
+    kind?: string;
   }[];
   unread_count?: number;
   last_message_is_outgoing?: boolean;
   has_unanswered_question?: boolean;
   hours_since_last_activity?: number;
   is_private_chat?: boolean;
+  has_missed_call?: boolean;
 }
 
 export interface ChatSummaryContext {
@@ -267,6 +1757,12 @@ export interface ResponseItem {
   priority: "urgent" | "needs_reply";
   summary: string;
   suggested_reply: string | null;
+  injection_warning?: string | null;
+  chat_note?: string | null;
+  message_count?: number;
+  reading_time_secs?: number;
+  category?: string | null;
+  last_message_id?: number | null;
 }
 
 export interface FYIItemData {
@@ -279,6 +1775,11 @@ export interface FYIItemData {
   last_message_date: string | null;
   priority: "fyi";
   summary: string;
+  injection_warning?: string | null;
+  chat_note?: string | null;
+  message_count?: number;
+  reading_time_secs?: number;
+  category?: string | null;
 }
 
 export interface BriefingStats {
@@ -294,6 +1795,24 @@ export interface BriefingV2Response {
   generated_at: string;
   cached: boolean;
   cache_age?: string;
+  briefing_id?: string | null;
+  failed_chats?: FailedChat[];
+  sections?: BriefingSection[];
+}
+
+// A custom category's chats; they also appear in needs_response or fyi_summaries
+export interface BriefingSection {
+  name: string;
+  description: string;
+  chat_ids: number[];
+}
+
+// Chat the model could not classify, even after a repair attempt
+export interface FailedChat {
+  chat_id: number;
+  chat_name: string;
+  unread_count: number;
+  reason: string;
 }
 
 export interface ChatSummaryResult {
@@ -307,6 +1826,15 @@ export interface ChatSummaryResult {
   needs_response: boolean;
   message_count: number;
   last_message_date: number;
+  topics?: TopicSummary[];
+}
+
+// One thread of conversation in a busy group
+export interface TopicSummary {
+  title: string;
+  summary: string;
+  participants: string[];
+  message_count: number;
 }
 
 export interface BatchSummaryResponse {
@@ -316,9 +1844,18 @@ export interface BatchSummaryResponse {
   cached: boolean;
 }
 
+// "options" returns three short alternative replies instead of one draft
+export type DraftMode = "single" | "options";
+
+export interface ReplyOption {
+  intent: string;
+  text: string;
+}
+
 export interface DraftResponse {
   draft: string;
   chat_id: number;
+  options?: ReplyOption[];
 }
 
 export async function generateBriefingV2(
@@ -329,6 +1866,52 @@ export async function generateBriefingV2(
   return invoke("generate_briefing_v2", { chats, forceRefresh, ttlMinutes });
 }
 
+// One chat's result from generateBriefingV2Stream
+export type BriefingItem =
+  | { kind: "needs_response"; data: ResponseItem }
+  | { kind: "fyi"; data: FYIItemData }
+  | { kind: "failed"; data: FailedChat };
+
+export interface BriefingItemEvent {
+  request_id: string;
+  position: number;
+  completed: number;
+  total: number;
+  item: BriefingItem;
+}
+
+export interface BriefingCompleteEvent {
+  request_id: string;
+  briefing: BriefingV2Response;
+}
+
+// Same result as generateBriefingV2; per-chat items arrive via onBriefingItem while it runs
+export async function generateBriefingV2Stream(
+  requestId: string,
+  chats: ChatContext[],
+  forceRefresh: boolean,
+  ttlMinutes: number
+): Promise<BriefingV2Response> {
+  return invoke("generate_briefing_v2_stream", { requestId, chats, forceRefresh, ttlMinutes });
+}
+
+// FYI items for reactions to the user's stories over the last day (Telegram posts no message for them)
+export async function getStoryReactionFyis(): Promise<FYIItemData[]> {
+  return invoke("get_story_reaction_fyis");
+}
+
+export function onBriefingItem(
+  callback: (event: BriefingItemEvent) => void
+): Promise<UnlistenFn> {
+  return listen<BriefingItemEvent>("ai://briefing-item", (event) => callback(event.payload));
+}
+
+export function onBriefingComplete(
+  callback: (event: BriefingCompleteEvent) => void
+): Promise<UnlistenFn> {
+  return listen<BriefingCompleteEvent>("ai://briefing-complete", (event) => callback(event.payload));
+}
+
 export async function generateBatchSummaries(
   chats: ChatSummaryContext[],
   regenerate: boolean,
@@ -337,21 +1920,150 @@ export async function generateBatchSummaries(
   return invoke("generate_batch_summaries", { chats, regenerate, ttlMinutes });
 }
 
+// Summarize one chat; messages are fetched by the backend. Pass sinceMessageId or
+// sinceDate (unix seconds) to summarize only newer messages.
+export async function summarizeChat(
+  chatId: number,
+  options: { sinceMessageId?: number; sinceDate?: number } = {}
+): Promise<ChatSummaryResult> {
+  return invoke("summarize_chat", {
+    chatId,
+    sinceMessageId: options.sinceMessageId ?? null,
+    sinceDate: options.sinceDate ?? null,
+  });
+}
+
+// useContactNotes adds your notes and tags for the contact to the prompt (DMs only)
 export async function generateDraft(
   chatId: number,
   chatTitle: string,
-  messages: DraftMessage[]
+  messages: DraftMessage[],
+  options: { mode?: DraftMode; useContactNotes?: boolean } = {}
 ): Promise<DraftResponse> {
-  return invoke("generate_draft", { chatId, chatTitle, messages });
+  return invoke("generate_draft", {
+    chatId,
+    chatTitle,
+    messages,
+    mode: options.mode ?? null,
+    useContactNotes: options.useContactNotes ?? null,
+  });
+}
+
+export interface MessageIntentAnalysis {
+  chat_id: number;
+  message_id: number;
+  sender_name: string;
+  tldr: string;
+  explicit_asks: string[];
+  implicit_expectations: string[];
+  suggested_next_steps: string[];
+  urgency: "low" | "medium" | "high";
+}
+
+// TL;DR of what the sender of a message wants and what to do about it
+export async function analyzeMessageIntent(chatId: number, messageId: number): Promise<MessageIntentAnalysis> {
+  return invoke("analyze_message_intent", { chatId, messageId });
+}
+
+export type BriefingExportFormat = "markdown" | "pdf";
+
+export async function exportBriefing(
+  briefingId: string | null,
+  format: BriefingExportFormat,
+  path: string
+): Promise<string> {
+  return invoke("export_briefing", { briefingId, format, path });
+}
+
+export async function syncSummariesToFolder(path: string | null): Promise<void> {
+  return invoke("sync_summaries_to_folder", { path });
+}
+
+export async function getSummarySyncFolder(): Promise<string | null> {
+  return invoke("get_summary_sync_folder");
+}
+
+// Writes a zip of scrubbed diagnostics; returns the written file path
+export async function createDebugBundle(path: string): Promise<string> {
+  return invoke("create_debug_bundle", { path });
+}
+
+export interface ConfigImportStats {
+  scopes: number;
+  cannedResponses: number;
+  notificationRules: number;
+  secretsRestored: boolean;
+}
+
+export interface DatasetExport {
+  path: string;
+  chats: number;
+  messages: number;
+}
+
+/** Write a saved scope's archived messages as JSON Lines or CSV; `path` may be a file or a folder */
+export async function exportScopeMessages(
+  scope: string,
+  format: "jsonl" | "csv",
+  path: string
+): Promise<DatasetExport> {
+  return invoke("export_scope_messages", { scope, format, path });
+}
+
+// API keys and the sync password are included (encrypted) only when a password is given
+export async function exportConfig(path: string, password?: string): Promise<string> {
+  return invoke("export_config", { path, password: password ?? null });
+}
+
+// Without the export password, the local API keys and sync password are kept
+export async function importConfig(path: string, password?: string): Promise<ConfigImportStats> {
+  return invoke("import_config", { path, password: password ?? null });
+}
+
+export interface BriefingDeliveryConfig {
+  enabled: boolean;
+  chatId: number | null; // null = Saved Messages
+}
+
+export async function getBriefingDelivery(): Promise<BriefingDeliveryConfig> {
+  return invoke("get_briefing_delivery");
+}
+
+export async function updateBriefingDelivery(config: BriefingDeliveryConfig): Promise<void> {
+  return invoke("update_briefing_delivery", { config });
+}
+
+export async function deliverBriefing(briefingId: string | null): Promise<number> {
+  return invoke("deliver_briefing", { briefingId });
 }
 
 // LLM Config types and commands
 
 export interface LLMConfig {
-  provider: "openai" | "ollama";
+  // "mock" serves canned responses from the directory in base_url (tests only)
+  provider: "openai" | "ollama" | "local_openai" | "mock";
+  base_url: string;
+  api_key: string | null;
+  model: string;
+  privacy_mode?: boolean;
+  fallback?: LLMFallback | null;
+  // Request timeout; null = 30s for OpenAI, 120s for local providers
+  timeout_secs?: number | null;
+  // Embedding model; null = text-embedding-3-small (OpenAI), nomic-embed-text (Ollama)
+  embedding_model?: string | null;
+  // Ollama model residency: "10m", "1h", "-1" = keep loaded, "0" = unload after each call; null = 5m
+  keep_alive?: string | null;
+  // Further Ollama base URLs; calls are spread round-robin across these and base_url
+  extra_hosts?: string[];
+}
+
+// Provider tried when the primary one is unreachable
+export interface LLMFallback {
+  provider: LLMConfig["provider"];
   base_url: string;
   api_key: string | null;
   model: string;
+  timeout_secs?: number | null;
 }
 
 export interface OllamaModel {
@@ -372,10 +2084,233 @@ export async function listOllamaModels(baseUrl?: string): Promise<OllamaModel[]>
   return invoke("list_ollama_models_cmd", { baseUrl });
 }
 
+export interface OllamaPullProgress {
+  model: string;
+  status: string;
+  digest: string | null;
+  total: number | null;
+  completed: number | null;
+}
+
+// Resolves when the download finishes; progress arrives via onOllamaPullProgress
+export async function pullOllamaModel(name: string, baseUrl?: string): Promise<void> {
+  return invoke("pull_ollama_model", { name, baseUrl });
+}
+
+export async function deleteOllamaModel(name: string, baseUrl?: string): Promise<void> {
+  return invoke("delete_ollama_model", { name, baseUrl });
+}
+
+export function onOllamaPullProgress(
+  callback: (progress: OllamaPullProgress) => void
+): Promise<UnlistenFn> {
+  return listen<OllamaPullProgress>("ollama://pull-progress", (event) => callback(event.payload));
+}
+
+// Models from an OpenAI-compatible local server (LM Studio, llama-server) via /v1/models
+export async function listLocalModels(baseUrl?: string, apiKey?: string | null): Promise<OllamaModel[]> {
+  return invoke("list_local_models_cmd", { baseUrl, apiKey });
+}
+
 export async function testLLMConnection(config: LLMConfig): Promise<string> {
   return invoke("test_llm_connection", { config });
 }
 
+export interface LLMPing {
+  reachable: boolean;
+  latency_ms: number;
+  model_available: boolean | null;
+  error: string | null;
+}
+
+export async function pingLLM(): Promise<LLMPing> {
+  return invoke("ping_llm");
+}
+
+export interface HostPing extends LLMPing {
+  base_url: string;
+}
+
+export async function checkLLMHosts(): Promise<HostPing[]> {
+  return invoke("check_llm_hosts");
+}
+
+/** Preload the Ollama model; resolves to the load time in ms, or null for other providers */
+export async function warmUpModel(): Promise<number | null> {
+  return invoke("warm_up_model");
+}
+
+/** Free the Ollama model's memory now; resolves to false for other providers */
+export async function unloadModel(): Promise<boolean> {
+  return invoke("unload_model");
+}
+
+export interface AICallRecord {
+  id: number;
+  provider: string;
+  model: string;
+  // Request messages as JSON, after redaction
+  prompt: string;
+  response: string | null;
+  error: string | null;
+  latencyMs: number;
+  promptTokens: number | null;
+  completionTokens: number | null;
+  createdAt: number;
+}
+
+export async function getAICallLog(limit?: number): Promise<AICallRecord[]> {
+  return invoke("get_ai_call_log", { limit });
+}
+
+export async function getAICallLogEnabled(): Promise<boolean> {
+  return invoke("get_ai_call_log_enabled");
+}
+
+/** Turning logging off also deletes the logged calls */
+export async function setAICallLogEnabled(enabled: boolean): Promise<void> {
+  return invoke("set_ai_call_log_enabled", { enabled });
+}
+
+export async function getLocalOnlyAI(): Promise<boolean> {
+  return invoke("get_local_only_ai");
+}
+
+/** Refused while the current provider or its fallback isn't on localhost */
+export async function setLocalOnlyAI(enabled: boolean): Promise<void> {
+  return invoke("set_local_only_ai", { enabled });
+}
+
+export async function clearAICallLog(): Promise<number> {
+  return invoke("clear_ai_call_log");
+}
+
+export interface EvalReport {
+  total: number;
+  correct: number;
+  accuracy: number;
+  failed: number;
+  perPriority: {
+    priority: string;
+    expected: number;
+    predicted: number;
+    correct: number;
+    precision: number | null;
+    recall: number | null;
+  }[];
+  mismatches: { name: string; chatId: number; expected: string; actual: string }[];
+}
+
+/** Dev tool (debug builds only): score the classifier against a fixture file (see src-tauri/fixtures) */
+export async function runBriefingEval(fixturePath: string): Promise<EvalReport> {
+  return invoke("run_briefing_eval", { fixturePath });
+}
+
 export async function isLLMConfigured(): Promise<boolean> {
   return invoke("is_llm_configured");
 }
+
+export interface RedactionRule {
+  name: string;
+  pattern: string;
+  replacement: string;
+}
+
+export interface SanitizationConfig {
+  filterInjections: boolean;
+  redactionRules: RedactionRule[];
+  openaiMaxLength: number;
+  ollamaMaxLength: number;
+}
+
+// Briefing feedback
+
+/** Tell future briefings which priority ("urgent", "needs_reply" or "fyi") a chat should have */
+export async function correctBriefingItem(chatId: number, correctPriority: string): Promise<void> {
+  return invoke("correct_briefing_item", { chatId, correctPriority });
+}
+
+export async function clearBriefingCorrection(chatId: number): Promise<void> {
+  return invoke("clear_briefing_correction", { chatId });
+}
+
+// Briefing pins: chats always given one priority, without asking the model
+export interface BriefingPin {
+  chatId: number;
+  priority: "urgent" | "needs_reply" | "fyi";
+  pinnedAt: number;
+}
+
+export async function getBriefingPins(): Promise<BriefingPin[]> {
+  return invoke("get_briefing_pins");
+}
+
+/** Pin a chat to a priority, or pass null to unpin it */
+export async function setBriefingPin(chatId: number, priority: BriefingPin["priority"] | null): Promise<void> {
+  return invoke("set_briefing_pin", { chatId, priority });
+}
+
+// Custom briefing categories
+export interface BriefingCategory {
+  id: number;
+  name: string;
+  description: string;
+  position: number;
+  createdAt: number;
+}
+
+export async function getBriefingCategories(): Promise<BriefingCategory[]> {
+  return invoke("get_briefing_categories");
+}
+
+export async function saveBriefingCategory(
+  id: number | null,
+  name: string,
+  description: string
+): Promise<number> {
+  return invoke("save_briefing_category", { id, name, description });
+}
+
+export async function reorderBriefingCategories(ids: number[]): Promise<void> {
+  return invoke("reorder_briefing_categories", { ids });
+}
+
+export async function deleteBriefingCategory(id: number): Promise<void> {
+  return invoke("delete_briefing_category", { id });
+}
+
+export async function getSanitizationConfig(): Promise<SanitizationConfig> {
+  return invoke("get_sanitization_config");
+}
+
+export async function updateSanitizationConfig(config: SanitizationConfig): Promise<void> {
+  return invoke("update_sanitization_config", { config });
+}
+
+export async function getSanitizationPresets(): Promise<RedactionRule[]> {
+  return invoke("get_sanitization_presets");
+}
+
+// Drops cached responses for low-temperature prompts; returns the number removed
+export async function clearLLMCache(): Promise<number> {
+  return invoke("clear_llm_cache");
+}
+
+export interface InjectionAuditEntry {
+  id: number;
+  chatId: number;
+  chatTitle: string;
+  senderName: string;
+  excerpt: string;
+  signals: string[];
+  score: number;
+  detectedAt: number;
+}
+
+export async function getInjectionAudit(limit?: number): Promise<InjectionAuditEntry[]> {
+  return invoke("get_injection_audit", { limit });
+}
+
+export async function clearInjectionAudit(): Promise<void> {
+  return invoke("clear_injection_audit");
+}
@@ -1,6 +1,58 @@
 import { type ClassValue, clsx } from "clsx";
 import { twMerge } from "tailwind-merge";
+import type { MessageContent } from "@/types/telegram";
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs));
 }
+
+// One-line plain-text rendering of a message (mirrors MessageContent::describe in the backend)
+export function describeMessageContent(content: MessageContent): string {
+  const withCaption = (label: string, caption?: string | null) =>
+    caption ? `[${label}] ${caption}` : `[${label}]`;
+  switch (content.type) {
+    case "text":
+      return content.text;
+    case "photo":
+      return withCaption("Photo", content.caption);
+    case "video":
+      return withCaption("Video", content.caption);
+    case "document":
+      return withCaption(`Document: ${content.fileName}`, content.caption);
+    case "voice":
+      return `[Voice message, ${content.duration}s]`;
+    case "sticker":
+      return content.emoji ? `[Sticker ${content.emoji}]` : "[Sticker]";
+    case "storyMention":
+      return "[Mentioned you in a story]";
+    case "story":
+      return "[Shared a story]";
+    case "memberJoined":
+      if (content.userIds.length === 0) return "[Joined the chat]";
+      return content.userIds.length === 1 ? "[Added a member]" : `[Added ${content.userIds.length} members]`;
+    case "memberLeft":
+      return "[Member left]";
+    case "titleChanged":
+      return `[Changed the chat title to "${content.title}"]`;
+    case "messagePinned":
+      return "[Pinned a message]";
+    case "call": {
+      const kind = content.video ? "video call" : "call";
+      if (content.missed) return `[Missed ${kind}]`;
+      return content.duration != null ? `[Ended ${kind}, ${content.duration}s]` : `[Cancelled ${kind}]`;
+    }
+    case "groupCall":
+      return content.duration != null ? `[Voice chat ended, ${content.duration}s]` : "[Started a voice chat]";
+    case "premiumGift":
+      return `[Gifted Telegram Premium for ${content.months} ${content.months === 1 ? "month" : "months"}]`;
+    case "starsGift":
+      return `[Gifted ${content.stars} Telegram Stars]`;
+    default:
+      return "[Media]";
+  }
+}
+
+/** Short label for an estimated reading time, e.g. "<1 min read" or "4 min read" */
+export function formatReadingTime(secs: number): string {
+  return secs < 60 ? "<1 min read" : `${Math.round(secs / 60)} min read`;
+}
@@ -0,0 +1,69 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { useBriefingStore } from "../briefingStore";
+import type { BriefingV2Response, ResponseItem } from "@/lib/tauri";
+
+vi.mock("@tauri-apps/api/core");
+
+function responseItem(chatId: number, category: string | null = null): ResponseItem {
+  return {
+    id: chatId,
+    chat_id: chatId,
+    chat_name: `Chat ${chatId}`,
+    chat_type: "dm",
+    unread_count: 1,
+    last_message: "Are we still on?",
+    last_message_date: null,
+    priority: "needs_reply",
+    summary: "",
+    suggested_reply: null,
+    category,
+  };
+}
+
+function briefing(): BriefingV2Response {
+  return {
+    needs_response: [responseItem(1, "Investors"), responseItem(2, "Investors"), responseItem(3, "Hiring")],
+    fyi_summaries: [],
+    stats: { needs_response_count: 3, fyi_count: 0, total_unread: 3 },
+    generated_at: "2026-01-01T09:00:00Z",
+    cached: false,
+    sections: [
+      { name: "Investors", description: "", chat_ids: [1, 2] },
+      { name: "Hiring", description: "", chat_ids: [3] },
+    ],
+  };
+}
+
+describe("briefingStore", () => {
+  beforeEach(() => {
+    useBriefingStore.getState().clear();
+  });
+
+  describe("removeItem", () => {
+    it("removes the chat from needs_response and its section", () => {
+      useBriefingStore.getState().setData(briefing());
+      useBriefingStore.getState().removeItem(1);
+
+      const data = useBriefingStore.getState().data!;
+      expect(data.needs_response.map((item) => item.chat_id)).toEqual([2, 3]);
+      expect(data.sections).toEqual([
+        { name: "Investors", description: "", chat_ids: [2] },
+        { name: "Hiring", description: "", chat_ids: [3] },
+      ]);
+    });
+
+    it("drops sections that become empty", () => {
+      useBriefingStore.getState().setData(briefing());
+      useBriefingStore.getState().removeItem(3);
+
+      expect(useBriefingStore.getState().data!.sections!.map((s) => s.name)).toEqual(["Investors"]);
+    });
+
+    it("keeps briefings without sections as they are", () => {
+      useBriefingStore.getState().setData({ ...briefing(), sections: undefined });
+      useBriefingStore.getState().removeItem(1);
+
+      expect(useBriefingStore.getState().data!.sections).toBeUndefined();
+    });
+  });
+});
@@ -0,0 +1,80 @@
+import { describe, it, expect, vi, beforeEach } from "vitest";
+import { invoke } from "@tauri-apps/api/core";
+import { useScopeStore } from "../scopeStore";
+import type { RawScopeProfile } from "@/types/scope";
+
+vi.mock("@tauri-apps/api/core");
+
+const mockInvoke = vi.mocked(invoke);
+
+const rawProfile: RawScopeProfile = {
+  id: "scope-1",
+  name: "Investors",
+  config: {
+    folder_ids: [3],
+    chat_types: ["private"],
+    excluded_chat_ids: [10],
+    included_chat_ids: [20],
+    contact_list_ids: ["list-1"],
+  },
+  is_default: false,
+  created_at: 1_700_000_000,
+  updated_at: 1_700_000_100,
+};
+
+describe("scopeStore", () => {
+  beforeEach(() => {
+    useScopeStore.getState().reset();
+    vi.clearAllMocks();
+  });
+
+  it("loads profiles from the backend's snake_case shape", async () => {
+    mockInvoke.mockResolvedValueOnce(["Investors"]).mockResolvedValueOnce(rawProfile);
+
+    await useScopeStore.getState().loadProfiles();
+
+    const [profile] = useScopeStore.getState().profiles;
+    expect(profile).toMatchObject({
+      id: "scope-1",
+      folderIds: [3],
+      chatTypes: ["private"],
+      excludedChatIds: [10],
+      includedChatIds: [20],
+      contactListIds: ["list-1"],
+      createdAt: 1_700_000_000_000,
+    });
+  });
+
+  it("keeps contact lists in the current config of a selected profile", async () => {
+    mockInvoke.mockResolvedValueOnce(["Investors"]).mockResolvedValueOnce(rawProfile);
+    await useScopeStore.getState().loadProfiles();
+
+    useScopeStore.getState().selectProfile(useScopeStore.getState().profiles[0]);
+
+    expect(useScopeStore.getState().getCurrentConfig().contactListIds).toEqual(["list-1"]);
+  });
+
+  it("saves the config with the keys the backend expects", async () => {
+    mockInvoke.mockResolvedValueOnce(undefined);
+    useScopeStore.getState().setCustomConfig({
+      folderIds: [1],
+      chatTypes: ["group"],
+      excludedChatIds: [],
+      includedChatIds: [5],
+      contactListIds: ["list-2"],
+    });
+
+    await useScopeStore.getState().saveProfile("Team");
+
+    expect(mockInvoke).toHaveBeenCalledWith("save_scope", {
+      name: "Team",
+      config: {
+        folder_ids: [1],
+        chat_types: ["group"],
+        excluded_chat_ids: [],
+        included_chat_ids: [5],
+        contact_list_ids: ["list-2"],
+      },
+    });
+  });
+});
@@ -148,6 +148,11 @@ export const useAuthStore = create<AuthStore>((set) => ({
       // Reset onboarding so new account sees filter options
       // Also reset chat filters since folder IDs are account-specific
       useSettingsStore.getState().resetOnboarding();
+      try {
+        await tauri.resetOnboarding();
+      } catch (e) {
+        console.error("Failed to reset onboarding:", e);
+      }
       useSettingsStore.getState().resetChatFilters();
 
       set({
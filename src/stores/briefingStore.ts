@@ -1,8 +1,9 @@
 import { create } from "zustand";
 import * as tauri from "@/lib/tauri";
-import type { ChatFilters, FYIItemData, BriefingV2Response } from "@/lib/tauri";
+import type { ChatFilters, FYIItemData, BriefingV2Response, ResponseItem, FailedChat } from "@/lib/tauri";
 import { useChatStore, DEFAULT_CHAT_LIMIT } from "@/stores/chatStore";
-import type { Chat } from "@/types/telegram";
+import { describeMessageContent } from "@/lib/utils";
+import type { Chat, Message } from "@/types/telegram";
 
 // Large groups (500+ members) are auto-classified as FYI to save API calls
 const LARGE_GROUP_THRESHOLD = 500;
@@ -14,6 +15,15 @@ function detectQuestion(msgs: { isOutgoing: boolean; content: { type: string; te
          (lastNonOutgoing.content.text?.trim().endsWith("?") ?? false);
 }
 
+// An incoming call was missed after the user's last outgoing message
+function detectMissedCall(msgs: Message[]): boolean {
+  for (const m of [...msgs].reverse()) {
+    if (m.isOutgoing) return false;
+    if (m.content.type === "call" && m.content.missed) return true;
+  }
+  return false;
+}
+
 function computeHoursSince(msgs: { date: number }[]): number {
   if (msgs.length === 0) return 999;
   const lastDate = msgs[msgs.length - 1].date;
@@ -77,6 +87,13 @@ export const useBriefingStore = create<BriefingStore>((set, get) => ({
           needs_response: state.data.needs_response.filter(
             (item) => item.chat_id !== chatId
           ),
+          // Category sections list their chats too; drop sections left empty
+          sections: state.data.sections
+            ?.map((section) => ({
+              ...section,
+              chat_ids: section.chat_ids.filter((id) => id !== chatId),
+            }))
+            .filter((section) => section.chat_ids.length > 0),
         },
       };
     });
@@ -112,14 +129,21 @@ export const useBriefingStore = create<BriefingStore>((set, get) => ({
         // Stale check: a newer load was started (force refresh while we were loading)
         if (thisGeneration !== loadGeneration) return;
 
+        // Story reactions don't arrive as messages, so they are listed as FYI directly
+        const storyFYIs = await tauri.getStoryReactionFyis().catch((e) => {
+          console.warn("Failed to load story reactions:", e);
+          return [] as FYIItemData[];
+        });
+        if (thisGeneration !== loadGeneration) return;
+
         const unreadChats = chats.filter((c: Chat) => c.unreadCount > 0);
 
         if (unreadChats.length === 0) {
           set({
             data: {
               needs_response: [],
-              fyi_summaries: [],
-              stats: { needs_response_count: 0, fyi_count: 0, total_unread: 0 },
+              fyi_summaries: storyFYIs,
+              stats: { needs_response_count: 0, fyi_count: storyFYIs.length, total_unread: 0 },
               generated_at: new Date().toISOString(),
               cached: false,
             },
@@ -144,11 +168,13 @@ export const useBriefingStore = create<BriefingStore>((set, get) => ({
           chat_name: chat.title,
           chat_type: chat.type === "channel" ? "channel" : "group",
           unread_count: chat.unreadCount,
-          last_message: chat.lastMessage?.content.type === "text" ? chat.lastMessage.content.text : null,
+          last_message: chat.lastMessage ? describeMessageContent(chat.lastMessage.content) : null,
           last_message_date: chat.lastMessage ? new Date(chat.lastMessage.date * 1000).toISOString() : null,
           priority: "fyi" as const,
           summary: `${chat.unreadCount} new messages in large group`,
+          chat_note: chat.note ?? null,
         }));
+        const autoFYIs = [...largeGroupFYIs, ...storyFYIs];
 
         // Batch-fetch messages for small chats
         const batchRequests = smallChats.map((chat: Chat) => ({
@@ -172,15 +198,17 @@ export const useBriefingStore = create<BriefingStore>((set, get) => ({
               messages: messages.map((m) => ({
                 id: Number(m.id),
                 sender_name: m.senderName,
-                text: m.content.type === "text" ? m.content.text : "[Media]",
+                text: describeMessageContent(m.content),
                 date: m.date,
                 is_outgoing: m.isOutgoing,
+                kind: m.content.type,
               })),
               unread_count: chat.unreadCount,
               last_message_is_outgoing: messages.length > 0 && messages[messages.length - 1].isOutgoing,
               has_unanswered_question: detectQuestion(messages),
               hours_since_last_activity: computeHoursSince(messages),
               is_private_chat: chat.type === "private",
+              has_missed_call: detectMissedCall(messages),
             };
           });
 
@@ -189,8 +217,8 @@ export const useBriefingStore = create<BriefingStore>((set, get) => ({
           set({
             data: {
               needs_response: [],
-              fyi_summaries: largeGroupFYIs,
-              stats: { needs_response_count: 0, fyi_count: largeGroupFYIs.length, total_unread: totalUnread },
+              fyi_summaries: autoFYIs,
+              stats: { needs_response_count: 0, fyi_count: autoFYIs.length, total_unread: totalUnread },
               generated_at: new Date().toISOString(),
               cached: false,
             },
@@ -201,18 +229,50 @@ export const useBriefingStore = create<BriefingStore>((set, get) => ({
           return;
         }
 
-        // Call AI
-        const result: BriefingV2Response = await tauri.generateBriefingV2(
-          chatContexts,
-          force,
-          briefingTTLMinutes
-        );
+        // Call AI, rendering each chat's result as soon as it arrives
+        const requestId = `briefing-${thisGeneration}-${Date.now()}`;
+        const partialNeeds: ResponseItem[] = [];
+        const partialFYIs: FYIItemData[] = [];
+        const partialFailed: FailedChat[] = [];
+        const unlisten = await tauri.onBriefingItem((event) => {
+          if (event.request_id !== requestId || thisGeneration !== loadGeneration) return;
+          const { item } = event;
+          if (item.kind === "needs_response") partialNeeds.push(item.data);
+          else if (item.kind === "fyi") partialFYIs.push(item.data);
+          else partialFailed.push(item.data);
+
+          const fyis = [...partialFYIs, ...autoFYIs];
+          const totalUnread = [...partialNeeds, ...fyis, ...partialFailed]
+            .reduce((sum, entry) => sum + entry.unread_count, 0);
+          set({
+            data: {
+              needs_response: [...partialNeeds],
+              fyi_summaries: fyis,
+              stats: { needs_response_count: partialNeeds.length, fyi_count: fyis.length, total_unread: totalUnread },
+              generated_at: new Date().toISOString(),
+              cached: false,
+              failed_chats: [...partialFailed],
+            },
+          });
+        });
+
+        let result: BriefingV2Response;
+        try {
+          result = await tauri.generateBriefingV2Stream(
+            requestId,
+            chatContexts,
+            force,
+            briefingTTLMinutes
+          );
+        } finally {
+          unlisten();
+        }
 
         // Stale check after AI call (the slowest part)
         if (thisGeneration !== loadGeneration) return;
 
-        // Merge large group FYIs with AI-generated FYIs
-        const mergedFYIs = [...result.fyi_summaries, ...largeGroupFYIs];
+        // Merge large group and story reaction FYIs with AI-generated FYIs
+        const mergedFYIs = [...result.fyi_summaries, ...autoFYIs];
         const largeGroupUnreadTotal = largeGroupFYIs.reduce((sum, item) => sum + item.unread_count, 0);
 
         set({
@@ -256,6 +256,8 @@ export const useChatStore = create<ChatStore>((set, get) => ({
   addMessage: (message) => {
     set((state) => {
       const existing = state.messages[message.chatId] || [];
+      // Messages sent from the app also arrive as updates
+      if (existing.some((m) => m.id === message.id)) return state;
       return {
         messages: {
           ...state.messages,
@@ -267,7 +269,19 @@ export const useChatStore = create<ChatStore>((set, get) => ({
 
   updateChat: (chat) => {
     set((state) => ({
-      chats: state.chats.map((c) => (c.id === chat.id ? chat : c)),
+      // Keep what the app adds on top of Telegram's chat (nicknames, stars, notes)
+      chats: state.chats.map((c) =>
+        c.id === chat.id
+          ? {
+              ...chat,
+              title: c.originalTitle ? c.title : chat.title,
+              photo: c.photo,
+              originalTitle: c.originalTitle,
+              isStarred: c.isStarred,
+              note: c.note,
+            }
+          : c
+      ),
     }));
   },
 
@@ -1,6 +1,6 @@
 import { create } from "zustand";
 import type { Folder, ChatType } from "@/types/telegram";
-import type { ScopeProfile, ScopeConfig, ActiveScope } from "@/types/scope";
+import type { ScopeProfile, ScopeConfig, ActiveScope, RawScopeConfig, RawScopeProfile } from "@/types/scope";
 import * as tauri from "@/lib/tauri";
 
 interface ScopeStore {
@@ -35,6 +35,31 @@ const defaultConfig: ScopeConfig = {
   includedChatIds: [],
 };
 
+export function toRawScopeConfig(config: ScopeConfig): RawScopeConfig {
+  return {
+    folder_ids: config.folderIds,
+    chat_types: config.chatTypes,
+    excluded_chat_ids: config.excludedChatIds,
+    included_chat_ids: config.includedChatIds,
+    contact_list_ids: config.contactListIds ?? [],
+  };
+}
+
+export function fromRawScopeProfile(raw: RawScopeProfile): ScopeProfile {
+  return {
+    id: raw.id,
+    name: raw.name,
+    folderIds: raw.config.folder_ids,
+    chatTypes: raw.config.chat_types as ChatType[],
+    excludedChatIds: raw.config.excluded_chat_ids,
+    includedChatIds: raw.config.included_chat_ids,
+    contactListIds: raw.config.contact_list_ids ?? [],
+    isDefault: raw.is_default,
+    createdAt: raw.created_at * 1000,
+    updatedAt: raw.updated_at * 1000,
+  };
+}
+
 export const useScopeStore = create<ScopeStore>((set, get) => ({
   folders: [],
   profiles: [],
@@ -59,8 +84,10 @@ export const useScopeStore = create<ScopeStore>((set, get) => ({
       const names = await tauri.listScopes();
       const profiles: ScopeProfile[] = [];
       for (const name of names) {
-        const profile = (await tauri.loadScope(name)) as ScopeProfile;
-        profiles.push(profile);
+        const raw = (await tauri.loadScope(name)) as RawScopeProfile | null;
+        if (raw) {
+          profiles.push(fromRawScopeProfile(raw));
+        }
       }
       set({ profiles });
     } catch (error) {
@@ -91,7 +118,7 @@ export const useScopeStore = create<ScopeStore>((set, get) => ({
       updatedAt: Date.now(),
     };
     try {
-      await tauri.saveScope(name, profile);
+      await tauri.saveScope(name, toRawScopeConfig(config));
       set((state) => ({
         profiles: [...state.profiles, profile],
         activeScope: { profile, customConfig: null },
@@ -192,6 +219,7 @@ export const useScopeStore = create<ScopeStore>((set, get) => ({
         chatTypes: activeScope.profile.chatTypes,
         excludedChatIds: activeScope.profile.excludedChatIds,
         includedChatIds: activeScope.profile.includedChatIds,
+        contactListIds: activeScope.profile.contactListIds ?? [],
       };
     }
     return activeScope.customConfig || defaultConfig;
@@ -3,6 +3,7 @@ import * as tauri from "@/lib/tauri";
 import { chatFiltersFromSettings } from "@/lib/tauri";
 import { useSettingsStore, getCacheTTL } from "@/stores/settingsStore";
 import { useChatStore, DEFAULT_CHAT_LIMIT } from "@/stores/chatStore";
+import { describeMessageContent } from "@/lib/utils";
 import type { Folder } from "@/types/telegram";
 import {
   type ChatSummary,
@@ -221,7 +222,7 @@ export const useSummaryStore = create<SummaryStore>((set, get) => ({
             messages: messages.map((m: any) => ({
               id: Number(m.id),
               sender_name: m.senderName,
-              text: m.content.type === "text" ? m.content.text : "[Media]",
+              text: describeMessageContent(m.content),
               date: m.date,
               is_outgoing: m.isOutgoing,
             })),
@@ -268,6 +269,7 @@ export const useSummaryStore = create<SummaryStore>((set, get) => ({
             typeof s.message_count === "number" ? s.message_count : 0,
           lastMessageDate:
             typeof s.last_message_date === "number" ? s.last_message_date : 0,
+          topics: Array.isArray(s.topics) ? s.topics : [],
         }));
 
       newSummaries = [...newSummaries, ...largeGroupSummaries];
@@ -10,6 +10,58 @@ export interface Contact {
   lastContactDate?: number;
   daysSinceContact?: number;
   unreadCount?: number;
+  birthday?: Birthday | null;
+  bio?: string | null;
+  isPremium?: boolean;
+  isVerified?: boolean;
+}
+
+export interface UserProfile {
+  userId: number;
+  bio: string | null;
+  username: string | null;
+  isPremium: boolean;
+  isVerified: boolean;
+  birthday: Birthday | null;
+}
+
+export interface Birthday {
+  day: number;
+  month: number;
+  year: number | null;
+}
+
+export interface UpcomingBirthday {
+  userId: number;
+  name: string;
+  username: string | null;
+  birthday: Birthday;
+  date: string;
+  daysUntil: number;
+  turningAge: number | null;
+  greetingDraft: string | null;
+}
+
+export type TimelineEventKind =
+  | "message_in"
+  | "message_out"
+  | "outreach"
+  | "tag_added"
+  | "tag_removed"
+  | "notes_updated"
+  | "removed_from_group"
+  | "welcomed_to_group"
+  | "pipeline_stage";
+
+export interface TimelineEvent {
+  kind: TimelineEventKind;
+  timestamp: number;
+  text: string;
+}
+
+export interface TagSuggestion {
+  tag: string;
+  reason: string;
 }
 
 export interface ContactTag {
@@ -7,6 +7,7 @@ export interface ScopeProfile {
   chatTypes: ChatType[];
   excludedChatIds: number[];
   includedChatIds: number[];
+  contactListIds?: string[];
   isDefault: boolean;
   createdAt: number;
   updatedAt: number;
@@ -17,9 +18,29 @@ export interface ScopeConfig {
   chatTypes: ChatType[];
   excludedChatIds: number[];
   includedChatIds: number[];
+  contactListIds?: string[];
 }
 
 export interface ActiveScope {
   profile: ScopeProfile | null;
   customConfig: ScopeConfig | null;
 }
+
+/** ScopeConfig as the backend stores it (db::scopes::ScopeConfig) */
+export interface RawScopeConfig {
+  folder_ids: number[];
+  chat_types: string[];
+  excluded_chat_ids: number[];
+  included_chat_ids: number[];
+  contact_list_ids: string[];
+}
+
+/** ScopeProfile as the backend returns it; timestamps are unix seconds */
+export interface RawScopeProfile {
+  id: string;
+  name: string;
+  config: RawScopeConfig;
+  is_default: boolean;
+  created_at: number;
+  updated_at: number;
+}
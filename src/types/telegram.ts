@@ -21,6 +21,12 @@ export interface Chat {
   isArchived?: boolean;
   isBot?: boolean;
   isContact?: boolean;
+  /** Telegram title when a local nickname replaces `title` */
+  originalTitle?: string | null;
+  /** Starred in the copilot, independent of Telegram pins */
+  isStarred?: boolean;
+  /** The user's notes on this chat */
+  note?: string | null;
 }
 
 export type ChatType = "private" | "group" | "supergroup" | "channel" | "secret";
@@ -34,15 +40,38 @@ export interface Message {
   date: number;
   isOutgoing: boolean;
   isRead: boolean;
+  replyToMessageId?: number | null;
+  // Telegram's flag for messages that @mention the user or reply to them
+  mentionsMe?: boolean;
+  // Rows of a bot's inline keyboard
+  inlineKeyboard?: InlineButton[][];
+}
+
+// Callback buttons carry base64 `data`; URL buttons carry `url`
+export interface InlineButton {
+  text: string;
+  data?: string | null;
+  url?: string | null;
 }
 
 export type MessageContent =
   | { type: "text"; text: string }
-  | { type: "photo"; caption?: string }
-  | { type: "video"; caption?: string }
-  | { type: "document"; fileName: string }
+  | { type: "photo"; caption?: string | null }
+  | { type: "video"; caption?: string | null }
+  | { type: "document"; fileName: string; caption?: string | null }
   | { type: "voice"; duration: number }
-  | { type: "sticker"; emoji?: string }
+  | { type: "sticker"; emoji?: string | null }
+  | { type: "storyMention"; storyId: number }
+  | { type: "story"; storyId: number }
+  // Service messages
+  | { type: "memberJoined"; userIds: number[] }
+  | { type: "memberLeft"; userId: number }
+  | { type: "titleChanged"; title: string }
+  | { type: "messagePinned" }
+  | { type: "call"; video: boolean; missed: boolean; duration?: number | null }
+  | { type: "groupCall"; duration?: number | null }
+  | { type: "premiumGift"; months: number }
+  | { type: "starsGift"; stars: number }
   | { type: "unknown" };
 
 export interface Folder {